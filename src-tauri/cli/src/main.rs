@@ -0,0 +1,473 @@
+//! `scythe`: scan, search and export from the command line, for build
+//! scripts and CI. Reads and writes the same database and settings as the
+//! desktop app.
+
+use scythe_core::db::{Asset, AssetSort, Database, Project};
+use scythe_core::deps::DependencyResolver;
+use scythe_core::error::{AppError, AppResult};
+use scythe_core::export::{ExportResult, Exporter};
+use scythe_core::indexer::Indexer;
+use scythe_core::plugins::PluginRegistry;
+use scythe_core::rules;
+use scythe_core::scanner::{count_scannable_files, scan_files_batch, ChangeDetection, DirectoryMtimes, WalkOptions};
+use scythe_core::settings::Settings;
+use scythe_core::storage;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+mod rpc;
+
+/// Same folder the app uses (its bundle identifier under the data dir)
+const APP_IDENTIFIER: &str = "com.scythe.database";
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+const USAGE: &str = "\
+Usage: scythe [--data-dir <dir>] [--verbose] <command> [options]
+
+Commands:
+  scan <path> [--full]              Index a project folder (quick re-scan unless --full)
+  search <query> [--project <path>] [--type <type>]... [--limit <n>]
+         [--sort name|size|build_size|estimated_size|modified] [--json]
+                                    Search indexed assets
+  export <asset> <dest> [--project <path>] [--bundle]
+                                    Copy an asset (id, GUID or relative path) to <dest>,
+                                    with its dependencies when --bundle is given
+  validate [--project <path>] [--json]
+                                    Run the validation rules and list violations; fails
+                                    when any rule with severity error is broken
+  rpc                               Serve JSON-RPC 2.0 on stdin/stdout, one message per line
+
+Search, export and validate default to the project last opened in the app.";
+
+/// Options that take a value; everything else starting with `--` is a switch
+const VALUE_OPTIONS: &[&str] = &["--data-dir", "--project", "--type", "--limit", "--sort"];
+
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    values: HashMap<String, Vec<String>>,
+    switches: Vec<String>,
+}
+
+impl Args {
+    fn parse(raw: impl Iterator<Item = String>) -> AppResult<Self> {
+        let mut args = Args::default();
+        let mut raw = raw.peekable();
+        while let Some(arg) = raw.next() {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = raw
+                    .next()
+                    .ok_or_else(|| AppError::InvalidArgument(format!("{} needs a value", arg)))?;
+                args.values.entry(arg).or_default().push(value);
+            } else if arg.starts_with("--") {
+                args.switches.push(arg);
+            } else {
+                args.positional.push(arg);
+            }
+        }
+        Ok(args)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|v| v.last()).map(String::as_str)
+    }
+
+    fn all(&self, name: &str) -> &[String] {
+        self.values.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|s| s == name)
+    }
+
+    fn positional(&self, index: usize, what: &str) -> AppResult<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| AppError::InvalidArgument(format!("Missing {}\n\n{}", what, USAGE)))
+    }
+}
+
+/// Settings and database shared by every command
+struct Context {
+    settings: Settings,
+    db: Arc<Database>,
+    plugins: Arc<PluginRegistry>,
+    rules_dir: PathBuf,
+}
+
+impl Context {
+    fn open(args: &Args) -> AppResult<Self> {
+        let app_data_dir = match args.value("--data-dir") {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::data_dir()
+                .ok_or_else(|| AppError::Custom("Could not determine the data directory".to_string()))?
+                .join(APP_IDENTIFIER),
+        };
+        std::fs::create_dir_all(&app_data_dir)?;
+
+        let settings = Settings::load(&app_data_dir.join("settings.json"))?;
+        // Same catalog as the app; a shared one is never written to
+        let db = match &settings.shared_catalog_dir {
+            Some(dir) => Database::open_read_only(
+                &storage::shared_catalog_database(Path::new(dir)),
+                &settings.db_options(),
+            )?,
+            None => {
+                let db_dir = storage::database_dir(&settings, &app_data_dir);
+                std::fs::create_dir_all(&db_dir)?;
+                Database::new(&db_dir.join(storage::DATABASE_FILE), &settings.db_options())?
+            }
+        };
+
+        let plugins = if settings.plugins_enabled {
+            PluginRegistry::load_dir(&storage::plugins_dir(&app_data_dir))
+        } else {
+            PluginRegistry::empty()
+        };
+
+        Ok(Self {
+            settings,
+            db: Arc::new(db),
+            plugins: Arc::new(plugins),
+            rules_dir: storage::rules_dir(&app_data_dir),
+        })
+    }
+
+    /// The project rooted at `root`, or the one last opened in the app
+    fn project(&self, root: Option<&str>) -> AppResult<Project> {
+        let root = match root {
+            Some(path) => absolute_path(path)?,
+            None => self.settings.project_root.clone().ok_or_else(|| {
+                AppError::InvalidArgument("No project open in the app; pass --project <path>".to_string())
+            })?,
+        };
+        self.db
+            .get_project_by_path(&root)?
+            .ok_or_else(|| AppError::NotFound(format!("Project not indexed: {} (run `scythe scan` first)", root)))
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.switch("--help") || args.positional.is_empty() {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let level = if args.switch("--verbose") {
+        tracing::Level::INFO
+    } else {
+        tracing::Level::WARN
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .init();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e @ AppError::InvalidArgument(_)) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> AppResult<()> {
+    let command: fn(&Context, &Args) -> AppResult<()> = match args.positional(0, "command")? {
+        "scan" => scan,
+        "search" => search,
+        "export" => export,
+        "validate" => validate,
+        "rpc" => |ctx: &Context, _: &Args| rpc::serve(ctx),
+        other => return Err(AppError::InvalidArgument(format!("Unknown command: {}\n\n{}", other, USAGE))),
+    };
+    let ctx = Context::open(args)?;
+    command(&ctx, args)
+}
+
+/// Outcome of `scan_project`, shaped like the app's scan job result
+#[derive(Serialize)]
+struct ScanSummary {
+    project: Project,
+    file_count: usize,
+    total_files: usize,
+    added: usize,
+    changed: usize,
+    skipped: usize,
+    /// Set when validation rules ran after the scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violations: Option<usize>,
+}
+
+fn scan(ctx: &Context, args: &Args) -> AppResult<()> {
+    let root = args.positional(1, "project path")?;
+    let mut current_phase = "";
+    let summary = scan_project(ctx, root, args.switch("--full"), |phase, processed, total| {
+        if phase != current_phase && !current_phase.is_empty() {
+            eprintln!();
+        }
+        current_phase = phase;
+        progress(&format!("{} {}/{}", phase, processed, total));
+    })?;
+    eprintln!();
+
+    println!(
+        "{}: {} files, {} new, {} changed, {} unchanged",
+        summary.project.name, summary.total_files, summary.added, summary.changed, summary.skipped
+    );
+    if let Some(violations) = summary.violations {
+        println!("{} rule violations (see `scythe validate`)", violations);
+    }
+    Ok(())
+}
+
+/// Index a project folder, resolve its dependencies and run the validation
+/// rules, reporting `(phase, processed, total)` for the "indexing",
+/// "dependencies" and "validating" phases
+fn scan_project(
+    ctx: &Context,
+    root: &str,
+    full: bool,
+    mut on_progress: impl FnMut(&'static str, usize, usize),
+) -> AppResult<ScanSummary> {
+    if ctx.db.is_read_only() {
+        return Err(AppError::ReadOnlyCatalog);
+    }
+    let root_path = absolute_path(root)?;
+    let root = Path::new(&root_path);
+    if !root.is_dir() {
+        return Err(AppError::InvalidProject("Not a valid folder.".to_string()));
+    }
+
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Unknown Folder".to_string());
+    let project = ctx.db.get_or_create_project(&root_path, &name)?;
+
+    let settings = &ctx.settings;
+    let excluded = ctx.db.get_excluded_paths(&project.id)?;
+    let walk = WalkOptions {
+        ignore_patterns: &settings.ignore_patterns,
+        threads: settings.effective_scan_threads(),
+        resume_after: None,
+        plugins: Some(&ctx.plugins),
+        excluded: Some(&excluded),
+    };
+    // Nothing cancels a headless scan short of killing the process
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+
+    let total_files = count_scannable_files(root, walk, Arc::clone(&cancel_flag), |_| {})?;
+
+    let existing = ctx.db.get_existing_asset_info(&project.id)?;
+    let directories = DirectoryMtimes::new(
+        ctx.db.get_directory_mtimes(&project.id)?,
+        settings.directory_change_detection,
+    );
+    let indexer = Indexer::new(Arc::clone(&ctx.db));
+    let (file_count, stats) = scan_files_batch(
+        root,
+        &project.id,
+        walk,
+        settings.scan_batch_size,
+        Arc::clone(&cancel_flag),
+        Some(ChangeDetection {
+            existing: &existing,
+            skip_unchanged: !full,
+            directories: Some(&directories),
+        }),
+        |batch, count, _current_path| {
+            if let Err(e) = indexer.upsert_batch(&batch) {
+                tracing::error!("Failed to index batch: {}", e);
+            }
+            on_progress("indexing", count, total_files);
+            true
+        },
+    )?;
+    ctx.db.replace_directory_mtimes(&project.id, &directories.into_current())?;
+
+    DependencyResolver::new(Arc::clone(&ctx.db))
+        .with_plugins(Arc::clone(&ctx.plugins))
+        .with_max_file_bytes(settings.dependency_max_file_bytes())
+        .resolve_all_for_project_with_progress(
+            &project.id,
+            settings.effective_dependency_threads(),
+            cancel_flag,
+            |processed, total| on_progress("dependencies", processed, total),
+        )?;
+
+    ctx.db.update_project_scan_time(&project.id, file_count as i64)?;
+    ctx.db.record_scan_totals(&project.id, if full { "full" } else { "quick" })?;
+    // A full pass supersedes any scan the app left unfinished
+    ctx.db.delete_scan_checkpoint(&project.id)?;
+
+    let violations = if rules::has_rules(&ctx.rules_dir) {
+        let summary = rules::run_rules(&ctx.db, &project.id, &ctx.rules_dir, |checked, total| {
+            on_progress("validating", checked, total);
+            true
+        })?;
+        for error in &summary.errors {
+            tracing::warn!("Rule {}: {}", error.rule, error.message);
+        }
+        Some(summary.violations)
+    } else {
+        None
+    };
+
+    Ok(ScanSummary {
+        project,
+        file_count,
+        total_files: stats.total_files,
+        added: stats.new_files,
+        changed: stats.changed_files,
+        skipped: stats.unchanged_skipped,
+        violations,
+    })
+}
+
+fn validate(ctx: &Context, args: &Args) -> AppResult<()> {
+    if ctx.db.is_read_only() {
+        return Err(AppError::ReadOnlyCatalog);
+    }
+    let project = ctx.project(args.value("--project"))?;
+    let summary = rules::run_rules(&ctx.db, &project.id, &ctx.rules_dir, |_, _| true)?;
+    let violations = ctx.db.get_rule_violations(&project.id, None, None, i64::MAX)?;
+
+    if args.switch("--json") {
+        println!("{}", serde_json::to_string_pretty(&violations)?);
+    } else {
+        for v in &violations {
+            println!("{:<7} {:<24} {}: {}", v.severity, v.rule, v.relative_path, v.message);
+        }
+        println!(
+            "{} violations from {} rules over {} assets",
+            summary.violations, summary.rules, summary.assets_checked
+        );
+    }
+    for error in &summary.errors {
+        eprintln!("rule {}: {}", error.rule, error.message);
+    }
+
+    let errors = violations.iter().filter(|v| v.severity == "error").count();
+    if errors > 0 {
+        return Err(AppError::Custom(format!("{} violations of error rules", errors)));
+    }
+    Ok(())
+}
+
+fn search(ctx: &Context, args: &Args) -> AppResult<()> {
+    let query = args.positional(1, "search query")?;
+    let project = ctx.project(args.value("--project"))?;
+    let limit = match args.value("--limit") {
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| AppError::InvalidArgument(format!("Invalid --limit: {}", value)))?,
+        None => DEFAULT_SEARCH_LIMIT,
+    };
+    let types = args.all("--type");
+    let sort = args.value("--sort").map(AssetSort::parse).transpose()?.unwrap_or_default();
+
+    let (assets, total) = ctx.db.get_assets(
+        &project.id,
+        Some(query),
+        (!types.is_empty()).then_some(types),
+        sort,
+        0,
+        limit,
+    )?;
+
+    if args.switch("--json") {
+        println!("{}", serde_json::to_string_pretty(&assets)?);
+    } else {
+        for asset in &assets {
+            println!("{}\t{}\t{}", asset.asset_type, asset.size_bytes, asset.relative_path);
+        }
+        eprintln!("{} of {} matches", assets.len(), total);
+    }
+    Ok(())
+}
+
+fn export(ctx: &Context, args: &Args) -> AppResult<()> {
+    let reference = args.positional(1, "asset")?;
+    let dest = args.positional(2, "destination folder")?;
+    let project = ctx.project(args.value("--project"))?;
+    let result = export_asset(ctx, &project, reference, Path::new(dest), args.switch("--bundle"))?;
+
+    for file in &result.exported_files {
+        println!("{}", file);
+    }
+    for file in &result.skipped_files {
+        eprintln!("skipped (already exists): {}", file);
+    }
+    if let Some(archive) = &result.archive_path {
+        eprintln!("Wrote {}", archive);
+    }
+    Ok(())
+}
+
+/// Export an asset with the app's default export options
+fn export_asset(
+    ctx: &Context,
+    project: &Project,
+    reference: &str,
+    dest: &Path,
+    bundle: bool,
+) -> AppResult<ExportResult> {
+    let asset = find_asset(&ctx.db, project, reference)?;
+    let exporter = Exporter::new(Arc::clone(&ctx.db));
+    let options = &ctx.settings.export_defaults;
+    let result = if bundle {
+        exporter.export_bundle(&asset, dest, options)?
+    } else {
+        exporter.export_file(&asset, dest, options)?
+    };
+
+    match result.error {
+        Some(error) => Err(AppError::Custom(error)),
+        None => Ok(result),
+    }
+}
+
+/// Look an asset up by id, Unity GUID or project-relative path
+fn find_asset(db: &Database, project: &Project, reference: &str) -> AppResult<Asset> {
+    if let Some(asset) = db.get_asset(reference)?.filter(|a| a.project_id == project.id) {
+        return Ok(asset);
+    }
+    if let Some(asset) = db.get_asset_by_guid(&project.id, reference)? {
+        return Ok(asset);
+    }
+    let relative = reference.replace(['/', '\\'], std::path::MAIN_SEPARATOR_STR);
+    db.get_asset_by_relative_path(&project.id, &relative)?
+        .ok_or_else(|| AppError::AssetNotFound(reference.to_string()))
+}
+
+fn absolute_path(path: &str) -> AppResult<String> {
+    Ok(std::path::absolute(path)?.to_string_lossy().to_string())
+}
+
+/// Overwrite the current stderr line with a progress message
+fn progress(message: &str) {
+    let mut stderr = std::io::stderr();
+    let _ = write!(stderr, "\r{}", message);
+    let _ = stderr.flush();
+}
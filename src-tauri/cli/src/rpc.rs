@@ -0,0 +1,250 @@
+//! JSON-RPC 2.0 over stdio for pipeline scripts and other tools.
+//!
+//! One JSON message per line in each direction. Method names and
+//! parameters mirror the app's Tauri commands; long scans report
+//! `progress` notifications before their response. Logs go to stderr so
+//! stdout only ever carries protocol messages.
+
+use crate::{export_asset, find_asset, scan_project, Context};
+use scythe_core::db::{retry_busy, AssetSort};
+use scythe_core::error::{AppError, AppResult};
+use scythe_core::search::{self, FileSearchOptions};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Application errors; `data` carries the `{ code, message, details }` payload
+const APP_ERROR: i64 = -32000;
+
+/// Upper bound for `page_size` on `get_assets`
+const MAX_PAGE_SIZE: i64 = 1000;
+const DEFAULT_PAGE_SIZE: i64 = 100;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: Option<String>,
+    /// Absent for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Failure of a single call, before it is wrapped in a response
+enum RpcError {
+    MethodNotFound(String),
+    InvalidParams(String),
+    App(AppError),
+}
+
+impl From<AppError> for RpcError {
+    fn from(e: AppError) -> Self {
+        RpcError::App(e)
+    }
+}
+
+#[derive(Deserialize)]
+struct ScanParams {
+    path: String,
+    #[serde(default)]
+    full: bool,
+}
+
+#[derive(Deserialize)]
+struct ProjectParams {
+    /// Project root; defaults to the project open in the app
+    project: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GetAssetsParams {
+    project: Option<String>,
+    search_query: Option<String>,
+    asset_types: Option<Vec<String>>,
+    sort: Option<String>,
+    #[serde(default)]
+    page: i64,
+    page_size: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct AssetParams {
+    /// Asset id, Unity GUID or project-relative path
+    asset: String,
+    project: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchInFilesParams {
+    project: Option<String>,
+    pattern: String,
+    types: Option<Vec<String>>,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    asset: String,
+    dest: String,
+    project: Option<String>,
+    #[serde(default)]
+    bundle: bool,
+}
+
+/// Answer requests from stdin until it is closed
+pub fn serve(ctx: &Context) -> AppResult<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(ctx, &line, &mut stdout) {
+            send(&mut stdout, &response)?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_line(ctx: &Context, line: &str, out: &mut impl Write) -> Option<Value> {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string(), None)),
+    };
+    let request: Request = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, INVALID_REQUEST, &e.to_string(), None)),
+    };
+    if request.jsonrpc.as_deref() != Some("2.0") {
+        let id = request.id.unwrap_or(Value::Null);
+        return Some(error_response(id, INVALID_REQUEST, "jsonrpc must be \"2.0\"", None));
+    }
+
+    let result = call(ctx, &request.method, request.params, &mut |notification| {
+        if let Err(e) = send(out, &notification) {
+            tracing::warn!("Failed to send notification: {}", e);
+        }
+    });
+
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(RpcError::MethodNotFound(method)) => {
+            error_response(id, METHOD_NOT_FOUND, &format!("Unknown method: {}", method), None)
+        }
+        Err(RpcError::InvalidParams(message)) => error_response(id, INVALID_PARAMS, &message, None),
+        Err(RpcError::App(e)) => {
+            let data = serde_json::to_value(&e).ok();
+            error_response(id, APP_ERROR, &e.to_string(), data)
+        }
+    })
+}
+
+fn call(ctx: &Context, method: &str, params: Value, notify: &mut dyn FnMut(Value)) -> Result<Value, RpcError> {
+    let result = match method {
+        "scan" => {
+            let p: ScanParams = parse_params(params)?;
+            let summary = scan_project(ctx, &p.path, p.full, |phase, processed, total| {
+                notify(json!({
+                    "jsonrpc": "2.0",
+                    "method": "progress",
+                    "params": { "phase": phase, "processed": processed, "total": total },
+                }))
+            })?;
+            to_value(&summary)?
+        }
+        "get_project" => {
+            let p: ProjectParams = parse_params(params)?;
+            to_value(&ctx.project(p.project.as_deref())?)?
+        }
+        "get_assets" => {
+            let p: GetAssetsParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            let page_size = p.page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+            let sort = p.sort.as_deref().map(AssetSort::parse).transpose()?.unwrap_or_default();
+            let (assets, total) = retry_busy(|| {
+                ctx.db.get_assets(
+                    &project.id,
+                    p.search_query.as_deref(),
+                    p.asset_types.as_deref(),
+                    sort,
+                    p.page.max(0),
+                    page_size,
+                )
+            })?;
+            json!({ "assets": assets, "total": total })
+        }
+        "get_asset" => {
+            let p: AssetParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            to_value(&find_asset(&ctx.db, &project, &p.asset)?)?
+        }
+        "get_dependencies" | "get_dependents" => {
+            let p: AssetParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            let asset = find_asset(&ctx.db, &project, &p.asset)?;
+            let deps = if method == "get_dependencies" {
+                retry_busy(|| ctx.db.get_dependencies(&asset.id))?
+            } else {
+                retry_busy(|| ctx.db.get_dependents(&asset.id))?
+            };
+            to_value(&deps)?
+        }
+        "get_type_counts" => {
+            let p: ProjectParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            to_value(&retry_busy(|| ctx.db.get_type_counts(&project.id, None))?)?
+        }
+        "search_in_files" => {
+            let p: SearchInFilesParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            let options = FileSearchOptions {
+                regex: p.regex,
+                case_sensitive: p.case_sensitive,
+                types: p.types,
+            };
+            to_value(&search::search_in_files(&ctx.db, &project.id, &p.pattern, &options)?)?
+        }
+        "export" => {
+            let p: ExportParams = parse_params(params)?;
+            let project = ctx.project(p.project.as_deref())?;
+            to_value(&export_asset(ctx, &project, &p.asset, Path::new(&p.dest), p.bundle)?)?
+        }
+        other => return Err(RpcError::MethodNotFound(other.to_string())),
+    };
+    Ok(result)
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Methods whose parameters are all optional may be called without any
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::InvalidParams(e.to_string()))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::App(e.into()))
+}
+
+fn error_response(id: Value, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn send(out: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    writeln!(out, "{}", message)?;
+    out.flush()
+}
@@ -0,0 +1,341 @@
+//! Changing asset files on disk from the catalog, keeping each file's
+//! `.meta` beside it and the index in step, so nothing has to be rescanned
+//! and no reference to the asset breaks unnoticed.
+//!
+//! Deleted assets go to the OS trash and each deletion is logged in
+//! `trashed_assets`, so it can be undone by restoring the files from the
+//! trash (on Windows and Linux; macOS has no API for it).
+
+use crate::db::{Asset, Database, TrashOperation, TrashedAsset};
+use crate::error::{AppError, AppResult};
+use crate::plugins::PluginRegistry;
+use crate::scanner::{classify_with_plugins, is_ignored_relative};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+fn meta_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.meta", path.display()))
+}
+
+/// Both paths name the same file on disk, e.g. two spellings of one name on
+/// a case-insensitive file system
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Both paths name the same file on disk, e.g. two spellings of one name on
+/// a case-insensitive file system. Canonical paths come back in the case
+/// stored on disk.
+#[cfg(not(unix))]
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Rename or move an asset within its project, along with its `.meta`.
+/// The row keeps its id, so dependency edges and cached reads stay attached;
+/// Unity keeps references because the GUID in the `.meta` is unchanged.
+/// Missing folders on the way are created. Returns the updated asset.
+pub fn move_asset(
+    db: &Database,
+    asset: &Asset,
+    new_relative_path: &str,
+    ignore_patterns: &[String],
+    plugins: Option<&PluginRegistry>,
+) -> AppResult<Asset> {
+    let project = db
+        .get_project(&asset.project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", asset.project_id)))?;
+    let root = Path::new(&project.root_path);
+
+    let trimmed = new_relative_path.trim().trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() || !Path::new(trimmed).components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::InvalidArgument(
+            "New path must be relative to the project root, without '..'".to_string(),
+        ));
+    }
+    // Rebuilt from its components so separators match what the scanner stores
+    let target = root.join(Path::new(trimmed).components().collect::<PathBuf>());
+    let relative_path = target.strip_prefix(root).unwrap_or(&target).to_string_lossy().to_string();
+    if relative_path == asset.relative_path {
+        return Ok(asset.clone());
+    }
+
+    let file_name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if file_name.to_lowercase().ends_with(".meta") {
+        return Err(AppError::InvalidArgument("New path cannot be a .meta file".to_string()));
+    }
+    if is_ignored_relative(&relative_path, ignore_patterns) {
+        return Err(AppError::InvalidArgument(format!(
+            "{} is ignored by the ignore patterns, so the next scan would drop it",
+            relative_path
+        )));
+    }
+    let asset_type = classify_with_plugins(&target, plugins);
+    if asset_type == "unknown" {
+        return Err(AppError::InvalidArgument(format!(
+            "{} is not a file type the catalog indexes",
+            file_name
+        )));
+    }
+
+    let source = Path::new(&asset.absolute_path);
+    if !source.is_file() {
+        return Err(AppError::NotFound(format!("{} is no longer on disk", asset.relative_path)));
+    }
+    // A case-only rename finds the file itself on case-insensitive file
+    // systems; on case-sensitive ones the other spelling is another file
+    let taken = |target: &Path, source: &Path| target.exists() && !same_file(target, source);
+    if taken(&target, source) || taken(&meta_path(&target), &meta_path(source)) {
+        return Err(AppError::ExportConflict(relative_path));
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(source, &target)?;
+    let source_meta = meta_path(source);
+    if source_meta.exists() {
+        if let Err(e) = fs::rename(&source_meta, meta_path(&target)) {
+            let _ = fs::rename(&target, source);
+            return Err(e.into());
+        }
+    }
+
+    let moved = Asset {
+        absolute_path: target.to_string_lossy().to_string(),
+        relative_path,
+        file_name,
+        extension: target.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default(),
+        asset_type: asset_type.into_owned(),
+        updated_at: chrono::Utc::now().timestamp(),
+        ..asset.clone()
+    };
+    if let Err(e) = db.update_asset_location(&moved) {
+        // Put the files back so the index still matches the disk
+        if meta_path(&target).exists() {
+            let _ = fs::rename(meta_path(&target), &source_meta);
+        }
+        let _ = fs::rename(&target, source);
+        return Err(e);
+    }
+
+    if let Err(e) = db.move_provenance(&asset.project_id, &asset.relative_path, &moved.relative_path) {
+        tracing::warn!("Provenance of {} not moved: {}", asset.relative_path, e);
+    }
+
+    tracing::info!("Moved {} to {}", asset.relative_path, moved.relative_path);
+    Ok(moved)
+}
+
+/// A reference from an asset that stays to one being deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenReference {
+    pub from_asset_id: String,
+    pub from_relative_path: String,
+    pub to_asset_id: String,
+    pub to_relative_path: String,
+    pub relation_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashFailure {
+    pub relative_path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteAssetsResult {
+    /// References the deletion breaks, or would break before it's confirmed
+    pub broken: Vec<BrokenReference>,
+    /// The logged deletion, once files were moved to the trash
+    pub operation: Option<TrashOperation>,
+    /// Assets whose files couldn't be moved; they stay in the index
+    pub failed: Vec<TrashFailure>,
+}
+
+/// References into `assets` from assets that aren't being deleted with them
+pub fn delete_impact(db: &Database, assets: &[Asset]) -> AppResult<Vec<BrokenReference>> {
+    let deleted: HashMap<&str, &Asset> = assets.iter().map(|a| (a.id.as_str(), a)).collect();
+    let mut paths: HashMap<String, String> = HashMap::new();
+    let mut broken = Vec::new();
+    for asset in assets {
+        for dep in db.get_dependents(&asset.id)? {
+            if deleted.contains_key(dep.from_asset_id.as_str()) {
+                continue;
+            }
+            let from_relative_path = match paths.get(&dep.from_asset_id) {
+                Some(path) => path.clone(),
+                None => {
+                    let path = db
+                        .get_asset(&dep.from_asset_id)?
+                        .map(|a| a.relative_path)
+                        .unwrap_or_default();
+                    paths.insert(dep.from_asset_id.clone(), path.clone());
+                    path
+                }
+            };
+            broken.push(BrokenReference {
+                from_asset_id: dep.from_asset_id,
+                from_relative_path,
+                to_asset_id: asset.id.clone(),
+                to_relative_path: asset.relative_path.clone(),
+                relation_type: dep.relation_type,
+            });
+        }
+    }
+    broken.sort_by(|a, b| {
+        a.to_relative_path
+            .cmp(&b.to_relative_path)
+            .then_with(|| a.from_relative_path.cmp(&b.from_relative_path))
+    });
+    Ok(broken)
+}
+
+/// Report what deleting `assets` would break; with `confirm`, also move
+/// their files and `.meta` files to the OS trash, log that as one operation
+/// and remove them from the index. All assets must be in one project.
+pub fn delete_assets(db: &Database, assets: &[Asset], confirm: bool) -> AppResult<DeleteAssetsResult> {
+    let projects: HashSet<&str> = assets.iter().map(|a| a.project_id.as_str()).collect();
+    if projects.len() > 1 {
+        return Err(AppError::InvalidArgument("Assets to delete must be in one project".to_string()));
+    }
+    let broken = delete_impact(db, assets)?;
+    if !confirm || assets.is_empty() {
+        return Ok(DeleteAssetsResult {
+            broken,
+            operation: None,
+            failed: Vec::new(),
+        });
+    }
+
+    // Log the operation first, so files that reach the trash can always be
+    // restored from it
+    let mut operation = TrashOperation {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: assets[0].project_id.clone(),
+        deleted_at: chrono::Utc::now().timestamp(),
+        restored_at: None,
+        assets: assets
+            .iter()
+            .map(|a| TrashedAsset {
+                relative_path: a.relative_path.clone(),
+                absolute_path: a.absolute_path.clone(),
+            })
+            .collect(),
+    };
+    db.record_trash_operation(&operation)?;
+
+    let mut trashed = Vec::new();
+    let mut failed = Vec::new();
+    for asset in assets {
+        let path = Path::new(&asset.absolute_path);
+        let mut files = vec![path.to_path_buf()];
+        let meta = meta_path(path);
+        if meta.exists() {
+            files.push(meta);
+        }
+        match trash::delete_all(&files) {
+            Ok(()) => trashed.push(asset),
+            Err(e) => failed.push(TrashFailure {
+                relative_path: asset.relative_path.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    if !failed.is_empty() {
+        let untrashed: Vec<String> = failed.iter().map(|f| f.relative_path.clone()).collect();
+        if let Err(e) = db.remove_trashed_assets(&operation.id, &untrashed) {
+            tracing::warn!("Failed to drop untrashed assets from deletion {}: {}", operation.id, e);
+        }
+        operation.assets.retain(|a| !untrashed.contains(&a.relative_path));
+    }
+    if trashed.is_empty() {
+        return Ok(DeleteAssetsResult {
+            broken,
+            operation: None,
+            failed,
+        });
+    }
+
+    let ids: Vec<String> = trashed.iter().map(|a| a.id.clone()).collect();
+    for thumb in db.delete_assets(&ids)? {
+        let _ = fs::remove_file(thumb);
+    }
+    tracing::info!("Moved {} assets to the trash", ids.len());
+
+    // Only references to assets that were actually deleted break
+    let broken = broken
+        .into_iter()
+        .filter(|b| ids.contains(&b.to_asset_id))
+        .collect();
+    Ok(DeleteAssetsResult {
+        broken,
+        operation: Some(operation),
+        failed,
+    })
+}
+
+/// Put the files of a logged deletion back from the OS trash. They are
+/// indexed again by the next scan.
+pub fn restore_from_trash(db: &Database, operation_id: &str) -> AppResult<TrashOperation> {
+    let mut operation = db
+        .get_trash_operations(None, Some(operation_id), 1)?
+        .pop()
+        .ok_or_else(|| AppError::NotFound(format!("Deletion {}", operation_id)))?;
+    if operation.restored_at.is_some() {
+        return Err(AppError::InvalidArgument("These assets were already restored".to_string()));
+    }
+
+    let mut paths = Vec::new();
+    for asset in &operation.assets {
+        let path = PathBuf::from(&asset.absolute_path);
+        paths.push(meta_path(&path));
+        paths.push(path);
+    }
+    if restore_files(&paths)? == 0 {
+        return Err(AppError::NotFound("None of the files are in the trash any more".to_string()));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    db.set_trash_operation_restored(operation_id, now)?;
+    operation.restored_at = Some(now);
+    Ok(operation)
+}
+
+/// Restore the most recent trash entry of each path that has one; returns
+/// how many did
+#[cfg(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+fn restore_files(paths: &[PathBuf]) -> AppResult<usize> {
+    let mut items = trash::os_limited::list().map_err(|e| AppError::Custom(format!("Reading the trash failed: {}", e)))?;
+    items.sort_by_key(|item| std::cmp::Reverse(item.time_deleted));
+
+    let mut restore = Vec::new();
+    for path in paths {
+        if let Some(index) = items.iter().position(|item| item.original_path() == *path) {
+            restore.push(items.remove(index));
+        }
+    }
+    let count = restore.len();
+    if count > 0 {
+        trash::os_limited::restore_all(restore)
+            .map_err(|e| AppError::Custom(format!("Restoring from the trash failed: {}", e)))?;
+    }
+    Ok(count)
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+fn restore_files(_paths: &[PathBuf]) -> AppResult<usize> {
+    Err(AppError::InvalidArgument(
+        "Restoring isn't supported on this system; use Put Back in the Trash instead".to_string(),
+    ))
+}
@@ -0,0 +1,440 @@
+//! Unity's Asset Store download cache (`Asset Store-5.x`), indexed as a
+//! library of its own so purchased packs can be searched without importing
+//! them, and single files extracted straight into a project.
+//!
+//! The cache holds `<publisher>/<category>/<package>.unitypackage`. A
+//! package is a gzipped tar with one folder per GUID containing `pathname`
+//! (the import path), `asset` (the file itself, absent for folders) and
+//! `asset.meta`.
+
+use crate::db::{Database, Project, StoreFile, StorePackage};
+use crate::duplicates::sha256_reader;
+use crate::error::{AppError, AppResult};
+use crate::provenance::{self, ProvenanceSource, SOURCE_ASSET_STORE};
+use crate::scanner::classify_file;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use tar::Archive;
+
+const CACHE_DIR_NAME: &str = "Asset Store-5.x";
+/// Overrides the cache location in Unity 2022.1+ (set in Unity Hub preferences)
+const CACHE_DIR_ENV: &str = "ASSETSTORE_CACHE_PATH";
+const PACKAGE_EXTENSION: &str = "unitypackage";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreIndexStats {
+    pub cache_dir: String,
+    pub packages: usize,
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub files: usize,
+    /// Packages that couldn't be read, typically unfinished downloads
+    pub failed: Vec<StorePackageError>,
+    /// False when cancelled; packages indexed so far are kept
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorePackageError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreExtraction {
+    pub dest_dir: String,
+    /// Paths written, relative to `dest_dir`
+    pub files: Vec<String>,
+    /// Requested GUIDs not found in the package
+    pub missing: Vec<String>,
+}
+
+/// Where Unity keeps downloaded packages on this machine: `configured`
+/// (from settings), then `ASSETSTORE_CACHE_PATH`, then the platform default
+pub fn cache_dir(configured: Option<&str>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV).filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(CACHE_DIR_NAME));
+    }
+
+    if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|home| home.join("Library").join("Unity").join(CACHE_DIR_NAME))
+    } else if cfg!(windows) {
+        dirs::data_dir().map(|appdata| appdata.join("Unity").join(CACHE_DIR_NAME))
+    } else {
+        dirs::data_dir().map(|data| data.join("unity3d").join(CACHE_DIR_NAME))
+    }
+}
+
+/// Index every package in `cache_dir`, re-reading only those whose size or
+/// modification time changed. `progress(done, total)` returns false to cancel.
+pub fn index_cache(
+    db: &Database,
+    cache_dir: &Path,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<StoreIndexStats> {
+    if !cache_dir.is_dir() {
+        return Err(AppError::NotFound(format!(
+            "Asset Store cache not found at {}",
+            cache_dir.display()
+        )));
+    }
+
+    let mut package_paths = Vec::new();
+    find_packages(cache_dir, &mut package_paths)?;
+    package_paths.sort();
+
+    let existing: HashMap<String, StorePackage> = db
+        .get_store_packages()?
+        .into_iter()
+        .map(|p| (p.path.clone(), p))
+        .collect();
+
+    let mut stats = StoreIndexStats {
+        cache_dir: cache_dir.to_string_lossy().to_string(),
+        packages: package_paths.len(),
+        ..Default::default()
+    };
+    let total = package_paths.len();
+    let mut seen = HashSet::new();
+
+    for (i, path) in package_paths.iter().enumerate() {
+        if !progress(i, total) {
+            return Ok(stats);
+        }
+
+        let path_str = path.to_string_lossy().to_string();
+        seen.insert(path_str.clone());
+
+        let metadata = fs::metadata(path)?;
+        let size_bytes = metadata.len() as i64;
+        let modified_time = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let previous = existing.get(&path_str);
+        if let Some(p) = previous {
+            if p.size_bytes == size_bytes && p.modified_time == modified_time {
+                stats.unchanged += 1;
+                stats.files += p.file_count as usize;
+                continue;
+            }
+        }
+
+        let package_id = previous
+            .map(|p| p.id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let files = match read_package_files(path, &package_id) {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                stats.failed.push(StorePackageError {
+                    path: path_str,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let (publisher, category) = package_location(cache_dir, path);
+        let package = StorePackage {
+            id: package_id,
+            path: path_str,
+            publisher,
+            category,
+            name: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size_bytes,
+            modified_time,
+            file_count: files.len() as i64,
+            indexed_at: chrono::Utc::now().timestamp(),
+        };
+        db.replace_store_package(&package, &files)?;
+        stats.indexed += 1;
+        stats.files += files.len();
+    }
+
+    let removed: Vec<String> = existing
+        .values()
+        .filter(|p| !seen.contains(&p.path))
+        .map(|p| p.id.clone())
+        .collect();
+    db.delete_store_packages(&removed)?;
+    stats.removed = removed.len();
+
+    progress(total, total);
+    stats.completed = true;
+    tracing::info!(
+        "Indexed Asset Store cache: {} packages ({} read, {} unchanged, {} failed), {} files",
+        stats.packages,
+        stats.indexed,
+        stats.unchanged,
+        stats.failed.len(),
+        stats.files
+    );
+    Ok(stats)
+}
+
+/// Copy the files with `guids` (and their `.meta`) out of a cached package
+/// into `dest_dir` at their import paths, so a project root receives them
+/// under `Assets/` exactly as Unity's importer would place them. Files that
+/// land inside `project` get a provenance record.
+pub fn extract_files(
+    db: &Database,
+    package_id: &str,
+    guids: &[String],
+    dest_dir: &Path,
+    project: Option<&Project>,
+) -> AppResult<StoreExtraction> {
+    let package = db
+        .get_store_package(package_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset Store package {}", package_id)))?;
+
+    let paths: HashMap<String, PathBuf> = db
+        .get_store_files(package_id)?
+        .into_iter()
+        .filter(|f| guids.contains(&f.guid))
+        .filter_map(|f| safe_relative_path(&f.path).map(|p| (f.guid, p)))
+        .collect();
+    let missing: Vec<String> = guids.iter().filter(|g| !paths.contains_key(*g)).cloned().collect();
+
+    let source = ProvenanceSource {
+        kind: SOURCE_ASSET_STORE,
+        id: &package.id,
+        name: &package.name,
+    };
+    let mut records = Vec::new();
+
+    let mut archive = open_package(Path::new(&package.path))?;
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let Some((guid, name)) = split_entry_path(&entry_path) else {
+            continue;
+        };
+        let Some(relative) = paths.get(&guid) else {
+            continue;
+        };
+
+        let is_asset = name == "asset";
+        let relative = match name.as_str() {
+            "asset" => relative.clone(),
+            "asset.meta" => {
+                let mut meta = relative.clone().into_os_string();
+                meta.push(".meta");
+                PathBuf::from(meta)
+            }
+            _ => continue,
+        };
+
+        let target = dest_dir.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = fs::File::create(&target)?;
+        io::copy(&mut entry, &mut out)?;
+        drop(out);
+        let file = relative.to_string_lossy().replace('\\', "/");
+        if let Some(project) = project.filter(|_| is_asset) {
+            match provenance::imported(project, &target, &source, &file, package.modified_time) {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("No provenance for {}: {}", file, e),
+            }
+        }
+        files.push(file);
+    }
+    files.sort();
+    provenance::record(db, &records);
+
+    tracing::info!(
+        "Extracted {} files from {} to {}",
+        files.len(),
+        package.name,
+        dest_dir.display()
+    );
+    Ok(StoreExtraction {
+        dest_dir: dest_dir.to_string_lossy().to_string(),
+        files,
+        missing,
+    })
+}
+
+/// SHA-256 of the files at `paths` (import paths) in a cached package as
+/// it is on disk now, read in one pass. Paths not in the package are left out.
+pub fn package_file_hashes(db: &Database, package: &StorePackage, paths: &[String]) -> AppResult<HashMap<String, String>> {
+    let guids = package_guids(db, &package.id, paths)?;
+    let mut hashes = HashMap::new();
+    let mut archive = open_package(Path::new(&package.path))?;
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let Some((guid, name)) = split_entry_path(&entry_path) else {
+            continue;
+        };
+        if let (Some(path), "asset") = (guids.get(&guid), name.as_str()) {
+            hashes.insert(path.clone(), sha256_reader(entry)?);
+        }
+    }
+    Ok(hashes)
+}
+
+/// Write the file at import path `path` of a cached package to `target`,
+/// leaving any `.meta` beside it alone. False if the package has no such file.
+pub fn extract_file_to(db: &Database, package: &StorePackage, path: &str, target: &Path) -> AppResult<bool> {
+    let guids = package_guids(db, &package.id, &[path.to_string()])?;
+    let mut archive = open_package(Path::new(&package.path))?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        match split_entry_path(&entry_path) {
+            Some((guid, name)) if name == "asset" && guids.contains_key(&guid) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                io::copy(&mut entry, &mut fs::File::create(target)?)?;
+                return Ok(true);
+            }
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// GUID -> import path of the indexed files at `paths`, compared with '/'
+/// separators as `extract_files` reports them
+fn package_guids(db: &Database, package_id: &str, paths: &[String]) -> AppResult<HashMap<String, String>> {
+    Ok(db
+        .get_store_files(package_id)?
+        .into_iter()
+        .map(|f| (f.guid, f.path.replace('\\', "/")))
+        .filter(|(_, path)| paths.contains(path))
+        .collect())
+}
+
+fn find_packages(dir: &Path, found: &mut Vec<PathBuf>) -> AppResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            find_packages(&path, found)?;
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(PACKAGE_EXTENSION))
+        {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Publisher and category from `<publisher>/<category>/<package>`; nested
+/// categories are joined with '/'
+fn package_location(cache_dir: &Path, path: &Path) -> (String, String) {
+    let parts: Vec<String> = path
+        .strip_prefix(cache_dir)
+        .unwrap_or(path)
+        .parent()
+        .map(|p| p.iter().map(|c| c.to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+
+    match parts.split_first() {
+        Some((publisher, category)) => (publisher.clone(), category.join("/")),
+        None => (String::new(), String::new()),
+    }
+}
+
+fn open_package(path: &Path) -> AppResult<Archive<GzDecoder<BufReader<fs::File>>>> {
+    let file = fs::File::open(path)?;
+    Ok(Archive::new(GzDecoder::new(BufReader::new(file))))
+}
+
+/// The files a package imports, skipping folders (GUID entries with no
+/// `asset`) and paths that would land outside the project
+fn read_package_files(path: &Path, package_id: &str) -> AppResult<Vec<StoreFile>> {
+    let mut pathnames: HashMap<String, String> = HashMap::new();
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+
+    let mut archive = open_package(path)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let Some((guid, name)) = split_entry_path(&entry_path) else {
+            continue;
+        };
+
+        match name.as_str() {
+            "pathname" => {
+                let mut text = String::new();
+                entry.read_to_string(&mut text)?;
+                // Some exporters append a second line ("00") after the path
+                let pathname = text.lines().next().unwrap_or_default().trim().to_string();
+                pathnames.insert(guid, pathname);
+            }
+            "asset" => {
+                sizes.insert(guid, entry.header().size()?);
+            }
+            _ => {}
+        }
+    }
+
+    let mut files: Vec<StoreFile> = pathnames
+        .into_iter()
+        .filter_map(|(guid, pathname)| {
+            let size = *sizes.get(&guid)?;
+            safe_relative_path(&pathname)?;
+            let file_path = Path::new(&pathname);
+            Some(StoreFile {
+                package_id: package_id.to_string(),
+                file_name: file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                extension: file_path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default(),
+                asset_type: classify_file(file_path).to_string(),
+                size_bytes: size as i64,
+                guid,
+                path: pathname,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(files)
+}
+
+/// `(guid, file name)` of an entry like `./<guid>/pathname`
+fn split_entry_path(path: &Path) -> Option<(String, String)> {
+    let mut parts = path.components().filter_map(|c| match c {
+        Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+        _ => None,
+    });
+    let guid = parts.next()?;
+    let name = parts.next()?;
+    parts.next().is_none().then_some((guid, name))
+}
+
+/// A package's import path, refused if it would escape the destination folder
+pub(crate) fn safe_relative_path(pathname: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(pathname.replace('\\', "/"));
+    let safe = path.components().all(|c| matches!(c, Component::Normal(_)));
+    (safe && !pathname.is_empty()).then_some(path)
+}
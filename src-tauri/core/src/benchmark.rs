@@ -0,0 +1,287 @@
+//! A fixed suite of timings over a real project, for comparing performance
+//! between versions and machines: full and incremental scan, dependency
+//! resolution, a batch of thumbnails and the queries the browser makes most.
+//!
+//! The scans and dependency pass write the same data a rescan would. The
+//! thumbnail step decodes, resizes and encodes in memory only, so it leaves
+//! the thumbnail cache and the stored thumbnail state alone.
+
+use crate::db::{Asset, AssetSort, Database, Project};
+use crate::deps::DependencyResolver;
+use crate::error::AppResult;
+use crate::indexer::Indexer;
+use crate::plugins::PluginRegistry;
+use crate::previews::open_image_limited;
+use crate::scanner::{scan_files_batch, ChangeDetection, DirectoryMtimes, WalkOptions};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
+
+/// Textures decoded by the thumbnail step
+const THUMBNAIL_BATCH: i64 = 1000;
+/// Times each query runs; the step reports the total
+const QUERY_RUNS: usize = 20;
+const QUERY_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkStep {
+    pub name: String,
+    pub duration_ms: f64,
+    /// Files, assets, thumbnails or query runs processed
+    pub items: usize,
+    pub items_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub app_version: String,
+    pub project_id: String,
+    pub asset_count: i64,
+    pub started_at: i64,
+    pub total_ms: f64,
+    pub steps: Vec<BenchmarkStep>,
+    /// False when cancelled; `steps` has what finished before that
+    pub completed: bool,
+}
+
+/// Run the suite. `progress` gets `(step, done, total)` and returns false to stop.
+pub fn run(
+    db: &Arc<Database>,
+    project: &Project,
+    settings: &Settings,
+    plugins: Option<Arc<PluginRegistry>>,
+    cancel_flag: Arc<AtomicBool>,
+    mut progress: impl FnMut(&str, usize, Option<usize>) -> bool,
+) -> AppResult<BenchmarkReport> {
+    let started = Instant::now();
+    let mut report = BenchmarkReport {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        project_id: project.id.clone(),
+        asset_count: 0,
+        started_at: chrono::Utc::now().timestamp(),
+        total_ms: 0.0,
+        steps: Vec::new(),
+        completed: false,
+    };
+    let stopped = |cancel_flag: &AtomicBool| cancel_flag.load(Ordering::SeqCst);
+
+    for (name, full) in [("full_scan", true), ("incremental_scan", false)] {
+        let timer = Instant::now();
+        let files = scan(db, project, settings, plugins.as_deref(), full, Arc::clone(&cancel_flag), |done| {
+            progress(name, done, None)
+        })?;
+        report.steps.push(step(name, timer, files));
+        if stopped(&cancel_flag) {
+            return Ok(finish(report, started));
+        }
+    }
+
+    let timer = Instant::now();
+    let mut resolver =
+        DependencyResolver::new(Arc::clone(db)).with_max_file_bytes(settings.dependency_max_file_bytes());
+    if let Some(plugins) = &plugins {
+        resolver = resolver.with_plugins(Arc::clone(plugins));
+    }
+    let parsed = resolver.resolve_all_for_project_with_progress(
+        &project.id,
+        settings.effective_dependency_threads(),
+        Arc::clone(&cancel_flag),
+        |done, total| {
+            if !progress("dependencies", done, Some(total)) {
+                cancel_flag.store(true, Ordering::SeqCst);
+            }
+        },
+    )?;
+    report.steps.push(step("dependencies", timer, parsed));
+    if stopped(&cancel_flag) {
+        return Ok(finish(report, started));
+    }
+
+    let timer = Instant::now();
+    let thumbnails = thumbnails(db, project, settings, &cancel_flag, &mut progress)?;
+    report.steps.push(step("thumbnails", timer, thumbnails));
+    if stopped(&cancel_flag) {
+        return Ok(finish(report, started));
+    }
+
+    let (first_page, total) = db.get_assets(&project.id, None, None, AssetSort::Name, 0, QUERY_PAGE_SIZE)?;
+    report.asset_count = total;
+    // Search for the start of a real file name so the query has hits
+    let term: String = first_page
+        .first()
+        .map(|asset| asset.file_name.chars().take_while(|c| c.is_alphanumeric()).take(3).collect())
+        .unwrap_or_default();
+    for name in ["query_first_page", "query_largest", "query_search", "query_type_counts", "query_dependents"] {
+        if !progress(name, 0, None) {
+            return Ok(finish(report, started));
+        }
+        let timer = Instant::now();
+        let runs = run_query(db, &project.id, name, &first_page, &term)?;
+        report.steps.push(step(name, timer, runs));
+    }
+
+    report.completed = true;
+    Ok(finish(report, started))
+}
+
+fn step(name: &str, timer: Instant, items: usize) -> BenchmarkStep {
+    let elapsed = timer.elapsed().as_secs_f64();
+    BenchmarkStep {
+        name: name.to_string(),
+        duration_ms: elapsed * 1000.0,
+        items,
+        items_per_sec: if elapsed > 0.0 { items as f64 / elapsed } else { 0.0 },
+    }
+}
+
+fn finish(mut report: BenchmarkReport, started: Instant) -> BenchmarkReport {
+    report.total_ms = started.elapsed().as_secs_f64() * 1000.0;
+    report
+}
+
+/// Walk and index the project the way a scan does, returning the files
+/// walked; `full` re-reads every file instead of skipping those whose size
+/// and mtime are unchanged
+fn scan(
+    db: &Arc<Database>,
+    project: &Project,
+    settings: &Settings,
+    plugins: Option<&PluginRegistry>,
+    full: bool,
+    cancel_flag: Arc<AtomicBool>,
+    mut progress: impl FnMut(usize) -> bool,
+) -> AppResult<usize> {
+    let excluded = db.get_excluded_paths(&project.id)?;
+    let walk = WalkOptions {
+        ignore_patterns: &settings.ignore_patterns,
+        threads: settings.effective_scan_threads(),
+        resume_after: None,
+        plugins,
+        excluded: Some(&excluded),
+    };
+    let existing = db.get_existing_asset_info(&project.id)?;
+    let directories = DirectoryMtimes::new(db.get_directory_mtimes(&project.id)?, settings.directory_change_detection);
+    let indexer = Indexer::new(Arc::clone(db));
+    let (_, stats) = scan_files_batch(
+        Path::new(&project.root_path),
+        &project.id,
+        walk,
+        settings.scan_batch_size,
+        Arc::clone(&cancel_flag),
+        Some(ChangeDetection {
+            existing: &existing,
+            skip_unchanged: !full,
+            directories: Some(&directories),
+        }),
+        |batch, count, _current_path| {
+            if let Err(e) = indexer.upsert_batch(&batch) {
+                tracing::error!("Failed to index batch: {}", e);
+            }
+            progress(count)
+        },
+    )?;
+    if !cancel_flag.load(Ordering::SeqCst) {
+        db.replace_directory_mtimes(&project.id, &directories.into_current())?;
+    }
+    Ok(stats.total_files)
+}
+
+/// Decode and shrink up to `THUMBNAIL_BATCH` textures on the thumbnail
+/// threads, encoding to memory
+fn thumbnails(
+    db: &Database,
+    project: &Project,
+    settings: &Settings,
+    cancel_flag: &AtomicBool,
+    progress: &mut impl FnMut(&str, usize, Option<usize>) -> bool,
+) -> AppResult<usize> {
+    let options = settings.thumbnail_options();
+    let (textures, _) = db.get_assets(
+        &project.id,
+        None,
+        Some(&["texture".to_string()]),
+        AssetSort::Name,
+        0,
+        THUMBNAIL_BATCH,
+    )?;
+    let textures: Vec<_> = textures
+        .into_iter()
+        .filter(|asset| {
+            matches!(asset.extension.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "tga" | "bmp" | "gif")
+                && !options.exceeds_decode_budget(Path::new(&asset.absolute_path))
+        })
+        .collect();
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel::<bool>();
+    let threads = settings.effective_thumbnail_threads().clamp(1, textures.len().max(1));
+    let mut processed = 0;
+    let mut encoded = 0;
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let tx = tx.clone();
+            let next = &next;
+            let textures = &textures;
+            let options = &options;
+            scope.spawn(move || loop {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Some(asset) = textures.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                    break;
+                };
+                let encoded = open_image_limited(Path::new(&asset.absolute_path), options).is_ok_and(|image| {
+                    let thumbnail = image.thumbnail(options.size, options.size);
+                    let mut buffer = std::io::Cursor::new(Vec::new());
+                    thumbnail.write_to(&mut buffer, image::ImageFormat::Png).is_ok()
+                });
+                if tx.send(encoded).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        // Report from this thread so the callback doesn't need to be `Send`;
+        // the channel closes as soon as the last worker exits
+        for ok in rx {
+            processed += 1;
+            encoded += ok as usize;
+            if !progress("thumbnails", processed, Some(textures.len())) {
+                cancel_flag.store(true, Ordering::SeqCst);
+            }
+        }
+    });
+
+    Ok(encoded)
+}
+
+/// Run one of the query steps `QUERY_RUNS` times, returning the run count
+fn run_query(db: &Database, project_id: &str, name: &str, first_page: &[Asset], term: &str) -> AppResult<usize> {
+    for _ in 0..QUERY_RUNS {
+        match name {
+            "query_first_page" => {
+                db.get_assets(project_id, None, None, AssetSort::Name, 0, QUERY_PAGE_SIZE)?;
+            }
+            "query_largest" => {
+                db.get_assets(project_id, None, None, AssetSort::Size, 0, QUERY_PAGE_SIZE)?;
+            }
+            "query_search" => {
+                db.get_assets(project_id, Some(term), None, AssetSort::Name, 0, QUERY_PAGE_SIZE)?;
+            }
+            "query_type_counts" => {
+                db.get_type_counts(project_id, None)?;
+            }
+            "query_dependents" => {
+                for asset in first_page {
+                    db.get_dependents(&asset.id)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(QUERY_RUNS)
+}
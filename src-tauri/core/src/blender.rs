@@ -0,0 +1,50 @@
+//! Model thumbnails rendered by a local Blender install. Blender runs in
+//! the background with the bundled `blender_thumbnail.py`, which imports
+//! the model, frames it and renders a PNG with Cycles on the CPU.
+
+use crate::process;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Model formats the render script can open
+pub const BLENDER_EXTENSIONS: &[&str] = &["blend", "fbx", "obj"];
+
+const RENDER_SCRIPT: &str = include_str!("blender_thumbnail.py");
+/// Blender is killed after this; a heavy scene shouldn't stall the thumbnail job
+const RENDER_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn renders(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| BLENDER_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Render `source` to a `size` x `size` PNG at `output`
+pub fn render_thumbnail(blender: &Path, source: &Path, output: &Path, size: u32) -> Result<(), String> {
+    let mut command = Command::new(blender);
+    command.args(["--background", "--factory-startup", "--disable-autoexec"]);
+    // A .blend is opened as the scene itself; other formats are imported by the script
+    if source.extension().is_some_and(|e| e.eq_ignore_ascii_case("blend")) {
+        command.arg(source);
+    }
+    command
+        .args(["--python-exit-code", "1", "--python-expr", RENDER_SCRIPT, "--"])
+        .arg(source)
+        .arg(output)
+        .arg(size.to_string());
+
+    let result = process::run_with_timeout(command, "Blender", RENDER_TIMEOUT)?;
+    if result.status.success() && output.is_file() {
+        return Ok(());
+    }
+
+    Err(match result.status.code() {
+        Some(3) => "Nothing to render (no visible meshes)".to_string(),
+        code => format!(
+            "Blender rendered no image (exit code {}): {}",
+            code.map(|c| c.to_string()).unwrap_or_else(|| "none".to_string()),
+            result.error_tail()
+        ),
+    })
+}
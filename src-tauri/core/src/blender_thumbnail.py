@@ -0,0 +1,72 @@
+# Renders a shaded thumbnail of a model. Run by Scythe as
+#   blender --background --factory-startup [file.blend] --python-expr <this> -- <source> <output.png> <size>
+# Works with Blender 2.93 and later. Exits non-zero when there is nothing to render.
+import math
+import sys
+
+import bpy
+from mathutils import Vector
+
+source, output, size = sys.argv[sys.argv.index("--") + 1:][:3]
+size = int(size)
+extension = source.rsplit(".", 1)[-1].lower()
+
+if extension != "blend":
+    bpy.ops.wm.read_factory_settings(use_empty=True)
+    if extension == "fbx":
+        bpy.ops.import_scene.fbx(filepath=source)
+    elif extension == "obj":
+        # The C++ importer replaced the Python one in 3.2 and the old one was removed in 4.0
+        if hasattr(bpy.ops.wm, "obj_import"):
+            bpy.ops.wm.obj_import(filepath=source)
+        else:
+            bpy.ops.import_scene.obj(filepath=source)
+    else:
+        sys.exit(2)
+
+scene = bpy.context.scene
+meshes = [o for o in scene.objects if o.type == "MESH" and not o.hide_render]
+if not meshes:
+    sys.exit(3)
+
+corners = [o.matrix_world @ Vector(corner) for o in meshes for corner in o.bound_box]
+low = Vector([min(c[i] for c in corners) for i in range(3)])
+high = Vector([max(c[i] for c in corners) for i in range(3)])
+center = (low + high) / 2
+radius = max((high - low).length / 2, 1e-4)
+
+# Three-quarter view from the front right, framing the bounding sphere
+camera_data = bpy.data.cameras.new("ScytheCamera")
+camera = bpy.data.objects.new("ScytheCamera", camera_data)
+scene.collection.objects.link(camera)
+direction = Vector((1.0, -1.0, 0.7)).normalized()
+distance = radius / math.sin(camera_data.angle / 2)
+camera.location = center + direction * distance
+camera.rotation_euler = (-direction).to_track_quat("-Z", "Y").to_euler()
+camera_data.clip_start = distance / 1000
+camera_data.clip_end = distance * 10
+scene.camera = camera
+
+light_data = bpy.data.lights.new("ScytheKey", type="SUN")
+light_data.energy = 3.0
+light = bpy.data.objects.new("ScytheKey", light_data)
+scene.collection.objects.link(light)
+light.rotation_euler = (-Vector((0.5, -1.0, 1.0)).normalized()).to_track_quat("-Z", "Y").to_euler()
+
+if scene.world is None:
+    scene.world = bpy.data.worlds.new("ScytheWorld")
+scene.world.use_nodes = False
+scene.world.color = (0.35, 0.35, 0.35)
+
+# Cycles on the CPU renders without a GPU or display, which background mode may not have
+scene.render.engine = "CYCLES"
+scene.cycles.device = "CPU"
+scene.cycles.samples = 16
+scene.render.resolution_x = size
+scene.render.resolution_y = size
+scene.render.resolution_percentage = 100
+scene.render.film_transparent = True
+scene.render.image_settings.file_format = "PNG"
+scene.render.image_settings.color_mode = "RGBA"
+scene.render.filepath = output
+bpy.ops.render.render(write_still=True)
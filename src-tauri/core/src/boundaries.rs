@@ -0,0 +1,194 @@
+//! Feature-folder dependency boundaries: folders set up in settings (such as
+//! `Assets/Features/*`, one boundary per feature folder) whose assets should
+//! only reference their own folder or shared assets outside every boundary.
+//! The report lists references that cross from one boundary into another.
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// References listed; counts include all of them
+pub const DEFAULT_LIMIT: usize = 1000;
+
+/// A boundary pattern is a folder relative to the project root in which `*`
+/// stands for any part of one folder name
+pub fn validate_boundary_pattern(pattern: &str) -> AppResult<()> {
+    let trimmed = pattern.trim().trim_end_matches(['/', '\\']);
+    let invalid = |reason: &str| AppError::InvalidArgument(format!("Dependency boundary {}: {}", pattern, reason));
+
+    if trimmed.is_empty() {
+        return Err(invalid("pattern is empty"));
+    }
+    if trimmed.contains(['?', '[', ']']) {
+        return Err(invalid("only * is supported as a wildcard"));
+    }
+    if trimmed.starts_with(['/', '\\']) || std::path::Path::new(trimmed).is_absolute() {
+        return Err(invalid("pattern must be relative to the project root"));
+    }
+    if trimmed.split(['/', '\\']).any(|segment| segment == ".." || segment.is_empty()) {
+        return Err(invalid("pattern cannot contain '..' or empty folder names"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundarySummary {
+    /// The boundary folder, e.g. `Assets/Features/Combat`
+    pub folder: String,
+    pub assets: usize,
+    /// References from this folder into other boundaries
+    pub outgoing: usize,
+    /// References from other boundaries into this folder
+    pub incoming: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryPair {
+    pub from_boundary: String,
+    pub to_boundary: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryViolation {
+    pub from_boundary: String,
+    pub from_path: String,
+    pub to_boundary: String,
+    pub to_path: String,
+    pub relation_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryReport {
+    pub patterns: Vec<String>,
+    /// Every folder matched by a pattern, the most entangled first
+    pub boundaries: Vec<BoundarySummary>,
+    pub violation_count: usize,
+    /// Cross-boundary references per direction, most first
+    pub pairs: Vec<BoundaryPair>,
+    pub violations: Vec<BoundaryViolation>,
+}
+
+/// References between different boundaries of `patterns`. An empty list of
+/// patterns gives an empty report.
+pub fn boundary_report(db: &Database, project_id: &str, patterns: &[String], limit: usize) -> AppResult<BoundaryReport> {
+    for pattern in patterns {
+        validate_boundary_pattern(pattern)?;
+    }
+    let patterns: Vec<Vec<&str>> = patterns
+        .iter()
+        .map(|p| p.trim().trim_end_matches(['/', '\\']).split(['/', '\\']).collect())
+        .collect();
+
+    let mut summaries: BTreeMap<String, BoundarySummary> = BTreeMap::new();
+    for asset in db.get_project_assets(project_id)? {
+        if let Some(folder) = boundary_of(&asset.relative_path, &patterns) {
+            summaries
+                .entry(folder.clone())
+                .or_insert_with(|| BoundarySummary {
+                    folder,
+                    assets: 0,
+                    outgoing: 0,
+                    incoming: 0,
+                })
+                .assets += 1;
+        }
+    }
+
+    let mut violations = Vec::new();
+    let mut pairs: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for link in db.get_dependency_links(project_id)? {
+        let Some(to_path) = link.to_path else {
+            continue;
+        };
+        let (Some(from_boundary), Some(to_boundary)) =
+            (boundary_of(&link.from_path, &patterns), boundary_of(&to_path, &patterns))
+        else {
+            continue;
+        };
+        if from_boundary == to_boundary {
+            continue;
+        }
+
+        if let Some(summary) = summaries.get_mut(&from_boundary) {
+            summary.outgoing += 1;
+        }
+        if let Some(summary) = summaries.get_mut(&to_boundary) {
+            summary.incoming += 1;
+        }
+        *pairs.entry((from_boundary.clone(), to_boundary.clone())).or_default() += 1;
+        violations.push(BoundaryViolation {
+            from_boundary,
+            from_path: link.from_path,
+            to_boundary,
+            to_path,
+            relation_type: link.relation_type,
+        });
+    }
+
+    violations.sort_by(|a, b| {
+        a.from_boundary
+            .cmp(&b.from_boundary)
+            .then_with(|| a.to_boundary.cmp(&b.to_boundary))
+            .then_with(|| a.from_path.cmp(&b.from_path))
+            .then_with(|| a.to_path.cmp(&b.to_path))
+    });
+    let mut pairs: Vec<BoundaryPair> = pairs
+        .into_iter()
+        .map(|((from_boundary, to_boundary), count)| BoundaryPair {
+            from_boundary,
+            to_boundary,
+            count,
+        })
+        .collect();
+    pairs.sort_by_key(|p| std::cmp::Reverse(p.count));
+    let mut boundaries: Vec<BoundarySummary> = summaries.into_values().collect();
+    boundaries.sort_by_key(|b| std::cmp::Reverse(b.outgoing + b.incoming));
+
+    Ok(BoundaryReport {
+        patterns: patterns.iter().map(|p| p.join("/")).collect(),
+        boundaries,
+        violation_count: violations.len(),
+        pairs,
+        violations: violations.into_iter().take(limit).collect(),
+    })
+}
+
+/// The boundary folder a path is inside; the most specific pattern wins
+fn boundary_of(relative_path: &str, patterns: &[Vec<&str>]) -> Option<String> {
+    let segments: Vec<&str> = relative_path.split(['/', '\\']).collect();
+    patterns
+        .iter()
+        .filter(|pattern| {
+            segments.len() > pattern.len()
+                && pattern.iter().zip(&segments).all(|(p, s)| wildcard_match(p, s))
+        })
+        .map(|pattern| pattern.len())
+        .max()
+        .map(|depth| segments[..depth].join("/"))
+}
+
+/// Match one folder name against a pattern where `*` is any run of
+/// characters, ignoring ASCII case as Windows and macOS do
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the whole name must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
@@ -0,0 +1,254 @@
+//! Import of the Addressables build layout report, mapping built bundle
+//! sizes back to the source assets that went into them.
+//!
+//! Addressables writes the report to `Library/com.unity.addressables/` when
+//! "Debug Build Layout" is enabled: `buildlayout.txt` in older versions,
+//! `buildlayout.json` from 1.21. Both list each bundle's file size and the
+//! assets packed into it, explicitly (addressable entries) or implicitly
+//! (pulled in as dependencies, possibly duplicated across bundles).
+
+use crate::db::{BuiltBundle, BundledAsset, Database};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LAYOUT_DIR: &str = "Library/com.unity.addressables";
+const LAYOUT_FILES: &[&str] = &["buildlayout.json", "buildlayout.txt"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildLayoutImport {
+    pub path: String,
+    pub bundles: usize,
+    pub assets: usize,
+    /// Bundled assets matched to an indexed asset by GUID or path
+    pub matched: usize,
+    /// Distinct bundled paths that aren't in the index, e.g. deleted since the build
+    pub unmatched: Vec<String>,
+}
+
+/// The report the last Addressables build left in `project_root`, if any
+pub fn find_layout(project_root: &Path) -> Option<PathBuf> {
+    LAYOUT_FILES
+        .iter()
+        .map(|name| project_root.join(LAYOUT_DIR).join(name))
+        .find(|path| path.is_file())
+}
+
+/// Parse `path` and replace the project's stored build layout with it
+pub fn import(db: &Database, project_id: &str, path: &Path) -> AppResult<BuildLayoutImport> {
+    let text = fs::read_to_string(path)?;
+    let (bundles, mut assets) = if text.trim_start().starts_with('{') {
+        parse_json(&serde_json::from_str(&text)?)
+    } else {
+        parse_text(&text)
+    };
+    if bundles.is_empty() {
+        return Err(AppError::InvalidArgument(format!(
+            "No bundles found in {}; is it an Addressables build layout?",
+            path.display()
+        )));
+    }
+
+    let matcher = AssetMatcher::load(db, project_id)?;
+    let mut unmatched = Vec::new();
+    for entry in &mut assets {
+        entry.asset_id = matcher.find(entry.guid.as_deref(), &entry.asset_path);
+        if entry.asset_id.is_none() {
+            unmatched.push(entry.asset_path.clone());
+        }
+    }
+    unmatched.sort();
+    unmatched.dedup();
+
+    db.replace_build_layout(project_id, &bundles, &assets)?;
+
+    Ok(BuildLayoutImport {
+        path: path.to_string_lossy().to_string(),
+        bundles: bundles.len(),
+        assets: assets.len(),
+        matched: assets.iter().filter(|a| a.asset_id.is_some()).count(),
+        unmatched,
+    })
+}
+
+/// Finds indexed assets for the paths and GUIDs Unity reports in builds
+pub(crate) struct AssetMatcher {
+    by_guid: HashMap<String, String>,
+    /// Keyed by lowercase path with forward slashes
+    by_path: HashMap<String, String>,
+}
+
+impl AssetMatcher {
+    pub(crate) fn load(db: &Database, project_id: &str) -> AppResult<Self> {
+        let mut by_guid = HashMap::new();
+        let mut by_path = HashMap::new();
+        for asset in db.get_project_assets(project_id)? {
+            by_path.insert(asset.relative_path.replace('\\', "/").to_lowercase(), asset.id.clone());
+            if let Some(guid) = asset.unity_guid {
+                by_guid.insert(guid, asset.id);
+            }
+        }
+        Ok(Self { by_guid, by_path })
+    }
+
+    /// Asset id by GUID, falling back to the path
+    pub(crate) fn find(&self, guid: Option<&str>, path: &str) -> Option<String> {
+        guid.and_then(|guid| self.by_guid.get(guid))
+            .or_else(|| self.by_path.get(&path.replace('\\', "/").to_lowercase()))
+            .cloned()
+    }
+}
+
+/// The text report: indented sections per group and archive, with sizes in
+/// parentheses after each name, e.g.
+/// `Assets/Hero.prefab (Total Size: 45KB, Size from Objects: 40KB, ...)`
+fn parse_text(text: &str) -> (Vec<BuiltBundle>, Vec<BundledAsset>) {
+    let mut bundles: Vec<BuiltBundle> = Vec::new();
+    let mut assets = Vec::new();
+    let mut group = String::new();
+    // None outside an asset list, otherwise whether it's the explicit one
+    let mut explicit: Option<bool> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (name, props) = split_props(line);
+
+        if let Some(rest) = name.strip_prefix("Group ") {
+            group = rest.trim().to_string();
+            explicit = None;
+        } else if let Some(rest) = name.strip_prefix("Archive ") {
+            bundles.push(BuiltBundle {
+                name: rest.trim().to_string(),
+                group_name: group.clone(),
+                size_bytes: prop_size(&props, "Size").unwrap_or(0),
+                compression: props.get("Compression").map(|c| c.to_string()),
+                asset_count: 0,
+            });
+            explicit = None;
+        } else if name == "Explicit Assets" {
+            explicit = Some(true);
+        } else if name.starts_with("Data From Other Assets") {
+            explicit = Some(false);
+        } else if let (Some(explicit), Some(bundle)) = (explicit, bundles.last()) {
+            if !(name.starts_with("Assets/") || name.starts_with("Packages/")) {
+                continue;
+            }
+            let size = prop_size(&props, "Total Size").or_else(|| {
+                let objects = prop_size(&props, "Size from Objects");
+                let streamed = prop_size(&props, "Size from Streamed Data");
+                (objects.is_some() || streamed.is_some()).then(|| objects.unwrap_or(0) + streamed.unwrap_or(0))
+            });
+            // Reference lists under an asset repeat paths without sizes
+            if let Some(size) = size {
+                assets.push(BundledAsset {
+                    bundle: bundle.name.clone(),
+                    asset_path: name.to_string(),
+                    guid: None,
+                    asset_id: None,
+                    size_bytes: size,
+                    explicit,
+                });
+            }
+        }
+    }
+
+    (bundles, assets)
+}
+
+/// `Name (Key: value, Key: value)` -> ("Name", {Key: value})
+fn split_props(line: &str) -> (&str, HashMap<&str, &str>) {
+    // The last parenthesis, since asset names can contain one: "Rock (1).fbx (Size: ...)"
+    let Some((name, rest)) = line.rsplit_once(" (") else {
+        return (line, HashMap::new());
+    };
+    let props = rest
+        .trim_end_matches(')')
+        .split(", ")
+        .filter_map(|pair| pair.split_once(": "))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    (name, props)
+}
+
+fn prop_size(props: &HashMap<&str, &str>, key: &str) -> Option<i64> {
+    props.get(key).and_then(|value| parse_size(value))
+}
+
+/// "1.5MB", "12.3KB", "0B" -> bytes
+pub(crate) fn parse_size(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let scale = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * scale).round() as i64)
+}
+
+/// The JSON report is a Unity `[SerializeReference]` dump: every object sits
+/// in `references.RefIds` tagged with its class, linked by `{"rid": n}`
+fn parse_json(report: &Value) -> (Vec<BuiltBundle>, Vec<BundledAsset>) {
+    let refs = report["references"]["RefIds"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let by_rid: HashMap<i64, &Value> = refs
+        .iter()
+        .filter_map(|r| Some((r["rid"].as_i64()?, r)))
+        .collect();
+    // "BuildLayout/Bundle" -> "Bundle"
+    let class = |r: &Value| {
+        let class = r["type"]["class"].as_str().unwrap_or_default();
+        class.rsplit('/').next().unwrap_or_default().to_string()
+    };
+    let linked = |link: &Value| link["rid"].as_i64().and_then(|rid| by_rid.get(&rid)).map(|r| &r["data"]);
+
+    let mut bundles = Vec::new();
+    let mut assets = Vec::new();
+    for r in refs {
+        let data = &r["data"];
+        match class(r).as_str() {
+            "Bundle" => bundles.push(BuiltBundle {
+                name: data["Name"].as_str().unwrap_or_default().to_string(),
+                group_name: linked(&data["Group"])
+                    .and_then(|g| g["Name"].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                size_bytes: data["FileSize"].as_i64().unwrap_or(0),
+                compression: data["Compression"].as_str().map(str::to_string),
+                asset_count: 0,
+            }),
+            kind @ ("ExplicitAsset" | "DataFromOtherAsset") => {
+                let explicit = kind == "ExplicitAsset";
+                // Implicit assets belong to a file, which belongs to the bundle
+                let bundle = if explicit {
+                    linked(&data["Bundle"])
+                } else {
+                    linked(&data["File"]).and_then(|file| linked(&file["Bundle"]))
+                };
+                let (Some(bundle), Some(path)) = (bundle.and_then(|b| b["Name"].as_str()), data["AssetPath"].as_str())
+                else {
+                    continue;
+                };
+                let guid = if explicit { &data["Guid"] } else { &data["AssetGuid"] };
+                assets.push(BundledAsset {
+                    bundle: bundle.to_string(),
+                    asset_path: path.to_string(),
+                    guid: guid.as_str().filter(|g| !g.is_empty()).map(str::to_string),
+                    asset_id: None,
+                    size_bytes: data["SerializedSize"].as_i64().unwrap_or(0)
+                        + data["StreamedSize"].as_i64().unwrap_or(0),
+                    explicit,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (bundles, assets)
+}
@@ -0,0 +1,158 @@
+//! Import of a player build report, recording what each source asset
+//! takes up in the build: the size after import settings (texture
+//! compression, mesh and audio import) rather than the size of the source
+//! file on disk.
+//!
+//! Two sources are read: the "Build Report" Unity appends to `Editor.log`
+//! after every player build, and a `BuildReport` saved as JSON by an editor
+//! script (`packedAssets[].contents[]`, as `JsonUtility.ToJson` writes it).
+
+use crate::build_layout::{parse_size, AssetMatcher};
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const REPORT_START: &str = "Build Report";
+const ASSET_LIST_START: &str = "Used Assets and files from the Resources folder";
+const TOTAL_SIZE_PREFIX: &str = "Complete build size";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildReportImport {
+    pub path: String,
+    /// Whole player size, when the report states it
+    pub build_size_bytes: Option<i64>,
+    pub assets: usize,
+    pub matched: usize,
+    /// Reported paths that aren't in the index, e.g. built-in resources
+    pub unmatched: Vec<String>,
+}
+
+/// Where the editor writes `Editor.log` on this machine
+pub fn editor_log_path() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|home| home.join("Library").join("Logs").join("Unity").join("Editor.log"))
+    } else if cfg!(windows) {
+        dirs::data_local_dir().map(|local| local.join("Unity").join("Editor").join("Editor.log"))
+    } else {
+        dirs::config_dir().map(|config| config.join("unity3d").join("Editor.log"))
+    }
+}
+
+/// Parse `path` and replace the project's recorded build sizes with it
+pub fn import(db: &Database, project_id: &str, path: &Path) -> AppResult<BuildReportImport> {
+    // Editor.log is rewritten on every editor start and can hold stray non-UTF-8 output
+    let text = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let report = if text.trim_start().starts_with('{') {
+        parse_json(&serde_json::from_str(&text)?)
+    } else {
+        parse_editor_log(&text)
+    }
+    .ok_or_else(|| {
+        AppError::InvalidArgument(format!(
+            "No build report found in {}; make a player build first",
+            path.display()
+        ))
+    })?;
+
+    let matcher = AssetMatcher::load(db, project_id)?;
+    let mut sizes: HashMap<String, i64> = HashMap::new();
+    let mut unmatched = Vec::new();
+    for entry in &report.assets {
+        match matcher.find(entry.guid.as_deref(), &entry.path) {
+            Some(asset_id) => *sizes.entry(asset_id).or_default() += entry.size_bytes,
+            None => unmatched.push(entry.path.clone()),
+        }
+    }
+    unmatched.sort();
+    unmatched.dedup();
+
+    let sizes: Vec<(String, i64)> = sizes.into_iter().collect();
+    db.replace_build_sizes(project_id, &sizes)?;
+
+    Ok(BuildReportImport {
+        path: path.to_string_lossy().to_string(),
+        build_size_bytes: report.total_bytes,
+        assets: report.assets.len(),
+        matched: sizes.len(),
+        unmatched,
+    })
+}
+
+struct ParsedReport {
+    total_bytes: Option<i64>,
+    assets: Vec<ReportedAsset>,
+}
+
+struct ReportedAsset {
+    path: String,
+    guid: Option<String>,
+    size_bytes: i64,
+}
+
+/// The last build report in the log. The asset list looks like
+/// ` 1.2 mb  4.1% Assets/Textures/Rock.png` (tab separated) and ends at a
+/// line of dashes.
+fn parse_editor_log(text: &str) -> Option<ParsedReport> {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.iter().rposition(|line| line.trim() == REPORT_START)?;
+
+    let mut total_bytes = None;
+    let mut assets = Vec::new();
+    let mut in_list = false;
+    for line in &lines[start + 1..] {
+        let line = line.trim();
+        if in_list {
+            if line.starts_with("---") || line.is_empty() {
+                break;
+            }
+            if let Some(asset) = parse_asset_line(line) {
+                assets.push(asset);
+            }
+        } else if line.starts_with(ASSET_LIST_START) {
+            in_list = true;
+        } else if let Some(size) = line.strip_prefix(TOTAL_SIZE_PREFIX) {
+            total_bytes = parse_size(size);
+        }
+    }
+
+    Some(ParsedReport { total_bytes, assets })
+}
+
+/// "1.2 mb  4.1% Assets/Textures/Rock.png": size, unit, share, then the path
+fn parse_asset_line(line: &str) -> Option<ReportedAsset> {
+    let (number, rest) = line.split_once(char::is_whitespace)?;
+    let (unit, rest) = rest.trim_start().split_once(char::is_whitespace)?;
+    let (share, path) = rest.trim_start().split_once(char::is_whitespace)?;
+    if !share.ends_with('%') {
+        return None;
+    }
+    Some(ReportedAsset {
+        path: path.trim().to_string(),
+        guid: None,
+        size_bytes: parse_size(&format!("{}{}", number, unit))?,
+    })
+}
+
+fn parse_json(report: &Value) -> Option<ParsedReport> {
+    let packed = report["packedAssets"].as_array()?;
+    let assets = packed
+        .iter()
+        .flat_map(|file| file["contents"].as_array().map(Vec::as_slice).unwrap_or_default())
+        .filter_map(|content| {
+            Some(ReportedAsset {
+                path: content["sourceAssetPath"].as_str().filter(|p| !p.is_empty())?.to_string(),
+                guid: content["sourceAssetGUID"].as_str().map(str::to_string),
+                size_bytes: content["packedSize"].as_i64().unwrap_or(0),
+            })
+        })
+        .collect();
+
+    Some(ParsedReport {
+        total_bytes: report["summary"]["totalSize"].as_i64(),
+        assets,
+    })
+}
@@ -0,0 +1,477 @@
+use crate::db::{Asset, AssetListEntry, Database, DependencyLink};
+use crate::deps::{DependencyGraph, GraphNode};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Same filters as the asset grid, without paging
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetListFilter {
+    pub search_query: Option<String>,
+    pub asset_types: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetListExport {
+    pub path: String,
+    pub format: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub path: String,
+    pub nodes: usize,
+    pub edges: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownExport {
+    pub folder: String,
+    pub total: usize,
+    pub written: usize,
+    /// Notes whose generated part was already up to date
+    pub unchanged: usize,
+    /// False if the export was stopped before every note was written
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributionExport {
+    pub path: String,
+    pub rows: usize,
+    /// Rows without a license field, to fill in before a release
+    pub unlicensed: usize,
+}
+
+/// Heading that starts the hand-written part of a note. Everything from
+/// this line on is kept when the notes are exported again.
+const NOTES_HEADING: &str = "## Notes";
+
+const CSV_COLUMNS: &[&str] = &[
+    "relative_path",
+    "file_name",
+    "asset_type",
+    "extension",
+    "size_bytes",
+    "modified_time",
+    "unity_guid",
+    "content_hash",
+    "dependency_count",
+    "missing_dependency_count",
+    "dependent_count",
+    "id",
+];
+
+/// Write the filtered asset table to `path` as "csv" or "json"
+pub fn export_asset_list(
+    db: &Database,
+    project_id: &str,
+    filter: &AssetListFilter,
+    format: &str,
+    path: &Path,
+) -> AppResult<AssetListExport> {
+    if !matches!(format, "csv" | "json") {
+        return Err(AppError::InvalidArgument(format!("Unknown list format: {}", format)));
+    }
+
+    let entries = db.get_asset_listing(
+        project_id,
+        filter.search_query.as_deref(),
+        filter.asset_types.as_deref(),
+    )?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    if format == "csv" {
+        write_csv(&mut out, &entries)?;
+    } else {
+        serde_json::to_writer_pretty(&mut out, &entries)?;
+    }
+    out.flush()?;
+
+    Ok(AssetListExport {
+        path: path.to_string_lossy().to_string(),
+        format: format.to_string(),
+        rows: entries.len(),
+    })
+}
+
+fn write_csv(out: &mut impl Write, entries: &[AssetListEntry]) -> AppResult<()> {
+    writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+    for e in entries {
+        let fields = [
+            csv_field(&e.relative_path),
+            csv_field(&e.file_name),
+            csv_field(&e.asset_type),
+            csv_field(&e.extension),
+            e.size_bytes.to_string(),
+            e.modified_time.to_string(),
+            csv_field(e.unity_guid.as_deref().unwrap_or("")),
+            csv_field(e.content_hash.as_deref().unwrap_or("")),
+            e.dependency_count.to_string(),
+            e.missing_dependency_count.to_string(),
+            e.dependent_count.to_string(),
+            csv_field(&e.id),
+        ];
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Metadata fields written as attribution columns, matched case-insensitively
+const ATTRIBUTION_FIELDS: &[&str] = &["source", "license", "author"];
+
+/// Write the source, license and author fields of a selection to a CSV
+/// for release attribution: `asset_ids`, or every indexed asset built into
+/// `bundle` (from the imported build layout). With `include_dependencies`
+/// everything the selection references is listed too. Rows are grouped
+/// by license, then source.
+pub fn export_attribution_csv(
+    db: &Database,
+    project_id: &str,
+    asset_ids: Option<Vec<String>>,
+    bundle: Option<&str>,
+    include_dependencies: bool,
+    path: &Path,
+) -> AppResult<AttributionExport> {
+    let mut selected: Vec<String> = match (asset_ids, bundle) {
+        (Some(_), Some(_)) => {
+            return Err(AppError::InvalidArgument("Pick either assets or a bundle, not both".to_string()));
+        }
+        (Some(ids), None) => ids,
+        (None, Some(bundle)) => db
+            .get_bundle_asset_ids(project_id, bundle)?
+            .ok_or_else(|| AppError::NotFound(format!("Bundle {} is not in the imported build layout", bundle)))?,
+        (None, None) => return Err(AppError::InvalidArgument("No assets selected".to_string())),
+    };
+
+    if include_dependencies {
+        let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+        for dep in db.get_project_dependencies(project_id)? {
+            if let Some(to) = dep.to_asset_id {
+                edges.entry(dep.from_asset_id).or_default().push(to);
+            }
+        }
+        let mut seen: HashSet<String> = selected.iter().cloned().collect();
+        let mut next = 0;
+        while let Some(current) = selected.get(next) {
+            next += 1;
+            let added: Vec<String> = edges
+                .get(current)
+                .into_iter()
+                .flatten()
+                .filter(|to| seen.insert((*to).clone()))
+                .cloned()
+                .collect();
+            selected.extend(added);
+        }
+    }
+
+    let selected: HashSet<String> = selected.into_iter().collect();
+    let fields = db.get_project_asset_fields(project_id)?;
+    let empty = Default::default();
+    let mut rows: Vec<(Vec<String>, Asset)> = db
+        .get_project_assets(project_id)?
+        .into_iter()
+        .filter(|a| selected.contains(&a.id))
+        .map(|a| {
+            let asset_fields = fields.get(&a.id).unwrap_or(&empty);
+            let values = ATTRIBUTION_FIELDS
+                .iter()
+                .map(|name| {
+                    asset_fields
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                        .map(|(_, value)| value.trim().to_string())
+                        .unwrap_or_default()
+                })
+                .collect();
+            (values, a)
+        })
+        .collect();
+    // ATTRIBUTION_FIELDS order is source, license, author
+    rows.sort_by(|(x, a), (y, b)| {
+        x[1].cmp(&y[1])
+            .then_with(|| x[0].cmp(&y[0]))
+            .then_with(|| a.relative_path.cmp(&b.relative_path))
+    });
+
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "relative_path,asset_type,{},unity_guid", ATTRIBUTION_FIELDS.join(","))?;
+    for (values, asset) in &rows {
+        let mut line = vec![csv_field(&asset.relative_path), csv_field(&asset.asset_type)];
+        line.extend(values.iter().map(|v| csv_field(v)));
+        line.push(csv_field(asset.unity_guid.as_deref().unwrap_or("")));
+        writeln!(out, "{}", line.join(","))?;
+    }
+    out.flush()?;
+
+    Ok(AttributionExport {
+        path: path.to_string_lossy().to_string(),
+        rows: rows.len(),
+        unlicensed: rows.iter().filter(|(values, _)| values[1].is_empty()).count(),
+    })
+}
+
+/// Quote a field if it contains a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Node fill colours, matching the type badges in the UI
+fn type_color(asset_type: &str) -> &'static str {
+    match asset_type {
+        "texture" => "#3b82f6",
+        "model" => "#8b5cf6",
+        "material" => "#f59e0b",
+        "prefab" => "#10b981",
+        "audio" => "#ec4899",
+        "video" => "#f43f5e",
+        "shader" => "#6366f1",
+        "scene" => "#14b8a6",
+        "script" => "#84cc16",
+        _ => "#64748b",
+    }
+}
+
+/// Render a dependency graph as Graphviz DOT. Nodes are coloured by type
+/// and scaled by file size; the root gets a double border and nodes with
+/// unresolved references a red outline.
+pub fn dependency_graph_dot(graph: &DependencyGraph) -> String {
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph dependencies {{");
+    let _ = writeln!(dot, "  rankdir=LR;");
+    let _ = writeln!(
+        dot,
+        "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\", fontcolor=white];"
+    );
+    let _ = writeln!(dot, "  edge [color=\"#94a3b8\", fontname=\"Helvetica\", fontsize=9];");
+
+    for node in &graph.nodes {
+        let _ = writeln!(dot, "  {} [{}];", dot_string(&node.id), node_attributes(node, node.id == graph.root_id));
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            dot,
+            "  {} -> {} [label={}];",
+            dot_string(&edge.from),
+            dot_string(&edge.to),
+            dot_string(&edge.relation_type)
+        );
+    }
+    if graph.truncated {
+        let _ = writeln!(dot, "  label=\"Graph truncated at the node limit\";");
+    }
+    let _ = writeln!(dot, "}}");
+    dot
+}
+
+fn node_attributes(node: &GraphNode, is_root: bool) -> String {
+    // 1 KB maps to the smallest box, 100 MB and up to the largest
+    let scale = (((node.size_bytes.max(1) as f64).log10() - 3.0) / 5.0).clamp(0.0, 1.0);
+
+    let mut label = format!("{}\n{}", node.file_name, format_bytes(node.size_bytes));
+    if node.missing_references > 0 {
+        label.push_str(&format!("\n{} missing", node.missing_references));
+    }
+
+    let mut attributes = vec![
+        format!("label={}", dot_string(&label)),
+        format!("tooltip={}", dot_string(&node.relative_path)),
+        format!("fillcolor=\"{}\"", type_color(&node.asset_type)),
+        format!("width={:.2}", 1.2 + 1.8 * scale),
+        format!("height={:.2}", 0.5 + 0.7 * scale),
+        format!("fontsize={:.0}", 10.0 + 8.0 * scale),
+    ];
+    if node.missing_references > 0 {
+        attributes.push("color=\"#ef4444\", penwidth=2".to_string());
+    }
+    if is_root {
+        attributes.push("peripheries=2".to_string());
+    }
+    attributes.join(", ")
+}
+
+/// Write `dependency_graph_dot` to a file
+pub fn write_dependency_dot(graph: &DependencyGraph, path: &Path) -> AppResult<GraphExport> {
+    std::fs::write(path, dependency_graph_dot(graph))?;
+    Ok(GraphExport {
+        path: path.to_string_lossy().to_string(),
+        nodes: graph.nodes.len(),
+        edges: graph.edges.len(),
+        truncated: graph.truncated,
+    })
+}
+
+/// Quoted DOT string (asset ids are UUIDs, which aren't valid bare
+/// identifiers). Line breaks become DOT's centred `\n`.
+fn dot_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Write one Markdown note per asset into `folder`, mirroring the project
+/// tree (`Assets/Foo.mat` becomes `Assets/Foo.mat.md`). Notes carry the
+/// asset metadata as front matter and link dependencies and dependents
+/// with `[[wiki-links]]`, so the folder can be opened as an Obsidian vault.
+///
+/// `progress(done, total)` is called after each note; returning false stops.
+pub fn export_markdown_notes(
+    db: &Database,
+    project_id: &str,
+    filter: &AssetListFilter,
+    folder: &Path,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<MarkdownExport> {
+    let entries = db.get_asset_listing(
+        project_id,
+        filter.search_query.as_deref(),
+        filter.asset_types.as_deref(),
+    )?;
+
+    let mut dependencies: HashMap<&str, Vec<&DependencyLink>> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&DependencyLink>> = HashMap::new();
+    let links = db.get_dependency_links(project_id)?;
+    for link in &links {
+        dependencies.entry(link.from_asset_id.as_str()).or_default().push(link);
+        if let Some(to) = &link.to_asset_id {
+            dependents.entry(to.as_str()).or_default().push(link);
+        }
+    }
+
+    std::fs::create_dir_all(folder)?;
+    let mut result = MarkdownExport {
+        folder: folder.to_string_lossy().to_string(),
+        total: entries.len(),
+        written: 0,
+        unchanged: 0,
+        completed: false,
+    };
+
+    for (done, entry) in entries.iter().enumerate() {
+        if !progress(done, entries.len()) {
+            return Ok(result);
+        }
+
+        let path = folder.join(format!("{}.md", entry.relative_path));
+        let existing = std::fs::read_to_string(&path).ok();
+        let notes = existing
+            .as_deref()
+            .and_then(|content| content.find(&format!("\n{}", NOTES_HEADING)).map(|i| content[i + 1..].to_string()))
+            .unwrap_or_else(|| format!("{}\n", NOTES_HEADING));
+
+        let content = format!(
+            "{}{}",
+            asset_note(
+                entry,
+                dependencies.get(entry.id.as_str()).map(Vec::as_slice).unwrap_or_default(),
+                dependents.get(entry.id.as_str()).map(Vec::as_slice).unwrap_or_default(),
+            ),
+            notes
+        );
+
+        if existing.as_deref() == Some(content.as_str()) {
+            result.unchanged += 1;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)?;
+        result.written += 1;
+    }
+
+    progress(entries.len(), entries.len());
+    result.completed = true;
+    Ok(result)
+}
+
+/// Generated part of a note, up to (not including) the notes heading
+fn asset_note(entry: &AssetListEntry, dependencies: &[&DependencyLink], dependents: &[&DependencyLink]) -> String {
+    let modified = chrono::DateTime::from_timestamp(entry.modified_time, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+
+    // JSON strings are valid YAML scalars and quote paths containing ':' or '#'
+    let mut note = String::from("---\n");
+    let _ = writeln!(note, "id: {}", yaml_string(&entry.id));
+    let _ = writeln!(note, "path: {}", yaml_string(&wiki_target(&entry.relative_path)));
+    let _ = writeln!(note, "type: {}", yaml_string(&entry.asset_type));
+    let _ = writeln!(note, "extension: {}", yaml_string(&entry.extension));
+    let _ = writeln!(note, "size_bytes: {}", entry.size_bytes);
+    let _ = writeln!(note, "modified: {}", yaml_string(&modified));
+    if let Some(guid) = &entry.unity_guid {
+        let _ = writeln!(note, "guid: {}", yaml_string(guid));
+    }
+    if let Some(hash) = &entry.content_hash {
+        let _ = writeln!(note, "content_hash: {}", yaml_string(hash));
+    }
+    let _ = writeln!(note, "dependencies: {}", entry.dependency_count);
+    let _ = writeln!(note, "dependents: {}", entry.dependent_count);
+    let _ = writeln!(note, "tags: [scythe, {}]", entry.asset_type);
+    note.push_str("---\n\n");
+
+    let _ = writeln!(note, "# {}\n", entry.file_name);
+    let _ = writeln!(note, "`{}` · {} · {}\n", entry.asset_type, format_bytes(entry.size_bytes), entry.relative_path);
+
+    note.push_str("## Dependencies\n\n");
+    if dependencies.is_empty() {
+        note.push_str("None\n");
+    }
+    for link in dependencies {
+        match &link.to_path {
+            Some(to) => {
+                let _ = writeln!(note, "- [[{}]] ({})", wiki_target(to), link.relation_type);
+            }
+            None => {
+                let _ = writeln!(note, "- Missing `{}` ({})", link.to_guid, link.relation_type);
+            }
+        }
+    }
+
+    note.push_str("\n## Used by\n\n");
+    if dependents.is_empty() {
+        note.push_str("None\n");
+    }
+    for link in dependents {
+        let _ = writeln!(note, "- [[{}]] ({})", wiki_target(&link.from_path), link.relation_type);
+    }
+    note.push('\n');
+    note
+}
+
+/// Link target for a note: its vault path with `/` separators and no `.md`
+fn wiki_target(relative_path: &str) -> String {
+    relative_path.replace('\\', "/")
+}
+
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
@@ -0,0 +1,76 @@
+//! Opening text assets (scripts, shaders) in the user's code editor at a
+//! given line, e.g. from an in-file search hit.
+//!
+//! Arguments may use `{file}`, `{line}` and `{column}`; line and column are
+//! 1-based and default to 1.
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeEditor {
+    pub name: String,
+    /// Executable name on PATH or full path
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl CodeEditor {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.command.trim().is_empty() {
+            return Err(AppError::InvalidArgument(format!("Code editor {} has no command", self.name)));
+        }
+        if !self.args.iter().any(|a| a.contains("{file}")) {
+            return Err(AppError::InvalidArgument(format!(
+                "Code editor {} never passes {{file}} to the editor",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Ready-made setups for common editors; the command may need a full path
+/// when the editor's launcher isn't on PATH
+pub fn presets() -> Vec<CodeEditor> {
+    let preset = |name: &str, command: &str, args: &[&str]| CodeEditor {
+        name: name.to_string(),
+        command: command.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    };
+
+    vec![
+        preset("Visual Studio Code", "code", &["--goto", "{file}:{line}:{column}"]),
+        preset(
+            "Rider",
+            if cfg!(windows) { "rider64.exe" } else { "rider" },
+            &["--line", "{line}", "--column", "{column}", "{file}"],
+        ),
+        preset("Cursor", "cursor", &["--goto", "{file}:{line}:{column}"]),
+        preset("Sublime Text", "subl", &["{file}:{line}:{column}"]),
+        preset("Notepad++", "notepad++", &["-n{line}", "-c{column}", "{file}"]),
+    ]
+}
+
+/// Launch `editor` on `file` without waiting for it to exit
+pub fn open(editor: &CodeEditor, file: &Path, line: Option<u32>, column: Option<u32>) -> AppResult<()> {
+    let line = line.unwrap_or(1).max(1).to_string();
+    let column = column.unwrap_or(1).max(1).to_string();
+
+    let mut command = Command::new(&editor.command);
+    for arg in &editor.args {
+        command.arg(
+            arg.replace("{file}", &file.to_string_lossy())
+                .replace("{line}", &line)
+                .replace("{column}", &column),
+        );
+    }
+    command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    command.spawn().map_err(|e| {
+        AppError::Custom(format!("Failed to start {} ({}): {}", editor.name, editor.command, e))
+    })?;
+    Ok(())
+}
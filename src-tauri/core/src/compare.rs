@@ -0,0 +1,366 @@
+//! `compare_projects`: what differs between two indexed projects, such as a
+//! fork and its origin or a contractor's copy.
+//!
+//! Assets are paired by relative path, then what's left by GUID (moved or
+//! renamed in Unity) and then by identical content (moved outside Unity,
+//! which gives the file a new `.meta`). Content is compared by SHA-256,
+//! cached in `assets.content_hash`, for files of equal size.
+//!
+//! `guid_collisions` looks across every indexed project for a GUID that
+//! points at different content in different projects. Unity resolves
+//! references by GUID, so a folder copied in with its `.meta` files can make
+//! references in the receiving project land on the wrong asset on import.
+
+use crate::db::{Asset, Database};
+use crate::duplicates::sha256_file;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Entries listed per section; counts include all of them
+pub const DEFAULT_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparedAsset {
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+}
+
+impl From<&Asset> for ComparedAsset {
+    fn from(asset: &Asset) -> Self {
+        Self {
+            relative_path: asset.relative_path.clone(),
+            asset_type: asset.asset_type.clone(),
+            size_bytes: asset.size_bytes,
+        }
+    }
+}
+
+/// An asset found in both projects that isn't the same in both
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetDifference {
+    pub a: ComparedAsset,
+    pub b: ComparedAsset,
+    /// How the two were paired: "path", "guid" or "content"
+    pub matched_by: String,
+    /// What differs: any of "path", "content" and "guid". A changed GUID
+    /// breaks every reference to the asset.
+    pub differences: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectComparison {
+    pub project_a: String,
+    pub project_b: String,
+    pub identical_count: usize,
+    pub only_in_a_count: usize,
+    pub only_in_b_count: usize,
+    pub changed_count: usize,
+    pub only_in_a: Vec<ComparedAsset>,
+    pub only_in_b: Vec<ComparedAsset>,
+    /// Paired assets that differ, moved ones included
+    pub changed: Vec<AssetDifference>,
+    /// False when content wasn't hashed, so equal-size files at the same
+    /// path count as identical
+    pub content_compared: bool,
+}
+
+/// Diff project `b` against project `a`. With `hash_content`, files of
+/// equal size are hashed (`threads` at a time) to tell them apart.
+pub fn compare_projects(
+    db: &Database,
+    project_a: &str,
+    project_b: &str,
+    hash_content: bool,
+    threads: usize,
+    limit: usize,
+) -> AppResult<ProjectComparison> {
+    if project_a == project_b {
+        return Err(AppError::InvalidArgument("Pick two different projects to compare".to_string()));
+    }
+    for id in [project_a, project_b] {
+        if db.get_project(id)?.is_none() {
+            return Err(AppError::NotFound(format!("Project {}", id)));
+        }
+    }
+    let mut a = db.get_project_assets(project_a)?;
+    let mut b = db.get_project_assets(project_b)?;
+
+    let b_by_path: HashMap<&str, usize> = b.iter().enumerate().map(|(i, x)| (x.relative_path.as_str(), i)).collect();
+    let path_pairs: Vec<(usize, usize)> = a
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| b_by_path.get(x.relative_path.as_str()).map(|&j| (i, j)))
+        .collect();
+
+    let mut paired_a = vec![false; a.len()];
+    let mut paired_b = vec![false; b.len()];
+    for &(i, j) in &path_pairs {
+        paired_a[i] = true;
+        paired_b[j] = true;
+    }
+
+    // Unity keeps the GUID when an asset is moved or renamed in the editor
+    let mut guid_pairs = Vec::new();
+    let unpaired_b_by_guid: HashMap<&str, usize> = b
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| !paired_b[*j])
+        .filter_map(|(j, x)| x.unity_guid.as_deref().map(|g| (g, j)))
+        .collect();
+    for (i, x) in a.iter().enumerate() {
+        if paired_a[i] {
+            continue;
+        }
+        if let Some(&j) = x.unity_guid.as_deref().and_then(|g| unpaired_b_by_guid.get(g)) {
+            if !paired_b[j] {
+                paired_a[i] = true;
+                paired_b[j] = true;
+                guid_pairs.push((i, j));
+            }
+        }
+    }
+
+    if hash_content {
+        // Hash both sides of same-size path and GUID pairs, and the
+        // unpaired files that share a size with one on the other side
+        let sizes_a: HashSet<i64> = (0..a.len()).filter(|&i| !paired_a[i]).map(|i| a[i].size_bytes).collect();
+        let sizes_b: HashSet<i64> = (0..b.len()).filter(|&j| !paired_b[j]).map(|j| b[j].size_bytes).collect();
+        let mut need_a: Vec<usize> = Vec::new();
+        let mut need_b: Vec<usize> = Vec::new();
+        for &(i, j) in path_pairs.iter().chain(&guid_pairs) {
+            if a[i].size_bytes == b[j].size_bytes {
+                need_a.push(i);
+                need_b.push(j);
+            }
+        }
+        need_a.extend((0..a.len()).filter(|&i| !paired_a[i] && sizes_b.contains(&a[i].size_bytes)));
+        need_b.extend((0..b.len()).filter(|&j| !paired_b[j] && sizes_a.contains(&b[j].size_bytes)));
+        fill_content_hashes(db, &mut a, &need_a, threads);
+        fill_content_hashes(db, &mut b, &need_b, threads);
+    }
+
+    // Moved outside Unity: a new GUID, but the same bytes
+    let mut content_pairs = Vec::new();
+    if hash_content {
+        let mut unpaired_b_by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (j, x) in b.iter().enumerate() {
+            if let (false, Some(hash)) = (paired_b[j], x.content_hash.as_deref()) {
+                unpaired_b_by_hash.entry(hash).or_default().push(j);
+            }
+        }
+        for (i, x) in a.iter().enumerate() {
+            if paired_a[i] {
+                continue;
+            }
+            let Some(candidates) = x.content_hash.as_deref().and_then(|h| unpaired_b_by_hash.get_mut(h)) else {
+                continue;
+            };
+            if let Some(j) = candidates.pop() {
+                paired_a[i] = true;
+                paired_b[j] = true;
+                content_pairs.push((i, j));
+            }
+        }
+    }
+
+    let mut identical_count = 0;
+    let mut changed = Vec::new();
+    let pairs = path_pairs
+        .iter()
+        .map(|&p| (p, "path"))
+        .chain(guid_pairs.iter().map(|&p| (p, "guid")))
+        .chain(content_pairs.iter().map(|&p| (p, "content")));
+    for ((i, j), matched_by) in pairs {
+        let differences = differences(&a[i], &b[j], hash_content);
+        if differences.is_empty() {
+            identical_count += 1;
+        } else {
+            changed.push(AssetDifference {
+                a: ComparedAsset::from(&a[i]),
+                b: ComparedAsset::from(&b[j]),
+                matched_by: matched_by.to_string(),
+                differences,
+            });
+        }
+    }
+    changed.sort_by(|x, y| x.a.relative_path.cmp(&y.a.relative_path));
+
+    let only_in_a: Vec<ComparedAsset> =
+        (0..a.len()).filter(|&i| !paired_a[i]).map(|i| ComparedAsset::from(&a[i])).collect();
+    let only_in_b: Vec<ComparedAsset> =
+        (0..b.len()).filter(|&j| !paired_b[j]).map(|j| ComparedAsset::from(&b[j])).collect();
+
+    Ok(ProjectComparison {
+        project_a: project_a.to_string(),
+        project_b: project_b.to_string(),
+        identical_count,
+        only_in_a_count: only_in_a.len(),
+        only_in_b_count: only_in_b.len(),
+        changed_count: changed.len(),
+        only_in_a: only_in_a.into_iter().take(limit).collect(),
+        only_in_b: only_in_b.into_iter().take(limit).collect(),
+        changed: changed.into_iter().take(limit).collect(),
+        content_compared: hash_content,
+    })
+}
+
+/// One project's asset with a GUID shared across projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuidCopy {
+    pub project_id: String,
+    pub project_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    pub content_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuidCollision {
+    pub guid: String,
+    pub copies: Vec<GuidCopy>,
+    /// Distinct contents among the copies
+    pub variants: usize,
+    /// The copies aren't all at one path: the GUID most likely came along
+    /// with a copied folder, rather than one asset being edited in a fork
+    pub paths_differ: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuidCollisionReport {
+    /// GUIDs used in more than one project
+    pub shared_guids: usize,
+    pub collision_count: usize,
+    /// Copies at different paths first
+    pub collisions: Vec<GuidCollision>,
+    /// False when content wasn't hashed, so only sizes told copies apart
+    pub content_compared: bool,
+}
+
+/// GUIDs used in several indexed projects for different content. With
+/// `hash_content`, copies of equal size are hashed (`threads` at a time).
+pub fn guid_collisions(db: &Database, hash_content: bool, threads: usize, limit: usize) -> AppResult<GuidCollisionReport> {
+    let mut assets = db.get_cross_project_guid_assets()?;
+    let mut by_guid: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, asset) in assets.iter().enumerate() {
+        if let Some(guid) = &asset.unity_guid {
+            by_guid.entry(guid.clone()).or_default().push(i);
+        }
+    }
+
+    if hash_content {
+        // Copies of different sizes already differ
+        let mut need = Vec::new();
+        for indices in by_guid.values() {
+            let mut sizes: HashMap<i64, usize> = HashMap::new();
+            for &i in indices {
+                *sizes.entry(assets[i].size_bytes).or_default() += 1;
+            }
+            need.extend(indices.iter().copied().filter(|&i| sizes[&assets[i].size_bytes] > 1));
+        }
+        fill_content_hashes(db, &mut assets, &need, threads);
+    }
+
+    let mut project_names: HashMap<String, String> = HashMap::new();
+    let mut collisions = Vec::new();
+    for (guid, indices) in &by_guid {
+        let content = |i: usize| match (&assets[i].content_hash, hash_content) {
+            (Some(hash), true) => hash.clone(),
+            _ => format!("{} bytes", assets[i].size_bytes),
+        };
+        // Differing content only counts between projects; duplicates within
+        // one project are Unity's to sort out on import
+        let collides = indices.iter().any(|&i| {
+            indices
+                .iter()
+                .any(|&j| assets[i].project_id != assets[j].project_id && content(i) != content(j))
+        });
+        if !collides {
+            continue;
+        }
+
+        let mut copies = Vec::with_capacity(indices.len());
+        for &i in indices {
+            let asset = &assets[i];
+            if !project_names.contains_key(&asset.project_id) {
+                let name = db.get_project(&asset.project_id)?.map(|p| p.name).unwrap_or_default();
+                project_names.insert(asset.project_id.clone(), name);
+            }
+            copies.push(GuidCopy {
+                project_id: asset.project_id.clone(),
+                project_name: project_names[&asset.project_id].clone(),
+                relative_path: asset.relative_path.clone(),
+                asset_type: asset.asset_type.clone(),
+                size_bytes: asset.size_bytes,
+                content_hash: asset.content_hash.clone(),
+            });
+        }
+        collisions.push(GuidCollision {
+            guid: guid.clone(),
+            variants: indices.iter().map(|&i| content(i)).collect::<HashSet<_>>().len(),
+            paths_differ: copies.iter().any(|c| c.relative_path != copies[0].relative_path),
+            copies,
+        });
+    }
+    collisions.sort_by(|a, b| b.paths_differ.cmp(&a.paths_differ).then_with(|| a.guid.cmp(&b.guid)));
+
+    Ok(GuidCollisionReport {
+        shared_guids: by_guid.len(),
+        collision_count: collisions.len(),
+        collisions: collisions.into_iter().take(limit).collect(),
+        content_compared: hash_content,
+    })
+}
+
+fn differences(a: &Asset, b: &Asset, hash_content: bool) -> Vec<String> {
+    let mut differences = Vec::new();
+    if a.relative_path != b.relative_path {
+        differences.push("path".to_string());
+    }
+    let content_differs = a.size_bytes != b.size_bytes
+        || (hash_content && a.content_hash.is_some() && b.content_hash.is_some() && a.content_hash != b.content_hash);
+    if content_differs {
+        differences.push("content".to_string());
+    }
+    if a.unity_guid.is_some() && b.unity_guid.is_some() && a.unity_guid != b.unity_guid {
+        differences.push("guid".to_string());
+    }
+    differences
+}
+
+/// Hash the files at `indices` that have no stored hash yet, storing them
+fn fill_content_hashes(db: &Database, assets: &mut [Asset], indices: &[usize], threads: usize) {
+    let missing: Vec<usize> = indices.iter().copied().filter(|&i| assets[i].content_hash.is_none()).collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let hashed = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, missing.len()) {
+            scope.spawn(|| {
+                while let Some(&i) = missing.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    if let Ok(hash) = sha256_file(Path::new(&assets[i].absolute_path)) {
+                        hashed.lock().unwrap().push((i, hash));
+                    }
+                }
+            });
+        }
+    });
+
+    let hashed = hashed.into_inner().unwrap();
+    tracing::info!("Hashed {} files to compare across projects", hashed.len());
+    for (i, hash) in hashed {
+        // A shared catalog is read-only; the hash is just not kept
+        if let Err(e) = db.set_content_hash(&assets[i].id, &hash) {
+            tracing::debug!("Content hash not stored for {}: {}", assets[i].relative_path, e);
+        }
+        assets[i].content_hash = Some(hash);
+    }
+}
@@ -0,0 +1,127 @@
+//! External command-line decoders for texture formats the image crate can't
+//! read (DDS, ASTC, PVR, KTX, Basis). A converter writes a PNG that then goes
+//! through the normal thumbnail pipeline, so results are cached like native ones.
+//!
+//! Arguments may use `{input}` (the source file), `{output}` (a PNG path to
+//! write) and `{output_dir}` (a scratch folder). Tools that pick their own
+//! output name are fine: the first PNG left in `{output_dir}` is used.
+
+use crate::error::{AppError, AppResult};
+use crate::process;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureConverter {
+    pub name: String,
+    /// Lowercase, without the dot
+    pub extensions: Vec<String>,
+    /// Executable name on PATH or full path
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl TextureConverter {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.command.trim().is_empty() {
+            return Err(AppError::InvalidArgument(format!("Converter {} has no command", self.name)));
+        }
+        if self.extensions.is_empty() {
+            return Err(AppError::InvalidArgument(format!("Converter {} has no extensions", self.name)));
+        }
+        if let Some(ext) = self
+            .extensions
+            .iter()
+            .find(|e| e.is_empty() || e.starts_with('.') || e.chars().any(|c| c.is_ascii_uppercase()))
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "Converter {}: extensions must be lowercase without the dot, got \"{}\"",
+                self.name, ext
+            )));
+        }
+        if !self.args.iter().any(|a| a.contains("{input}")) {
+            return Err(AppError::InvalidArgument(format!(
+                "Converter {} never passes {{input}} to the tool",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn handles(&self, extension: &str) -> bool {
+        self.extensions.iter().any(|e| e == extension)
+    }
+}
+
+/// Ready-made setups for common tools; the command usually needs a full path
+pub fn presets() -> Vec<TextureConverter> {
+    let preset = |name: &str, extensions: &[&str], command: &str, args: &[&str]| TextureConverter {
+        name: name.to_string(),
+        extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        command: command.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    };
+
+    vec![
+        preset(
+            "texconv",
+            &["dds"],
+            "texconv",
+            &["-nologo", "-y", "-ft", "png", "-f", "R8G8B8A8_UNORM", "-o", "{output_dir}", "{input}"],
+        ),
+        preset(
+            "PVRTexTool",
+            &["pvr", "ktx", "astc"],
+            "PVRTexToolCLI",
+            &["-i", "{input}", "-noout", "-d", "{output}"],
+        ),
+        preset("astcenc", &["astc"], "astcenc", &["-dl", "{input}", "{output}"]),
+        preset(
+            "basisu",
+            &["basis", "ktx2"],
+            "basisu",
+            &["-unpack", "-no_ktx", "-output_path", "{output_dir}", "{input}"],
+        ),
+    ]
+}
+
+/// Run `converter` on `input`, returning the PNG it produced inside `work_dir`
+pub fn convert(converter: &TextureConverter, input: &Path, work_dir: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(work_dir).map_err(|e| e.to_string())?;
+    let stem = input.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let output = work_dir.join(format!("{}.png", stem));
+
+    let mut command = Command::new(&converter.command);
+    for arg in &converter.args {
+        command.arg(
+            arg.replace("{input}", &input.to_string_lossy())
+                .replace("{output_dir}", &work_dir.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy()),
+        );
+    }
+
+    let result = process::run_with_timeout(command, &converter.name, CONVERT_TIMEOUT)?;
+    if !result.status.success() {
+        return Err(format!("{} failed: {}", converter.name, result.error_tail()));
+    }
+
+    if output.is_file() {
+        return Ok(output);
+    }
+    first_png(work_dir).ok_or_else(|| format!("{} wrote no PNG", converter.name))
+}
+
+fn first_png(dir: &Path) -> Option<PathBuf> {
+    let mut pngs: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")))
+        .collect();
+    pngs.sort();
+    pngs.into_iter().next()
+}
@@ -0,0 +1,5172 @@
+use crate::deliveries::{Delivery, DeliveryBadge};
+use crate::error::{AppError, AppResult};
+use crate::lods::{ModelStats, MODEL_STATS_VERSION};
+use crate::media::{AudioLevels, MediaInfo};
+use crate::provenance::Provenance;
+use crate::tags::{AssetTags, BulkEdit, TagCount};
+use crate::texture_size::TextureEstimate;
+use crate::vcs::VcsInfo;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{params, OpenFlags};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub type DbPool = Pool<SqliteConnectionManager>;
+
+/// Attempts made by `retry_busy`, with the delay doubling after each one
+const BUSY_RETRY_ATTEMPTS: u32 = 4;
+const BUSY_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+/// `warm_up` stops at the first read connection not available this quickly
+const WARM_UP_CONNECT_TIMEOUT: Duration = Duration::from_millis(100);
+/// Recent searches kept per project
+const MAX_SEARCH_HISTORY: i64 = 200;
+/// A query extended within this many seconds replaces the shorter one
+const SEARCH_TYPING_SECS: i64 = 30;
+/// Rows between progress reports of a bulk tag or field edit
+const BULK_EDIT_PROGRESS_EVERY: usize = 500;
+
+/// Connection settings, applied when the pool is created at startup
+#[derive(Debug, Clone)]
+pub struct DbOptions {
+    /// Read-only connections; writes always use a single dedicated connection
+    pub pool_size: u32,
+    /// How long a connection waits on a lock before reporting SQLITE_BUSY
+    pub busy_timeout_ms: u64,
+    /// Bytes of the file each connection memory-maps; 0 reads through the OS
+    /// page cache with read() calls only
+    pub mmap_size_mb: u64,
+    /// Page size of the database file. Changing it rebuilds the file (VACUUM)
+    /// on the next open, which takes a while for a large catalog.
+    pub page_size: u32,
+    /// Page cache per connection
+    pub cache_size_mb: u32,
+}
+
+impl Default for DbOptions {
+    fn default() -> Self {
+        Self {
+            pool_size: 4,
+            busy_timeout_ms: 5000,
+            mmap_size_mb: 0,
+            page_size: 4096,
+            cache_size_mb: 64,
+        }
+    }
+}
+
+/// Retry an operation that failed with SQLITE_BUSY after `busy_timeout`
+/// already expired, e.g. a read transaction that couldn't be upgraded to a write
+pub fn retry_busy<T>(mut op: impl FnMut() -> AppResult<T>) -> AppResult<T> {
+    let mut delay = BUSY_RETRY_BASE_DELAY;
+    for attempt in 1..BUSY_RETRY_ATTEMPTS {
+        match op() {
+            Err(e) if e.is_busy() => {
+                tracing::debug!("Database busy (attempt {}), retrying in {:?}", attempt, delay);
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    op()
+}
+
+pub struct Database {
+    /// Single connection that every write goes through, so writers queue
+    /// here instead of contending for the SQLite write lock
+    writer: DbPool,
+    /// Read-only connections for queries, which WAL lets run alongside a write
+    readers: DbPool,
+    path: PathBuf,
+    /// Opened with `open_read_only`; the writer connection is read-only too
+    read_only: bool,
+}
+
+impl Database {
+    pub fn new(path: &Path, options: &DbOptions) -> AppResult<Self> {
+        let writer = Pool::builder()
+            .max_size(1)
+            .build(connection_manager(path, options, OpenFlags::default()))?;
+
+        {
+            let conn = writer.get()?;
+            apply_page_size(&conn, options.page_size)?;
+            // Enable WAL mode for better concurrent performance (stored in the file)
+            conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        }
+
+        // Readers connect lazily (min_idle 0) since the schema doesn't exist yet
+        let readers = Pool::builder()
+            .max_size(options.pool_size)
+            .min_idle(Some(0))
+            .build(connection_manager(path, options, read_only_flags()))?;
+
+        let db = Self {
+            writer,
+            readers,
+            path: path.to_path_buf(),
+            read_only: false,
+        };
+        db.init_schema()?;
+        Ok(db)
+    }
+
+    /// Open a published catalog, typically on a network share, without ever
+    /// writing to it: no schema setup, no WAL, read-only connections only.
+    /// Writes fail with SQLITE_READONLY; callers check `is_read_only` first.
+    pub fn open_read_only(path: &Path, options: &DbOptions) -> AppResult<Self> {
+        if !path.is_file() {
+            return Err(AppError::NotFound(format!("No catalog at {}", path.display())));
+        }
+
+        let writer = Pool::builder()
+            .max_size(1)
+            .min_idle(Some(0))
+            .build(connection_manager(path, options, read_only_flags()))?;
+        let readers = Pool::builder()
+            .max_size(options.pool_size)
+            .build(connection_manager(path, options, read_only_flags()))?;
+
+        // Fail now, not on the first query, if this isn't a Scythe catalog
+        readers
+            .get()?
+            .query_row("SELECT COUNT(*) FROM projects", [], |_| Ok(()))?;
+
+        Ok(Self {
+            writer,
+            readers,
+            path: path.to_path_buf(),
+            read_only: true,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// File behind a stored thumbnail path. Published catalogs store paths
+    /// relative to the catalog folder so every machine finds the shared copies.
+    pub fn thumbnail_file(&self, stored: &str) -> PathBuf {
+        let stored = Path::new(stored);
+        match self.path.parent() {
+            Some(dir) if stored.is_relative() => dir.join(stored),
+            _ => stored.to_path_buf(),
+        }
+    }
+
+    /// Write a self-contained copy for `open_read_only` to `dest`: rollback
+    /// journal instead of WAL (readers on a share can't create the -shm file),
+    /// thumbnail paths under `thumbnail_dir` made relative as `thumbnails/<file>`,
+    /// and no per-machine scan or thumbnail bookkeeping.
+    pub fn write_published_copy(&self, dest: &Path, thumbnail_dir: &str) -> AppResult<()> {
+        {
+            let conn = self.writer.get()?;
+            conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])?;
+        }
+
+        let conn = rusqlite::Connection::open(dest)?;
+        conn.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))?;
+        conn.execute(
+            r#"
+            UPDATE assets
+            SET thumbnail_path = 'thumbnails/' || ltrim(replace(substr(thumbnail_path, length(?1) + 1), '\', '/'), '/')
+            WHERE substr(thumbnail_path, 1, length(?1)) = ?1
+            "#,
+            params![thumbnail_dir],
+        )?;
+        conn.execute_batch(
+            r#"
+            DELETE FROM preview_cache;
+            DELETE FROM thumbnail_failures;
+            DELETE FROM scan_checkpoints;
+            VACUUM;
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Fold the WAL back into the main database file and truncate it
+    pub fn checkpoint(&self) -> AppResult<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        let conn = self.writer.get()?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    fn init_schema(&self) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        conn.execute_batch(
+            r#"
+            -- Projects table
+            CREATE TABLE IF NOT EXISTS projects (
+                id TEXT PRIMARY KEY,
+                root_path TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                last_scan_time INTEGER,
+                file_count INTEGER DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- Main assets table
+            CREATE TABLE IF NOT EXISTS assets (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                absolute_path TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                extension TEXT NOT NULL,
+                asset_type TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                modified_time INTEGER NOT NULL,
+                content_hash TEXT,
+                unity_guid TEXT,
+                import_type TEXT,
+                thumbnail_path TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                UNIQUE(project_id, relative_path)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_assets_project ON assets(project_id);
+            CREATE INDEX IF NOT EXISTS idx_assets_type ON assets(asset_type);
+            CREATE INDEX IF NOT EXISTS idx_assets_guid ON assets(unity_guid);
+            CREATE INDEX IF NOT EXISTS idx_assets_relative_path ON assets(relative_path);
+            CREATE INDEX IF NOT EXISTS idx_assets_project_type ON assets(project_id, asset_type);
+            -- Keyset pages in name order (large-project mode)
+            CREATE INDEX IF NOT EXISTS idx_assets_project_name ON assets(project_id, file_name, id);
+            -- Same-size candidates for content comparison
+            CREATE INDEX IF NOT EXISTS idx_assets_project_size ON assets(project_id, size_bytes);
+
+            -- Full-text search virtual table
+            CREATE VIRTUAL TABLE IF NOT EXISTS assets_fts USING fts5(
+                file_name,
+                relative_path,
+                content=assets,
+                content_rowid=rowid
+            );
+
+            -- Indexed terms and how many assets hold each, for search suggestions
+            CREATE VIRTUAL TABLE IF NOT EXISTS assets_fts_vocab USING fts5vocab(assets_fts, 'row');
+
+            -- Dependencies table
+            CREATE TABLE IF NOT EXISTS dependencies (
+                id TEXT PRIMARY KEY,
+                from_asset_id TEXT NOT NULL,
+                to_asset_id TEXT,
+                to_guid TEXT,
+                relation_type TEXT NOT NULL,
+                confidence TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (from_asset_id) REFERENCES assets(id) ON DELETE CASCADE,
+                FOREIGN KEY (to_asset_id) REFERENCES assets(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_deps_from ON dependencies(from_asset_id);
+            CREATE INDEX IF NOT EXISTS idx_deps_to ON dependencies(to_asset_id);
+            CREATE INDEX IF NOT EXISTS idx_deps_guid ON dependencies(to_guid);
+
+            -- Preview cache tracking
+            CREATE TABLE IF NOT EXISTS preview_cache (
+                asset_id TEXT PRIMARY KEY,
+                thumb_path TEXT NOT NULL,
+                thumb_size INTEGER NOT NULL,
+                version_key TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Thumbnail failures, counted per file version so an edited file
+            -- gets a fresh set of attempts
+            CREATE TABLE IF NOT EXISTS thumbnail_failures (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                attempts INTEGER NOT NULL,
+                last_error TEXT NOT NULL,
+                last_attempt_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Progress of an unfinished scan, so a crashed or killed scan can resume
+            CREATE TABLE IF NOT EXISTS scan_checkpoints (
+                project_id TEXT PRIMARY KEY,
+                mode TEXT NOT NULL,
+                phase TEXT NOT NULL,
+                total_files INTEGER NOT NULL,
+                indexed_count INTEGER NOT NULL,
+                last_path TEXT,
+                started_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- Findings of the last validation rule run over a project
+            CREATE TABLE IF NOT EXISTS rule_violations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                asset_id TEXT NOT NULL,
+                rule TEXT NOT NULL,
+                severity TEXT NOT NULL,
+                message TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_violations_project ON rule_violations(project_id, rule);
+            CREATE INDEX IF NOT EXISTS idx_violations_asset ON rule_violations(asset_id);
+
+            -- ffprobe results for audio and video, per file version
+            CREATE TABLE IF NOT EXISTS media_info (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                duration_secs REAL,
+                format TEXT,
+                codec TEXT,
+                bit_rate INTEGER,
+                sample_rate INTEGER,
+                channels INTEGER,
+                width INTEGER,
+                height INTEGER,
+                frame_rate REAL,
+                probed_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Peak, loudness, clipping and silence of audio decoded by ffmpeg, per file version
+            CREATE TABLE IF NOT EXISTS audio_levels (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                peak_db REAL,
+                rms_db REAL,
+                loudness_lufs REAL,
+                clipped_samples INTEGER NOT NULL,
+                silence_secs REAL NOT NULL,
+                leading_silence_secs REAL NOT NULL,
+                trailing_silence_secs REAL NOT NULL,
+                analyzed_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Unity's Asset Store download cache, indexed as a library apart from projects
+            CREATE TABLE IF NOT EXISTS store_packages (
+                id TEXT PRIMARY KEY,
+                path TEXT NOT NULL UNIQUE,
+                publisher TEXT NOT NULL,
+                category TEXT NOT NULL,
+                name TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                modified_time INTEGER NOT NULL,
+                file_count INTEGER NOT NULL,
+                indexed_at INTEGER NOT NULL
+            );
+
+            -- Files inside each cached .unitypackage, keyed by their Unity GUID
+            CREATE TABLE IF NOT EXISTS store_files (
+                package_id TEXT NOT NULL,
+                guid TEXT NOT NULL,
+                path TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                extension TEXT NOT NULL,
+                asset_type TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                PRIMARY KEY (package_id, guid),
+                FOREIGN KEY (package_id) REFERENCES store_packages(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_store_files_type ON store_files(asset_type);
+
+            -- Bundles from the last Addressables build layout imported for a project
+            CREATE TABLE IF NOT EXISTS build_bundles (
+                project_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                group_name TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                compression TEXT,
+                imported_at INTEGER NOT NULL,
+                PRIMARY KEY (project_id, name),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- Source assets packed into each bundle; asset_id is NULL when the
+            -- path isn't in the index
+            CREATE TABLE IF NOT EXISTS bundle_assets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                bundle TEXT NOT NULL,
+                asset_path TEXT NOT NULL,
+                guid TEXT,
+                asset_id TEXT,
+                size_bytes INTEGER NOT NULL,
+                explicit INTEGER NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_bundle_assets_project ON bundle_assets(project_id, bundle);
+            CREATE INDEX IF NOT EXISTS idx_bundle_assets_asset ON bundle_assets(asset_id);
+
+            -- Size of each asset in the last imported player build report
+            CREATE TABLE IF NOT EXISTS asset_build_sizes (
+                asset_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                imported_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_build_sizes_project ON asset_build_sizes(project_id);
+
+            -- Estimated in-build texture sizes, per version of the file and its .meta
+            CREATE TABLE IF NOT EXISTS texture_estimates (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                meta_modified_time INTEGER NOT NULL,
+                width INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                build_width INTEGER NOT NULL,
+                build_height INTEGER NOT NULL,
+                max_size INTEGER NOT NULL,
+                format TEXT NOT NULL,
+                crunched INTEGER NOT NULL,
+                mipmaps INTEGER NOT NULL,
+                estimated_bytes INTEGER NOT NULL,
+                estimated_at INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Unity Localization tables found in a project, rebuilt on each scan
+            CREATE TABLE IF NOT EXISTS localization_tables (
+                asset_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                collection TEXT NOT NULL,
+                locale TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                entry_count INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_localization_tables_project ON localization_tables(project_id);
+
+            -- One row per key and locale of each collection. A locale missing
+            -- the key has neither value nor guid; asset_id is the localized
+            -- asset of an asset table entry, when it's in the index.
+            CREATE TABLE IF NOT EXISTS localization_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                collection TEXT NOT NULL,
+                key_id INTEGER NOT NULL,
+                key_name TEXT NOT NULL,
+                locale TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                value TEXT,
+                guid TEXT,
+                asset_id TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_localization_entries_key ON localization_entries(project_id, collection, key_id);
+            CREATE INDEX IF NOT EXISTS idx_localization_entries_asset ON localization_entries(asset_id);
+
+            -- Directory modification times (ns) at the last completed scan, for
+            -- skipping unchanged directories on quick scans
+            CREATE TABLE IF NOT EXISTS directory_mtimes (
+                project_id TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                PRIMARY KEY (project_id, relative_path),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- Perceptual hashes of images, per version of the file
+            CREATE TABLE IF NOT EXISTS image_hashes (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                hash INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Whether each texture has an alpha channel and if it is opaque everywhere, per file version
+            CREATE TABLE IF NOT EXISTS texture_alpha (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                has_alpha INTEGER NOT NULL,
+                opaque INTEGER NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Model geometry and embedded LOD levels, per file version and parser version
+            CREATE TABLE IF NOT EXISTS model_stats (
+                asset_id TEXT PRIMARY KEY,
+                modified_time INTEGER NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                parser_version INTEGER NOT NULL,
+                vertex_count INTEGER,
+                triangle_count INTEGER,
+                embedded_lods TEXT NOT NULL,
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Per-type totals after each scan that changed them, for growth charts
+            CREATE TABLE IF NOT EXISTS scan_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                scanned_at INTEGER NOT NULL,
+                mode TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_scan_history_project ON scan_history(project_id, scanned_at);
+
+            CREATE TABLE IF NOT EXISTS scan_type_totals (
+                scan_id INTEGER NOT NULL,
+                asset_type TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                total_bytes INTEGER NOT NULL,
+                PRIMARY KEY (scan_id, asset_type),
+                FOREIGN KEY (scan_id) REFERENCES scan_history(id) ON DELETE CASCADE
+            );
+
+            -- Files kept out of the index and skipped by scans until included again
+            CREATE TABLE IF NOT EXISTS excluded_assets (
+                project_id TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                excluded_at INTEGER NOT NULL,
+                PRIMARY KEY (project_id, relative_path),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- Assets deleted to the OS trash from the catalog, for undo
+            CREATE TABLE IF NOT EXISTS trashed_assets (
+                operation_id TEXT NOT NULL,
+                project_id TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                absolute_path TEXT NOT NULL,
+                deleted_at INTEGER NOT NULL,
+                restored_at INTEGER,
+                PRIMARY KEY (operation_id, relative_path),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_trashed_assets_project ON trashed_assets(project_id, deleted_at);
+
+            -- Recent asset searches per project, for search suggestions
+            CREATE TABLE IF NOT EXISTS search_history (
+                project_id TEXT NOT NULL,
+                query TEXT NOT NULL,
+                last_used INTEGER NOT NULL,
+                uses INTEGER NOT NULL,
+                PRIMARY KEY (project_id, query),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- User tags and metadata fields, edited one by one or in bulk
+            CREATE TABLE IF NOT EXISTS asset_tags (
+                asset_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (asset_id, tag),
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_asset_tags_tag ON asset_tags(tag);
+
+            CREATE TABLE IF NOT EXISTS asset_fields (
+                asset_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (asset_id, key),
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Every file an export wrote, with the hash of what was exported, for
+            -- delivered badges and checking the destination still has it
+            CREATE TABLE IF NOT EXISTS asset_deliveries (
+                asset_id TEXT NOT NULL,
+                destination TEXT NOT NULL,
+                path TEXT NOT NULL,
+                archive INTEGER NOT NULL DEFAULT 0,
+                content_hash TEXT NOT NULL,
+                source_size INTEGER NOT NULL,
+                source_modified_time INTEGER NOT NULL,
+                exported_at INTEGER NOT NULL,
+                verified_at INTEGER,
+                status TEXT,
+                PRIMARY KEY (asset_id, destination, path),
+                FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE
+            );
+
+            -- Where files copied in from a library or the Asset Store came from,
+            -- by their path in the receiving project since they aren't indexed
+            -- until the next scan
+            CREATE TABLE IF NOT EXISTS asset_provenance (
+                project_id TEXT NOT NULL,
+                relative_path TEXT NOT NULL,
+                source_kind TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                source_name TEXT NOT NULL,
+                source_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                source_size INTEGER NOT NULL,
+                source_modified_time INTEGER NOT NULL,
+                imported_at INTEGER NOT NULL,
+                PRIMARY KEY (project_id, relative_path)
+            );
+            "#,
+        )?;
+
+        // Create FTS triggers if they don't exist (checking first to avoid errors)
+        let trigger_exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='trigger' AND name='assets_ai')",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if !trigger_exists {
+            conn.execute_batch(
+                r#"
+                CREATE TRIGGER assets_ai AFTER INSERT ON assets BEGIN
+                    INSERT INTO assets_fts(rowid, file_name, relative_path)
+                    VALUES (NEW.rowid, NEW.file_name, NEW.relative_path);
+                END;
+
+                CREATE TRIGGER assets_ad AFTER DELETE ON assets BEGIN
+                    INSERT INTO assets_fts(assets_fts, rowid, file_name, relative_path)
+                    VALUES ('delete', OLD.rowid, OLD.file_name, OLD.relative_path);
+                END;
+
+                CREATE TRIGGER assets_au AFTER UPDATE ON assets BEGIN
+                    INSERT INTO assets_fts(assets_fts, rowid, file_name, relative_path)
+                    VALUES ('delete', OLD.rowid, OLD.file_name, OLD.relative_path);
+                    INSERT INTO assets_fts(rowid, file_name, relative_path)
+                    VALUES (NEW.rowid, NEW.file_name, NEW.relative_path);
+                END;
+                "#,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn store_package_from_row(row: &rusqlite::Row) -> rusqlite::Result<StorePackage> {
+    Ok(StorePackage {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        publisher: row.get(2)?,
+        category: row.get(3)?,
+        name: row.get(4)?,
+        size_bytes: row.get(5)?,
+        modified_time: row.get(6)?,
+        file_count: row.get(7)?,
+        indexed_at: row.get(8)?,
+    })
+}
+
+const UPSERT_ASSET_SQL: &str = r#"
+    INSERT INTO assets (id, project_id, absolute_path, relative_path, file_name, extension,
+                       asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                       import_type, thumbnail_path, created_at, updated_at)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+    ON CONFLICT(project_id, relative_path) DO UPDATE SET
+        absolute_path = excluded.absolute_path,
+        file_name = excluded.file_name,
+        extension = excluded.extension,
+        asset_type = excluded.asset_type,
+        size_bytes = excluded.size_bytes,
+        modified_time = excluded.modified_time,
+        content_hash = excluded.content_hash,
+        unity_guid = excluded.unity_guid,
+        import_type = excluded.import_type,
+        thumbnail_path = excluded.thumbnail_path,
+        updated_at = excluded.updated_at
+"#;
+
+/// Parameters for `UPSERT_ASSET_SQL`
+fn asset_params(asset: &Asset) -> [&dyn rusqlite::ToSql; 15] {
+    [
+        &asset.id,
+        &asset.project_id,
+        &asset.absolute_path,
+        &asset.relative_path,
+        &asset.file_name,
+        &asset.extension,
+        &asset.asset_type,
+        &asset.size_bytes,
+        &asset.modified_time,
+        &asset.content_hash,
+        &asset.unity_guid,
+        &asset.import_type,
+        &asset.thumbnail_path,
+        &asset.created_at,
+        &asset.updated_at,
+    ]
+}
+
+const LOCALIZATION_ENTRY_SELECT: &str = r#"
+    SELECT e.collection, e.key_id, e.key_name, e.locale, e.kind, e.value, e.guid, e.asset_id, a.relative_path
+    FROM localization_entries e
+    LEFT JOIN assets a ON a.id = e.asset_id
+"#;
+
+fn localization_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<LocalizationEntry> {
+    Ok(LocalizationEntry {
+        collection: row.get(0)?,
+        key_id: row.get(1)?,
+        key: row.get(2)?,
+        locale: row.get(3)?,
+        kind: row.get(4)?,
+        value: row.get(5)?,
+        guid: row.get(6)?,
+        asset_id: row.get(7)?,
+        relative_path: row.get(8)?,
+    })
+}
+
+/// Columns width, height, build_width, build_height, max_size, format,
+/// crunched, mipmaps, estimated_bytes, in that order from index 0
+fn texture_estimate_from_row(row: &rusqlite::Row) -> rusqlite::Result<TextureEstimate> {
+    Ok(TextureEstimate {
+        width: row.get(0)?,
+        height: row.get(1)?,
+        build_width: row.get(2)?,
+        build_height: row.get(3)?,
+        max_size: row.get(4)?,
+        format: row.get(5)?,
+        crunched: row.get(6)?,
+        mipmaps: row.get(7)?,
+        estimated_bytes: row.get(8)?,
+    })
+}
+
+fn read_only_flags() -> OpenFlags {
+    (OpenFlags::default() - OpenFlags::SQLITE_OPEN_READ_WRITE - OpenFlags::SQLITE_OPEN_CREATE)
+        | OpenFlags::SQLITE_OPEN_READ_ONLY
+}
+
+/// Prepared statements kept per connection by `prepare_cached`. The scan
+/// and dependency phases run a handful of statements hundreds of thousands
+/// of times; this covers them with room for the occasional query.
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Connections share busy_timeout, the statement cache size and the per-connection pragmas
+fn connection_manager(path: &Path, options: &DbOptions, flags: OpenFlags) -> SqliteConnectionManager {
+    let busy_timeout = Duration::from_millis(options.busy_timeout_ms);
+    // A negative cache_size is in KiB rather than pages
+    let pragmas = format!(
+        r#"
+        PRAGMA synchronous = NORMAL;
+        PRAGMA cache_size = -{};
+        PRAGMA mmap_size = {};
+        PRAGMA temp_store = MEMORY;
+        "#,
+        options.cache_size_mb as u64 * 1024,
+        options.mmap_size_mb * 1024 * 1024
+    );
+    SqliteConnectionManager::file(path)
+        .with_flags(flags)
+        .with_init(move |conn| {
+            conn.busy_timeout(busy_timeout)?;
+            conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+            conn.execute_batch(&pragmas)
+        })
+}
+
+/// Set the page size of a new database, or rebuild an existing one whose
+/// page size differs. WAL databases can't change page size, so the rebuild
+/// briefly switches to a rollback journal.
+fn apply_page_size(conn: &rusqlite::Connection, page_size: u32) -> AppResult<()> {
+    let current: u32 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    if current == page_size {
+        return Ok(());
+    }
+    let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    if pages == 0 {
+        conn.execute_batch(&format!("PRAGMA page_size = {};", page_size))?;
+        return Ok(());
+    }
+
+    tracing::info!("Rebuilding database with {} byte pages (was {})", page_size, current);
+    let started = std::time::Instant::now();
+    conn.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))?;
+    conn.execute_batch(&format!("PRAGMA page_size = {}; VACUUM;", page_size))?;
+    tracing::info!("Database rebuilt in {:?}", started.elapsed());
+    Ok(())
+}
+
+// Data structures for database operations
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub root_path: String,
+    pub name: String,
+    pub last_scan_time: Option<i64>,
+    pub file_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    pub id: String,
+    pub project_id: String,
+    pub absolute_path: String,
+    pub relative_path: String,
+    pub file_name: String,
+    pub extension: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    pub modified_time: i64,
+    pub content_hash: Option<String>,
+    pub unity_guid: Option<String>,
+    pub import_type: Option<String>,
+    pub thumbnail_path: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Version control state, filled in when the asset is requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs: Option<VcsInfo>,
+    /// Size in the last imported player build, as opposed to `size_bytes` on disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_size_bytes: Option<i64>,
+    /// Set when an export delivered the asset, filled in for the asset list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliveryBadge>,
+}
+
+impl Asset {
+    /// A thumbnail was generated, as opposed to none yet or a failure marker
+    pub fn has_thumbnail(&self) -> bool {
+        matches!(
+            self.thumbnail_path.as_deref(),
+            Some(path) if path != "TOO_LARGE" && path != "UNSUPPORTED"
+        )
+    }
+}
+
+/// Just what a grid tile shows, for listings where the full `Asset` would
+/// mostly be paths and timestamps nobody reads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetSummary {
+    pub id: String,
+    pub file_name: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    pub has_thumbnail: bool,
+    /// Relative path with the middle folders elided, e.g. `Assets/…/Props/Rock.png`
+    pub short_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliveryBadge>,
+}
+
+impl From<&Asset> for AssetSummary {
+    fn from(asset: &Asset) -> Self {
+        Self {
+            id: asset.id.clone(),
+            file_name: asset.file_name.clone(),
+            asset_type: asset.asset_type.clone(),
+            size_bytes: asset.size_bytes,
+            has_thumbnail: asset.has_thumbnail(),
+            short_path: short_path(&asset.relative_path),
+            delivery: asset.delivery.clone(),
+        }
+    }
+}
+
+/// Keep the first folder and the last two path components
+fn short_path(relative_path: &str) -> String {
+    let parts: Vec<&str> = relative_path.split(['/', '\\']).collect();
+    if parts.len() <= 4 {
+        return relative_path.to_string();
+    }
+    format!("{}/…/{}", parts[0], parts[parts.len() - 2..].join("/"))
+}
+
+/// Order of `get_assets` results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssetSort {
+    #[default]
+    Name,
+    /// Largest on disk first
+    Size,
+    /// Largest in the player build first; assets not in the build last
+    BuildSize,
+    /// Largest estimated texture size first; other assets last
+    EstimatedSize,
+    /// Most recently modified first
+    Modified,
+}
+
+impl AssetSort {
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "name" => Ok(AssetSort::Name),
+            "size" => Ok(AssetSort::Size),
+            "build_size" => Ok(AssetSort::BuildSize),
+            "estimated_size" => Ok(AssetSort::EstimatedSize),
+            "modified" => Ok(AssetSort::Modified),
+            other => Err(AppError::InvalidArgument(format!(
+                "Unknown sort: {} (expected name, size, build_size, estimated_size or modified)",
+                other
+            ))),
+        }
+    }
+
+    /// ORDER BY clause over `assets a` joined with `asset_build_sizes bs`
+    /// and `texture_estimates te`
+    fn order_by(self) -> &'static str {
+        match self {
+            AssetSort::Name => "a.file_name ASC",
+            AssetSort::Size => "a.size_bytes DESC, a.file_name ASC",
+            AssetSort::BuildSize => "bs.size_bytes IS NULL, bs.size_bytes DESC, a.file_name ASC",
+            AssetSort::EstimatedSize => "te.estimated_bytes IS NULL, te.estimated_bytes DESC, a.file_name ASC",
+            AssetSort::Modified => "a.modified_time DESC, a.file_name ASC",
+        }
+    }
+
+    /// Integer that `get_assets_after` pages on, descending, before file
+    /// name and id; None when the order is by name alone. Missing build and
+    /// texture sizes map to -1 so they sort last, as in `order_by`
+    fn keyset_key(self) -> Option<&'static str> {
+        match self {
+            AssetSort::Name => None,
+            AssetSort::Size => Some("a.size_bytes"),
+            AssetSort::BuildSize => Some("COALESCE(bs.size_bytes, -1)"),
+            AssetSort::EstimatedSize => Some("COALESCE(te.estimated_bytes, -1)"),
+            AssetSort::Modified => Some("a.modified_time"),
+        }
+    }
+}
+
+/// Position after the last row of a `get_assets_after` page; opaque to callers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetCursor {
+    pub key: i64,
+    pub file_name: String,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub id: String,
+    pub from_asset_id: String,
+    pub to_asset_id: Option<String>,
+    pub to_guid: String,
+    pub relation_type: String,
+    pub confidence: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeCount {
+    pub asset_type: String,
+    pub count: i64,
+    pub total_bytes: i64,
+}
+
+/// Assets under a folder two levels deep (`Assets/Art`), or one level for
+/// files directly under a top-level folder; "" for files at the root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderRollup {
+    pub folder: String,
+    pub asset_count: i64,
+    pub total_bytes: i64,
+}
+
+/// Project totals recorded at the end of one scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrowthPoint {
+    pub scanned_at: i64,
+    /// "quick" or "full"
+    pub mode: String,
+    pub total_count: i64,
+    pub total_bytes: i64,
+    /// Change since the previous point; the first point counts from zero
+    pub delta_count: i64,
+    pub delta_bytes: i64,
+    pub types: Vec<TypeCount>,
+}
+
+/// The `FolderRollup` folder that `relative_path` counts toward
+pub fn rollup_folder(relative_path: &str) -> String {
+    let mut parts: Vec<&str> = relative_path.split(['/', '\\']).collect();
+    parts.pop();
+    parts.truncate(2);
+    parts.join("/")
+}
+
+/// One row of `get_asset_listing`: the asset table plus dependency counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetListEntry {
+    pub id: String,
+    pub relative_path: String,
+    pub file_name: String,
+    pub asset_type: String,
+    pub extension: String,
+    pub size_bytes: i64,
+    pub modified_time: i64,
+    pub unity_guid: Option<String>,
+    pub content_hash: Option<String>,
+    /// Outgoing references, including unresolved ones
+    pub dependency_count: i64,
+    /// References to GUIDs that aren't in the index
+    pub missing_dependency_count: i64,
+    pub dependent_count: i64,
+}
+
+/// A dependency with the paths of both ends, for exports that link assets by path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyLink {
+    pub from_asset_id: String,
+    pub from_path: String,
+    pub to_asset_id: Option<String>,
+    /// `None` when the GUID isn't in the index
+    pub to_path: Option<String>,
+    pub to_guid: String,
+    pub relation_type: String,
+}
+
+/// Saved after every indexed batch. `last_path` is the last relative path
+/// committed; the scan walk is sorted, so everything before it is indexed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub project_id: String,
+    pub mode: String,
+    pub phase: String, // "indexing", "dependencies"
+    pub total_files: usize,
+    /// Files walked up to and including `last_path`, indexed or unchanged
+    pub indexed_count: usize,
+    pub last_path: Option<String>,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailFailure {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub attempts: i64,
+    pub last_error: String,
+    pub last_attempt_at: i64,
+}
+
+/// An asset that broke a validation rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolation {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub rule: String,
+    /// "error", "warning" or "info"
+    pub severity: String,
+    pub message: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolationCount {
+    pub rule: String,
+    pub severity: String,
+    pub count: i64,
+}
+
+/// A `.unitypackage` in the Asset Store download cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorePackage {
+    pub id: String,
+    pub path: String,
+    pub publisher: String,
+    pub category: String,
+    pub name: String,
+    pub size_bytes: i64,
+    pub modified_time: i64,
+    pub file_count: i64,
+    pub indexed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreFile {
+    pub package_id: String,
+    pub guid: String,
+    /// Path the file imports to, e.g. `Assets/Rope/Models/Rope.fbx`
+    pub path: String,
+    pub file_name: String,
+    pub extension: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+}
+
+/// A `StoreFile` search result with the package it comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreFileHit {
+    pub package_id: String,
+    pub package_name: String,
+    pub publisher: String,
+    pub guid: String,
+    pub path: String,
+    pub file_name: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+}
+
+/// An Addressables bundle as built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltBundle {
+    pub name: String,
+    pub group_name: String,
+    /// Size of the bundle file, after compression
+    pub size_bytes: i64,
+    pub compression: Option<String>,
+    pub asset_count: i64,
+}
+
+/// One source asset inside a built bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledAsset {
+    pub bundle: String,
+    /// Path as Unity reports it, e.g. `Assets/Props/Crate.prefab`
+    pub asset_path: String,
+    pub guid: Option<String>,
+    pub asset_id: Option<String>,
+    /// Serialized plus streamed data, before bundle compression
+    pub size_bytes: i64,
+    /// Addressable entry rather than pulled in as a dependency
+    pub explicit: bool,
+}
+
+/// What one source asset costs across every bundle it was built into
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetBuildCost {
+    pub asset_id: Option<String>,
+    pub asset_path: String,
+    pub asset_type: Option<String>,
+    pub bundle_count: i64,
+    /// Uncompressed size summed over bundles, so duplicates count every copy
+    pub size_bytes: i64,
+    /// Share of the bundle file sizes, in proportion to the asset's
+    /// uncompressed size within each bundle
+    pub download_bytes: i64,
+    pub explicit: bool,
+}
+
+/// An asset's size on disk and, where known, in a build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetSizes {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    /// From an imported build report, else the texture estimate
+    pub build_bytes: Option<i64>,
+}
+
+/// A texture estimate with the asset it belongs to, for size reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureEstimateEntry {
+    pub asset_id: String,
+    pub relative_path: String,
+    /// Source file size on disk
+    pub size_bytes: i64,
+    #[serde(flatten)]
+    pub estimate: TextureEstimate,
+}
+
+/// Audio levels with the asset they belong to, for the audio QA report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioLevelsEntry {
+    pub asset_id: String,
+    pub relative_path: String,
+    /// From ffprobe, when it has been probed
+    pub duration_secs: Option<f64>,
+    #[serde(flatten)]
+    pub levels: AudioLevels,
+}
+
+/// A string or asset table for one locale of a Localization collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizationTable {
+    pub asset_id: String,
+    pub collection: String,
+    /// Locale code, e.g. "en" or "fr-CA"
+    pub locale: String,
+    /// "string" or "asset"
+    pub kind: String,
+    /// Keys with a value in this locale
+    pub entry_count: i64,
+}
+
+/// One key in one locale. A missing translation has neither `value` nor `guid`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizationEntry {
+    pub collection: String,
+    pub key_id: i64,
+    pub key: String,
+    pub locale: String,
+    pub kind: String,
+    /// The translated text, for string tables
+    pub value: Option<String>,
+    /// GUID of the localized asset, for asset tables
+    pub guid: Option<String>,
+    pub asset_id: Option<String>,
+    /// Filled in on reads when the localized asset is in the index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<String>,
+}
+
+impl LocalizationEntry {
+    pub fn is_missing(&self) -> bool {
+        self.value.is_none() && self.guid.is_none()
+    }
+}
+
+/// A file kept out of the index until it is included again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExcludedAsset {
+    pub relative_path: String,
+    pub excluded_at: i64,
+}
+
+/// Assets deleted to the OS trash together, undone together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashOperation {
+    pub id: String,
+    pub project_id: String,
+    pub deleted_at: i64,
+    /// Set once the files were put back
+    pub restored_at: Option<i64>,
+    pub assets: Vec<TrashedAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedAsset {
+    pub relative_path: String,
+    pub absolute_path: String,
+}
+
+/// `OR` clause adding assets with one of `extensions` (plugin or Blender
+/// formats) to a type filter. Plugin manifests only allow `[a-z0-9_-]` in extensions.
+fn extension_clause(extensions: &[String]) -> String {
+    if extensions.is_empty() {
+        return String::new();
+    }
+    let quoted: Vec<String> = extensions.iter().map(|e| format!("'{}'", e.replace('\'', "''"))).collect();
+    format!(" OR lower(extension) IN ({})", quoted.join(", "))
+}
+
+/// SQL ordering term ranking assets by their type's place in `asset_types`,
+/// unlisted types last
+fn priority_clause(asset_types: &[String]) -> String {
+    let ranks: Vec<String> = asset_types
+        .iter()
+        .enumerate()
+        .map(|(rank, t)| format!("WHEN '{}' THEN {}", t.replace('\'', "''"), rank))
+        .collect();
+    if ranks.is_empty() {
+        return "asset_type".to_string();
+    }
+    format!("CASE asset_type {} ELSE {} END", ranks.join(" "), ranks.len())
+}
+
+/// SQL condition excluding assets that used up their thumbnail attempts for
+/// the current file version. `param` is the placeholder bound to the limit.
+fn skip_failed_thumbnails(param: usize) -> String {
+    format!(
+        r#"NOT EXISTS (
+            SELECT 1 FROM thumbnail_failures f
+            WHERE f.asset_id = assets.id
+              AND f.modified_time = assets.modified_time
+              AND f.attempts >= ?{}
+        )"#,
+        param
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// Storage settings in effect on a read connection, with a quick read
+/// benchmark to compare page, cache and mmap settings on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbTuning {
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Per connection
+    pub cache_size_kib: i64,
+    pub mmap_size_bytes: i64,
+    pub journal_mode: String,
+    /// Cold-ish full scan of the assets table
+    pub scan_rows: i64,
+    pub scan_ms: f64,
+    /// Primary key lookups of the scanned rows' ids, averaged
+    pub lookups: usize,
+    pub lookup_us: f64,
+}
+
+impl Database {
+    pub fn get_or_create_project(&self, root_path: &str, name: &str) -> AppResult<Project> {
+        let conn = self.writer.get()?;
+        let now = chrono::Utc::now().timestamp();
+
+        // Try to find existing project
+        let existing: Option<Project> = conn
+            .query_row(
+                "SELECT id, root_path, name, last_scan_time, file_count, created_at, updated_at
+                 FROM projects WHERE root_path = ?1",
+                params![root_path],
+                |row| {
+                    Ok(Project {
+                        id: row.get(0)?,
+                        root_path: row.get(1)?,
+                        name: row.get(2)?,
+                        last_scan_time: row.get(3)?,
+                        file_count: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .ok();
+
+        if let Some(project) = existing {
+            return Ok(project);
+        }
+
+        // Create new project
+        let id = uuid::Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO projects (id, root_path, name, file_count, created_at, updated_at)
+             VALUES (?1, ?2, ?3, 0, ?4, ?4)",
+            params![id, root_path, name, now],
+        )?;
+
+        Ok(Project {
+            id,
+            root_path: root_path.to_string(),
+            name: name.to_string(),
+            last_scan_time: None,
+            file_count: 0,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    pub fn get_project(&self, id: &str) -> AppResult<Option<Project>> {
+        let conn = self.readers.get()?;
+
+        let result = conn
+            .query_row(
+                "SELECT id, root_path, name, last_scan_time, file_count, created_at, updated_at
+                 FROM projects WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(Project {
+                        id: row.get(0)?,
+                        root_path: row.get(1)?,
+                        name: row.get(2)?,
+                        last_scan_time: row.get(3)?,
+                        file_count: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
+    pub fn get_project_by_path(&self, root_path: &str) -> AppResult<Option<Project>> {
+        let conn = self.readers.get()?;
+
+        let result = conn
+            .query_row(
+                "SELECT id, root_path, name, last_scan_time, file_count, created_at, updated_at
+                 FROM projects WHERE root_path = ?1",
+                params![root_path],
+                |row| {
+                    Ok(Project {
+                        id: row.get(0)?,
+                        root_path: row.get(1)?,
+                        name: row.get(2)?,
+                        last_scan_time: row.get(3)?,
+                        file_count: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
+    pub fn update_project_scan_time(&self, project_id: &str, file_count: i64) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            "UPDATE projects SET last_scan_time = ?1, file_count = ?2, updated_at = ?1 WHERE id = ?3",
+            params![now, file_count, project_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Record the project's per-type totals after a scan, unless they are
+    /// the same as the last recorded ones. Returns whether a point was added.
+    pub fn record_scan_totals(&self, project_id: &str, mode: &str) -> AppResult<bool> {
+        let mut conn = self.writer.get()?;
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            let current: Vec<(String, i64, i64)> = tx
+                .prepare(
+                    r#"
+                    SELECT asset_type, COUNT(*), COALESCE(SUM(size_bytes), 0)
+                    FROM assets WHERE project_id = ?1
+                    GROUP BY asset_type ORDER BY asset_type
+                    "#,
+                )?
+                .query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<_, _>>()?;
+            let previous: Vec<(String, i64, i64)> = tx
+                .prepare(
+                    r#"
+                    SELECT asset_type, count, total_bytes FROM scan_type_totals
+                    WHERE scan_id = (SELECT MAX(id) FROM scan_history WHERE project_id = ?1)
+                    ORDER BY asset_type
+                    "#,
+                )?
+                .query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<_, _>>()?;
+            if current == previous {
+                return Ok(false);
+            }
+
+            tx.execute(
+                "INSERT INTO scan_history (project_id, scanned_at, mode) VALUES (?1, ?2, ?3)",
+                params![project_id, chrono::Utc::now().timestamp(), mode],
+            )?;
+            let scan_id = tx.last_insert_rowid();
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO scan_type_totals (scan_id, asset_type, count, total_bytes) VALUES (?1, ?2, ?3, ?4)",
+                )?;
+                for (asset_type, count, total_bytes) in &current {
+                    stmt.execute(params![scan_id, asset_type, count, total_bytes])?;
+                }
+            }
+            tx.commit()?;
+            Ok(true)
+        })
+    }
+
+    /// Recorded scan totals oldest first, optionally only from `since` (Unix seconds)
+    pub fn get_project_growth(&self, project_id: &str, since: Option<i64>) -> AppResult<Vec<GrowthPoint>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT h.id, h.scanned_at, h.mode, t.asset_type, t.count, t.total_bytes
+            FROM scan_history h
+            LEFT JOIN scan_type_totals t ON t.scan_id = h.id
+            WHERE h.project_id = ?1
+            ORDER BY h.id, t.asset_type
+            "#,
+        )?;
+        let mut rows = stmt.query(params![project_id])?;
+
+        let mut points: Vec<(i64, GrowthPoint)> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let scan_id: i64 = row.get(0)?;
+            if points.last().is_none_or(|(id, _)| *id != scan_id) {
+                points.push((
+                    scan_id,
+                    GrowthPoint {
+                        scanned_at: row.get(1)?,
+                        mode: row.get(2)?,
+                        total_count: 0,
+                        total_bytes: 0,
+                        delta_count: 0,
+                        delta_bytes: 0,
+                        types: Vec::new(),
+                    },
+                ));
+            }
+            let point = &mut points.last_mut().expect("pushed above").1;
+            if let Some(asset_type) = row.get::<_, Option<String>>(3)? {
+                let count: i64 = row.get(4)?;
+                let total_bytes: i64 = row.get(5)?;
+                point.total_count += count;
+                point.total_bytes += total_bytes;
+                point.types.push(TypeCount { asset_type, count, total_bytes });
+            }
+        }
+
+        // Deltas are taken before `since` drops points, so the first point
+        // shown still reports its change from the one before it
+        let mut previous = (0, 0);
+        let mut growth = Vec::with_capacity(points.len());
+        for (_, mut point) in points {
+            point.delta_count = point.total_count - previous.0;
+            point.delta_bytes = point.total_bytes - previous.1;
+            previous = (point.total_count, point.total_bytes);
+            if since.is_none_or(|since| point.scanned_at >= since) {
+                growth.push(point);
+            }
+        }
+        Ok(growth)
+    }
+
+    pub fn upsert_asset(&self, asset: &Asset) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        retry_busy(|| {
+            conn.prepare_cached(UPSERT_ASSET_SQL)?.execute(asset_params(asset))?;
+            Ok(())
+        })
+    }
+
+    /// Point an asset's row at the path it was moved to, keeping its id so
+    /// dependencies, previews and cached reads stay attached
+    pub fn update_asset_location(&self, asset: &Asset) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let updated = conn.execute(
+                r#"
+                UPDATE assets
+                SET absolute_path = ?2, relative_path = ?3, file_name = ?4, extension = ?5,
+                    asset_type = ?6, updated_at = ?7
+                WHERE id = ?1
+                "#,
+                params![
+                    asset.id,
+                    asset.absolute_path,
+                    asset.relative_path,
+                    asset.file_name,
+                    asset.extension,
+                    asset.asset_type,
+                    asset.updated_at,
+                ],
+            )?;
+            if updated == 0 {
+                return Err(AppError::AssetNotFound(asset.id.clone()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Upsert a batch in one transaction on the writer connection. An asset
+    /// that fails is logged and skipped; returns how many were written.
+    pub fn upsert_assets(&self, assets: &[Asset]) -> AppResult<usize> {
+        let mut conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            let mut count = 0;
+            {
+                let mut stmt = tx.prepare_cached(UPSERT_ASSET_SQL)?;
+                for asset in assets {
+                    // A failed statement only rolls back itself, not the transaction
+                    match stmt.execute(asset_params(asset)) {
+                        Ok(_) => count += 1,
+                        Err(e) => {
+                            let e = AppError::from(e);
+                            // Busy aborts the batch so retry_busy can run it again
+                            if e.is_busy() {
+                                return Err(e);
+                            }
+                            tracing::warn!("Failed to upsert asset {}: {}", asset.relative_path, e);
+                        }
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok(count)
+        })
+    }
+
+    pub fn get_assets(
+        &self,
+        project_id: &str,
+        search_query: Option<&str>,
+        asset_types: Option<&[String]>,
+        sort: AssetSort,
+        page: i64,
+        page_size: i64,
+    ) -> AppResult<(Vec<Asset>, i64)> {
+        let conn = self.readers.get()?;
+        let offset = page * page_size;
+
+        let (where_clause, count_where) = if let Some(query) = search_query {
+            if query.trim().is_empty() {
+                ("WHERE a.project_id = ?1".to_string(), "WHERE project_id = ?1".to_string())
+            } else {
+                (
+                    "WHERE a.project_id = ?1 AND a.rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?4)".to_string(),
+                    "WHERE project_id = ?1 AND rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?2)".to_string(),
+                )
+            }
+        } else {
+            ("WHERE a.project_id = ?1".to_string(), "WHERE project_id = ?1".to_string())
+        };
+
+        let type_filter = if let Some(types) = asset_types {
+            if types.is_empty() {
+                String::new()
+            } else {
+                let placeholders: Vec<String> = types.iter().map(|t| format!("'{}'", t)).collect();
+                format!(" AND asset_type IN ({})", placeholders.join(", "))
+            }
+        } else {
+            String::new()
+        };
+
+        // Get total count
+        let count_sql = format!(
+            "SELECT COUNT(*) FROM assets {}{}",
+            count_where, type_filter
+        );
+
+        let total: i64 = if let Some(query) = search_query {
+            if query.trim().is_empty() {
+                conn.query_row(&count_sql, params![project_id], |row| row.get(0))?
+            } else {
+                let fts_query = format!("{}*", query);
+                conn.query_row(&count_sql, params![project_id, fts_query], |row| row.get(0))?
+            }
+        } else {
+            conn.query_row(&count_sql, params![project_id], |row| row.get(0))?
+        };
+
+        // Get assets
+        let sql = format!(
+            r#"
+            SELECT a.id, a.project_id, a.absolute_path, a.relative_path, a.file_name,
+                   a.extension, a.asset_type, a.size_bytes, a.modified_time, a.content_hash,
+                   a.unity_guid, a.import_type, a.thumbnail_path, a.created_at, a.updated_at,
+                   bs.size_bytes
+            FROM assets a
+            LEFT JOIN asset_build_sizes bs ON bs.asset_id = a.id
+            LEFT JOIN texture_estimates te ON te.asset_id = a.id
+            {}{}
+            ORDER BY {}
+            LIMIT ?2 OFFSET ?3
+            "#,
+            where_clause,
+            type_filter,
+            sort.order_by()
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+
+        let assets: Vec<Asset> = if let Some(query) = search_query {
+            if query.trim().is_empty() {
+                stmt.query_map(params![project_id, page_size, offset], |row| {
+                    Ok(Asset {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        absolute_path: row.get(2)?,
+                        relative_path: row.get(3)?,
+                        file_name: row.get(4)?,
+                        extension: row.get(5)?,
+                        asset_type: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        modified_time: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        unity_guid: row.get(10)?,
+                        import_type: row.get(11)?,
+                        thumbnail_path: row.get(12)?,
+                        created_at: row.get(13)?,
+                        updated_at: row.get(14)?,
+                        vcs: None,
+                        build_size_bytes: row.get(15)?,
+                        delivery: None,
+                    })
+                })?
+                .filter_map(|r| r.ok())
+                .collect()
+            } else {
+                let fts_query = format!("{}*", query);
+                stmt.query_map(params![project_id, page_size, offset, fts_query], |row| {
+                    Ok(Asset {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        absolute_path: row.get(2)?,
+                        relative_path: row.get(3)?,
+                        file_name: row.get(4)?,
+                        extension: row.get(5)?,
+                        asset_type: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        modified_time: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        unity_guid: row.get(10)?,
+                        import_type: row.get(11)?,
+                        thumbnail_path: row.get(12)?,
+                        created_at: row.get(13)?,
+                        updated_at: row.get(14)?,
+                        vcs: None,
+                        build_size_bytes: row.get(15)?,
+                        delivery: None,
+                    })
+                })?
+                .filter_map(|r| r.ok())
+                .collect()
+            }
+        } else {
+            stmt.query_map(params![project_id, page_size, offset], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: row.get(15)?,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect()
+        };
+
+        Ok((assets, total))
+    }
+
+    /// Page of `get_assets` results after `cursor` (from the start when None),
+    /// without counting matches. Cost doesn't grow with the page number, which
+    /// matters on projects where an OFFSET walks hundreds of thousands of
+    /// rows. Returns the cursor for the next page, or None after the last one.
+    pub fn get_assets_after(
+        &self,
+        project_id: &str,
+        search_query: Option<&str>,
+        asset_types: Option<&[String]>,
+        sort: AssetSort,
+        cursor: Option<&AssetCursor>,
+        limit: i64,
+    ) -> AppResult<(Vec<Asset>, Option<AssetCursor>)> {
+        let conn = self.readers.get()?;
+        let key = sort.keyset_key();
+
+        let mut sql = format!(
+            r#"
+            SELECT a.id, a.project_id, a.absolute_path, a.relative_path, a.file_name,
+                   a.extension, a.asset_type, a.size_bytes, a.modified_time, a.content_hash,
+                   a.unity_guid, a.import_type, a.thumbnail_path, a.created_at, a.updated_at,
+                   bs.size_bytes, {}
+            FROM assets a
+            LEFT JOIN asset_build_sizes bs ON bs.asset_id = a.id
+            LEFT JOIN texture_estimates te ON te.asset_id = a.id
+            WHERE a.project_id = ?
+            "#,
+            key.unwrap_or("0")
+        );
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(project_id.to_string())];
+
+        if let Some(query) = search_query.filter(|q| !q.trim().is_empty()) {
+            sql.push_str(" AND a.rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?)");
+            values.push(Box::new(format!("{}*", query)));
+        }
+        if let Some(types) = asset_types.filter(|t| !t.is_empty()) {
+            sql.push_str(&format!(" AND a.asset_type IN ({})", vec!["?"; types.len()].join(", ")));
+            values.extend(types.iter().map(|t| Box::new(t.clone()) as Box<dyn rusqlite::ToSql>));
+        }
+        if let Some(cursor) = cursor {
+            let after_name = "(a.file_name > ? OR (a.file_name = ? AND a.id > ?))";
+            match key {
+                Some(key) => {
+                    sql.push_str(&format!(" AND ({key} < ? OR ({key} = ? AND {after_name}))"));
+                    values.push(Box::new(cursor.key));
+                    values.push(Box::new(cursor.key));
+                }
+                None => sql.push_str(&format!(" AND {after_name}")),
+            }
+            values.push(Box::new(cursor.file_name.clone()));
+            values.push(Box::new(cursor.file_name.clone()));
+            values.push(Box::new(cursor.id.clone()));
+        }
+        match key {
+            Some(key) => sql.push_str(&format!(" ORDER BY {key} DESC, a.file_name ASC, a.id ASC LIMIT ?")),
+            None => sql.push_str(" ORDER BY a.file_name ASC, a.id ASC LIMIT ?"),
+        }
+        values.push(Box::new(limit));
+
+        let mut stmt = conn.prepare_cached(&sql)?;
+        let rows: Vec<(Asset, i64)> = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                Ok((
+                    Asset {
+                        id: row.get(0)?,
+                        project_id: row.get(1)?,
+                        absolute_path: row.get(2)?,
+                        relative_path: row.get(3)?,
+                        file_name: row.get(4)?,
+                        extension: row.get(5)?,
+                        asset_type: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        modified_time: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        unity_guid: row.get(10)?,
+                        import_type: row.get(11)?,
+                        thumbnail_path: row.get(12)?,
+                        created_at: row.get(13)?,
+                        updated_at: row.get(14)?,
+                        vcs: None,
+                        build_size_bytes: row.get(15)?,
+                        delivery: None,
+                    },
+                    row.get(16)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let next = match rows.last() {
+            Some((asset, key)) if rows.len() as i64 == limit => Some(AssetCursor {
+                key: *key,
+                file_name: asset.file_name.clone(),
+                id: asset.id.clone(),
+            }),
+            _ => None,
+        };
+        Ok((rows.into_iter().map(|(asset, _)| asset).collect(), next))
+    }
+
+    pub fn get_asset(&self, id: &str) -> AppResult<Option<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at,
+                   (SELECT size_bytes FROM asset_build_sizes WHERE asset_id = assets.id)
+            FROM assets WHERE id = ?1
+            "#,
+        )?;
+        let result = stmt
+            .query_row(params![id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: row.get(15)?,
+                    delivery: None,
+                })
+            })
+            .ok();
+
+        Ok(result)
+    }
+
+    pub fn get_asset_by_guid(&self, project_id: &str, guid: &str) -> AppResult<Option<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets WHERE project_id = ?1 AND unity_guid = ?2
+            "#,
+        )?;
+        let result = stmt
+            .query_row(params![project_id, guid], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })
+            .ok();
+
+        Ok(result)
+    }
+
+    /// GUID -> (asset id, asset type) for every asset in the project that has one
+    pub fn get_guid_index(&self, project_id: &str) -> AppResult<std::collections::HashMap<String, (String, String)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT unity_guid, id, asset_type FROM assets WHERE project_id = ?1 AND unity_guid IS NOT NULL",
+        )?;
+        let index = stmt
+            .query_map(params![project_id], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+        Ok(index)
+    }
+
+    pub fn get_asset_by_relative_path(&self, project_id: &str, relative_path: &str) -> AppResult<Option<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets WHERE project_id = ?1 AND relative_path = ?2
+            "#,
+        )?;
+        let result = stmt
+            .query_row(params![project_id, relative_path], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })
+            .ok();
+
+        Ok(result)
+    }
+
+    /// Per-type asset counts and byte totals, optionally limited to a relative path prefix
+    pub fn get_type_counts(
+        &self,
+        project_id: &str,
+        path_prefix: Option<&str>,
+    ) -> AppResult<Vec<TypeCount>> {
+        let conn = self.readers.get()?;
+
+        // substr comparison avoids LIKE wildcard escaping for paths containing '_' or '%'
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT asset_type, COUNT(*) as count, COALESCE(SUM(size_bytes), 0) as total_bytes
+            FROM assets
+            WHERE project_id = ?1
+              AND (?2 IS NULL OR substr(relative_path, 1, length(?2)) = ?2)
+            GROUP BY asset_type
+            "#,
+        )?;
+
+        let counts: Vec<TypeCount> = stmt
+            .query_map(params![project_id, path_prefix], |row| {
+                Ok(TypeCount {
+                    asset_type: row.get(0)?,
+                    count: row.get(1)?,
+                    total_bytes: row.get(2)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(counts)
+    }
+
+    /// Asset counts and byte totals per `FolderRollup` folder, largest first
+    pub fn get_folder_rollups(&self, project_id: &str) -> AppResult<Vec<FolderRollup>> {
+        let conn = self.readers.get()?;
+        let mut stmt = conn.prepare_cached("SELECT relative_path, size_bytes FROM assets WHERE project_id = ?1")?;
+        let mut rows = stmt.query(params![project_id])?;
+
+        let mut folders: std::collections::HashMap<String, (i64, i64)> = std::collections::HashMap::new();
+        while let Some(row) = rows.next()? {
+            let relative_path: String = row.get(0)?;
+            let size_bytes: i64 = row.get(1)?;
+            let entry = folders.entry(rollup_folder(&relative_path)).or_default();
+            entry.0 += 1;
+            entry.1 += size_bytes;
+        }
+
+        let mut rollups: Vec<FolderRollup> = folders
+            .into_iter()
+            .map(|(folder, (asset_count, total_bytes))| FolderRollup { folder, asset_count, total_bytes })
+            .collect();
+        rollups.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then_with(|| a.folder.cmp(&b.folder)));
+        Ok(rollups)
+    }
+
+    /// Read what the browser's first page, search and type counts touch on
+    /// each available read connection, so those queries find the asset table,
+    /// the name index and the search index already cached. One connection
+    /// is left for other queries while this runs. Returns the number warmed.
+    pub fn warm_up(&self, project_id: &str) -> AppResult<usize> {
+        let mut warmed = Vec::new();
+        while warmed.is_empty() || warmed.len() + 1 < self.readers.max_size() as usize {
+            // Opens a connection when fewer than max_size exist, as readers connect lazily
+            let Ok(conn) = self.readers.get_timeout(WARM_UP_CONNECT_TIMEOUT) else {
+                break;
+            };
+            conn.query_row(
+                "SELECT COUNT(*), SUM(size_bytes), MAX(length(relative_path)) FROM assets WHERE project_id = ?1",
+                params![project_id],
+                |_| Ok(()),
+            )?;
+            conn.query_row(
+                r#"
+                SELECT COUNT(*) FROM (
+                    SELECT id FROM assets WHERE project_id = ?1 ORDER BY file_name, id LIMIT 1000
+                )
+                "#,
+                params![project_id],
+                |_| Ok(()),
+            )?;
+            conn.query_row("SELECT SUM(length(block)) FROM assets_fts_data", [], |_| Ok(()))?;
+            warmed.push(conn);
+        }
+        Ok(warmed.len())
+    }
+
+    pub fn insert_dependency(&self, dep: &Dependency) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let mut stmt = conn.prepare_cached(
+                r#"
+                INSERT OR REPLACE INTO dependencies (id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+            )?;
+            stmt.execute(params![
+                dep.id,
+                dep.from_asset_id,
+                dep.to_asset_id,
+                dep.to_guid,
+                dep.relation_type,
+                dep.confidence,
+                dep.created_at,
+            ])?;
+            Ok(())
+        })
+    }
+
+    /// Replace the outgoing dependencies of `from_asset_ids` with `deps` in
+    /// one transaction: one delete for the batch, then a cached insert per edge
+    pub fn replace_dependencies(&self, from_asset_ids: &[String], deps: &[Dependency]) -> AppResult<()> {
+        if from_asset_ids.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.writer.get()?;
+        let placeholders = vec!["?"; from_asset_ids.len()].join(", ");
+
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                &format!("DELETE FROM dependencies WHERE from_asset_id IN ({})", placeholders),
+                rusqlite::params_from_iter(from_asset_ids),
+            )?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    INSERT OR REPLACE INTO dependencies (id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    "#,
+                )?;
+                for dep in deps {
+                    stmt.execute(params![
+                        dep.id,
+                        dep.from_asset_id,
+                        dep.to_asset_id,
+                        dep.to_guid,
+                        dep.relation_type,
+                        dep.confidence,
+                        dep.created_at,
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Point unresolved references in the project at `assets` by GUID, for
+    /// assets indexed outside a scan's full dependency pass
+    pub fn relink_dependencies(&self, project_id: &str, assets: &[Asset]) -> AppResult<usize> {
+        let mut conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            let mut linked = 0;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    UPDATE dependencies SET to_asset_id = ?1
+                    WHERE to_guid = ?2 AND to_asset_id IS NULL
+                      AND from_asset_id IN (SELECT id FROM assets WHERE project_id = ?3)
+                    "#,
+                )?;
+                for asset in assets {
+                    if let Some(guid) = &asset.unity_guid {
+                        linked += stmt.execute(params![asset.id, guid, project_id])?;
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok(linked)
+        })
+    }
+
+    pub fn get_dependencies(&self, asset_id: &str) -> AppResult<Vec<Dependency>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at
+             FROM dependencies WHERE from_asset_id = ?1",
+        )?;
+
+        let deps: Vec<Dependency> = stmt
+            .query_map(params![asset_id], |row| {
+                Ok(Dependency {
+                    id: row.get(0)?,
+                    from_asset_id: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_guid: row.get(3)?,
+                    relation_type: row.get(4)?,
+                    confidence: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(deps)
+    }
+
+    pub fn get_dependents(&self, asset_id: &str) -> AppResult<Vec<Dependency>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at
+             FROM dependencies WHERE to_asset_id = ?1",
+        )?;
+
+        let deps: Vec<Dependency> = stmt
+            .query_map(params![asset_id], |row| {
+                Ok(Dependency {
+                    id: row.get(0)?,
+                    from_asset_id: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_guid: row.get(3)?,
+                    relation_type: row.get(4)?,
+                    confidence: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(deps)
+    }
+
+    pub fn delete_dependencies_for_asset(&self, asset_id: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        retry_busy(|| {
+            conn.prepare_cached("DELETE FROM dependencies WHERE from_asset_id = ?1")?
+                .execute(params![asset_id])?;
+            Ok(())
+        })
+    }
+
+    pub fn update_asset_thumbnail(&self, asset_id: &str, thumbnail_path: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        retry_busy(|| {
+            conn.execute(
+                "UPDATE assets SET thumbnail_path = ?1 WHERE id = ?2",
+                params![thumbnail_path, asset_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Assets without a thumbnail, skipping ones that failed `max_attempts` times.
+    /// `extra_extensions` adds formats a plugin or Blender can preview. Types
+    /// come in `type_priority` order, then any others.
+    pub fn get_assets_needing_thumbnails(
+        &self,
+        project_id: &str,
+        limit: i64,
+        max_attempts: u32,
+        extra_extensions: &[String],
+        type_priority: &[String],
+    ) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let sql = format!(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1
+              AND (asset_type IN ('texture', 'material', 'prefab', 'scene'){})
+              AND thumbnail_path IS NULL
+              AND {}
+            ORDER BY {}
+            LIMIT ?2
+            "#,
+            extension_clause(extra_extensions),
+            skip_failed_thumbnails(3),
+            priority_clause(type_priority)
+        );
+        let mut stmt = conn.prepare(&sql)?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id, limit, max_attempts], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Textures and materials an asset references directly, the most
+    /// referenced across the project first, then the largest
+    pub fn get_composite_sources(&self, asset_id: &str, limit: usize) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT a.id, a.project_id, a.absolute_path, a.relative_path, a.file_name, a.extension,
+                   a.asset_type, a.size_bytes, a.modified_time, a.content_hash, a.unity_guid,
+                   a.import_type, a.thumbnail_path, a.created_at, a.updated_at
+            FROM assets a
+            WHERE a.asset_type IN ('texture', 'material')
+              AND a.id IN (SELECT to_asset_id FROM dependencies WHERE from_asset_id = ?1)
+            ORDER BY (SELECT COUNT(*) FROM dependencies r WHERE r.to_asset_id = a.id) DESC,
+                     a.size_bytes DESC, a.relative_path ASC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![asset_id, limit as i64], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    pub fn count_thumbnail_assets(&self, project_id: &str, extra_extensions: &[String]) -> AppResult<usize> {
+        let conn = self.readers.get()?;
+
+        let count: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM assets WHERE project_id = ?1 AND (asset_type IN ('texture', 'material', 'prefab', 'scene'){})",
+                extension_clause(extra_extensions)
+            ),
+            params![project_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(count as usize)
+    }
+
+    /// Reset thumbnails so they are regenerated. Assets that used up their
+    /// attempts keep their UNSUPPORTED marker until `reset_thumbnail_failures`.
+    pub fn clear_thumbnail_paths(&self, project_id: &str, max_attempts: u32) -> AppResult<usize> {
+        let conn = self.writer.get()?;
+
+        let sql = format!(
+            r#"
+            UPDATE assets SET thumbnail_path = NULL
+            WHERE project_id = ?1 AND asset_type IN ('texture', 'material', 'prefab', 'scene') AND {}
+            "#,
+            skip_failed_thumbnails(2)
+        );
+        let updated = conn.execute(&sql, params![project_id, max_attempts])?;
+
+        Ok(updated)
+    }
+
+    /// Count one more failed attempt for the asset's current file version
+    pub fn record_thumbnail_failure(&self, asset_id: &str, modified_time: i64, error: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        let now = chrono::Utc::now().timestamp();
+
+        conn.execute(
+            r#"
+            INSERT INTO thumbnail_failures (asset_id, modified_time, attempts, last_error, last_attempt_at)
+            VALUES (?1, ?2, 1, ?3, ?4)
+            ON CONFLICT(asset_id) DO UPDATE SET
+                attempts = CASE
+                    WHEN modified_time = excluded.modified_time THEN attempts + 1
+                    ELSE 1
+                END,
+                modified_time = excluded.modified_time,
+                last_error = excluded.last_error,
+                last_attempt_at = excluded.last_attempt_at
+            "#,
+            params![asset_id, modified_time, error, now],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn clear_thumbnail_failure(&self, asset_id: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        conn.execute("DELETE FROM thumbnail_failures WHERE asset_id = ?1", params![asset_id])?;
+        Ok(())
+    }
+
+    pub fn get_thumbnail_failures(&self, project_id: &str) -> AppResult<Vec<ThumbnailFailure>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT f.asset_id, a.relative_path, f.attempts, f.last_error, f.last_attempt_at
+            FROM thumbnail_failures f
+            JOIN assets a ON a.id = f.asset_id
+            WHERE a.project_id = ?1
+            ORDER BY f.attempts DESC, a.relative_path ASC
+            "#,
+        )?;
+
+        let failures = stmt
+            .query_map(params![project_id], |row| {
+                Ok(ThumbnailFailure {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    attempts: row.get(2)?,
+                    last_error: row.get(3)?,
+                    last_attempt_at: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(failures)
+    }
+
+    /// Replace the project's violations with the results of a new rule run
+    pub fn replace_rule_violations(&self, project_id: &str, violations: &[RuleViolation]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM rule_violations WHERE project_id = ?1", params![project_id])?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO rule_violations (project_id, asset_id, rule, severity, message, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )?;
+            for v in violations {
+                stmt.execute(params![project_id, v.asset_id, v.rule, v.severity, v.message, v.created_at])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Violations from the last rule run, errors first, optionally narrowed
+    /// to one rule or one asset
+    pub fn get_rule_violations(
+        &self,
+        project_id: &str,
+        rule: Option<&str>,
+        asset_id: Option<&str>,
+        limit: i64,
+    ) -> AppResult<Vec<RuleViolation>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT v.asset_id, a.relative_path, a.asset_type, v.rule, v.severity, v.message, v.created_at
+            FROM rule_violations v
+            JOIN assets a ON a.id = v.asset_id
+            WHERE v.project_id = ?1
+              AND (?2 IS NULL OR v.rule = ?2)
+              AND (?3 IS NULL OR v.asset_id = ?3)
+            ORDER BY
+              CASE v.severity WHEN 'error' THEN 1 WHEN 'warning' THEN 2 ELSE 3 END,
+              v.rule ASC,
+              a.relative_path ASC
+            LIMIT ?4
+            "#,
+        )?;
+
+        let violations = stmt
+            .query_map(params![project_id, rule, asset_id, limit], |row| {
+                Ok(RuleViolation {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    asset_type: row.get(2)?,
+                    rule: row.get(3)?,
+                    severity: row.get(4)?,
+                    message: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(violations)
+    }
+
+    pub fn get_rule_violation_counts(&self, project_id: &str) -> AppResult<Vec<RuleViolationCount>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT rule, severity, COUNT(*)
+            FROM rule_violations
+            WHERE project_id = ?1
+            GROUP BY rule, severity
+            ORDER BY rule ASC
+            "#,
+        )?;
+
+        let counts = stmt
+            .query_map(params![project_id], |row| {
+                Ok(RuleViolationCount {
+                    rule: row.get(0)?,
+                    severity: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(counts)
+    }
+
+    /// Cached ffprobe result, if it was taken from the file's current version
+    pub fn get_media_info(&self, asset_id: &str, modified_time: i64) -> AppResult<Option<MediaInfo>> {
+        let conn = self.readers.get()?;
+
+        let info = conn
+            .query_row(
+                r#"
+                SELECT duration_secs, format, codec, bit_rate, sample_rate, channels, width, height, frame_rate
+                FROM media_info WHERE asset_id = ?1 AND modified_time = ?2
+                "#,
+                params![asset_id, modified_time],
+                |row| {
+                    Ok(MediaInfo {
+                        duration_secs: row.get(0)?,
+                        format: row.get(1)?,
+                        codec: row.get(2)?,
+                        bit_rate: row.get(3)?,
+                        sample_rate: row.get(4)?,
+                        channels: row.get(5)?,
+                        width: row.get(6)?,
+                        height: row.get(7)?,
+                        frame_rate: row.get(8)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(info)
+    }
+
+    pub fn save_media_info(&self, asset_id: &str, modified_time: i64, info: &MediaInfo) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO media_info
+                (asset_id, modified_time, duration_secs, format, codec, bit_rate, sample_rate,
+                 channels, width, height, frame_rate, probed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#,
+            params![
+                asset_id,
+                modified_time,
+                info.duration_secs,
+                info.format,
+                info.codec,
+                info.bit_rate,
+                info.sample_rate,
+                info.channels,
+                info.width,
+                info.height,
+                info.frame_rate,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Audio and video assets with no ffprobe result for their current version
+    pub fn get_assets_needing_media_info(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.project_id, a.absolute_path, a.relative_path, a.file_name, a.extension,
+                   a.asset_type, a.size_bytes, a.modified_time, a.content_hash, a.unity_guid,
+                   a.import_type, a.thumbnail_path, a.created_at, a.updated_at
+            FROM assets a
+            LEFT JOIN media_info m ON m.asset_id = a.id AND m.modified_time = a.modified_time
+            WHERE a.project_id = ?1 AND a.asset_type IN ('audio', 'video') AND m.asset_id IS NULL
+            ORDER BY a.relative_path ASC
+            "#,
+        )?;
+        let assets = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(assets)
+    }
+
+    pub fn save_audio_levels(&self, asset_id: &str, modified_time: i64, levels: &AudioLevels) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO audio_levels
+                (asset_id, modified_time, peak_db, rms_db, loudness_lufs, clipped_samples,
+                 silence_secs, leading_silence_secs, trailing_silence_secs, analyzed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            params![
+                asset_id,
+                modified_time,
+                levels.peak_db,
+                levels.rms_db,
+                levels.loudness_lufs,
+                levels.clipped_samples,
+                levels.silence_secs,
+                levels.leading_silence_secs,
+                levels.trailing_silence_secs,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Audio levels of the project's audio assets, where taken from the
+    /// current version of the file
+    pub fn get_audio_levels(&self, project_id: &str) -> AppResult<Vec<AudioLevelsEntry>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.relative_path, m.duration_secs, l.peak_db, l.rms_db, l.loudness_lufs,
+                   l.clipped_samples, l.silence_secs, l.leading_silence_secs, l.trailing_silence_secs
+            FROM audio_levels l
+            JOIN assets a ON a.id = l.asset_id AND a.modified_time = l.modified_time
+            LEFT JOIN media_info m ON m.asset_id = a.id AND m.modified_time = a.modified_time
+            WHERE a.project_id = ?1
+            ORDER BY a.relative_path ASC
+            "#,
+        )?;
+        let entries = stmt
+            .query_map(params![project_id], |row| {
+                Ok(AudioLevelsEntry {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    duration_secs: row.get(2)?,
+                    levels: AudioLevels {
+                        peak_db: row.get(3)?,
+                        rms_db: row.get(4)?,
+                        loudness_lufs: row.get(5)?,
+                        clipped_samples: row.get(6)?,
+                        silence_secs: row.get(7)?,
+                        leading_silence_secs: row.get(8)?,
+                        trailing_silence_secs: row.get(9)?,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Audio assets with no levels for their current version
+    pub fn get_assets_needing_audio_levels(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.project_id, a.absolute_path, a.relative_path, a.file_name, a.extension,
+                   a.asset_type, a.size_bytes, a.modified_time, a.content_hash, a.unity_guid,
+                   a.import_type, a.thumbnail_path, a.created_at, a.updated_at
+            FROM assets a
+            LEFT JOIN audio_levels l ON l.asset_id = a.id AND l.modified_time = a.modified_time
+            WHERE a.project_id = ?1 AND a.asset_type = 'audio' AND l.asset_id IS NULL
+            ORDER BY a.relative_path ASC
+            "#,
+        )?;
+        let assets = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(assets)
+    }
+
+    pub fn get_store_packages(&self) -> AppResult<Vec<StorePackage>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, path, publisher, category, name, size_bytes, modified_time, file_count, indexed_at
+            FROM store_packages
+            ORDER BY publisher COLLATE NOCASE, name COLLATE NOCASE
+            "#,
+        )?;
+        let packages = stmt
+            .query_map([], store_package_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(packages)
+    }
+
+    pub fn get_store_package(&self, package_id: &str) -> AppResult<Option<StorePackage>> {
+        let conn = self.readers.get()?;
+
+        let package = conn
+            .query_row(
+                r#"
+                SELECT id, path, publisher, category, name, size_bytes, modified_time, file_count, indexed_at
+                FROM store_packages WHERE id = ?1
+                "#,
+                params![package_id],
+                store_package_from_row,
+            )
+            .ok();
+
+        Ok(package)
+    }
+
+    /// Insert or update a package and replace its file list
+    pub fn replace_store_package(&self, package: &StorePackage, files: &[StoreFile]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            r#"
+            INSERT INTO store_packages
+                (id, path, publisher, category, name, size_bytes, modified_time, file_count, indexed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(id) DO UPDATE SET
+                path = excluded.path,
+                publisher = excluded.publisher,
+                category = excluded.category,
+                name = excluded.name,
+                size_bytes = excluded.size_bytes,
+                modified_time = excluded.modified_time,
+                file_count = excluded.file_count,
+                indexed_at = excluded.indexed_at
+            "#,
+            params![
+                package.id,
+                package.path,
+                package.publisher,
+                package.category,
+                package.name,
+                package.size_bytes,
+                package.modified_time,
+                package.file_count,
+                package.indexed_at,
+            ],
+        )?;
+        tx.execute("DELETE FROM store_files WHERE package_id = ?1", params![package.id])?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO store_files
+                    (package_id, guid, path, file_name, extension, asset_type, size_bytes)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+            )?;
+            for f in files {
+                stmt.execute(params![
+                    package.id,
+                    f.guid,
+                    f.path,
+                    f.file_name,
+                    f.extension,
+                    f.asset_type,
+                    f.size_bytes
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Forget packages that are no longer in the download cache
+    pub fn delete_store_packages(&self, package_ids: &[String]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+
+        for id in package_ids {
+            tx.execute("DELETE FROM store_files WHERE package_id = ?1", params![id])?;
+            tx.execute("DELETE FROM store_packages WHERE id = ?1", params![id])?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_store_files(&self, package_id: &str) -> AppResult<Vec<StoreFile>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT package_id, guid, path, file_name, extension, asset_type, size_bytes
+            FROM store_files
+            WHERE package_id = ?1
+            ORDER BY path ASC
+            "#,
+        )?;
+        let files = stmt
+            .query_map(params![package_id], |row| {
+                Ok(StoreFile {
+                    package_id: row.get(0)?,
+                    guid: row.get(1)?,
+                    path: row.get(2)?,
+                    file_name: row.get(3)?,
+                    extension: row.get(4)?,
+                    asset_type: row.get(5)?,
+                    size_bytes: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(files)
+    }
+
+    /// Files across all cached packages where every word of `query` appears
+    /// in the file's path or the package name, case-insensitively
+    pub fn search_store_files(
+        &self,
+        query: &str,
+        asset_type: Option<&str>,
+        limit: i64,
+    ) -> AppResult<Vec<StoreFileHit>> {
+        let conn = self.readers.get()?;
+
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut sql = String::from(
+            r#"
+            SELECT f.package_id, p.name, p.publisher, f.guid, f.path, f.file_name, f.asset_type, f.size_bytes
+            FROM store_files f
+            JOIN store_packages p ON p.id = f.package_id
+            WHERE (?1 IS NULL OR f.asset_type = ?1)
+            "#,
+        );
+        for i in 0..terms.len() {
+            // instr rather than LIKE so '_' and '%' in names match literally
+            sql.push_str(&format!(
+                " AND (instr(lower(f.path), ?{n}) > 0 OR instr(lower(p.name), ?{n}) > 0)",
+                n = i + 3
+            ));
+        }
+        sql.push_str(" ORDER BY p.name COLLATE NOCASE, f.path ASC LIMIT ?2");
+
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(asset_type), Box::new(limit)];
+        values.extend(terms.into_iter().map(|t| Box::new(t) as Box<dyn rusqlite::ToSql>));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let hits = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                Ok(StoreFileHit {
+                    package_id: row.get(0)?,
+                    package_name: row.get(1)?,
+                    publisher: row.get(2)?,
+                    guid: row.get(3)?,
+                    path: row.get(4)?,
+                    file_name: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(hits)
+    }
+
+    /// Forget recorded failures and clear the assets' thumbnails so the next
+    /// pass tries them again. Returns the ids that were reset.
+    pub fn reset_thumbnail_failures(&self, project_id: &str) -> AppResult<Vec<String>> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+
+        let ids: Vec<String> = {
+            let mut stmt = tx.prepare(
+                r#"
+                SELECT f.asset_id FROM thumbnail_failures f
+                JOIN assets a ON a.id = f.asset_id
+                WHERE a.project_id = ?1
+                "#,
+            )?;
+            let ids = stmt
+                .query_map(params![project_id], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            ids
+        };
+
+        for id in &ids {
+            tx.execute("DELETE FROM thumbnail_failures WHERE asset_id = ?1", params![id])?;
+            tx.execute("UPDATE assets SET thumbnail_path = NULL WHERE id = ?1", params![id])?;
+        }
+
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    pub fn get_assets_by_types(&self, project_id: &str, types: &[String]) -> AppResult<Vec<Asset>> {
+        if types.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.readers.get()?;
+
+        let placeholders: Vec<String> = (0..types.len()).map(|i| format!("?{}", i + 2)).collect();
+        let sql = format!(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1 AND asset_type IN ({})
+            ORDER BY relative_path ASC
+            "#,
+            placeholders.join(", ")
+        );
+
+        let mut values: Vec<&dyn rusqlite::ToSql> = vec![&project_id];
+        for t in types {
+            values.push(t);
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let assets: Vec<Asset> = stmt
+            .query_map(values.as_slice(), |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Assets exactly `size_bytes` long, the only ones that can be identical to a file that size
+    pub fn get_assets_by_size(&self, project_id: &str, size_bytes: i64) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1 AND size_bytes = ?2
+            ORDER BY relative_path ASC
+            "#,
+        )?;
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id, size_bytes], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Record a content hash; reindexing the file clears it
+    pub fn set_content_hash(&self, asset_id: &str, content_hash: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        retry_busy(|| {
+            conn.execute(
+                "UPDATE assets SET content_hash = ?2 WHERE id = ?1",
+                params![asset_id, content_hash],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Every asset matching the same filters as `get_assets`, unpaginated,
+    /// ordered by relative path
+    pub fn get_asset_listing(
+        &self,
+        project_id: &str,
+        search_query: Option<&str>,
+        asset_types: Option<&[String]>,
+    ) -> AppResult<Vec<AssetListEntry>> {
+        let conn = self.readers.get()?;
+
+        let mut values: Vec<String> = vec![project_id.to_string()];
+        let mut filters = String::new();
+        if let Some(query) = search_query.filter(|q| !q.trim().is_empty()) {
+            values.push(format!("{}*", query));
+            filters.push_str(&format!(
+                " AND a.rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?{})",
+                values.len()
+            ));
+        }
+        if let Some(types) = asset_types.filter(|t| !t.is_empty()) {
+            let placeholders: Vec<String> = types
+                .iter()
+                .map(|t| {
+                    values.push(t.clone());
+                    format!("?{}", values.len())
+                })
+                .collect();
+            filters.push_str(&format!(" AND a.asset_type IN ({})", placeholders.join(", ")));
+        }
+
+        let sql = format!(
+            r#"
+            SELECT a.id, a.relative_path, a.file_name, a.asset_type, a.extension,
+                   a.size_bytes, a.modified_time, a.unity_guid, a.content_hash,
+                   (SELECT COUNT(*) FROM dependencies d WHERE d.from_asset_id = a.id),
+                   (SELECT COUNT(*) FROM dependencies d WHERE d.from_asset_id = a.id AND d.to_asset_id IS NULL),
+                   (SELECT COUNT(*) FROM dependencies d WHERE d.to_asset_id = a.id)
+            FROM assets a
+            WHERE a.project_id = ?1{}
+            ORDER BY a.relative_path ASC
+            "#,
+            filters
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                Ok(AssetListEntry {
+                    id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    file_name: row.get(2)?,
+                    asset_type: row.get(3)?,
+                    extension: row.get(4)?,
+                    size_bytes: row.get(5)?,
+                    modified_time: row.get(6)?,
+                    unity_guid: row.get(7)?,
+                    content_hash: row.get(8)?,
+                    dependency_count: row.get(9)?,
+                    missing_dependency_count: row.get(10)?,
+                    dependent_count: row.get(11)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Disk and in-build sizes of every asset in a project
+    pub fn get_asset_sizes(&self, project_id: &str) -> AppResult<Vec<AssetSizes>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.relative_path, a.asset_type, a.size_bytes,
+                   COALESCE(bs.size_bytes, te.estimated_bytes)
+            FROM assets a
+            LEFT JOIN asset_build_sizes bs ON bs.asset_id = a.id
+            LEFT JOIN texture_estimates te ON te.asset_id = a.id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+
+        let sizes = stmt
+            .query_map(params![project_id], |row| {
+                Ok(AssetSizes {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    asset_type: row.get(2)?,
+                    size_bytes: row.get(3)?,
+                    build_bytes: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(sizes)
+    }
+
+    /// Every dependency in a project, with the relative paths of both ends
+    pub fn get_dependency_links(&self, project_id: &str) -> AppResult<Vec<DependencyLink>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.from_asset_id, f.relative_path, d.to_asset_id, t.relative_path,
+                   COALESCE(d.to_guid, ''), d.relation_type
+            FROM dependencies d
+            JOIN assets f ON f.id = d.from_asset_id
+            LEFT JOIN assets t ON t.id = d.to_asset_id
+            WHERE f.project_id = ?1
+            ORDER BY f.relative_path, t.relative_path
+            "#,
+        )?;
+
+        let links = stmt
+            .query_map(params![project_id], |row| {
+                Ok(DependencyLink {
+                    from_asset_id: row.get(0)?,
+                    from_path: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_path: row.get(3)?,
+                    to_guid: row.get(4)?,
+                    relation_type: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(links)
+    }
+
+    /// Every asset in a project, ordered by relative path
+    pub fn get_project_assets(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1
+            ORDER BY relative_path ASC
+            "#,
+        )?;
+
+        let assets = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(assets)
+    }
+
+    /// Assets whose GUID is also used in another project, grouped by GUID
+    pub fn get_cross_project_guid_assets(&self) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE unity_guid IN (
+                SELECT unity_guid FROM assets
+                WHERE unity_guid IS NOT NULL
+                GROUP BY unity_guid
+                HAVING COUNT(DISTINCT project_id) > 1
+            )
+            ORDER BY unity_guid ASC, project_id ASC, relative_path ASC
+            "#,
+        )?;
+
+        let assets = stmt
+            .query_map([], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(assets)
+    }
+
+    /// Every dependency whose source asset is in the project
+    pub fn get_project_dependencies(&self, project_id: &str) -> AppResult<Vec<Dependency>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.id, d.from_asset_id, d.to_asset_id, COALESCE(d.to_guid, ''),
+                   d.relation_type, d.confidence, d.created_at
+            FROM dependencies d
+            JOIN assets a ON a.id = d.from_asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+
+        let deps = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Dependency {
+                    id: row.get(0)?,
+                    from_asset_id: row.get(1)?,
+                    to_asset_id: row.get(2)?,
+                    to_guid: row.get(3)?,
+                    relation_type: row.get(4)?,
+                    confidence: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(deps)
+    }
+
+    /// Insert a complete project (from a snapshot) in one transaction,
+    /// deleting the `replaced` project in the same one. Returns the replaced
+    /// project's thumbnail files, to remove once this succeeded.
+    pub fn import_project(
+        &self,
+        project: &Project,
+        assets: &[Asset],
+        deps: &[Dependency],
+        replaced: Option<&str>,
+    ) -> AppResult<Vec<String>> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let thumbnails = match replaced {
+            Some(project_id) => Self::delete_project_rows(&tx, project_id)?,
+            None => Vec::new(),
+        };
+
+        tx.execute(
+            "INSERT INTO projects (id, root_path, name, last_scan_time, file_count, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                project.id,
+                project.root_path,
+                project.name,
+                project.last_scan_time,
+                project.file_count,
+                project.created_at,
+                project.updated_at,
+            ],
+        )?;
+
+        {
+            let mut insert_asset = tx.prepare(
+                r#"
+                INSERT INTO assets (id, project_id, absolute_path, relative_path, file_name, extension,
+                                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                                   import_type, thumbnail_path, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                "#,
+            )?;
+            for asset in assets {
+                insert_asset.execute(params![
+                    asset.id,
+                    asset.project_id,
+                    asset.absolute_path,
+                    asset.relative_path,
+                    asset.file_name,
+                    asset.extension,
+                    asset.asset_type,
+                    asset.size_bytes,
+                    asset.modified_time,
+                    asset.content_hash,
+                    asset.unity_guid,
+                    asset.import_type,
+                    asset.thumbnail_path,
+                    asset.created_at,
+                    asset.updated_at,
+                ])?;
+            }
+
+            let mut insert_dep = tx.prepare(
+                r#"
+                INSERT INTO dependencies (id, from_asset_id, to_asset_id, to_guid, relation_type, confidence, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+            )?;
+            for dep in deps {
+                insert_dep.execute(params![
+                    dep.id,
+                    dep.from_asset_id,
+                    dep.to_asset_id,
+                    dep.to_guid,
+                    dep.relation_type,
+                    dep.confidence,
+                    dep.created_at,
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(thumbnails)
+    }
+
+    /// Replace the project's build layout with a newly imported one
+    pub fn replace_build_layout(
+        &self,
+        project_id: &str,
+        bundles: &[BuiltBundle],
+        assets: &[BundledAsset],
+    ) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        tx.execute("DELETE FROM bundle_assets WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM build_bundles WHERE project_id = ?1", params![project_id])?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO build_bundles (project_id, name, group_name, size_bytes, compression, imported_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )?;
+            for b in bundles {
+                stmt.execute(params![project_id, b.name, b.group_name, b.size_bytes, b.compression, now])?;
+            }
+
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO bundle_assets (project_id, bundle, asset_path, guid, asset_id, size_bytes, explicit)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+            )?;
+            for a in assets {
+                stmt.execute(params![
+                    project_id,
+                    a.bundle,
+                    a.asset_path,
+                    a.guid,
+                    a.asset_id,
+                    a.size_bytes,
+                    a.explicit
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Bundles of the last imported build layout, largest first
+    pub fn get_built_bundles(&self, project_id: &str) -> AppResult<Vec<BuiltBundle>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT b.name, b.group_name, b.size_bytes, b.compression,
+                   (SELECT COUNT(*) FROM bundle_assets ba WHERE ba.project_id = b.project_id AND ba.bundle = b.name)
+            FROM build_bundles b
+            WHERE b.project_id = ?1
+            ORDER BY b.size_bytes DESC, b.name ASC
+            "#,
+        )?;
+
+        let bundles = stmt
+            .query_map(params![project_id], |row| {
+                Ok(BuiltBundle {
+                    name: row.get(0)?,
+                    group_name: row.get(1)?,
+                    size_bytes: row.get(2)?,
+                    compression: row.get(3)?,
+                    asset_count: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(bundles)
+    }
+
+    /// Source assets ranked by the download size they account for
+    pub fn get_asset_build_costs(&self, project_id: &str, limit: i64) -> AppResult<Vec<AssetBuildCost>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT MAX(ba.asset_id), ba.asset_path, MAX(a.asset_type), COUNT(DISTINCT ba.bundle),
+                   SUM(ba.size_bytes),
+                   CAST(SUM(CASE WHEN t.total > 0 THEN 1.0 * ba.size_bytes * b.size_bytes / t.total ELSE 0 END) AS INTEGER),
+                   MAX(ba.explicit)
+            FROM bundle_assets ba
+            JOIN build_bundles b ON b.project_id = ba.project_id AND b.name = ba.bundle
+            JOIN (
+                SELECT bundle, SUM(size_bytes) AS total
+                FROM bundle_assets WHERE project_id = ?1
+                GROUP BY bundle
+            ) t ON t.bundle = ba.bundle
+            LEFT JOIN assets a ON a.id = ba.asset_id
+            WHERE ba.project_id = ?1
+            GROUP BY ba.asset_path
+            ORDER BY 6 DESC, ba.asset_path ASC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let costs = stmt
+            .query_map(params![project_id, limit], |row| {
+                Ok(AssetBuildCost {
+                    asset_id: row.get(0)?,
+                    asset_path: row.get(1)?,
+                    asset_type: row.get(2)?,
+                    bundle_count: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    download_bytes: row.get(5)?,
+                    explicit: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(costs)
+    }
+
+    /// The bundles an indexed asset was built into
+    pub fn get_asset_bundle_entries(&self, asset_id: &str) -> AppResult<Vec<BundledAsset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT bundle, asset_path, guid, asset_id, size_bytes, explicit
+            FROM bundle_assets
+            WHERE asset_id = ?1
+            ORDER BY explicit DESC, bundle ASC
+            "#,
+        )?;
+
+        let entries = stmt
+            .query_map(params![asset_id], |row| {
+                Ok(BundledAsset {
+                    bundle: row.get(0)?,
+                    asset_path: row.get(1)?,
+                    guid: row.get(2)?,
+                    asset_id: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                    explicit: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Replace the project's build sizes with those of a newly imported
+    /// build report, given as (asset id, bytes)
+    pub fn replace_build_sizes(&self, project_id: &str, sizes: &[(String, i64)]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+
+        tx.execute("DELETE FROM asset_build_sizes WHERE project_id = ?1", params![project_id])?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO asset_build_sizes (asset_id, project_id, size_bytes, imported_at)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )?;
+            for (asset_id, size) in sizes {
+                stmt.execute(params![asset_id, project_id, size, now])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Directory relative path -> mtime recorded by the project's last completed scan
+    pub fn get_directory_mtimes(&self, project_id: &str) -> AppResult<std::collections::HashMap<String, i64>> {
+        let conn = self.readers.get()?;
+        let mut stmt = conn.prepare("SELECT relative_path, mtime FROM directory_mtimes WHERE project_id = ?1")?;
+        let rows = stmt.query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn replace_directory_mtimes(
+        &self,
+        project_id: &str,
+        mtimes: &std::collections::HashMap<String, i64>,
+    ) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM directory_mtimes WHERE project_id = ?1", params![project_id])?;
+            {
+                let mut stmt =
+                    tx.prepare("INSERT INTO directory_mtimes (project_id, relative_path, mtime) VALUES (?1, ?2, ?3)")?;
+                for (relative_path, mtime) in mtimes {
+                    stmt.execute(params![project_id, relative_path, mtime])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Perceptual image hashes with the (mtime, size) of the file each was made from
+    pub fn get_image_hashes(
+        &self,
+        project_id: &str,
+    ) -> AppResult<std::collections::HashMap<String, (i64, i64, u64)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT h.asset_id, h.modified_time, h.size_bytes, h.hash
+            FROM image_hashes h
+            JOIN assets a ON a.id = h.asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+        let hashes = stmt
+            .query_map(params![project_id], |row| {
+                Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get::<_, i64>(3)? as u64)))
+            })?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+        Ok(hashes)
+    }
+
+    /// Store `(asset_id, mtime, size, hash)` rows, replacing older versions
+    pub fn save_image_hashes(&self, hashes: &[(String, i64, i64, u64)]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT OR REPLACE INTO image_hashes (asset_id, modified_time, size_bytes, hash) VALUES (?1, ?2, ?3, ?4)",
+                )?;
+                for (asset_id, modified_time, size_bytes, hash) in hashes {
+                    stmt.execute(params![asset_id, modified_time, size_bytes, *hash as i64])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// (mtime, size, has alpha, alpha opaque) of each texture's last alpha check
+    pub fn get_texture_alpha(
+        &self,
+        project_id: &str,
+    ) -> AppResult<std::collections::HashMap<String, (i64, i64, bool, bool)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.asset_id, t.modified_time, t.size_bytes, t.has_alpha, t.opaque
+            FROM texture_alpha t
+            JOIN assets a ON a.id = t.asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+        let checks = stmt
+            .query_map(params![project_id], |row| {
+                Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+            })?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+        Ok(checks)
+    }
+
+    /// Store `(asset_id, mtime, size, has alpha, opaque)` rows, replacing older versions
+    pub fn save_texture_alpha(&self, checks: &[(String, i64, i64, bool, bool)]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT OR REPLACE INTO texture_alpha (asset_id, modified_time, size_bytes, has_alpha, opaque) VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?;
+                for (asset_id, modified_time, size_bytes, has_alpha, opaque) in checks {
+                    stmt.execute(params![asset_id, modified_time, size_bytes, has_alpha, opaque])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Model stats read by the current parsers, with the (mtime, size) of
+    /// the file each was read from
+    pub fn get_model_stats(
+        &self,
+        project_id: &str,
+    ) -> AppResult<std::collections::HashMap<String, (i64, i64, ModelStats)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT s.asset_id, s.modified_time, s.size_bytes, s.vertex_count, s.triangle_count, s.embedded_lods
+            FROM model_stats s
+            JOIN assets a ON a.id = s.asset_id
+            WHERE a.project_id = ?1 AND s.parser_version = ?2
+            "#,
+        )?;
+        let stats = stmt
+            .query_map(params![project_id, MODEL_STATS_VERSION], |row| {
+                let embedded_lods: String = row.get(5)?;
+                Ok((
+                    row.get(0)?,
+                    (
+                        row.get(1)?,
+                        row.get(2)?,
+                        ModelStats {
+                            vertex_count: row.get::<_, Option<i64>>(3)?.map(|count| count as u64),
+                            triangle_count: row.get::<_, Option<i64>>(4)?.map(|count| count as u64),
+                            embedded_lods: embedded_lods.split(',').filter_map(|level| level.parse().ok()).collect(),
+                        },
+                    ),
+                ))
+            })?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+        Ok(stats)
+    }
+
+    /// Store `(asset_id, mtime, size, stats)` rows, replacing older versions
+    pub fn save_model_stats(&self, stats: &[(String, i64, i64, ModelStats)]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    INSERT OR REPLACE INTO model_stats
+                        (asset_id, modified_time, size_bytes, parser_version, vertex_count, triangle_count, embedded_lods)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    "#,
+                )?;
+                for (asset_id, modified_time, size_bytes, model) in stats {
+                    let embedded_lods: Vec<String> = model.embedded_lods.iter().map(|level| level.to_string()).collect();
+                    stmt.execute(params![
+                        asset_id,
+                        modified_time,
+                        size_bytes,
+                        MODEL_STATS_VERSION,
+                        model.vertex_count.map(|count| count as i64),
+                        model.triangle_count.map(|count| count as i64),
+                        embedded_lods.join(","),
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// (asset mtime, .meta mtime) each texture estimate was made from
+    pub fn get_texture_estimate_versions(&self, project_id: &str) -> AppResult<std::collections::HashMap<String, (i64, i64)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT te.asset_id, te.modified_time, te.meta_modified_time
+            FROM texture_estimates te
+            JOIN assets a ON a.id = te.asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+        let versions = stmt
+            .query_map(params![project_id], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+        Ok(versions)
+    }
+
+    /// Stored estimate, if it was made from these versions of the file and its .meta
+    pub fn get_texture_estimate(
+        &self,
+        asset_id: &str,
+        modified_time: i64,
+        meta_modified_time: i64,
+    ) -> AppResult<Option<TextureEstimate>> {
+        let conn = self.readers.get()?;
+
+        let estimate = conn
+            .query_row(
+                r#"
+                SELECT width, height, build_width, build_height, max_size, format, crunched, mipmaps, estimated_bytes
+                FROM texture_estimates
+                WHERE asset_id = ?1 AND modified_time = ?2 AND meta_modified_time = ?3
+                "#,
+                params![asset_id, modified_time, meta_modified_time],
+                texture_estimate_from_row,
+            )
+            .ok();
+
+        Ok(estimate)
+    }
+
+    pub fn save_texture_estimate(
+        &self,
+        asset_id: &str,
+        modified_time: i64,
+        meta_modified_time: i64,
+        estimate: &TextureEstimate,
+    ) -> AppResult<()> {
+        let conn = self.writer.get()?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO texture_estimates
+                (asset_id, modified_time, meta_modified_time, width, height, build_width, build_height,
+                 max_size, format, crunched, mipmaps, estimated_bytes, estimated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            "#,
+            params![
+                asset_id,
+                modified_time,
+                meta_modified_time,
+                estimate.width,
+                estimate.height,
+                estimate.build_width,
+                estimate.build_height,
+                estimate.max_size,
+                estimate.format,
+                estimate.crunched,
+                estimate.mipmaps,
+                estimate.estimated_bytes,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn delete_texture_estimate(&self, asset_id: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        conn.execute("DELETE FROM texture_estimates WHERE asset_id = ?1", params![asset_id])?;
+        Ok(())
+    }
+
+    /// Textures ranked by estimated build size
+    pub fn get_texture_estimates(&self, project_id: &str, limit: i64) -> AppResult<Vec<TextureEstimateEntry>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT te.width, te.height, te.build_width, te.build_height, te.max_size, te.format,
+                   te.crunched, te.mipmaps, te.estimated_bytes, a.id, a.relative_path, a.size_bytes
+            FROM texture_estimates te
+            JOIN assets a ON a.id = te.asset_id
+            WHERE a.project_id = ?1
+            ORDER BY te.estimated_bytes DESC, a.relative_path ASC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let entries = stmt
+            .query_map(params![project_id, limit], |row| {
+                Ok(TextureEstimateEntry {
+                    asset_id: row.get(9)?,
+                    relative_path: row.get(10)?,
+                    size_bytes: row.get(11)?,
+                    estimate: texture_estimate_from_row(row)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Replace the project's Localization tables and entries with a fresh parse
+    pub fn replace_localization(
+        &self,
+        project_id: &str,
+        tables: &[LocalizationTable],
+        entries: &[LocalizationEntry],
+    ) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM localization_entries WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM localization_tables WHERE project_id = ?1", params![project_id])?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO localization_tables (asset_id, project_id, collection, locale, kind, entry_count)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )?;
+            for table in tables {
+                stmt.execute(params![
+                    table.asset_id,
+                    project_id,
+                    table.collection,
+                    table.locale,
+                    table.kind,
+                    table.entry_count
+                ])?;
+            }
+
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO localization_entries
+                    (project_id, collection, key_id, key_name, locale, kind, value, guid, asset_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                "#,
+            )?;
+            for entry in entries {
+                stmt.execute(params![
+                    project_id,
+                    entry.collection,
+                    entry.key_id,
+                    entry.key,
+                    entry.locale,
+                    entry.kind,
+                    entry.value,
+                    entry.guid,
+                    entry.asset_id
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_localization_tables(&self, project_id: &str) -> AppResult<Vec<LocalizationTable>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT asset_id, collection, locale, kind, entry_count
+            FROM localization_tables
+            WHERE project_id = ?1
+            ORDER BY collection, locale
+            "#,
+        )?;
+
+        let tables = stmt
+            .query_map(params![project_id], |row| {
+                Ok(LocalizationTable {
+                    asset_id: row.get(0)?,
+                    collection: row.get(1)?,
+                    locale: row.get(2)?,
+                    kind: row.get(3)?,
+                    entry_count: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tables)
+    }
+
+    /// Every locale's entry for up to `limit` keys whose name contains
+    /// `query` (case-insensitive), ordered by collection and key. With
+    /// `missing_only`, only keys missing from at least one locale.
+    pub fn search_localization_entries(
+        &self,
+        project_id: &str,
+        query: &str,
+        missing_only: bool,
+        limit: i64,
+    ) -> AppResult<Vec<LocalizationEntry>> {
+        let conn = self.readers.get()?;
+
+        // instr rather than LIKE so '_' and '%' in keys match literally
+        let sql = format!(
+            r#"
+            {}
+            WHERE e.project_id = ?1 AND (e.collection, e.key_id) IN (
+                SELECT collection, key_id FROM localization_entries
+                WHERE project_id = ?1 AND instr(lower(key_name), lower(?2)) > 0 {}
+                GROUP BY collection, key_id
+                ORDER BY collection, key_name
+                LIMIT ?3
+            )
+            ORDER BY e.collection, e.key_name, e.key_id, e.locale
+            "#,
+            LOCALIZATION_ENTRY_SELECT,
+            if missing_only { "AND value IS NULL AND guid IS NULL" } else { "" }
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(params![project_id, query, limit], localization_entry_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Every locale's entry for the keys that localize to `asset_id`
+    pub fn get_asset_localization_entries(&self, asset_id: &str) -> AppResult<Vec<LocalizationEntry>> {
+        let conn = self.readers.get()?;
+
+        let sql = format!(
+            r#"
+            {}
+            WHERE (e.project_id, e.collection, e.key_id) IN (
+                SELECT project_id, collection, key_id FROM localization_entries WHERE asset_id = ?1
+            )
+            ORDER BY e.collection, e.key_name, e.key_id, e.locale
+            "#,
+            LOCALIZATION_ENTRY_SELECT
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt
+            .query_map(params![asset_id], localization_entry_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Remove a project and everything indexed for it. Returns the thumbnail
+    /// paths of its assets so callers can remove the files.
+    pub fn delete_project(&self, project_id: &str) -> AppResult<Vec<String>> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let thumbnails = Self::delete_project_rows(&tx, project_id)?;
+        tx.commit()?;
+        Ok(thumbnails)
+    }
+
+    /// Delete a project and everything stored for it, returning the
+    /// thumbnail files of its assets for the caller to remove once the
+    /// transaction commits
+    fn delete_project_rows(tx: &rusqlite::Connection, project_id: &str) -> AppResult<Vec<String>> {
+        let thumbnails: Vec<String> = {
+            let mut stmt = tx.prepare(
+                "SELECT thumbnail_path FROM assets WHERE project_id = ?1 AND thumbnail_path IS NOT NULL",
+            )?;
+            let thumbnails = stmt
+                .query_map(params![project_id], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            thumbnails
+        };
+
+        let in_project = "SELECT id FROM assets WHERE project_id = ?1";
+        tx.execute(
+            &format!("DELETE FROM dependencies WHERE from_asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("UPDATE dependencies SET to_asset_id = NULL WHERE to_asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM preview_cache WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM thumbnail_failures WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM media_info WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM audio_levels WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM texture_estimates WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM image_hashes WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM texture_alpha WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM model_stats WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM asset_tags WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM asset_fields WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM asset_deliveries WHERE asset_id IN ({})", in_project),
+            params![project_id],
+        )?;
+        tx.execute("DELETE FROM localization_entries WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM localization_tables WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM rule_violations WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM bundle_assets WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM build_bundles WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM asset_build_sizes WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM assets WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM scan_checkpoints WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM directory_mtimes WHERE project_id = ?1", params![project_id])?;
+        tx.execute(
+            "DELETE FROM scan_type_totals WHERE scan_id IN (SELECT id FROM scan_history WHERE project_id = ?1)",
+            params![project_id],
+        )?;
+        tx.execute("DELETE FROM scan_history WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM excluded_assets WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM trashed_assets WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM search_history WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM asset_provenance WHERE project_id = ?1", params![project_id])?;
+        tx.execute("DELETE FROM projects WHERE id = ?1", params![project_id])?;
+        Ok(thumbnails)
+    }
+
+    pub fn get_model_assets(&self, project_id: &str) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1 AND asset_type = 'model'
+            "#,
+        )?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+
+    /// Get lightweight info about existing assets for change detection during re-scan.
+    /// Returns a HashMap keyed by relative_path with (id, modified_time, size_bytes).
+    pub fn get_existing_asset_info(
+        &self,
+        project_id: &str,
+    ) -> AppResult<std::collections::HashMap<String, (String, i64, i64)>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, relative_path, modified_time, size_bytes FROM assets WHERE project_id = ?1",
+        )?;
+
+        let mut map = std::collections::HashMap::new();
+        let rows = stmt.query_map(params![project_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?, // id
+                row.get::<_, String>(1)?, // relative_path
+                row.get::<_, i64>(2)?,    // modified_time
+                row.get::<_, i64>(3)?,    // size_bytes
+            ))
+        })?;
+
+        for row in rows {
+            if let Ok((id, relative_path, modified_time, size_bytes)) = row {
+                map.insert(relative_path, (id, modified_time, size_bytes));
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Delete assets along with their dependencies and preview cache rows.
+    /// Returns the thumbnail paths of the deleted assets so callers can remove the files.
+    pub fn delete_assets(&self, asset_ids: &[String]) -> AppResult<Vec<String>> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let mut thumbnails = Vec::new();
+
+        for id in asset_ids {
+            let thumbnail: Option<String> = tx
+                .query_row(
+                    "SELECT thumbnail_path FROM assets WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(None);
+            thumbnails.extend(thumbnail);
+
+            tx.execute("DELETE FROM dependencies WHERE from_asset_id = ?1", params![id])?;
+            tx.execute(
+                "UPDATE dependencies SET to_asset_id = NULL WHERE to_asset_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM preview_cache WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM thumbnail_failures WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM rule_violations WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM media_info WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM audio_levels WHERE asset_id = ?1", params![id])?;
+            tx.execute("UPDATE bundle_assets SET asset_id = NULL WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM asset_build_sizes WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM texture_estimates WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM image_hashes WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM texture_alpha WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM model_stats WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM asset_tags WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM asset_fields WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM asset_deliveries WHERE asset_id = ?1", params![id])?;
+            tx.execute("DELETE FROM localization_tables WHERE asset_id = ?1", params![id])?;
+            tx.execute(
+                "UPDATE localization_entries SET asset_id = NULL WHERE asset_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM assets WHERE id = ?1", params![id])?;
+        }
+
+        tx.commit()?;
+        Ok(thumbnails)
+    }
+
+    /// Record the assets' paths as excluded so scans skip them. The asset
+    /// rows themselves are removed with `delete_assets`. Returns how many
+    /// paths were newly excluded.
+    pub fn exclude_assets(&self, asset_ids: &[String]) -> AppResult<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let mut excluded = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                r#"
+                INSERT OR IGNORE INTO excluded_assets (project_id, relative_path, excluded_at)
+                SELECT project_id, relative_path, ?2 FROM assets WHERE id = ?1
+                "#,
+            )?;
+            for id in asset_ids {
+                excluded += stmt.execute(params![id, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(excluded)
+    }
+
+    /// Stop excluding these paths; the files are indexed again by the next
+    /// scan. Returns how many were excluded.
+    pub fn include_excluded_assets(&self, project_id: &str, relative_paths: &[String]) -> AppResult<usize> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        let mut included = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "DELETE FROM excluded_assets WHERE project_id = ?1 AND relative_path = ?2",
+            )?;
+            for relative_path in relative_paths {
+                included += stmt.execute(params![project_id, relative_path])?;
+            }
+        }
+        tx.commit()?;
+        Ok(included)
+    }
+
+    /// Excluded paths of a project, most recently excluded first
+    pub fn get_excluded_assets(&self, project_id: &str) -> AppResult<Vec<ExcludedAsset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT relative_path, excluded_at FROM excluded_assets
+            WHERE project_id = ?1
+            ORDER BY excluded_at DESC, relative_path
+            "#,
+        )?;
+
+        let excluded = stmt
+            .query_map(params![project_id], |row| {
+                Ok(ExcludedAsset {
+                    relative_path: row.get(0)?,
+                    excluded_at: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(excluded)
+    }
+
+    /// Excluded relative paths, for scans to skip
+    pub fn get_excluded_paths(&self, project_id: &str) -> AppResult<std::collections::HashSet<String>> {
+        Ok(self
+            .get_excluded_assets(project_id)?
+            .into_iter()
+            .map(|e| e.relative_path)
+            .collect())
+    }
+
+    /// Log assets moved to the OS trash as one operation
+    pub fn record_trash_operation(&self, operation: &TrashOperation) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                r#"
+                INSERT OR REPLACE INTO trashed_assets
+                    (operation_id, project_id, relative_path, absolute_path, deleted_at, restored_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )?;
+            for asset in &operation.assets {
+                stmt.execute(params![
+                    operation.id,
+                    operation.project_id,
+                    asset.relative_path,
+                    asset.absolute_path,
+                    operation.deleted_at,
+                    operation.restored_at,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drop assets from a logged trash operation, for files that could not
+    /// be moved to the trash after all
+    pub fn remove_trashed_assets(&self, operation_id: &str, relative_paths: &[String]) -> AppResult<()> {
+        let mut conn = self.writer.get()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt =
+                tx.prepare_cached("DELETE FROM trashed_assets WHERE operation_id = ?1 AND relative_path = ?2")?;
+            for relative_path in relative_paths {
+                stmt.execute(params![operation_id, relative_path])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set_trash_operation_restored(&self, operation_id: &str, restored_at: i64) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        conn.execute(
+            "UPDATE trashed_assets SET restored_at = ?2 WHERE operation_id = ?1",
+            params![operation_id, restored_at],
+        )?;
+        Ok(())
+    }
+
+    /// Logged trash operations of a project, most recent first. With
+    /// `operation_id` only that one.
+    pub fn get_trash_operations(
+        &self,
+        project_id: Option<&str>,
+        operation_id: Option<&str>,
+        limit: i64,
+    ) -> AppResult<Vec<TrashOperation>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT operation_id, project_id, relative_path, absolute_path, deleted_at, restored_at
+            FROM trashed_assets
+            WHERE operation_id IN (
+                SELECT operation_id FROM trashed_assets
+                WHERE (?1 IS NULL OR project_id = ?1) AND (?2 IS NULL OR operation_id = ?2)
+                GROUP BY operation_id
+                ORDER BY MAX(deleted_at) DESC
+                LIMIT ?3
+            )
+            ORDER BY deleted_at DESC, operation_id, relative_path
+            "#,
+        )?;
+
+        let mut operations: Vec<TrashOperation> = Vec::new();
+        let rows = stmt.query_map(params![project_id, operation_id, limit], |row| {
+            Ok((
+                TrashOperation {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    deleted_at: row.get(4)?,
+                    restored_at: row.get(5)?,
+                    assets: Vec::new(),
+                },
+                TrashedAsset {
+                    relative_path: row.get(2)?,
+                    absolute_path: row.get(3)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (operation, asset) = row?;
+            match operations.last_mut() {
+                Some(last) if last.id == operation.id => last.assets.push(asset),
+                _ => operations.push(TrashOperation {
+                    assets: vec![asset],
+                    ..operation
+                }),
+            }
+        }
+
+        Ok(operations)
+    }
+
+    /// Remember an asset search. Shorter queries typed on into this one in
+    /// the last half minute are dropped, so typing "rock" doesn't also
+    /// leave "r", "ro" and "roc" behind.
+    pub fn record_search_query(&self, project_id: &str, query: &str) -> AppResult<()> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                r#"
+                DELETE FROM search_history
+                WHERE project_id = ?1 AND last_used >= ?3 - ?4
+                  AND length(query) < length(?2) AND substr(?2, 1, length(query)) = query
+                "#,
+                params![project_id, query, now, SEARCH_TYPING_SECS],
+            )?;
+            tx.execute(
+                r#"
+                INSERT INTO search_history (project_id, query, last_used, uses) VALUES (?1, ?2, ?3, 1)
+                ON CONFLICT(project_id, query) DO UPDATE SET last_used = excluded.last_used, uses = uses + 1
+                "#,
+                params![project_id, query, now],
+            )?;
+            tx.execute(
+                r#"
+                DELETE FROM search_history
+                WHERE project_id = ?1 AND query NOT IN (
+                    SELECT query FROM search_history WHERE project_id = ?1 ORDER BY last_used DESC LIMIT ?2
+                )
+                "#,
+                params![project_id, MAX_SEARCH_HISTORY],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Recent searches starting with `prefix` (any case), most recent first
+    pub fn get_recent_searches(&self, project_id: &str, prefix: &str, limit: i64) -> AppResult<Vec<String>> {
+        let conn = self.readers.get()?;
+        let pattern = format!("{}%", prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT query FROM search_history
+            WHERE project_id = ?1 AND query LIKE ?2 ESCAPE '\'
+            ORDER BY last_used DESC
+            LIMIT ?3
+            "#,
+        )?;
+        let queries = stmt
+            .query_map(params![project_id, pattern, limit], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(queries)
+    }
+
+    /// Indexed terms starting with `prefix` and how many assets hold each,
+    /// most common first. The vocabulary spans every project, so terms are
+    /// checked against this one's assets before they count.
+    pub fn get_search_terms(&self, project_id: &str, prefix: &str, limit: usize) -> AppResult<Vec<(String, i64)>> {
+        let conn = self.readers.get()?;
+        let prefix = prefix.to_lowercase();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT term, doc FROM assets_fts_vocab
+            WHERE term >= ?1 AND term < ?2
+            ORDER BY doc DESC, term
+            LIMIT ?3
+            "#,
+        )?;
+        let candidates: Vec<(String, i64)> = stmt
+            .query_map(params![prefix, format!("{}\u{10FFFF}", prefix), (limit * 5) as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut in_project = conn.prepare_cached(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM assets
+                WHERE project_id = ?1 AND rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?2)
+            )
+            "#,
+        )?;
+        let mut terms = Vec::new();
+        for (term, count) in candidates {
+            if terms.len() >= limit {
+                break;
+            }
+            let query = format!("\"{}\"", term.replace('"', "\"\""));
+            if in_project.query_row(params![project_id, query], |row| row.get::<_, bool>(0))? {
+                terms.push((term, count));
+            }
+        }
+
+        Ok(terms)
+    }
+
+    /// Relative paths of up to `limit` assets matching an FTS query
+    pub fn get_search_matches(&self, project_id: &str, fts_query: &str, limit: i64) -> AppResult<Vec<String>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT relative_path FROM assets
+            WHERE project_id = ?1 AND rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?2)
+            LIMIT ?3
+            "#,
+        )?;
+        let paths = stmt
+            .query_map(params![project_id, fts_query, limit], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(paths)
+    }
+
+    /// Ids of the project's assets matching the grid's search and type filters
+    pub fn get_filtered_asset_ids(
+        &self,
+        project_id: &str,
+        search_query: Option<&str>,
+        asset_types: Option<&[String]>,
+    ) -> AppResult<Vec<String>> {
+        let conn = self.readers.get()?;
+
+        let mut sql = "SELECT id FROM assets WHERE project_id = ?1".to_string();
+        let mut values: Vec<String> = vec![project_id.to_string()];
+        if let Some(query) = search_query.filter(|q| !q.trim().is_empty()) {
+            values.push(format!("{}*", query));
+            sql.push_str(&format!(
+                " AND rowid IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?{})",
+                values.len()
+            ));
+        }
+        if let Some(types) = asset_types.filter(|t| !t.is_empty()) {
+            let placeholders: Vec<String> = types
+                .iter()
+                .map(|t| {
+                    values.push(t.clone());
+                    format!("?{}", values.len())
+                })
+                .collect();
+            sql.push_str(&format!(" AND asset_type IN ({})", placeholders.join(", ")));
+        }
+        sql.push_str(" ORDER BY relative_path");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let ids = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ids)
+    }
+
+    /// Apply one tag or field edit to the project's assets among `asset_ids`
+    /// in a single transaction. Returns how many rows it changed.
+    pub fn apply_bulk_edit(
+        &self,
+        project_id: &str,
+        asset_ids: &[String],
+        edit: &BulkEdit,
+        mut progress: impl FnMut(usize, usize),
+    ) -> AppResult<usize> {
+        let mut conn = self.writer.get()?;
+
+        retry_busy(|| {
+            let tx = conn.transaction()?;
+            let mut changed = 0;
+            {
+                let (sql, text, value) = match edit {
+                    BulkEdit::AddTag { tag } => (
+                        "INSERT OR IGNORE INTO asset_tags (asset_id, tag) SELECT id, ?3 FROM assets WHERE id = ?1 AND project_id = ?2",
+                        tag,
+                        None,
+                    ),
+                    BulkEdit::RemoveTag { tag } => (
+                        "DELETE FROM asset_tags WHERE asset_id IN (SELECT id FROM assets WHERE id = ?1 AND project_id = ?2) AND tag = ?3",
+                        tag,
+                        None,
+                    ),
+                    BulkEdit::SetField { key, value: Some(value) } => (
+                        r#"
+                        INSERT INTO asset_fields (asset_id, key, value)
+                        SELECT id, ?3, ?4 FROM assets WHERE id = ?1 AND project_id = ?2
+                        ON CONFLICT(asset_id, key) DO UPDATE SET value = excluded.value
+                        WHERE value IS NOT excluded.value
+                        "#,
+                        key,
+                        Some(value),
+                    ),
+                    BulkEdit::SetField { key, value: None } => (
+                        "DELETE FROM asset_fields WHERE asset_id IN (SELECT id FROM assets WHERE id = ?1 AND project_id = ?2) AND key = ?3",
+                        key,
+                        None,
+                    ),
+                };
+                let mut stmt = tx.prepare_cached(sql)?;
+                for (i, id) in asset_ids.iter().enumerate() {
+                    changed += match value {
+                        Some(value) => stmt.execute(params![id, project_id, text, value])?,
+                        None => stmt.execute(params![id, project_id, text])?,
+                    };
+                    if (i + 1) % BULK_EDIT_PROGRESS_EVERY == 0 {
+                        progress(i + 1, asset_ids.len());
+                    }
+                }
+            }
+            tx.commit()?;
+            progress(asset_ids.len(), asset_ids.len());
+            Ok(changed)
+        })
+    }
+
+    /// Tags and metadata fields of one asset
+    pub fn get_asset_tags(&self, asset_id: &str) -> AppResult<AssetTags> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare_cached("SELECT tag FROM asset_tags WHERE asset_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map(params![asset_id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let mut stmt = conn.prepare_cached("SELECT key, value FROM asset_fields WHERE asset_id = ?1")?;
+        let fields = stmt
+            .query_map(params![asset_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(AssetTags { tags, fields })
+    }
+
+    /// Every tag used in a project with how many assets carry it
+    pub fn get_project_tags(&self, project_id: &str) -> AppResult<Vec<TagCount>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.tag, COUNT(*) FROM asset_tags t
+            JOIN assets a ON a.id = t.asset_id
+            WHERE a.project_id = ?1
+            GROUP BY t.tag
+            ORDER BY COUNT(*) DESC, t.tag
+            "#,
+        )?;
+        let tags = stmt
+            .query_map(params![project_id], |row| {
+                Ok(TagCount {
+                    tag: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// Log the files an export wrote, replacing earlier deliveries to the same
+    /// place along with their verification
+    pub fn record_deliveries(&self, deliveries: &[Delivery]) -> AppResult<()> {
+        retry_busy(|| {
+            let mut conn = self.writer.get()?;
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    INSERT OR REPLACE INTO asset_deliveries
+                        (asset_id, destination, path, archive, content_hash, source_size,
+                         source_modified_time, exported_at, verified_at, status)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, NULL, NULL)
+                    "#,
+                )?;
+                for d in deliveries {
+                    stmt.execute(params![
+                        d.asset_id,
+                        d.destination,
+                        d.path,
+                        d.archive,
+                        d.content_hash,
+                        d.source_size,
+                        d.source_modified_time,
+                        d.exported_at,
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Deliveries of a project's assets, or of one asset, newest first
+    pub fn get_deliveries(&self, project_id: &str, asset_id: Option<&str>) -> AppResult<Vec<Delivery>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.asset_id, a.relative_path, d.destination, d.path, d.archive, d.content_hash,
+                   d.source_size, d.source_modified_time, d.exported_at, d.verified_at, d.status,
+                   d.source_size = a.size_bytes AND d.source_modified_time = a.modified_time
+            FROM asset_deliveries d
+            JOIN assets a ON a.id = d.asset_id
+            WHERE a.project_id = ?1 AND (?2 IS NULL OR d.asset_id = ?2)
+            ORDER BY d.exported_at DESC, a.relative_path ASC
+            "#,
+        )?;
+        let deliveries = stmt
+            .query_map(params![project_id, asset_id], |row| {
+                Ok(Delivery {
+                    asset_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    destination: row.get(2)?,
+                    path: row.get(3)?,
+                    archive: row.get(4)?,
+                    content_hash: row.get(5)?,
+                    source_size: row.get(6)?,
+                    source_modified_time: row.get(7)?,
+                    exported_at: row.get(8)?,
+                    verified_at: row.get(9)?,
+                    status: row.get(10)?,
+                    current_version: row.get(11)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(deliveries)
+    }
+
+    /// Delivered badge of every delivered asset in a project, by asset id
+    pub fn get_delivery_badges(&self, project_id: &str) -> AppResult<std::collections::HashMap<String, DeliveryBadge>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT d.asset_id, COUNT(DISTINCT d.destination), MAX(d.exported_at),
+                   MAX(d.source_size = a.size_bytes AND d.source_modified_time = a.modified_time)
+            FROM asset_deliveries d
+            JOIN assets a ON a.id = d.asset_id
+            WHERE a.project_id = ?1
+            GROUP BY d.asset_id
+            "#,
+        )?;
+        let badges = stmt
+            .query_map(params![project_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    DeliveryBadge {
+                        destinations: row.get(1)?,
+                        last_delivered_at: row.get(2)?,
+                        changed_since: !row.get::<_, bool>(3)?,
+                    },
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(badges)
+    }
+
+    /// Store the outcome of checking deliveries at their destinations
+    pub fn set_delivery_statuses(&self, deliveries: &[Delivery]) -> AppResult<()> {
+        retry_busy(|| {
+            let mut conn = self.writer.get()?;
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    UPDATE asset_deliveries SET verified_at = ?4, status = ?5
+                    WHERE asset_id = ?1 AND destination = ?2 AND path = ?3
+                    "#,
+                )?;
+                for d in deliveries {
+                    stmt.execute(params![d.asset_id, d.destination, d.path, d.verified_at, d.status])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Record where copied files came from, replacing what was recorded for
+    /// the same paths by an earlier copy
+    pub fn record_provenance(&self, records: &[Provenance]) -> AppResult<()> {
+        retry_busy(|| {
+            let mut conn = self.writer.get()?;
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    r#"
+                    INSERT OR REPLACE INTO asset_provenance
+                        (project_id, relative_path, source_kind, source_id, source_name, source_path,
+                         content_hash, source_size, source_modified_time, imported_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                    "#,
+                )?;
+                for p in records {
+                    stmt.execute(params![
+                        p.project_id,
+                        p.relative_path,
+                        p.source_kind,
+                        p.source_id,
+                        p.source_name,
+                        p.source_path,
+                        p.content_hash,
+                        p.source_size,
+                        p.source_modified_time,
+                        p.imported_at,
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Provenance of a project's copied-in files, or of one asset, newest first
+    pub fn get_provenance(&self, project_id: &str, asset_id: Option<&str>) -> AppResult<Vec<Provenance>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT p.project_id, p.relative_path, a.id, p.source_kind, p.source_id, p.source_name,
+                   p.source_path, p.content_hash, p.source_size, p.source_modified_time, p.imported_at
+            FROM asset_provenance p
+            LEFT JOIN assets a ON a.project_id = p.project_id AND a.relative_path = p.relative_path
+            WHERE p.project_id = ?1 AND (?2 IS NULL OR a.id = ?2)
+            ORDER BY p.imported_at DESC, p.relative_path ASC
+            "#,
+        )?;
+        let records = stmt
+            .query_map(params![project_id, asset_id], |row| {
+                Ok(Provenance {
+                    project_id: row.get(0)?,
+                    relative_path: row.get(1)?,
+                    asset_id: row.get(2)?,
+                    source_kind: row.get(3)?,
+                    source_id: row.get(4)?,
+                    source_name: row.get(5)?,
+                    source_path: row.get(6)?,
+                    content_hash: row.get(7)?,
+                    source_size: row.get(8)?,
+                    source_modified_time: row.get(9)?,
+                    imported_at: row.get(10)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// Keep a moved asset's provenance with it
+    pub fn move_provenance(&self, project_id: &str, from_path: &str, to_path: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        retry_busy(|| {
+            conn.execute(
+                "UPDATE OR REPLACE asset_provenance SET relative_path = ?3 WHERE project_id = ?1 AND relative_path = ?2",
+                params![project_id, from_path, to_path],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Metadata fields of every asset in a project that has any, by asset id
+    pub fn get_project_asset_fields(
+        &self,
+        project_id: &str,
+    ) -> AppResult<std::collections::HashMap<String, std::collections::BTreeMap<String, String>>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT f.asset_id, f.key, f.value FROM asset_fields f
+            JOIN assets a ON a.id = f.asset_id
+            WHERE a.project_id = ?1
+            "#,
+        )?;
+        let mut fields: std::collections::HashMap<String, std::collections::BTreeMap<String, String>> =
+            std::collections::HashMap::new();
+        let rows = stmt.query_map(params![project_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        for row in rows {
+            let (asset_id, key, value) = row?;
+            fields.entry(asset_id).or_default().insert(key, value);
+        }
+
+        Ok(fields)
+    }
+
+    /// Indexed assets built into a bundle of the last imported build layout;
+    /// None if the layout has no such bundle
+    pub fn get_bundle_asset_ids(&self, project_id: &str, bundle: &str) -> AppResult<Option<Vec<String>>> {
+        let conn = self.readers.get()?;
+
+        let known: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM build_bundles WHERE project_id = ?1 AND name = ?2)",
+            params![project_id, bundle],
+            |row| row.get(0),
+        )?;
+        if !known {
+            return Ok(None);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT asset_id FROM bundle_assets WHERE project_id = ?1 AND bundle = ?2 AND asset_id IS NOT NULL",
+        )?;
+        let ids = stmt
+            .query_map(params![project_id, bundle], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        Ok(Some(ids))
+    }
+
+    /// Point stored thumbnail paths at a new cache folder after it was moved
+    pub fn rebase_thumbnail_paths(&self, from_dir: &str, to_dir: &str) -> AppResult<usize> {
+        let conn = self.writer.get()?;
+
+        let updated = conn.execute(
+            r#"
+            UPDATE assets SET thumbnail_path = ?2 || substr(thumbnail_path, length(?1) + 1)
+            WHERE substr(thumbnail_path, 1, length(?1)) = ?1
+            "#,
+            params![from_dir, to_dir],
+        )?;
+        conn.execute(
+            r#"
+            UPDATE preview_cache SET thumb_path = ?2 || substr(thumb_path, length(?1) + 1)
+            WHERE substr(thumb_path, 1, length(?1)) = ?1
+            "#,
+            params![from_dir, to_dir],
+        )?;
+
+        Ok(updated)
+    }
+
+    pub fn save_scan_checkpoint(&self, checkpoint: &ScanCheckpoint) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        retry_busy(|| {
+            conn.execute(
+                r#"
+                INSERT OR REPLACE INTO scan_checkpoints
+                    (project_id, mode, phase, total_files, indexed_count, last_path, started_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+                params![
+                    checkpoint.project_id,
+                    checkpoint.mode,
+                    checkpoint.phase,
+                    checkpoint.total_files as i64,
+                    checkpoint.indexed_count as i64,
+                    checkpoint.last_path,
+                    checkpoint.started_at,
+                    checkpoint.updated_at,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_scan_checkpoint(&self, project_id: &str) -> AppResult<Option<ScanCheckpoint>> {
+        let conn = self.readers.get()?;
+
+        let result = conn
+            .query_row(
+                r#"
+                SELECT project_id, mode, phase, total_files, indexed_count, last_path, started_at, updated_at
+                FROM scan_checkpoints WHERE project_id = ?1
+                "#,
+                params![project_id],
+                |row| {
+                    Ok(ScanCheckpoint {
+                        project_id: row.get(0)?,
+                        mode: row.get(1)?,
+                        phase: row.get(2)?,
+                        total_files: row.get::<_, i64>(3)? as usize,
+                        indexed_count: row.get::<_, i64>(4)? as usize,
+                        last_path: row.get(5)?,
+                        started_at: row.get(6)?,
+                        updated_at: row.get(7)?,
+                    })
+                },
+            )
+            .ok();
+
+        Ok(result)
+    }
+
+    pub fn delete_scan_checkpoint(&self, project_id: &str) -> AppResult<()> {
+        let conn = self.writer.get()?;
+        conn.execute("DELETE FROM scan_checkpoints WHERE project_id = ?1", params![project_id])?;
+        Ok(())
+    }
+
+    /// Run `PRAGMA integrity_check`, returning at most `limit` problems
+    pub fn integrity_check(&self, limit: usize) -> AppResult<Vec<String>> {
+        let conn = self.readers.get()?;
+        let mut stmt = conn.prepare("PRAGMA integrity_check(?1)")?;
+        let messages: Vec<String> = stmt
+            .query_map(params![limit as i64], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .filter(|message: &String| message != "ok")
+            .collect();
+
+        Ok(messages)
+    }
+
+    /// Row counts for every table in the schema, for diagnostics
+    /// Effective storage pragmas and read timings for diagnostics
+    pub fn get_tuning_report(&self) -> AppResult<DbTuning> {
+        const BENCH_LOOKUPS: usize = 1000;
+        let conn = self.readers.get()?;
+        let pragma = |name: &str| conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+        let cache_size = pragma("cache_size")?;
+        let page_size = pragma("page_size")?;
+
+        let started = std::time::Instant::now();
+        let (scan_rows, ids) = {
+            let mut stmt = conn.prepare("SELECT id, size_bytes, relative_path FROM assets")?;
+            let mut rows = stmt.query([])?;
+            let mut count = 0;
+            let mut ids = Vec::new();
+            while let Some(row) = rows.next()? {
+                // Sample ids across the table for the lookup benchmark
+                if ids.len() < BENCH_LOOKUPS && count % 16 == 0 {
+                    ids.push(row.get::<_, String>(0)?);
+                }
+                count += 1;
+            }
+            (count, ids)
+        };
+        let scan_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        let started = std::time::Instant::now();
+        {
+            let mut stmt = conn.prepare("SELECT relative_path FROM assets WHERE id = ?1")?;
+            for id in &ids {
+                stmt.query_row(params![id], |row| row.get::<_, String>(0))?;
+            }
+        }
+        let lookup_us = if ids.is_empty() {
+            0.0
+        } else {
+            started.elapsed().as_secs_f64() * 1_000_000.0 / ids.len() as f64
+        };
+
+        Ok(DbTuning {
+            page_size,
+            page_count: pragma("page_count")?,
+            // Negative values are KiB, positive ones pages
+            cache_size_kib: if cache_size < 0 { -cache_size } else { cache_size * page_size / 1024 },
+            mmap_size_bytes: pragma("mmap_size")?,
+            journal_mode: conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?,
+            scan_rows,
+            scan_ms,
+            lookups: ids.len(),
+            lookup_us,
+        })
+    }
+
+    pub fn get_table_row_counts(&self) -> AppResult<Vec<TableRowCount>> {
+        let conn = self.readers.get()?;
+
+        let mut counts = Vec::new();
+        for table in [
+            "projects",
+            "assets",
+            "dependencies",
+            "preview_cache",
+            "thumbnail_failures",
+            "rule_violations",
+            "media_info",
+            "audio_levels",
+            "store_packages",
+            "store_files",
+            "build_bundles",
+            "bundle_assets",
+            "asset_build_sizes",
+            "texture_estimates",
+            "localization_tables",
+            "localization_entries",
+            "directory_mtimes",
+            "image_hashes",
+            "texture_alpha",
+            "model_stats",
+            "scan_history",
+            "scan_type_totals",
+            "excluded_assets",
+            "trashed_assets",
+            "search_history",
+            "asset_tags",
+            "asset_fields",
+            "asset_deliveries",
+            "asset_provenance",
+        ] {
+            let rows: i64 =
+                conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?;
+            counts.push(TableRowCount {
+                table: table.to_string(),
+                rows,
+            });
+        }
+
+        Ok(counts)
+    }
+
+    /// Assets the dependency resolver reads, plus `plugin_extensions` that a plugin parses
+    pub fn get_parseable_assets(&self, project_id: &str, plugin_extensions: &[String]) -> AppResult<Vec<Asset>> {
+        let conn = self.readers.get()?;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT id, project_id, absolute_path, relative_path, file_name, extension,
+                   asset_type, size_bytes, modified_time, content_hash, unity_guid,
+                   import_type, thumbnail_path, created_at, updated_at
+            FROM assets
+            WHERE project_id = ?1
+              AND (asset_type IN ('material', 'prefab', 'scene', 'scriptable_object'){})
+            "#,
+            extension_clause(plugin_extensions)
+        ))?;
+
+        let assets: Vec<Asset> = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Asset {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    absolute_path: row.get(2)?,
+                    relative_path: row.get(3)?,
+                    file_name: row.get(4)?,
+                    extension: row.get(5)?,
+                    asset_type: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    modified_time: row.get(8)?,
+                    content_hash: row.get(9)?,
+                    unity_guid: row.get(10)?,
+                    import_type: row.get(11)?,
+                    thumbnail_path: row.get(12)?,
+                    created_at: row.get(13)?,
+                    updated_at: row.get(14)?,
+                    vcs: None,
+                    build_size_bytes: None,
+                    delivery: None,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assets)
+    }
+}
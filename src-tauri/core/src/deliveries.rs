@@ -0,0 +1,154 @@
+//! Delivered assets: every file an export writes is logged in
+//! `asset_deliveries` with the SHA-256 of the exported version, so the asset
+//! list can badge and filter what went out (and what changed since), and
+//! `verify_deliveries` can check on demand that the destinations still hold
+//! those versions.
+
+use crate::db::{Asset, Database};
+use crate::duplicates::{sha256_file, sha256_reader};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// One file an export wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delivery {
+    pub asset_id: String,
+    /// Filled in from the asset when deliveries are read
+    pub relative_path: String,
+    /// Export folder, or the archive written into it
+    pub destination: String,
+    /// File written, or the entry inside the archive
+    pub path: String,
+    pub archive: bool,
+    /// SHA-256 of the source when it was exported
+    pub content_hash: String,
+    pub source_size: i64,
+    pub source_modified_time: i64,
+    pub exported_at: i64,
+    pub verified_at: Option<i64>,
+    /// Last check: "current", "modified" or "missing"
+    pub status: Option<String>,
+    /// The asset on disk is still the version that was exported
+    pub current_version: bool,
+}
+
+/// What the asset list shows for a delivered asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryBadge {
+    pub destinations: usize,
+    pub last_delivered_at: i64,
+    /// No destination got the asset's current version
+    pub changed_since: bool,
+}
+
+/// Asset list filter on delivery state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryFilter {
+    Delivered,
+    NotDelivered,
+    /// Delivered, but changed since
+    ChangedSince,
+}
+
+impl DeliveryFilter {
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "delivered" => Ok(DeliveryFilter::Delivered),
+            "not_delivered" => Ok(DeliveryFilter::NotDelivered),
+            "changed_since" => Ok(DeliveryFilter::ChangedSince),
+            other => Err(AppError::InvalidArgument(format!(
+                "Unknown delivery filter: {} (expected delivered, not_delivered or changed_since)",
+                other
+            ))),
+        }
+    }
+
+    pub fn matches(&self, badge: Option<&DeliveryBadge>) -> bool {
+        match self {
+            DeliveryFilter::Delivered => badge.is_some(),
+            DeliveryFilter::NotDelivered => badge.is_none(),
+            DeliveryFilter::ChangedSince => badge.is_some_and(|b| b.changed_since),
+        }
+    }
+}
+
+/// Set the delivered badge of each asset
+pub fn annotate(db: &Database, project_id: &str, assets: &mut [Asset]) -> AppResult<()> {
+    let mut badges = db.get_delivery_badges(project_id)?;
+    for asset in assets {
+        asset.delivery = badges.remove(&asset.id);
+    }
+    Ok(())
+}
+
+/// A delivery of `asset` as it is on disk now, written to `path`
+pub fn delivery(asset: &Asset, destination: &str, path: &str, archive: bool) -> AppResult<Delivery> {
+    let source = Path::new(&asset.absolute_path);
+    let metadata = fs::metadata(source)?;
+    Ok(Delivery {
+        asset_id: asset.id.clone(),
+        relative_path: asset.relative_path.clone(),
+        destination: destination.to_string(),
+        path: path.to_string(),
+        archive,
+        content_hash: sha256_file(source)?,
+        source_size: metadata.len() as i64,
+        source_modified_time: asset.modified_time,
+        exported_at: chrono::Utc::now().timestamp(),
+        verified_at: None,
+        status: None,
+        current_version: true,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryCheck {
+    pub checked: usize,
+    pub current: usize,
+    pub modified: usize,
+    pub missing: usize,
+    pub deliveries: Vec<Delivery>,
+}
+
+/// Hash each delivered file at its destination and compare it with what was
+/// exported, for a project or one asset. Results are stored with the log.
+pub fn verify_deliveries(db: &Database, project_id: &str, asset_id: Option<&str>) -> AppResult<DeliveryCheck> {
+    let mut deliveries = db.get_deliveries(project_id, asset_id)?;
+    let mut archives: HashMap<String, Option<ZipArchive<fs::File>>> = HashMap::new();
+    let now = chrono::Utc::now().timestamp();
+
+    for d in &mut deliveries {
+        let hash = if d.archive {
+            let archive = archives
+                .entry(d.destination.clone())
+                .or_insert_with(|| fs::File::open(&d.destination).ok().and_then(|f| ZipArchive::new(f).ok()));
+            archive
+                .as_mut()
+                .and_then(|a| a.by_name(&d.path).ok().map(sha256_reader))
+                .and_then(|hash| hash.ok())
+        } else {
+            sha256_file(Path::new(&d.path)).ok()
+        };
+        let status = match hash {
+            Some(hash) if hash == d.content_hash => "current",
+            Some(_) => "modified",
+            None => "missing",
+        };
+        d.status = Some(status.to_string());
+        d.verified_at = Some(now);
+    }
+    db.set_delivery_statuses(&deliveries)?;
+
+    let count = |status: &str| deliveries.iter().filter(|d| d.status.as_deref() == Some(status)).count();
+    Ok(DeliveryCheck {
+        checked: deliveries.len(),
+        current: count("current"),
+        modified: count("modified"),
+        missing: count("missing"),
+        deliveries,
+    })
+}
@@ -0,0 +1,564 @@
+use crate::db::{Asset, Database, Dependency};
+use crate::error::AppResult;
+use crate::plugins::{PluginCapability, PluginReference, PluginRegistry};
+use memchr::memmem::Finder;
+use memmap2::MmapOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, MAIN_SEPARATOR_STR};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, RwLock};
+
+/// Stop expanding a graph once it reaches this many nodes
+const MAX_GRAPH_NODES: usize = 500;
+/// Parsed assets whose edges are written together in one transaction
+const WRITE_BATCH_ASSETS: usize = 200;
+/// Assets are parsed in chunks of this size rather than read whole
+const READ_CHUNK_BYTES: usize = 1024 * 1024;
+/// Bytes kept from the end of a chunk with no line break in it, enough to
+/// hold a `guid: <32 hex>` reference cut by the chunk boundary
+const CHUNK_OVERLAP_BYTES: usize = 64;
+/// Files at least this large are memory-mapped rather than streamed
+const MMAP_MIN_BYTES: u64 = 1024 * 1024;
+/// What precedes every GUID reference in a serialized asset
+const GUID_PREFIX: &[u8] = b"guid:";
+/// Default `max_file_bytes`
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub file_name: String,
+    pub relative_path: String,
+    pub asset_type: String,
+    pub size_bytes: i64,
+    pub has_thumbnail: bool,
+    /// Distance from the root asset
+    pub depth: usize,
+    /// References from this asset whose GUID couldn't be resolved
+    pub missing_references: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub relation_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    pub root_id: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// True if the node limit was hit before reaching the requested depth
+    pub truncated: bool,
+}
+
+/// Every GUID in one project with the asset it belongs to, so resolving a
+/// reference is a hash lookup instead of a query
+struct GuidIndex {
+    project_id: String,
+    /// GUID -> (asset id, asset type)
+    assets: HashMap<String, (String, String)>,
+}
+
+pub struct DependencyResolver {
+    db: Arc<Database>,
+    guid_finder: Finder<'static>,
+    plugins: Option<Arc<PluginRegistry>>,
+    guid_index: RwLock<Option<GuidIndex>>,
+    /// Stop reading an asset after this many bytes; 0 reads it all
+    max_file_bytes: u64,
+}
+
+impl DependencyResolver {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            guid_finder: Finder::new(GUID_PREFIX),
+            plugins: None,
+            guid_index: RwLock::new(None),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+        }
+    }
+
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Load the project's GUIDs into memory. Until then, or for other
+    /// projects, each reference is looked up in the database.
+    pub fn load_guid_index(&self, project_id: &str) -> AppResult<()> {
+        let assets = self.db.get_guid_index(project_id)?;
+        *self.guid_index.write().unwrap_or_else(|e| e.into_inner()) = Some(GuidIndex {
+            project_id: project_id.to_string(),
+            assets,
+        });
+        Ok(())
+    }
+
+    /// Keep a loaded index in step with an asset that was added or re-indexed
+    pub fn index_asset(&self, asset: &Asset) {
+        let mut index = self.guid_index.write().unwrap_or_else(|e| e.into_inner());
+        if let (Some(index), Some(guid)) = (index.as_mut(), &asset.unity_guid) {
+            if index.project_id == asset.project_id {
+                index
+                    .assets
+                    .insert(guid.clone(), (asset.id.clone(), asset.asset_type.clone()));
+            }
+        }
+    }
+
+    /// Keep a loaded index in step with a removed asset
+    pub fn unindex_asset(&self, asset: &Asset) {
+        let mut index = self.guid_index.write().unwrap_or_else(|e| e.into_inner());
+        if let (Some(index), Some(guid)) = (index.as_mut(), &asset.unity_guid) {
+            if index.project_id == asset.project_id && index.assets.get(guid).is_some_and(|(id, _)| *id == asset.id) {
+                index.assets.remove(guid);
+            }
+        }
+    }
+
+    /// (asset id, asset type) of the asset with `guid`
+    fn find_guid(&self, project_id: &str, guid: &str) -> AppResult<Option<(String, String)>> {
+        {
+            let index = self.guid_index.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(index) = index.as_ref().filter(|index| index.project_id == project_id) {
+                return Ok(index.assets.get(guid).cloned());
+            }
+        }
+        Ok(self
+            .db
+            .get_asset_by_guid(project_id, guid)?
+            .map(|asset| (asset.id, asset.asset_type)))
+    }
+
+    /// Let plugins parse the formats they claim
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = Some(plugins);
+        self
+    }
+
+    pub fn resolve_dependencies_for_asset(&self, asset: &Asset) -> AppResult<Vec<Dependency>> {
+        let plugin_references = self
+            .plugins
+            .as_ref()
+            .and_then(|plugins| plugins.parse_dependencies(Path::new(&asset.absolute_path)));
+        if let Some(references) = plugin_references {
+            return match references {
+                Ok(references) => self.resolve_plugin_references(asset, references),
+                Err(e) => {
+                    tracing::warn!("{} ({})", e, asset.relative_path);
+                    Ok(Vec::new())
+                }
+            };
+        }
+
+        // Only parse certain asset types
+        match asset.asset_type.as_str() {
+            "material" | "prefab" | "scene" | "scriptable_object" => {}
+            _ => return Ok(Vec::new()),
+        }
+
+        let guids = match self.extract_guids(Path::new(&asset.absolute_path)) {
+            Ok(guids) => guids,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        // Filter out self-reference and create dependencies
+        let mut dependencies = Vec::new();
+        let now = chrono::Utc::now().timestamp();
+
+        for guid in guids {
+            // Skip self-reference
+            if asset.unity_guid.as_ref() == Some(&guid) {
+                continue;
+            }
+
+            // Try to resolve the GUID to an asset
+            let to_asset = self.find_guid(&asset.project_id, &guid)?;
+
+            let relation_type =
+                self.infer_relation_type(&asset.asset_type, to_asset.as_ref().map(|(_, t)| t.as_str()));
+
+            let dep = Dependency {
+                id: uuid::Uuid::new_v4().to_string(),
+                from_asset_id: asset.id.clone(),
+                to_asset_id: to_asset.map(|(id, _)| id),
+                to_guid: guid,
+                relation_type,
+                confidence: "high".to_string(),
+                created_at: now,
+            };
+
+            dependencies.push(dep);
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Turn references reported by a plugin into dependencies. Path references
+    /// are stored with the target's GUID when it has one, else with the path.
+    fn resolve_plugin_references(&self, asset: &Asset, references: Vec<PluginReference>) -> AppResult<Vec<Dependency>> {
+        let mut dependencies = Vec::new();
+        let mut seen = HashSet::new();
+        let now = chrono::Utc::now().timestamp();
+
+        for reference in references {
+            let guid = reference.guid.filter(|g| !g.trim().is_empty());
+            let path = reference.path.filter(|p| !p.trim().is_empty());
+            let (to_asset_id, to_guid, confidence) = match (guid, path) {
+                (Some(guid), _) => {
+                    let to_asset = self.find_guid(&asset.project_id, &guid)?;
+                    (to_asset.map(|(id, _)| id), guid, "high")
+                }
+                (None, Some(path)) => {
+                    let relative = path.trim_start_matches("./").replace(['/', '\\'], MAIN_SEPARATOR_STR);
+                    let to_asset = self.db.get_asset_by_relative_path(&asset.project_id, &relative)?;
+                    let to_guid = to_asset.as_ref().and_then(|a| a.unity_guid.clone()).unwrap_or(path);
+                    (to_asset.map(|a| a.id), to_guid, "medium")
+                }
+                (None, None) => continue,
+            };
+
+            if asset.unity_guid.as_ref() == Some(&to_guid) || !seen.insert(to_guid.clone()) {
+                continue;
+            }
+
+            dependencies.push(Dependency {
+                id: uuid::Uuid::new_v4().to_string(),
+                from_asset_id: asset.id.clone(),
+                to_asset_id,
+                to_guid,
+                relation_type: reference.relation_type.unwrap_or_else(|| "reference".to_string()),
+                confidence: confidence.to_string(),
+                created_at: now,
+            });
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Referenced GUIDs. Large files are memory-mapped and searched in place,
+    /// so a giant scene is neither copied into a buffer nor UTF-8 validated;
+    /// smaller files, and any the OS refuses to map, are streamed instead.
+    fn extract_guids(&self, path: &Path) -> std::io::Result<Vec<String>> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let limit = match self.max_file_bytes {
+            0 => len,
+            max => len.min(max),
+        };
+
+        let mut guids = HashSet::new();
+        if limit >= MMAP_MIN_BYTES {
+            // SAFETY: the map is read-only and dropped before returning. If
+            // another process truncates the file meanwhile, reads past the new
+            // end fault, as with any mapping; Unity writes scenes by replacing
+            // the file, which leaves this mapping intact.
+            match unsafe { MmapOptions::new().len(limit as usize).map(&file) } {
+                Ok(map) => self.scan_guids(&map, &mut guids),
+                Err(e) => {
+                    tracing::debug!("Couldn't map {}, reading it instead: {}", path.display(), e);
+                    self.stream_guids(file, limit, &mut guids)?;
+                }
+            }
+        } else {
+            self.stream_guids(file, limit, &mut guids)?;
+        }
+
+        if limit < len {
+            tracing::warn!(
+                "Stopped reading {} after {} bytes; references past that point are not recorded",
+                path.display(),
+                limit
+            );
+        }
+
+        Ok(guids.into_iter().collect())
+    }
+
+    /// Read `limit` bytes of `file` in chunks. Each pass searches up to the
+    /// last line break read so far and carries the rest over, since a
+    /// reference never spans lines.
+    fn stream_guids(&self, file: File, limit: u64, guids: &mut HashSet<String>) -> std::io::Result<()> {
+        let mut file = file.take(limit);
+        let mut buffer: Vec<u8> = Vec::with_capacity(READ_CHUNK_BYTES + CHUNK_OVERLAP_BYTES);
+        let mut chunk = vec![0u8; READ_CHUNK_BYTES];
+
+        loop {
+            let read = file.read(&mut chunk)?;
+            let at_end = read == 0;
+            buffer.extend_from_slice(&chunk[..read]);
+
+            // How much to search now and how much to drop afterwards
+            let (searched, consumed) = if at_end {
+                (buffer.len(), buffer.len())
+            } else {
+                match memchr::memrchr(b'\n', &buffer) {
+                    Some(newline) => (newline + 1, newline + 1),
+                    // One long line: search all of it but keep the tail in case a
+                    // reference is cut off; the set drops anything seen twice
+                    None => (buffer.len(), buffer.len().saturating_sub(CHUNK_OVERLAP_BYTES)),
+                }
+            };
+            self.scan_guids(&buffer[..searched], guids);
+
+            if at_end {
+                return Ok(());
+            }
+            buffer.drain(..consumed);
+        }
+    }
+
+    /// `guid: <32 lowercase hex digits>` references in `haystack`
+    fn scan_guids(&self, haystack: &[u8], guids: &mut HashSet<String>) {
+        for start in self.guid_finder.find_iter(haystack) {
+            let rest = &haystack[start + GUID_PREFIX.len()..];
+            let skip = rest.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            let Some(guid) = rest.get(skip..skip + 32) else {
+                continue;
+            };
+            if guid.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+                // All ASCII, so always valid UTF-8
+                guids.insert(String::from_utf8_lossy(guid).into_owned());
+            }
+        }
+    }
+
+    fn infer_relation_type(&self, from_type: &str, to_type: Option<&str>) -> String {
+        let to_type = to_type.unwrap_or("unknown");
+
+        match (from_type, to_type) {
+            ("material", "texture") => "material_texture".to_string(),
+            ("material", "shader") => "material_shader".to_string(),
+            ("prefab", "material") => "prefab_material".to_string(),
+            ("prefab", "model") => "prefab_model".to_string(),
+            ("prefab", "prefab") => "prefab_prefab".to_string(),
+            ("prefab", "texture") => "prefab_texture".to_string(),
+            ("scene", "prefab") => "scene_prefab".to_string(),
+            ("scene", "material") => "scene_material".to_string(),
+            ("scene", _) => "scene_reference".to_string(),
+            _ => "reference".to_string(),
+        }
+    }
+
+    pub fn resolve_all_for_project(&self, project_id: &str) -> AppResult<usize> {
+        self.resolve_all_for_project_with_progress(project_id, 1, Arc::new(AtomicBool::new(false)), |_, _| {})
+    }
+
+    /// Parse assets on `threads` workers; results are written and reported
+    /// from the calling thread so the callback doesn't need to be `Send`.
+    pub fn resolve_all_for_project_with_progress(
+        &self,
+        project_id: &str,
+        threads: usize,
+        cancel_flag: Arc<AtomicBool>,
+        mut progress_callback: impl FnMut(usize, usize),
+    ) -> AppResult<usize> {
+        let plugin_extensions = self
+            .plugins
+            .as_ref()
+            .map(|plugins| plugins.extensions_with(PluginCapability::Dependencies))
+            .unwrap_or_default();
+        let assets = self.db.get_parseable_assets(project_id, &plugin_extensions)?;
+        let total = assets.len();
+        self.load_guid_index(project_id)?;
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel::<(&Asset, AppResult<Vec<Dependency>>)>();
+
+        std::thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, total.max(1)) {
+                let tx = tx.clone();
+                let next = &next;
+                let assets = &assets;
+                let cancel_flag = &cancel_flag;
+                scope.spawn(move || loop {
+                    // Check cancellation
+                    if cancel_flag.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(asset) = assets.get(index) else {
+                        break;
+                    };
+
+                    if tx.send((asset, self.resolve_dependencies_for_asset(asset))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(tx);
+
+            let mut total_deps = 0;
+            let mut processed = 0;
+            let mut batch_assets: Vec<String> = Vec::with_capacity(WRITE_BATCH_ASSETS);
+            let mut batch_deps: Vec<Dependency> = Vec::new();
+
+            for (asset, deps) in rx {
+                let deps = deps?;
+                total_deps += deps.len();
+                processed += 1;
+
+                // Existing dependencies of the batch's assets are replaced
+                batch_assets.push(asset.id.clone());
+                batch_deps.extend(deps);
+                if batch_assets.len() >= WRITE_BATCH_ASSETS {
+                    self.db.replace_dependencies(&batch_assets, &batch_deps)?;
+                    batch_assets.clear();
+                    batch_deps.clear();
+                }
+
+                // Report progress
+                progress_callback(processed, total);
+            }
+            // What was parsed before a cancel is still written
+            if !batch_assets.is_empty() {
+                self.db.replace_dependencies(&batch_assets, &batch_deps)?;
+            }
+
+            Ok(total_deps)
+        })
+    }
+
+    /// Breadth-first subgraph around an asset for graph visualization.
+    /// `direction` is "dependencies", "dependents" or "both".
+    pub fn get_dependency_graph(
+        &self,
+        root: &Asset,
+        max_depth: usize,
+        direction: &str,
+    ) -> AppResult<DependencyGraph> {
+        let follow_deps = direction == "dependencies" || direction == "both";
+        let follow_dependents = direction == "dependents" || direction == "both";
+
+        let mut nodes: HashMap<String, GraphNode> = HashMap::new();
+        let mut edges = Vec::new();
+        let mut seen_edges = HashSet::new();
+        let mut queue = VecDeque::new();
+        let mut truncated = false;
+
+        nodes.insert(root.id.clone(), graph_node(root, 0));
+        queue.push_back((root.id.clone(), 0usize));
+
+        while let Some((asset_id, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+
+            let mut neighbours = Vec::new();
+
+            if follow_deps {
+                for dep in self.db.get_dependencies(&asset_id)? {
+                    match dep.to_asset_id {
+                        Some(to_id) => neighbours.push((asset_id.clone(), to_id, dep.relation_type)),
+                        None => {
+                            if let Some(node) = nodes.get_mut(&asset_id) {
+                                node.missing_references += 1;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if follow_dependents {
+                for dep in self.db.get_dependents(&asset_id)? {
+                    neighbours.push((dep.from_asset_id, asset_id.clone(), dep.relation_type));
+                }
+            }
+
+            for (from, to, relation_type) in neighbours {
+                let other = if from == asset_id { &to } else { &from };
+
+                if !nodes.contains_key(other) {
+                    if nodes.len() >= MAX_GRAPH_NODES {
+                        truncated = true;
+                        continue;
+                    }
+                    let Some(other_asset) = self.db.get_asset(other)? else {
+                        continue;
+                    };
+                    nodes.insert(other.clone(), graph_node(&other_asset, depth + 1));
+                    queue.push_back((other.clone(), depth + 1));
+                }
+
+                if seen_edges.insert((from.clone(), to.clone())) {
+                    edges.push(GraphEdge {
+                        from,
+                        to,
+                        relation_type,
+                    });
+                }
+            }
+        }
+
+        let mut nodes: Vec<GraphNode> = nodes.into_values().collect();
+        nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.file_name.cmp(&b.file_name)));
+
+        Ok(DependencyGraph {
+            root_id: root.id.clone(),
+            nodes,
+            edges,
+            truncated,
+        })
+    }
+
+    pub fn get_dependency_tree(
+        &self,
+        asset_id: &str,
+        max_depth: usize,
+    ) -> AppResult<Vec<String>> {
+        let mut visited = HashSet::new();
+        let mut result = Vec::new();
+
+        self.collect_dependencies(asset_id, 0, max_depth, &mut visited, &mut result)?;
+
+        Ok(result)
+    }
+
+    fn collect_dependencies(
+        &self,
+        asset_id: &str,
+        depth: usize,
+        max_depth: usize,
+        visited: &mut HashSet<String>,
+        result: &mut Vec<String>,
+    ) -> AppResult<()> {
+        if depth >= max_depth || visited.contains(asset_id) {
+            return Ok(());
+        }
+
+        visited.insert(asset_id.to_string());
+
+        let deps = self.db.get_dependencies(asset_id)?;
+
+        for dep in deps {
+            if let Some(to_id) = dep.to_asset_id {
+                if !visited.contains(&to_id) {
+                    result.push(to_id.clone());
+                    self.collect_dependencies(&to_id, depth + 1, max_depth, visited, result)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn graph_node(asset: &Asset, depth: usize) -> GraphNode {
+    GraphNode {
+        id: asset.id.clone(),
+        file_name: asset.file_name.clone(),
+        relative_path: asset.relative_path.clone(),
+        asset_type: asset.asset_type.clone(),
+        size_bytes: asset.size_bytes,
+        has_thumbnail: asset.has_thumbnail(),
+        depth,
+        missing_references: 0,
+    }
+}
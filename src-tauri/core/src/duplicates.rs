@@ -0,0 +1,217 @@
+//! Checking whether a file from outside the project (a download, something
+//! a client sent) is already in it, before it gets imported a second time.
+//!
+//! Identical files are found by SHA-256 among assets of the same size; the
+//! hashes are kept in `assets.content_hash` until the file is reindexed.
+//! Images are also compared by a 64-bit difference hash, so a re-encoded
+//! or resized copy still turns up. Those hashes are kept in `image_hashes`
+//! per file version and computed for the whole project on first use.
+
+use crate::db::{Asset, AssetSummary, Database};
+use crate::error::AppResult;
+use crate::previews::{open_image_limited, ThumbnailOptions};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Image hashes at most this many bits apart count as similar
+pub const DEFAULT_MAX_DISTANCE: u32 = 10;
+/// Matches reported, identical ones first
+const MAX_MATCHES: usize = 50;
+/// Formats the image hash is computed for
+const HASHED_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tga", "bmp", "gif", "webp"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalFileMatch {
+    pub asset: AssetSummary,
+    pub relative_path: String,
+    /// "identical" (same bytes) or "similar" (close image hash)
+    pub kind: String,
+    /// Bits the image hashes differ by; 0 for identical files
+    pub distance: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalFileReport {
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    /// False when the file isn't an image this can decode, so only
+    /// identical copies were looked for
+    pub compared_images: bool,
+    pub matches: Vec<ExternalFileMatch>,
+}
+
+/// Look for `path` among the project's assets. `threads` decode images whose
+/// hash isn't stored yet.
+pub fn find_external_file(
+    db: &Database,
+    project_id: &str,
+    path: &Path,
+    options: &ThumbnailOptions,
+    threads: usize,
+    max_distance: u32,
+) -> AppResult<ExternalFileReport> {
+    let size_bytes = std::fs::metadata(path)?.len();
+    let sha256 = sha256_file(path)?;
+
+    let mut matches = Vec::new();
+    for asset in db.get_assets_by_size(project_id, size_bytes as i64)? {
+        let hash = match &asset.content_hash {
+            Some(hash) => hash.clone(),
+            None => {
+                let Ok(hash) = sha256_file(Path::new(&asset.absolute_path)) else {
+                    continue;
+                };
+                // A shared catalog is read-only; the hash is just not kept
+                if let Err(e) = db.set_content_hash(&asset.id, &hash) {
+                    tracing::debug!("Content hash not stored for {}: {}", asset.relative_path, e);
+                }
+                hash
+            }
+        };
+        if hash == sha256 {
+            matches.push(found(&asset, "identical", 0));
+        }
+    }
+
+    let image_hash = is_hashed_image(path).then(|| image_hash(path, options)).flatten();
+    if let Some(image_hash) = image_hash {
+        let hashes = project_image_hashes(db, project_id, options, threads)?;
+        let mut similar: Vec<(u32, &Asset)> = hashes
+            .iter()
+            .map(|(asset, hash)| ((hash ^ image_hash).count_ones(), asset))
+            .filter(|(distance, asset)| {
+                *distance <= max_distance && !matches.iter().any(|m: &ExternalFileMatch| m.asset.id == asset.id)
+            })
+            .collect();
+        similar.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.relative_path.cmp(&b.1.relative_path)));
+        matches.extend(similar.into_iter().map(|(distance, asset)| found(asset, "similar", distance)));
+    }
+    matches.truncate(MAX_MATCHES);
+
+    Ok(ExternalFileReport {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        sha256,
+        compared_images: image_hash.is_some(),
+        matches,
+    })
+}
+
+fn found(asset: &Asset, kind: &str, distance: u32) -> ExternalFileMatch {
+    ExternalFileMatch {
+        asset: AssetSummary::from(asset),
+        relative_path: asset.relative_path.clone(),
+        kind: kind.to_string(),
+        distance,
+    }
+}
+
+/// Hex SHA-256 of a file's contents
+pub fn sha256_file(path: &Path) -> AppResult<String> {
+    sha256_reader(File::open(path)?)
+}
+
+/// Hex SHA-256 of everything `reader` yields, such as an archive entry
+pub fn sha256_reader(mut reader: impl Read) -> AppResult<String> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn is_hashed_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| HASHED_IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Difference hash: shrink to 9x8 grey and set a bit wherever a pixel is
+/// brighter than its right neighbour. Survives resizing and re-encoding.
+pub fn image_hash(path: &Path, options: &ThumbnailOptions) -> Option<u64> {
+    if options.exceeds_decode_budget(path) {
+        return None;
+    }
+    let image = open_image_limited(path, options).ok()?;
+    let grey = image.resize_exact(9, 8, image::imageops::FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if grey.get_pixel(x, y)[0] > grey.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    Some(hash)
+}
+
+/// Image hashes for every decodable texture in the project, computing and
+/// storing those missing or made from an older version of the file
+pub fn project_image_hashes(
+    db: &Database,
+    project_id: &str,
+    options: &ThumbnailOptions,
+    threads: usize,
+) -> AppResult<Vec<(Asset, u64)>> {
+    let stored = db.get_image_hashes(project_id)?;
+    let textures: Vec<Asset> = db
+        .get_assets_by_types(project_id, &["texture".to_string()])?
+        .into_iter()
+        .filter(|asset| is_hashed_image(Path::new(&asset.file_name)))
+        .collect();
+
+    let mut hashes = Vec::with_capacity(textures.len());
+    let mut stale = Vec::new();
+    for asset in textures {
+        match stored.get(&asset.id) {
+            Some(&(modified_time, size_bytes, hash))
+                if modified_time == asset.modified_time && size_bytes == asset.size_bytes =>
+            {
+                hashes.push((asset, hash))
+            }
+            _ => stale.push(asset),
+        }
+    }
+    if stale.is_empty() {
+        return Ok(hashes);
+    }
+
+    let next = AtomicUsize::new(0);
+    let computed = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, stale.len()) {
+            scope.spawn(|| {
+                while let Some(asset) = stale.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    if let Some(hash) = image_hash(Path::new(&asset.absolute_path), options) {
+                        computed.lock().unwrap().push((asset.clone(), hash));
+                    }
+                }
+            });
+        }
+    });
+    let computed = computed.into_inner().unwrap();
+
+    let rows: Vec<(String, i64, i64, u64)> = computed
+        .iter()
+        .map(|(asset, hash)| (asset.id.clone(), asset.modified_time, asset.size_bytes, *hash))
+        .collect();
+    if let Err(e) = db.save_image_hashes(&rows) {
+        tracing::debug!("Image hashes not stored: {}", e);
+    }
+    tracing::info!("Computed {} image hashes", rows.len());
+
+    hashes.extend(computed);
+    Ok(hashes)
+}
@@ -0,0 +1,112 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid project: {0}")]
+    InvalidProject(String),
+
+    #[error("Asset not found: {0}")]
+    AssetNotFound(String),
+
+    #[error("A scan is already running for this project")]
+    ScanAlreadyRunning,
+
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Destination already exists: {0}")]
+    ExportConflict(String),
+
+    #[error("The catalog is open read-only")]
+    ReadOnlyCatalog,
+
+    #[error("Plugin {plugin} failed: {message}")]
+    Plugin { plugin: String, message: String },
+
+    #[error("R2D2 pool error: {0}")]
+    Pool(#[from] r2d2::Error),
+
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl AppError {
+    /// Stable code the frontend can match on instead of the message text
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) if self.is_busy() => "DB_BUSY",
+            AppError::Database(_) => "DATABASE",
+            AppError::Io(_) => "IO",
+            AppError::Image(_) => "IMAGE",
+            AppError::Json(_) => "JSON",
+            AppError::InvalidProject(_) => "INVALID_PROJECT",
+            AppError::AssetNotFound(_) => "ASSET_NOT_FOUND",
+            AppError::ScanAlreadyRunning => "SCAN_RUNNING",
+            AppError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::ExportConflict(_) => "EXPORT_CONFLICT",
+            AppError::ReadOnlyCatalog => "READ_ONLY",
+            AppError::Plugin { .. } => "PLUGIN",
+            // The pool only fails when every connection stays checked out
+            AppError::Pool(_) => "DB_BUSY",
+            AppError::Custom(_) => "INTERNAL",
+        }
+    }
+
+    /// SQLite reported the database busy or locked
+    pub fn is_busy(&self) -> bool {
+        match self {
+            AppError::Database(e) => matches!(
+                e.sqlite_error_code(),
+                Some(rusqlite::ErrorCode::DatabaseBusy) | Some(rusqlite::ErrorCode::DatabaseLocked)
+            ),
+            _ => false,
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            AppError::Database(rusqlite::Error::SqliteFailure(e, _)) => {
+                Some(serde_json::json!({ "sqlite_code": e.extended_code }))
+            }
+            AppError::Io(e) => Some(serde_json::json!({ "kind": format!("{:?}", e.kind()) })),
+            AppError::AssetNotFound(id) => Some(serde_json::json!({ "asset_id": id })),
+            AppError::ExportConflict(path) => Some(serde_json::json!({ "path": path })),
+            AppError::Plugin { plugin, .. } => Some(serde_json::json!({ "plugin": plugin })),
+            _ => None,
+        }
+    }
+}
+
+/// Errors cross the IPC boundary as `{ code, message, details }`
+impl serde::Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut payload = serializer.serialize_struct("AppError", 3)?;
+        payload.serialize_field("code", self.code())?;
+        payload.serialize_field("message", &self.to_string())?;
+        payload.serialize_field("details", &self.details())?;
+        payload.end()
+    }
+}
+
+pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,663 @@
+use crate::db::{Asset, Database};
+use crate::deliveries;
+use crate::deps::DependencyResolver;
+use crate::error::{AppError, AppResult};
+use crate::scanner::read_unity_guid;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Deepest dependency level an export may follow
+pub const MAX_EXPORT_DEPTH: usize = 20;
+
+/// Drag staging folders older than this are removed by the next `stage_for_drag`
+const DRAG_STAGE_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Export behaviour. Defaults live in settings; each call may override fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Dependency levels followed by `export_bundle`
+    pub max_depth: usize,
+    /// "overwrite", "skip", "rename" or "fail" when a destination file already exists
+    pub conflict_policy: String,
+    /// Copy `.meta` files so GUIDs (and therefore references) survive the
+    /// export; a .unitypackage always has them
+    pub include_meta: bool,
+    /// "folder" copies files into the destination, "zip" writes one archive
+    /// there and "unitypackage" a package Unity can import
+    pub archive_format: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            conflict_policy: "overwrite".to_string(),
+            include_meta: true,
+            archive_format: "folder".to_string(),
+        }
+    }
+}
+
+/// Per-call overrides for `ExportOptions`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportOverrides {
+    pub max_depth: Option<usize>,
+    pub conflict_policy: Option<String>,
+    pub include_meta: Option<bool>,
+    pub archive_format: Option<String>,
+}
+
+impl ExportOptions {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.max_depth > MAX_EXPORT_DEPTH {
+            return Err(AppError::InvalidArgument(format!(
+                "Export depth must be at most {}",
+                MAX_EXPORT_DEPTH
+            )));
+        }
+        if !matches!(self.conflict_policy.as_str(), "overwrite" | "skip" | "rename" | "fail") {
+            return Err(AppError::InvalidArgument(format!(
+                "Unknown conflict policy: {}",
+                self.conflict_policy
+            )));
+        }
+        if !matches!(self.archive_format.as_str(), "folder" | "zip" | "unitypackage") {
+            return Err(AppError::InvalidArgument(format!(
+                "Unknown archive format: {}",
+                self.archive_format
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn with_overrides(&self, overrides: Option<ExportOverrides>) -> AppResult<Self> {
+        let mut options = self.clone();
+        if let Some(o) = overrides {
+            if let Some(depth) = o.max_depth {
+                options.max_depth = depth;
+            }
+            if let Some(policy) = o.conflict_policy {
+                options.conflict_policy = policy;
+            }
+            if let Some(include_meta) = o.include_meta {
+                options.include_meta = include_meta;
+            }
+            if let Some(format) = o.archive_format {
+                options.archive_format = format;
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub version: String,
+    pub exported_at: String,
+    pub source_project: String,
+    pub root_asset: String,
+    pub assets: Vec<ExportedAsset>,
+    pub dependency_graph: Vec<DependencyEdge>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedAsset {
+    pub relative_path: String,
+    pub asset_type: String,
+    pub unity_guid: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub relation_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub success: bool,
+    pub exported_files: Vec<String>,
+    pub manifest_path: Option<String>,
+    /// Files left alone because of the "skip" conflict policy
+    pub skipped_files: Vec<String>,
+    /// Set when the export was written as an archive
+    pub archive_path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct Exporter {
+    db: Arc<Database>,
+    dep_resolver: Arc<DependencyResolver>,
+}
+
+impl Exporter {
+    pub fn new(db: Arc<Database>) -> Self {
+        let dep_resolver = Arc::new(DependencyResolver::new(Arc::clone(&db)));
+        Self { db, dep_resolver }
+    }
+
+    pub fn export_file(
+        &self,
+        asset: &Asset,
+        dest_folder: &Path,
+        options: &ExportOptions,
+    ) -> AppResult<ExportResult> {
+        let source_path = Path::new(&asset.absolute_path);
+
+        if !source_path.exists() {
+            return Ok(ExportResult {
+                success: false,
+                exported_files: vec![],
+                manifest_path: None,
+                skipped_files: vec![],
+                archive_path: None,
+                error: Some(format!("Source file not found: {}", asset.absolute_path)),
+            });
+        }
+
+        let mut result = ExportResult {
+            success: true,
+            exported_files: vec![],
+            manifest_path: None,
+            skipped_files: vec![],
+            archive_path: None,
+            error: None,
+        };
+
+        let Some(mut target) = ExportTarget::open(dest_folder, asset, options)? else {
+            result.skipped_files.push(asset.relative_path.clone());
+            return Ok(result);
+        };
+
+        // Preserve relative path structure
+        match target.add_asset(&asset.relative_path, source_path, options.include_meta)? {
+            Some(written) => result.exported_files.push(written),
+            None => result.skipped_files.push(asset.relative_path.clone()),
+        }
+
+        result.archive_path = target.finish()?;
+        let written: Vec<(&Asset, &str)> = result.exported_files.iter().map(|path| (asset, path.as_str())).collect();
+        self.record_deliveries(&written, dest_folder, result.archive_path.as_deref());
+        Ok(result)
+    }
+
+    pub fn export_bundle(
+        &self,
+        asset: &Asset,
+        dest_folder: &Path,
+        options: &ExportOptions,
+    ) -> AppResult<ExportResult> {
+        let mut exported_files = Vec::new();
+        let mut skipped_files = Vec::new();
+        let mut exported_paths = HashSet::new();
+        let mut dependency_edges = Vec::new();
+
+        let Some(mut target) = ExportTarget::open(dest_folder, asset, options)? else {
+            return Ok(ExportResult {
+                success: true,
+                exported_files,
+                manifest_path: None,
+                skipped_files: vec![asset.relative_path.clone()],
+                archive_path: None,
+                error: None,
+            });
+        };
+
+        // Get all dependencies recursively
+        let dep_ids = self
+            .dep_resolver
+            .get_dependency_tree(&asset.id, options.max_depth)?;
+
+        // Collect all assets to export (root + dependencies)
+        let mut assets_to_export = vec![asset.clone()];
+
+        for dep_id in &dep_ids {
+            if let Some(dep_asset) = self.db.get_asset(dep_id)? {
+                assets_to_export.push(dep_asset);
+            }
+        }
+
+        // Export each asset
+        let mut written = Vec::new();
+        for export_asset in &assets_to_export {
+            let source_path = Path::new(&export_asset.absolute_path);
+
+            if !source_path.exists() {
+                tracing::warn!("Skipping missing file: {}", export_asset.absolute_path);
+                continue;
+            }
+
+            if exported_paths.contains(&export_asset.relative_path) {
+                continue;
+            }
+
+            match target.add_asset(&export_asset.relative_path, source_path, options.include_meta)? {
+                Some(path) => {
+                    written.push((export_asset, path.clone()));
+                    exported_files.push(path);
+                }
+                None => skipped_files.push(export_asset.relative_path.clone()),
+            }
+
+            // Skipped files already exist at the destination, so the manifest still lists them
+            exported_paths.insert(export_asset.relative_path.clone());
+        }
+
+        // Build dependency graph for manifest
+        for export_asset in &assets_to_export {
+            let deps = self.db.get_dependencies(&export_asset.id)?;
+
+            for dep in deps {
+                if let Some(to_asset_id) = dep.to_asset_id {
+                    if let Some(to_asset) = self.db.get_asset(&to_asset_id)? {
+                        if exported_paths.contains(&to_asset.relative_path) {
+                            dependency_edges.push(DependencyEdge {
+                                from: export_asset.relative_path.clone(),
+                                to: to_asset.relative_path.clone(),
+                                relation_type: dep.relation_type,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Get project info for manifest
+        let project = self.db.get_project_by_path(
+            Path::new(&asset.absolute_path)
+                .parent()
+                .and_then(|p| {
+                    // Walk up to find project root
+                    let mut current = p;
+                    while !current.join("Assets").exists() {
+                        current = current.parent()?;
+                    }
+                    Some(current)
+                })
+                .map(|p| p.to_string_lossy().to_string())
+                .as_deref()
+                .unwrap_or(""),
+        )?;
+
+        // Create manifest
+        let manifest = ExportManifest {
+            version: "1.0".to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            source_project: project
+                .map(|p| p.name)
+                .unwrap_or_else(|| "Unknown".to_string()),
+            root_asset: asset.relative_path.clone(),
+            assets: assets_to_export
+                .iter()
+                .filter(|a| exported_paths.contains(&a.relative_path))
+                .map(|a| ExportedAsset {
+                    relative_path: a.relative_path.clone(),
+                    asset_type: a.asset_type.clone(),
+                    unity_guid: a.unity_guid.clone(),
+                })
+                .collect(),
+            dependency_graph: dependency_edges,
+        };
+
+        // Write manifest
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+        let manifest_path = target.add_manifest(manifest_json.as_bytes())?;
+        let archive_path = target.finish()?;
+        let written: Vec<(&Asset, &str)> = written.iter().map(|(asset, path)| (*asset, path.as_str())).collect();
+        self.record_deliveries(&written, dest_folder, archive_path.as_deref());
+
+        Ok(ExportResult {
+            success: true,
+            exported_files,
+            manifest_path,
+            skipped_files,
+            archive_path,
+            error: None,
+        })
+    }
+
+    /// Log the files written for delivered badges. The export itself
+    /// succeeded, so a failure here (such as a read-only catalog) is only logged.
+    fn record_deliveries(&self, written: &[(&Asset, &str)], dest_folder: &Path, archive_path: Option<&str>) {
+        let result = written
+            .iter()
+            .map(|(asset, path)| match archive_path {
+                Some(archive) => deliveries::delivery(asset, archive, &path.replace('\\', "/"), true),
+                None => deliveries::delivery(
+                    asset,
+                    &dest_folder.to_string_lossy(),
+                    &dest_folder.join(path).to_string_lossy(),
+                    false,
+                ),
+            })
+            .collect::<AppResult<Vec<_>>>()
+            .and_then(|deliveries| self.db.record_deliveries(&deliveries));
+        if let Err(e) = result {
+            tracing::warn!("Export not logged as a delivery: {}", e);
+        }
+    }
+}
+
+/// Files staged by `stage_for_drag`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DragStage {
+    /// Folder holding the staged files
+    pub path: String,
+    /// Staged assets in the order given; `.meta` files sit beside them
+    pub files: Vec<String>,
+}
+
+/// Put `assets` and their `.meta` files side by side in a new folder under
+/// `staging_root`, for dragging out of the app into Explorer or Unity.
+/// Assets with the same name get `Name (1).ext`-style names.
+///
+/// The files are copies, never hard links: a drop target that edits a
+/// staged file in place would otherwise edit the project's asset. The copy
+/// clones blocks on file systems that support it (APFS, Btrfs, XFS), so it
+/// stays quick there.
+pub fn stage_for_drag(assets: &[Asset], staging_root: &Path) -> AppResult<DragStage> {
+    remove_old_stages(staging_root);
+
+    let stage = staging_root.join(uuid::Uuid::new_v4().to_string());
+    fs::create_dir_all(&stage)?;
+
+    let mut files = Vec::with_capacity(assets.len());
+    for asset in assets {
+        let source = Path::new(&asset.absolute_path);
+        let mut dest = stage.join(&asset.file_name);
+        if dest.exists() {
+            dest = next_free_path(&dest);
+        }
+        fs::copy(source, &dest)?;
+
+        let meta_source = PathBuf::from(format!("{}.meta", source.display()));
+        if meta_source.exists() {
+            let meta_dest = PathBuf::from(format!("{}.meta", dest.display()));
+            fs::copy(&meta_source, &meta_dest)?;
+        }
+        files.push(dest.to_string_lossy().to_string());
+    }
+
+    Ok(DragStage {
+        path: stage.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+/// Best effort: a drop target may still be reading a recent stage, so only
+/// old ones go
+fn remove_old_stages(staging_root: &Path) {
+    let Ok(entries) = fs::read_dir(staging_root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let old = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > DRAG_STAGE_MAX_AGE);
+        if old && entry.path().is_dir() {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Where exported files go: a plain folder, or a zip archive or
+/// .unitypackage inside it
+enum ExportTarget {
+    Folder {
+        root: PathBuf,
+        conflict_policy: String,
+    },
+    Zip {
+        writer: ZipWriter<fs::File>,
+        path: PathBuf,
+    },
+    /// A gzipped tar with one folder per GUID holding `asset`, `asset.meta`
+    /// and `pathname`, the path Unity imports it to
+    UnityPackage {
+        builder: tar::Builder<GzEncoder<BufWriter<fs::File>>>,
+        path: PathBuf,
+        /// GUIDs written so far, folders included; each goes in once
+        guids: HashSet<String>,
+    },
+}
+
+impl ExportTarget {
+    /// Returns `None` if the archive already exists and the policy is "skip"
+    fn open(dest_folder: &Path, root_asset: &Asset, options: &ExportOptions) -> AppResult<Option<Self>> {
+        fs::create_dir_all(dest_folder)?;
+
+        if options.archive_format == "folder" {
+            return Ok(Some(Self::Folder {
+                root: dest_folder.to_path_buf(),
+                conflict_policy: options.conflict_policy.clone(),
+            }));
+        }
+
+        let stem = Path::new(&root_asset.file_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "export".to_string());
+        let file_name = format!("{}.{}", stem, options.archive_format);
+        let Some(path) = resolve_conflict(dest_folder.join(file_name), &options.conflict_policy)? else {
+            return Ok(None);
+        };
+
+        let file = fs::File::create(&path)?;
+        if options.archive_format == "unitypackage" {
+            return Ok(Some(Self::UnityPackage {
+                builder: tar::Builder::new(GzEncoder::new(BufWriter::new(file), Compression::default())),
+                path,
+                guids: HashSet::new(),
+            }));
+        }
+        Ok(Some(Self::Zip {
+            writer: ZipWriter::new(file),
+            path,
+        }))
+    }
+
+    /// Copy an asset (and optionally its .meta). Returns the relative path
+    /// actually written, or `None` if it was skipped by the conflict policy.
+    fn add_asset(&mut self, relative_path: &str, source: &Path, include_meta: bool) -> AppResult<Option<String>> {
+        let meta_source = PathBuf::from(format!("{}.meta", source.display()));
+        let copy_meta = include_meta && meta_source.exists();
+
+        match self {
+            Self::Folder { root, conflict_policy } => {
+                let Some(dest_path) = resolve_conflict(root.join(relative_path), conflict_policy)? else {
+                    return Ok(None);
+                };
+
+                if let Some(parent) = dest_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(source, &dest_path)?;
+
+                if copy_meta {
+                    let meta_dest = PathBuf::from(format!("{}.meta", dest_path.display()));
+                    let _ = fs::copy(&meta_source, &meta_dest);
+                }
+
+                let written = dest_path.strip_prefix(root.as_path()).unwrap_or(&dest_path);
+                Ok(Some(written.to_string_lossy().to_string()))
+            }
+            Self::Zip { writer, .. } => {
+                let entry = relative_path.replace('\\', "/");
+                write_zip_entry(writer, &entry, &fs::read(source)?)?;
+
+                if copy_meta {
+                    write_zip_entry(writer, &format!("{}.meta", entry), &fs::read(&meta_source)?)?;
+                }
+
+                Ok(Some(relative_path.to_string()))
+            }
+            Self::UnityPackage { builder, guids, .. } => {
+                let pathname = unity_pathname(relative_path);
+                // Parent folders go in with their own GUIDs so the import
+                // recreates them as they were; the first one is Assets itself
+                let project_root = source.ancestors().nth(Path::new(relative_path).components().count());
+                if let Some(project_root) = project_root {
+                    for folder in pathname.match_indices('/').map(|(i, _)| &pathname[..i]).skip(1) {
+                        let folder_meta = project_root.join(format!("{}.meta", folder));
+                        let Some(guid) = read_unity_guid(&folder_meta) else {
+                            continue;
+                        };
+                        if guids.insert(guid.clone()) {
+                            append_package_entry(builder, &guid, "asset.meta", &fs::read(&folder_meta)?)?;
+                            append_package_entry(builder, &guid, "pathname", folder.as_bytes())?;
+                        }
+                    }
+                }
+
+                // Unity can't place an asset without a GUID, so one without
+                // a .meta gets a fresh one
+                let (guid, meta) = match read_unity_guid(&meta_source) {
+                    Some(guid) => (guid, fs::read(&meta_source)?),
+                    None => {
+                        let (guid, meta) = generated_meta();
+                        (guid, meta.into_bytes())
+                    }
+                };
+                if !guids.insert(guid.clone()) {
+                    return Ok(None);
+                }
+                append_package_entry(builder, &guid, "asset", &fs::read(source)?)?;
+                append_package_entry(builder, &guid, "asset.meta", &meta)?;
+                append_package_entry(builder, &guid, "pathname", pathname.as_bytes())?;
+                Ok(Some(pathname))
+            }
+        }
+    }
+
+    /// The manifest describes this export, so it always replaces an older one
+    fn add_manifest(&mut self, content: &[u8]) -> AppResult<Option<String>> {
+        match self {
+            Self::Folder { root, .. } => {
+                let path = root.join("manifest.json");
+                fs::write(&path, content)?;
+                Ok(Some(path.to_string_lossy().to_string()))
+            }
+            Self::Zip { writer, .. } => {
+                write_zip_entry(writer, "manifest.json", content)?;
+                Ok(None)
+            }
+            // Unity has nowhere to put it, and the package says where everything goes
+            Self::UnityPackage { .. } => Ok(None),
+        }
+    }
+
+    /// Finalize the archive, returning its path
+    fn finish(self) -> AppResult<Option<String>> {
+        match self {
+            Self::Folder { .. } => Ok(None),
+            Self::Zip { writer, path } => {
+                writer
+                    .finish()
+                    .map_err(|e| AppError::Custom(format!("Failed to write archive: {}", e)))?;
+                Ok(Some(path.to_string_lossy().to_string()))
+            }
+            Self::UnityPackage { builder, path, .. } => {
+                builder
+                    .into_inner()
+                    .and_then(|encoder| encoder.finish())
+                    .and_then(|mut writer| writer.flush())
+                    .map_err(|e| AppError::Custom(format!("Failed to write package: {}", e)))?;
+                Ok(Some(path.to_string_lossy().to_string()))
+            }
+        }
+    }
+}
+
+/// A minimal .meta with a fresh GUID, for a file that has none. Unity
+/// fills in the importer settings the first time it sees the file.
+pub fn generated_meta() -> (String, String) {
+    let guid = uuid::Uuid::new_v4().simple().to_string();
+    let meta = format!("fileFormatVersion: 2\nguid: {}\n", guid);
+    (guid, meta)
+}
+
+/// The path Unity imports a file to; packages can only target `Assets/`
+fn unity_pathname(relative_path: &str) -> String {
+    let path = relative_path.replace('\\', "/");
+    if path.starts_with("Assets/") {
+        path
+    } else {
+        format!("Assets/{}", path)
+    }
+}
+
+fn append_package_entry(
+    builder: &mut tar::Builder<GzEncoder<BufWriter<fs::File>>>,
+    guid: &str,
+    name: &str,
+    content: &[u8],
+) -> AppResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, format!("{}/{}", guid, name), content)?;
+    Ok(())
+}
+
+fn write_zip_entry(writer: &mut ZipWriter<fs::File>, name: &str, content: &[u8]) -> AppResult<()> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(content.len() as u64 >= u32::MAX as u64);
+    writer
+        .start_file(name, options)
+        .map_err(|e| AppError::Custom(format!("Failed to write archive: {}", e)))?;
+    writer.write_all(content)?;
+    Ok(())
+}
+
+/// Apply the conflict policy to a destination path. `None` means skip.
+fn resolve_conflict(path: PathBuf, policy: &str) -> AppResult<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(Some(path));
+    }
+
+    match policy {
+        "skip" => Ok(None),
+        "rename" => Ok(Some(next_free_path(&path))),
+        "fail" => Err(AppError::ExportConflict(path.to_string_lossy().to_string())),
+        _ => Ok(Some(path)),
+    }
+}
+
+/// `Texture.png` -> `Texture (1).png`, `Texture (2).png`, ...
+fn next_free_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut index = 1;
+    loop {
+        let candidate = path.with_file_name(format!("{} ({}){}", stem, index, extension));
+        if !candidate.exists() {
+            return candidate;
+        }
+        index += 1;
+    }
+}
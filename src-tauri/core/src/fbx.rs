@@ -0,0 +1,467 @@
+//! Geometry of binary FBX files for `parse_model_info`. The file is a tree of
+//! nodes, each with typed properties (numbers, strings, arrays that may be
+//! zlib-compressed) followed by its child nodes. Only the nodes geometry
+//! stats need are decoded: the `Objects` geometry and models, the
+//! `Connections` that place geometry under models and the unit scale.
+//!
+//! Counts are of the mesh as authored: vertices are control points before
+//! Unity splits them at normal and UV seams, and polygons are triangulated
+//! as fans. Bounds are in metres, scaled by the file's unit the way Unity's
+//! default import does, in the file's own axes.
+
+use crate::previews::{multiply, transform_bounds, union_bounds, ModelInfo, IDENTITY_MATRIX};
+use flate2::read::ZlibDecoder;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+const MAGIC: &[u8] = b"Kaydara FBX Binary  \0";
+/// Magic, two bytes and the version
+const HEADER_LEN: usize = 27;
+/// Node records have 64-bit offsets from this version on
+const WIDE_VERSION: u32 = 7500;
+/// Model nesting followed for transforms; deeper means a cycle
+const MAX_MODEL_DEPTH: usize = 256;
+/// FBX units are centimetres times the file's `UnitScaleFactor`
+const CENTIMETRE: f64 = 0.01;
+
+/// A node record: its name and property bytes, and where its children are
+struct Node<'a> {
+    name: &'a [u8],
+    properties: &'a [u8],
+    property_count: usize,
+    children_start: usize,
+    end: usize,
+}
+
+enum Value<'a> {
+    Integer(i64),
+    Number(f64),
+    Text(&'a [u8]),
+    Integers(Vec<i64>),
+    Numbers(Vec<f64>),
+    /// Raw bytes, which geometry never needs
+    Other,
+}
+
+impl Value<'_> {
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(v) => Some(*v as f64),
+            Value::Number(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn as_text(&self) -> Option<&[u8]> {
+        match self {
+            Value::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    /// 64-bit record offsets
+    wide: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// Node records from `start` up to the null record closing the list or
+    /// `end`. A broken record ends the list early.
+    fn nodes(&self, start: usize, end: usize) -> Vec<Node<'a>> {
+        let mut nodes = Vec::new();
+        let mut offset = start;
+        while let Some(node) = self.record(offset, end) {
+            offset = node.end;
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// The record at `offset`: its end offset, property count and property
+    /// bytes, then a length-prefixed name. `None` for the null record.
+    fn record(&self, offset: usize, end: usize) -> Option<Node<'a>> {
+        let (width, header) = if self.wide { (8, 25) } else { (4, 13) };
+        let field = |offset: usize| -> Option<usize> {
+            if self.wide {
+                usize::try_from(read_u64(self.data, offset)?).ok()
+            } else {
+                Some(read_u32(self.data, offset)? as usize)
+            }
+        };
+        let node_end = field(offset)?;
+        let property_count = field(offset + width)?;
+        let list_len = field(offset + 2 * width)?;
+        let name_start = offset + header;
+        let properties_start = name_start + *self.data.get(offset + header - 1)? as usize;
+        let children_start = properties_start.checked_add(list_len)?;
+        if node_end <= offset || node_end > end || children_start > node_end {
+            return None;
+        }
+        Some(Node {
+            name: self.data.get(name_start..properties_start)?,
+            properties: self.data.get(properties_start..children_start)?,
+            property_count,
+            children_start,
+            end: node_end,
+        })
+    }
+
+    fn children(&self, node: &Node<'a>) -> Vec<Node<'a>> {
+        self.nodes(node.children_start, node.end)
+    }
+
+    fn child(&self, node: &Node<'a>, name: &[u8]) -> Option<Node<'a>> {
+        self.children(node).into_iter().find(|c| c.name == name)
+    }
+}
+
+/// Decode a node's properties, stopping at an unknown type
+fn properties<'a>(node: &Node<'a>) -> Vec<Value<'a>> {
+    let data = node.properties;
+    let mut values = Vec::new();
+    let mut offset = 0;
+    while values.len() < node.property_count {
+        let Some(&kind) = data.get(offset) else {
+            break;
+        };
+        offset += 1;
+        let read = |len: usize| data.get(offset..offset + len);
+        let (value, len) = match kind {
+            b'C' => (read(1).map(|b| Value::Integer(b[0] as i64)), 1),
+            b'Y' => (read(2).map(|b| Value::Integer(i16::from_le_bytes([b[0], b[1]]) as i64)), 2),
+            b'I' => (read_u32(data, offset).map(|v| Value::Integer(v as i32 as i64)), 4),
+            b'L' => (read_u64(data, offset).map(|v| Value::Integer(v as i64)), 8),
+            b'F' => (read_u32(data, offset).map(|v| Value::Number(f32::from_bits(v) as f64)), 4),
+            b'D' => (read_u64(data, offset).map(|v| Value::Number(f64::from_bits(v))), 8),
+            b'S' | b'R' => {
+                let Some(len) = read_u32(data, offset).map(|l| l as usize) else {
+                    break;
+                };
+                let bytes = data.get(offset + 4..offset + 4 + len);
+                let value = if kind == b'S' { bytes.map(Value::Text) } else { bytes.map(|_| Value::Other) };
+                (value, 4 + len)
+            }
+            b'f' | b'd' | b'i' | b'l' | b'b' => {
+                let (Some(count), Some(encoding), Some(stored)) = (
+                    read_u32(data, offset),
+                    read_u32(data, offset + 4),
+                    read_u32(data, offset + 8),
+                ) else {
+                    break;
+                };
+                let value = data
+                    .get(offset + 12..offset + 12 + stored as usize)
+                    .and_then(|bytes| array(kind, count as usize, encoding, bytes));
+                (value, 12 + stored as usize)
+            }
+            _ => break,
+        };
+        let Some(value) = value else {
+            break;
+        };
+        values.push(value);
+        offset += len;
+    }
+    values
+}
+
+/// An array property's elements, inflating it when `encoding` is 1
+fn array<'a>(kind: u8, count: usize, encoding: u32, stored: &[u8]) -> Option<Value<'a>> {
+    let size = match kind {
+        b'b' => 1,
+        b'f' | b'i' => 4,
+        _ => 8,
+    };
+    let len = count.checked_mul(size)?;
+    let inflated;
+    let bytes = match encoding {
+        0 => stored.get(..len)?,
+        1 => {
+            let mut buffer = Vec::new();
+            ZlibDecoder::new(stored).take(len as u64).read_to_end(&mut buffer).ok()?;
+            inflated = buffer;
+            inflated.get(..len)?
+        }
+        _ => return None,
+    };
+
+    let elements = bytes.chunks_exact(size);
+    Some(match kind {
+        b'f' => Value::Numbers(elements.map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64).collect()),
+        b'd' => Value::Numbers(elements.map(|c| f64::from_le_bytes(c.try_into().unwrap())).collect()),
+        b'i' => Value::Integers(elements.map(|c| i32::from_le_bytes(c.try_into().unwrap()) as i64).collect()),
+        b'l' => Value::Integers(elements.map(|c| i64::from_le_bytes(c.try_into().unwrap())).collect()),
+        _ => Value::Integers(bytes.iter().map(|&b| b as i64).collect()),
+    })
+}
+
+/// Geometry stats of a binary FBX, or `None` when `data` isn't one
+pub fn parse_binary(data: &[u8]) -> Option<ModelInfo> {
+    if !data.starts_with(MAGIC) {
+        return None;
+    }
+    let version = read_u32(data, 23)?;
+    let reader = Reader {
+        data,
+        wide: version >= WIDE_VERSION,
+    };
+    let top = reader.nodes(HEADER_LEN, data.len());
+    let objects = top.iter().find(|n| n.name == b"Objects")?;
+
+    let mut vertex_count = 0u64;
+    let mut triangle_count = 0u64;
+    let mut submesh_count = 0u32;
+    let mut has_normals = false;
+    let mut has_uvs = false;
+    // Position bounds of each mesh by object id, in its own space
+    let mut mesh_bounds: HashMap<i64, Option<[f64; 6]>> = HashMap::new();
+    // Object id -> (local transform, geometric transform) of each model
+    let mut models: HashMap<i64, ([f64; 16], [f64; 16])> = HashMap::new();
+    for object in reader.children(objects) {
+        let values = properties(&object);
+        let Some(id) = values.first().and_then(Value::as_i64) else {
+            continue;
+        };
+        match object.name {
+            // Blend shapes and curves are geometry too
+            b"Geometry" if values.get(2).and_then(Value::as_text) == Some(b"Mesh") => {
+                let mut bounds = None;
+                let mut materials = 1;
+                for child in reader.children(&object) {
+                    match child.name {
+                        b"Vertices" => {
+                            if let Some(Value::Numbers(positions)) = properties(&child).first() {
+                                vertex_count += positions.len() as u64 / 3;
+                                bounds = positions_bounds(positions);
+                            }
+                        }
+                        b"PolygonVertexIndex" => {
+                            if let Some(Value::Integers(indices)) = properties(&child).first() {
+                                triangle_count += polygon_triangles(indices);
+                            }
+                        }
+                        b"LayerElementNormal" => has_normals = true,
+                        b"LayerElementUV" => has_uvs = true,
+                        b"LayerElementMaterial" => {
+                            materials = material_count(&reader, &child).max(materials);
+                        }
+                        _ => {}
+                    }
+                }
+                submesh_count += materials;
+                mesh_bounds.insert(id, bounds);
+            }
+            b"Model" => {
+                models.insert(id, model_transforms(&reader, &object));
+            }
+            _ => {}
+        }
+    }
+
+    // Child object id -> parent object ids; 0 is the scene root
+    let mut parents: HashMap<i64, Vec<i64>> = HashMap::new();
+    if let Some(connections) = top.iter().find(|n| n.name == b"Connections") {
+        for connection in reader.children(connections) {
+            let values = properties(&connection);
+            if values.first().and_then(Value::as_text) != Some(b"OO") {
+                continue;
+            }
+            if let (Some(child), Some(parent)) = (values.get(1).and_then(Value::as_i64), values.get(2).and_then(Value::as_i64)) {
+                parents.entry(child).or_default().push(parent);
+            }
+        }
+    }
+
+    let unit = unit_scale_factor(&reader, &top) * CENTIMETRE;
+    let scene = [unit, 0.0, 0.0, 0.0, 0.0, unit, 0.0, 0.0, 0.0, 0.0, unit, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let mut bounds = None;
+    for (id, local) in &mesh_bounds {
+        let Some(local) = local else {
+            continue;
+        };
+        let placements: Vec<[f64; 16]> = parents
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|parent| {
+                let (_, geometric) = models.get(parent)?;
+                Some(multiply(&world_transform(*parent, &models, &parents), geometric))
+            })
+            .collect();
+        // Geometry no model uses still has a size
+        let placements = if placements.is_empty() { vec![IDENTITY_MATRIX] } else { placements };
+        for placement in placements {
+            bounds = Some(union_bounds(bounds, transform_bounds(&multiply(&scene, &placement), local)));
+        }
+    }
+
+    Some(ModelInfo {
+        vertex_count: Some(vertex_count),
+        triangle_count: Some(triangle_count),
+        submesh_count: Some(submesh_count),
+        has_normals,
+        has_uvs,
+        bounds: bounds.map(|b| b.map(|v| v as f32)),
+    })
+}
+
+fn positions_bounds(positions: &[f64]) -> Option<[f64; 6]> {
+    positions
+        .chunks_exact(3)
+        .map(|p| [p[0], p[1], p[2], p[0], p[1], p[2]])
+        .reduce(|a, b| union_bounds(Some(a), b))
+}
+
+/// Triangles of the polygons in a `PolygonVertexIndex`, where the last
+/// index of each polygon is stored as `-index - 1`
+fn polygon_triangles(indices: &[i64]) -> u64 {
+    let mut triangles = 0;
+    let mut corners = 0u64;
+    for &index in indices {
+        corners += 1;
+        if index < 0 {
+            triangles += corners.saturating_sub(2);
+            corners = 0;
+        }
+    }
+    triangles
+}
+
+/// Distinct materials a mesh's polygons use, which Unity imports as one
+/// submesh each
+fn material_count(reader: &Reader, layer: &Node) -> u32 {
+    let mapping = reader.child(layer, b"MappingInformationType");
+    if mapping.is_some_and(|m| properties(&m).first().and_then(Value::as_text) == Some(b"AllSame")) {
+        return 1;
+    }
+    match reader.child(layer, b"Materials").map(|m| properties(&m)) {
+        Some(values) => match values.first() {
+            Some(Value::Integers(materials)) => materials.iter().collect::<HashSet<_>>().len().max(1) as u32,
+            _ => 1,
+        },
+        None => 1,
+    }
+}
+
+/// `P` entries of a node's `Properties70`: name -> numeric values
+fn property_values(reader: &Reader, node: &Node) -> HashMap<Vec<u8>, Vec<f64>> {
+    let mut entries = HashMap::new();
+    let Some(list) = reader.child(node, b"Properties70") else {
+        return entries;
+    };
+    for entry in reader.children(&list).iter().filter(|p| p.name == b"P") {
+        let values = properties(entry);
+        if let Some(name) = values.first().and_then(Value::as_text) {
+            entries.insert(name.to_vec(), values.iter().skip(4).filter_map(Value::as_f64).collect());
+        }
+    }
+    entries
+}
+
+fn unit_scale_factor(reader: &Reader, top: &[Node]) -> f64 {
+    top.iter()
+        .find(|n| n.name == b"GlobalSettings")
+        .and_then(|settings| property_values(reader, settings).remove(&b"UnitScaleFactor"[..]))
+        .and_then(|v| v.first().copied())
+        .filter(|f| f.is_finite() && *f > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// A model's transform relative to its parent, translation * pre-rotation *
+/// rotation * scale (pivots and offsets aside), and the geometric transform
+/// applied to its geometry only
+fn model_transforms(reader: &Reader, model: &Node) -> ([f64; 16], [f64; 16]) {
+    let values = property_values(reader, model);
+    let vector = |name: &str, default: f64| -> [f64; 3] {
+        match values.get(name.as_bytes()) {
+            Some(v) if v.len() >= 3 => [v[0], v[1], v[2]],
+            _ => [default; 3],
+        }
+    };
+    let order = values
+        .get(&b"RotationOrder"[..])
+        .and_then(|v| v.first())
+        .map_or(0, |o| *o as i64);
+
+    let local = multiply(
+        &multiply(&translation(vector("Lcl Translation", 0.0)), &rotation(vector("PreRotation", 0.0), 0)),
+        &multiply(&rotation(vector("Lcl Rotation", 0.0), order), &scale(vector("Lcl Scaling", 1.0))),
+    );
+    let geometric = multiply(
+        &multiply(&translation(vector("GeometricTranslation", 0.0)), &rotation(vector("GeometricRotation", 0.0), 0)),
+        &scale(vector("GeometricScaling", 1.0)),
+    );
+    (local, geometric)
+}
+
+/// A model's transform in the scene, following its parent models
+fn world_transform(id: i64, models: &HashMap<i64, ([f64; 16], [f64; 16])>, parents: &HashMap<i64, Vec<i64>>) -> [f64; 16] {
+    let mut transform = IDENTITY_MATRIX;
+    let mut current = id;
+    for _ in 0..MAX_MODEL_DEPTH {
+        let Some((local, _)) = models.get(&current) else {
+            break;
+        };
+        transform = multiply(local, &transform);
+        let parent = parents
+            .get(&current)
+            .and_then(|p| p.iter().find(|parent| models.contains_key(parent)));
+        match parent {
+            Some(parent) => current = *parent,
+            None => break,
+        }
+    }
+    transform
+}
+
+fn translation([x, y, z]: [f64; 3]) -> [f64; 16] {
+    let mut m = IDENTITY_MATRIX;
+    m[12] = x;
+    m[13] = y;
+    m[14] = z;
+    m
+}
+
+fn scale([x, y, z]: [f64; 3]) -> [f64; 16] {
+    let mut m = IDENTITY_MATRIX;
+    m[0] = x;
+    m[5] = y;
+    m[10] = z;
+    m
+}
+
+/// Euler angles in degrees applied in FBX `RotationOrder`: 0 is XYZ (X
+/// first), then XZY, YZX, YXZ, ZXY and ZYX
+fn rotation(degrees: [f64; 3], order: i64) -> [f64; 16] {
+    let [x, y, z] = degrees.map(f64::to_radians);
+    let (sx, cx, sy, cy, sz, cz) = (x.sin(), x.cos(), y.sin(), y.cos(), z.sin(), z.cos());
+    let rx = [1.0, 0.0, 0.0, 0.0, 0.0, cx, sx, 0.0, 0.0, -sx, cx, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let ry = [cy, 0.0, -sy, 0.0, 0.0, 1.0, 0.0, 0.0, sy, 0.0, cy, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let rz = [cz, sz, 0.0, 0.0, -sz, cz, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let (first, second, third) = match order {
+        1 => (rx, rz, ry),
+        2 => (ry, rz, rx),
+        3 => (ry, rx, rz),
+        4 => (rz, rx, ry),
+        5 => (rz, ry, rx),
+        _ => (rx, ry, rz),
+    };
+    multiply(&third, &multiply(&second, &first))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
@@ -0,0 +1,162 @@
+//! Hex/ASCII dumps of any range of a file, with the format its leading
+//! bytes name, for triaging unknown or corrupted assets.
+
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const DEFAULT_LENGTH: usize = 4096;
+pub const MAX_LENGTH: usize = 64 * 1024;
+const BYTES_PER_LINE: usize = 16;
+/// Leading bytes read for signature detection
+const HEADER_BYTES: usize = 512;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexPreview {
+    pub offset: u64,
+    /// `00000010  49 48 44 52 ...  |IHDR....|`, 16 bytes per line
+    pub lines: Vec<String>,
+    pub bytes_read: usize,
+    pub total_bytes: u64,
+    /// Format named by the file's leading bytes
+    pub signature: Option<FileSignature>,
+    /// The signature belongs to other extensions, e.g. a JPEG saved as .png
+    pub extension_mismatch: bool,
+    /// The first bytes are all zero, as in a file truncated or zeroed by a
+    /// failed write or sync
+    pub header_zeroed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSignature {
+    pub format: String,
+    /// Extensions this format is normally saved with; empty when any goes
+    pub extensions: Vec<String>,
+}
+
+/// Leading bytes (at `offset`) and the format they identify
+struct Magic {
+    offset: usize,
+    bytes: &'static [u8],
+    format: &'static str,
+    extensions: &'static [&'static str],
+}
+
+const fn magic(offset: usize, bytes: &'static [u8], format: &'static str, extensions: &'static [&'static str]) -> Magic {
+    Magic { offset, bytes, format, extensions }
+}
+
+/// Checked in order; more specific entries come first
+const SIGNATURES: &[Magic] = &[
+    magic(0, b"\x89PNG\r\n\x1a\n", "PNG image", &["png"]),
+    magic(0, b"\xff\xd8\xff", "JPEG image", &["jpg", "jpeg"]),
+    magic(0, b"GIF87a", "GIF image", &["gif"]),
+    magic(0, b"GIF89a", "GIF image", &["gif"]),
+    magic(0, b"8BPS", "Photoshop document", &["psd", "psb"]),
+    magic(0, b"II*\0", "TIFF image", &["tif", "tiff"]),
+    magic(0, b"MM\0*", "TIFF image", &["tif", "tiff"]),
+    magic(0, b"DDS ", "DirectDraw Surface texture", &["dds"]),
+    magic(0, b"\xabKTX 11\xbb", "KTX texture", &["ktx"]),
+    magic(0, b"\xabKTX 20\xbb", "KTX2 texture", &["ktx2"]),
+    magic(0, b"v/1\x01", "OpenEXR image", &["exr"]),
+    magic(0, b"#?RADIANCE", "Radiance HDR image", &["hdr"]),
+    magic(0, b"#?RGBE", "Radiance HDR image", &["hdr"]),
+    magic(8, b"WEBP", "WebP image", &["webp"]),
+    magic(8, b"WAVE", "WAV audio", &["wav"]),
+    magic(8, b"AVI ", "AVI video", &["avi"]),
+    magic(0, b"BM", "BMP image", &["bmp"]),
+    magic(0, b"OggS", "Ogg audio/video", &["ogg", "oga", "ogv"]),
+    magic(0, b"fLaC", "FLAC audio", &["flac"]),
+    magic(0, b"ID3", "MP3 audio", &["mp3"]),
+    magic(0, b"FORM", "AIFF audio", &["aif", "aiff"]),
+    magic(0, b"MThd", "MIDI", &["mid", "midi"]),
+    magic(0, b"FSB5", "FMOD sound bank", &["fsb", "bank"]),
+    magic(0, b"BKHD", "Wwise sound bank", &["bnk"]),
+    magic(4, b"ftyp", "MP4/QuickTime media", &["mp4", "m4a", "m4v", "mov"]),
+    magic(0, b"\x1a\x45\xdf\xa3", "Matroska/WebM video", &["mkv", "webm"]),
+    magic(0, b"Kaydara FBX Binary", "Binary FBX model", &["fbx"]),
+    magic(0, b"glTF", "Binary glTF model", &["glb"]),
+    magic(0, b"BLENDER", "Blender file", &["blend"]),
+    magic(0, b"UnityFS", "Unity AssetBundle", &[]),
+    magic(0, b"UnityWeb", "Unity web bundle", &[]),
+    magic(0, b"%YAML", "Unity YAML asset", &[]),
+    magic(0, b"PK\x03\x04", "Zip archive", &["zip", "jar", "apk", "aar", "nupkg"]),
+    magic(0, b"\x1f\x8b", "Gzip archive", &["gz", "tgz", "unitypackage"]),
+    magic(0, b"7z\xbc\xaf\x27\x1c", "7-Zip archive", &["7z"]),
+    magic(0, b"%PDF", "PDF document", &["pdf"]),
+    magic(0, b"wOFF", "WOFF font", &["woff"]),
+    magic(0, b"wOF2", "WOFF2 font", &["woff2"]),
+    magic(0, b"OTTO", "OpenType font", &["otf"]),
+    magic(0, b"\0\x01\0\0", "TrueType font", &["ttf", "otf"]),
+    magic(0, b"MZ", "Windows executable or DLL", &["dll", "exe"]),
+    magic(0, b"\x7fELF", "ELF binary", &["so"]),
+    magic(0, b"\xcf\xfa\xed\xfe", "Mach-O binary", &["dylib", "bundle"]),
+    magic(0, b"\xef\xbb\xbf", "UTF-8 text with BOM", &[]),
+];
+
+/// Dump `length` bytes (capped at `MAX_LENGTH`) from `offset` and identify
+/// the file by its header. An offset past the end returns no lines.
+pub fn read_hex_preview(path: &Path, offset: u64, length: usize) -> AppResult<HexPreview> {
+    let mut file = File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+
+    let mut header = Vec::with_capacity(HEADER_BYTES);
+    (&mut file).take(HEADER_BYTES as u64).read_to_end(&mut header)?;
+
+    let mut buf = Vec::with_capacity(length.min(MAX_LENGTH));
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(length.min(MAX_LENGTH) as u64).read_to_end(&mut buf)?;
+
+    let signature = detect_signature(&header);
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension_mismatch = signature
+        .as_ref()
+        .is_some_and(|s| !s.extensions.is_empty() && !s.extensions.contains(&extension));
+
+    Ok(HexPreview {
+        offset,
+        lines: buf
+            .chunks(BYTES_PER_LINE)
+            .enumerate()
+            .map(|(i, chunk)| hex_line(offset + (i * BYTES_PER_LINE) as u64, chunk))
+            .collect(),
+        bytes_read: buf.len(),
+        total_bytes,
+        signature,
+        extension_mismatch,
+        header_zeroed: !header.is_empty() && header.iter().all(|&b| b == 0),
+    })
+}
+
+pub fn detect_signature(header: &[u8]) -> Option<FileSignature> {
+    SIGNATURES
+        .iter()
+        .find(|m| header.get(m.offset..m.offset + m.bytes.len()) == Some(m.bytes))
+        .map(|m| FileSignature {
+            format: m.format.to_string(),
+            extensions: m.extensions.iter().map(|e| e.to_string()).collect(),
+        })
+}
+
+/// `offset  8 hex bytes  8 hex bytes  |ascii|`, padded so short last lines align
+fn hex_line(offset: u64, bytes: &[u8]) -> String {
+    let mut line = format!("{:08x} ", offset);
+    for i in 0..BYTES_PER_LINE {
+        if i % 8 == 0 {
+            line.push(' ');
+        }
+        match bytes.get(i) {
+            Some(b) => line.push_str(&format!("{:02x} ", b)),
+            None => line.push_str("   "),
+        }
+    }
+    line.push('|');
+    line.extend(bytes.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+    line.push('|');
+    line
+}
@@ -0,0 +1,19 @@
+use crate::db::{Asset, Database};
+use crate::error::AppResult;
+use std::sync::Arc;
+
+pub struct Indexer {
+    db: Arc<Database>,
+}
+
+impl Indexer {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// The whole batch is written in one transaction on the writer
+    /// connection, so a scan checkpoint never records a half-written batch
+    pub fn upsert_batch(&self, assets: &[Asset]) -> AppResult<usize> {
+        self.db.upsert_assets(assets)
+    }
+}
@@ -0,0 +1,49 @@
+//! Everything that doesn't need a window: the SQLite catalog, project
+//! scanning, dependency resolution, previews, export, format plugins,
+//! validation rules, version control annotations and the Asset Store cache
+//! library. Used by the Tauri app and the `scythe` CLI.
+
+pub mod asset_files;
+pub mod asset_store;
+pub mod benchmark;
+pub mod blender;
+pub mod boundaries;
+pub mod build_layout;
+pub mod build_report;
+pub mod catalog;
+pub mod code_editor;
+pub mod compare;
+pub mod converters;
+pub mod db;
+pub mod deliveries;
+pub mod deps;
+pub mod duplicates;
+pub mod error;
+pub mod export;
+pub mod fbx;
+pub mod hex_preview;
+pub mod indexer;
+pub mod library;
+pub mod localization;
+pub mod lods;
+pub mod media;
+pub mod plugins;
+pub mod previews;
+pub mod process;
+pub mod provenance;
+pub mod render_pipeline;
+pub mod rules;
+pub mod scanner;
+pub mod scene_costs;
+pub mod search;
+pub mod settings;
+pub mod similar;
+pub mod snapshot;
+pub mod storage;
+pub mod tags;
+pub mod texture_health;
+pub mod texture_size;
+pub mod unity_library;
+pub mod validation;
+pub mod vcs;
+pub mod watcher;
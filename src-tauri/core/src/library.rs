@@ -0,0 +1,115 @@
+//! Libraries: reference folders outside any Unity project, such as a
+//! studio-wide texture archive. They are scanned and searched like projects
+//! but skip the Unity-specific scan phases, and their files are brought into
+//! the active project with `copy_into_project`, which writes a `.meta` for
+//! every copied file that doesn't have one yet and records its provenance.
+
+use crate::db::{Asset, Database, Project};
+use crate::error::{AppError, AppResult};
+use crate::export::{generated_meta, ExportOptions, Exporter};
+use crate::provenance::{self, ProvenanceSource, SOURCE_LIBRARY};
+use crate::scanner::modified_secs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryCopy {
+    /// Folder inside the active project the files went to
+    pub dest_dir: String,
+    /// Paths written, relative to `dest_dir`
+    pub copied_files: Vec<String>,
+    /// Files left alone because of the "skip" conflict policy
+    pub skipped_files: Vec<String>,
+    /// Copied files that got a freshly generated `.meta`
+    pub generated_metas: usize,
+    /// Per-file failures; the other files are still copied
+    pub errors: Vec<String>,
+}
+
+/// Where to copy a library's files in `project_root`: `dest_folder` relative
+/// to the project, or `Assets/<library folder name>` when not given
+pub fn destination(project_root: &Path, library: &Project, dest_folder: Option<&str>) -> AppResult<PathBuf> {
+    let relative = match dest_folder.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(folder) => PathBuf::from(folder),
+        None => Path::new("Assets").join(&library.name),
+    };
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::InvalidArgument(format!(
+            "Destination {} must be a folder inside the project",
+            relative.display()
+        )));
+    }
+    Ok(project_root.join(relative))
+}
+
+/// Copy assets of `library` into `dest_dir` inside `project`, keeping their
+/// folders relative to the library root. The conflict policy and
+/// `include_meta` of `options` apply; files are always copied loose, never
+/// archived. Copies are recorded as deliveries like any other export, and
+/// with their provenance in the project.
+pub fn copy_into_project(
+    db: Arc<Database>,
+    library: &Project,
+    project: &Project,
+    assets: &[Asset],
+    dest_dir: &Path,
+    options: &ExportOptions,
+) -> AppResult<LibraryCopy> {
+    let options = ExportOptions {
+        archive_format: "folder".to_string(),
+        ..options.clone()
+    };
+    let exporter = Exporter::new(Arc::clone(&db));
+    let source = ProvenanceSource {
+        kind: SOURCE_LIBRARY,
+        id: &library.id,
+        name: &library.name,
+    };
+    let mut records = Vec::new();
+
+    let mut copy = LibraryCopy {
+        dest_dir: dest_dir.to_string_lossy().to_string(),
+        copied_files: Vec::new(),
+        skipped_files: Vec::new(),
+        generated_metas: 0,
+        errors: Vec::new(),
+    };
+    for asset in assets {
+        let result = match exporter.export_file(asset, dest_dir, &options) {
+            Ok(result) => result,
+            Err(e) => {
+                copy.errors.push(format!("{}: {}", asset.relative_path, e));
+                continue;
+            }
+        };
+        if let Some(error) = result.error {
+            copy.errors.push(error);
+        }
+        copy.skipped_files.extend(result.skipped_files);
+        let source_modified_time = fs::metadata(&asset.absolute_path)
+            .map(|m| modified_secs(&m))
+            .unwrap_or(asset.modified_time);
+
+        for written in result.exported_files {
+            let dest_file = dest_dir.join(&written);
+            // Unity would make one on import too, but with a GUID nothing
+            // else knows about until then
+            let meta_path = PathBuf::from(format!("{}.meta", dest_file.display()));
+            if !meta_path.exists() {
+                let (_, meta) = generated_meta();
+                fs::write(&meta_path, meta)?;
+                copy.generated_metas += 1;
+            }
+            match provenance::imported(project, &dest_file, &source, &asset.relative_path, source_modified_time) {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("No provenance for {}: {}", written, e),
+            }
+            copy.copied_files.push(written);
+        }
+    }
+    provenance::record(&db, &records);
+    Ok(copy)
+}
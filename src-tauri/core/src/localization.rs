@@ -0,0 +1,370 @@
+//! Index of Unity Localization tables. A collection is one `SharedTableData`
+//! asset holding the keys, plus a string or asset table per locale holding
+//! each key's translation or the GUID of the localized asset variant.
+//!
+//! Entries are stored for every key in every locale of its collection, so a
+//! key search lists each locale's value or asset and shows the locales that
+//! have nothing for it.
+
+use crate::build_layout::AssetMatcher;
+use crate::db::{Asset, Database, LocalizationEntry, LocalizationTable};
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Localization fields come right after `m_Name`, well within this
+const SNIFF_BYTES: u64 = 4096;
+const COLLECTION_NAME_FIELD: &str = "m_TableCollectionName:";
+const LOCALE_FIELD: &str = "m_LocaleId:";
+const SHARED_DATA_FIELD: &str = "m_SharedData:";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalizationStats {
+    pub collections: usize,
+    pub tables: usize,
+    pub keys: usize,
+    /// Key and locale pairs with a value
+    pub entries: usize,
+    /// Key and locale pairs without one
+    pub missing: usize,
+    /// False when cancelled; the previous index is kept
+    pub completed: bool,
+}
+
+/// A key with its entry in every locale of the collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizedKey {
+    pub collection: String,
+    pub key_id: i64,
+    pub key: String,
+    /// "string" or "asset"
+    pub kind: String,
+    /// Ordered by locale; missing ones included
+    pub locales: Vec<LocalizationEntry>,
+    pub missing_locales: Vec<String>,
+}
+
+/// Parse every Localization table among the project's `.asset` files and
+/// replace the stored index. `progress(done, total)` returns false to cancel.
+pub fn index_project(
+    db: &Database,
+    project_id: &str,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<LocalizationStats> {
+    let candidates = db.get_assets_by_types(project_id, &["scriptable_object".to_string()])?;
+    let mut stats = LocalizationStats::default();
+
+    // Keyed by the shared data's GUID, which the locale tables reference
+    let mut shared: HashMap<String, SharedTableData> = HashMap::new();
+    let mut locale_tables: Vec<(Asset, LocaleTable)> = Vec::new();
+    for (i, asset) in candidates.iter().enumerate() {
+        if !progress(i, candidates.len()) {
+            return Ok(stats);
+        }
+
+        let path = Path::new(&asset.absolute_path);
+        let Some(kind) = sniff(path) else {
+            continue;
+        };
+        let Ok(text) = fs::read_to_string(path) else {
+            continue;
+        };
+        match kind {
+            TableKind::Shared => {
+                if let (Some(guid), Some(data)) = (&asset.unity_guid, SharedTableData::parse(&text)) {
+                    shared.insert(guid.clone(), data);
+                }
+            }
+            TableKind::Locale => {
+                if let Some(table) = LocaleTable::parse(&text) {
+                    locale_tables.push((asset.clone(), table));
+                }
+            }
+        }
+    }
+
+    let matcher = AssetMatcher::load(db, project_id)?;
+    let mut tables = Vec::new();
+    let mut entries = Vec::new();
+    // Collection GUID -> tables, with locales in a stable order
+    let mut by_collection: BTreeMap<&str, Vec<&(Asset, LocaleTable)>> = BTreeMap::new();
+    for table in &locale_tables {
+        match shared.get(&table.1.shared_guid) {
+            Some(_) => by_collection.entry(&table.1.shared_guid).or_default().push(table),
+            None => tracing::warn!("Localization table {} has no shared table data", table.0.relative_path),
+        }
+    }
+
+    for (shared_guid, mut collection_tables) in by_collection {
+        let data = &shared[shared_guid];
+        collection_tables.sort_by(|a, b| a.1.locale.cmp(&b.1.locale));
+        // A collection is an asset collection if any of its tables holds GUIDs
+        let kind = if collection_tables.iter().any(|(_, t)| t.holds_assets()) {
+            "asset"
+        } else {
+            "string"
+        };
+
+        for (asset, table) in &collection_tables {
+            let mut entry_count = 0;
+            for (key_id, key) in &data.keys {
+                let localized = table.values.get(key_id).filter(|v| !v.is_empty());
+                let mut entry = LocalizationEntry {
+                    collection: data.name.clone(),
+                    key_id: *key_id,
+                    key: key.clone(),
+                    locale: table.locale.clone(),
+                    kind: kind.to_string(),
+                    value: None,
+                    guid: None,
+                    asset_id: None,
+                    relative_path: None,
+                };
+                match localized {
+                    Some(value) if kind == "asset" => {
+                        // Sub-assets are stored as "guid[name]"
+                        let guid = value.split('[').next().unwrap_or(value).to_string();
+                        entry.asset_id = matcher.find(Some(&guid), "");
+                        entry.guid = Some(guid);
+                    }
+                    Some(value) => entry.value = Some(value.clone()),
+                    None => {}
+                }
+                if entry.is_missing() {
+                    stats.missing += 1;
+                } else {
+                    entry_count += 1;
+                }
+                entries.push(entry);
+            }
+
+            stats.entries += entry_count;
+            tables.push(LocalizationTable {
+                asset_id: asset.id.clone(),
+                collection: data.name.clone(),
+                locale: table.locale.clone(),
+                kind: kind.to_string(),
+                entry_count: entry_count as i64,
+            });
+        }
+
+        stats.collections += 1;
+        stats.keys += data.keys.len();
+    }
+
+    stats.tables = tables.len();
+    db.replace_localization(project_id, &tables, &entries)?;
+    stats.completed = true;
+    Ok(stats)
+}
+
+/// Keys whose name contains `query`, with every locale's entry
+pub fn search(db: &Database, project_id: &str, query: &str, missing_only: bool, limit: i64) -> AppResult<Vec<LocalizedKey>> {
+    Ok(group_keys(db.search_localization_entries(project_id, query, missing_only, limit)?))
+}
+
+/// The keys `asset_id` is a localized variant of, with their other variants
+pub fn asset_keys(db: &Database, asset_id: &str) -> AppResult<Vec<LocalizedKey>> {
+    Ok(group_keys(db.get_asset_localization_entries(asset_id)?))
+}
+
+/// Entries arrive ordered by collection and key
+fn group_keys(entries: Vec<LocalizationEntry>) -> Vec<LocalizedKey> {
+    let mut keys: Vec<LocalizedKey> = Vec::new();
+    for entry in entries {
+        let same_key = keys
+            .last()
+            .is_some_and(|k| k.collection == entry.collection && k.key_id == entry.key_id);
+        if !same_key {
+            keys.push(LocalizedKey {
+                collection: entry.collection.clone(),
+                key_id: entry.key_id,
+                key: entry.key.clone(),
+                kind: entry.kind.clone(),
+                locales: Vec::new(),
+                missing_locales: Vec::new(),
+            });
+        }
+        let key = keys.last_mut().expect("pushed above");
+        if entry.is_missing() {
+            key.missing_locales.push(entry.locale.clone());
+        }
+        key.locales.push(entry);
+    }
+    keys
+}
+
+enum TableKind {
+    Shared,
+    Locale,
+}
+
+/// Tell Localization tables from other ScriptableObjects by their first fields
+fn sniff(path: &Path) -> Option<TableKind> {
+    let mut head = Vec::new();
+    File::open(path).ok()?.take(SNIFF_BYTES).read_to_end(&mut head).ok()?;
+    let head = String::from_utf8_lossy(&head);
+    if head.contains(COLLECTION_NAME_FIELD) {
+        Some(TableKind::Shared)
+    } else if head.contains(LOCALE_FIELD) && head.contains(SHARED_DATA_FIELD) {
+        Some(TableKind::Locale)
+    } else {
+        None
+    }
+}
+
+struct SharedTableData {
+    name: String,
+    /// (key id, key name) in the order the collection lists them
+    keys: Vec<(i64, String)>,
+}
+
+impl SharedTableData {
+    fn parse(text: &str) -> Option<Self> {
+        let name = text
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(COLLECTION_NAME_FIELD))
+            .map(|name| unquote(name.trim()))?;
+        Some(Self {
+            name,
+            keys: parse_list(text, "m_Entries", "m_Key"),
+        })
+    }
+}
+
+struct LocaleTable {
+    locale: String,
+    shared_guid: String,
+    values: HashMap<i64, String>,
+}
+
+impl LocaleTable {
+    fn parse(text: &str) -> Option<Self> {
+        let lines: Vec<&str> = text.lines().collect();
+        // m_LocaleId:\n    m_Code: en
+        let locale_line = lines.iter().position(|line| line.trim() == LOCALE_FIELD)?;
+        let locale = lines[locale_line + 1..]
+            .iter()
+            .find_map(|line| line.trim().strip_prefix("m_Code:"))
+            .map(|code| unquote(code.trim()))?;
+        // m_SharedData: {fileID: 11400000, guid: 0123abcd..., type: 2}
+        let shared_guid = lines
+            .iter()
+            .find_map(|line| line.trim().strip_prefix(SHARED_DATA_FIELD))
+            .and_then(|link| link.split("guid:").nth(1))
+            .and_then(|rest| rest.split([',', '}']).next())
+            .map(|guid| guid.trim().to_string())
+            .filter(|guid| !guid.is_empty())?;
+
+        Some(Self {
+            locale,
+            shared_guid,
+            values: parse_list(text, "m_TableData", "m_Localized").into_iter().collect(),
+        })
+    }
+
+    /// Asset tables store the localized asset's GUID, optionally followed by
+    /// a sub-asset name in brackets
+    fn holds_assets(&self) -> bool {
+        let mut values = self.values.values().filter(|v| !v.is_empty()).peekable();
+        values.peek().is_some() && values.all(|v| is_guid(v.split('[').next().unwrap_or(v)))
+    }
+}
+
+fn is_guid(value: &str) -> bool {
+    value.len() == 32 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// `(m_Id, field)` pairs from a top-level list of maps, as Unity writes them:
+///
+/// ```text
+///   m_TableData:
+///   - m_Id: 1234
+///     m_Localized: Hello
+///     m_Metadata:
+/// ```
+///
+/// Values may continue on more-indented lines.
+fn parse_list(text: &str, list: &str, field: &str) -> Vec<(i64, String)> {
+    let header = format!("  {}:", list);
+    let field_prefix = format!("    {}:", field);
+    let mut lines = text.lines().skip_while(|line| line.trim_end() != header).skip(1).peekable();
+
+    let mut items = Vec::new();
+    let mut id: Option<i64> = None;
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("  - m_Id:") {
+            id = rest.trim().parse().ok();
+        } else if let Some(rest) = line.strip_prefix(&field_prefix) {
+            let mut parts = vec![rest.trim().to_string()];
+            while let Some(next) = lines.peek() {
+                if !next.starts_with("      ") && !next.trim().is_empty() {
+                    break;
+                }
+                parts.push(next.trim().to_string());
+                lines.next();
+            }
+            if let Some(id) = id.take() {
+                items.push((id, unquote(&fold(&parts))));
+            }
+        } else if !line.starts_with("    ") && !line.starts_with("  - ") {
+            // Back at a top-level field
+            break;
+        }
+    }
+    items
+}
+
+/// Join a multi-line YAML scalar: line breaks become spaces, blank lines
+/// become newlines
+fn fold(parts: &[String]) -> String {
+    let mut out = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            out.push('\n');
+        } else {
+            if i > 0 && !out.ends_with('\n') {
+                out.push(' ');
+            }
+            out.push_str(part);
+        }
+    }
+    out
+}
+
+/// Strip YAML single or double quotes, resolving their escapes
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.replace("''", "'");
+    }
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            // An escaped line break joins the lines without a space
+            Some(' ') => {}
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                out.extend(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32));
+            }
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,318 @@
+//! LOD coverage and triangle budgets for models. LODs are recognised three
+//! ways: separate files named `Rock_LOD0.fbx`, `Rock_LOD1.fbx`...; meshes
+//! named `_LODn` inside one model, which Unity turns into a LODGroup on
+//! import; and prefabs with a LODGroup component, whose referenced models
+//! count as covered.
+//!
+//! Geometry stats come from `parse_model_info` and are kept in
+//! `model_stats` per file version, so each model is read once.
+
+use crate::db::{Asset, Database};
+use crate::error::{AppError, AppResult};
+use crate::previews::parse_model_info;
+use crate::scanner::validate_ignore_pattern;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// Default for `Settings::lod_min_triangles`
+pub const DEFAULT_LOD_MIN_TRIANGLES: u64 = 5_000;
+/// Stored with each `model_stats` row; bump when the model parsers change
+/// so existing counts are read again
+pub const MODEL_STATS_VERSION: i64 = 3;
+
+static LOD_SUFFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^(.+)_LOD(\d{1,2})$").unwrap());
+static EMBEDDED_LOD: LazyLock<regex::bytes::Regex> =
+    LazyLock::new(|| regex::bytes::Regex::new(r"(?i)[A-Za-z0-9]_LOD(\d{1,2})\b").unwrap());
+
+/// Triangle limit for models under a folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriangleBudget {
+    /// Shown in the report, e.g. "Characters"
+    pub category: String,
+    /// Folder or file prefix relative to the project root, like an ignore pattern
+    pub path_prefix: String,
+    pub max_triangles: u64,
+}
+
+impl TriangleBudget {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.category.trim().is_empty() {
+            return Err(AppError::InvalidArgument("Triangle budget has no category".to_string()));
+        }
+        validate_ignore_pattern(&self.path_prefix)
+            .map_err(|e| AppError::InvalidArgument(format!("Triangle budget {}: {}", self.category, e)))?;
+        if self.max_triangles == 0 {
+            return Err(AppError::InvalidArgument(format!(
+                "Triangle budget {} must allow at least one triangle",
+                self.category
+            )));
+        }
+        Ok(())
+    }
+
+    fn matches(&self, relative_path: &str) -> bool {
+        let prefix = self.path_prefix.trim().replace('\\', "/");
+        relative_path.replace('\\', "/").starts_with(prefix.trim_end_matches('/'))
+    }
+}
+
+/// Geometry of one model file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelStats {
+    pub vertex_count: Option<u64>,
+    /// Every mesh in the file, so all LOD levels of an embedded group together
+    pub triangle_count: Option<u64>,
+    /// Distinct `_LODn` levels named inside the file, when there are two or more
+    pub embedded_lods: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LodModel {
+    pub asset_id: String,
+    pub relative_path: String,
+    /// None for models the parsers can't count
+    pub triangle_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LodGroup {
+    /// File name without the `_LODn` suffix, the model or the prefab
+    pub name: String,
+    /// "suffix" (one file per level), "embedded" (levels named inside one
+    /// model) or "lod_group" (a prefab's LODGroup component)
+    pub source: String,
+    /// Level numbers; a LODGroup's are its configured levels
+    pub levels: Vec<u32>,
+    /// Suffix groups: one model per level, in level order. Otherwise the
+    /// model itself, or the models the prefab references.
+    pub models: Vec<LodModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetOverrun {
+    #[serde(flatten)]
+    pub model: LodModel,
+    pub category: String,
+    pub max_triangles: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LodReport {
+    pub models: usize,
+    /// Models whose triangles the parsers couldn't count; they are left out
+    /// of the missing-LOD and budget checks
+    pub uncounted: usize,
+    pub groups: Vec<LodGroup>,
+    /// Models over `min_triangles` in no LOD group, most triangles first
+    pub missing_lods: Vec<LodModel>,
+    pub missing_lods_count: usize,
+    /// Models over their folder's budget, furthest over first
+    pub over_budget: Vec<BudgetOverrun>,
+    pub over_budget_count: usize,
+}
+
+/// Build the report. `threads` read models not read since they last
+/// changed; the two model lists are capped at `limit`.
+pub fn lod_report(
+    db: &Database,
+    project_id: &str,
+    budgets: &[TriangleBudget],
+    min_triangles: u64,
+    threads: usize,
+    limit: usize,
+) -> AppResult<LodReport> {
+    let models = project_model_stats(db, project_id, threads)?;
+    let entry = |asset: &Asset, stats: &ModelStats| LodModel {
+        asset_id: asset.id.clone(),
+        relative_path: asset.relative_path.clone(),
+        triangle_count: stats.triangle_count,
+    };
+
+    let mut groups = Vec::new();
+    let mut grouped: HashSet<String> = HashSet::new();
+
+    // Separate files per level, grouped by folder and base name
+    let mut by_base: BTreeMap<String, Vec<(u32, usize)>> = BTreeMap::new();
+    for (i, (asset, _)) in models.iter().enumerate() {
+        let stem = Path::new(&asset.file_name).file_stem().unwrap_or_default().to_string_lossy();
+        if let Some(caps) = LOD_SUFFIX.captures(&stem) {
+            let folder = asset.relative_path.rsplit_once(['/', '\\']).map(|(folder, _)| folder).unwrap_or("");
+            let key = format!("{}/{}", folder, caps[1].to_lowercase());
+            by_base.entry(key).or_default().push((caps[2].parse().unwrap_or(0), i));
+        }
+    }
+    for mut levels in by_base.into_values().filter(|levels| levels.len() > 1) {
+        levels.sort();
+        let (_, first) = levels[0];
+        let stem = Path::new(&models[first].0.file_name).file_stem().unwrap_or_default().to_string_lossy();
+        groups.push(LodGroup {
+            name: LOD_SUFFIX.captures(&stem).map(|caps| caps[1].to_string()).unwrap_or_default(),
+            source: "suffix".to_string(),
+            levels: levels.iter().map(|(level, _)| *level).collect(),
+            models: levels.iter().map(|&(_, i)| entry(&models[i].0, &models[i].1)).collect(),
+        });
+        grouped.extend(levels.iter().map(|&(_, i)| models[i].0.id.clone()));
+    }
+
+    for (asset, stats) in &models {
+        if !stats.embedded_lods.is_empty() {
+            groups.push(LodGroup {
+                name: asset.file_name.clone(),
+                source: "embedded".to_string(),
+                levels: stats.embedded_lods.clone(),
+                models: vec![entry(asset, stats)],
+            });
+            grouped.insert(asset.id.clone());
+        }
+    }
+
+    // Prefabs with a LODGroup cover the models they reference
+    let by_id: HashMap<&str, (&Asset, &ModelStats)> =
+        models.iter().map(|(asset, stats)| (asset.id.as_str(), (asset, stats))).collect();
+    let mut references: HashMap<String, Vec<String>> = HashMap::new();
+    for link in db.get_dependency_links(project_id)? {
+        if let Some(to) = link.to_asset_id.filter(|to| by_id.contains_key(to.as_str())) {
+            references.entry(link.from_asset_id).or_default().push(to);
+        }
+    }
+    for prefab in db.get_assets_by_types(project_id, &["prefab".to_string()])? {
+        let Some(referenced) = references.get(&prefab.id) else {
+            continue;
+        };
+        let Some(levels) = lod_group_levels(Path::new(&prefab.absolute_path)) else {
+            continue;
+        };
+        let referenced: BTreeSet<&String> = referenced.iter().collect();
+        groups.push(LodGroup {
+            name: prefab.file_name.clone(),
+            source: "lod_group".to_string(),
+            levels: (0..levels).collect(),
+            models: referenced
+                .iter()
+                .filter_map(|id| by_id.get(id.as_str()))
+                .map(|(asset, stats)| entry(asset, stats))
+                .collect(),
+        });
+        grouped.extend(referenced.into_iter().cloned());
+    }
+
+    let mut missing_lods: Vec<LodModel> = models
+        .iter()
+        .filter(|(asset, stats)| {
+            !grouped.contains(&asset.id) && stats.triangle_count.is_some_and(|count| count >= min_triangles)
+        })
+        .map(|(asset, stats)| entry(asset, stats))
+        .collect();
+    missing_lods.sort_by(|a, b| b.triangle_count.cmp(&a.triangle_count).then_with(|| a.relative_path.cmp(&b.relative_path)));
+
+    let mut over_budget: Vec<BudgetOverrun> = models
+        .iter()
+        .filter_map(|(asset, stats)| {
+            let budget = budgets.iter().find(|budget| budget.matches(&asset.relative_path))?;
+            stats.triangle_count.filter(|count| *count > budget.max_triangles)?;
+            Some(BudgetOverrun {
+                model: entry(asset, stats),
+                category: budget.category.clone(),
+                max_triangles: budget.max_triangles,
+            })
+        })
+        .collect();
+    let overrun = |o: &BudgetOverrun| o.model.triangle_count.unwrap_or(0) as f64 / o.max_triangles as f64;
+    over_budget.sort_by(|a, b| overrun(b).total_cmp(&overrun(a)).then_with(|| a.model.relative_path.cmp(&b.model.relative_path)));
+
+    let (missing_lods_count, over_budget_count) = (missing_lods.len(), over_budget.len());
+    missing_lods.truncate(limit);
+    over_budget.truncate(limit);
+    Ok(LodReport {
+        models: models.len(),
+        uncounted: models.iter().filter(|(_, stats)| stats.triangle_count.is_none()).count(),
+        groups,
+        missing_lods,
+        missing_lods_count,
+        over_budget,
+        over_budget_count,
+    })
+}
+
+/// Read a model's geometry and any `_LODn` mesh names inside it
+pub fn read_model_stats(path: &Path) -> ModelStats {
+    let info = parse_model_info(path);
+    // The file's own name (e.g. Rock_LOD0.fbx) is often stored inside it, so
+    // a single level says nothing
+    let embedded_lods: BTreeSet<u32> = std::fs::read(path)
+        .map(|data| {
+            EMBEDDED_LOD
+                .captures_iter(&data)
+                .filter_map(|caps| std::str::from_utf8(&caps[1]).ok()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ModelStats {
+        vertex_count: info.as_ref().and_then(|info| info.vertex_count),
+        triangle_count: info.as_ref().and_then(|info| info.triangle_count),
+        embedded_lods: if embedded_lods.len() > 1 { embedded_lods.into_iter().collect() } else { Vec::new() },
+    }
+}
+
+/// LOD levels of the first LODGroup in a prefab, None when it has none
+fn lod_group_levels(path: &Path) -> Option<u32> {
+    let data = std::fs::read(path).ok()?;
+    let start = memchr::memmem::find(&data, b"\nLODGroup:")?;
+    // Components are separated by `--- !u!` document headers
+    let end = memchr::memmem::find(&data[start..], b"\n--- ").map_or(data.len(), |end| start + end);
+    Some(memchr::memmem::find_iter(&data[start..end], b"screenRelativeHeight:").count() as u32)
+}
+
+/// Stats for every model in the project, reading those missing or made
+/// from an older version of the file
+fn project_model_stats(db: &Database, project_id: &str, threads: usize) -> AppResult<Vec<(Asset, ModelStats)>> {
+    let stored = db.get_model_stats(project_id)?;
+    let mut models = Vec::new();
+    let mut stale = Vec::new();
+    for asset in db.get_assets_by_types(project_id, &["model".to_string()])? {
+        match stored.get(&asset.id) {
+            Some((modified_time, size_bytes, stats))
+                if *modified_time == asset.modified_time && *size_bytes == asset.size_bytes =>
+            {
+                models.push((asset, stats.clone()))
+            }
+            _ => stale.push(asset),
+        }
+    }
+    if stale.is_empty() {
+        return Ok(models);
+    }
+
+    let next = AtomicUsize::new(0);
+    let read = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, stale.len()) {
+            scope.spawn(|| {
+                while let Some(asset) = stale.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let stats = read_model_stats(Path::new(&asset.absolute_path));
+                    read.lock().unwrap().push((asset.clone(), stats));
+                }
+            });
+        }
+    });
+    let read = read.into_inner().unwrap();
+
+    let rows: Vec<(String, i64, i64, ModelStats)> = read
+        .iter()
+        .map(|(asset, stats)| (asset.id.clone(), asset.modified_time, asset.size_bytes, stats.clone()))
+        .collect();
+    // A shared catalog is read-only; the stats are just not kept
+    if let Err(e) = db.save_model_stats(&rows) {
+        tracing::debug!("Model stats not stored: {}", e);
+    }
+    tracing::info!("Read geometry of {} models", rows.len());
+
+    models.extend(read);
+    models.sort_by(|a, b| a.0.relative_path.cmp(&b.0.relative_path));
+    Ok(models)
+}
@@ -0,0 +1,342 @@
+//! Audio and video metadata read by an optional ffprobe sidecar. Results
+//! are cached in `media_info` per file version, so each file is probed once.
+//!
+//! When an ffmpeg sits next to ffprobe, audio is also decoded once per file
+//! version for peak, loudness, clipping and silence (`audio_levels`), which
+//! the audio QA report is built from.
+
+use crate::db::{AudioLevelsEntry, Database};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Asset types ffprobe is run on
+pub const MEDIA_TYPES: &[&str] = &["audio", "video"];
+/// Level below which audio counts as silence
+const SILENCE_DB: f64 = -60.0;
+/// Shortest quiet stretch counted as silence
+const MIN_SILENCE_SECS: f64 = 0.1;
+/// Peaks at or above this are at full scale
+const FULL_SCALE_DB: f64 = -0.01;
+/// ebur128 reports this floor for clips with nothing above its gate
+const LOUDNESS_FLOOR_LUFS: f64 = -70.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaInfo {
+    pub duration_secs: Option<f64>,
+    /// Container, e.g. "wav", "ogg" or "mov,mp4,m4a,3gp,3g2,mj2"
+    pub format: Option<String>,
+    /// Codec of the first audio stream, or the video stream for video files
+    pub codec: Option<String>,
+    pub bit_rate: Option<i64>,
+    pub sample_rate: Option<i64>,
+    pub channels: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub frame_rate: Option<f64>,
+}
+
+/// Run `ffprobe` on `path` and collect what it reports about the container
+/// and its first audio and video streams
+pub fn probe(ffprobe: &Path, path: &Path) -> AppResult<MediaInfo> {
+    let output = command(ffprobe)
+        .args(["-v", "error", "-print_format", "json", "-show_format", "-show_streams"])
+        .arg(path)
+        .output()
+        .map_err(|e| AppError::Custom(format!("Failed to run ffprobe ({}): {}", ffprobe.display(), e)))?;
+    if !output.status.success() {
+        return Err(AppError::Custom(format!(
+            "ffprobe failed on {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    Ok(parse_report(&report))
+}
+
+fn parse_report(report: &Value) -> MediaInfo {
+    let format = &report["format"];
+    let streams = report["streams"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let audio = streams.iter().find(|s| s["codec_type"] == "audio");
+    // Cover art in audio files shows up as a one-frame video stream
+    let video = streams
+        .iter()
+        .find(|s| s["codec_type"] == "video" && s["disposition"]["attached_pic"] != 1);
+
+    MediaInfo {
+        duration_secs: number(&format["duration"]).or_else(|| audio.and_then(|s| number(&s["duration"]))),
+        format: format["format_name"].as_str().map(str::to_string),
+        codec: video
+            .or(audio)
+            .and_then(|s| s["codec_name"].as_str())
+            .map(str::to_string),
+        bit_rate: number(&format["bit_rate"]).map(|b| b as i64),
+        sample_rate: audio.and_then(|s| number(&s["sample_rate"])).map(|r| r as i64),
+        channels: audio.and_then(|s| s["channels"].as_i64()),
+        width: video.and_then(|s| s["width"].as_i64()),
+        height: video.and_then(|s| s["height"].as_i64()),
+        frame_rate: video.and_then(|s| s["avg_frame_rate"].as_str()).and_then(parse_rate),
+    }
+}
+
+/// ffprobe reports most numbers as strings
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// "30000/1001" -> 29.97; "0/0" when unknown
+fn parse_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+    (den != 0.0 && num != 0.0).then(|| num / den)
+}
+
+fn command(program: &Path) -> Command {
+    #[cfg_attr(not(windows), allow(unused_mut))]
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console for every file
+        command.creation_flags(0x0800_0000);
+    }
+    command
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioLevels {
+    /// Sample peak in dBFS; None for digital silence
+    pub peak_db: Option<f64>,
+    pub rms_db: Option<f64>,
+    /// Integrated loudness (EBU R128); None when too quiet to measure
+    pub loudness_lufs: Option<f64>,
+    /// Samples at full scale, when the peak reaches it
+    pub clipped_samples: i64,
+    /// All stretches below -60 dBFS, including those at either end
+    pub silence_secs: f64,
+    pub leading_silence_secs: f64,
+    pub trailing_silence_secs: f64,
+}
+
+/// The ffmpeg next to an ffprobe executable, e.g. `ffmpeg.exe` beside
+/// `ffprobe.exe`. A bare `ffprobe` (found on PATH) gives a bare `ffmpeg`.
+pub fn ffmpeg_beside(ffprobe: &Path) -> Option<PathBuf> {
+    let name = ffprobe.file_name()?.to_string_lossy().replacen("ffprobe", "ffmpeg", 1);
+    let ffmpeg = ffprobe.with_file_name(name);
+    if ffmpeg == ffprobe {
+        return None;
+    }
+    (ffmpeg.parent().is_none_or(|dir| dir.as_os_str().is_empty()) || ffmpeg.is_file()).then_some(ffmpeg)
+}
+
+/// Decode the audio at `path` with ffmpeg's astats, silencedetect and
+/// ebur128 filters. `duration_secs` (from ffprobe) places trailing silence.
+pub fn analyze_levels(ffmpeg: &Path, path: &Path, duration_secs: Option<f64>) -> AppResult<AudioLevels> {
+    let filters = format!(
+        "astats,silencedetect=noise={}dB:duration={},ebur128=framelog=verbose",
+        SILENCE_DB, MIN_SILENCE_SECS
+    );
+    let output = command(ffmpeg)
+        .args(["-hide_banner", "-nostats", "-v", "info", "-i"])
+        .arg(path)
+        .args(["-vn", "-af", &filters, "-f", "null", "-"])
+        .output()
+        .map_err(|e| AppError::Custom(format!("Failed to run ffmpeg ({}): {}", ffmpeg.display(), e)))?;
+    let log = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(AppError::Custom(format!(
+            "ffmpeg failed on {}: {}",
+            path.display(),
+            log.lines().last().unwrap_or_default().trim()
+        )));
+    }
+
+    Ok(parse_levels(&log, duration_secs))
+}
+
+/// Read the filters' end-of-stream summaries out of ffmpeg's log. Lines of
+/// a summary after the first carry no `[filter @ 0x..]` prefix.
+fn parse_levels(log: &str, duration_secs: Option<f64>) -> AudioLevels {
+    let mut levels = AudioLevels::default();
+    let mut in_overall = false;
+    let mut in_integrated = false;
+    let mut peak_count = 0;
+    let mut silences: Vec<(f64, Option<f64>)> = Vec::new();
+
+    for line in log.lines() {
+        let text = match line.split_once("] ") {
+            Some((prefix, rest)) if line.starts_with('[') && prefix.contains(" @ ") => rest.trim(),
+            _ => line.trim(),
+        };
+
+        if line.contains("astats") {
+            if text == "Overall" {
+                in_overall = true;
+            } else if text.starts_with("Channel:") {
+                in_overall = false;
+            } else if in_overall {
+                if let Some(value) = text.strip_prefix("Peak level dB:") {
+                    levels.peak_db = decibels(value);
+                } else if let Some(value) = text.strip_prefix("RMS level dB:") {
+                    levels.rms_db = decibels(value);
+                } else if let Some(value) = text.strip_prefix("Peak count:") {
+                    peak_count = value.trim().parse::<f64>().map(|count| count as i64).unwrap_or(0);
+                }
+            }
+        }
+
+        if let Some(value) = text.strip_prefix("silence_start:") {
+            if let Ok(start) = value.trim().parse() {
+                silences.push((start, None));
+            }
+        } else if let Some(value) = text.strip_prefix("silence_end:") {
+            let end = value.split('|').next().and_then(|end| end.trim().parse().ok());
+            if let Some(last) = silences.last_mut().filter(|(_, end)| end.is_none()) {
+                last.1 = end;
+            }
+        }
+
+        if text == "Integrated loudness:" {
+            in_integrated = true;
+        } else if in_integrated {
+            if let Some(value) = text.strip_prefix("I:") {
+                levels.loudness_lufs = value
+                    .trim()
+                    .trim_end_matches("LUFS")
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|lufs: &f64| *lufs > LOUDNESS_FLOOR_LUFS);
+                in_integrated = false;
+            }
+        }
+    }
+
+    if levels.peak_db.is_some_and(|peak| peak >= FULL_SCALE_DB) {
+        levels.clipped_samples = peak_count;
+    }
+
+    // A silence still open at the end of the stream runs to the end
+    let end_of_audio = duration_secs.or_else(|| silences.iter().filter_map(|(_, end)| *end).reduce(f64::max));
+    for (start, end) in &silences {
+        let Some(end) = end.or(end_of_audio) else {
+            continue;
+        };
+        let length = (end - start).max(0.0);
+        levels.silence_secs += length;
+        if *start <= MIN_SILENCE_SECS / 2.0 {
+            levels.leading_silence_secs = length;
+        }
+        if end_of_audio.is_some_and(|total| total - end <= MIN_SILENCE_SECS / 2.0) {
+            levels.trailing_silence_secs = length;
+        }
+    }
+
+    levels
+}
+
+/// "-3.01" -> Some(-3.01); "-inf" (digital silence) -> None
+fn decibels(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|db| db.is_finite())
+}
+
+/// Limits the audio QA report flags clips by
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioQaThresholds {
+    /// Flag peaks above this, in dBFS
+    pub peak_db: f64,
+    /// Samples at full scale before a clip counts as clipping; a normalized
+    /// peak touches full scale once or twice, clipped audio sits there
+    pub min_clipped_samples: i64,
+    /// Flag silence at the start or end at least this long
+    pub edge_silence_secs: f64,
+    /// Flag clips silent for at least this share of their length
+    pub silence_ratio: f64,
+}
+
+impl Default for AudioQaThresholds {
+    fn default() -> Self {
+        Self {
+            peak_db: -0.1,
+            min_clipped_samples: 3,
+            edge_silence_secs: 0.5,
+            silence_ratio: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioQaIssue {
+    /// "clipping", "peak_above" or "excessive_silence"
+    pub issue: String,
+    pub count: usize,
+    /// Worst first
+    pub assets: Vec<AudioLevelsEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioQaReport {
+    /// Audio assets with levels for their current version
+    pub analyzed: usize,
+    /// Audio assets still to analyze (or that ffmpeg couldn't decode)
+    pub not_analyzed: usize,
+    pub thresholds: AudioQaThresholds,
+    pub issues: Vec<AudioQaIssue>,
+}
+
+/// Audio assets over the thresholds, from the levels stored by the last
+/// media probe. Each issue lists up to `limit` assets.
+pub fn qa_report(
+    db: &Database,
+    project_id: &str,
+    thresholds: AudioQaThresholds,
+    limit: usize,
+) -> AppResult<AudioQaReport> {
+    let entries = db.get_audio_levels(project_id)?;
+    let not_analyzed = db.get_assets_needing_audio_levels(project_id)?.len();
+
+    let issues = ["clipping", "peak_above", "excessive_silence"]
+        .into_iter()
+        .map(|issue| {
+            // Sort key: how far past the threshold each asset is
+            let severity = |entry: &AudioLevelsEntry| -> Option<f64> {
+                let levels = &entry.levels;
+                match issue {
+                    "clipping" => (levels.clipped_samples >= thresholds.min_clipped_samples)
+                        .then_some(levels.clipped_samples as f64),
+                    "peak_above" => levels.peak_db.filter(|peak| *peak > thresholds.peak_db),
+                    _ => {
+                        let ratio = entry
+                            .duration_secs
+                            .filter(|duration| *duration > 0.0)
+                            .map(|duration| levels.silence_secs / duration)
+                            .unwrap_or(0.0);
+                        let edge = levels.leading_silence_secs.max(levels.trailing_silence_secs);
+                        (ratio >= thresholds.silence_ratio || edge >= thresholds.edge_silence_secs)
+                            .then_some(levels.silence_secs)
+                    }
+                }
+            };
+            let mut flagged: Vec<(f64, &AudioLevelsEntry)> =
+                entries.iter().filter_map(|entry| Some((severity(entry)?, entry))).collect();
+            flagged.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.relative_path.cmp(&b.1.relative_path)));
+            AudioQaIssue {
+                issue: issue.to_string(),
+                count: flagged.len(),
+                assets: flagged.into_iter().take(limit).map(|(_, entry)| entry.clone()).collect(),
+            }
+        })
+        .collect();
+
+    Ok(AudioQaReport {
+        analyzed: entries.len(),
+        not_analyzed,
+        thresholds,
+        issues,
+    })
+}
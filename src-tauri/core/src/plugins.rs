@@ -0,0 +1,412 @@
+//! Plugins that teach the scanner about formats it doesn't know.
+//!
+//! A plugin is a dynamic library (`.dll`, `.so`, `.dylib`) in the plugins
+//! folder exporting a small C ABI. Every call passes JSON both ways so
+//! plugins can be written in any language that can build a C library:
+//!
+//! ```c
+//! uint32_t scythe_plugin_abi_version(void);          // must return 1
+//! char *scythe_plugin_manifest(void);                 // PluginManifest as JSON
+//! char *scythe_plugin_call(const char *request);      // {"method", "path", ...}
+//! void scythe_plugin_free(char *response);            // frees strings returned above
+//! ```
+//!
+//! `scythe_plugin_call` answers `{"result": ...}` or `{"error": "..."}`. It is
+//! called from several threads at once (the scan, dependency and thumbnail
+//! workers), so it must be thread-safe: guard any global state with a lock.
+//! Methods, each only called for the extensions the manifest claims:
+//!
+//! - `classify` `{path}` → asset type string, or null to fall back to the built-in type
+//! - `parse_dependencies` `{path}` → `[PluginReference]`
+//! - `extract_metadata` `{path}` → any JSON object shown in the asset details
+//! - `generate_preview` `{path, output, size}` → true once a PNG of at most
+//!   `size` pixels per side has been written to `output`
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Version of the C ABI above; plugins built for another one are rejected
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    Classify,
+    Dependencies,
+    Metadata,
+    Preview,
+}
+
+/// What a plugin declares about itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    /// File extensions without the dot, matched case-insensitively
+    pub extensions: Vec<String>,
+    pub capabilities: Vec<PluginCapability>,
+}
+
+/// A reference found by a plugin. At least one of `guid` and `path` is set;
+/// `path` is relative to the project root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginReference {
+    pub guid: Option<String>,
+    pub path: Option<String>,
+    /// Defaults to "reference"
+    pub relation_type: Option<String>,
+}
+
+/// Metadata one plugin extracted from an asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginMetadata {
+    pub plugin: String,
+    pub data: Value,
+}
+
+/// A plugin found in the plugins folder, loaded or not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginStatus {
+    pub path: String,
+    pub manifest: Option<PluginManifest>,
+    /// Why the library couldn't be loaded
+    pub error: Option<String>,
+}
+
+/// Format support added from outside the app. Implemented by dynamic
+/// library plugins; anything else that can answer these calls (an
+/// in-process format, another runtime) can be registered the same way.
+pub trait AssetPlugin: Send + Sync {
+    fn manifest(&self) -> &PluginManifest;
+
+    fn classify(&self, path: &Path) -> AppResult<Option<String>>;
+
+    fn parse_dependencies(&self, path: &Path) -> AppResult<Vec<PluginReference>>;
+
+    fn extract_metadata(&self, path: &Path) -> AppResult<Option<Value>>;
+
+    fn generate_preview(&self, path: &Path, output: &Path, size: u32) -> AppResult<bool>;
+
+    fn supports(&self, capability: PluginCapability) -> bool {
+        self.manifest().capabilities.contains(&capability)
+    }
+}
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *mut c_char;
+type CallFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// A plugin library loaded with `libloading`
+pub struct DynamicPlugin {
+    manifest: PluginManifest,
+    call: CallFn,
+    free: FreeFn,
+    // Declared last so the functions above are never used after unloading
+    _library: libloading::Library,
+}
+
+impl DynamicPlugin {
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let load_error = |message: String| AppError::Plugin {
+            plugin: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string()),
+            message,
+        };
+
+        // SAFETY: loading runs the library's initializers. Plugins are trusted
+        // code the user placed in the plugins folder.
+        let library = unsafe { libloading::Library::new(path) }.map_err(|e| load_error(e.to_string()))?;
+        let (abi_version, manifest_fn, call, free) = unsafe {
+            (
+                symbol::<AbiVersionFn>(&library, "scythe_plugin_abi_version").map_err(load_error)?,
+                symbol::<ManifestFn>(&library, "scythe_plugin_manifest").map_err(load_error)?,
+                symbol::<CallFn>(&library, "scythe_plugin_call").map_err(load_error)?,
+                symbol::<FreeFn>(&library, "scythe_plugin_free").map_err(load_error)?,
+            )
+        };
+
+        let version = unsafe { abi_version() };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(load_error(format!(
+                "Built for plugin ABI {}, this version of Scythe uses {}",
+                version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let raw = unsafe { take_string(manifest_fn(), free) }
+            .ok_or_else(|| load_error("Returned no manifest".to_string()))?;
+        let mut manifest: PluginManifest =
+            serde_json::from_str(&raw).map_err(|e| load_error(format!("Invalid manifest: {}", e)))?;
+        normalize_manifest(&mut manifest).map_err(load_error)?;
+
+        Ok(Self {
+            manifest,
+            call,
+            free,
+            _library: library,
+        })
+    }
+
+    /// Send one request and unwrap the `{"result"}` / `{"error"}` envelope
+    fn request(&self, method: &str, mut params: Value) -> AppResult<Value> {
+        let plugin_error = |message: String| AppError::Plugin {
+            plugin: self.manifest.name.clone(),
+            message,
+        };
+
+        params["method"] = json!(method);
+        let request = CString::new(params.to_string()).map_err(|e| plugin_error(e.to_string()))?;
+        let raw = unsafe { take_string((self.call)(request.as_ptr()), self.free) }
+            .ok_or_else(|| plugin_error(format!("No response to {}", method)))?;
+
+        let mut response: Value =
+            serde_json::from_str(&raw).map_err(|e| plugin_error(format!("Invalid response to {}: {}", method, e)))?;
+        if let Some(error) = response.get("error").and_then(|e| e.as_str()) {
+            return Err(plugin_error(error.to_string()));
+        }
+        Ok(response.get_mut("result").map(Value::take).unwrap_or(Value::Null))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, method: &str, value: Value) -> AppResult<T> {
+        serde_json::from_value(value).map_err(|e| AppError::Plugin {
+            plugin: self.manifest.name.clone(),
+            message: format!("Unexpected result from {}: {}", method, e),
+        })
+    }
+}
+
+impl AssetPlugin for DynamicPlugin {
+    fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    fn classify(&self, path: &Path) -> AppResult<Option<String>> {
+        let result = self.request("classify", json!({ "path": path }))?;
+        self.decode("classify", result)
+    }
+
+    fn parse_dependencies(&self, path: &Path) -> AppResult<Vec<PluginReference>> {
+        let result = self.request("parse_dependencies", json!({ "path": path }))?;
+        let references: Option<Vec<PluginReference>> = self.decode("parse_dependencies", result)?;
+        Ok(references.unwrap_or_default())
+    }
+
+    fn extract_metadata(&self, path: &Path) -> AppResult<Option<Value>> {
+        let result = self.request("extract_metadata", json!({ "path": path }))?;
+        Ok((!result.is_null()).then_some(result))
+    }
+
+    fn generate_preview(&self, path: &Path, output: &Path, size: u32) -> AppResult<bool> {
+        let result = self.request(
+            "generate_preview",
+            json!({ "path": path, "output": output, "size": size }),
+        )?;
+        Ok(result.as_bool().unwrap_or(false) && output.is_file())
+    }
+}
+
+/// Look up an exported function; the pointer stays valid while `library` is loaded
+unsafe fn symbol<T: Copy>(library: &libloading::Library, name: &str) -> Result<T, String> {
+    let mut bytes = name.as_bytes().to_vec();
+    bytes.push(0);
+    library
+        .get::<T>(&bytes)
+        .map(|symbol| *symbol)
+        .map_err(|e| format!("Missing {}: {}", name, e))
+}
+
+/// Copy a string returned by the plugin and hand it back to be freed
+unsafe fn take_string(ptr: *mut c_char, free: FreeFn) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let value = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    free(ptr);
+    Some(value)
+}
+
+/// Lowercase the extensions and reject ones that could never match a file
+fn normalize_manifest(manifest: &mut PluginManifest) -> Result<(), String> {
+    if manifest.name.trim().is_empty() {
+        return Err("Manifest has no name".to_string());
+    }
+    for extension in &mut manifest.extensions {
+        *extension = extension.trim_start_matches('.').to_lowercase();
+        if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("Invalid extension in manifest: {:?}", extension));
+        }
+    }
+    Ok(())
+}
+
+/// Every loaded plugin, indexed by the extensions they claim
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Arc<dyn AssetPlugin>>,
+    by_extension: HashMap<String, Vec<usize>>,
+    statuses: Vec<PluginStatus>,
+}
+
+impl PluginRegistry {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Load every library in `dir`. A plugin that fails to load is reported
+    /// in `statuses` and skipped; a missing folder just means no plugins.
+    pub fn load_dir(dir: &Path) -> Self {
+        let mut registry = Self::empty();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return registry;
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && is_library(p))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match DynamicPlugin::load(&path) {
+                Ok(plugin) => {
+                    let manifest = plugin.manifest().clone();
+                    tracing::info!(
+                        "Loaded plugin {} {} for .{}",
+                        manifest.name,
+                        manifest.version,
+                        manifest.extensions.join(", .")
+                    );
+                    registry.register(Arc::new(plugin));
+                    registry.statuses.push(PluginStatus {
+                        path: path.to_string_lossy().to_string(),
+                        manifest: Some(manifest),
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    registry.statuses.push(PluginStatus {
+                        path: path.to_string_lossy().to_string(),
+                        manifest: None,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+        registry
+    }
+
+    pub fn register(&mut self, plugin: Arc<dyn AssetPlugin>) {
+        let index = self.plugins.len();
+        for extension in &plugin.manifest().extensions {
+            self.by_extension.entry(extension.to_lowercase()).or_default().push(index);
+        }
+        self.plugins.push(plugin);
+    }
+
+    pub fn statuses(&self) -> &[PluginStatus] {
+        &self.statuses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Extensions claimed by plugins with `capability`
+    pub fn extensions_with(&self, capability: PluginCapability) -> Vec<String> {
+        let mut extensions: Vec<String> = self
+            .by_extension
+            .iter()
+            .filter(|(_, indices)| indices.iter().any(|&i| self.plugins[i].supports(capability)))
+            .map(|(extension, _)| extension.clone())
+            .collect();
+        extensions.sort();
+        extensions
+    }
+
+    /// Plugins claiming the file's extension with `capability`, in load order
+    fn plugins_for<'a>(
+        &'a self,
+        path: &Path,
+        capability: PluginCapability,
+    ) -> impl Iterator<Item = &'a Arc<dyn AssetPlugin>> + 'a {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        self.by_extension
+            .get(&extension)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.plugins[i])
+            .filter(move |plugin| plugin.supports(capability))
+    }
+
+    /// Asset type from the first plugin that recognises the file
+    pub fn classify(&self, path: &Path) -> Option<String> {
+        self.plugins_for(path, PluginCapability::Classify).find_map(|plugin| {
+            match plugin.classify(path) {
+                Ok(asset_type) => asset_type.filter(|t| !t.is_empty()),
+                Err(e) => {
+                    tracing::warn!("{} ({})", e, path.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// References from the first plugin that parses the file, or `None`
+    /// when no plugin handles it and the built-in parser should be used
+    pub fn parse_dependencies(&self, path: &Path) -> Option<AppResult<Vec<PluginReference>>> {
+        self.plugins_for(path, PluginCapability::Dependencies)
+            .next()
+            .map(|plugin| plugin.parse_dependencies(path))
+    }
+
+    /// Whether any plugin claims the file's extension with `capability`
+    pub fn handles(&self, path: &Path, capability: PluginCapability) -> bool {
+        self.plugins_for(path, capability).next().is_some()
+    }
+
+    /// Metadata from every plugin that extracts it; failures are logged and skipped
+    pub fn extract_metadata(&self, path: &Path) -> Vec<PluginMetadata> {
+        self.plugins_for(path, PluginCapability::Metadata)
+            .filter_map(|plugin| match plugin.extract_metadata(path) {
+                Ok(data) => data.map(|data| PluginMetadata {
+                    plugin: plugin.manifest().name.clone(),
+                    data,
+                }),
+                Err(e) => {
+                    tracing::warn!("{} ({})", e, path.display());
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Let the first plugin that can render the file write `output`.
+    /// `None` when no plugin previews this extension.
+    pub fn generate_preview(&self, path: &Path, output: &Path, size: u32) -> Option<AppResult<bool>> {
+        self.plugins_for(path, PluginCapability::Preview)
+            .next()
+            .map(|plugin| plugin.generate_preview(path, output, size))
+    }
+}
+
+fn is_library(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    matches!(extension.as_str(), "dll" | "so" | "dylib")
+}
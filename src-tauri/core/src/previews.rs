@@ -0,0 +1,1240 @@
+use crate::blender;
+use crate::converters::{self, TextureConverter};
+use crate::db::{Asset, Database};
+use crate::error::AppResult;
+use crate::fbx;
+use crate::plugins::{PluginCapability, PluginRegistry};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ExtendedColorType, GenericImageView, ImageEncoder, RgbaImage, Rgba};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufWriter, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialInfo {
+    pub shader_name: Option<String>,
+    pub textures: Vec<MaterialTexture>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialTexture {
+    pub slot_name: String,
+    pub texture_guid: Option<String>,
+    pub texture_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub vertex_count: Option<u64>,
+    pub triangle_count: Option<u64>,
+    pub submesh_count: Option<u32>,
+    pub has_normals: bool,
+    pub has_uvs: bool,
+    pub bounds: Option<[f32; 6]>, // min_x, min_y, min_z, max_x, max_y, max_z
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextPreview {
+    pub content: Option<String>,
+    pub is_binary: bool,
+    pub truncated: bool,
+    pub total_bytes: u64,
+}
+
+/// Size and encoding of generated thumbnails
+#[derive(Debug, Clone)]
+pub struct ThumbnailOptions {
+    pub size: u32,
+    /// "png", "webp" or "jpeg"
+    pub format: String,
+    /// JPEG quality (1-100). WebP is always lossless since the image crate
+    /// has no lossy encoder, but it is still much smaller than PNG.
+    pub quality: u8,
+    /// Source images above either budget are marked TOO_LARGE without decoding
+    pub max_pixels: u64,
+    pub max_file_bytes: u64,
+    /// Failed attempts before an asset is skipped until its file changes
+    pub max_attempts: u32,
+    /// Asset types generated first, in order
+    pub type_priority: Vec<String>,
+}
+
+/// Worst-case decoded size per pixel (16-bit RGBA), used to cap decoder allocations
+const DECODED_BYTES_PER_PIXEL: u64 = 8;
+
+/// Dependency thumbnails tiled into a prefab or scene thumbnail
+const COMPOSITE_TILES: usize = 4;
+
+pub(crate) const IDENTITY_MATRIX: [f64; 16] = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+
+impl ThumbnailOptions {
+    fn extension(&self) -> &'static str {
+        match self.format.as_str() {
+            "webp" => "webp",
+            "jpeg" => "jpg",
+            _ => "png",
+        }
+    }
+
+    /// Check a source image against the decode budget using only its file
+    /// size and header. Unreadable headers pass; the decoder limits catch them.
+    pub fn exceeds_decode_budget(&self, path: &Path) -> bool {
+        if fs::metadata(path).map(|m| m.len()).unwrap_or(0) > self.max_file_bytes {
+            return true;
+        }
+
+        match read_image_dimensions(path) {
+            Some((width, height)) => width as u64 * height as u64 > self.max_pixels,
+            None => false,
+        }
+    }
+
+    fn decode_limits(&self) -> image::Limits {
+        let mut limits = image::Limits::default();
+        limits.max_alloc = Some(self.max_pixels.saturating_mul(DECODED_BYTES_PER_PIXEL));
+        limits
+    }
+}
+
+pub struct PreviewGenerator {
+    db: Arc<Database>,
+    thumbnail_dir: PathBuf,
+    options: ThumbnailOptions,
+    plugins: Option<Arc<PluginRegistry>>,
+    /// Blender executable used to render model thumbnails
+    blender: Option<PathBuf>,
+    /// External decoders for texture formats the image crate can't read
+    converters: Vec<TextureConverter>,
+}
+
+impl PreviewGenerator {
+    pub fn new(db: Arc<Database>, thumbnail_dir: PathBuf, options: ThumbnailOptions) -> Self {
+        Self {
+            db,
+            thumbnail_dir,
+            options,
+            plugins: None,
+            blender: None,
+            converters: Vec::new(),
+        }
+    }
+
+    /// Let plugins render previews for the formats they claim
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = Some(plugins);
+        self
+    }
+
+    /// Render .blend/.fbx/.obj thumbnails with Blender. Without it model
+    /// thumbnails are left to the app's WebGL renderer.
+    pub fn with_blender(mut self, blender: PathBuf) -> Self {
+        self.blender = Some(blender);
+        self
+    }
+
+    /// Decode exotic texture formats (DDS, ASTC, PVR...) with external tools
+    pub fn with_converters(mut self, converters: Vec<TextureConverter>) -> Self {
+        self.converters = converters;
+        self
+    }
+
+    /// Extensions rendered by a plugin or Blender on top of textures and
+    /// materials, for selecting assets that need thumbnails
+    pub fn extra_extensions(&self) -> Vec<String> {
+        let mut extensions = self
+            .plugins
+            .as_ref()
+            .map(|plugins| plugins.extensions_with(PluginCapability::Preview))
+            .unwrap_or_default();
+        if self.blender.is_some() {
+            extensions.extend(blender::BLENDER_EXTENSIONS.iter().map(|e| e.to_string()));
+        }
+        extensions
+    }
+
+    pub fn generate_thumbnail(&self, asset: &Asset) -> AppResult<Option<String>> {
+        let plugins = self
+            .plugins
+            .as_ref()
+            .filter(|plugins| plugins.handles(Path::new(&asset.absolute_path), PluginCapability::Preview));
+        let result = match (plugins, asset.asset_type.as_str()) {
+            (Some(plugins), _) => self.generate_plugin_thumbnail(plugins, asset)?,
+            (None, "texture") => self.generate_texture_thumbnail(asset)?,
+            (None, "material") => self.generate_material_thumbnail(asset)?,
+            (None, "prefab" | "scene") => self.generate_composite_thumbnail(asset)?,
+            (None, "model") => match &self.blender {
+                Some(blender) if blender::renders(Path::new(&asset.absolute_path)) => {
+                    self.generate_blender_thumbnail(blender, asset)?
+                }
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        if result.as_deref().is_some_and(|path| path != "UNSUPPORTED" && path != "TOO_LARGE") {
+            self.db.clear_thumbnail_failure(&asset.id)?;
+        }
+        Ok(result)
+    }
+
+    /// Mark the asset UNSUPPORTED and count the failure towards its attempt limit
+    fn mark_failed(&self, asset: &Asset, reason: &str) -> AppResult<Option<String>> {
+        self.db.update_asset_thumbnail(&asset.id, "UNSUPPORTED")?;
+        self.db.record_thumbnail_failure(&asset.id, asset.modified_time, reason)?;
+        Ok(Some("UNSUPPORTED".to_string()))
+    }
+
+    fn generate_texture_thumbnail(&self, asset: &Asset) -> AppResult<Option<String>> {
+        let source_path = Path::new(&asset.absolute_path);
+        let start_time = std::time::Instant::now();
+
+        // Check if we support this format
+        let extension = source_path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let is_psd = extension == "psd";
+        let converter = match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "tga" | "bmp" | "gif" | "psd" => None,
+            _ => match self.converters.iter().find(|c| c.handles(&extension)) {
+                Some(converter) => Some(converter),
+                // Marked so the thumbnail job moves past it instead of selecting it again
+                None => {
+                    self.db.update_asset_thumbnail(&asset.id, "UNSUPPORTED")?;
+                    return Ok(Some("UNSUPPORTED".to_string()));
+                }
+            },
+        };
+
+        // Generate a unique filename
+        let thumb_name = format!(
+            "{:x}_{}.{}",
+            md5_hash(&asset.absolute_path),
+            asset.modified_time,
+            self.options.extension()
+        );
+        let thumb_path = self.thumbnail_dir.join(&thumb_name);
+
+        // Check if thumbnail already exists
+        if thumb_path.exists() {
+            let thumb_path_str = thumb_path.to_string_lossy().to_string();
+            self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+            return Ok(Some(thumb_path_str));
+        }
+
+        // Refuse oversized images before decoding allocates their full size
+        if self.options.exceeds_decode_budget(source_path) {
+            tracing::warn!("Image over decode budget: {}", asset.absolute_path);
+            self.db.update_asset_thumbnail(&asset.id, "TOO_LARGE")?;
+            return Ok(Some("TOO_LARGE".to_string()));
+        }
+
+        // Load image with timeout protection (3 seconds max)
+        // This prevents hanging on corrupted or problematic files
+        const LOAD_TIMEOUT_SECS: u64 = 3;
+
+        let img = if let Some(converter) = converter {
+            // Decode to a PNG with the external tool, then load that like any other image
+            let work_dir = self.thumbnail_dir.join(format!("{}.convert", thumb_name));
+            let loaded = converters::convert(converter, source_path, &work_dir)
+                .and_then(|png| load_image_with_timeout(&png, &self.options, LOAD_TIMEOUT_SECS));
+            let _ = fs::remove_dir_all(&work_dir);
+            match loaded {
+                Ok(img) => img,
+                Err(e) => {
+                    tracing::warn!("Converter failed for {}: {}", asset.absolute_path, e);
+                    return self.mark_failed(asset, &e);
+                }
+            }
+        } else if is_psd {
+            // Handle PSD files with timeout
+            match load_psd_with_timeout(source_path, LOAD_TIMEOUT_SECS) {
+                Ok(img) => img,
+                Err(e) => {
+                    tracing::warn!("PSD load failed for {}: {}", asset.absolute_path, e);
+                    return self.mark_failed(asset, &e);
+                }
+            }
+        } else {
+            // Handle other image formats with timeout
+            match load_image_with_timeout(source_path, &self.options, LOAD_TIMEOUT_SECS) {
+                Ok(img) => img,
+                Err(e) => {
+                    tracing::warn!("Image load failed for {}: {}", asset.absolute_path, e);
+                    return self.mark_failed(asset, &e);
+                }
+            }
+        };
+
+        let (width, height) = img.dimensions();
+        let load_time = start_time.elapsed();
+
+        // Log slow loads (over 1 second)
+        if load_time.as_secs() >= 1 {
+            tracing::info!(
+                "Slow image load: {}x{} in {:?} - {}",
+                width, height, load_time, asset.absolute_path
+            );
+        }
+
+        if width == 0 || height == 0 {
+            return self.mark_failed(asset, "Image has no pixels");
+        }
+
+        // Formats without a readable header are only checked after decoding
+        if width as u64 * height as u64 > self.options.max_pixels {
+            tracing::warn!(
+                "Image too large to process: {}x{} ({})",
+                width, height, asset.absolute_path
+            );
+            self.db.update_asset_thumbnail(&asset.id, "TOO_LARGE")?;
+            return Ok(Some("TOO_LARGE".to_string()));
+        }
+
+        // Use thumbnail() which is faster than resize_exact() for large images
+        // It maintains aspect ratio and uses efficient algorithms
+        let resized = img.thumbnail(self.options.size, self.options.size);
+
+        // Log slow resizes
+        let resize_time = start_time.elapsed() - load_time;
+        if resize_time.as_millis() >= 500 {
+            tracing::info!(
+                "Slow resize: {}x{} took {:?} - {}",
+                width, height, resize_time, asset.absolute_path
+            );
+        }
+
+        if let Err(e) = save_thumbnail(&resized, &thumb_path, &self.options) {
+            tracing::warn!("Failed to save thumbnail {}: {}", thumb_path.display(), e);
+            return self.mark_failed(asset, &format!("Failed to save thumbnail: {}", e));
+        }
+
+        let thumb_path_str = thumb_path.to_string_lossy().to_string();
+        self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+
+        Ok(Some(thumb_path_str))
+    }
+
+    /// Have a plugin render a PNG, then resize and encode it like any other thumbnail
+    fn generate_plugin_thumbnail(&self, plugins: &PluginRegistry, asset: &Asset) -> AppResult<Option<String>> {
+        let source_path = Path::new(&asset.absolute_path);
+        let thumb_name = format!(
+            "plugin_{:x}_{}.{}",
+            md5_hash(&asset.absolute_path),
+            asset.modified_time,
+            self.options.extension()
+        );
+        let thumb_path = self.thumbnail_dir.join(&thumb_name);
+
+        if !thumb_path.exists() {
+            let rendered = self.thumbnail_dir.join(format!("{}.plugin.png", thumb_name));
+            let result = plugins.generate_preview(source_path, &rendered, self.options.size);
+            let saved = match result {
+                Some(Ok(true)) => self.save_rendered(&rendered, &thumb_path),
+                Some(Ok(false)) | None => {
+                    let _ = fs::remove_file(&rendered);
+                    return Ok(None);
+                }
+                Some(Err(e)) => {
+                    let _ = fs::remove_file(&rendered);
+                    Err(e.to_string())
+                }
+            };
+            if let Err(e) = saved {
+                tracing::warn!("Plugin preview failed for {}: {}", asset.absolute_path, e);
+                return self.mark_failed(asset, &e);
+            }
+        }
+
+        let thumb_path_str = thumb_path.to_string_lossy().to_string();
+        self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+        Ok(Some(thumb_path_str))
+    }
+
+    fn generate_blender_thumbnail(&self, blender: &Path, asset: &Asset) -> AppResult<Option<String>> {
+        let thumb_name = format!(
+            "blender_{:x}_{}.{}",
+            md5_hash(&asset.absolute_path),
+            asset.modified_time,
+            self.options.extension()
+        );
+        let thumb_path = self.thumbnail_dir.join(&thumb_name);
+
+        if !thumb_path.exists() {
+            let rendered = self.thumbnail_dir.join(format!("{}.blender.png", thumb_name));
+            let saved = blender::render_thumbnail(blender, Path::new(&asset.absolute_path), &rendered, self.options.size)
+                .and_then(|()| self.save_rendered(&rendered, &thumb_path));
+            let _ = fs::remove_file(&rendered);
+            if let Err(e) = saved {
+                tracing::warn!("Blender render failed for {}: {}", asset.absolute_path, e);
+                return self.mark_failed(asset, &e);
+            }
+        }
+
+        let thumb_path_str = thumb_path.to_string_lossy().to_string();
+        self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+        Ok(Some(thumb_path_str))
+    }
+
+    /// Resize and encode an image an external renderer wrote, then delete it
+    fn save_rendered(&self, rendered: &Path, thumb_path: &Path) -> Result<(), String> {
+        let image = open_image_limited(rendered, &self.options).map_err(|e| e.to_string());
+        let _ = fs::remove_file(rendered);
+
+        let img = image?;
+        let resized = img.thumbnail(self.options.size, self.options.size);
+        save_thumbnail(&resized, thumb_path, &self.options)
+    }
+
+    fn generate_material_thumbnail(&self, asset: &Asset) -> AppResult<Option<String>> {
+        // Parse material to get texture info
+        let mat_info = match parse_material_file(Path::new(&asset.absolute_path)) {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+
+        if mat_info.textures.is_empty() {
+            return Ok(None);
+        }
+
+        // Generate thumbnail name
+        let thumb_name = format!(
+            "mat_{:x}_{}.{}",
+            md5_hash(&asset.absolute_path),
+            asset.modified_time,
+            self.options.extension()
+        );
+        let thumb_path = self.thumbnail_dir.join(&thumb_name);
+
+        if thumb_path.exists() {
+            let thumb_path_str = thumb_path.to_string_lossy().to_string();
+            self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+            return Ok(Some(thumb_path_str));
+        }
+
+        // Try to find and load the main texture (albedo/diffuse)
+        let main_texture = mat_info.textures.iter().find(|t| {
+            let slot = t.slot_name.to_lowercase();
+            slot.contains("albedo") || slot.contains("diffuse") || slot.contains("maintex") || slot.contains("base")
+        }).or_else(|| mat_info.textures.first());
+
+        if let Some(tex) = main_texture {
+            if let Some(guid) = &tex.texture_guid {
+                // Look up texture by GUID
+                if let Ok(Some(tex_asset)) = self.db.get_asset_by_guid(&asset.project_id, guid) {
+                    // Generate thumbnail from that texture
+                    if let Ok(Some(thumb)) = self.generate_texture_thumbnail(&tex_asset) {
+                        // Copy/link to material thumbnail
+                        if let Err(e) = fs::copy(&thumb, &thumb_path) {
+                            tracing::warn!("Failed to copy material thumbnail: {}", e);
+                        } else {
+                            let thumb_path_str = thumb_path.to_string_lossy().to_string();
+                            self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+                            return Ok(Some(thumb_path_str));
+                        }
+                    }
+                }
+            }
+        }
+
+        // If we couldn't find a texture, create a colored placeholder based on material properties
+        let placeholder = create_material_placeholder(&mat_info, self.options.size);
+        if let Err(e) = save_thumbnail(&DynamicImage::ImageRgba8(placeholder), &thumb_path, &self.options) {
+            tracing::warn!("Failed to save material placeholder: {}", e);
+            return Ok(None);
+        }
+
+        let thumb_path_str = thumb_path.to_string_lossy().to_string();
+        self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+        Ok(Some(thumb_path_str))
+    }
+
+    /// Tile the thumbnails of the textures and materials a prefab or scene
+    /// uses, the most referenced and largest first, since there is no 3D
+    /// renderer to draw the asset itself
+    fn generate_composite_thumbnail(&self, asset: &Asset) -> AppResult<Option<String>> {
+        // Fetch a few spares for sources that have no usable thumbnail
+        let mut tiles = Vec::new();
+        for source in self.db.get_composite_sources(&asset.id, COMPOSITE_TILES * 2)? {
+            if tiles.len() == COMPOSITE_TILES {
+                break;
+            }
+            let thumb = match &source.thumbnail_path {
+                Some(path) => Some(path.clone()),
+                None => self.generate_thumbnail(&source)?,
+            };
+            if let Some(path) = thumb.filter(|path| Path::new(path).is_file()) {
+                tiles.push(path);
+            }
+        }
+
+        // Nothing to show yet; marked so the thumbnail job moves past it. It
+        // is not counted as a failure, as a later run may have textures to use.
+        if tiles.is_empty() {
+            self.db.update_asset_thumbnail(&asset.id, "UNSUPPORTED")?;
+            return Ok(Some("UNSUPPORTED".to_string()));
+        }
+
+        // The tiles are part of the name so a composite is rebuilt when they change
+        let thumb_name = format!(
+            "comp_{:x}_{}.{}",
+            md5_hash(&format!("{}|{}", asset.absolute_path, tiles.join("|"))),
+            asset.modified_time,
+            self.options.extension()
+        );
+        let thumb_path = self.thumbnail_dir.join(&thumb_name);
+
+        if !thumb_path.exists() {
+            let images: Vec<DynamicImage> = tiles
+                .iter()
+                .filter_map(|tile| open_image_limited(Path::new(tile), &self.options).ok())
+                .collect();
+            if images.is_empty() {
+                return self.mark_failed(asset, "No dependency thumbnail could be read");
+            }
+            let composite = compose_tiles(&images, self.options.size);
+            if let Err(e) = save_thumbnail(&DynamicImage::ImageRgba8(composite), &thumb_path, &self.options) {
+                tracing::warn!("Failed to save composite thumbnail {}: {}", thumb_path.display(), e);
+                return self.mark_failed(asset, &format!("Failed to save thumbnail: {}", e));
+            }
+        }
+
+        let thumb_path_str = thumb_path.to_string_lossy().to_string();
+        self.db.update_asset_thumbnail(&asset.id, &thumb_path_str)?;
+        Ok(Some(thumb_path_str))
+    }
+
+    pub fn generate_thumbnails_for_project(
+        &self,
+        project_id: &str,
+        limit: i64,
+        threads: usize,
+    ) -> AppResult<usize> {
+        let assets = self.db.get_assets_needing_thumbnails(
+            project_id,
+            limit,
+            self.options.max_attempts,
+            &self.extra_extensions(),
+            &self.options.type_priority,
+        )?;
+        let next = AtomicUsize::new(0);
+        let generated = AtomicUsize::new(0);
+
+        // Workers pull the next asset index until the batch is exhausted
+        std::thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, assets.len().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(asset) = assets.get(index) else {
+                        break;
+                    };
+
+                    match self.generate_thumbnail(asset) {
+                        Ok(Some(_)) => {
+                            generated.fetch_add(1, Ordering::SeqCst);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            tracing::warn!("Failed to generate thumbnail for {}: {}", asset.relative_path, e);
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(generated.into_inner())
+    }
+}
+
+/// Parse a Unity .mat file to extract texture references
+pub fn parse_material_file(path: &Path) -> Option<MaterialInfo> {
+    let content = fs::read_to_string(path).ok()?;
+
+    let mut info = MaterialInfo {
+        shader_name: None,
+        textures: Vec::new(),
+    };
+
+    // Extract shader name
+    let shader_re = Regex::new(r"m_Shader:\s*\{[^}]*\}").ok()?;
+    if let Some(_) = shader_re.find(&content) {
+        // Try to find shader name in the file
+        let name_re = Regex::new(r#"m_Name:\s*([^\n\r]+)"#).ok()?;
+        if let Some(cap) = name_re.captures(&content) {
+            info.shader_name = Some(cap.get(1)?.as_str().trim().to_string());
+        }
+    }
+
+    // Extract texture properties
+    // Unity materials have sections like:
+    // - _MainTex:
+    //     m_Texture: {fileID: 2800000, guid: abc123..., type: 3}
+    let tex_section_re = Regex::new(r"- (\w+):\s*\n\s*m_Texture:\s*\{[^}]*guid:\s*([a-f0-9]{32})").ok()?;
+
+    for cap in tex_section_re.captures_iter(&content) {
+        let slot_name = cap.get(1)?.as_str().to_string();
+        let guid = cap.get(2)?.as_str().to_string();
+
+        info.textures.push(MaterialTexture {
+            slot_name,
+            texture_guid: Some(guid),
+            texture_path: None,
+        });
+    }
+
+    // Also try alternate format without guid (embedded or null textures)
+    let tex_name_re = Regex::new(r"- (\w+):\s*\n\s*m_Texture:").ok()?;
+    for cap in tex_name_re.captures_iter(&content) {
+        let slot_name = cap.get(1)?.as_str().to_string();
+        // Only add if not already present
+        if !info.textures.iter().any(|t| t.slot_name == slot_name) {
+            info.textures.push(MaterialTexture {
+                slot_name,
+                texture_guid: None,
+                texture_path: None,
+            });
+        }
+    }
+
+    Some(info)
+}
+
+/// Read the first `max_bytes` of a file for display, detecting binary content.
+/// Binary files (NUL bytes or invalid UTF-8) return no content.
+pub fn read_text_preview(path: &Path, max_bytes: usize) -> AppResult<TextPreview> {
+    use std::io::Read;
+
+    let file = fs::File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+
+    let mut buf = Vec::with_capacity(max_bytes.min(total_bytes as usize));
+    file.take(max_bytes as u64).read_to_end(&mut buf)?;
+    let truncated = (buf.len() as u64) < total_bytes;
+
+    if buf.contains(&0) {
+        return Ok(TextPreview {
+            content: None,
+            is_binary: true,
+            truncated,
+            total_bytes,
+        });
+    }
+
+    let content = match std::str::from_utf8(&buf) {
+        Ok(text) => text.to_string(),
+        // A truncated read can split a multi-byte character at the end
+        Err(e) if truncated && e.error_len().is_none() => {
+            String::from_utf8_lossy(&buf[..e.valid_up_to()]).to_string()
+        }
+        Err(_) => {
+            return Ok(TextPreview {
+                content: None,
+                is_binary: true,
+                truncated,
+                total_bytes,
+            });
+        }
+    };
+
+    Ok(TextPreview {
+        content: Some(content),
+        is_binary: false,
+        truncated,
+        total_bytes,
+    })
+}
+
+/// Parse basic model info from supported formats
+pub fn parse_model_info(path: &Path) -> Option<ModelInfo> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+
+    match extension.as_str() {
+        "obj" => parse_obj_info(path),
+        "gltf" | "glb" => parse_gltf_info(path),
+        "fbx" => parse_fbx_info(path),
+        "dae" => parse_dae_info(path),
+        "blend" => Some(ModelInfo {
+            vertex_count: None,
+            triangle_count: None,
+            submesh_count: None,
+            has_normals: true,
+            has_uvs: true,
+            bounds: None,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_fbx_info(path: &Path) -> Option<ModelInfo> {
+    let data = fs::read(path).ok()?;
+
+    // Binary FBX starts with "Kaydara FBX Binary"
+    if data.starts_with(b"Kaydara FBX Binary") {
+        fbx::parse_binary(&data)
+    } else {
+        // ASCII FBX
+        let content = String::from_utf8_lossy(&data);
+
+        let mut vertex_count = 0u64;
+        let mut has_normals = false;
+        let mut has_uvs = false;
+
+        // Count vertices in ASCII FBX
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with("Vertices:") {
+                // Try to parse vertex count from the line
+                if let Some(rest) = line.strip_prefix("Vertices: *") {
+                    if let Some(count_str) = rest.split_whitespace().next() {
+                        if let Ok(count) = count_str.parse::<u64>() {
+                            vertex_count = count / 3; // 3 floats per vertex
+                        }
+                    }
+                }
+            }
+            if line.contains("Normals:") || line.contains("LayerElementNormal") {
+                has_normals = true;
+            }
+            if line.contains("UV:") || line.contains("LayerElementUV") {
+                has_uvs = true;
+            }
+        }
+
+        Some(ModelInfo {
+            vertex_count: if vertex_count > 0 { Some(vertex_count) } else { None },
+            triangle_count: None,
+            submesh_count: None,
+            has_normals,
+            has_uvs,
+            bounds: None,
+        })
+    }
+}
+
+fn parse_dae_info(path: &Path) -> Option<ModelInfo> {
+    // COLLADA (DAE) is XML-based
+    let content = fs::read_to_string(path).ok()?;
+
+    let mut vertex_count = 0u64;
+    let mut triangle_count = 0u64;
+    let has_normals = content.contains("<source") && content.contains("NORMAL");
+    let has_uvs = content.contains("TEXCOORD");
+
+    // Try to find float_array with positions
+    // Format: <float_array id="...-positions-array" count="123">
+    let pos_re = regex::Regex::new(r#"positions-array"?\s+count="(\d+)""#).ok()?;
+    if let Some(cap) = pos_re.captures(&content) {
+        if let Ok(count) = cap.get(1)?.as_str().parse::<u64>() {
+            vertex_count = count / 3;
+        }
+    }
+
+    // Try to find triangle count
+    // Format: <triangles count="123">
+    let tri_re = regex::Regex::new(r#"<triangles[^>]*count="(\d+)""#).ok()?;
+    for cap in tri_re.captures_iter(&content) {
+        if let Ok(count) = cap.get(1).map(|m| m.as_str()).unwrap_or("0").parse::<u64>() {
+            triangle_count += count;
+        }
+    }
+
+    Some(ModelInfo {
+        vertex_count: if vertex_count > 0 { Some(vertex_count) } else { None },
+        triangle_count: if triangle_count > 0 { Some(triangle_count) } else { None },
+        submesh_count: None,
+        has_normals,
+        has_uvs,
+        bounds: None,
+    })
+}
+
+fn parse_obj_info(path: &Path) -> Option<ModelInfo> {
+    let content = fs::read_to_string(path).ok()?;
+
+    let mut vertex_count = 0u64;
+    let mut face_count = 0u64;
+    let mut has_normals = false;
+    let mut has_uvs = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with("v ") {
+            vertex_count += 1;
+        } else if line.starts_with("f ") {
+            // Count triangles - faces can be quads or ngons
+            let parts: Vec<&str> = line.split_whitespace().skip(1).collect();
+            if parts.len() >= 3 {
+                face_count += (parts.len() - 2) as u64; // Triangulate
+            }
+        } else if line.starts_with("vn ") {
+            has_normals = true;
+        } else if line.starts_with("vt ") {
+            has_uvs = true;
+        }
+    }
+
+    Some(ModelInfo {
+        vertex_count: Some(vertex_count),
+        triangle_count: Some(face_count),
+        submesh_count: Some(1),
+        has_normals,
+        has_uvs,
+        bounds: None,
+    })
+}
+
+/// glTF and GLB: counts come from the accessors the meshes use, so the
+/// binary buffers are never read. Meshes are counted once however many
+/// nodes place them; bounds are those placements in scene space.
+fn parse_gltf_info(path: &Path) -> Option<ModelInfo> {
+    let gltf = read_gltf_json(path)?;
+    let accessors = gltf.get("accessors").and_then(|a| a.as_array());
+    let accessor = |index: Option<&serde_json::Value>| {
+        index
+            .and_then(|i| i.as_u64())
+            .and_then(|i| accessors?.get(i as usize))
+    };
+    let count = |accessor: Option<&serde_json::Value>| accessor.and_then(|a| a.get("count")?.as_u64());
+
+    let meshes: &[serde_json::Value] = gltf.get("meshes").and_then(|m| m.as_array()).map_or(&[], Vec::as_slice);
+    let mut vertex_count = 0u64;
+    let mut triangle_count = 0u64;
+    let mut submesh_count = 0u32;
+    let mut has_normals = false;
+    let mut has_uvs = false;
+    // Position bounds of each mesh, in its own space
+    let mut mesh_bounds: Vec<Option<[f64; 6]>> = Vec::with_capacity(meshes.len());
+    for mesh in meshes {
+        let mut bounds: Option<[f64; 6]> = None;
+        let primitives = mesh.get("primitives").and_then(|p| p.as_array()).map_or(&[][..], Vec::as_slice);
+        for primitive in primitives {
+            let attributes = primitive.get("attributes");
+            let position = accessor(attributes.and_then(|a| a.get("POSITION")));
+            let vertices = count(position).unwrap_or(0);
+            // Without indices every vertex is drawn once, in order
+            let drawn = count(accessor(primitive.get("indices"))).unwrap_or(vertices);
+
+            submesh_count += 1;
+            vertex_count += vertices;
+            triangle_count += match primitive.get("mode").and_then(|m| m.as_u64()).unwrap_or(4) {
+                4 => drawn / 3,
+                // Triangle strip and fan
+                5 | 6 => drawn.saturating_sub(2),
+                // Points and lines
+                _ => 0,
+            };
+            has_normals |= attributes.is_some_and(|a| a.get("NORMAL").is_some());
+            has_uvs |= attributes.is_some_and(|a| a.get("TEXCOORD_0").is_some());
+            if let Some(b) = position.and_then(accessor_bounds) {
+                bounds = Some(union_bounds(bounds, b));
+            }
+        }
+        mesh_bounds.push(bounds);
+    }
+
+    let scene_bounds = gltf_scene_bounds(&gltf, &mesh_bounds);
+    let bounds = scene_bounds.or_else(|| mesh_bounds.iter().flatten().copied().reduce(|a, b| union_bounds(Some(a), b)));
+    Some(ModelInfo {
+        vertex_count: Some(vertex_count),
+        triangle_count: Some(triangle_count),
+        submesh_count: Some(submesh_count),
+        has_normals,
+        has_uvs,
+        bounds: bounds.map(|b| b.map(|v| v as f32)),
+    })
+}
+
+/// The JSON of a .gltf, or the JSON chunk of a .glb: a 12-byte header
+/// (`glTF`, version 2, total length) and then chunks of length, type and data,
+/// the first of which is always the JSON
+fn read_gltf_json(path: &Path) -> Option<serde_json::Value> {
+    let data = fs::read(path).ok()?;
+    if !data.starts_with(b"glTF") {
+        return serde_json::from_slice(&data).ok();
+    }
+    let read_u32 = |offset: usize| Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?));
+    if read_u32(4)? != 2 {
+        return None;
+    }
+    let length = read_u32(12)? as usize;
+    if data.get(16..20)? != b"JSON" {
+        return None;
+    }
+    serde_json::from_slice(data.get(20..20 + length)?).ok()
+}
+
+/// `min` and `max` of a POSITION accessor, which the spec requires
+fn accessor_bounds(accessor: &serde_json::Value) -> Option<[f64; 6]> {
+    let corner = |key: &str| -> Option<Vec<f64>> {
+        let values: Vec<f64> = accessor.get(key)?.as_array()?.iter().filter_map(|v| v.as_f64()).collect();
+        (values.len() == 3).then_some(values)
+    };
+    let (min, max) = (corner("min")?, corner("max")?);
+    Some([min[0], min[1], min[2], max[0], max[1], max[2]])
+}
+
+pub(crate) fn union_bounds(a: Option<[f64; 6]>, b: [f64; 6]) -> [f64; 6] {
+    let Some(a) = a else {
+        return b;
+    };
+    [
+        a[0].min(b[0]),
+        a[1].min(b[1]),
+        a[2].min(b[2]),
+        a[3].max(b[3]),
+        a[4].max(b[4]),
+        a[5].max(b[5]),
+    ]
+}
+
+/// Bounds of every mesh placed by the default scene (or the first), with
+/// each node's transform applied. `None` for a file without scenes.
+fn gltf_scene_bounds(gltf: &serde_json::Value, mesh_bounds: &[Option<[f64; 6]>]) -> Option<[f64; 6]> {
+    let scenes = gltf.get("scenes")?.as_array()?;
+    let scene_index = gltf.get("scene").and_then(|s| s.as_u64()).unwrap_or(0) as usize;
+    let roots = scenes.get(scene_index)?.get("nodes")?.as_array()?;
+    let nodes = gltf.get("nodes").and_then(|n| n.as_array()).map_or(&[][..], Vec::as_slice);
+
+    let mut bounds = None;
+    // Nodes form a tree, so each is placed once; a broken file listing one
+    // twice, as a shared child or in a cycle, doesn't place it again
+    let mut placed = HashSet::new();
+    let mut stack: Vec<(usize, [f64; 16])> =
+        roots.iter().filter_map(|n| Some((n.as_u64()? as usize, IDENTITY_MATRIX))).collect();
+    while let Some((index, parent)) = stack.pop() {
+        let Some(node) = nodes.get(index) else {
+            continue;
+        };
+        if !placed.insert(index) {
+            continue;
+        }
+        let transform = multiply(&parent, &node_transform(node));
+        let mesh = node.get("mesh").and_then(|m| m.as_u64());
+        if let Some(Some(local)) = mesh.and_then(|m| mesh_bounds.get(m as usize)) {
+            bounds = Some(union_bounds(bounds, transform_bounds(&transform, local)));
+        }
+        for child in node.get("children").and_then(|c| c.as_array()).into_iter().flatten() {
+            if let Some(child) = child.as_u64() {
+                stack.push((child as usize, transform));
+            }
+        }
+    }
+    bounds
+}
+
+/// A node's local transform, column-major: its `matrix`, or translation *
+/// rotation * scale
+fn node_transform(node: &serde_json::Value) -> [f64; 16] {
+    let numbers = |key: &str, len: usize| -> Option<Vec<f64>> {
+        let values: Vec<f64> = node.get(key)?.as_array()?.iter().filter_map(|v| v.as_f64()).collect();
+        (values.len() == len).then_some(values)
+    };
+    if let Some(matrix) = numbers("matrix", 16) {
+        let mut m = [0.0; 16];
+        m.copy_from_slice(&matrix);
+        return m;
+    }
+
+    let t = numbers("translation", 3).unwrap_or_else(|| vec![0.0, 0.0, 0.0]);
+    let q = numbers("rotation", 4).unwrap_or_else(|| vec![0.0, 0.0, 0.0, 1.0]);
+    let s = numbers("scale", 3).unwrap_or_else(|| vec![1.0, 1.0, 1.0]);
+    let (x, y, z, w) = (q[0], q[1], q[2], q[3]);
+    [
+        (1.0 - 2.0 * (y * y + z * z)) * s[0],
+        2.0 * (x * y + z * w) * s[0],
+        2.0 * (x * z - y * w) * s[0],
+        0.0,
+        2.0 * (x * y - z * w) * s[1],
+        (1.0 - 2.0 * (x * x + z * z)) * s[1],
+        2.0 * (y * z + x * w) * s[1],
+        0.0,
+        2.0 * (x * z + y * w) * s[2],
+        2.0 * (y * z - x * w) * s[2],
+        (1.0 - 2.0 * (x * x + y * y)) * s[2],
+        0.0,
+        t[0],
+        t[1],
+        t[2],
+        1.0,
+    ]
+}
+
+pub(crate) fn multiply(a: &[f64; 16], b: &[f64; 16]) -> [f64; 16] {
+    let mut m = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            m[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+    m
+}
+
+pub(crate) fn transform_point(m: &[f64; 16], [x, y, z]: [f64; 3]) -> [f64; 3] {
+    [
+        m[0] * x + m[4] * y + m[8] * z + m[12],
+        m[1] * x + m[5] * y + m[9] * z + m[13],
+        m[2] * x + m[6] * y + m[10] * z + m[14],
+    ]
+}
+
+/// Box around the eight corners of `local` moved by `m`
+pub(crate) fn transform_bounds(m: &[f64; 16], local: &[f64; 6]) -> [f64; 6] {
+    let mut bounds = None;
+    for corner in 0..8 {
+        let point = [
+            local[if corner & 1 == 0 { 0 } else { 3 }],
+            local[if corner & 2 == 0 { 1 } else { 4 }],
+            local[if corner & 4 == 0 { 2 } else { 5 }],
+        ];
+        let [x, y, z] = transform_point(m, point);
+        bounds = Some(union_bounds(bounds, [x, y, z, x, y, z]));
+    }
+    bounds.unwrap_or(*local)
+}
+
+/// Create a placeholder image for materials without loadable textures
+fn create_material_placeholder(info: &MaterialInfo, size: u32) -> RgbaImage {
+    let mut img = RgbaImage::new(size, size);
+
+    // Base color - gradient based on texture slots
+    let has_normal = info.textures.iter().any(|t| t.slot_name.to_lowercase().contains("normal") || t.slot_name.to_lowercase().contains("bump"));
+    let has_metallic = info.textures.iter().any(|t| t.slot_name.to_lowercase().contains("metallic") || t.slot_name.to_lowercase().contains("specular"));
+    let has_emission = info.textures.iter().any(|t| t.slot_name.to_lowercase().contains("emission"));
+
+    // Create a stylized material icon
+    for y in 0..size {
+        for x in 0..size {
+            let fx = x as f32 / size as f32;
+            let fy = y as f32 / size as f32;
+
+            // Sphere-like shading
+            let cx = fx - 0.5;
+            let cy = fy - 0.5;
+            let dist = (cx * cx + cy * cy).sqrt();
+
+            if dist < 0.45 {
+                let shade = 1.0 - (dist / 0.45);
+                let highlight = if cx + cy < -0.2 { 0.3 } else { 0.0 };
+
+                let r = if has_emission { 200 } else { 100 };
+                let g = if has_metallic { 120 } else { 100 };
+                let b = if has_normal { 140 } else { 120 };
+
+                let r = ((r as f32 * shade + highlight * 255.0).min(255.0)) as u8;
+                let g = ((g as f32 * shade + highlight * 255.0).min(255.0)) as u8;
+                let b = ((b as f32 * shade + highlight * 255.0).min(255.0)) as u8;
+
+                img.put_pixel(x, y, Rgba([r, g, b, 255]));
+            } else {
+                img.put_pixel(x, y, Rgba([30, 30, 35, 255]));
+            }
+        }
+    }
+
+    img
+}
+
+/// Lay images out in a `size` square: one fills it, two sit side by side and
+/// three or four form a 2x2 grid, a lone last image taking the full bottom row.
+/// Each image is cropped to fill its cell.
+fn compose_tiles(images: &[DynamicImage], size: u32) -> RgbaImage {
+    let mut canvas = RgbaImage::from_pixel(size, size, Rgba([30, 30, 35, 255]));
+    let columns = if images.len() == 1 { 1 } else { 2 };
+    let rows = images.len().div_ceil(columns);
+
+    for (row, row_images) in images.chunks(columns).enumerate() {
+        let top = size * row as u32 / rows as u32;
+        let bottom = size * (row as u32 + 1) / rows as u32;
+        for (column, image) in row_images.iter().enumerate() {
+            let left = size * column as u32 / row_images.len() as u32;
+            let right = size * (column as u32 + 1) / row_images.len() as u32;
+            if right <= left || bottom <= top {
+                continue;
+            }
+            let cell = image.resize_to_fill(right - left, bottom - top, image::imageops::FilterType::Triangle);
+            image::imageops::replace(&mut canvas, &cell.to_rgba8(), left as i64, top as i64);
+        }
+    }
+
+    canvas
+}
+
+/// Encode a thumbnail in the configured format
+fn save_thumbnail(img: &DynamicImage, path: &Path, options: &ThumbnailOptions) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| e.to_string())?;
+    let writer = BufWriter::new(file);
+
+    let result = match options.format.as_str() {
+        "jpeg" => {
+            // JPEG has no alpha channel
+            let rgb = img.to_rgb8();
+            JpegEncoder::new_with_quality(writer, options.quality).write_image(
+                &rgb,
+                rgb.width(),
+                rgb.height(),
+                ExtendedColorType::Rgb8,
+            )
+        }
+        "webp" => {
+            let rgba = img.to_rgba8();
+            WebPEncoder::new_lossless(writer).write_image(
+                &rgba,
+                rgba.width(),
+                rgba.height(),
+                ExtendedColorType::Rgba8,
+            )
+        }
+        _ => {
+            let rgba = img.to_rgba8();
+            PngEncoder::new(writer).write_image(
+                &rgba,
+                rgba.width(),
+                rgba.height(),
+                ExtendedColorType::Rgba8,
+            )
+        }
+    };
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(path);
+        e.to_string()
+    })
+}
+
+fn md5_hash(input: &str) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Width and height from the file header, without decoding any pixels
+pub fn read_image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let is_psd = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("psd"));
+
+    if is_psd {
+        read_psd_dimensions(path)
+    } else {
+        image::image_dimensions(path).ok()
+    }
+}
+
+/// Whether the image stores an alpha channel, from the file header
+pub fn read_image_has_alpha(path: &Path) -> Option<bool> {
+    let is_psd = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("psd"));
+
+    if is_psd {
+        read_psd_header(path).map(|header| header.channels >= 4)
+    } else {
+        let decoder = image::ImageReader::open(path).ok()?.with_guessed_format().ok()?.into_decoder().ok()?;
+        Some(image::ImageDecoder::color_type(&decoder).has_alpha())
+    }
+}
+
+fn read_psd_dimensions(path: &Path) -> Option<(u32, u32)> {
+    read_psd_header(path).map(|header| (header.width, header.height))
+}
+
+struct PsdHeader {
+    channels: u16,
+    width: u32,
+    height: u32,
+}
+
+/// PSD header: signature, version, reserved bytes and channel count,
+/// followed by big-endian height and width
+fn read_psd_header(path: &Path) -> Option<PsdHeader> {
+    let mut header = [0u8; 22];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if &header[0..4] != b"8BPS" {
+        return None;
+    }
+
+    Some(PsdHeader {
+        channels: u16::from_be_bytes(header[12..14].try_into().ok()?),
+        height: u32::from_be_bytes(header[14..18].try_into().ok()?),
+        width: u32::from_be_bytes(header[18..22].try_into().ok()?),
+    })
+}
+
+/// Decode an image with allocations capped by the decode budget
+pub fn open_image_limited(path: &Path, options: &ThumbnailOptions) -> image::ImageResult<DynamicImage> {
+    let mut reader = image::ImageReader::open(path)?.with_guessed_format()?;
+    reader.limits(options.decode_limits());
+    reader.decode()
+}
+
+/// Load an image with a timeout to prevent hanging on problematic files
+fn load_image_with_timeout(
+    path: &Path,
+    options: &ThumbnailOptions,
+    timeout_secs: u64,
+) -> Result<DynamicImage, String> {
+    let path_owned = path.to_path_buf();
+    let options = options.clone();
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let result = open_image_limited(&path_owned, &options);
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(Duration::from_secs(timeout_secs)) {
+        Ok(Ok(img)) => Ok(img),
+        Ok(Err(e)) => Err(format!("Image load error: {}", e)),
+        Err(_) => Err("Image load timed out".to_string()),
+    }
+}
+
+/// Load a PSD file with timeout protection
+fn load_psd_with_timeout(path: &Path, timeout_secs: u64) -> Result<DynamicImage, String> {
+    let path_owned = path.to_path_buf();
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        use std::panic::AssertUnwindSafe;
+
+        let result = match std::fs::read(&path_owned) {
+            Ok(data) => {
+                let data_ref = &data;
+                let psd_result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    psd::Psd::from_bytes(data_ref).ok().and_then(|psd| {
+                        let rgba = psd.rgba();
+                        let width = psd.width();
+                        let height = psd.height();
+                        image::RgbaImage::from_raw(width, height, rgba)
+                    })
+                }));
+
+                match psd_result {
+                    Ok(Some(img)) => Ok(DynamicImage::ImageRgba8(img)),
+                    Ok(None) => Err("Failed to parse PSD".to_string()),
+                    Err(_) => Err("PSD parsing panicked".to_string()),
+                }
+            }
+            Err(e) => Err(format!("Failed to read file: {}", e)),
+        };
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(Duration::from_secs(timeout_secs)) {
+        Ok(result) => result,
+        Err(_) => Err("PSD load timed out".to_string()),
+    }
+}
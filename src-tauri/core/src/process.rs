@@ -0,0 +1,75 @@
+//! Running external tools (Blender, texture converters) with a time limit,
+//! without a console window and with their stderr kept for error messages
+
+use std::io::Read;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// Most tool output is noise; only the end of stderr is worth reporting
+const MAX_ERROR_CHARS: usize = 500;
+
+pub struct ToolOutput {
+    pub status: ExitStatus,
+    pub stderr: String,
+}
+
+impl ToolOutput {
+    /// The last few hundred characters of stderr
+    pub fn error_tail(&self) -> &str {
+        let stderr = self.stderr.trim();
+        let start = stderr
+            .char_indices()
+            .rev()
+            .nth(MAX_ERROR_CHARS - 1)
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        &stderr[start..]
+    }
+}
+
+/// Run `command` to completion, killing it after `timeout`. `name` is used
+/// in error messages.
+pub fn run_with_timeout(mut command: Command, name: &str, timeout: Duration) -> Result<ToolOutput, String> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console for every file
+        command.creation_flags(0x0800_0000);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start {} ({}): {}", name, command.get_program().to_string_lossy(), e))?;
+
+    // Drain stderr on another thread so a chatty tool can't fill the pipe and block
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{} timed out after {}s", name, timeout.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for {}: {}", name, e)),
+        }
+    };
+    let stderr = stderr_reader
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+
+    Ok(ToolOutput { status, stderr })
+}
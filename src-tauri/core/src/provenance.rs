@@ -0,0 +1,307 @@
+//! Provenance of files brought into a project from a library or an Asset
+//! Store package: where each came from and the SHA-256 it had when copied.
+//! Records are kept by path in the receiving project, so they exist before
+//! the next scan indexes the copies and follow an asset moved in the app.
+//!
+//! `check_updates` compares each source with what was copied and lists the
+//! copies whose source has a newer version; `reimport` brings one over.
+
+use crate::asset_store;
+use crate::db::{Database, Project};
+use crate::duplicates::sha256_file;
+use crate::error::{AppError, AppResult};
+use crate::scanner::modified_secs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const SOURCE_LIBRARY: &str = "library";
+pub const SOURCE_ASSET_STORE: &str = "asset_store";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    pub project_id: String,
+    /// Path of the copy in the project
+    pub relative_path: String,
+    /// The copy's asset once a scan has indexed it
+    pub asset_id: Option<String>,
+    /// "library" or "asset_store"
+    pub source_kind: String,
+    /// Library project id or Asset Store package id
+    pub source_id: String,
+    /// Library or package name when copied
+    pub source_name: String,
+    /// Path in the library, or the package's import path
+    pub source_path: String,
+    /// SHA-256 of the file as copied
+    pub content_hash: String,
+    pub source_size: i64,
+    /// Of the library file, or of the package for the Asset Store
+    pub source_modified_time: i64,
+    pub imported_at: i64,
+}
+
+/// The library or package files were copied from
+pub struct ProvenanceSource<'a> {
+    pub kind: &'a str,
+    pub id: &'a str,
+    pub name: &'a str,
+}
+
+/// Provenance of a file just copied to `dest_file` from `source_path`, or
+/// `None` when the copy isn't inside `project`
+pub fn imported(
+    project: &Project,
+    dest_file: &Path,
+    source: &ProvenanceSource,
+    source_path: &str,
+    source_modified_time: i64,
+) -> AppResult<Option<Provenance>> {
+    let Ok(relative) = dest_file.strip_prefix(&project.root_path) else {
+        return Ok(None);
+    };
+    Ok(Some(Provenance {
+        project_id: project.id.clone(),
+        relative_path: relative.to_string_lossy().to_string(),
+        asset_id: None,
+        source_kind: source.kind.to_string(),
+        source_id: source.id.to_string(),
+        source_name: source.name.to_string(),
+        source_path: source_path.to_string(),
+        content_hash: sha256_file(dest_file)?,
+        source_size: fs::metadata(dest_file)?.len() as i64,
+        source_modified_time,
+        imported_at: chrono::Utc::now().timestamp(),
+    }))
+}
+
+/// Store provenance records, logging rather than failing: the files are
+/// already copied by the time this runs
+pub fn record(db: &Database, records: &[Provenance]) {
+    if records.is_empty() {
+        return;
+    }
+    if let Err(e) = db.record_provenance(records) {
+        tracing::warn!("Failed to record provenance of {} files: {}", records.len(), e);
+    }
+}
+
+/// A project copy whose source no longer matches what was copied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceUpdate {
+    pub provenance: Provenance,
+    /// "updated" when the source has another version now, "source_missing"
+    /// when the library, package or file is gone
+    pub status: String,
+    /// SHA-256 of the source's current version
+    pub source_hash: Option<String>,
+    pub source_modified_time: Option<i64>,
+    /// The copy was edited in the project after it was copied in, so
+    /// re-importing would discard those edits
+    pub local_changes: bool,
+    /// The copy is no longer in the project
+    pub copy_missing: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub checked: usize,
+    pub current: usize,
+    pub updated: usize,
+    pub source_missing: usize,
+    /// Every copy that isn't current, updated ones first
+    pub updates: Vec<SourceUpdate>,
+}
+
+/// A package's modification time and the hashes of files read from it
+type PackageState = (i64, HashMap<String, String>);
+
+enum SourceState {
+    Unchanged,
+    Changed { hash: String, modified_time: i64 },
+    Missing,
+}
+
+/// Compare the source of every copied-in file of a project with what was
+/// copied. Sources whose size and modification time match the record are
+/// taken as unchanged without hashing.
+pub fn check_updates(db: &Database, project_id: &str) -> AppResult<UpdateCheck> {
+    let project = db
+        .get_project(project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", project_id)))?;
+    let records = db.get_provenance(project_id, None)?;
+    let packages = store_package_hashes(db, &records)?;
+
+    let mut check = UpdateCheck {
+        checked: records.len(),
+        current: 0,
+        updated: 0,
+        source_missing: 0,
+        updates: Vec::new(),
+    };
+    for record in records {
+        let state = match record.source_kind.as_str() {
+            SOURCE_LIBRARY => library_source_state(db, &record)?,
+            _ => match packages.get(&record.source_id) {
+                Some(Some((modified_time, _))) if *modified_time == record.source_modified_time => {
+                    SourceState::Unchanged
+                }
+                Some(Some((modified_time, hashes))) => match hashes.get(&record.source_path) {
+                    Some(hash) if *hash == record.content_hash => SourceState::Unchanged,
+                    Some(hash) => SourceState::Changed {
+                        hash: hash.clone(),
+                        modified_time: *modified_time,
+                    },
+                    None => SourceState::Missing,
+                },
+                _ => SourceState::Missing,
+            },
+        };
+        if matches!(state, SourceState::Unchanged) {
+            check.current += 1;
+            continue;
+        }
+
+        let copy_hash = sha256_file(&Path::new(&project.root_path).join(&record.relative_path)).ok();
+        let (status, source_hash, source_modified_time) = match state {
+            // Already brought up to date by hand
+            SourceState::Changed { hash, .. } if copy_hash.as_ref() == Some(&hash) => {
+                check.current += 1;
+                continue;
+            }
+            SourceState::Changed { hash, modified_time } => {
+                check.updated += 1;
+                ("updated", Some(hash), Some(modified_time))
+            }
+            _ => {
+                check.source_missing += 1;
+                ("source_missing", None, None)
+            }
+        };
+        check.updates.push(SourceUpdate {
+            local_changes: copy_hash.as_ref().is_some_and(|hash| *hash != record.content_hash),
+            copy_missing: copy_hash.is_none(),
+            provenance: record,
+            status: status.to_string(),
+            source_hash,
+            source_modified_time,
+        });
+    }
+    check.updates.sort_by(|a, b| {
+        (a.status != "updated")
+            .cmp(&(b.status != "updated"))
+            .then_with(|| a.provenance.relative_path.cmp(&b.provenance.relative_path))
+    });
+    Ok(check)
+}
+
+fn library_source_state(db: &Database, record: &Provenance) -> AppResult<SourceState> {
+    let Some(library) = db.get_project(&record.source_id)? else {
+        return Ok(SourceState::Missing);
+    };
+    let source = Path::new(&library.root_path).join(&record.source_path);
+    let Ok(metadata) = fs::metadata(&source) else {
+        return Ok(SourceState::Missing);
+    };
+    let modified_time = modified_secs(&metadata);
+    if modified_time == record.source_modified_time && metadata.len() as i64 == record.source_size {
+        return Ok(SourceState::Unchanged);
+    }
+    let hash = sha256_file(&source)?;
+    Ok(if hash == record.content_hash {
+        SourceState::Unchanged
+    } else {
+        SourceState::Changed { hash, modified_time }
+    })
+}
+
+/// Modification time of each Asset Store package files were taken from, and
+/// the hashes of those files where the package changed since; each package
+/// is read once. `None` for a package no longer in the cache.
+fn store_package_hashes(db: &Database, records: &[Provenance]) -> AppResult<HashMap<String, Option<PackageState>>> {
+    let mut packages = HashMap::new();
+    for record in records.iter().filter(|r| r.source_kind == SOURCE_ASSET_STORE) {
+        if packages.contains_key(&record.source_id) {
+            continue;
+        }
+        let package = db
+            .get_store_package(&record.source_id)?
+            .and_then(|p| fs::metadata(&p.path).ok().map(|m| (modified_secs(&m), p)));
+        let state = package.map(|(modified_time, package)| {
+            let stale: Vec<String> = records
+                .iter()
+                .filter(|r| r.source_id == package.id && r.source_modified_time != modified_time)
+                .map(|r| r.source_path.clone())
+                .collect();
+            let hashes = if stale.is_empty() {
+                HashMap::new()
+            } else {
+                asset_store::package_file_hashes(db, &package, &stale).unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read {}: {}", package.path, e);
+                    HashMap::new()
+                })
+            };
+            (modified_time, hashes)
+        });
+        packages.insert(record.source_id.clone(), state);
+    }
+    Ok(packages)
+}
+
+/// Replace the project copy at `relative_path` with its source's current
+/// version and record that as its provenance. The copy's `.meta` is left
+/// alone, so its GUID and references are kept.
+pub fn reimport(db: &Database, project_id: &str, relative_path: &str) -> AppResult<Provenance> {
+    let project = db
+        .get_project(project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", project_id)))?;
+    let record = db
+        .get_provenance(project_id, None)?
+        .into_iter()
+        .find(|r| r.relative_path == relative_path)
+        .ok_or_else(|| AppError::NotFound(format!("No provenance recorded for {}", relative_path)))?;
+    let target = Path::new(&project.root_path).join(&record.relative_path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let (source_name, modified_time) = match record.source_kind.as_str() {
+        SOURCE_LIBRARY => {
+            let library = db
+                .get_project(&record.source_id)?
+                .ok_or_else(|| AppError::NotFound(format!("Library {}", record.source_name)))?;
+            let source = Path::new(&library.root_path).join(&record.source_path);
+            let metadata = fs::metadata(&source)
+                .map_err(|_| AppError::NotFound(format!("{} is no longer in {}", record.source_path, library.name)))?;
+            fs::copy(&source, &target)?;
+            (library.name, modified_secs(&metadata))
+        }
+        SOURCE_ASSET_STORE => {
+            let package = db
+                .get_store_package(&record.source_id)?
+                .ok_or_else(|| AppError::NotFound(format!("Asset Store package {}", record.source_name)))?;
+            let modified_time = modified_secs(&fs::metadata(&package.path)?);
+            if !asset_store::extract_file_to(db, &package, &record.source_path, &target)? {
+                return Err(AppError::NotFound(format!(
+                    "{} is no longer in {}",
+                    record.source_path, package.name
+                )));
+            }
+            (package.name, modified_time)
+        }
+        other => return Err(AppError::InvalidArgument(format!("Unknown provenance source: {}", other))),
+    };
+
+    let source = ProvenanceSource {
+        kind: &record.source_kind,
+        id: &record.source_id,
+        name: &source_name,
+    };
+    let mut updated = imported(&project, &target, &source, &record.source_path, modified_time)?
+        .ok_or_else(|| AppError::Custom(format!("{} is outside {}", target.display(), project.name)))?;
+    updated.asset_id = record.asset_id;
+    db.record_provenance(std::slice::from_ref(&updated))?;
+    tracing::info!("Re-imported {} from {}", record.relative_path, source_name);
+    Ok(updated)
+}
@@ -0,0 +1,319 @@
+//! Render-pipeline migration audit: which pipelines each material's shader
+//! renders in, and the materials (with per-folder rollups) that would break
+//! when the project moves to a target pipeline.
+//!
+//! Materials name their shader only by GUID and file id. Those are resolved
+//! to Unity's built-in shaders, the URP/HDRP package shaders listed below or
+//! a shader in the project, whose source says which pipelines it targets.
+//! Shaders that can't be placed are reported as unresolved rather than
+//! guessed at.
+
+use crate::db::{Asset, Database};
+use crate::error::{AppError, AppResult};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Materials listed per section; counts include all of them
+pub const DEFAULT_LIMIT: usize = 500;
+
+pub const PIPELINES: &[&str] = &["builtin", "urp", "hdrp"];
+
+/// Shader source read to find its name and pipeline tags
+const MAX_SHADER_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Unity's built-in shaders live in these two resource files
+const BUILTIN_GUIDS: &[&str] = &["0000000000000000f000000000000000", "0000000000000000e000000000000000"];
+
+/// Built-in shaders by file id that are known by name; sprite and UI
+/// shaders still draw in the scriptable pipelines
+const BUILTIN_SHADERS: &[(i64, &str, &[&str])] = &[
+    (45, "Standard (Specular setup)", &["builtin"]),
+    (46, "Standard", &["builtin"]),
+    (10753, "Sprites/Default", &["builtin", "urp", "hdrp"]),
+    (10770, "UI/Default", &["builtin", "urp", "hdrp"]),
+];
+
+/// Lit and unlit shaders of the URP and HDRP packages, which aren't indexed
+const PACKAGE_SHADERS: &[(&str, &str, &str)] = &[
+    ("933532a4fcc9baf4fa0491de14d08ed7", "Universal Render Pipeline/Lit", "urp"),
+    ("8d2bb70cbf9db8d4da26e15b26e74248", "Universal Render Pipeline/Simple Lit", "urp"),
+    ("650dd9526735d5b46b79224bc6e94025", "Universal Render Pipeline/Unlit", "urp"),
+    ("6e4ae4064600d784cac1e41a9e6f2e59", "HDRP/Lit", "hdrp"),
+    ("c4edd00ff2db5b24391a4fcb1762e459", "HDRP/Unlit", "hdrp"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialPipeline {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub shader_name: Option<String>,
+    /// The shader's file, when it is in the project
+    pub shader_path: Option<String>,
+    /// Pipelines the shader renders in; empty when it couldn't be resolved
+    pub pipelines: Vec<String>,
+    /// Assets that reference the material directly
+    pub dependents: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineFolder {
+    pub folder: String,
+    pub materials: usize,
+    pub breaking: usize,
+    pub unresolved: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderPipelineReport {
+    pub project_id: String,
+    /// Pipeline the project would move to
+    pub target: String,
+    pub materials_checked: usize,
+    /// Materials per pipeline their shader renders in ("unknown" for
+    /// unresolved); a shader for several pipelines counts for each
+    pub by_pipeline: BTreeMap<String, usize>,
+    pub breaking_count: usize,
+    /// Materials whose shader doesn't render in the target pipeline, the
+    /// most referenced first
+    pub breaking: Vec<MaterialPipeline>,
+    pub unresolved_count: usize,
+    pub unresolved: Vec<MaterialPipeline>,
+    /// Distinct assets referencing a breaking material
+    pub affected_assets: usize,
+    /// Folders holding breaking or unresolved materials, worst first
+    pub folders: Vec<PipelineFolder>,
+}
+
+/// A shader a material points at
+struct ResolvedShader {
+    name: Option<String>,
+    path: Option<String>,
+    pipelines: Vec<String>,
+}
+
+pub fn audit_project(db: &Database, project_id: &str, target: &str, limit: usize) -> AppResult<RenderPipelineReport> {
+    if !PIPELINES.contains(&target) {
+        return Err(AppError::InvalidArgument(format!(
+            "Unknown render pipeline: {} (expected builtin, urp or hdrp)",
+            target
+        )));
+    }
+    if db.get_project(project_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Project {}", project_id)));
+    }
+
+    let project_shaders: HashMap<String, Asset> = db
+        .get_assets_by_types(project_id, &["shader".to_string()])?
+        .into_iter()
+        .filter_map(|a| a.unity_guid.clone().map(|guid| (guid, a)))
+        .collect();
+    let mut dependents: HashMap<String, HashSet<String>> = HashMap::new();
+    for dep in db.get_project_dependencies(project_id)? {
+        if let Some(to) = dep.to_asset_id {
+            dependents.entry(to).or_default().insert(dep.from_asset_id);
+        }
+    }
+
+    let shader_re = shader_reference_regex()?;
+    let mut resolved: HashMap<(i64, String), ResolvedShader> = HashMap::new();
+    let mut materials = Vec::new();
+    for material in db.get_assets_by_types(project_id, &["material".to_string()])? {
+        let reference = read_shader_reference(&shader_re, Path::new(&material.absolute_path));
+        let shader: Option<&ResolvedShader> = reference.map(|key| {
+            &*resolved
+                .entry(key.clone())
+                .or_insert_with(|| resolve_shader(key.0, &key.1, &project_shaders))
+        });
+        materials.push(MaterialPipeline {
+            dependents: dependents.get(&material.id).map_or(0, |d| d.len()),
+            asset_id: material.id,
+            relative_path: material.relative_path,
+            shader_name: shader.and_then(|s| s.name.clone()),
+            shader_path: shader.and_then(|s| s.path.clone()),
+            pipelines: shader.map(|s| s.pipelines.clone()).unwrap_or_default(),
+        });
+    }
+
+    let mut by_pipeline: BTreeMap<String, usize> = BTreeMap::new();
+    let mut folders: BTreeMap<String, PipelineFolder> = BTreeMap::new();
+    let mut breaking = Vec::new();
+    let mut unresolved = Vec::new();
+    for material in &materials {
+        if material.pipelines.is_empty() {
+            *by_pipeline.entry("unknown".to_string()).or_default() += 1;
+        }
+        for pipeline in &material.pipelines {
+            *by_pipeline.entry(pipeline.clone()).or_default() += 1;
+        }
+
+        let folder = Path::new(&material.relative_path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let rollup = folders.entry(folder.clone()).or_insert_with(|| PipelineFolder {
+            folder,
+            materials: 0,
+            breaking: 0,
+            unresolved: 0,
+        });
+        rollup.materials += 1;
+        if material.pipelines.is_empty() {
+            rollup.unresolved += 1;
+            unresolved.push(material);
+        } else if !material.pipelines.iter().any(|p| p == target) {
+            rollup.breaking += 1;
+            breaking.push(material);
+        }
+    }
+
+    let affected_assets = breaking
+        .iter()
+        .filter_map(|m| dependents.get(&m.asset_id))
+        .flatten()
+        .collect::<HashSet<_>>()
+        .len();
+    breaking.sort_by(|a, b| b.dependents.cmp(&a.dependents).then_with(|| a.relative_path.cmp(&b.relative_path)));
+    unresolved.sort_by(|a, b| b.dependents.cmp(&a.dependents).then_with(|| a.relative_path.cmp(&b.relative_path)));
+    let mut folders: Vec<PipelineFolder> =
+        folders.into_values().filter(|f| f.breaking > 0 || f.unresolved > 0).collect();
+    folders.sort_by(|a, b| {
+        b.breaking
+            .cmp(&a.breaking)
+            .then_with(|| b.unresolved.cmp(&a.unresolved))
+            .then_with(|| a.folder.cmp(&b.folder))
+    });
+
+    Ok(RenderPipelineReport {
+        project_id: project_id.to_string(),
+        target: target.to_string(),
+        materials_checked: materials.len(),
+        by_pipeline,
+        breaking_count: breaking.len(),
+        unresolved_count: unresolved.len(),
+        breaking: breaking.into_iter().take(limit).cloned().collect(),
+        unresolved: unresolved.into_iter().take(limit).cloned().collect(),
+        affected_assets,
+        folders,
+    })
+}
+
+/// Matches the `m_Shader` reference of a .mat file
+pub(crate) fn shader_reference_regex() -> AppResult<Regex> {
+    Regex::new(r"m_Shader:\s*\{\s*fileID:\s*(-?\d+)(?:,\s*guid:\s*([0-9a-fA-F]{32}))?")
+        .map_err(|e| AppError::Custom(e.to_string()))
+}
+
+/// The (file id, lowercase GUID) of the shader a material uses; the GUID
+/// is empty for shaders referenced by file id alone
+pub(crate) fn read_shader_reference(shader_re: &Regex, material: &Path) -> Option<(i64, String)> {
+    let content = fs::read_to_string(material).ok()?;
+    let cap = shader_re.captures(&content)?;
+    let file_id = cap[1].parse::<i64>().ok()?;
+    Some((file_id, cap.get(2).map(|g| g.as_str().to_lowercase()).unwrap_or_default()))
+}
+
+fn resolve_shader(file_id: i64, guid: &str, project_shaders: &HashMap<String, Asset>) -> ResolvedShader {
+    let pipelines = |list: &[&str]| list.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+    if BUILTIN_GUIDS.contains(&guid) {
+        return match BUILTIN_SHADERS.iter().find(|(id, _, _)| *id == file_id) {
+            Some((_, name, supported)) => ResolvedShader {
+                name: Some(name.to_string()),
+                path: None,
+                pipelines: pipelines(supported),
+            },
+            // Other built-in shaders (Legacy, Mobile, Particles, ...) are
+            // lit or written for the built-in pipeline
+            None => ResolvedShader {
+                name: None,
+                path: None,
+                pipelines: pipelines(&["builtin"]),
+            },
+        };
+    }
+    if let Some((_, name, pipeline)) = PACKAGE_SHADERS.iter().find(|(g, _, _)| *g == guid) {
+        return ResolvedShader {
+            name: Some(name.to_string()),
+            path: None,
+            pipelines: pipelines(&[pipeline]),
+        };
+    }
+    match project_shaders.get(guid) {
+        Some(shader) => {
+            let (name, pipelines) = read_shader(shader);
+            ResolvedShader {
+                name,
+                path: Some(shader.relative_path.clone()),
+                pipelines,
+            }
+        }
+        None => ResolvedShader {
+            name: None,
+            path: None,
+            pipelines: Vec::new(),
+        },
+    }
+}
+
+/// Name and target pipelines of a shader or Shader Graph in the project
+fn read_shader(shader: &Asset) -> (Option<String>, Vec<String>) {
+    let mut content = String::new();
+    let read = fs::File::open(&shader.absolute_path)
+        .and_then(|f| f.take(MAX_SHADER_BYTES).read_to_string(&mut content));
+    if read.is_err() {
+        return (None, Vec::new());
+    }
+
+    let mut pipelines = Vec::new();
+    if shader.extension.eq_ignore_ascii_case("shadergraph") {
+        // Each graph target is serialized with its type name
+        for (marker, pipeline) in [
+            ("BuiltInTarget", "builtin"),
+            ("UniversalTarget", "urp"),
+            ("HDTarget", "hdrp"),
+        ] {
+            if content.contains(marker) {
+                pipelines.push(pipeline.to_string());
+            }
+        }
+        let name = Path::new(&shader.file_name)
+            .file_stem()
+            .map(|s| format!("Shader Graphs/{}", s.to_string_lossy()));
+        return (name, pipelines);
+    }
+
+    let name = Regex::new(r#"Shader\s+"([^"]+)""#)
+        .ok()
+        .and_then(|re| re.captures(&content).map(|cap| cap[1].to_string()));
+    let tagged = |tag: &str| {
+        Regex::new(&format!(r#""{}"\s*=\s*"?([A-Za-z]+)"#, tag))
+            .map(|re| re.captures_iter(&content).map(|cap| cap[1].to_string()).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    let render_pipelines = tagged("RenderPipeline");
+    let light_modes = tagged("LightMode");
+
+    // Surface shaders and the built-in lighting passes don't render in URP/HDRP
+    let builtin_lighting = content.contains("#pragma surface")
+        || light_modes
+            .iter()
+            .any(|m| matches!(m.as_str(), "ForwardBase" | "ForwardAdd" | "Deferred" | "PrePassBase" | "Vertex"));
+    if builtin_lighting {
+        pipelines.push("builtin".to_string());
+    }
+    if render_pipelines.iter().any(|p| p.starts_with("Universal"))
+        || content.contains("com.unity.render-pipelines.universal")
+    {
+        pipelines.push("urp".to_string());
+    }
+    if render_pipelines.iter().any(|p| p == "HDRenderPipeline")
+        || content.contains("com.unity.render-pipelines.high-definition")
+    {
+        pipelines.push("hdrp".to_string());
+    }
+    (name, pipelines)
+}
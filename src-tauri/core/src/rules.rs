@@ -0,0 +1,391 @@
+//! User-defined validation rules, written in Rhai and run over every asset
+//! of a project after a scan.
+//!
+//! Each rule is a `<name>.rhai` file in the rules folder. The script runs
+//! once per asset with an `asset` map in scope (`id`, `path`, `name`,
+//! `extension`, `type`, `size`, `guid`, `modified`, `absolute_path`) and
+//! reports a violation by returning `false`, a message, or an array of
+//! messages. Returning nothing (or `true`) means the asset passes.
+//!
+//! A `// severity: error` comment (or `warning`, the default, or `info`)
+//! in the script's leading comments sets the severity. Helpers:
+//!
+//! - `image_size(asset)` → `#{ width, height }` from the image header, or `()`
+//! - `dependencies(asset)` / `dependents(asset)` → array of
+//!   `#{ path, type, guid, relation, missing }`
+//! - `folder(path)` → the path without its last segment
+//!
+//! ```rhai
+//! // severity: error
+//! if asset.type != "texture" || !asset.path.starts_with("Assets/UI/") { return; }
+//! let size = image_size(asset);
+//! if size != () && (size.width > 1024 || size.height > 1024) {
+//!     return `UI texture is ${size.width}x${size.height}, the limit is 1024`;
+//! }
+//! ```
+
+use crate::db::{Asset, Database, RuleViolation};
+use crate::error::{AppError, AppResult};
+use crate::previews::read_image_dimensions;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const RULE_EXTENSION: &str = "rhai";
+/// Script steps per asset before the rule is considered stuck
+const MAX_OPERATIONS: u64 = 100_000;
+/// A rule that fails this often is stopped for the rest of the run
+const MAX_RULE_ERRORS: usize = 20;
+
+/// A rule file as listed for the user, with its compile error if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleInfo {
+    pub name: String,
+    pub severity: String,
+    pub path: String,
+    pub error: Option<String>,
+}
+
+/// A rule that stopped or failed to compile during a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleError {
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleRunSummary {
+    pub rules: usize,
+    pub assets_checked: usize,
+    pub violations: usize,
+    pub errors: Vec<RuleError>,
+}
+
+struct CompiledRule {
+    name: String,
+    severity: String,
+    ast: AST,
+    errors: usize,
+}
+
+/// Rule files in `dir`, sorted by name
+fn rule_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == RULE_EXTENSION))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn rule_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// `// severity: ...` from the comments at the top of a script
+fn parse_severity(script: &str) -> String {
+    script
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("//"))
+        .filter_map(|line| line.trim_start_matches('/').trim().strip_prefix("severity:"))
+        .map(|value| value.trim().to_lowercase())
+        .find(|value| matches!(value.as_str(), "error" | "warning" | "info"))
+        .unwrap_or_else(|| "warning".to_string())
+}
+
+/// Rule names become file names, so keep them to something safe everywhere
+fn validate_rule_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(AppError::InvalidArgument(format!(
+            "Invalid rule name \"{}\"; use letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+pub fn list_rules(dir: &Path) -> Vec<RuleInfo> {
+    let engine = Engine::new();
+    rule_files(dir)
+        .into_iter()
+        .map(|path| {
+            let (severity, error) = match fs::read_to_string(&path) {
+                Ok(script) => (
+                    parse_severity(&script),
+                    engine.compile(&script).err().map(|e| e.to_string()),
+                ),
+                Err(e) => ("warning".to_string(), Some(e.to_string())),
+            };
+            RuleInfo {
+                name: rule_name(&path),
+                severity,
+                path: path.to_string_lossy().to_string(),
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Write a rule after checking that it compiles
+pub fn save_rule(dir: &Path, name: &str, script: &str) -> AppResult<RuleInfo> {
+    validate_rule_name(name)?;
+    Engine::new()
+        .compile(script)
+        .map_err(|e| AppError::InvalidArgument(format!("Rule does not compile: {}", e)))?;
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", name, RULE_EXTENSION));
+    fs::write(&path, script)?;
+
+    Ok(RuleInfo {
+        name: name.to_string(),
+        severity: parse_severity(script),
+        path: path.to_string_lossy().to_string(),
+        error: None,
+    })
+}
+
+pub fn delete_rule(dir: &Path, name: &str) -> AppResult<()> {
+    validate_rule_name(name)?;
+    let path = dir.join(format!("{}.{}", name, RULE_EXTENSION));
+    if !path.is_file() {
+        return Err(AppError::NotFound(format!("No rule named {}", name)));
+    }
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+pub fn has_rules(dir: &Path) -> bool {
+    !rule_files(dir).is_empty()
+}
+
+/// Run every rule in `dir` over the project's assets and replace its stored
+/// violations. `progress(checked, total)` returns false to cancel, in which
+/// case the previous violations are kept.
+pub fn run_rules(
+    db: &Database,
+    project_id: &str,
+    dir: &Path,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<RuleRunSummary> {
+    let assets = db.get_project_assets(project_id)?;
+    let deps = db.get_project_dependencies(project_id)?;
+
+    let mut summary = RuleRunSummary::default();
+    let maps: HashMap<String, Map> = assets.iter().map(|a| (a.id.clone(), asset_map(a))).collect();
+    let graph = Arc::new(DependencyIndex::new(&maps, &deps));
+    let engine = rule_engine(Arc::clone(&graph));
+
+    let mut rules = Vec::new();
+    for path in rule_files(dir) {
+        let name = rule_name(&path);
+        let compiled = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|script| {
+                let ast = engine.compile(&script).map_err(|e| e.to_string())?;
+                Ok((parse_severity(&script), ast))
+            });
+        match compiled {
+            Ok((severity, ast)) => rules.push(CompiledRule {
+                name,
+                severity,
+                ast,
+                errors: 0,
+            }),
+            Err(message) => summary.errors.push(RuleError { rule: name, message }),
+        }
+    }
+    summary.rules = rules.len();
+
+    let now = chrono::Utc::now().timestamp();
+    let mut violations = Vec::new();
+    let total = assets.len();
+
+    for (i, asset) in assets.iter().enumerate() {
+        if i % 100 == 0 && !progress(i, total) {
+            return Ok(summary);
+        }
+
+        for rule in rules.iter_mut().filter(|r| r.errors < MAX_RULE_ERRORS) {
+            let mut scope = Scope::new();
+            scope.push("asset", maps[&asset.id].clone());
+
+            match engine.eval_ast_with_scope::<Dynamic>(&mut scope, &rule.ast) {
+                Ok(result) => {
+                    for message in violation_messages(result, &rule.name) {
+                        violations.push(RuleViolation {
+                            asset_id: asset.id.clone(),
+                            relative_path: asset.relative_path.clone(),
+                            asset_type: asset.asset_type.clone(),
+                            rule: rule.name.clone(),
+                            severity: rule.severity.clone(),
+                            message,
+                            created_at: now,
+                        });
+                    }
+                }
+                Err(e) => {
+                    rule.errors += 1;
+                    if rule.errors == MAX_RULE_ERRORS {
+                        let message = format!("Stopped after {} errors, the last on {}: {}", rule.errors, asset.relative_path, e);
+                        tracing::warn!("Rule {}: {}", rule.name, message);
+                        summary.errors.push(RuleError {
+                            rule: rule.name.clone(),
+                            message,
+                        });
+                    }
+                }
+            }
+        }
+        summary.assets_checked += 1;
+    }
+    progress(total, total);
+
+    db.replace_rule_violations(project_id, &violations)?;
+    summary.violations = violations.len();
+    tracing::info!(
+        "Validated {} assets against {} rules: {} violations",
+        summary.assets_checked,
+        summary.rules,
+        summary.violations
+    );
+    Ok(summary)
+}
+
+/// What a script returned, as violation messages
+fn violation_messages(result: Dynamic, rule: &str) -> Vec<String> {
+    if result.is_unit() {
+        return Vec::new();
+    }
+    if let Ok(passed) = result.as_bool() {
+        return if passed {
+            Vec::new()
+        } else {
+            vec![format!("Failed {}", rule)]
+        };
+    }
+    if result.is_array() {
+        return result
+            .into_array()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| m.to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+    }
+    vec![result.to_string()]
+}
+
+fn asset_map(asset: &Asset) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), asset.id.clone().into());
+    map.insert("path".into(), asset.relative_path.replace('\\', "/").into());
+    map.insert("name".into(), asset.file_name.clone().into());
+    map.insert("extension".into(), asset.extension.to_lowercase().into());
+    map.insert("type".into(), asset.asset_type.clone().into());
+    map.insert("size".into(), asset.size_bytes.into());
+    map.insert("modified".into(), asset.modified_time.into());
+    map.insert(
+        "guid".into(),
+        asset.unity_guid.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map.insert("absolute_path".into(), asset.absolute_path.clone().into());
+    map
+}
+
+/// Project dependencies in the shape scripts see, keyed by asset id
+struct DependencyIndex {
+    dependencies: HashMap<String, Array>,
+    dependents: HashMap<String, Array>,
+}
+
+impl DependencyIndex {
+    fn new(assets: &HashMap<String, Map>, deps: &[crate::db::Dependency]) -> Self {
+        let mut dependencies: HashMap<String, Array> = HashMap::new();
+        let mut dependents: HashMap<String, Array> = HashMap::new();
+
+        for dep in deps {
+            let target = dep.to_asset_id.as_ref().and_then(|id| assets.get(id));
+            let field = |asset: Option<&Map>, key: &str| {
+                asset.and_then(|a| a.get(key)).cloned().unwrap_or(Dynamic::UNIT)
+            };
+
+            let mut link = Map::new();
+            link.insert("path".into(), field(target, "path"));
+            link.insert("type".into(), field(target, "type"));
+            link.insert("guid".into(), dep.to_guid.clone().into());
+            link.insert("relation".into(), dep.relation_type.clone().into());
+            link.insert("missing".into(), target.is_none().into());
+            dependencies.entry(dep.from_asset_id.clone()).or_default().push(link.into());
+
+            if let Some(to_id) = &dep.to_asset_id {
+                let source = assets.get(&dep.from_asset_id);
+                let mut link = Map::new();
+                link.insert("path".into(), field(source, "path"));
+                link.insert("type".into(), field(source, "type"));
+                link.insert("guid".into(), field(source, "guid"));
+                link.insert("relation".into(), dep.relation_type.clone().into());
+                link.insert("missing".into(), false.into());
+                dependents.entry(to_id.clone()).or_default().push(link.into());
+            }
+        }
+
+        Self {
+            dependencies,
+            dependents,
+        }
+    }
+}
+
+fn asset_field(asset: &Map, key: &str) -> String {
+    asset
+        .get(key)
+        .cloned()
+        .and_then(|v| v.into_string().ok())
+        .unwrap_or_default()
+}
+
+fn rule_engine(graph: Arc<DependencyIndex>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    engine.register_fn("image_size", |asset: Map| -> Dynamic {
+        match read_image_dimensions(Path::new(&asset_field(&asset, "absolute_path"))) {
+            Some((width, height)) => {
+                let mut size = Map::new();
+                size.insert("width".into(), (width as i64).into());
+                size.insert("height".into(), (height as i64).into());
+                size.into()
+            }
+            None => Dynamic::UNIT,
+        }
+    });
+
+    let index = Arc::clone(&graph);
+    engine.register_fn("dependencies", move |asset: Map| -> Array {
+        index.dependencies.get(&asset_field(&asset, "id")).cloned().unwrap_or_default()
+    });
+    let index = graph;
+    engine.register_fn("dependents", move |asset: Map| -> Array {
+        index.dependents.get(&asset_field(&asset, "id")).cloned().unwrap_or_default()
+    });
+
+    engine.register_fn("folder", |path: &str| -> String {
+        path.rsplit_once('/').map(|(folder, _)| folder.to_string()).unwrap_or_default()
+    });
+
+    engine
+}
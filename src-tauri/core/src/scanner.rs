@@ -0,0 +1,661 @@
+use crate::db::Asset;
+use crate::error::{AppError, AppResult};
+use crate::plugins::PluginRegistry;
+use jwalk::{Parallelism, WalkDir};
+use regex::Regex;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Map of relative_path -> (id, modified_time, size_bytes) for existing assets
+pub type ExistingAssetMap = HashMap<String, (String, i64, i64)>;
+
+/// Map of directory relative_path -> modification time in nanoseconds
+pub type DirectoryMtimeMap = HashMap<String, i64>;
+
+/// Existing index state used to reuse asset ids and detect unchanged files
+#[derive(Clone, Copy)]
+pub struct ChangeDetection<'a> {
+    pub existing: &'a ExistingAssetMap,
+    /// Skip files whose size and mtime match the index (quick scan)
+    pub skip_unchanged: bool,
+    /// Directory mtimes to compare against and record
+    pub directories: Option<&'a DirectoryMtimes>,
+}
+
+/// Directory modification times from the last completed scan, and the ones
+/// this walk sees (saved by the caller once the scan completes).
+///
+/// Adding, removing or renaming a file touches its directory, including the
+/// write-to-temp-then-rename most editors and Unity save with. A file
+/// rewritten in place doesn't, so trusting unchanged directories is opt-in.
+#[derive(Default)]
+pub struct DirectoryMtimes {
+    pub previous: DirectoryMtimeMap,
+    /// On a quick scan, take indexed files in a directory whose mtime still
+    /// matches as unchanged without statting them
+    pub trust_unchanged: bool,
+    pub current: RefCell<DirectoryMtimeMap>,
+}
+
+impl DirectoryMtimes {
+    pub fn new(previous: DirectoryMtimeMap, trust_unchanged: bool) -> Self {
+        Self {
+            previous,
+            trust_unchanged,
+            current: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn into_current(self) -> DirectoryMtimeMap {
+        self.current.into_inner()
+    }
+}
+
+/// Statistics about a scan operation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanStats {
+    pub total_files: usize,
+    pub unchanged_skipped: usize,
+    pub new_or_changed: usize,
+    /// Files that were not in the index before
+    pub new_files: usize,
+    /// Previously indexed files whose size or mtime changed
+    pub changed_files: usize,
+}
+
+pub struct Scanner {
+    ignore_patterns: Vec<String>,
+}
+
+impl Scanner {
+    pub fn new(ignore_patterns: Vec<String>) -> Self {
+        Self { ignore_patterns }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_valid_unity_project(root: &Path) -> bool {
+        root.join("Assets").is_dir() && root.join("ProjectSettings").is_dir()
+    }
+
+    pub fn is_valid_folder(root: &Path) -> bool {
+        root.is_dir()
+    }
+
+    #[allow(dead_code)]
+    pub fn scan(&self, root: &Path, project_id: &str) -> AppResult<Vec<Asset>> {
+        if !Self::is_valid_folder(root) {
+            return Err(AppError::InvalidProject(
+                "Not a valid folder".to_string(),
+            ));
+        }
+
+        let mut assets = Vec::new();
+        let now = chrono::Utc::now().timestamp();
+        let root_path = root.to_path_buf();
+        let ignore_patterns = self.ignore_patterns.clone();
+
+        for entry in WalkDir::new(root)
+            .follow_links(false)
+            .process_read_dir(move |_depth, _path, _state, children| {
+                // Filter out ignored directories to prevent descending into them
+                children.retain(|entry| {
+                    entry.as_ref().map_or(true, |e| {
+                        !should_ignore_path(&e.path(), &root_path, &ignore_patterns)
+                    })
+                });
+            })
+        {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+
+            // Skip .meta files for main asset list (we process them separately)
+            if path.extension().map(|e| e == "meta").unwrap_or(false) {
+                continue;
+            }
+
+            let asset_type = classify_file(&path);
+            if asset_type == "unknown" {
+                continue;
+            }
+
+            let relative_path = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let metadata = fs::metadata(&path)?;
+            let size_bytes = metadata.len() as i64;
+
+            let modified_time = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+
+            // Try to read Unity GUID from .meta file
+            let unity_guid = read_unity_guid(&path.with_extension(format!(
+                "{}.meta",
+                extension
+            )));
+
+            let asset = Asset {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: project_id.to_string(),
+                absolute_path: path.to_string_lossy().to_string(),
+                relative_path,
+                file_name,
+                extension,
+                asset_type: asset_type.to_string(),
+                size_bytes,
+                modified_time,
+                content_hash: None,
+                unity_guid,
+                import_type: None,
+                thumbnail_path: None,
+                created_at: now,
+                updated_at: now,
+                vcs: None,
+                build_size_bytes: None,
+                delivery: None,
+            };
+
+            assets.push(asset);
+        }
+
+        Ok(assets)
+    }
+
+    fn should_ignore(&self, path: &Path, root: &Path) -> bool {
+        should_ignore_path(path, root, &self.ignore_patterns)
+    }
+}
+
+/// Standalone helper for ignore checking (usable in closures)
+fn should_ignore_path(path: &Path, root: &Path, ignore_patterns: &[String]) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    is_ignored_relative(&relative.to_string_lossy(), ignore_patterns)
+}
+
+/// Check a project-relative path against the ignore patterns.
+/// A pattern matches a leading path prefix or any nested path segment.
+pub fn is_ignored_relative(relative_str: &str, ignore_patterns: &[String]) -> bool {
+    for pattern in ignore_patterns {
+        let pattern_normalized = pattern.trim_end_matches('/');
+        if relative_str.starts_with(pattern_normalized)
+            || relative_str.contains(&format!("/{}", pattern_normalized))
+            || relative_str.contains(&format!("\\{}", pattern_normalized))
+        {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Check that an ignore pattern is something `is_ignored_relative` can match.
+/// Patterns are literal folder or file prefixes, so wildcards are rejected
+/// rather than silently never matching.
+pub fn validate_ignore_pattern(pattern: &str) -> Result<(), String> {
+    let trimmed = pattern.trim();
+
+    if trimmed.is_empty() || trimmed == "/" {
+        return Err("Pattern is empty".to_string());
+    }
+    if trimmed.contains(['*', '?', '[', ']']) {
+        return Err("Wildcards are not supported; use a folder or file prefix like \"Temp/\"".to_string());
+    }
+    if Path::new(trimmed).is_absolute() || trimmed.starts_with('/') || trimmed.starts_with('\\') {
+        return Err("Pattern must be relative to the project root".to_string());
+    }
+    if trimmed.split(['/', '\\']).any(|segment| segment == "..") {
+        return Err("Pattern cannot contain '..'".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn classify_file(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        // Textures
+        "png" | "jpg" | "jpeg" | "tga" | "psd" | "bmp" | "gif" | "exr" | "hdr" => "texture",
+        // GPU formats, previewed through an external converter
+        "dds" | "ktx" | "ktx2" | "astc" | "pvr" | "basis" => "texture",
+
+        // Models
+        "fbx" | "obj" | "blend" | "dae" | "gltf" | "glb" | "3ds" | "max" => "model",
+
+        // Materials
+        "mat" => "material",
+
+        // Prefabs
+        "prefab" => "prefab",
+
+        // Audio
+        "wav" | "mp3" | "ogg" | "aiff" | "aif" | "flac" => "audio",
+
+        // Video (VideoClip sources)
+        "mp4" | "m4v" | "mov" | "webm" | "avi" | "wmv" | "ogv" | "mpg" | "mpeg" => "video",
+
+        // Shaders
+        "shader" | "shadergraph" | "shadersubgraph" | "compute" | "cginc" | "hlsl" | "glsl" => {
+            "shader"
+        }
+
+        // Scenes
+        "unity" => "scene",
+
+        // ScriptableObjects and other assets
+        "asset" => "scriptable_object",
+
+        // Animation
+        "anim" | "controller" | "overrideController" => "unknown", // Could add animation type later
+
+        // Scripts, indexed for content search and opening in the code editor
+        "cs" | "js" | "boo" => "script",
+
+        _ => "unknown",
+    }
+}
+
+/// `classify_file`, except that plugins get the first say on the extensions they claim
+pub fn classify_with_plugins(path: &Path, plugins: Option<&PluginRegistry>) -> Cow<'static, str> {
+    match plugins.and_then(|plugins| plugins.classify(path)) {
+        Some(asset_type) => Cow::Owned(asset_type),
+        None => Cow::Borrowed(classify_file(path)),
+    }
+}
+
+/// The GUID a Unity .meta file assigns to its asset
+pub fn read_unity_guid(meta_path: &Path) -> Option<String> {
+    if !meta_path.exists() {
+        return None;
+    }
+
+    let content = fs::read_to_string(meta_path).ok()?;
+
+    // Parse GUID from Unity .meta file
+    // Format: guid: 32hexcharacters
+    let re = Regex::new(r"guid:\s*([a-f0-9]{32})").ok()?;
+
+    re.captures(&content)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// How to walk a project tree
+#[derive(Clone, Copy)]
+pub struct WalkOptions<'a> {
+    pub ignore_patterns: &'a [String],
+    /// Directory-reading threads; 1 walks on the calling thread
+    pub threads: usize,
+    /// Only visit files after this relative path in walk order, pruning
+    /// directories that sort entirely before it (used to resume a scan)
+    pub resume_after: Option<&'a Path>,
+    /// Plugins that classify extra formats
+    pub plugins: Option<&'a PluginRegistry>,
+    /// Relative paths of files excluded one by one from the index
+    pub excluded: Option<&'a HashSet<String>>,
+}
+
+/// Walker over a project that skips ignored directories without descending into them.
+/// Entries are sorted so the walk order is stable between runs.
+fn project_walker(root: &Path, walk: WalkOptions) -> WalkDir {
+    let root_path = root.to_path_buf();
+    let patterns = walk.ignore_patterns.to_vec();
+    let resume_after = walk.resume_after.map(Path::to_path_buf);
+    let excluded = walk.excluded.cloned().unwrap_or_default();
+    let parallelism = if walk.threads <= 1 {
+        Parallelism::Serial
+    } else {
+        Parallelism::RayonNewPool(walk.threads)
+    };
+
+    WalkDir::new(root)
+        .follow_links(false)
+        .sort(true)
+        .parallelism(parallelism)
+        .process_read_dir(move |_depth, _path, _state, children| {
+            children.retain(|entry| {
+                entry.as_ref().map_or(true, |e| {
+                    let path = e.path();
+                    if should_ignore_path(&path, &root_path, &patterns) {
+                        return false;
+                    }
+                    if !excluded.is_empty()
+                        && e.file_type().is_file()
+                        && excluded.contains(path.strip_prefix(&root_path).unwrap_or(&path).to_string_lossy().as_ref())
+                    {
+                        return false;
+                    }
+                    match &resume_after {
+                        Some(last) => is_after_in_walk(path.strip_prefix(&root_path).unwrap_or(&path), last),
+                        None => true,
+                    }
+                })
+            });
+        })
+}
+
+/// Sorted depth-first order is component-wise path order. Ancestors of
+/// `last` are kept so the walk can descend to the entries after it.
+fn is_after_in_walk(relative: &Path, last: &Path) -> bool {
+    if last.starts_with(relative) {
+        return relative != last;
+    }
+    relative.components().cmp(last.components()).is_gt()
+}
+
+/// Count files that would be scanned (quick pre-count for progress)
+pub fn count_scannable_files(
+    root: &Path,
+    walk: WalkOptions,
+    cancel_flag: Arc<AtomicBool>,
+    mut progress_callback: impl FnMut(usize),
+) -> AppResult<usize> {
+    if !Scanner::is_valid_folder(root) {
+        return Err(AppError::InvalidProject(
+            "Not a valid folder".to_string(),
+        ));
+    }
+
+    let mut count = 0;
+
+    for entry in project_walker(root, walk) {
+        // Check cancellation
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Ok(count);
+        }
+
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+
+        if path.extension().map(|e| e == "meta").unwrap_or(false) {
+            continue;
+        }
+
+        let asset_type = classify_with_plugins(&path, walk.plugins);
+        if asset_type == "unknown" {
+            continue;
+        }
+
+        count += 1;
+
+        // Report progress every 100 files
+        if count % 100 == 0 {
+            progress_callback(count);
+        }
+    }
+
+    progress_callback(count);
+    Ok(count)
+}
+
+/// Index row for one file, the way a scan builds it, with a new id. `None`
+/// for files the index doesn't take (`.meta`, unclassified or gone).
+pub fn read_asset(root: &Path, path: &Path, project_id: &str, plugins: Option<&PluginRegistry>) -> Option<Asset> {
+    if path.extension().is_some_and(|e| e == "meta") {
+        return None;
+    }
+    let asset_type = classify_with_plugins(path, plugins);
+    if asset_type == "unknown" {
+        return None;
+    }
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file())?;
+    let relative_path = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+
+    Some(new_asset(
+        path,
+        relative_path,
+        project_id,
+        asset_type.into_owned(),
+        &metadata,
+        uuid::Uuid::new_v4().to_string(),
+        chrono::Utc::now().timestamp(),
+    ))
+}
+
+pub(crate) fn modified_secs(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn new_asset(
+    path: &Path,
+    relative_path: String,
+    project_id: &str,
+    asset_type: String,
+    metadata: &fs::Metadata,
+    id: String,
+    now: i64,
+) -> Asset {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let meta_path = PathBuf::from(format!("{}.meta", path.display()));
+    let unity_guid = read_unity_guid(&meta_path);
+
+    Asset {
+        id,
+        project_id: project_id.to_string(),
+        absolute_path: path.to_string_lossy().to_string(),
+        relative_path,
+        file_name,
+        extension,
+        asset_type,
+        size_bytes: metadata.len() as i64,
+        modified_time: modified_secs(metadata),
+        content_hash: None,
+        unity_guid,
+        import_type: None,
+        thumbnail_path: None,
+        created_at: now,
+        updated_at: now,
+        vcs: None,
+        build_size_bytes: None,
+        delivery: None,
+    }
+}
+
+/// Walk `root` and hand new or changed files to `callback` in batches of
+/// `batch_size`, with the number of files walked so far (skipped unchanged
+/// ones included) and the current path. Returns the number of files handed
+/// over and the walk's stats.
+pub fn scan_files_batch(
+    root: &Path,
+    project_id: &str,
+    walk: WalkOptions,
+    batch_size: usize,
+    cancel_flag: Arc<AtomicBool>,
+    change_detection: Option<ChangeDetection>,
+    mut callback: impl FnMut(Vec<Asset>, usize, &str) -> bool,  // Returns false to stop
+) -> AppResult<(usize, ScanStats)> {
+    if !Scanner::is_valid_folder(root) {
+        return Err(AppError::InvalidProject(
+            "Not a valid folder".to_string(),
+        ));
+    }
+
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut total_count = 0;
+    let mut stats = ScanStats::default();
+    let now = chrono::Utc::now().timestamp();
+    let existing_assets = change_detection.map(|c| c.existing);
+    let skip_unchanged = change_detection.is_some_and(|c| c.skip_unchanged);
+    let directories = change_detection.and_then(|c| c.directories);
+    let trust_directories = skip_unchanged && directories.is_some_and(|d| d.trust_unchanged);
+    // Directories whose mtime matches the last scan
+    let mut unchanged_dirs: HashSet<PathBuf> = HashSet::new();
+
+    for entry in project_walker(root, walk) {
+        // Check cancellation
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Ok((total_count, stats));
+        }
+
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        // Directories come before their contents in the sorted walk
+        if entry.file_type().is_dir() {
+            if let Some(directories) = directories {
+                let path = entry.path();
+                let Some(mtime) = fs::metadata(&path).ok().and_then(|m| m.modified().ok()).and_then(|t| {
+                    t.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_nanos() as i64)
+                }) else {
+                    continue;
+                };
+                let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+                if trust_directories && directories.previous.get(&relative) == Some(&mtime) {
+                    unchanged_dirs.insert(path);
+                }
+                directories.current.borrow_mut().insert(relative, mtime);
+            }
+            continue;
+        }
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+
+        if path.extension().map(|e| e == "meta").unwrap_or(false) {
+            continue;
+        }
+
+        let asset_type = classify_with_plugins(&path, walk.plugins);
+        if asset_type == "unknown" {
+            continue;
+        }
+
+        let relative_path = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+
+        // Already indexed and its directory untouched: skip the stat
+        if path.parent().is_some_and(|parent| unchanged_dirs.contains(parent))
+            && existing_assets.is_some_and(|m| m.contains_key(&relative_path))
+        {
+            stats.total_files += 1;
+            stats.unchanged_skipped += 1;
+            continue;
+        }
+
+        let metadata = match fs::metadata(&path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        let size_bytes = metadata.len() as i64;
+
+        let modified_time = modified_secs(&metadata);
+
+        stats.total_files += 1;
+
+        // Check if file is unchanged (same modified_time and size_bytes)
+        match existing_assets.and_then(|m| m.get(&relative_path)) {
+            Some((_, existing_mtime, existing_size)) => {
+                if *existing_mtime == modified_time && *existing_size == size_bytes {
+                    if skip_unchanged {
+                        // File unchanged, skip indexing
+                        stats.unchanged_skipped += 1;
+                        continue;
+                    }
+                } else {
+                    stats.changed_files += 1;
+                }
+            }
+            None => stats.new_files += 1,
+        }
+
+        // File is new, changed, or being re-indexed by a full scan
+        stats.new_or_changed += 1;
+
+        // Reuse existing asset ID if the file existed before (but was modified)
+        let asset_id = existing_assets
+            .and_then(|m| m.get(&relative_path))
+            .map(|(id, _, _)| id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        let asset = new_asset(
+            &path,
+            relative_path.clone(),
+            project_id,
+            asset_type.into_owned(),
+            &metadata,
+            asset_id,
+            now,
+        );
+
+        batch.push(asset);
+        total_count += 1;
+
+        if batch.len() >= batch_size {
+            let should_continue = callback(std::mem::take(&mut batch), stats.total_files, &relative_path);
+            if !should_continue {
+                return Ok((total_count, stats));
+            }
+            batch = Vec::with_capacity(batch_size);
+        }
+    }
+
+    // Send remaining batch
+    if !batch.is_empty() {
+        callback(batch, stats.total_files, "");
+    }
+
+    Ok((total_count, stats))
+}
@@ -0,0 +1,119 @@
+//! What each scene costs: every asset its dependency closure pulls in,
+//! split into assets only that scene uses (what cutting the scene would
+//! save) and assets it shares with other scenes.
+//!
+//! In-build sizes come from an imported build report where there is one,
+//! else the texture size estimate, else the size on disk.
+
+use crate::db::Database;
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SceneCost {
+    pub asset_id: String,
+    pub relative_path: String,
+    /// Unique assets in the closure, the scene file included
+    pub asset_count: usize,
+    pub size_bytes: i64,
+    pub build_bytes: i64,
+    /// Assets no other scene reaches
+    pub exclusive_count: usize,
+    pub exclusive_size_bytes: i64,
+    pub exclusive_build_bytes: i64,
+    /// Assets at least one other scene reaches too
+    pub shared_count: usize,
+    pub shared_size_bytes: i64,
+    pub shared_build_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneCostReport {
+    /// Largest in-build cost first
+    pub scenes: Vec<SceneCost>,
+    /// Assets reached by any scene, each counted once
+    pub asset_count: usize,
+    pub size_bytes: i64,
+    pub build_bytes: i64,
+    /// Of those, assets sized from a build report or texture estimate
+    /// rather than their file size
+    pub build_sized_count: usize,
+}
+
+pub fn scene_costs(db: &Database, project_id: &str) -> AppResult<SceneCostReport> {
+    let assets = db.get_asset_sizes(project_id)?;
+    let index: HashMap<&str, usize> = assets.iter().enumerate().map(|(i, a)| (a.asset_id.as_str(), i)).collect();
+
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); assets.len()];
+    for dep in db.get_project_dependencies(project_id)? {
+        let from = index.get(dep.from_asset_id.as_str());
+        let to = dep.to_asset_id.as_deref().and_then(|to| index.get(to));
+        if let (Some(&from), Some(&to)) = (from, to) {
+            edges[from].push(to);
+        }
+    }
+
+    let scenes: Vec<usize> = (0..assets.len()).filter(|&i| assets[i].asset_type == "scene").collect();
+    // Each scene's closure, and how many scenes reach each asset
+    let mut closures: Vec<Vec<usize>> = Vec::with_capacity(scenes.len());
+    let mut reached_by = vec![0u32; assets.len()];
+    let mut seen = vec![usize::MAX; assets.len()];
+    for (n, &scene) in scenes.iter().enumerate() {
+        let mut closure = vec![scene];
+        seen[scene] = n;
+        let mut next = 0;
+        while let Some(&current) = closure.get(next) {
+            next += 1;
+            for &to in &edges[current] {
+                if seen[to] != n {
+                    seen[to] = n;
+                    closure.push(to);
+                }
+            }
+        }
+        for &i in &closure {
+            reached_by[i] += 1;
+        }
+        closures.push(closure);
+    }
+
+    let build_bytes = |i: usize| assets[i].build_bytes.unwrap_or(assets[i].size_bytes);
+    let mut costs: Vec<SceneCost> = scenes
+        .iter()
+        .zip(&closures)
+        .map(|(&scene, closure)| {
+            let mut cost = SceneCost {
+                asset_id: assets[scene].asset_id.clone(),
+                relative_path: assets[scene].relative_path.clone(),
+                asset_count: closure.len(),
+                ..Default::default()
+            };
+            for &i in closure {
+                let (size, build) = (assets[i].size_bytes, build_bytes(i));
+                cost.size_bytes += size;
+                cost.build_bytes += build;
+                if reached_by[i] == 1 {
+                    cost.exclusive_count += 1;
+                    cost.exclusive_size_bytes += size;
+                    cost.exclusive_build_bytes += build;
+                } else {
+                    cost.shared_count += 1;
+                    cost.shared_size_bytes += size;
+                    cost.shared_build_bytes += build;
+                }
+            }
+            cost
+        })
+        .collect();
+    costs.sort_by(|a, b| b.build_bytes.cmp(&a.build_bytes).then_with(|| a.relative_path.cmp(&b.relative_path)));
+
+    let used: Vec<usize> = (0..assets.len()).filter(|&i| reached_by[i] > 0).collect();
+    Ok(SceneCostReport {
+        scenes: costs,
+        asset_count: used.len(),
+        size_bytes: used.iter().map(|&i| assets[i].size_bytes).sum(),
+        build_bytes: used.iter().map(|&i| build_bytes(i)).sum(),
+        build_sized_count: used.iter().filter(|&&i| assets[i].build_bytes.is_some()).count(),
+    })
+}
@@ -0,0 +1,257 @@
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Asset types whose content is plain text (Unity YAML or source)
+pub const TEXT_ASSET_TYPES: &[&str] = &[
+    "material",
+    "prefab",
+    "scene",
+    "scriptable_object",
+    "script",
+    "shader",
+];
+
+/// Stop collecting hits after this many matches
+const MAX_HITS: usize = 1000;
+/// Files larger than this are skipped (mostly huge scenes)
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// Matching lines are clipped to this many characters
+const MAX_LINE_CHARS: usize = 300;
+/// Suggestions returned by default
+pub const DEFAULT_SUGGESTIONS: usize = 10;
+/// Characters typed before the index is consulted; shorter prefixes only
+/// get recent searches
+const MIN_SUGGESTION_PREFIX: usize = 2;
+/// Matching assets read to find file and folder names
+const SUGGESTION_SAMPLE: i64 = 2000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub asset_id: String,
+    pub relative_path: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSearchResult {
+    pub hits: Vec<SearchHit>,
+    pub files_searched: usize,
+    pub files_matched: usize,
+    /// True if the hit limit was reached
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSuggestion {
+    /// What to put in the search box
+    pub text: String,
+    /// "recent", "folder", "file" or "term"
+    pub kind: String,
+    /// Assets it matches (among those sampled for folders); 0 for recent searches
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileSearchOptions {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub types: Option<Vec<String>>,
+}
+
+/// Grep the content of indexed text assets
+pub fn search_in_files(
+    db: &Arc<Database>,
+    project_id: &str,
+    pattern: &str,
+    options: &FileSearchOptions,
+) -> AppResult<FileSearchResult> {
+    if pattern.is_empty() {
+        return Err(AppError::InvalidArgument("Search pattern is empty".to_string()));
+    }
+
+    let source = if options.regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    let re = RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| AppError::InvalidArgument(format!("Invalid search pattern: {}", e)))?;
+
+    let types: Vec<String> = match &options.types {
+        Some(types) if !types.is_empty() => types
+            .iter()
+            .filter(|t| TEXT_ASSET_TYPES.contains(&t.as_str()))
+            .cloned()
+            .collect(),
+        _ => TEXT_ASSET_TYPES.iter().map(|t| t.to_string()).collect(),
+    };
+
+    let assets = db.get_assets_by_types(project_id, &types)?;
+
+    let mut result = FileSearchResult {
+        hits: Vec::new(),
+        files_searched: 0,
+        files_matched: 0,
+        truncated: false,
+    };
+
+    for asset in assets {
+        if asset.size_bytes as u64 > MAX_FILE_SIZE {
+            continue;
+        }
+
+        let file = match fs::File::open(&asset.absolute_path) {
+            Ok(f) => f,
+            Err(_) => continue,
+        };
+        let mut reader = BufReader::new(file);
+
+        // Skip binary-serialized assets
+        if is_binary(&mut reader) {
+            continue;
+        }
+
+        result.files_searched += 1;
+        let mut matched = false;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(l) => l,
+                Err(_) => break,
+            };
+
+            if !re.is_match(&line) {
+                continue;
+            }
+
+            matched = true;
+            result.hits.push(SearchHit {
+                asset_id: asset.id.clone(),
+                relative_path: asset.relative_path.clone(),
+                line_number: index + 1,
+                line: line.trim().chars().take(MAX_LINE_CHARS).collect(),
+            });
+
+            if result.hits.len() >= MAX_HITS {
+                result.truncated = true;
+                result.files_matched += 1;
+                return Ok(result);
+            }
+        }
+
+        if matched {
+            result.files_matched += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Peek at the buffered start of the file and look for NUL bytes
+/// Completions for the asset search box: recent searches starting with
+/// `prefix`, then folders, file names and indexed terms with a word that
+/// starts with its last word
+pub fn search_suggestions(
+    db: &Database,
+    project_id: &str,
+    prefix: &str,
+    limit: usize,
+) -> AppResult<Vec<SearchSuggestion>> {
+    let mut suggestions: Vec<SearchSuggestion> = db
+        .get_recent_searches(project_id, prefix.trim_start(), (limit / 2).max(1) as i64)?
+        .into_iter()
+        .map(|text| SearchSuggestion {
+            text,
+            kind: "recent".to_string(),
+            count: 0,
+        })
+        .collect();
+
+    // The word being typed, as the FTS tokenizer splits it
+    let typed_word = prefix.rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or_default();
+    let last_word = typed_word.to_lowercase();
+    if last_word.chars().count() >= MIN_SUGGESTION_PREFIX {
+        let mut folders: HashMap<String, i64> = HashMap::new();
+        let mut files: HashMap<String, i64> = HashMap::new();
+        for path in db.get_search_matches(project_id, &format!("\"{}\"*", last_word), SUGGESTION_SAMPLE)? {
+            let path = Path::new(&path);
+            if let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
+                if has_word_starting(&name, &last_word) {
+                    *files.entry(name).or_default() += 1;
+                }
+            }
+            let mut folder = PathBuf::new();
+            for component in path.parent().into_iter().flat_map(Path::components) {
+                folder.push(component);
+                if has_word_starting(&component.as_os_str().to_string_lossy(), &last_word) {
+                    *folders.entry(folder.to_string_lossy().to_string()).or_default() += 1;
+                }
+            }
+        }
+        let ranked = |counts: HashMap<String, i64>, kind: &str| {
+            let mut ranked: Vec<SearchSuggestion> = counts
+                .into_iter()
+                .map(|(text, count)| SearchSuggestion {
+                    text,
+                    kind: kind.to_string(),
+                    count,
+                })
+                .collect();
+            // Names that start with the word first, then the most used
+            ranked.sort_by(|a, b| {
+                let starts = |s: &SearchSuggestion| {
+                    !Path::new(&s.text)
+                        .file_name()
+                        .is_some_and(|n| n.to_string_lossy().to_lowercase().starts_with(&last_word))
+                };
+                starts(a)
+                    .cmp(&starts(b))
+                    .then_with(|| b.count.cmp(&a.count))
+                    .then_with(|| a.text.len().cmp(&b.text.len()))
+                    .then_with(|| a.text.cmp(&b.text))
+            });
+            ranked
+        };
+        suggestions.extend(ranked(folders, "folder").into_iter().take(3));
+        suggestions.extend(ranked(files, "file"));
+
+        // Complete the last word of what was typed
+        let typed = &prefix[..prefix.len() - typed_word.len()];
+        for (term, count) in db.get_search_terms(project_id, &last_word, limit)? {
+            suggestions.push(SearchSuggestion {
+                text: format!("{}{}", typed, term),
+                kind: "term".to_string(),
+                count,
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    suggestions.retain(|s| seen.insert(s.text.to_lowercase()));
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+/// Whether one of the words of `name` (as the FTS tokenizer splits them)
+/// starts with the lowercase `word`
+fn has_word_starting(name: &str, word: &str) -> bool {
+    name.split(|c: char| !c.is_alphanumeric())
+        .any(|w| w.to_lowercase().starts_with(word))
+}
+
+fn is_binary<R: Read>(reader: &mut BufReader<R>) -> bool {
+    match reader.fill_buf() {
+        Ok(buf) => buf.contains(&0),
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,938 @@
+use crate::boundaries::validate_boundary_pattern;
+use crate::code_editor::CodeEditor;
+use crate::converters::TextureConverter;
+use crate::db::DbOptions;
+use crate::error::{AppError, AppResult};
+use crate::export::ExportOptions;
+use crate::lods::{TriangleBudget, DEFAULT_LOD_MIN_TRIANGLES};
+use crate::previews::ThumbnailOptions;
+use crate::scanner::validate_ignore_pattern;
+use crate::storage::StorageMove;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Current settings schema version. Bump it and add a step to `migrate`
+/// whenever a key is renamed or an old value needs rewriting.
+pub const SETTINGS_VERSION: u32 = 2;
+
+/// Missing keys fall back to `Settings::default()`, so adding a field
+/// never invalidates an existing settings file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    #[serde(skip)]
+    pub path: Option<std::path::PathBuf>,
+
+    pub version: u32,
+    /// The active project, one of `project_roots`
+    pub project_root: Option<String>,
+    /// Every project folder added, in the order added
+    pub project_roots: Vec<String>,
+    /// Reference folders added as libraries, such as a studio-wide texture
+    /// archive: scanned and searchable, but not Unity projects and never active
+    pub library_roots: Vec<String>,
+    pub output_folder: Option<String>,
+    pub ignore_patterns: Vec<String>,
+    pub thumbnail_size: u32,
+    /// "png", "webp" or "jpeg"
+    pub thumbnail_format: String,
+    /// JPEG quality, 1-100
+    pub thumbnail_quality: u8,
+    /// Images over either budget are marked TOO_LARGE instead of decoded
+    pub max_decode_pixels: u64,
+    pub max_image_file_mb: u64,
+    /// Failed thumbnail attempts before an asset is skipped until it changes
+    pub thumbnail_max_attempts: u32,
+    /// Asset types whose thumbnails are generated first, in order; types not
+    /// listed follow in no particular order
+    pub thumbnail_type_priority: Vec<String>,
+    pub scan_on_focus: bool,
+    /// Keep the index in step with file changes once the project is scanned
+    pub watch_project: bool,
+    /// Opt-in anonymous usage metrics written to `telemetry.jsonl`
+    pub telemetry_enabled: bool,
+
+    /// Files indexed per database transaction during a scan
+    pub scan_batch_size: usize,
+    /// Minimum interval between `assets-updated` events while indexing
+    pub ui_refresh_ms: u64,
+    /// Quick scans skip statting indexed files in directories whose mtime is
+    /// unchanged. Misses files edited in place without a rename; a full
+    /// scan still catches those.
+    pub directory_change_detection: bool,
+    /// Projects with at least this many assets page the browser by cursor
+    /// and report an estimated total, counting exact matches in the
+    /// background; 0 always counts exactly
+    pub large_project_threshold: u64,
+
+    /// Read connection pool size (writes share a single connection) and
+    /// lock wait, applied on next startup
+    pub db_pool_size: u32,
+    pub db_busy_timeout_ms: u64,
+    /// Storage tuning for very large catalogs, applied on next startup:
+    /// memory-mapped reads (0 disables), page size (changing it rebuilds the
+    /// database once) and page cache per connection
+    pub db_mmap_mb: u64,
+    pub db_page_size: u32,
+    pub db_cache_mb: u32,
+    /// Warm the database cache for the current project in the background
+    /// on startup
+    pub startup_warm_up: bool,
+
+    /// Worker thread counts; `None` picks a default from the CPU count
+    pub scan_threads: Option<usize>,
+    pub dependency_threads: Option<usize>,
+    pub thumbnail_threads: Option<usize>,
+    /// Dependency parsing reads at most this much of each file; 0 reads
+    /// files whole however large
+    pub dependency_max_file_mb: u64,
+
+    /// Read-only HTTP API on 127.0.0.1 for editor scripts and internal tools
+    pub api_enabled: bool,
+    pub api_port: u16,
+    /// Bearer token the API requires; generated the first time it is enabled
+    pub api_token: Option<String>,
+
+    /// OS notification when a scan or export finishes
+    pub notify_on_complete: bool,
+    /// Receives a JSON POST when a scan or export finishes
+    pub webhook_url: Option<String>,
+
+    /// Load format plugins from the `plugins` folder, applied on next startup
+    pub plugins_enabled: bool,
+
+    /// Unity's `Asset Store-5.x` download cache; `None` uses Unity's default location
+    pub asset_store_cache_dir: Option<String>,
+
+    /// Blender executable for shaded .blend/.fbx/.obj thumbnails; without it
+    /// models are rendered in the app's WebGL viewer
+    pub blender_path: Option<String>,
+    /// ffprobe executable for audio/video duration, codec and resolution. An
+    /// ffmpeg beside it is also used to measure audio levels for QA.
+    pub ffprobe_path: Option<String>,
+    /// External decoders for textures the built-in ones can't read
+    pub texture_converters: Vec<TextureConverter>,
+    /// Per-folder triangle limits for the LOD report; the first match applies
+    pub triangle_budgets: Vec<TriangleBudget>,
+    /// Models with at least this many triangles are expected to have LODs
+    pub lod_min_triangles: u64,
+    /// Feature folders whose assets shouldn't reference each other, such as
+    /// `Assets/Features/*` (`*` matches within one folder name)
+    pub dependency_boundaries: Vec<String>,
+    /// Editor that `open_in_code_editor` launches at a file and line
+    pub code_editor: Option<CodeEditor>,
+
+    /// Used by `export_file` / `export_bundle` unless a call overrides them
+    pub export_defaults: ExportOptions,
+
+    /// Folders for `scythe.db` and the thumbnail cache; `None` uses the app data dir
+    pub database_dir: Option<String>,
+    pub thumbnail_dir: Option<String>,
+    /// Set when the locations above changed; the data is moved on next startup
+    pub pending_storage_move: Option<StorageMove>,
+    /// Published team catalog (`scythe.db` + `thumbnails/`) opened read-only
+    /// instead of the local database; takes effect on next startup
+    pub shared_catalog_dir: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            path: None,
+            version: SETTINGS_VERSION,
+            project_root: None,
+            project_roots: Vec::new(),
+            library_roots: Vec::new(),
+            output_folder: None,
+            ignore_patterns: vec![
+                "Library/".to_string(),
+                "Temp/".to_string(),
+                "obj/".to_string(),
+                "Logs/".to_string(),
+                "UserSettings/".to_string(),
+                ".git/".to_string(),
+                ".vs/".to_string(),
+                "Builds/".to_string(),
+                "Build/".to_string(),
+            ],
+            thumbnail_size: 128,
+            thumbnail_format: "png".to_string(),
+            thumbnail_quality: 85,
+            max_decode_pixels: 2048 * 2048,
+            max_image_file_mb: 50,
+            thumbnail_max_attempts: 3,
+            thumbnail_type_priority: DEFAULT_THUMBNAIL_TYPE_PRIORITY
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+            scan_on_focus: true,
+            watch_project: true,
+            telemetry_enabled: false,
+            scan_batch_size: 25,
+            ui_refresh_ms: 200,
+            directory_change_detection: false,
+            large_project_threshold: 250_000,
+            db_pool_size: 4,
+            db_busy_timeout_ms: 5000,
+            db_mmap_mb: DbOptions::default().mmap_size_mb,
+            db_page_size: DbOptions::default().page_size,
+            db_cache_mb: DbOptions::default().cache_size_mb,
+            startup_warm_up: true,
+            scan_threads: None,
+            dependency_threads: None,
+            thumbnail_threads: None,
+            dependency_max_file_mb: crate::deps::DEFAULT_MAX_FILE_BYTES / (1024 * 1024),
+            api_enabled: false,
+            api_port: DEFAULT_API_PORT,
+            api_token: None,
+            notify_on_complete: true,
+            webhook_url: None,
+            plugins_enabled: true,
+            asset_store_cache_dir: None,
+            blender_path: None,
+            ffprobe_path: None,
+            texture_converters: Vec::new(),
+            triangle_budgets: Vec::new(),
+            lod_min_triangles: DEFAULT_LOD_MIN_TRIANGLES,
+            dependency_boundaries: Vec::new(),
+            code_editor: None,
+            export_defaults: ExportOptions::default(),
+            database_dir: None,
+            thumbnail_dir: None,
+            pending_storage_move: None,
+            shared_catalog_dir: None,
+        }
+    }
+}
+
+/// Bounds accepted for `thumbnail_size`
+pub const MIN_THUMBNAIL_SIZE: u32 = 32;
+pub const MAX_THUMBNAIL_SIZE: u32 = 1024;
+
+/// Bounds accepted for `max_decode_pixels` and `max_image_file_mb`
+pub const MIN_DECODE_PIXELS: u64 = 256 * 256;
+pub const MAX_DECODE_PIXELS: u64 = 16384 * 16384;
+pub const MAX_IMAGE_FILE_MB: u64 = 2048;
+
+/// Upper bound for `thumbnail_max_attempts`
+pub const MAX_THUMBNAIL_ATTEMPTS: u32 = 100;
+
+/// Thumbnail queue order out of the box. Models and audio only get
+/// thumbnails from Blender or a preview plugin.
+pub const DEFAULT_THUMBNAIL_TYPE_PRIORITY: &[&str] =
+    &["texture", "material", "model", "audio", "prefab", "scene"];
+
+/// Bounds accepted for `scan_batch_size` and `ui_refresh_ms`
+pub const MAX_SCAN_BATCH_SIZE: usize = 5000;
+pub const MIN_UI_REFRESH_MS: u64 = 50;
+pub const MAX_UI_REFRESH_MS: u64 = 10_000;
+
+/// Bounds accepted for `db_pool_size` and `db_busy_timeout_ms`
+pub const MAX_DB_POOL_SIZE: u32 = 32;
+pub const MAX_DB_BUSY_TIMEOUT_MS: u64 = 60_000;
+/// Bounds for the storage tuning settings; SQLite accepts page sizes that
+/// are powers of two in this range
+pub const MAX_DB_MMAP_MB: u64 = 64 * 1024;
+pub const MIN_DB_PAGE_SIZE: u32 = 512;
+pub const MAX_DB_PAGE_SIZE: u32 = 65536;
+pub const MIN_DB_CACHE_MB: u32 = 2;
+pub const MAX_DB_CACHE_MB: u32 = 4096;
+
+/// Upper bound for any configured worker thread count
+pub const MAX_THREADS: usize = 64;
+
+/// Default and lowest accepted `api_port` (no privileged ports)
+pub const DEFAULT_API_PORT: u16 = 7878;
+pub const MIN_API_PORT: u16 = 1024;
+
+/// Partial update sent by `update_settings`; absent fields are left unchanged
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SettingsPatch {
+    pub output_folder: Option<String>,
+    pub ignore_patterns: Option<Vec<String>>,
+    pub thumbnail_size: Option<u32>,
+    pub thumbnail_format: Option<String>,
+    pub thumbnail_quality: Option<u8>,
+    pub max_decode_pixels: Option<u64>,
+    pub max_image_file_mb: Option<u64>,
+    pub thumbnail_max_attempts: Option<u32>,
+    pub thumbnail_type_priority: Option<Vec<String>>,
+    pub scan_on_focus: Option<bool>,
+    pub watch_project: Option<bool>,
+    pub telemetry_enabled: Option<bool>,
+    pub scan_batch_size: Option<usize>,
+    pub ui_refresh_ms: Option<u64>,
+    pub directory_change_detection: Option<bool>,
+    pub large_project_threshold: Option<u64>,
+    pub db_pool_size: Option<u32>,
+    pub db_busy_timeout_ms: Option<u64>,
+    pub db_mmap_mb: Option<u64>,
+    pub db_page_size: Option<u32>,
+    pub db_cache_mb: Option<u32>,
+    pub startup_warm_up: Option<bool>,
+    /// Thread counts; 0 resets to the automatic default
+    pub scan_threads: Option<usize>,
+    pub dependency_threads: Option<usize>,
+    pub thumbnail_threads: Option<usize>,
+    pub dependency_max_file_mb: Option<u64>,
+    pub api_enabled: Option<bool>,
+    pub api_port: Option<u16>,
+    pub notify_on_complete: Option<bool>,
+    /// An empty string removes the webhook
+    pub webhook_url: Option<String>,
+    pub plugins_enabled: Option<bool>,
+    /// An empty string goes back to Unity's default location
+    pub asset_store_cache_dir: Option<String>,
+    /// An empty string stops using Blender
+    pub blender_path: Option<String>,
+    /// An empty string stops using ffprobe
+    pub ffprobe_path: Option<String>,
+    pub texture_converters: Option<Vec<TextureConverter>>,
+    pub triangle_budgets: Option<Vec<TriangleBudget>>,
+    pub lod_min_triangles: Option<u64>,
+    pub dependency_boundaries: Option<Vec<String>>,
+    /// A blank command stops using the editor
+    pub code_editor: Option<CodeEditor>,
+    pub export_defaults: Option<ExportOptions>,
+}
+
+/// Shareable subset of settings, without machine-specific paths or
+/// hardware-dependent thread counts. Absent fields are left unchanged on import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    pub version: u32,
+    pub ignore_patterns: Option<Vec<String>>,
+    pub thumbnail_size: Option<u32>,
+    pub thumbnail_format: Option<String>,
+    pub thumbnail_quality: Option<u8>,
+    pub scan_on_focus: Option<bool>,
+    pub watch_project: Option<bool>,
+    pub export_defaults: Option<ExportOptions>,
+    pub triangle_budgets: Option<Vec<TriangleBudget>>,
+    pub lod_min_triangles: Option<u64>,
+    pub dependency_boundaries: Option<Vec<String>>,
+}
+
+impl SettingsProfile {
+    pub fn into_patch(self) -> SettingsPatch {
+        SettingsPatch {
+            ignore_patterns: self.ignore_patterns,
+            thumbnail_size: self.thumbnail_size,
+            thumbnail_format: self.thumbnail_format,
+            thumbnail_quality: self.thumbnail_quality,
+            scan_on_focus: self.scan_on_focus,
+            watch_project: self.watch_project,
+            export_defaults: self.export_defaults,
+            triangle_budgets: self.triangle_budgets,
+            lod_min_triangles: self.lod_min_triangles,
+            dependency_boundaries: self.dependency_boundaries,
+            ..Default::default()
+        }
+    }
+}
+
+impl Settings {
+    pub fn to_profile(&self) -> SettingsProfile {
+        SettingsProfile {
+            version: SETTINGS_VERSION,
+            ignore_patterns: Some(self.ignore_patterns.clone()),
+            thumbnail_size: Some(self.thumbnail_size),
+            thumbnail_format: Some(self.thumbnail_format.clone()),
+            thumbnail_quality: Some(self.thumbnail_quality),
+            scan_on_focus: Some(self.scan_on_focus),
+            watch_project: Some(self.watch_project),
+            export_defaults: Some(self.export_defaults.clone()),
+            triangle_budgets: Some(self.triangle_budgets.clone()),
+            lod_min_triangles: Some(self.lod_min_triangles),
+            dependency_boundaries: Some(self.dependency_boundaries.clone()),
+        }
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        if !path.exists() {
+            return Self::create_default(path);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let mut value: serde_json::Value = match serde_json::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                // Keep the unreadable file instead of silently overwriting it
+                let backup = backup_file(path, "corrupt")?;
+                tracing::error!(
+                    "Settings file is invalid ({}), backed up to {}",
+                    e,
+                    backup.display()
+                );
+                return Self::create_default(path);
+            }
+        };
+
+        let from_version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+
+        if from_version != SETTINGS_VERSION {
+            let backup = backup_file(path, &format!("v{}", from_version))?;
+            tracing::info!(
+                "Migrating settings from version {} to {} (backup at {})",
+                from_version,
+                SETTINGS_VERSION,
+                backup.display()
+            );
+            if from_version > SETTINGS_VERSION {
+                tracing::warn!(
+                    "Settings were written by a newer version; unknown keys will be dropped"
+                );
+            }
+            migrate(&mut value, from_version);
+        }
+
+        let mut settings: Settings = match serde_json::from_value(value) {
+            Ok(s) => s,
+            Err(e) => {
+                let backup = backup_file(path, "corrupt")?;
+                tracing::error!(
+                    "Settings could not be read ({}), backed up to {}",
+                    e,
+                    backup.display()
+                );
+                return Self::create_default(path);
+            }
+        };
+
+        settings.version = SETTINGS_VERSION;
+        settings.path = Some(path.to_path_buf());
+        if from_version != SETTINGS_VERSION {
+            settings.save()?;
+        }
+        Ok(settings)
+    }
+
+    fn create_default(path: &Path) -> AppResult<Self> {
+        let mut settings = Settings::default();
+        settings.path = Some(path.to_path_buf());
+        settings.save()?;
+        Ok(settings)
+    }
+
+    /// Add a project folder to `project_roots` unless it is there already
+    pub fn register_project(&mut self, root: &str) {
+        if !self.project_roots.iter().any(|r| r == root) {
+            self.project_roots.push(root.to_string());
+        }
+    }
+
+    /// Make `root` the active project, adding it if needed
+    pub fn activate_project(&mut self, root: &str) {
+        self.register_project(root);
+        self.project_root = Some(root.to_string());
+    }
+
+    /// Add a folder to `library_roots` unless it is there already
+    pub fn register_library(&mut self, root: &str) {
+        if !self.is_library(root) {
+            self.library_roots.push(root.to_string());
+        }
+    }
+
+    pub fn is_library(&self, root: &str) -> bool {
+        self.library_roots.iter().any(|r| r == root)
+    }
+
+    /// Take a folder out of `project_roots` or `library_roots`. If it was
+    /// the active project, the first remaining one becomes active.
+    pub fn unregister_project(&mut self, root: &str) {
+        self.project_roots.retain(|r| r != root);
+        self.library_roots.retain(|r| r != root);
+        if self.project_root.as_deref() == Some(root) {
+            self.project_root = self.project_roots.first().cloned();
+        }
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        if let Some(path) = &self.path {
+            let content = serde_json::to_string_pretty(self)?;
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Validate a patch and apply it. Nothing is changed if any field is invalid.
+    pub fn apply_patch(&mut self, patch: SettingsPatch) -> AppResult<()> {
+        if let Some(folder) = &patch.output_folder {
+            if !Path::new(folder).is_dir() {
+                return Err(AppError::InvalidArgument(format!(
+                    "Output folder does not exist: {}",
+                    folder
+                )));
+            }
+        }
+
+        if let Some(patterns) = &patch.ignore_patterns {
+            for pattern in patterns {
+                validate_ignore_pattern(pattern).map_err(|reason| {
+                    AppError::InvalidArgument(format!(
+                        "Invalid ignore pattern '{}': {}",
+                        pattern, reason
+                    ))
+                })?;
+            }
+        }
+
+        if let Some(size) = patch.thumbnail_size {
+            if !(MIN_THUMBNAIL_SIZE..=MAX_THUMBNAIL_SIZE).contains(&size) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Thumbnail size must be between {} and {}",
+                    MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE
+                )));
+            }
+        }
+
+        if let Some(format) = &patch.thumbnail_format {
+            if !matches!(format.as_str(), "png" | "webp" | "jpeg") {
+                return Err(AppError::InvalidArgument(format!(
+                    "Unknown thumbnail format: {}",
+                    format
+                )));
+            }
+        }
+
+        if let Some(quality) = patch.thumbnail_quality {
+            if !(1..=100).contains(&quality) {
+                return Err(AppError::InvalidArgument(
+                    "Thumbnail quality must be between 1 and 100".to_string(),
+                ));
+            }
+        }
+
+        if let Some(pixels) = patch.max_decode_pixels {
+            if !(MIN_DECODE_PIXELS..=MAX_DECODE_PIXELS).contains(&pixels) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Decode pixel budget must be between {} and {}",
+                    MIN_DECODE_PIXELS, MAX_DECODE_PIXELS
+                )));
+            }
+        }
+
+        if let Some(file_mb) = patch.max_image_file_mb {
+            if !(1..=MAX_IMAGE_FILE_MB).contains(&file_mb) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Image file size budget must be between 1 and {} MB",
+                    MAX_IMAGE_FILE_MB
+                )));
+            }
+        }
+
+        if let Some(attempts) = patch.thumbnail_max_attempts {
+            if !(1..=MAX_THUMBNAIL_ATTEMPTS).contains(&attempts) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Thumbnail attempts must be between 1 and {}",
+                    MAX_THUMBNAIL_ATTEMPTS
+                )));
+            }
+        }
+
+        if let Some(types) = &patch.thumbnail_type_priority {
+            if types.iter().any(|t| t.trim().is_empty()) {
+                return Err(AppError::InvalidArgument(
+                    "Thumbnail priority has an empty asset type".to_string(),
+                ));
+            }
+        }
+
+        if let Some(batch_size) = patch.scan_batch_size {
+            if !(1..=MAX_SCAN_BATCH_SIZE).contains(&batch_size) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Scan batch size must be between 1 and {}",
+                    MAX_SCAN_BATCH_SIZE
+                )));
+            }
+        }
+
+        if let Some(refresh_ms) = patch.ui_refresh_ms {
+            if !(MIN_UI_REFRESH_MS..=MAX_UI_REFRESH_MS).contains(&refresh_ms) {
+                return Err(AppError::InvalidArgument(format!(
+                    "UI refresh interval must be between {} and {} ms",
+                    MIN_UI_REFRESH_MS, MAX_UI_REFRESH_MS
+                )));
+            }
+        }
+
+        if let Some(pool_size) = patch.db_pool_size {
+            if !(1..=MAX_DB_POOL_SIZE).contains(&pool_size) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Database pool size must be between 1 and {}",
+                    MAX_DB_POOL_SIZE
+                )));
+            }
+        }
+
+        if let Some(timeout_ms) = patch.db_busy_timeout_ms {
+            if timeout_ms > MAX_DB_BUSY_TIMEOUT_MS {
+                return Err(AppError::InvalidArgument(format!(
+                    "Database busy timeout must be at most {} ms",
+                    MAX_DB_BUSY_TIMEOUT_MS
+                )));
+            }
+        }
+
+        if let Some(mmap_mb) = patch.db_mmap_mb {
+            if mmap_mb > MAX_DB_MMAP_MB {
+                return Err(AppError::InvalidArgument(format!(
+                    "Database mmap size must be at most {} MB",
+                    MAX_DB_MMAP_MB
+                )));
+            }
+        }
+
+        if let Some(page_size) = patch.db_page_size {
+            if !page_size.is_power_of_two()
+                || !(MIN_DB_PAGE_SIZE..=MAX_DB_PAGE_SIZE).contains(&page_size)
+            {
+                return Err(AppError::InvalidArgument(format!(
+                    "Database page size must be a power of two between {} and {}",
+                    MIN_DB_PAGE_SIZE, MAX_DB_PAGE_SIZE
+                )));
+            }
+        }
+
+        if let Some(cache_mb) = patch.db_cache_mb {
+            if !(MIN_DB_CACHE_MB..=MAX_DB_CACHE_MB).contains(&cache_mb) {
+                return Err(AppError::InvalidArgument(format!(
+                    "Database cache size must be between {} and {} MB",
+                    MIN_DB_CACHE_MB, MAX_DB_CACHE_MB
+                )));
+            }
+        }
+
+        for threads in [
+            patch.scan_threads,
+            patch.dependency_threads,
+            patch.thumbnail_threads,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if threads > MAX_THREADS {
+                return Err(AppError::InvalidArgument(format!(
+                    "Thread count must be at most {}",
+                    MAX_THREADS
+                )));
+            }
+        }
+
+        if let Some(port) = patch.api_port {
+            if port < MIN_API_PORT {
+                return Err(AppError::InvalidArgument(format!(
+                    "API port must be at least {}",
+                    MIN_API_PORT
+                )));
+            }
+        }
+
+        if let Some(url) = patch.webhook_url.as_deref().map(str::trim) {
+            let is_http = url.starts_with("http://") || url.starts_with("https://");
+            if !url.is_empty() && !is_http {
+                return Err(AppError::InvalidArgument(format!(
+                    "Webhook URL must start with http:// or https://: {}",
+                    url
+                )));
+            }
+        }
+
+        if let Some(dir) = patch.asset_store_cache_dir.as_deref().map(str::trim) {
+            if !dir.is_empty() && !Path::new(dir).is_absolute() {
+                return Err(AppError::InvalidArgument(format!(
+                    "Asset Store cache location must be an absolute path: {}",
+                    dir
+                )));
+            }
+        }
+
+        if let Some(path) = patch.blender_path.as_deref().map(str::trim) {
+            if !path.is_empty() && !Path::new(path).is_file() {
+                return Err(AppError::InvalidArgument(format!(
+                    "Blender executable not found: {}",
+                    path
+                )));
+            }
+        }
+
+        if let Some(path) = patch.ffprobe_path.as_deref().map(str::trim) {
+            if !path.is_empty() && !Path::new(path).is_file() {
+                return Err(AppError::InvalidArgument(format!(
+                    "ffprobe executable not found: {}",
+                    path
+                )));
+            }
+        }
+
+        if let Some(budgets) = &patch.triangle_budgets {
+            for budget in budgets {
+                budget.validate()?;
+            }
+        }
+
+        if let Some(patterns) = &patch.dependency_boundaries {
+            for pattern in patterns {
+                validate_boundary_pattern(pattern)?;
+            }
+        }
+
+        if let Some(converters) = &patch.texture_converters {
+            for converter in converters {
+                converter.validate()?;
+            }
+        }
+
+        if let Some(editor) = patch
+            .code_editor
+            .as_ref()
+            .filter(|e| !e.command.trim().is_empty())
+        {
+            editor.validate()?;
+        }
+
+        if let Some(export_defaults) = &patch.export_defaults {
+            export_defaults.validate()?;
+        }
+
+        if let Some(folder) = patch.output_folder {
+            self.output_folder = Some(folder);
+        }
+        if let Some(patterns) = patch.ignore_patterns {
+            self.ignore_patterns = patterns.into_iter().map(|p| p.trim().to_string()).collect();
+        }
+        if let Some(size) = patch.thumbnail_size {
+            self.thumbnail_size = size;
+        }
+        if let Some(format) = patch.thumbnail_format {
+            self.thumbnail_format = format;
+        }
+        if let Some(quality) = patch.thumbnail_quality {
+            self.thumbnail_quality = quality;
+        }
+        if let Some(pixels) = patch.max_decode_pixels {
+            self.max_decode_pixels = pixels;
+        }
+        if let Some(file_mb) = patch.max_image_file_mb {
+            self.max_image_file_mb = file_mb;
+        }
+        if let Some(attempts) = patch.thumbnail_max_attempts {
+            self.thumbnail_max_attempts = attempts;
+        }
+        if let Some(types) = patch.thumbnail_type_priority {
+            let mut priority: Vec<String> = Vec::new();
+            for asset_type in types.iter().map(|t| t.trim().to_lowercase()) {
+                if !priority.contains(&asset_type) {
+                    priority.push(asset_type);
+                }
+            }
+            self.thumbnail_type_priority = priority;
+        }
+        if let Some(scan_on_focus) = patch.scan_on_focus {
+            self.scan_on_focus = scan_on_focus;
+        }
+        if let Some(watch_project) = patch.watch_project {
+            self.watch_project = watch_project;
+        }
+        if let Some(enabled) = patch.telemetry_enabled {
+            self.telemetry_enabled = enabled;
+        }
+        if let Some(batch_size) = patch.scan_batch_size {
+            self.scan_batch_size = batch_size;
+        }
+        if let Some(refresh_ms) = patch.ui_refresh_ms {
+            self.ui_refresh_ms = refresh_ms;
+        }
+        if let Some(enabled) = patch.directory_change_detection {
+            self.directory_change_detection = enabled;
+        }
+        if let Some(threshold) = patch.large_project_threshold {
+            self.large_project_threshold = threshold;
+        }
+        if let Some(pool_size) = patch.db_pool_size {
+            self.db_pool_size = pool_size;
+        }
+        if let Some(timeout_ms) = patch.db_busy_timeout_ms {
+            self.db_busy_timeout_ms = timeout_ms;
+        }
+        if let Some(mmap_mb) = patch.db_mmap_mb {
+            self.db_mmap_mb = mmap_mb;
+        }
+        if let Some(page_size) = patch.db_page_size {
+            self.db_page_size = page_size;
+        }
+        if let Some(cache_mb) = patch.db_cache_mb {
+            self.db_cache_mb = cache_mb;
+        }
+        if let Some(enabled) = patch.startup_warm_up {
+            self.startup_warm_up = enabled;
+        }
+        if let Some(threads) = patch.scan_threads {
+            self.scan_threads = (threads > 0).then_some(threads);
+        }
+        if let Some(threads) = patch.dependency_threads {
+            self.dependency_threads = (threads > 0).then_some(threads);
+        }
+        if let Some(threads) = patch.thumbnail_threads {
+            self.thumbnail_threads = (threads > 0).then_some(threads);
+        }
+        if let Some(max_mb) = patch.dependency_max_file_mb {
+            self.dependency_max_file_mb = max_mb;
+        }
+        if let Some(enabled) = patch.api_enabled {
+            self.api_enabled = enabled;
+            if enabled && self.api_token.is_none() {
+                self.regenerate_api_token();
+            }
+        }
+        if let Some(port) = patch.api_port {
+            self.api_port = port;
+        }
+        if let Some(notify) = patch.notify_on_complete {
+            self.notify_on_complete = notify;
+        }
+        if let Some(url) = patch.webhook_url {
+            let url = url.trim();
+            self.webhook_url = (!url.is_empty()).then(|| url.to_string());
+        }
+        if let Some(enabled) = patch.plugins_enabled {
+            self.plugins_enabled = enabled;
+        }
+        if let Some(dir) = patch.asset_store_cache_dir {
+            let dir = dir.trim();
+            self.asset_store_cache_dir = (!dir.is_empty()).then(|| dir.to_string());
+        }
+        if let Some(path) = patch.blender_path {
+            let path = path.trim();
+            self.blender_path = (!path.is_empty()).then(|| path.to_string());
+        }
+        if let Some(path) = patch.ffprobe_path {
+            let path = path.trim();
+            self.ffprobe_path = (!path.is_empty()).then(|| path.to_string());
+        }
+        if let Some(converters) = patch.texture_converters {
+            self.texture_converters = converters;
+        }
+        if let Some(budgets) = patch.triangle_budgets {
+            self.triangle_budgets = budgets;
+        }
+        if let Some(triangles) = patch.lod_min_triangles {
+            self.lod_min_triangles = triangles;
+        }
+        if let Some(patterns) = patch.dependency_boundaries {
+            self.dependency_boundaries =
+                patterns.into_iter().map(|p| p.trim().to_string()).collect();
+        }
+        if let Some(editor) = patch.code_editor {
+            self.code_editor = (!editor.command.trim().is_empty()).then_some(editor);
+        }
+        if let Some(export_defaults) = patch.export_defaults {
+            self.export_defaults = export_defaults;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the API token, invalidating the old one
+    pub fn regenerate_api_token(&mut self) {
+        self.api_token = Some(uuid::Uuid::new_v4().simple().to_string());
+    }
+
+    pub fn db_options(&self) -> DbOptions {
+        DbOptions {
+            pool_size: self.db_pool_size,
+            busy_timeout_ms: self.db_busy_timeout_ms,
+            mmap_size_mb: self.db_mmap_mb,
+            page_size: self.db_page_size,
+            cache_size_mb: self.db_cache_mb,
+        }
+    }
+
+    pub fn thumbnail_options(&self) -> ThumbnailOptions {
+        ThumbnailOptions {
+            size: self.thumbnail_size,
+            format: self.thumbnail_format.clone(),
+            quality: self.thumbnail_quality,
+            max_pixels: self.max_decode_pixels,
+            max_file_bytes: self.max_image_file_mb * 1024 * 1024,
+            max_attempts: self.thumbnail_max_attempts,
+            type_priority: self.thumbnail_type_priority.clone(),
+        }
+    }
+
+    /// Threads used to walk the project tree
+    pub fn effective_scan_threads(&self) -> usize {
+        self.scan_threads.unwrap_or_else(cpu_count)
+    }
+
+    /// Threads used to parse YAML assets for dependencies
+    pub fn effective_dependency_threads(&self) -> usize {
+        self.dependency_threads.unwrap_or_else(cpu_count)
+    }
+
+    /// Byte cap per file for dependency parsing, 0 for none
+    pub fn dependency_max_file_bytes(&self) -> u64 {
+        self.dependency_max_file_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Threads used to decode images for thumbnails. Defaults to half the
+    /// cores since large textures make decoding memory-bound.
+    pub fn effective_thumbnail_threads(&self) -> usize {
+        self.thumbnail_threads
+            .unwrap_or_else(|| (cpu_count() / 2).max(1))
+    }
+}
+
+/// Upgrade a raw settings object one version at a time
+fn migrate(value: &mut serde_json::Value, from_version: u32) {
+    let Some(map) = value.as_object_mut() else {
+        return;
+    };
+
+    for version in from_version..SETTINGS_VERSION {
+        if version == 0 {
+            // v0 -> v1: values written before validation existed
+            if let Some(size) = map.get("thumbnail_size").and_then(|v| v.as_u64()) {
+                let clamped = (size as u32).clamp(MIN_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE);
+                map.insert("thumbnail_size".to_string(), clamped.into());
+            }
+            if let Some(patterns) = map
+                .get_mut("ignore_patterns")
+                .and_then(|v| v.as_array_mut())
+            {
+                // An empty or "/" pattern would ignore the whole project
+                patterns.retain(|p| {
+                    p.as_str()
+                        .is_some_and(|p| validate_ignore_pattern(p).is_ok())
+                });
+            }
+        }
+        if version == 1 {
+            // v1 -> v2: the single project folder becomes the first of the list
+            if let Some(root) = map.get("project_root").filter(|v| v.is_string()).cloned() {
+                map.insert(
+                    "project_roots".to_string(),
+                    serde_json::Value::Array(vec![root]),
+                );
+            }
+        }
+    }
+
+    map.insert("version".to_string(), SETTINGS_VERSION.into());
+}
+
+/// Copy the settings file aside as `settings.json.<label>.bak`
+fn backup_file(path: &Path, label: &str) -> AppResult<std::path::PathBuf> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", label));
+    let backup = path.with_file_name(name);
+    std::fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+fn cpu_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
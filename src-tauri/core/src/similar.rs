@@ -0,0 +1,224 @@
+//! "Similar assets" for the detail pane: other assets of the same type that
+//! could be related to, or stand in for, the selected one.
+//!
+//! Each candidate is scored on the signals that apply to its type: shared
+//! file name words, folder proximity, matching dimensions (textures), the
+//! same shader (materials) and image hash distance (textures). The score is
+//! the weighted share of the applicable signals, so a texture and a prefab
+//! are both scored out of 1.
+
+use crate::db::{Asset, AssetSummary, Database};
+use crate::duplicates;
+use crate::error::{AppError, AppResult};
+use crate::previews::ThumbnailOptions;
+use crate::render_pipeline::{read_shader_reference, shader_reference_regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Suggestions returned for one asset
+pub const DEFAULT_LIMIT: usize = 20;
+/// Candidates scoring below this aren't suggested
+const MIN_SCORE: f64 = 0.25;
+/// Image hashes this many bits apart or more share nothing
+const IMAGE_DISTANCE_RANGE: u32 = 20;
+
+const NAME_WEIGHT: f64 = 0.35;
+const FOLDER_WEIGHT: f64 = 0.15;
+const DIMENSIONS_WEIGHT: f64 = 0.15;
+const SHADER_WEIGHT: f64 = 0.2;
+const IMAGE_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarAsset {
+    pub asset: AssetSummary,
+    pub relative_path: String,
+    /// 0 to 1, higher is more alike
+    pub score: f64,
+    /// Signals that matched: "name", "folder", "dimensions", "shader", "image"
+    pub reasons: Vec<String>,
+    /// Bits the image hashes differ by, for textures with one
+    pub image_distance: Option<u32>,
+}
+
+/// What is known about the selected asset and each candidate
+struct Signals {
+    tokens: HashSet<String>,
+    folder: Vec<String>,
+    dimensions: Option<(u32, u32)>,
+    shader: Option<(i64, String)>,
+    image_hash: Option<u64>,
+}
+
+/// Assets most like `asset_id`, best first. `threads` decode textures whose
+/// image hash isn't stored yet.
+pub fn similar_assets(
+    db: &Database,
+    asset_id: &str,
+    options: &ThumbnailOptions,
+    threads: usize,
+    limit: usize,
+) -> AppResult<Vec<SimilarAsset>> {
+    let target = db
+        .get_asset(asset_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Asset {}", asset_id)))?;
+    let candidates: Vec<Asset> = db
+        .get_assets_by_types(&target.project_id, std::slice::from_ref(&target.asset_type))?
+        .into_iter()
+        .filter(|a| a.id != target.id)
+        .collect();
+
+    let mut dimensions: HashMap<String, (u32, u32)> = HashMap::new();
+    let mut image_hashes: HashMap<String, u64> = HashMap::new();
+    let mut shaders: HashMap<String, (i64, String)> = HashMap::new();
+    match target.asset_type.as_str() {
+        "texture" => {
+            for entry in db.get_texture_estimates(&target.project_id, i64::MAX)? {
+                dimensions.insert(entry.asset_id, (entry.estimate.width, entry.estimate.height));
+            }
+            for (asset, hash) in duplicates::project_image_hashes(db, &target.project_id, options, threads)? {
+                image_hashes.insert(asset.id, hash);
+            }
+        }
+        "material" => {
+            let shader_re = shader_reference_regex()?;
+            for asset in candidates.iter().chain([&target]) {
+                if let Some(reference) = read_shader_reference(&shader_re, Path::new(&asset.absolute_path)) {
+                    shaders.insert(asset.id.clone(), reference);
+                }
+            }
+        }
+        _ => {}
+    }
+    let signals_of = |asset: &Asset| Signals {
+        tokens: name_tokens(&asset.file_name),
+        folder: folder_segments(&asset.relative_path),
+        dimensions: dimensions.get(&asset.id).copied(),
+        shader: shaders.get(&asset.id).cloned(),
+        image_hash: image_hashes.get(&asset.id).copied(),
+    };
+
+    let selected = signals_of(&target);
+    let mut similar: Vec<SimilarAsset> = candidates
+        .iter()
+        .filter_map(|candidate| compare(&selected, &signals_of(candidate)).map(|found| (candidate, found)))
+        .map(|(candidate, (score, reasons, image_distance))| SimilarAsset {
+            asset: AssetSummary::from(candidate),
+            relative_path: candidate.relative_path.clone(),
+            score,
+            reasons,
+            image_distance,
+        })
+        .collect();
+
+    similar.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.relative_path.cmp(&b.relative_path))
+    });
+    similar.truncate(limit);
+    Ok(similar)
+}
+
+/// Score a candidate, or `None` when it shares no more than a folder
+fn compare(selected: &Signals, candidate: &Signals) -> Option<(f64, Vec<String>, Option<u32>)> {
+    let mut reasons = Vec::new();
+    let mut total = 0.0;
+    let mut score = 0.0;
+    let mut add = |reason: &str, weight: f64, value: f64| {
+        total += weight;
+        score += weight * value;
+        if value > 0.0 {
+            reasons.push(reason.to_string());
+        }
+    };
+
+    add("name", NAME_WEIGHT, jaccard(&selected.tokens, &candidate.tokens));
+    add("folder", FOLDER_WEIGHT, folder_proximity(&selected.folder, &candidate.folder));
+    if let Some((width, height)) = selected.dimensions {
+        let value = match candidate.dimensions {
+            Some(dimensions) if dimensions == (width, height) => 1.0,
+            // Same aspect ratio at another size, e.g. 1024x512 and 512x256
+            Some((w, h)) if w as u64 * height as u64 == h as u64 * width as u64 => 0.5,
+            _ => 0.0,
+        };
+        add("dimensions", DIMENSIONS_WEIGHT, value);
+    }
+    if let Some(shader) = &selected.shader {
+        add("shader", SHADER_WEIGHT, if candidate.shader.as_ref() == Some(shader) { 1.0 } else { 0.0 });
+    }
+    let mut image_distance = None;
+    if let Some(hash) = selected.image_hash {
+        let distance = candidate.image_hash.map(|h| (h ^ hash).count_ones());
+        let value = distance.map_or(0.0, |d| 1.0 - d.min(IMAGE_DISTANCE_RANGE) as f64 / IMAGE_DISTANCE_RANGE as f64);
+        add("image", IMAGE_WEIGHT, value);
+        image_distance = distance;
+    }
+
+    let score = score / total;
+    if score < MIN_SCORE || reasons.iter().all(|r| r == "folder") {
+        return None;
+    }
+    Some(((score * 1000.0).round() / 1000.0, reasons, image_distance))
+}
+
+/// Lowercase words of a file name without its extension, split at
+/// separators, case changes and digits. Numbers are dropped so that
+/// `Rock_01` and `Rock_02` count as the same name.
+fn name_tokens(file_name: &str) -> HashSet<String> {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut tokens = HashSet::new();
+    let mut current = String::new();
+    let mut previous: Option<char> = None;
+    for c in stem.chars() {
+        let boundary = match previous {
+            _ if !c.is_alphanumeric() => true,
+            Some(p) => (p.is_lowercase() && c.is_uppercase()) || (p.is_ascii_digit() != c.is_ascii_digit()),
+            None => false,
+        };
+        if boundary && !current.is_empty() {
+            tokens.insert(std::mem::take(&mut current));
+        }
+        if c.is_alphanumeric() {
+            current.extend(c.to_lowercase());
+        }
+        previous = Some(c);
+    }
+    if !current.is_empty() {
+        tokens.insert(current);
+    }
+    tokens.retain(|t| !t.chars().all(|c| c.is_ascii_digit()));
+    tokens
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Folders of a relative path, without the file name
+fn folder_segments(relative_path: &str) -> Vec<String> {
+    let mut segments: Vec<String> = relative_path.split(['/', '\\']).map(str::to_lowercase).collect();
+    segments.pop();
+    segments
+}
+
+/// 1 for the same folder, 0.5 for a parent, child or sibling folder
+fn folder_proximity(a: &[String], b: &[String]) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let shared = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    if shared > 0 && shared + 1 >= a.len().max(b.len()) {
+        0.5
+    } else {
+        0.0
+    }
+}
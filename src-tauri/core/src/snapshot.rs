@@ -0,0 +1,380 @@
+//! Portable project snapshots: one project's whole index (assets,
+//! dependencies, thumbnails and stats) in a single zip file that another
+//! machine can import and browse without having the project files.
+//!
+//! Layout: `snapshot.json` (manifest), `assets.jsonl` and
+//! `dependencies.jsonl` (one record per line), and `thumbnails/<asset id>.<ext>`.
+
+use crate::asset_store::safe_relative_path;
+use crate::db::{Asset, Database, Dependency, Project, TypeCount};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const SNAPSHOT_FORMAT: &str = "scythe-snapshot";
+/// Bumped when the layout changes incompatibly; newer snapshots are refused
+const SNAPSHOT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "snapshot.json";
+const ASSETS_ENTRY: &str = "assets.jsonl";
+const DEPENDENCIES_ENTRY: &str = "dependencies.jsonl";
+const THUMBNAILS_PREFIX: &str = "thumbnails/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: i64,
+    /// The project as it was indexed on the exporting machine
+    pub project: Project,
+    pub stats: SnapshotStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotStats {
+    pub asset_count: usize,
+    pub dependency_count: usize,
+    pub thumbnail_count: usize,
+    pub total_bytes: i64,
+    pub type_counts: Vec<TypeCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotExport {
+    pub path: String,
+    pub stats: SnapshotStats,
+    /// False when cancelled; no file is left behind then
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotImport {
+    /// The imported project; None when cancelled
+    pub project: Option<Project>,
+    pub stats: SnapshotStats,
+    pub completed: bool,
+}
+
+/// Write `project_id`'s index to a snapshot at `path`. Thumbnails whose
+/// files are gone are left out rather than failing the export.
+///
+/// `progress(done, total)` is called per asset; returning false stops.
+pub fn export_snapshot(
+    db: &Database,
+    project_id: &str,
+    path: &Path,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<SnapshotExport> {
+    let project = db
+        .get_project(project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project not found: {}", project_id)))?;
+    let mut assets = db.get_project_assets(project_id)?;
+    let deps = db.get_project_dependencies(project_id)?;
+    let type_counts = db.get_type_counts(project_id, None)?;
+    for asset in &mut assets {
+        if let Some(thumbnail) = &mut asset.thumbnail_path {
+            *thumbnail = db.thumbnail_file(thumbnail).to_string_lossy().to_string();
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Written next to the destination and renamed at the end, so a cancelled
+    // or failed export never leaves a truncated snapshot behind
+    let partial = partial_path(path);
+    let result = write_snapshot(&partial, &project, &mut assets, &deps, type_counts, &mut progress);
+
+    match result {
+        Ok(Some(stats)) => {
+            fs::rename(&partial, path)?;
+            Ok(SnapshotExport {
+                path: path.to_string_lossy().to_string(),
+                stats,
+                completed: true,
+            })
+        }
+        Ok(None) => {
+            let _ = fs::remove_file(&partial);
+            Ok(SnapshotExport {
+                path: path.to_string_lossy().to_string(),
+                stats: stats_for(&assets, &deps, 0, Vec::new()),
+                completed: false,
+            })
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+/// Returns None when cancelled
+fn write_snapshot(
+    path: &Path,
+    project: &Project,
+    assets: &mut [Asset],
+    deps: &[Dependency],
+    type_counts: Vec<TypeCount>,
+    progress: &mut impl FnMut(usize, usize) -> bool,
+) -> AppResult<Option<SnapshotStats>> {
+    let mut writer = ZipWriter::new(fs::File::create(path)?);
+    let total = assets.len();
+
+    // Thumbnails first, so asset records can point at the entries that made it in
+    let mut thumbnail_count = 0;
+    for (i, asset) in assets.iter_mut().enumerate() {
+        if let Some(thumbnail) = asset.thumbnail_path.take() {
+            match fs::read(&thumbnail) {
+                Ok(data) => {
+                    let extension = Path::new(&thumbnail)
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .unwrap_or("png");
+                    let entry = format!("{}{}.{}", THUMBNAILS_PREFIX, asset.id, extension);
+                    // Images are already compressed
+                    start_entry(&mut writer, &entry, CompressionMethod::Stored)?;
+                    writer.write_all(&data)?;
+                    asset.thumbnail_path = Some(entry);
+                    thumbnail_count += 1;
+                }
+                Err(e) => tracing::debug!("Skipping thumbnail {}: {}", thumbnail, e),
+            }
+        }
+        if !progress(i + 1, total) {
+            return Ok(None);
+        }
+    }
+
+    start_entry(&mut writer, ASSETS_ENTRY, CompressionMethod::Deflated)?;
+    for asset in assets.iter() {
+        serde_json::to_writer(&mut writer, asset)?;
+        writer.write_all(b"\n")?;
+    }
+
+    start_entry(&mut writer, DEPENDENCIES_ENTRY, CompressionMethod::Deflated)?;
+    for dep in deps {
+        serde_json::to_writer(&mut writer, dep)?;
+        writer.write_all(b"\n")?;
+    }
+
+    let stats = stats_for(assets, deps, thumbnail_count, type_counts);
+    let manifest = SnapshotManifest {
+        format: SNAPSHOT_FORMAT.to_string(),
+        version: SNAPSHOT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        project: project.clone(),
+        stats: stats.clone(),
+    };
+    start_entry(&mut writer, MANIFEST_ENTRY, CompressionMethod::Deflated)?;
+    serde_json::to_writer_pretty(&mut writer, &manifest)?;
+
+    writer.finish().map_err(zip_error)?;
+    Ok(Some(stats))
+}
+
+/// Read the manifest without importing, e.g. to show what a snapshot holds
+pub fn read_snapshot_manifest(path: &Path) -> AppResult<SnapshotManifest> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?).map_err(zip_error)?;
+    read_manifest(&mut archive)
+}
+
+/// Import a snapshot as a new project rooted at `root_path` (the exporting
+/// machine's root when None). Assets get fresh ids so a snapshot can be
+/// imported next to the project it was taken from. An existing project at
+/// the same root is only replaced when `replace` is set.
+///
+/// `progress(done, total)` is called per thumbnail; returning false stops
+/// before anything is written to the database.
+pub fn import_snapshot(
+    db: &Database,
+    path: &Path,
+    thumbnail_dir: &Path,
+    root_path: Option<&str>,
+    replace: bool,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<SnapshotImport> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?).map_err(zip_error)?;
+    let manifest = read_manifest(&mut archive)?;
+
+    let root_path = root_path.unwrap_or(&manifest.project.root_path).to_string();
+    let existing = db.get_project_by_path(&root_path)?;
+    if existing.is_some() && !replace {
+        return Err(AppError::InvalidArgument(format!(
+            "A project is already indexed at {}; import with replace to overwrite it",
+            root_path
+        )));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let project = Project {
+        id: uuid::Uuid::new_v4().to_string(),
+        root_path: root_path.clone(),
+        name: manifest.project.name.clone(),
+        last_scan_time: manifest.project.last_scan_time,
+        file_count: manifest.project.file_count,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut ids = HashMap::new();
+    let mut assets: Vec<Asset> = read_lines(&mut archive, ASSETS_ENTRY)?;
+    for asset in &mut assets {
+        // A shared snapshot could point rows outside the project, where
+        // moving, trashing and exporting them would then act
+        if safe_relative_path(&asset.relative_path).is_none() {
+            return Err(AppError::InvalidArgument(format!(
+                "Snapshot asset {} is not a path inside the project",
+                asset.relative_path
+            )));
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        ids.insert(std::mem::replace(&mut asset.id, id), asset.id.clone());
+        asset.project_id = project.id.clone();
+        asset.absolute_path = Path::new(&root_path)
+            .join(&asset.relative_path)
+            .to_string_lossy()
+            .to_string();
+    }
+
+    let mut deps: Vec<Dependency> = read_lines(&mut archive, DEPENDENCIES_ENTRY)?;
+    deps.retain_mut(|dep| {
+        let Some(from) = ids.get(&dep.from_asset_id) else {
+            return false;
+        };
+        dep.id = uuid::Uuid::new_v4().to_string();
+        dep.from_asset_id = from.clone();
+        dep.to_asset_id = dep.to_asset_id.as_ref().and_then(|id| ids.get(id)).cloned();
+        true
+    });
+
+    // Thumbnails go into the local cache under the new asset ids
+    fs::create_dir_all(thumbnail_dir)?;
+    let total = assets.iter().filter(|a| a.thumbnail_path.is_some()).count();
+    let mut extracted: Vec<PathBuf> = Vec::new();
+    for asset in assets.iter_mut() {
+        let Some(entry) = asset.thumbnail_path.take() else {
+            continue;
+        };
+        if !entry.starts_with(THUMBNAILS_PREFIX) {
+            continue;
+        }
+        let extension = Path::new(&entry).extension().and_then(|e| e.to_str()).unwrap_or("png");
+        let dest = thumbnail_dir.join(format!("{}.{}", asset.id, extension));
+
+        let copied = archive
+            .by_name(&entry)
+            .map_err(zip_error)
+            .and_then(|mut file| Ok(std::io::copy(&mut file, &mut fs::File::create(&dest)?)?));
+        match copied {
+            Ok(_) => {
+                asset.thumbnail_path = Some(dest.to_string_lossy().to_string());
+                extracted.push(dest);
+            }
+            Err(e) => tracing::warn!("Skipping thumbnail {}: {}", entry, e),
+        }
+
+        if !progress(extracted.len(), total) {
+            remove_files(&extracted);
+            return Ok(SnapshotImport {
+                project: None,
+                stats: manifest.stats,
+                completed: false,
+            });
+        }
+    }
+
+    let stats = stats_for(&assets, &deps, extracted.len(), manifest.stats.type_counts.clone());
+
+    match db.import_project(&project, &assets, &deps, existing.as_ref().map(|p| p.id.as_str())) {
+        Ok(replaced_thumbnails) => remove_files(&replaced_thumbnails),
+        Err(e) => {
+            remove_files(&extracted);
+            return Err(e);
+        }
+    }
+
+    tracing::info!(
+        "Imported snapshot of {} ({} assets) at {}",
+        project.name,
+        assets.len(),
+        project.root_path
+    );
+    Ok(SnapshotImport {
+        project: Some(project),
+        stats,
+        completed: true,
+    })
+}
+
+fn read_manifest(archive: &mut ZipArchive<fs::File>) -> AppResult<SnapshotManifest> {
+    let mut content = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| AppError::InvalidArgument("Not a Scythe snapshot: snapshot.json is missing".to_string()))?
+        .read_to_string(&mut content)?;
+
+    let manifest: SnapshotManifest = serde_json::from_str(&content)?;
+    if manifest.format != SNAPSHOT_FORMAT {
+        return Err(AppError::InvalidArgument(format!(
+            "Not a Scythe snapshot (format {})",
+            manifest.format
+        )));
+    }
+    if manifest.version > SNAPSHOT_VERSION {
+        return Err(AppError::InvalidArgument(format!(
+            "Snapshot version {} needs a newer version of Scythe (this one reads up to {})",
+            manifest.version, SNAPSHOT_VERSION
+        )));
+    }
+    Ok(manifest)
+}
+
+fn read_lines<T: serde::de::DeserializeOwned>(archive: &mut ZipArchive<fs::File>, name: &str) -> AppResult<Vec<T>> {
+    let file = archive.by_name(name).map_err(zip_error)?;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            records.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(records)
+}
+
+fn stats_for(assets: &[Asset], deps: &[Dependency], thumbnail_count: usize, type_counts: Vec<TypeCount>) -> SnapshotStats {
+    SnapshotStats {
+        asset_count: assets.len(),
+        dependency_count: deps.len(),
+        thumbnail_count,
+        total_bytes: assets.iter().map(|a| a.size_bytes).sum(),
+        type_counts,
+    }
+}
+
+fn start_entry(writer: &mut ZipWriter<fs::File>, name: &str, method: CompressionMethod) -> AppResult<()> {
+    let options = SimpleFileOptions::default().compression_method(method).large_file(true);
+    writer.start_file(name, options).map_err(zip_error)
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+fn remove_files<P: AsRef<Path>>(paths: &[P]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::Custom(format!("Snapshot archive error: {}", e))
+}
@@ -0,0 +1,307 @@
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DATABASE_FILE: &str = "scythe.db";
+/// SQLite side files that must travel with the database
+const DATABASE_SIDE_FILES: &[&str] = &["scythe.db-wal", "scythe.db-shm"];
+const THUMBNAIL_DIR: &str = "thumbnails";
+const PLUGIN_DIR: &str = "plugins";
+const RULES_DIR: &str = "rules";
+const DRAG_STAGING_DIR: &str = "scythe-drag";
+
+/// Locations in use before a storage change, recorded until the next startup
+/// moves the data. The database can't be moved while the pool has it open.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMove {
+    pub from_database_dir: String,
+    pub from_thumbnail_dir: String,
+}
+
+/// Thumbnail directory change applied during startup, so the stored
+/// absolute thumbnail paths can be rewritten once the database is open
+pub struct ThumbnailRebase {
+    pub from: String,
+    pub to: String,
+}
+
+pub fn database_dir(settings: &Settings, app_data_dir: &Path) -> PathBuf {
+    settings
+        .database_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| app_data_dir.to_path_buf())
+}
+
+pub fn thumbnail_dir(settings: &Settings, app_data_dir: &Path) -> PathBuf {
+    settings
+        .thumbnail_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| app_data_dir.join(THUMBNAIL_DIR))
+}
+
+/// Folder scanned for plugin libraries at startup
+pub fn plugins_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(PLUGIN_DIR)
+}
+
+/// Folder holding the user's validation rules (`<name>.rhai`)
+pub fn rules_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(RULES_DIR)
+}
+
+/// Temp folder that `stage_assets_for_drag` stages files under
+pub fn drag_staging_dir() -> PathBuf {
+    std::env::temp_dir().join(DRAG_STAGING_DIR)
+}
+
+/// Database file of a shared catalog folder
+pub fn shared_catalog_database(catalog_dir: &Path) -> PathBuf {
+    catalog_dir.join(DATABASE_FILE)
+}
+
+/// Thumbnails of a shared catalog live next to its database
+pub fn shared_catalog_thumbnails(catalog_dir: &Path) -> PathBuf {
+    catalog_dir.join(THUMBNAIL_DIR)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedCatalog {
+    pub database_path: String,
+    pub thumbnails_copied: usize,
+    /// Thumbnails in the shared folder that no longer exist locally
+    pub thumbnails_removed: usize,
+}
+
+/// Publish the local catalog to `dest_dir` (e.g. a network share) for
+/// teammates to open read-only: `scythe.db` plus a `thumbnails` folder.
+/// Only changed thumbnails are copied. The database is swapped in last so
+/// readers never see rows pointing at thumbnails that aren't there yet.
+pub fn publish_catalog(db: &Database, thumbnail_dir: &Path, dest_dir: &Path) -> AppResult<PublishedCatalog> {
+    validate_storage_dir(dest_dir)?;
+    let dest_thumbs = shared_catalog_thumbnails(dest_dir);
+    fs::create_dir_all(&dest_thumbs)?;
+
+    let mut thumbnails_copied = 0;
+    let mut local = std::collections::HashSet::new();
+    if thumbnail_dir.is_dir() {
+        for entry in fs::read_dir(thumbnail_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let target = dest_thumbs.join(entry.file_name());
+            let size = entry.metadata()?.len();
+            // Thumbnail names include the source's mtime, so same name and size means same image
+            if fs::metadata(&target).map(|m| m.len() != size).unwrap_or(true) {
+                fs::copy(entry.path(), &target)?;
+                thumbnails_copied += 1;
+            }
+            local.insert(entry.file_name());
+        }
+    }
+
+    let partial = dest_dir.join(format!("{}.partial", DATABASE_FILE));
+    let _ = fs::remove_file(&partial);
+    let written = db.write_published_copy(&partial, &thumbnail_dir.to_string_lossy());
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    let database_path = shared_catalog_database(dest_dir);
+    fs::rename(&partial, &database_path).map_err(|e| {
+        let _ = fs::remove_file(&partial);
+        AppError::Custom(format!(
+            "Failed to replace {} (is it open on another machine?): {}",
+            database_path.display(),
+            e
+        ))
+    })?;
+
+    let mut thumbnails_removed = 0;
+    for entry in fs::read_dir(&dest_thumbs)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && !local.contains(&entry.file_name()) {
+            fs::remove_file(entry.path())?;
+            thumbnails_removed += 1;
+        }
+    }
+
+    tracing::info!(
+        "Published catalog to {} ({} thumbnails copied, {} removed)",
+        dest_dir.display(),
+        thumbnails_copied,
+        thumbnails_removed
+    );
+    Ok(PublishedCatalog {
+        database_path: database_path.to_string_lossy().to_string(),
+        thumbnails_copied,
+        thumbnails_removed,
+    })
+}
+
+/// Check that a folder exists (or can be created) and is writable
+pub fn validate_storage_dir(dir: &Path) -> AppResult<()> {
+    if !dir.is_absolute() {
+        return Err(AppError::InvalidArgument(format!(
+            "Storage location must be an absolute path: {}",
+            dir.display()
+        )));
+    }
+
+    fs::create_dir_all(dir)?;
+
+    let probe = dir.join(".scythe-write-test");
+    fs::write(&probe, b"ok").map_err(|e| {
+        AppError::InvalidArgument(format!("Storage location is not writable ({}): {}", dir.display(), e))
+    })?;
+    let _ = fs::remove_file(&probe);
+
+    Ok(())
+}
+
+/// Move data left behind by a storage change. Runs before the database is opened.
+/// Files are copied to the new location and the originals deleted only once
+/// every copy succeeded; on failure the partial copies are removed and the
+/// previous locations kept, so nothing is lost.
+pub fn apply_pending_move(settings: &mut Settings, app_data_dir: &Path) -> Option<ThumbnailRebase> {
+    let pending = settings.pending_storage_move.take()?;
+
+    let from_db = PathBuf::from(&pending.from_database_dir);
+    let to_db = database_dir(settings, app_data_dir);
+    if from_db != to_db {
+        match copy_database(&from_db, &to_db) {
+            Ok(sources) => {
+                remove_sources(&sources);
+                tracing::info!("Moved database from {} to {}", from_db.display(), to_db.display());
+            }
+            Err(e) => {
+                tracing::error!("Failed to move database to {}: {}", to_db.display(), e);
+                settings.database_dir = Some(pending.from_database_dir.clone());
+            }
+        }
+    }
+
+    let from_thumbs = PathBuf::from(&pending.from_thumbnail_dir);
+    let to_thumbs = thumbnail_dir(settings, app_data_dir);
+    if from_thumbs == to_thumbs {
+        return None;
+    }
+
+    match copy_dir_contents(&from_thumbs, &to_thumbs) {
+        Ok(sources) => {
+            remove_sources(&sources);
+            // Only removes the folder if nothing else was left in it
+            let _ = fs::remove_dir(&from_thumbs);
+            tracing::info!("Moved {} thumbnails to {}", sources.len(), to_thumbs.display());
+            Some(ThumbnailRebase {
+                from: from_thumbs.to_string_lossy().to_string(),
+                to: to_thumbs.to_string_lossy().to_string(),
+            })
+        }
+        Err(e) => {
+            tracing::error!("Failed to move thumbnails to {}: {}", to_thumbs.display(), e);
+            settings.thumbnail_dir = Some(pending.from_thumbnail_dir);
+            None
+        }
+    }
+}
+
+/// Copy the database and its side files into `to_dir`, returning the
+/// originals to delete. Nothing is left in `to_dir` on failure.
+fn copy_database(from_dir: &Path, to_dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let from = from_dir.join(DATABASE_FILE);
+    if !from.exists() {
+        return Ok(Vec::new());
+    }
+
+    let to = to_dir.join(DATABASE_FILE);
+    if to.exists() {
+        return Err(AppError::Custom(format!(
+            "{} already contains a database",
+            to_dir.display()
+        )));
+    }
+
+    fs::create_dir_all(to_dir)?;
+    // Side files without a database are left over from an earlier one and
+    // would be replayed into the copy
+    for side in DATABASE_SIDE_FILES {
+        let _ = fs::remove_file(to_dir.join(side));
+    }
+    let files: Vec<&str> = std::iter::once(DATABASE_FILE)
+        .chain(DATABASE_SIDE_FILES.iter().copied())
+        .filter(|name| from_dir.join(name).exists())
+        .collect();
+    let mut copied = Vec::new();
+    for name in &files {
+        let target = to_dir.join(name);
+        copied.push(target.clone());
+        if let Err(e) = fs::copy(from_dir.join(name), &target) {
+            remove_copies(&copied);
+            return Err(e.into());
+        }
+    }
+    Ok(files.iter().map(|name| from_dir.join(name)).collect())
+}
+
+/// Copy the files of `from` into `to`, returning the originals to delete.
+/// Files already in `to` with the same name and size are kept as they are,
+/// any other clash fails; the copies made are removed again on failure.
+fn copy_dir_contents(from: &Path, to: &Path) -> AppResult<Vec<PathBuf>> {
+    fs::create_dir_all(to)?;
+    if !from.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut sources = Vec::new();
+    let mut copied = Vec::new();
+    let result = (|| -> AppResult<()> {
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let target = to.join(entry.file_name());
+            match fs::symlink_metadata(&target) {
+                // Thumbnail names include the source's mtime, so same name
+                // and size means same image
+                Ok(existing) if existing.is_file() && existing.len() == entry.metadata()?.len() => {}
+                Ok(_) => {
+                    return Err(AppError::Custom(format!("{} already exists", target.display())));
+                }
+                Err(_) => {
+                    copied.push(target.clone());
+                    fs::copy(entry.path(), &target)?;
+                }
+            }
+            sources.push(entry.path());
+        }
+        Ok(())
+    })();
+    if let Err(e) = result {
+        remove_copies(&copied);
+        return Err(e);
+    }
+    Ok(sources)
+}
+
+fn remove_copies(copies: &[PathBuf]) {
+    for copy in copies {
+        let _ = fs::remove_file(copy);
+    }
+}
+
+/// Delete originals once their copies are in place; a file left behind only
+/// wastes space, so failures are logged
+fn remove_sources(sources: &[PathBuf]) {
+    for source in sources {
+        if let Err(e) = fs::remove_file(source) {
+            tracing::warn!("Failed to remove {} after moving it: {}", source.display(), e);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! User tags and free-form metadata fields on assets, kept in `asset_tags`
+//! and `asset_fields` and edited in bulk: one backend operation over every
+//! asset matching a grid filter, in a single transaction.
+
+use crate::catalog::AssetListFilter;
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Longest tag, field name or field value accepted
+const MAX_TAG_CHARS: usize = 100;
+const MAX_VALUE_CHARS: usize = 4000;
+
+/// One change applied to every targeted asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkEdit {
+    AddTag { tag: String },
+    RemoveTag { tag: String },
+    /// Set a metadata field; no value clears it
+    SetField { key: String, value: Option<String> },
+}
+
+impl BulkEdit {
+    /// Trimmed copy, or why it can't be applied
+    pub fn normalized(&self) -> AppResult<BulkEdit> {
+        Ok(match self {
+            BulkEdit::AddTag { tag } => BulkEdit::AddTag { tag: name(tag, "Tag")? },
+            BulkEdit::RemoveTag { tag } => BulkEdit::RemoveTag { tag: name(tag, "Tag")? },
+            BulkEdit::SetField { key, value } => {
+                if value.as_ref().is_some_and(|v| v.chars().count() > MAX_VALUE_CHARS) {
+                    return Err(AppError::InvalidArgument(format!(
+                        "Field values are limited to {} characters",
+                        MAX_VALUE_CHARS
+                    )));
+                }
+                BulkEdit::SetField {
+                    key: name(key, "Field name")?,
+                    value: value.clone(),
+                }
+            }
+        })
+    }
+}
+
+fn name(text: &str, what: &str) -> AppResult<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::InvalidArgument(format!("{} is empty", what)));
+    }
+    if trimmed.chars().count() > MAX_TAG_CHARS {
+        return Err(AppError::InvalidArgument(format!(
+            "{} is longer than {} characters",
+            what, MAX_TAG_CHARS
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkEditResult {
+    /// Assets the edit was applied to
+    pub matched: usize,
+    /// Of those, the ones it changed
+    pub changed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetTags {
+    pub tags: Vec<String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// Apply `edit` to `asset_ids` (those in the project), or when there are
+/// none to every asset of the project matching `filter`.
+/// `progress(done, total)` is called as rows are written.
+pub fn bulk_edit(
+    db: &Database,
+    project_id: &str,
+    filter: &AssetListFilter,
+    asset_ids: Option<Vec<String>>,
+    edit: &BulkEdit,
+    progress: impl FnMut(usize, usize),
+) -> AppResult<BulkEditResult> {
+    let edit = edit.normalized()?;
+    let ids = match asset_ids {
+        Some(ids) => ids,
+        None => db.get_filtered_asset_ids(project_id, filter.search_query.as_deref(), filter.asset_types.as_deref())?,
+    };
+    let changed = db.apply_bulk_edit(project_id, &ids, &edit, progress)?;
+    tracing::info!("Bulk edit {:?} changed {} of {} assets", edit, changed, ids.len());
+
+    Ok(BulkEditResult {
+        matched: ids.len(),
+        changed,
+    })
+}
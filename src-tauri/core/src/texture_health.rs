@@ -0,0 +1,182 @@
+//! Textures whose source or importer settings waste memory or quality:
+//! non-power-of-two sizes, sources larger than the importer's max size,
+//! uncompressed build formats and alpha channels that are fully opaque.
+//!
+//! The first three come from the stored texture estimates, so they are as
+//! of the last scan or estimate run. The alpha check decodes each image
+//! that has an alpha channel and is kept in `texture_alpha` per file
+//! version, so only changed textures are decoded again.
+
+use crate::db::{Asset, Database, TextureEstimateEntry};
+use crate::error::AppResult;
+use crate::previews::{open_image_limited, read_image_has_alpha, ThumbnailOptions};
+use crate::texture_size;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Textures listed per issue, largest first; counts include all of them
+pub const DEFAULT_LIMIT: usize = 500;
+
+const ISSUES: &[&str] = &["non_power_of_two", "over_max_size", "uncompressed", "opaque_alpha"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureHealthIssue {
+    /// "non_power_of_two", "over_max_size", "uncompressed" or "opaque_alpha"
+    pub issue: String,
+    pub count: usize,
+    /// Source bytes of every texture with the issue
+    pub total_bytes: i64,
+    pub textures: Vec<TextureEstimateEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureHealthReport {
+    /// Textures with importer settings; the others can't be judged
+    pub checked: usize,
+    /// Of those, the ones with an alpha channel that was read
+    pub alpha_checked: usize,
+    /// Every issue, including those no texture has
+    pub issues: Vec<TextureHealthIssue>,
+}
+
+/// Check every estimated texture in the project. `threads` decode alpha
+/// channels not checked since the file last changed.
+pub fn check_project(
+    db: &Database,
+    project_id: &str,
+    options: &ThumbnailOptions,
+    threads: usize,
+    limit: usize,
+) -> AppResult<TextureHealthReport> {
+    let mut entries = db.get_texture_estimates(project_id, i64::MAX)?;
+    entries.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.relative_path.cmp(&b.relative_path)));
+    let estimated: HashSet<&str> = entries.iter().map(|e| e.asset_id.as_str()).collect();
+    let alpha = alpha_channels(db, project_id, &estimated, options, threads)?;
+
+    let issues = ISSUES
+        .iter()
+        .map(|&issue| {
+            let flagged: Vec<&TextureEstimateEntry> =
+                entries.iter().filter(|e| has_issue(issue, e, &alpha.opaque)).collect();
+            TextureHealthIssue {
+                issue: issue.to_string(),
+                count: flagged.len(),
+                total_bytes: flagged.iter().map(|e| e.size_bytes).sum(),
+                textures: flagged.into_iter().take(limit).cloned().collect(),
+            }
+        })
+        .collect();
+
+    Ok(TextureHealthReport {
+        checked: entries.len(),
+        alpha_checked: alpha.with_alpha,
+        issues,
+    })
+}
+
+fn has_issue(issue: &str, entry: &TextureEstimateEntry, opaque_alpha: &HashSet<String>) -> bool {
+    let estimate = &entry.estimate;
+    match issue {
+        "non_power_of_two" => !estimate.width.is_power_of_two() || !estimate.height.is_power_of_two(),
+        "over_max_size" => estimate.width.max(estimate.height) > estimate.max_size,
+        "uncompressed" => !texture_size::is_compressed_format(&estimate.format),
+        "opaque_alpha" => opaque_alpha.contains(&entry.asset_id),
+        _ => false,
+    }
+}
+
+struct AlphaChannels {
+    /// Textures that have an alpha channel
+    with_alpha: usize,
+    /// Ids of those whose alpha is opaque everywhere
+    opaque: HashSet<String>,
+}
+
+/// Alpha channels of the `estimated` textures, reading those not checked
+/// since they last changed
+fn alpha_channels(
+    db: &Database,
+    project_id: &str,
+    estimated: &HashSet<&str>,
+    options: &ThumbnailOptions,
+    threads: usize,
+) -> AppResult<AlphaChannels> {
+    let stored = db.get_texture_alpha(project_id)?;
+    let mut checked = Vec::new();
+    let mut stale: Vec<Asset> = Vec::new();
+    for asset in db.get_assets_by_types(project_id, &["texture".to_string()])? {
+        if !estimated.contains(asset.id.as_str()) {
+            continue;
+        }
+        match stored.get(&asset.id) {
+            Some(&(modified_time, size_bytes, has_alpha, opaque))
+                if modified_time == asset.modified_time && size_bytes == asset.size_bytes =>
+            {
+                checked.push((asset.id, has_alpha, opaque))
+            }
+            _ => stale.push(asset),
+        }
+    }
+
+    if !stale.is_empty() {
+        let next = AtomicUsize::new(0);
+        let read = Mutex::new(Vec::new());
+        std::thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, stale.len()) {
+                scope.spawn(|| {
+                    while let Some(asset) = stale.get(next.fetch_add(1, Ordering::SeqCst)) {
+                        let (has_alpha, opaque) = read_alpha(Path::new(&asset.absolute_path), options);
+                        read.lock().unwrap().push((asset, has_alpha, opaque));
+                    }
+                });
+            }
+        });
+        let read = read.into_inner().unwrap();
+
+        let rows: Vec<(String, i64, i64, bool, bool)> = read
+            .iter()
+            .map(|(asset, has_alpha, opaque)| (asset.id.clone(), asset.modified_time, asset.size_bytes, *has_alpha, *opaque))
+            .collect();
+        // A shared catalog is read-only; the results are just not kept
+        if let Err(e) = db.save_texture_alpha(&rows) {
+            tracing::debug!("Texture alpha checks not stored: {}", e);
+        }
+        tracing::info!("Checked alpha channels of {} textures", rows.len());
+        checked.extend(read.into_iter().map(|(asset, has_alpha, opaque)| (asset.id.clone(), has_alpha, opaque)));
+    }
+
+    Ok(AlphaChannels {
+        with_alpha: checked.iter().filter(|(_, has_alpha, _)| *has_alpha).count(),
+        opaque: checked
+            .into_iter()
+            .filter(|(_, has_alpha, opaque)| *has_alpha && *opaque)
+            .map(|(id, ..)| id)
+            .collect(),
+    })
+}
+
+/// (has an alpha channel, every pixel of it is opaque). Images too large
+/// to decode, or that fail to, count as not opaque.
+fn read_alpha(path: &Path, options: &ThumbnailOptions) -> (bool, bool) {
+    if read_image_has_alpha(path) != Some(true) {
+        return (false, false);
+    }
+    if options.exceeds_decode_budget(path) {
+        return (true, false);
+    }
+    let Ok(image) = open_image_limited(path, options) else {
+        return (true, false);
+    };
+    let opaque = match image {
+        image::DynamicImage::ImageLumaA8(image) => image.pixels().all(|p| p[1] == u8::MAX),
+        image::DynamicImage::ImageRgba8(image) => image.pixels().all(|p| p[3] == u8::MAX),
+        image::DynamicImage::ImageLumaA16(image) => image.pixels().all(|p| p[1] == u16::MAX),
+        image::DynamicImage::ImageRgba16(image) => image.pixels().all(|p| p[3] == u16::MAX),
+        image::DynamicImage::ImageRgba32F(image) => image.pixels().all(|p| p[3] >= 1.0),
+        other => other.to_rgba8().pixels().all(|p| p[3] == u8::MAX),
+    };
+    (true, opaque)
+}
@@ -0,0 +1,343 @@
+//! Estimated in-build size of textures, worked out from the importer
+//! settings in each `.meta` (max size, format, compression, crunch, mip
+//! maps) and the source dimensions. Raw file size says little about build
+//! cost: a 40 MB layered PSD capped at 512 px costs less than a small PNG
+//! imported uncompressed at 4K.
+//!
+//! Estimates are for desktop builds (the Standalone override, else the
+//! default platform settings). Automatic formats resolve the way Unity picks
+//! them there: DXT1/DXT5, BC7 for high quality, BC4 for single channel and
+//! BC6H for HDR sources. Crunch is content-dependent, so crunched textures
+//! are counted at a typical ratio.
+
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::previews::{read_image_dimensions, read_image_has_alpha};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Typical size of crunched DXT data relative to plain DXT
+const CRUNCH_RATIO: f64 = 0.5;
+const DEFAULT_MAX_SIZE: u32 = 2048;
+const PLATFORM: &str = "Standalone";
+const DEFAULT_PLATFORM: &str = "DefaultTexturePlatform";
+
+// TextureImporterType values that change the automatic format or NPOT handling
+const TYPE_NORMAL_MAP: i64 = 1;
+const TYPE_GUI: i64 = 2;
+const TYPE_SPRITE: i64 = 8;
+const TYPE_SINGLE_CHANNEL: i64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureEstimate {
+    /// Source image size
+    pub width: u32,
+    pub height: u32,
+    /// After power-of-two scaling and the max size cap
+    pub build_width: u32,
+    pub build_height: u32,
+    pub max_size: u32,
+    /// Unity texture format name, e.g. "DXT5" or "RGBA32"
+    pub format: String,
+    pub crunched: bool,
+    pub mipmaps: bool,
+    pub estimated_bytes: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TextureEstimateStats {
+    pub textures: usize,
+    pub estimated: usize,
+    pub unchanged: usize,
+    /// No `.meta` with texture importer settings, or unreadable dimensions
+    pub unsupported: usize,
+    /// False when cancelled; estimates made so far are kept
+    pub completed: bool,
+}
+
+/// Estimate the texture at `path` from its `.meta`. None when there is no
+/// texture importer section or the image header can't be read.
+pub fn estimate(path: &Path) -> Option<TextureEstimate> {
+    let meta = fs::read_to_string(meta_path(path)).ok()?;
+    let settings = ImporterSettings::parse(&meta)?;
+    let (width, height) = read_image_dimensions(path)?;
+
+    let hdr = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("exr") || e.eq_ignore_ascii_case("hdr"));
+    let has_alpha = match settings.alpha_usage {
+        0 => false,
+        2 => true,
+        // From the source; assume alpha when the header can't tell
+        _ => read_image_has_alpha(path).unwrap_or(true),
+    };
+    let (format, bits_per_pixel) = settings.format(has_alpha, hdr);
+
+    let (mut build_width, mut build_height) = (width.max(1), height.max(1));
+    if settings.npot_scale != 0 && settings.texture_type != TYPE_SPRITE && settings.texture_type != TYPE_GUI {
+        build_width = nearest_power_of_two(build_width);
+        build_height = nearest_power_of_two(build_height);
+    }
+    let largest = build_width.max(build_height);
+    if largest > settings.max_size {
+        let scale = settings.max_size as f64 / largest as f64;
+        build_width = ((build_width as f64 * scale).round() as u32).max(1);
+        build_height = ((build_height as f64 * scale).round() as u32).max(1);
+    }
+
+    let mut bytes = build_width as f64 * build_height as f64 * bits_per_pixel / 8.0;
+    if settings.mipmaps {
+        // A full mip chain adds a third
+        bytes *= 4.0 / 3.0;
+    }
+    let crunched = format.ends_with("Crunched");
+    if crunched {
+        bytes *= CRUNCH_RATIO;
+    }
+
+    Some(TextureEstimate {
+        width,
+        height,
+        build_width,
+        build_height,
+        max_size: settings.max_size,
+        format,
+        crunched,
+        mipmaps: settings.mipmaps,
+        estimated_bytes: bytes.round() as i64,
+    })
+}
+
+/// Modification time of the asset's `.meta`, 0 when it has none. Importer
+/// changes only touch the `.meta`, so estimates are keyed on both files.
+pub fn meta_modified_time(path: &Path) -> i64 {
+    fs::metadata(meta_path(path))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Estimate every texture whose file or `.meta` changed since it was last
+/// estimated. `progress(done, total)` returns false to cancel.
+pub fn estimate_project(
+    db: &Database,
+    project_id: &str,
+    mut progress: impl FnMut(usize, usize) -> bool,
+) -> AppResult<TextureEstimateStats> {
+    let textures = db.get_assets_by_types(project_id, &["texture".to_string()])?;
+    let known = db.get_texture_estimate_versions(project_id)?;
+    let mut stats = TextureEstimateStats {
+        textures: textures.len(),
+        ..Default::default()
+    };
+
+    for (i, asset) in textures.iter().enumerate() {
+        if !progress(i, textures.len()) {
+            return Ok(stats);
+        }
+
+        let path = Path::new(&asset.absolute_path);
+        let meta_time = meta_modified_time(path);
+        if known.get(&asset.id) == Some(&(asset.modified_time, meta_time)) {
+            stats.unchanged += 1;
+            continue;
+        }
+
+        match estimate(path) {
+            Some(estimate) => {
+                db.save_texture_estimate(&asset.id, asset.modified_time, meta_time, &estimate)?;
+                stats.estimated += 1;
+            }
+            None => {
+                db.delete_texture_estimate(&asset.id)?;
+                stats.unsupported += 1;
+            }
+        }
+    }
+
+    stats.completed = true;
+    Ok(stats)
+}
+
+/// Whether a format name from `TextureEstimate::format` is block compressed
+pub fn is_compressed_format(format: &str) -> bool {
+    ["DXT", "BC", "PVRTC", "ETC", "EAC", "ASTC"]
+        .iter()
+        .any(|prefix| format.starts_with(prefix))
+}
+
+fn meta_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.meta", path.display()))
+}
+
+fn nearest_power_of_two(value: u32) -> u32 {
+    let upper = value.next_power_of_two();
+    let lower = upper / 2;
+    if lower > 0 && value - lower < upper - value {
+        lower
+    } else {
+        upper
+    }
+}
+
+/// The parts of a `TextureImporter` block the estimate needs
+struct ImporterSettings {
+    texture_type: i64,
+    max_size: u32,
+    /// TextureFormat id, or -1 for automatic
+    texture_format: i64,
+    /// 0 none, 1 normal, 2 low, 3 high quality
+    compression: i64,
+    crunched: bool,
+    mipmaps: bool,
+    /// 0 none, 1 from input, 2 from grayscale
+    alpha_usage: i64,
+    npot_scale: i64,
+}
+
+impl ImporterSettings {
+    /// Top-level keys sit at two spaces of indentation; `platformSettings`
+    /// is a list of maps starting at `  - `
+    fn parse(meta: &str) -> Option<Self> {
+        if !meta.lines().any(|line| line.trim_end() == "TextureImporter:") {
+            return None;
+        }
+
+        let mut top: HashMap<&str, &str> = HashMap::new();
+        let mut platforms: Vec<HashMap<&str, &str>> = Vec::new();
+        let mut in_platforms = false;
+        let mut mipmaps = None;
+        for line in meta.lines() {
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            if let Some(value) = trimmed.strip_prefix("enableMipMap:") {
+                mipmaps = Some(value.trim() == "1");
+            }
+
+            if indent == 2 && !trimmed.starts_with("- ") {
+                in_platforms = trimmed == "platformSettings:";
+                if let Some((key, value)) = trimmed.split_once(':') {
+                    top.insert(key, value.trim());
+                }
+            } else if in_platforms && indent <= 4 {
+                let entry = match trimmed.strip_prefix("- ") {
+                    Some(first) => {
+                        platforms.push(HashMap::new());
+                        first
+                    }
+                    None => trimmed,
+                };
+                if let (Some(platform), Some((key, value))) = (platforms.last_mut(), entry.split_once(':')) {
+                    platform.insert(key, value.trim());
+                }
+            }
+        }
+
+        let target = |name: &str| platforms.iter().find(|p| p.get("buildTarget") == Some(&name));
+        let platform = target(PLATFORM)
+            .filter(|p| p.get("overridden") == Some(&"1"))
+            .or_else(|| target(DEFAULT_PLATFORM));
+        let int = |key: &str| {
+            platform
+                .and_then(|p| p.get(key))
+                .or_else(|| top.get(key))
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+        let top_int = |key: &str| top.get(key).and_then(|v| v.parse::<i64>().ok());
+
+        Some(Self {
+            texture_type: top_int("textureType").unwrap_or(0),
+            max_size: int("maxTextureSize")
+                .filter(|size| *size > 0)
+                .map(|size| size as u32)
+                .unwrap_or(DEFAULT_MAX_SIZE),
+            texture_format: int("textureFormat").unwrap_or(-1),
+            compression: int("textureCompression").unwrap_or(1),
+            crunched: int("crunchedCompression") == Some(1),
+            mipmaps: mipmaps.unwrap_or(true),
+            alpha_usage: top_int("alphaUsage").unwrap_or(1),
+            npot_scale: top_int("nPOTScale").unwrap_or(1),
+        })
+    }
+
+    /// Format name and bits per pixel
+    fn format(&self, has_alpha: bool, hdr: bool) -> (String, f64) {
+        if self.texture_format >= 0 {
+            if let Some((name, bits)) = format_by_id(self.texture_format) {
+                return (name.to_string(), bits);
+            }
+        }
+
+        let (name, bits) = match (self.compression, self.texture_type) {
+            (0, TYPE_SINGLE_CHANNEL) => ("R8", 8.0),
+            (0, _) if hdr => ("RGBAHalf", 64.0),
+            (0, _) if has_alpha => ("RGBA32", 32.0),
+            (0, _) => ("RGB24", 24.0),
+            (_, TYPE_SINGLE_CHANNEL) => ("BC4", 4.0),
+            _ if hdr => ("BC6H", 8.0),
+            (3, _) => ("BC7", 8.0),
+            (_, TYPE_NORMAL_MAP) => ("DXT5", 8.0),
+            _ if has_alpha => ("DXT5", 8.0),
+            _ => ("DXT1", 4.0),
+        };
+        if self.crunched && name.starts_with("DXT") {
+            return (format!("{}Crunched", name), bits);
+        }
+        (name.to_string(), bits)
+    }
+}
+
+/// UnityEngine.TextureFormat ids of common formats and their bits per pixel
+fn format_by_id(id: i64) -> Option<(&'static str, f64)> {
+    Some(match id {
+        1 => ("Alpha8", 8.0),
+        2 => ("ARGB4444", 16.0),
+        3 => ("RGB24", 24.0),
+        4 => ("RGBA32", 32.0),
+        5 => ("ARGB32", 32.0),
+        7 => ("RGB565", 16.0),
+        9 => ("R16", 16.0),
+        10 => ("DXT1", 4.0),
+        12 => ("DXT5", 8.0),
+        13 => ("RGBA4444", 16.0),
+        14 => ("BGRA32", 32.0),
+        15 => ("RHalf", 16.0),
+        16 => ("RGHalf", 32.0),
+        17 => ("RGBAHalf", 64.0),
+        18 => ("RFloat", 32.0),
+        19 => ("RGFloat", 64.0),
+        20 => ("RGBAFloat", 128.0),
+        22 => ("RGB9e5Float", 32.0),
+        24 => ("BC6H", 8.0),
+        25 => ("BC7", 8.0),
+        26 => ("BC4", 4.0),
+        27 => ("BC5", 8.0),
+        28 => ("DXT1Crunched", 4.0),
+        29 => ("DXT5Crunched", 8.0),
+        30 => ("PVRTC_RGB2", 2.0),
+        31 => ("PVRTC_RGBA2", 2.0),
+        32 => ("PVRTC_RGB4", 4.0),
+        33 => ("PVRTC_RGBA4", 4.0),
+        34 => ("ETC_RGB4", 4.0),
+        41 | 42 => ("EAC_R", 4.0),
+        43 | 44 => ("EAC_RG", 8.0),
+        45 => ("ETC2_RGB", 4.0),
+        46 => ("ETC2_RGBA1", 4.0),
+        47 => ("ETC2_RGBA8", 8.0),
+        48 | 66 => ("ASTC_4x4", 8.0),
+        49 | 67 => ("ASTC_5x5", 5.12),
+        50 | 68 => ("ASTC_6x6", 3.56),
+        51 | 69 => ("ASTC_8x8", 2.0),
+        52 | 70 => ("ASTC_10x10", 1.28),
+        53 | 71 => ("ASTC_12x12", 0.89),
+        62 => ("RG16", 16.0),
+        63 => ("R8", 8.0),
+        64 => ("ETC_RGB4Crunched", 4.0),
+        65 => ("ETC2_RGBA8Crunched", 8.0),
+        _ => return None,
+    })
+}
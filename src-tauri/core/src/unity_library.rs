@@ -0,0 +1,378 @@
+//! Read-only inspection of a project's Unity `Library` folder, to cross-check
+//! the GUID-to-path mapping Unity last imported against our own index. A
+//! path mismatch or an asset only one side knows about means either Unity's
+//! Library is stale (the project changed since the editor last ran) or the
+//! scanner missed files.
+//!
+//! Asset Database v2 (Unity 2019.3+) keeps the mapping in
+//! `Library/SourceAssetDB`, an LMDB file; its `GuidToPath` table is read
+//! straight from the file, loaded into memory first so an editor writing it
+//! meanwhile can't change pages under the reader. The older `Library/metadata/<xx>/<guid>` layout only gives GUIDs.
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::scanner::is_ignored_relative;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Entries listed per discrepancy; counts include all of them
+pub const DEFAULT_LIMIT: usize = 500;
+
+const SOURCE_ASSET_DB: &str = "Library/SourceAssetDB";
+const METADATA_DIR: &str = "Library/metadata";
+const GUID_TO_PATH: &[u8] = b"GuidToPath";
+
+const LMDB_MAGIC: u32 = 0xBEEF_C0DE;
+const PAGE_HEADER: usize = 16;
+const P_BRANCH: u16 = 0x01;
+const P_LEAF: u16 = 0x02;
+const P_LEAF2: u16 = 0x20;
+const F_BIGDATA: u16 = 0x01;
+const F_SUBDATA: u16 = 0x02;
+const F_DUPDATA: u16 = 0x04;
+/// Root page of an empty table
+const P_INVALID: u64 = u64::MAX;
+/// B-tree levels followed; real tables are a handful deep
+const MAX_TREE_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryPathMismatch {
+    pub guid: String,
+    /// Where Unity last saw the GUID
+    pub library_path: String,
+    /// Where the `.meta` holding it is now
+    pub index_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryOnlyEntry {
+    pub guid: String,
+    /// Not known for the older `Library/metadata` layout
+    pub path: Option<String>,
+    /// The file is on disk, so the scanner should have indexed it
+    pub on_disk: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexOnlyEntry {
+    pub guid: String,
+    pub relative_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryInspection {
+    pub library_dir: String,
+    /// "source_asset_db", "metadata", or `None` when the project has no
+    /// readable Library (never opened in Unity, or an unknown layout)
+    pub format: Option<String>,
+    /// When Unity last wrote the mapping, to judge how stale it may be
+    pub library_modified_at: Option<i64>,
+    /// Library entries under `Assets/` compared with the index
+    pub library_entries: usize,
+    pub matched: usize,
+    pub path_mismatch_count: usize,
+    pub path_mismatches: Vec<LibraryPathMismatch>,
+    /// In the Library but not the index: deleted since Unity last ran, or
+    /// missed by the scanner when still on disk
+    pub library_only_count: usize,
+    pub library_only: Vec<LibraryOnlyEntry>,
+    /// Indexed with a GUID the Library doesn't know: added since Unity last ran
+    pub index_only_count: usize,
+    pub index_only: Vec<IndexOnlyEntry>,
+}
+
+/// Compare the project's Library mapping with the indexed GUIDs. Folders,
+/// packages and paths the ignore patterns skip are left out on both sides.
+pub fn inspect_library(
+    db: &Database,
+    project_id: &str,
+    ignore_patterns: &[String],
+    limit: usize,
+) -> AppResult<LibraryInspection> {
+    let project = db
+        .get_project(project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", project_id)))?;
+    let root = Path::new(&project.root_path);
+
+    // GUID -> relative path with '/' separators, as Unity writes them
+    let indexed: HashMap<String, String> = db
+        .get_project_assets(project_id)?
+        .into_iter()
+        .filter_map(|a| Some((a.unity_guid?.to_lowercase(), a.relative_path.replace('\\', "/"))))
+        .filter(|(_, path)| path.starts_with("Assets/"))
+        .collect();
+    let (format, library_modified_at, entries) = match read_library_mapping(root, &indexed)? {
+        Some(mapping) => (Some(mapping.format.to_string()), mapping.modified_at, mapping.entries),
+        None => (None, None, Vec::new()),
+    };
+    let excluded = db.get_excluded_paths(project_id)?;
+    let skipped = |path: &str| {
+        !path.starts_with("Assets/")
+            || is_ignored_relative(path, ignore_patterns)
+            || excluded.contains(path)
+            || excluded.contains(&path.replace('/', std::path::MAIN_SEPARATOR_STR))
+            || root.join(path).is_dir()
+    };
+
+    let mut mismatches = Vec::new();
+    let mut library_only = Vec::new();
+    let mut seen = HashSet::new();
+    let mut compared = 0;
+    let mut matched = 0;
+    for (guid, path) in entries {
+        if path.as_deref().is_some_and(skipped) {
+            continue;
+        }
+        // Without a path, a GUID the index lacks may be a folder or a
+        // package file, so only matches are counted
+        if path.is_none() && !indexed.contains_key(&guid) {
+            continue;
+        }
+        compared += 1;
+        seen.insert(guid.clone());
+        match (indexed.get(&guid), path) {
+            (Some(index_path), Some(library_path)) if *index_path != library_path => {
+                mismatches.push(LibraryPathMismatch {
+                    guid,
+                    library_path,
+                    index_path: index_path.clone(),
+                })
+            }
+            (Some(_), _) => matched += 1,
+            (None, path) => library_only.push(LibraryOnlyEntry {
+                on_disk: path.as_deref().is_some_and(|p| root.join(p).is_file()),
+                guid,
+                path,
+            }),
+        }
+    }
+
+    let mut index_only: Vec<IndexOnlyEntry> = if format.is_some() {
+        indexed
+            .into_iter()
+            .filter(|(guid, _)| !seen.contains(guid))
+            .map(|(guid, relative_path)| IndexOnlyEntry { guid, relative_path })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    mismatches.sort_by(|a, b| a.index_path.cmp(&b.index_path));
+    // Files on disk the scanner missed matter most
+    library_only.sort_by(|a, b| b.on_disk.cmp(&a.on_disk).then_with(|| a.path.cmp(&b.path)));
+    index_only.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    Ok(LibraryInspection {
+        library_dir: root.join("Library").to_string_lossy().to_string(),
+        format,
+        library_modified_at,
+        library_entries: compared,
+        matched,
+        path_mismatch_count: mismatches.len(),
+        path_mismatches: mismatches.into_iter().take(limit).collect(),
+        library_only_count: library_only.len(),
+        library_only: library_only.into_iter().take(limit).collect(),
+        index_only_count: index_only.len(),
+        index_only: index_only.into_iter().take(limit).collect(),
+    })
+}
+
+/// What the Library says about the project's assets
+struct LibraryMapping {
+    format: &'static str,
+    modified_at: Option<i64>,
+    /// GUID (lowercase hex) and path; the older layout has no paths
+    entries: Vec<(String, Option<String>)>,
+}
+
+/// Read the Library's mapping, or `None` if there is no Library to read.
+/// `indexed` GUIDs settle how binary GUIDs are written out.
+fn read_library_mapping(root: &Path, indexed: &HashMap<String, String>) -> AppResult<Option<LibraryMapping>> {
+    let source_asset_db = root.join(SOURCE_ASSET_DB);
+    if source_asset_db.is_file() {
+        let data = fs::read(&source_asset_db)?;
+        let pairs = read_lmdb_table(&data, GUID_TO_PATH)
+            .ok_or_else(|| AppError::Custom(format!("{} is not a readable LMDB file", source_asset_db.display())))?;
+        return Ok(Some(LibraryMapping {
+            format: "source_asset_db",
+            modified_at: modified_at(&source_asset_db),
+            entries: guid_paths(pairs, indexed),
+        }));
+    }
+
+    let metadata_dir = root.join(METADATA_DIR);
+    if metadata_dir.is_dir() {
+        let mut entries = Vec::new();
+        for bucket in fs::read_dir(&metadata_dir)?.flatten() {
+            let Ok(files) = fs::read_dir(bucket.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let name = file.file_name().to_string_lossy().to_lowercase();
+                // `<guid>.info` files sit beside each `<guid>`
+                if name.len() == 32 && name.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    entries.push((name, None));
+                }
+            }
+        }
+        return Ok(Some(LibraryMapping {
+            format: "metadata",
+            modified_at: modified_at(&metadata_dir),
+            entries,
+        }));
+    }
+    Ok(None)
+}
+
+fn modified_at(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64)
+}
+
+/// Decode `GuidToPath` pairs: a key ending in the 16 GUID bytes, and a
+/// value holding the path. Unity writes GUIDs as hex with the two nibbles of
+/// each byte swapped (the form in `.meta` files); the plain byte order is
+/// used instead if it matches more indexed GUIDs. Pairs without a readable
+/// path are dropped.
+fn guid_paths(pairs: Vec<(Vec<u8>, Vec<u8>)>, indexed: &HashMap<String, String>) -> Vec<(String, Option<String>)> {
+    let decoded: Vec<(&[u8], String)> = pairs
+        .iter()
+        .filter(|(key, _)| key.len() >= 16)
+        .filter_map(|(key, value)| Some((&key[key.len() - 16..], path_in_value(value)?)))
+        .collect();
+    let swapped = |guid: &[u8]| guid.iter().map(|b| format!("{:x}{:x}", b & 0x0f, b >> 4)).collect::<String>();
+    let plain = |guid: &[u8]| guid.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    let matches = |encode: &dyn Fn(&[u8]) -> String| decoded.iter().filter(|(g, _)| indexed.contains_key(&encode(g))).count();
+    let encode: &dyn Fn(&[u8]) -> String = if matches(&plain) > matches(&swapped) { &plain } else { &swapped };
+
+    decoded.into_iter().map(|(guid, path)| (encode(guid), Some(path))).collect()
+}
+
+/// The path stored in a value, which may carry a length prefix or a
+/// trailing NUL around the text
+fn path_in_value(value: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(value);
+    let start = text.find("Assets/").or_else(|| text.find("Packages/"))?;
+    let path: String = text[start..].chars().take_while(|c| !c.is_control() && *c != '\u{fffd}').collect();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Key/value pairs of the named table `name` in an LMDB file image, or
+/// `None` if the file isn't LMDB. An absent table reads as empty.
+fn read_lmdb_table(data: &[u8], name: &[u8]) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+    // Two meta pages; the one with the later transaction is current. The page
+    // size is kept in the free-list table's pad field.
+    let page_size = read_u32(data, PAGE_HEADER + 24)? as usize;
+    if read_u32(data, PAGE_HEADER)? != LMDB_MAGIC || !(512..=65536).contains(&page_size) {
+        return None;
+    }
+    let meta = [0, page_size]
+        .into_iter()
+        .filter(|&offset| read_u32(data, offset + PAGE_HEADER) == Some(LMDB_MAGIC))
+        .max_by_key(|&offset| read_u64(data, offset + PAGE_HEADER + 128))?;
+    // Main table record: after magic, version, address, map size and the free-list record
+    let main_root = read_u64(data, meta + PAGE_HEADER + 72 + 40)?;
+
+    let reader = LmdbReader { data, page_size };
+    let mut table_root = None;
+    reader.walk(main_root, 0, &mut |key, flags, value| {
+        if flags & F_SUBDATA != 0 && key == name {
+            table_root = read_u64(value, 40);
+        }
+    });
+    let mut pairs = Vec::new();
+    if let Some(root) = table_root {
+        reader.walk(root, 0, &mut |key, flags, value| {
+            if flags & (F_SUBDATA | F_DUPDATA) == 0 {
+                pairs.push((key.to_vec(), value.to_vec()));
+            }
+        });
+    }
+    Some(pairs)
+}
+
+struct LmdbReader<'a> {
+    data: &'a [u8],
+    page_size: usize,
+}
+
+impl LmdbReader<'_> {
+    /// Visit every leaf node under `page_number` with its key, flags and
+    /// data, following overflow pages for large values. Damaged pages are
+    /// skipped.
+    fn walk(&self, page_number: u64, depth: usize, visit: &mut impl FnMut(&[u8], u16, &[u8])) {
+        if page_number == P_INVALID || depth > MAX_TREE_DEPTH {
+            return;
+        }
+        let Some(page) = self.page(page_number) else {
+            return;
+        };
+        let flags = read_u16(page, 10).unwrap_or(0);
+        // Fixed-size duplicate pages have no nodes; they only occur in
+        // sorted-duplicate tables, which aren't read
+        if flags & P_LEAF2 != 0 {
+            return;
+        }
+        let lower = read_u16(page, 12).unwrap_or(0) as usize;
+        let count = lower.saturating_sub(PAGE_HEADER) / 2;
+
+        for i in 0..count {
+            let Some(node) = read_u16(page, PAGE_HEADER + i * 2).map(|offset| offset as usize) else {
+                continue;
+            };
+            let (Some(lo), Some(hi), Some(node_flags), Some(key_size)) = (
+                read_u16(page, node),
+                read_u16(page, node + 2),
+                read_u16(page, node + 4),
+                read_u16(page, node + 6),
+            ) else {
+                continue;
+            };
+            let key_start = node + 8;
+            let Some(key) = page.get(key_start..key_start + key_size as usize) else {
+                continue;
+            };
+
+            if flags & P_BRANCH != 0 {
+                // A branch node's size fields hold the child page number
+                let child = lo as u64 | (hi as u64) << 16 | (node_flags as u64) << 32;
+                self.walk(child, depth + 1, visit);
+            } else if flags & P_LEAF != 0 {
+                let size = lo as usize | (hi as usize) << 16;
+                let data_start = key_start + key_size as usize;
+                let value = if node_flags & F_BIGDATA != 0 {
+                    read_u64(page, data_start).and_then(|overflow| self.overflow(overflow, size))
+                } else {
+                    page.get(data_start..data_start + size)
+                };
+                if let Some(value) = value {
+                    visit(key, node_flags, value);
+                }
+            }
+        }
+    }
+
+    /// A value of `size` bytes stored on consecutive overflow pages
+    fn overflow(&self, page_number: u64, size: usize) -> Option<&[u8]> {
+        let start = usize::try_from(page_number).ok()?.checked_mul(self.page_size)? + PAGE_HEADER;
+        self.data.get(start..start.checked_add(size)?)
+    }
+
+    fn page(&self, page_number: u64) -> Option<&[u8]> {
+        let start = usize::try_from(page_number).ok()?.checked_mul(self.page_size)?;
+        self.data.get(start..start.checked_add(self.page_size)?)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
@@ -0,0 +1,208 @@
+//! `validate_project`: project-wide checks for problems that break a Unity
+//! project or its references, grouped by check for a health dashboard.
+//!
+//! Everything except empty folders comes from the index, so it is as of the
+//! last scan; empty folders are found by walking the disk. Missing `.meta`
+//! files and empty folders are only checked under `Assets/` of a Unity
+//! project, where Unity creates them.
+
+use crate::db::{Asset, Database};
+use crate::error::{AppError, AppResult};
+use crate::scanner::{is_ignored_relative, Scanner};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// Items listed per check; counts include all of them
+pub const DEFAULT_LIMIT: usize = 500;
+
+/// Unity's built-in resources, which are never in a project
+const BUILTIN_GUID_PREFIX: &str = "0000000000000000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationItem {
+    /// The paths involved, e.g. both assets sharing a GUID
+    pub paths: Vec<String>,
+    /// What they have in common or point at, such as the GUID
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationCheck {
+    /// "missing_meta", "duplicate_guids", "case_collisions",
+    /// "broken_references" or "empty_folders"
+    pub check: String,
+    /// "error" for what breaks the project or its references, "warning"
+    /// for clutter
+    pub severity: String,
+    pub count: usize,
+    pub items: Vec<ValidationItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub project_id: String,
+    pub checked_at: i64,
+    pub unity_project: bool,
+    pub assets_checked: usize,
+    /// Every check, including those nothing failed
+    pub checks: Vec<ValidationCheck>,
+}
+
+pub fn validate_project(
+    db: &Database,
+    project_id: &str,
+    ignore_patterns: &[String],
+    limit: usize,
+) -> AppResult<ValidationReport> {
+    let project = db
+        .get_project(project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", project_id)))?;
+    let root = Path::new(&project.root_path);
+    let unity_project = Scanner::is_valid_unity_project(root);
+    let assets = db.get_project_assets(project_id)?;
+
+    let missing_meta = if unity_project {
+        assets
+            .iter()
+            .filter(|a| in_assets_folder(&a.relative_path))
+            .filter(|a| !Path::new(&format!("{}.meta", a.absolute_path)).exists())
+            .map(|a| item(vec![a.relative_path.clone()], None))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let empty_folders = if unity_project {
+        let mut found = Vec::new();
+        let assets_dir = root.join("Assets");
+        find_empty_folders(&assets_dir, root, ignore_patterns, &mut found);
+        // An empty Assets folder is an empty project, not clutter
+        found.retain(|f| Path::new(f) != Path::new("Assets"));
+        found.into_iter().map(|f| item(vec![f], None)).collect()
+    } else {
+        Vec::new()
+    };
+
+    let broken_references = db
+        .get_dependency_links(project_id)?
+        .into_iter()
+        .filter(|l| l.to_asset_id.is_none() && !l.to_guid.starts_with(BUILTIN_GUID_PREFIX))
+        .map(|l| item(vec![l.from_path], Some(format!("{} reference to {}", l.relation_type, l.to_guid))))
+        .collect();
+
+    let checks = vec![
+        check("missing_meta", "error", missing_meta, limit),
+        check("duplicate_guids", "error", duplicate_guids(&assets), limit),
+        check("case_collisions", "error", case_collisions(&assets), limit),
+        check("broken_references", "error", broken_references, limit),
+        check("empty_folders", "warning", empty_folders, limit),
+    ];
+
+    Ok(ValidationReport {
+        project_id: project_id.to_string(),
+        checked_at: chrono::Utc::now().timestamp(),
+        unity_project,
+        assets_checked: assets.len(),
+        checks,
+    })
+}
+
+fn item(paths: Vec<String>, detail: Option<String>) -> ValidationItem {
+    ValidationItem { paths, detail }
+}
+
+fn check(check: &str, severity: &str, mut items: Vec<ValidationItem>, limit: usize) -> ValidationCheck {
+    let count = items.len();
+    items.truncate(limit);
+    ValidationCheck {
+        check: check.to_string(),
+        severity: severity.to_string(),
+        count,
+        items,
+    }
+}
+
+fn in_assets_folder(relative_path: &str) -> bool {
+    Path::new(relative_path).components().next().is_some_and(|c| c.as_os_str() == "Assets")
+}
+
+/// Assets whose `.meta` files carry the same GUID; Unity keeps one and
+/// gives the others new GUIDs, breaking references to them
+fn duplicate_guids(assets: &[Asset]) -> Vec<ValidationItem> {
+    let mut by_guid: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for asset in assets {
+        if let Some(guid) = &asset.unity_guid {
+            by_guid.entry(guid).or_default().push(asset.relative_path.clone());
+        }
+    }
+    by_guid
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(guid, paths)| item(paths, Some(guid.to_string())))
+        .collect()
+}
+
+/// Files or folders whose paths differ only in case, which can't both exist
+/// on Windows or macOS
+fn case_collisions(assets: &[Asset]) -> Vec<ValidationItem> {
+    let mut paths: BTreeSet<String> = BTreeSet::new();
+    for asset in assets {
+        let mut path = Path::new(&asset.relative_path);
+        paths.insert(asset.relative_path.clone());
+        while let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !paths.insert(parent.to_string_lossy().to_string()) {
+                break;
+            }
+            path = parent;
+        }
+    }
+
+    let mut by_lower: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for path in paths {
+        by_lower.entry(path.to_lowercase()).or_default().push(path);
+    }
+    by_lower.into_values().filter(|paths| paths.len() > 1).map(|paths| item(paths, None)).collect()
+}
+
+/// Collect the topmost folders under `dir` holding nothing but `.meta`
+/// files and empty folders. Returns whether `dir` itself is empty.
+fn find_empty_folders(dir: &Path, root: &Path, ignore_patterns: &[String], found: &mut Vec<String>) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    let mut entries: Vec<fs::DirEntry> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    let mark = found.len();
+    let mut empty = true;
+    for entry in entries {
+        // Unity skips hidden files such as .DS_Store, so they don't fill a folder
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+        // What the scan doesn't see still counts as content
+        if is_ignored_relative(&relative, ignore_patterns) {
+            empty = false;
+            continue;
+        }
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => {
+                if !find_empty_folders(&path, root, ignore_patterns, found) {
+                    empty = false;
+                }
+            }
+            Ok(t) if t.is_file() && path.extension().is_some_and(|e| e == "meta") => {}
+            _ => empty = false,
+        }
+    }
+
+    if empty {
+        found.truncate(mark);
+        found.push(dir.strip_prefix(root).unwrap_or(dir).to_string_lossy().to_string());
+    }
+    empty
+}
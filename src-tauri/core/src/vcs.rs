@@ -0,0 +1,613 @@
+//! Version control annotations for assets.
+//!
+//! Git is read through libgit2, so no git install is needed; Plastic SCM
+//! (Unity Version Control) is queried through its `cm` command line client.
+//! Annotations are computed when assets are requested rather than stored
+//! with the index, since status changes without any file being rescanned.
+
+use crate::db::Asset;
+use crate::error::{AppError, AppResult};
+use git2::{AttrCheckFlags, Commit, DiffOptions, Oid, Repository, Sort, Status, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Commits walked back from HEAD when looking for the last change to a file
+const MAX_HISTORY_COMMITS: usize = 5000;
+/// LFS pointers are a few lines of text; anything larger is real content
+const MAX_LFS_POINTER_SIZE: i64 = 1024;
+const LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+/// Branches a feature branch is compared against, in order of preference
+const BASE_BRANCHES: &[&str] = &["origin/HEAD", "origin/main", "origin/master", "main", "master"];
+/// Separates fields in `cm` output; chosen so it can't appear in a path
+const CM_FIELD_SEPARATOR: &str = "#|#";
+/// Paths passed to one `cm fileinfo` call, keeping the command line short
+const CM_FILEINFO_BATCH: usize = 50;
+const PLASTIC_MAIN_BRANCH: &str = "/main";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VcsInfo {
+    /// "git" or "plastic"
+    pub provider: String,
+    /// Uncommitted state: "modified", "added", "deleted", "renamed",
+    /// "untracked" or "conflicted"; None when the file is unchanged
+    pub status: Option<String>,
+    /// Committed on this branch since it left the base branch
+    pub changed_on_branch: bool,
+    pub last_commit: Option<CommitInfo>,
+    /// Plastic user holding an exclusive checkout (lock) on the file
+    pub locked_by: Option<String>,
+    /// Stored in Git LFS (`filter=lfs` in .gitattributes)
+    pub lfs: bool,
+    /// The working copy is still an LFS pointer, i.e. the content was not pulled
+    pub lfs_pointer: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub email: Option<String>,
+    /// Unix seconds
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VcsRepoInfo {
+    pub provider: String,
+    /// Repository working directory; may be a parent of the project root
+    pub root: String,
+    /// Checked-out branch, None when HEAD is detached
+    pub branch: Option<String>,
+    /// Branch that "changed on this branch" is measured against
+    pub base_branch: Option<String>,
+    pub head: Option<String>,
+}
+
+/// Asset list filter on version control state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcsFilter {
+    /// Uncommitted changes to tracked files
+    Modified,
+    Untracked,
+    /// Any uncommitted change, including untracked files
+    Changed,
+    /// Committed on this branch or changed in the working copy
+    Branch,
+    /// LFS content that has not been pulled
+    LfsPointer,
+}
+
+impl VcsFilter {
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "modified" => Ok(VcsFilter::Modified),
+            "untracked" => Ok(VcsFilter::Untracked),
+            "changed" => Ok(VcsFilter::Changed),
+            "branch" => Ok(VcsFilter::Branch),
+            "lfs_pointer" => Ok(VcsFilter::LfsPointer),
+            other => Err(AppError::InvalidArgument(format!(
+                "Unknown VCS filter: {} (expected modified, untracked, changed, branch or lfs_pointer)",
+                other
+            ))),
+        }
+    }
+
+    pub fn matches(&self, info: &VcsInfo) -> bool {
+        match self {
+            VcsFilter::Modified => info.status.as_deref().is_some_and(|s| s != "untracked"),
+            VcsFilter::Untracked => info.status.as_deref() == Some("untracked"),
+            VcsFilter::Changed => info.status.is_some(),
+            VcsFilter::Branch => info.changed_on_branch || info.status.is_some(),
+            VcsFilter::LfsPointer => info.lfs_pointer,
+        }
+    }
+}
+
+/// Version control system a project is checked out from
+pub enum Vcs {
+    Git(GitRepo),
+    Plastic(PlasticWorkspace),
+}
+
+impl Vcs {
+    /// The innermost git repository or Plastic workspace holding the project
+    pub fn discover(project_root: &Path) -> Option<Self> {
+        let git = GitRepo::discover(project_root);
+        let plastic = PlasticWorkspace::discover(project_root);
+        match (git, plastic) {
+            (Some(git), Some(plastic)) => {
+                let git_depth = git.repo.workdir().map_or(0, |p| p.components().count());
+                if plastic.root.components().count() > git_depth {
+                    Some(Vcs::Plastic(plastic))
+                } else {
+                    Some(Vcs::Git(git))
+                }
+            }
+            (git, plastic) => git.map(Vcs::Git).or(plastic.map(Vcs::Plastic)),
+        }
+    }
+
+    pub fn info(&self) -> VcsRepoInfo {
+        match self {
+            Vcs::Git(repo) => repo.info(),
+            Vcs::Plastic(workspace) => workspace.info(),
+        }
+    }
+
+    /// Per-project state for every asset: status and branch changes
+    pub fn annotate(&self, assets: &mut [Asset]) -> AppResult<()> {
+        match self {
+            Vcs::Git(repo) => repo.annotate(assets),
+            Vcs::Plastic(workspace) => workspace.annotate(assets),
+        }
+    }
+
+    /// Per-file lookups that are too slow for a whole project (git history,
+    /// Plastic lock owners); only call this for the page being shown
+    pub fn annotate_details(&self, assets: &mut [Asset]) -> AppResult<()> {
+        match self {
+            Vcs::Git(repo) => repo.annotate_details(assets),
+            Vcs::Plastic(workspace) => workspace.annotate_details(assets),
+        }
+    }
+}
+
+/// Git repository containing a project
+pub struct GitRepo {
+    repo: Repository,
+    /// Project root relative to the working directory, '/'-separated and
+    /// empty when the project sits at the top of the repository
+    prefix: String,
+}
+
+impl GitRepo {
+    /// The repository the project lives in, if it is in one
+    pub fn discover(project_root: &Path) -> Option<Self> {
+        let repo = Repository::discover(project_root).ok()?;
+        let workdir = repo.workdir()?.canonicalize().ok()?;
+        let root = project_root.canonicalize().ok()?;
+        let prefix = normalize(&root.strip_prefix(&workdir).ok()?.to_string_lossy());
+        Some(Self { repo, prefix })
+    }
+
+    pub fn info(&self) -> VcsRepoInfo {
+        let head = self.repo.head().ok();
+        VcsRepoInfo {
+            provider: "git".to_string(),
+            root: self
+                .repo
+                .workdir()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            branch: head
+                .as_ref()
+                .filter(|h| h.is_branch())
+                .and_then(|h| h.shorthand().map(String::from)),
+            base_branch: self.base_branch().map(|(name, _)| name),
+            head: head.and_then(|h| h.target()).map(|oid| oid.to_string()),
+        }
+    }
+
+    /// Fill in status, branch changes and LFS state; `last_commit` is left
+    /// for `annotate_details` since walking history is the slow part
+    pub fn annotate(&self, assets: &mut [Asset]) -> AppResult<()> {
+        let statuses = self.statuses()?;
+        let branch_changes = self.branch_changes().unwrap_or_else(|e| {
+            tracing::warn!("Failed to diff against base branch: {}", e);
+            HashSet::new()
+        });
+
+        for asset in assets {
+            let path = self.repo_path(&asset.relative_path);
+            let lfs = self.is_lfs_tracked(&path);
+            asset.vcs = Some(VcsInfo {
+                provider: "git".to_string(),
+                status: statuses.get(&path).cloned(),
+                changed_on_branch: branch_changes.contains(&path),
+                last_commit: None,
+                locked_by: None,
+                lfs,
+                lfs_pointer: lfs && is_lfs_pointer(asset),
+            });
+        }
+        Ok(())
+    }
+
+    /// Set `last_commit` on assets already passed through `annotate`
+    pub fn annotate_details(&self, assets: &mut [Asset]) -> AppResult<()> {
+        let paths: Vec<String> = assets.iter().map(|a| self.repo_path(&a.relative_path)).collect();
+        let mut commits = self.last_commits(&paths)?;
+        for (asset, path) in assets.iter_mut().zip(&paths) {
+            if let Some(vcs) = asset.vcs.as_mut() {
+                vcs.last_commit = commits.remove(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Uncommitted changes under the project, keyed by repository path
+    fn statuses(&self) -> AppResult<HashMap<String, String>> {
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .renames_head_to_index(true);
+        if !self.prefix.is_empty() {
+            options.pathspec(&self.prefix);
+        }
+
+        let statuses = self.repo.statuses(Some(&mut options)).map_err(git_error)?;
+        Ok(statuses
+            .iter()
+            .filter_map(|entry| {
+                let status = status_name(entry.status())?;
+                Some((entry.path()?.to_string(), status.to_string()))
+            })
+            .collect())
+    }
+
+    /// Files committed since HEAD diverged from the base branch
+    fn branch_changes(&self) -> AppResult<HashSet<String>> {
+        let mut changed = HashSet::new();
+        let (Some((_, base)), Ok(head)) = (self.base_branch(), self.repo.head()) else {
+            return Ok(changed);
+        };
+        let head = head.peel_to_commit().map_err(git_error)?;
+        // Unrelated histories have no merge base; nothing to compare
+        let Ok(merge_base) = self.repo.merge_base(head.id(), base) else {
+            return Ok(changed);
+        };
+        if merge_base == head.id() {
+            return Ok(changed);
+        }
+
+        let old_tree = self
+            .repo
+            .find_commit(merge_base)
+            .and_then(|c| c.tree())
+            .map_err(git_error)?;
+        let new_tree = head.tree().map_err(git_error)?;
+        let mut options = DiffOptions::new();
+        if !self.prefix.is_empty() {
+            options.pathspec(&self.prefix);
+        }
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut options))
+            .map_err(git_error)?;
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                changed.insert(normalize(&path.to_string_lossy()));
+            }
+        }
+        Ok(changed)
+    }
+
+    fn base_branch(&self) -> Option<(String, Oid)> {
+        BASE_BRANCHES.iter().find_map(|name| {
+            let oid = self
+                .repo
+                .revparse_single(name)
+                .and_then(|o| o.peel_to_commit())
+                .ok()?
+                .id();
+            // origin/HEAD is only an alias; report the branch it points to
+            let name = self
+                .repo
+                .find_reference(&format!("refs/remotes/{}", name))
+                .ok()
+                .and_then(|r| r.symbolic_target().map(String::from))
+                .map(|target| target.trim_start_matches("refs/remotes/").to_string())
+                .unwrap_or_else(|| name.to_string());
+            Some((name, oid))
+        })
+    }
+
+    /// Most recent commit touching each path, following first parents from
+    /// HEAD until every path is found or the history limit is reached
+    fn last_commits(&self, paths: &[String]) -> AppResult<HashMap<String, CommitInfo>> {
+        let mut found = HashMap::new();
+        let mut wanted: HashSet<&str> = paths.iter().map(String::as_str).collect();
+        let mut walk = self.repo.revwalk().map_err(git_error)?;
+        // An empty repository has no HEAD to walk from
+        if wanted.is_empty() || walk.push_head().is_err() {
+            return Ok(found);
+        }
+        walk.set_sorting(Sort::TIME).map_err(git_error)?;
+        walk.simplify_first_parent().map_err(git_error)?;
+
+        for oid in walk.take(MAX_HISTORY_COMMITS) {
+            let commit = self.repo.find_commit(oid.map_err(git_error)?).map_err(git_error)?;
+            let tree = commit.tree().map_err(git_error)?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree().map_err(git_error)?),
+                Err(_) => None,
+            };
+
+            let mut options = DiffOptions::new();
+            options.disable_pathspec_match(true);
+            for path in &wanted {
+                options.pathspec(path);
+            }
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+                .map_err(git_error)?;
+
+            for delta in diff.deltas() {
+                let Some(path) = delta.new_file().path() else {
+                    continue;
+                };
+                let path = normalize(&path.to_string_lossy());
+                if wanted.remove(path.as_str()) {
+                    found.insert(path, commit_info(&commit));
+                }
+            }
+            if wanted.is_empty() {
+                break;
+            }
+        }
+        Ok(found)
+    }
+
+    fn is_lfs_tracked(&self, repo_path: &str) -> bool {
+        self.repo
+            .get_attr(Path::new(repo_path), "filter", AttrCheckFlags::FILE_THEN_INDEX)
+            .ok()
+            .flatten()
+            == Some("lfs")
+    }
+
+    fn repo_path(&self, relative_path: &str) -> String {
+        let relative_path = normalize(relative_path);
+        if self.prefix.is_empty() {
+            relative_path
+        } else {
+            format!("{}/{}", self.prefix, relative_path)
+        }
+    }
+}
+
+/// Plastic SCM / Unity Version Control workspace containing a project
+pub struct PlasticWorkspace {
+    /// Workspace root, the folder holding `.plastic`
+    root: PathBuf,
+    project_root: PathBuf,
+}
+
+impl PlasticWorkspace {
+    pub fn discover(project_root: &Path) -> Option<Self> {
+        let root = project_root
+            .ancestors()
+            .find(|dir| dir.join(".plastic").is_dir())?
+            .to_path_buf();
+        Some(Self {
+            root,
+            project_root: project_root.to_path_buf(),
+        })
+    }
+
+    pub fn info(&self) -> VcsRepoInfo {
+        let branch = self.branch().unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            None
+        });
+        VcsRepoInfo {
+            provider: "plastic".to_string(),
+            root: self.root.to_string_lossy().to_string(),
+            base_branch: branch
+                .as_deref()
+                .filter(|b| *b != PLASTIC_MAIN_BRANCH)
+                .map(|_| PLASTIC_MAIN_BRANCH.to_string()),
+            branch,
+            head: None,
+        }
+    }
+
+    pub fn annotate(&self, assets: &mut [Asset]) -> AppResult<()> {
+        let statuses = self.statuses()?;
+        let branch_changes = self.branch_changes().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list branch changes: {}", e);
+            HashSet::new()
+        });
+
+        for asset in assets {
+            let path = normalize(&asset.relative_path);
+            asset.vcs = Some(VcsInfo {
+                provider: "plastic".to_string(),
+                status: statuses.get(&path).cloned(),
+                changed_on_branch: branch_changes.contains(&path),
+                last_commit: None,
+                locked_by: None,
+                lfs: false,
+                lfs_pointer: false,
+            });
+        }
+        Ok(())
+    }
+
+    /// Set `locked_by` from the server's exclusive checkouts
+    pub fn annotate_details(&self, assets: &mut [Asset]) -> AppResult<()> {
+        let format = format!("--format={{ClientPath}}{}{{LockedBy}}", CM_FIELD_SEPARATOR);
+        for chunk in assets.chunks_mut(CM_FILEINFO_BATCH) {
+            // Private files aren't on the server; cm rejects the whole call for them
+            let paths: Vec<&str> = chunk
+                .iter()
+                .filter(|a| a.vcs.as_ref().is_some_and(|v| v.status.as_deref() != Some("untracked")))
+                .map(|a| a.absolute_path.as_str())
+                .collect();
+            if paths.is_empty() {
+                continue;
+            }
+
+            let mut args = vec!["fileinfo", format.as_str()];
+            args.extend(paths);
+            let output = self.cm(&args)?;
+            let mut owners: HashMap<String, String> = output
+                .lines()
+                .filter_map(|line| {
+                    let (path, owner) = line.split_once(CM_FIELD_SEPARATOR)?;
+                    let owner = owner.trim();
+                    if owner.is_empty() {
+                        return None;
+                    }
+                    Some((self.relative_path(path.trim())?, owner.to_string()))
+                })
+                .collect();
+
+            for asset in chunk {
+                if let Some(vcs) = asset.vcs.as_mut() {
+                    vcs.locked_by = owners.remove(&normalize(&asset.relative_path));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Branch the workspace is switched to, from `cm wi`
+    /// ("Branch /main/task001@repo@server")
+    fn branch(&self) -> AppResult<Option<String>> {
+        let output = self.cm(&["wi"])?;
+        Ok(output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Branch "))
+            .and_then(|spec| spec.split('@').next())
+            .map(|branch| branch.trim().to_string()))
+    }
+
+    /// Pending changes keyed by project-relative path
+    fn statuses(&self) -> AppResult<HashMap<String, String>> {
+        let separator = format!("--fieldseparator={}", CM_FIELD_SEPARATOR);
+        let project_root = self.project_root.to_string_lossy();
+        let output = self.cm(&["status", "--all", "--machinereadable", separator.as_str(), &project_root])?;
+
+        // One change per line: "<code><sep><absolute path><sep>..."
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(CM_FIELD_SEPARATOR);
+                let status = plastic_status_name(fields.next()?.trim())?;
+                Some((self.relative_path(fields.next()?.trim())?, status.to_string()))
+            })
+            .collect())
+    }
+
+    /// Files changed on the current branch, unless it is the main branch
+    fn branch_changes(&self) -> AppResult<HashSet<String>> {
+        let Some(branch) = self.branch()?.filter(|b| b != PLASTIC_MAIN_BRANCH) else {
+            return Ok(HashSet::new());
+        };
+        let spec = format!("br:{}", branch);
+        let output = self.cm(&["diff", spec.as_str(), "--format={path}"])?;
+
+        // Server paths are rooted at the workspace root ("/Assets/Foo.png")
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('/'))
+            .filter_map(|line| {
+                let path = self.root.join(line.trim_start_matches('/'));
+                self.relative_path(&path.to_string_lossy())
+            })
+            .collect())
+    }
+
+    fn relative_path(&self, path: &str) -> Option<String> {
+        Path::new(path)
+            .strip_prefix(&self.project_root)
+            .ok()
+            .map(|p| normalize(&p.to_string_lossy()))
+    }
+
+    fn cm<S: AsRef<OsStr>>(&self, args: &[S]) -> AppResult<String> {
+        let mut command = Command::new("cm");
+        command.args(args).current_dir(&self.root);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            // CREATE_NO_WINDOW: don't flash a console for every query
+            command.creation_flags(0x0800_0000);
+        }
+
+        let output = command.output().map_err(|e| {
+            AppError::Custom(format!("Failed to run cm (is Unity Version Control installed?): {}", e))
+        })?;
+        if !output.status.success() {
+            let subcommand = args.first().map(|a| a.as_ref().to_string_lossy()).unwrap_or_default();
+            return Err(AppError::Custom(format!(
+                "cm {} failed: {}",
+                subcommand,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+fn status_name(status: Status) -> Option<&'static str> {
+    if status.is_conflicted() {
+        Some("conflicted")
+    } else if status.is_wt_new() {
+        Some("untracked")
+    } else if status.is_index_new() {
+        Some("added")
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        Some("deleted")
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        Some("renamed")
+    } else if status.intersects(
+        Status::INDEX_MODIFIED | Status::WT_MODIFIED | Status::INDEX_TYPECHANGE | Status::WT_TYPECHANGE,
+    ) {
+        Some("modified")
+    } else {
+        None
+    }
+}
+
+/// Map `cm status` change codes onto the git-style names used in `VcsInfo`
+fn plastic_status_name(code: &str) -> Option<&'static str> {
+    match code {
+        "CH" | "CO" | "RP" | "CP" => Some("modified"),
+        "AD" => Some("added"),
+        "DE" | "LD" => Some("deleted"),
+        "MV" | "LM" => Some("renamed"),
+        "PR" => Some("untracked"),
+        _ => None,
+    }
+}
+
+fn is_lfs_pointer(asset: &Asset) -> bool {
+    if asset.size_bytes > MAX_LFS_POINTER_SIZE {
+        return false;
+    }
+    let mut header = [0u8; LFS_POINTER_HEADER.len()];
+    std::fs::File::open(&asset.absolute_path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok()
+        && header == LFS_POINTER_HEADER
+}
+
+fn commit_info(commit: &Commit) -> CommitInfo {
+    let author = commit.author();
+    CommitInfo {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        email: author.email().map(String::from),
+        time: commit.time().seconds(),
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+fn git_error(e: git2::Error) -> AppError {
+    AppError::Custom(format!("Git error: {}", e.message()))
+}
@@ -0,0 +1,306 @@
+//! Live indexing: once a project has been scanned, `ProjectWatcher` follows
+//! its folder with `notify` and keeps the index in step as files are added,
+//! changed, moved or deleted, so new files show up without another scan.
+//!
+//! Events are debounced and applied in batches on the watcher's thread.
+//! Changed files are read the way a scan reads them (GUID from the `.meta`)
+//! and their dependencies re-resolved; texture estimates, localization,
+//! rules and thumbnails wait for the next scan or thumbnail run.
+
+use crate::db::{Asset, Database, Project};
+use crate::deps::DependencyResolver;
+use crate::error::AppResult;
+use crate::plugins::PluginRegistry;
+use crate::scanner::{is_ignored_relative, read_asset};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Quiet time after the last event before a batch is applied
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Longest a steady stream of events (an import, a branch switch) is held back
+const MAX_DELAY: Duration = Duration::from_secs(5);
+/// How often the thread checks for a stop between events
+const POLL: Duration = Duration::from_millis(100);
+
+pub struct WatchOptions {
+    pub ignore_patterns: Vec<String>,
+    pub plugins: Option<Arc<PluginRegistry>>,
+    pub dependency_max_file_bytes: u64,
+}
+
+/// What one batch of file events changed in the index
+#[derive(Debug, Default)]
+pub struct WatchBatch {
+    pub added: Vec<Asset>,
+    pub updated: Vec<Asset>,
+    /// Relative paths of the assets taken out of the index
+    pub removed: Vec<String>,
+}
+
+impl WatchBatch {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Watches one project until dropped
+pub struct ProjectWatcher {
+    project_id: String,
+    ignore_patterns: Vec<String>,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ProjectWatcher {
+    /// Start watching on a thread of its own. Nothing is applied while
+    /// `paused` is set (during a scan); events collected meanwhile are
+    /// applied after. `on_batch` is called on that thread.
+    pub fn start(
+        db: Arc<Database>,
+        project: &Project,
+        options: WatchOptions,
+        paused: Arc<AtomicBool>,
+        on_batch: impl FnMut(WatchBatch) + Send + 'static,
+    ) -> AppResult<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let ignore_patterns = options.ignore_patterns.clone();
+        let worker = {
+            let stop = Arc::clone(&stop);
+            let project_id = project.id.clone();
+            let root = PathBuf::from(&project.root_path);
+            std::thread::Builder::new()
+                .name("project-watcher".to_string())
+                .spawn(move || watch(db, &project_id, &root, options, &paused, &stop, on_batch))?
+        };
+
+        Ok(Self {
+            project_id: project.id.clone(),
+            ignore_patterns,
+            stop,
+            worker: Some(worker),
+        })
+    }
+
+    /// Already watching `project_id` with these ignore patterns
+    pub fn matches(&self, project_id: &str, ignore_patterns: &[String]) -> bool {
+        self.project_id == project_id && self.ignore_patterns == ignore_patterns
+    }
+}
+
+impl Drop for ProjectWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn watch(
+    db: Arc<Database>,
+    project_id: &str,
+    root: &Path,
+    options: WatchOptions,
+    paused: &AtomicBool,
+    stop: &AtomicBool,
+    mut on_batch: impl FnMut(WatchBatch),
+) {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    // Setting up a recursive watch walks the tree, so it happens here
+    // rather than on the caller's thread
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::error!("Failed to start file watcher: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+        // On Linux this is usually the inotify watch limit on a large project
+        tracing::error!("Failed to watch {}: {}", root.display(), e);
+        return;
+    }
+    tracing::info!("Watching {} for changes", root.display());
+
+    let mut resolver = DependencyResolver::new(Arc::clone(&db)).with_max_file_bytes(options.dependency_max_file_bytes);
+    if let Some(plugins) = &options.plugins {
+        resolver = resolver.with_plugins(Arc::clone(plugins));
+    }
+
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    let mut first_event: Option<Instant> = None;
+    let mut last_event = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        match rx.recv_timeout(POLL) {
+            Ok(Ok(event)) => {
+                if event.need_rescan() {
+                    tracing::warn!("File watcher missed events in {}; a scan will pick them up", root.display());
+                }
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                // Unity rewrites Library/ and Temp/ constantly; drop them here
+                // so they don't hold batches back
+                let relevant: Vec<PathBuf> = event
+                    .paths
+                    .into_iter()
+                    .filter(|path| path.strip_prefix(root).is_ok_and(|r| !is_ignored(r, &options.ignore_patterns)))
+                    .collect();
+                if !relevant.is_empty() {
+                    pending.extend(relevant);
+                    first_event.get_or_insert_with(Instant::now);
+                    last_event = Instant::now();
+                }
+            }
+            Ok(Err(e)) => tracing::warn!("File watcher error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let due = last_event.elapsed() >= DEBOUNCE || first_event.is_some_and(|t| t.elapsed() >= MAX_DELAY);
+        if pending.is_empty() || !due || paused.load(Ordering::SeqCst) {
+            continue;
+        }
+        first_event = None;
+        let paths = std::mem::take(&mut pending);
+        match apply_changes(&db, &resolver, project_id, root, &options, paths) {
+            Ok(batch) if !batch.is_empty() => {
+                tracing::debug!(
+                    "Live index: {} added, {} updated, {} removed",
+                    batch.added.len(),
+                    batch.updated.len(),
+                    batch.removed.len()
+                );
+                on_batch(batch);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to apply file changes: {}", e),
+        }
+    }
+    tracing::info!("Stopped watching {}", root.display());
+}
+
+fn is_ignored(relative: &Path, ignore_patterns: &[String]) -> bool {
+    is_ignored_relative(&relative.to_string_lossy(), ignore_patterns)
+}
+
+/// Bring the index in line with what is on disk now at `paths`
+fn apply_changes(
+    db: &Database,
+    resolver: &DependencyResolver,
+    project_id: &str,
+    root: &Path,
+    options: &WatchOptions,
+    paths: HashSet<PathBuf>,
+) -> AppResult<WatchBatch> {
+    let existing = db.get_existing_asset_info(project_id)?;
+    let excluded = db.get_excluded_paths(project_id)?;
+
+    let mut files: BTreeSet<PathBuf> = BTreeSet::new();
+    // A changed .meta re-reads its asset even if the asset is unchanged,
+    // since the GUID may be new
+    let mut meta_changed: HashSet<PathBuf> = HashSet::new();
+    let mut gone: BTreeSet<String> = BTreeSet::new();
+    for path in paths {
+        let path = if path.extension().is_some_and(|e| e == "meta") {
+            let asset_path = path.with_extension("");
+            meta_changed.insert(asset_path.clone());
+            asset_path
+        } else {
+            path
+        };
+
+        if path.is_file() {
+            files.insert(path);
+        } else if path.is_dir() {
+            // A folder moved or copied in arrives as one event
+            if !meta_changed.contains(&path) {
+                collect_files(&path, root, &options.ignore_patterns, &mut files);
+            }
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            gone.insert(relative.to_string_lossy().to_string());
+        }
+    }
+
+    let mut batch = WatchBatch::default();
+    for path in files {
+        let Some(mut asset) = read_asset(root, &path, project_id, options.plugins.as_deref()) else {
+            continue;
+        };
+        if excluded.contains(&asset.relative_path) {
+            continue;
+        }
+        match existing.get(&asset.relative_path) {
+            Some((id, modified_time, size_bytes)) => {
+                let unchanged = *modified_time == asset.modified_time && *size_bytes == asset.size_bytes;
+                if unchanged && !meta_changed.contains(&path) {
+                    continue;
+                }
+                asset.id = id.clone();
+                batch.updated.push(asset);
+            }
+            None => batch.added.push(asset),
+        }
+    }
+
+    // A deleted or moved-away folder takes everything under it along
+    let mut removed_ids = Vec::new();
+    for (relative_path, (id, _, _)) in &existing {
+        let under_gone = gone.iter().any(|g| {
+            relative_path == g
+                || relative_path
+                    .strip_prefix(g.as_str())
+                    .is_some_and(|rest| rest.starts_with(['/', MAIN_SEPARATOR]))
+        });
+        if under_gone && !root.join(relative_path).exists() {
+            removed_ids.push(id.clone());
+            batch.removed.push(relative_path.clone());
+        }
+    }
+
+    let indexed: Vec<Asset> = batch.added.iter().chain(&batch.updated).cloned().collect();
+    if !indexed.is_empty() {
+        db.upsert_assets(&indexed)?;
+        let mut dependencies = Vec::new();
+        for asset in &indexed {
+            dependencies.extend(resolver.resolve_dependencies_for_asset(asset)?);
+        }
+        let ids: Vec<String> = indexed.iter().map(|a| a.id.clone()).collect();
+        db.replace_dependencies(&ids, &dependencies)?;
+        db.relink_dependencies(project_id, &indexed)?;
+    }
+    if !removed_ids.is_empty() {
+        for thumb in db.delete_assets(&removed_ids)? {
+            let _ = fs::remove_file(thumb);
+        }
+    }
+
+    Ok(batch)
+}
+
+/// Files under `dir`, skipping ignored folders
+fn collect_files(dir: &Path, root: &Path, ignore_patterns: &[String], files: &mut BTreeSet<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.strip_prefix(root).is_ok_and(|r| is_ignored(r, ignore_patterns)) {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_files(&path, root, ignore_patterns, files),
+            Ok(t) if t.is_file() => {
+                files.insert(path);
+            }
+            _ => {}
+        }
+    }
+}
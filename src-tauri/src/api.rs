@@ -0,0 +1,301 @@
+use crate::db::{retry_busy, AssetSort, Database, Project};
+use crate::error::{AppError, AppResult};
+use crate::search::{self, FileSearchOptions};
+use crate::settings::Settings;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Requests are served by this many threads; each one only does short reads
+const API_WORKERS: usize = 4;
+/// Upper bound for `page_size` on `/assets`
+const MAX_PAGE_SIZE: i64 = 500;
+const DEFAULT_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    /// Base URL of the API while it is running
+    pub url: Option<String>,
+    pub token: Option<String>,
+    /// Why the server failed to start (usually the port is taken)
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AssetsPage<T> {
+    assets: Vec<T>,
+    total: i64,
+}
+
+/// Embedded read-only HTTP server bound to 127.0.0.1.
+///
+/// Every request must carry `Authorization: Bearer <api_token>`. Endpoints
+/// (all `GET`, JSON unless noted; `project=<root path>` selects a project
+/// other than the one open in the app):
+///
+/// - `/api/v1/project`
+/// - `/api/v1/assets?q=&type=texture,model&sort=&page=&page_size=`
+/// - `/api/v1/assets/{id}`, `/dependencies`, `/dependents`
+/// - `/api/v1/assets/{id}/thumbnail` (image bytes)
+/// - `/api/v1/search?pattern=&regex=&case_sensitive=&type=` (content search)
+pub struct ApiServer {
+    server: Arc<Server>,
+    workers: Vec<JoinHandle<()>>,
+    port: u16,
+    token: String,
+}
+
+struct ApiContext {
+    db: Arc<Database>,
+    settings: Arc<RwLock<Settings>>,
+    token: String,
+}
+
+impl ApiServer {
+    pub fn start(
+        port: u16,
+        token: String,
+        db: Arc<Database>,
+        settings: Arc<RwLock<Settings>>,
+    ) -> AppResult<Self> {
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| AppError::Custom(format!("Failed to start API on port {}: {}", port, e)))?;
+        let server = Arc::new(server);
+        let context = Arc::new(ApiContext {
+            db,
+            settings,
+            token: token.clone(),
+        });
+
+        let workers = (0..API_WORKERS)
+            .map(|i| {
+                let server = Arc::clone(&server);
+                let context = Arc::clone(&context);
+                std::thread::Builder::new()
+                    .name(format!("api-{}", i))
+                    .spawn(move || {
+                        for request in server.incoming_requests() {
+                            context.handle(request);
+                        }
+                    })
+                    .map_err(AppError::Io)
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        tracing::info!("API listening on http://127.0.0.1:{}", port);
+        Ok(Self {
+            server,
+            workers,
+            port,
+            token,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/api/v1", self.port)
+    }
+
+    /// True if the server was started with these settings
+    pub fn matches(&self, port: u16, token: &str) -> bool {
+        self.port == port && self.token == token
+    }
+
+    /// Stop accepting requests and wait for in-flight ones to finish
+    pub fn stop(self) {
+        for _ in &self.workers {
+            self.server.unblock();
+        }
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+        tracing::info!("API stopped");
+    }
+}
+
+impl ApiContext {
+    fn handle(&self, request: Request) {
+        let response = if !self.is_authorized(&request) {
+            json_response(
+                401,
+                &serde_json::json!({
+                    "code": "UNAUTHORIZED",
+                    "message": "Missing or invalid API token",
+                    "details": null,
+                }),
+            )
+        } else if *request.method() != Method::Get {
+            json_response(
+                405,
+                &serde_json::json!({
+                    "code": "METHOD_NOT_ALLOWED",
+                    "message": "The API is read-only",
+                    "details": null,
+                }),
+            )
+        } else {
+            let (path, query) = split_url(request.url());
+            match self.route(&path, &query) {
+                Ok(response) => response,
+                Err(e) => json_response(status_for(&e), &e),
+            }
+        };
+
+        if let Err(e) = request.respond(response) {
+            tracing::debug!("Failed to send API response: {}", e);
+        }
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Authorization"))
+            .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), self.token.as_bytes()))
+    }
+
+    fn route(&self, path: &str, query: &HashMap<String, String>) -> AppResult<Response<std::io::Cursor<Vec<u8>>>> {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match segments.as_slice() {
+            ["api", "v1", "project"] => Ok(json_response(200, &self.project(query)?)),
+            ["api", "v1", "assets"] => {
+                let project = self.project(query)?;
+                let page = parse_number(query, "page")?.unwrap_or(0).max(0);
+                let page_size = parse_number(query, "page_size")?
+                    .unwrap_or(DEFAULT_PAGE_SIZE)
+                    .clamp(1, MAX_PAGE_SIZE);
+                let types = list_param(query, "type");
+                let sort = query.get("sort").map(|s| AssetSort::parse(s)).transpose()?.unwrap_or_default();
+                let (assets, total) = retry_busy(|| {
+                    self.db.get_assets(
+                        &project.id,
+                        query.get("q").map(String::as_str),
+                        types.as_deref(),
+                        sort,
+                        page,
+                        page_size,
+                    )
+                })?;
+                Ok(json_response(200, &AssetsPage { assets, total }))
+            }
+            ["api", "v1", "assets", id] => {
+                let asset = retry_busy(|| self.db.get_asset(id))?
+                    .ok_or_else(|| AppError::AssetNotFound(id.to_string()))?;
+                Ok(json_response(200, &asset))
+            }
+            ["api", "v1", "assets", id, "dependencies"] => {
+                Ok(json_response(200, &retry_busy(|| self.db.get_dependencies(id))?))
+            }
+            ["api", "v1", "assets", id, "dependents"] => {
+                Ok(json_response(200, &retry_busy(|| self.db.get_dependents(id))?))
+            }
+            ["api", "v1", "assets", id, "thumbnail"] => self.thumbnail(id),
+            ["api", "v1", "search"] => {
+                let project = self.project(query)?;
+                let pattern = query.get("pattern").map(String::as_str).unwrap_or("");
+                let options = FileSearchOptions {
+                    regex: query.get("regex").is_some_and(|v| v == "true"),
+                    case_sensitive: query.get("case_sensitive").is_some_and(|v| v == "true"),
+                    types: list_param(query, "type"),
+                };
+                let result = search::search_in_files(&self.db, &project.id, pattern, &options)?;
+                Ok(json_response(200, &result))
+            }
+            _ => Err(AppError::NotFound(format!("No such endpoint: {}", path))),
+        }
+    }
+
+    /// The project named by `project=`, or the one open in the app
+    fn project(&self, query: &HashMap<String, String>) -> AppResult<Project> {
+        let root = match query.get("project") {
+            Some(root) => root.clone(),
+            None => self
+                .settings
+                .read()
+                .project_root
+                .clone()
+                .ok_or_else(|| AppError::NotFound("No project is open".to_string()))?,
+        };
+        retry_busy(|| self.db.get_project_by_path(&root))?
+            .ok_or_else(|| AppError::NotFound(format!("Project not indexed: {}", root)))
+    }
+
+    fn thumbnail(&self, id: &str) -> AppResult<Response<std::io::Cursor<Vec<u8>>>> {
+        let asset = retry_busy(|| self.db.get_asset(id))?
+            .ok_or_else(|| AppError::AssetNotFound(id.to_string()))?;
+        let path = asset
+            .thumbnail_path
+            .ok_or_else(|| AppError::NotFound(format!("No thumbnail for {}", asset.relative_path)))?;
+
+        let content_type = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+            Some("webp") => "image/webp",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            _ => "image/png",
+        };
+        let data = std::fs::read(self.db.thumbnail_file(&path))?;
+        Ok(Response::from_data(data).with_header(header("Content-Type", content_type)))
+    }
+}
+
+fn json_response<T: Serialize + ?Sized>(status: u16, body: &T) -> Response<std::io::Cursor<Vec<u8>>> {
+    let data = serde_json::to_vec(body).unwrap_or_default();
+    Response::from_data(data)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn status_for(error: &AppError) -> u16 {
+    match error {
+        AppError::NotFound(_) | AppError::AssetNotFound(_) => 404,
+        AppError::InvalidArgument(_) => 400,
+        AppError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => 404,
+        e if e.code() == "DB_BUSY" => 503,
+        _ => 500,
+    }
+}
+
+fn split_url(url: &str) -> (String, HashMap<String, String>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    (path.to_string(), query)
+}
+
+fn parse_number(query: &HashMap<String, String>, name: &str) -> AppResult<Option<i64>> {
+    query
+        .get(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| AppError::InvalidArgument(format!("Invalid {}: {}", name, value)))
+        })
+        .transpose()
+}
+
+/// Comma-separated list parameter (`type=texture,model`)
+fn list_param(query: &HashMap<String, String>, name: &str) -> Option<Vec<String>> {
+    query.get(name).map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+            .collect()
+    })
+}
+
+/// Compare tokens without leaking the matching prefix length through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,113 @@
+use crate::db::{rollup_folder, Asset};
+use crate::scanner::ExistingAssetMap;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Payload of `assets-updated`: what changed in a project since the last one
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AssetsChanged {
+    pub project_id: String,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// Assets that got a new thumbnail
+    pub thumbnails: usize,
+    /// `FolderRollup` folders and asset types of the added, updated and
+    /// removed assets; empty when the operation doesn't know them
+    pub folders: Vec<String>,
+    pub asset_types: Vec<String>,
+}
+
+/// Collects changes during a long operation and emits them as one
+/// `assets-updated` event at most once per interval, so the frontend
+/// refetches only the views a change touches, and not on every batch
+pub struct ChangeEmitter {
+    app_handle: AppHandle,
+    project_id: String,
+    interval: Duration,
+    last_emit: Instant,
+    added: usize,
+    updated: usize,
+    removed: usize,
+    thumbnails: usize,
+    folders: BTreeSet<String>,
+    asset_types: BTreeSet<String>,
+}
+
+impl ChangeEmitter {
+    pub fn new(app_handle: AppHandle, project_id: &str, interval: Duration) -> Self {
+        Self {
+            app_handle,
+            project_id: project_id.to_string(),
+            interval,
+            last_emit: Instant::now(),
+            added: 0,
+            updated: 0,
+            removed: 0,
+            thumbnails: 0,
+            folders: BTreeSet::new(),
+            asset_types: BTreeSet::new(),
+        }
+    }
+
+    /// Indexed assets; those already in `existing` count as updated
+    pub fn assets_indexed(&mut self, assets: &[Asset], existing: Option<&ExistingAssetMap>) {
+        for asset in assets {
+            if existing.is_some_and(|e| e.contains_key(&asset.relative_path)) {
+                self.updated += 1;
+            } else {
+                self.added += 1;
+            }
+            self.folders.insert(rollup_folder(&asset.relative_path));
+            self.asset_types.insert(asset.asset_type.clone());
+        }
+    }
+
+    /// Re-indexed assets that were already in the index
+    pub fn assets_updated(&mut self, assets: &[Asset]) {
+        for asset in assets {
+            self.updated += 1;
+            self.folders.insert(rollup_folder(&asset.relative_path));
+            self.asset_types.insert(asset.asset_type.clone());
+        }
+    }
+
+    /// Removed assets, by relative path
+    pub fn assets_removed<'a>(&mut self, relative_paths: impl IntoIterator<Item = &'a str>) {
+        for relative_path in relative_paths {
+            self.removed += 1;
+            self.folders.insert(rollup_folder(relative_path));
+        }
+    }
+
+    pub fn thumbnails_generated(&mut self, count: usize) {
+        self.thumbnails += count;
+    }
+
+    /// Emit what has collected if the interval has passed since the last event
+    pub fn emit_if_due(&mut self) {
+        if self.last_emit.elapsed() > self.interval {
+            self.emit();
+        }
+    }
+
+    /// Emit what has collected now, if anything
+    pub fn emit(&mut self) {
+        self.last_emit = Instant::now();
+        if self.added + self.updated + self.removed + self.thumbnails == 0 {
+            return;
+        }
+        let changes = AssetsChanged {
+            project_id: self.project_id.clone(),
+            added: std::mem::take(&mut self.added),
+            updated: std::mem::take(&mut self.updated),
+            removed: std::mem::take(&mut self.removed),
+            thumbnails: std::mem::take(&mut self.thumbnails),
+            folders: std::mem::take(&mut self.folders).into_iter().collect(),
+            asset_types: std::mem::take(&mut self.asset_types).into_iter().collect(),
+        };
+        let _ = self.app_handle.emit("assets-updated", changes);
+    }
+}
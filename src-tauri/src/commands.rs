@@ -1,48 +1,124 @@
-use crate::db::{Asset, Dependency, Project, TypeCount};
-use crate::deps::DependencyResolver;
+use crate::api::ApiStatus;
+use crate::asset_files::{self, DeleteAssetsResult};
+use crate::asset_store::{self, StoreExtraction};
+use crate::benchmark::{self, BenchmarkReport};
+use crate::boundaries::{self, BoundaryReport};
+use crate::build_layout::{self, BuildLayoutImport};
+use crate::build_report::{self, BuildReportImport};
+use crate::changes::ChangeEmitter;
+use crate::catalog::{self, AssetListExport, AssetListFilter, AttributionExport, GraphExport};
+use crate::code_editor::{self, CodeEditor};
+use crate::compare::{self, GuidCollisionReport, ProjectComparison};
+use crate::converters::{self, TextureConverter};
+use crate::deliveries::{self, Delivery, DeliveryCheck, DeliveryFilter};
+use crate::db::{
+    retry_busy, Asset, AssetBuildCost, AssetCursor, AssetSort, AssetSummary, BuiltBundle, BundledAsset, Database, Dependency, ExcludedAsset, FolderRollup, GrowthPoint, Project, RuleViolation, RuleViolationCount, ScanCheckpoint,
+    StoreFile, StoreFileHit, StorePackage, LocalizationTable, TextureEstimateEntry, ThumbnailFailure, TrashOperation, TypeCount,
+};
+use crate::deps::{DependencyGraph, DependencyResolver};
+use crate::diagnostics::{self, Diagnostics};
+use crate::duplicates::{self, ExternalFileReport};
 use crate::error::AppError;
-use crate::export::{ExportResult, Exporter};
+use crate::export::{self, DragStage, ExportOverrides, ExportResult, Exporter};
+use crate::hex_preview::{self, HexPreview};
 use crate::indexer::Indexer;
-use crate::previews::{parse_material_file, parse_model_info, MaterialInfo, ModelInfo, PreviewGenerator};
-use crate::scanner::{count_scannable_files, scan_files_batch, ScanStats};
+use crate::library::{self, LibraryCopy};
+use crate::jobs::{JobStatus, ProgressExtra, ProgressReporter};
+use crate::localization::{self, LocalizedKey};
+use crate::lods::{self, LodReport};
+use crate::logging::{self, LogEntry};
+use crate::media::{self, AudioQaReport, AudioQaThresholds, MediaInfo};
+use crate::metrics::PerformanceMetrics;
+use crate::notify::{self, WebhookPayload};
+use crate::plugins::{PluginMetadata, PluginStatus};
+use crate::provenance::{self, Provenance, UpdateCheck};
+use crate::previews::{
+    open_image_limited, parse_material_file, parse_model_info, read_text_preview, MaterialInfo,
+    ModelInfo, PreviewGenerator, TextPreview, ThumbnailOptions,
+};
+use crate::render_pipeline::{self, RenderPipelineReport};
+use crate::rules::{self, RuleInfo, RuleRunSummary};
+use crate::scanner::{
+    count_scannable_files, is_ignored_relative, scan_files_batch, validate_ignore_pattern,
+    ChangeDetection, DirectoryMtimes, ExistingAssetMap, ScanStats, WalkOptions,
+};
+use crate::scene_costs::{self, SceneCostReport};
+use crate::search::{self, FileSearchOptions, FileSearchResult, SearchSuggestion};
+use crate::session::{IntegrityReport, StartupStatus};
+use crate::settings::{Settings, SettingsPatch, SettingsProfile};
+use crate::similar::{self, SimilarAsset};
+use crate::snapshot::{self, SnapshotManifest};
 use crate::state::AppState;
+use crate::tags::{self, AssetTags, BulkEdit, TagCount};
+use crate::telemetry::TelemetryEvent;
+use crate::texture_health::{self, TextureHealthReport};
+use crate::texture_size::{self, TextureEstimate};
+use crate::unity_library::{self, LibraryInspection};
+use crate::validation::{self, ValidationReport};
+use crate::vcs::{Vcs, VcsFilter, VcsRepoInfo};
+use crate::storage::{self, PublishedCatalog, StorageMove};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::{Emitter, State};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScanProgress {
-    pub scanned: usize,
-    pub total: Option<usize>,
-    pub current_path: String,
-    pub phase: String,
-    /// Number of unchanged files skipped during re-scan
-    pub skipped: Option<usize>,
-    /// Number of new or changed files processed
-    pub changed: Option<usize>,
-}
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AssetsResponse {
     pub assets: Vec<Asset>,
     pub total: i64,
+    /// False in large-project mode, where `total` is an estimate and the
+    /// exact count follows in an `assets-count` event
+    pub total_exact: bool,
+    /// Pass back as `cursor` for the next page in large-project mode
+    pub next_cursor: Option<AssetCursor>,
+}
+
+/// Payload of `assets-count`: the exact total for the query it names
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetsCountEvent {
+    pub project_id: String,
+    pub search_query: Option<String>,
+    pub asset_types: Option<Vec<String>>,
+    pub total: i64,
 }
 
+/// Wait before a background count starts, so typing a search runs one
+/// count for the final query instead of one per keystroke
+const ASSET_COUNT_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Bumped for every scheduled count; a count that is no longer the latest is dropped
+static ASSET_COUNT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SettingsResponse {
     pub project_root: Option<String>,
     pub output_folder: Option<String>,
 }
 
-#[tauri::command]
-pub async fn set_project_root(
-    path: String,
-    state: State<'_, AppState>,
-) -> Result<Project, AppError> {
-    let root = Path::new(&path);
+/// A registered project or library and whether it is the active one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectListEntry {
+    #[serde(flatten)]
+    pub project: Project,
+    /// "project" or "library"
+    pub kind: String,
+    pub active: bool,
+}
+
+/// Check a project folder and get or create its catalog entry
+fn open_project(state: &AppState, path: &str) -> Result<Project, AppError> {
+    // A shared catalog can only show projects it already contains, and the
+    // folder is usually another machine's path
+    if state.db.is_read_only() {
+        return state
+            .db
+            .get_project_by_path(path)?
+            .ok_or_else(|| AppError::NotFound(format!("{} is not in the shared catalog", path)));
+    }
 
     // Accept any valid folder, not just Unity projects
+    let root = Path::new(path);
     if !root.is_dir() {
         return Err(AppError::InvalidProject(
             "Not a valid folder.".to_string(),
@@ -56,16 +132,118 @@ pub async fn set_project_root(
         .unwrap_or_else(|| "Unknown Folder".to_string());
 
     // Create or get project in database
-    let project = state.db.get_or_create_project(&path, &name)?;
+    state.db.get_or_create_project(path, &name)
+}
+
+/// Add a project folder and make it the active project
+#[tauri::command]
+pub async fn set_project_root(
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Project, AppError> {
+    if state.settings.read().is_library(&path) {
+        return Err(AppError::InvalidArgument(format!("{} is already added as a library", path)));
+    }
+    let project = open_project(&state, &path)?;
+    state.activate_project(&path, &app_handle)?;
+    Ok(project)
+}
+
+/// Add a project folder without switching to it
+#[tauri::command]
+pub async fn add_project(path: String, state: State<'_, AppState>) -> Result<Project, AppError> {
+    if state.settings.read().is_library(&path) {
+        return Err(AppError::InvalidArgument(format!("{} is already added as a library", path)));
+    }
+    let project = open_project(&state, &path)?;
+    let mut settings = state.settings.write();
+    settings.register_project(&path);
+    settings.save()?;
+    Ok(project)
+}
+
+/// Add a reference folder (a texture archive, an asset dump) as a library.
+/// It is scanned and searched like a project, but skips the Unity-specific
+/// scan phases and can't be made active; `copy_from_library` brings its
+/// files into the active project.
+#[tauri::command]
+pub async fn add_library(path: String, state: State<'_, AppState>) -> Result<Project, AppError> {
+    if state.settings.read().project_roots.contains(&path) {
+        return Err(AppError::InvalidArgument(format!("{} is already added as a project", path)));
+    }
+    let project = open_project(&state, &path)?;
+    let mut settings = state.settings.write();
+    settings.register_library(&path);
+    settings.save()?;
+    Ok(project)
+}
+
+/// Registered projects in the order added, then libraries. Folders the
+/// catalog has no entry for (a shared catalog from another team) are left out.
+#[tauri::command]
+pub async fn list_projects(state: State<'_, AppState>) -> Result<Vec<ProjectListEntry>, AppError> {
+    let (roots, active) = {
+        let settings = state.settings.read();
+        let roots: Vec<(String, &str)> = settings
+            .project_roots
+            .iter()
+            .map(|r| (r.clone(), "project"))
+            .chain(settings.library_roots.iter().map(|r| (r.clone(), "library")))
+            .collect();
+        (roots, settings.project_root.clone())
+    };
+
+    let mut projects = Vec::new();
+    for (root, kind) in roots {
+        if let Some(project) = state.db.get_project_by_path(&root)? {
+            projects.push(ProjectListEntry {
+                active: active.as_deref() == Some(root.as_str()),
+                kind: kind.to_string(),
+                project,
+            });
+        }
+    }
+    Ok(projects)
+}
+
+/// Make another project in the catalog the active one
+#[tauri::command]
+pub async fn switch_project(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Project, AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    if state.settings.read().is_library(&project.root_path) {
+        return Err(AppError::InvalidArgument(format!("{} is a library, not a project", project.name)));
+    }
+    state.activate_project(&project.root_path, &app_handle)?;
+    Ok(project)
+}
 
-    // Save to settings
+/// Take a project off the list. Its scan data stays in the catalog and is
+/// used again if the folder is added back.
+#[tauri::command]
+pub async fn remove_project(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
     {
         let mut settings = state.settings.write();
-        settings.project_root = Some(path);
+        settings.unregister_project(&project.root_path);
         settings.save()?;
     }
-
-    Ok(project)
+    state.apply_watch_settings(&app_handle);
+    Ok(())
 }
 
 #[tauri::command]
@@ -89,450 +267,3113 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<SettingsResponse
 }
 
 #[tauri::command]
-pub async fn get_current_project(state: State<'_, AppState>) -> Result<Option<Project>, AppError> {
-    let settings = state.settings.read();
-
-    if let Some(root) = &settings.project_root {
-        return state.db.get_project_by_path(root);
-    }
-
-    Ok(None)
+pub async fn get_all_settings(state: State<'_, AppState>) -> Result<Settings, AppError> {
+    Ok(state.settings.read().clone())
 }
 
 #[tauri::command]
-pub async fn start_scan(
-    project_id: String,
+pub async fn update_settings(
+    patch: SettingsPatch,
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), AppError> {
-    // Cancel any existing scan and wait for it to finish
-    if state.is_scan_running() {
-        state.request_cancel();
-        // Wait for the existing scan to finish (with timeout)
-        let scan_running = Arc::clone(&state.scan_running);
-        tokio::task::spawn_blocking(move || {
-            let start = std::time::Instant::now();
-            while scan_running.load(std::sync::atomic::Ordering::SeqCst) {
-                std::thread::sleep(std::time::Duration::from_millis(50));
-                // Timeout after 5 seconds
-                if start.elapsed().as_secs() > 5 {
-                    tracing::warn!("Timeout waiting for previous scan to cancel");
-                    break;
-                }
-            }
-        })
-        .await
-        .ok();
-    }
-
-    let db = Arc::clone(&state.db);
-    let settings = state.settings.read().clone();
-    let cancel_flag = Arc::clone(&state.cancel_flag);
-    let scan_running = Arc::clone(&state.scan_running);
+) -> Result<Settings, AppError> {
+    let updated = {
+        let mut settings = state.settings.write();
+        settings.apply_patch(patch)?;
+        settings.save()?;
+        state.telemetry.set_enabled(settings.telemetry_enabled);
+        settings.clone()
+    };
+    state.apply_api_settings();
+    state.apply_watch_settings(&app_handle);
+    Ok(updated)
+}
 
-    // Reset cancel flag and mark scan as running
-    state.reset_cancel();
-    state.set_scan_running(true);
+#[tauri::command]
+pub async fn get_api_status(state: State<'_, AppState>) -> Result<ApiStatus, AppError> {
+    Ok(state.api_status())
+}
 
-    let project = state
-        .db
-        .get_project_by_path(settings.project_root.as_deref().unwrap_or(""))?
-        .ok_or_else(|| AppError::Custom("Project not found".to_string()))?;
+/// Issue a new API token; clients using the old one get 401 from now on
+#[tauri::command]
+pub async fn regenerate_api_token(state: State<'_, AppState>) -> Result<ApiStatus, AppError> {
+    {
+        let mut settings = state.settings.write();
+        settings.regenerate_api_token();
+        settings.save()?;
+    }
+    state.apply_api_settings();
+    Ok(state.api_status())
+}
 
-    let root_path = project.root_path.clone();
-    let project_id_clone = project_id.clone();
-    let ignore_patterns = settings.ignore_patterns.clone();
+/// POST a "test" payload to `url`, or to the configured webhook
+#[tauri::command]
+pub async fn send_test_webhook(url: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    let (configured, project_root) = {
+        let settings = state.settings.read();
+        (settings.webhook_url.clone(), settings.project_root.clone())
+    };
+    let url = url
+        .or(configured)
+        .ok_or_else(|| AppError::InvalidArgument("No webhook URL configured".to_string()))?;
 
-    // Spawn scanning task
     tokio::task::spawn_blocking(move || {
-        let db_clone = Arc::clone(&db);
-        let indexer = Indexer::new(Arc::clone(&db));
-        let mut last_refresh = std::time::Instant::now();
-
-        // Fetch existing assets for change detection (skip unchanged files on re-scan)
-        let existing_assets = match db.get_existing_asset_info(&project_id_clone) {
-            Ok(map) => {
-                if map.is_empty() {
-                    None
-                } else {
-                    tracing::info!("Found {} existing assets for change detection", map.len());
-                    Some(map)
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Failed to fetch existing assets for change detection: {}", e);
-                None
-            }
-        };
-
-        // Phase 0: Count files first for accurate progress
-        let _ = app_handle.emit(
-            "scan-progress",
-            ScanProgress {
-                scanned: 0,
-                total: None,
-                current_path: "".to_string(),
-                phase: "counting".to_string(),
-                skipped: None,
-                changed: None,
-            },
-        );
+        notify::post_webhook(&url, &WebhookPayload::new("test", project_root, None))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Webhook task failed: {}", e)))?
+}
 
-        let cancel_flag_count = Arc::clone(&cancel_flag);
-        let total_files = match count_scannable_files(
-            Path::new(&root_path),
-            &ignore_patterns,
-            cancel_flag_count,
-            |count| {
-                let _ = app_handle.emit(
-                    "scan-progress",
-                    ScanProgress {
-                        scanned: count,
-                        total: None,
-                        current_path: "".to_string(),
-                        phase: "counting".to_string(),
-                        skipped: None,
-                        changed: None,
-                    },
-                );
-            },
-        ) {
-            Ok(count) => count,
-            Err(e) => {
-                tracing::error!("Failed to count files: {}", e);
-                0
-            }
-        };
+/// Write the shareable part of the current settings to a JSON profile
+#[tauri::command]
+pub async fn export_settings_profile(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<SettingsProfile, AppError> {
+    let profile = state.settings.read().to_profile();
+    std::fs::write(&path, serde_json::to_string_pretty(&profile)?)?;
+    Ok(profile)
+}
 
-        // Check if cancelled during counting
-        if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
-            let _ = app_handle.emit(
-                "scan-progress",
-                ScanProgress {
-                    scanned: 0,
-                    total: None,
-                    current_path: "".to_string(),
-                    phase: "cancelled".to_string(),
-                    skipped: None,
-                    changed: None,
-                },
-            );
-            scan_running.store(false, std::sync::atomic::Ordering::SeqCst);
-            return;
-        }
+/// Apply a profile exported by `export_settings_profile`. Values are validated
+/// like `update_settings`; nothing is changed if any of them is invalid.
+#[tauri::command]
+pub async fn import_settings_profile(
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Settings, AppError> {
+    let content = std::fs::read_to_string(&path)?;
+    let profile: SettingsProfile = serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidArgument(format!("Invalid settings profile: {}", e)))?;
 
-        // Phase 1: Scan and index files
-        let _ = app_handle.emit(
-            "scan-progress",
-            ScanProgress {
-                scanned: 0,
-                total: Some(total_files),
-                current_path: "".to_string(),
-                phase: "indexing".to_string(),
-                skipped: None,
-                changed: None,
-            },
-        );
+    let updated = {
+        let mut settings = state.settings.write();
+        settings.apply_patch(profile.into_patch())?;
+        settings.save()?;
+        settings.clone()
+    };
+    state.apply_watch_settings(&app_handle);
+    Ok(updated)
+}
 
-        let cancel_flag_scan = Arc::clone(&cancel_flag);
-        let mut final_stats = ScanStats::default();
-        let total = scan_files_batch(
-            Path::new(&root_path),
-            &project_id_clone,
-            &ignore_patterns,
-            25,
-            cancel_flag_scan,
-            existing_assets.as_ref(),
-            |batch, count, current_path| {
-                // Index the batch
-                if let Err(e) = indexer.upsert_batch(&batch) {
-                    tracing::error!("Failed to index batch: {}", e);
-                }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvalidIgnorePattern {
+    pub pattern: String,
+    pub reason: String,
+}
 
-                let _ = app_handle.emit(
-                    "scan-progress",
-                    ScanProgress {
-                        scanned: count,
-                        total: Some(total_files),
-                        current_path: current_path.to_string(),
-                        phase: "indexing".to_string(),
-                        skipped: None,
-                        changed: None,
-                    },
-                );
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IgnorePatternsResult {
+    /// Patterns that failed validation; nothing is saved if this is non-empty
+    pub invalid: Vec<InvalidIgnorePattern>,
+    /// Number of indexed assets the new patterns would exclude
+    pub newly_ignored_count: usize,
+    /// First few newly ignored paths, for display
+    pub newly_ignored_sample: Vec<String>,
+    pub pruned: usize,
+    pub saved: bool,
+}
 
-                // Signal frontend to refresh every 200ms
-                if last_refresh.elapsed().as_millis() > 200 {
-                    let _ = app_handle.emit("assets-updated", count);
-                    last_refresh = std::time::Instant::now();
-                }
+const IGNORED_SAMPLE_SIZE: usize = 100;
 
-                // Return true to continue, false to stop
-                !cancel_flag.load(std::sync::atomic::Ordering::SeqCst)
-            },
-        );
+/// Validate and save ignore patterns, reporting which indexed assets they exclude.
+/// With `dry_run` nothing is saved; with `prune` the excluded assets are removed from the index.
+#[tauri::command]
+pub async fn update_ignore_patterns(
+    project_id: Option<String>,
+    patterns: Vec<String>,
+    prune: Option<bool>,
+    dry_run: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<IgnorePatternsResult, AppError> {
+    let patterns: Vec<String> = patterns.iter().map(|p| p.trim().to_string()).collect();
+    let invalid: Vec<InvalidIgnorePattern> = patterns
+        .iter()
+        .filter_map(|p| {
+            validate_ignore_pattern(p).err().map(|reason| InvalidIgnorePattern {
+                pattern: p.clone(),
+                reason,
+            })
+        })
+        .collect();
+
+    if !invalid.is_empty() {
+        return Ok(IgnorePatternsResult {
+            invalid,
+            newly_ignored_count: 0,
+            newly_ignored_sample: Vec::new(),
+            pruned: 0,
+            saved: false,
+        });
+    }
 
-        // Extract stats from scan result
-        if let Ok((_, stats)) = &total {
-            final_stats = stats.clone();
-            tracing::info!(
-                "Scan complete: {} total files, {} unchanged (skipped), {} new/changed",
-                stats.total_files,
-                stats.unchanged_skipped,
-                stats.new_or_changed
-            );
+    let project_id = match project_id {
+        Some(id) => Some(id),
+        None => {
+            let root = state.settings.read().project_root.clone();
+            match root {
+                Some(root) => state.db.get_project_by_path(&root)?.map(|p| p.id),
+                None => None,
+            }
         }
+    };
+
+    // Find indexed assets that match the new patterns
+    let mut newly_ignored: Vec<(String, String)> = match &project_id {
+        Some(id) => state
+            .db
+            .get_existing_asset_info(id)?
+            .into_iter()
+            .filter(|(relative_path, _)| is_ignored_relative(relative_path, &patterns))
+            .map(|(relative_path, (asset_id, _, _))| (relative_path, asset_id))
+            .collect(),
+        None => Vec::new(),
+    };
+    newly_ignored.sort();
+
+    let mut result = IgnorePatternsResult {
+        invalid,
+        newly_ignored_count: newly_ignored.len(),
+        newly_ignored_sample: newly_ignored
+            .iter()
+            .take(IGNORED_SAMPLE_SIZE)
+            .map(|(path, _)| path.clone())
+            .collect(),
+        pruned: 0,
+        saved: false,
+    };
+
+    if dry_run.unwrap_or(false) {
+        return Ok(result);
+    }
 
-        // Check if cancelled during indexing
-        if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
-            let _ = app_handle.emit(
-                "scan-progress",
-                ScanProgress {
-                    scanned: 0,
-                    total: None,
-                    current_path: "".to_string(),
-                    phase: "cancelled".to_string(),
-                    skipped: None,
-                    changed: None,
-                },
-            );
-            scan_running.store(false, std::sync::atomic::Ordering::SeqCst);
-            return;
+    // Don't delete rows out from under a running scan
+    let prune = prune.unwrap_or(false) && !newly_ignored.is_empty();
+    if prune {
+        state.ensure_writable()?;
+        if state.is_scan_running() {
+            return Err(AppError::ScanAlreadyRunning);
         }
+    }
 
-        let file_count = total.map(|(count, _)| count).unwrap_or(0) as i64;
+    {
+        let mut settings = state.settings.write();
+        settings.ignore_patterns = patterns;
+        settings.save()?;
+    }
+    result.saved = true;
+    state.apply_watch_settings(&app_handle);
+
+    if let (true, Some(project_id)) = (prune, &project_id) {
+        let ids: Vec<String> = newly_ignored.iter().map(|(_, id)| id.clone()).collect();
+        let thumbnails = state.db.delete_assets(&ids)?;
+        for thumb in thumbnails {
+            let _ = std::fs::remove_file(thumb);
+        }
+        result.pruned = ids.len();
 
-        // Signal final asset update
-        let _ = app_handle.emit("assets-updated", file_count);
-
-        // Phase 2: Resolve dependencies with progress
-        let _ = app_handle.emit(
-            "scan-progress",
-            ScanProgress {
-                scanned: 0,
-                total: None,
-                current_path: "".to_string(),
-                phase: "dependencies".to_string(),
-                skipped: Some(final_stats.unchanged_skipped),
-                changed: Some(final_stats.new_or_changed),
-            },
-        );
+        let mut changes = ChangeEmitter::new(app_handle, project_id, Duration::ZERO);
+        changes.assets_removed(newly_ignored.iter().map(|(path, _)| path.as_str()));
+        changes.emit();
+    }
 
-        let dep_resolver = DependencyResolver::new(Arc::clone(&db_clone));
-        let cancel_flag_deps = Arc::clone(&cancel_flag);
-        let app_handle_deps = app_handle.clone();
-        let stats_for_deps = final_stats.clone();
-        if let Err(e) = dep_resolver.resolve_all_for_project_with_progress(
-            &project_id_clone,
-            cancel_flag_deps,
-            |processed, total| {
-                let _ = app_handle_deps.emit(
-                    "scan-progress",
-                    ScanProgress {
-                        scanned: processed,
-                        total: Some(total),
-                        current_path: "".to_string(),
-                        phase: "dependencies".to_string(),
-                        skipped: Some(stats_for_deps.unchanged_skipped),
-                        changed: Some(stats_for_deps.new_or_changed),
-                    },
-                );
-            },
-        ) {
-            tracing::error!("Failed to resolve dependencies: {}", e);
-        }
+    Ok(result)
+}
 
-        // Check if cancelled during dependencies
-        if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
-            let _ = app_handle.emit(
-                "scan-progress",
-                ScanProgress {
-                    scanned: 0,
-                    total: None,
-                    current_path: "".to_string(),
-                    phase: "cancelled".to_string(),
-                    skipped: Some(final_stats.unchanged_skipped),
-                    changed: Some(final_stats.new_or_changed),
-                },
-            );
-            scan_running.store(false, std::sync::atomic::Ordering::SeqCst);
-            return;
-        }
+/// Take assets out of the index and keep later scans from adding them back,
+/// without touching the ignore patterns. Returns how many were excluded.
+#[tauri::command]
+pub async fn exclude_assets(
+    asset_ids: Vec<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    if asset_ids.is_empty() {
+        return Err(AppError::InvalidArgument("No assets to exclude".to_string()));
+    }
+    state.ensure_writable()?;
+    // Don't delete rows out from under a running scan
+    if state.is_scan_running() {
+        return Err(AppError::ScanAlreadyRunning);
+    }
+    let db = Arc::clone(&state.db);
 
-        // Update project scan time
-        if let Err(e) = db_clone.update_project_scan_time(&project_id_clone, file_count) {
-            tracing::error!("Failed to update project scan time: {}", e);
+    let assets = tokio::task::spawn_blocking(move || {
+        let assets = asset_ids
+            .into_iter()
+            .map(|id| db.get_asset(&id)?.ok_or(AppError::AssetNotFound(id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ids: Vec<String> = assets.iter().map(|a| a.id.clone()).collect();
+        db.exclude_assets(&ids)?;
+        for thumb in db.delete_assets(&ids)? {
+            let _ = std::fs::remove_file(thumb);
         }
+        Ok::<_, AppError>(assets)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Exclude task failed: {}", e)))??;
 
-        // Complete scan phase - thumbnails will be generated separately via regenerate_thumbnails
-        let _ = app_handle.emit(
-            "scan-progress",
-            ScanProgress {
-                scanned: file_count as usize,
-                total: Some(final_stats.total_files),
-                current_path: "".to_string(),
-                phase: "complete".to_string(),
-                skipped: Some(final_stats.unchanged_skipped),
-                changed: Some(final_stats.new_or_changed),
-            },
-        );
-
-        // Mark scan as no longer running
-        scan_running.store(false, std::sync::atomic::Ordering::SeqCst);
-    });
+    let mut by_project: std::collections::HashMap<&str, Vec<&str>> = std::collections::HashMap::new();
+    for asset in &assets {
+        by_project.entry(&asset.project_id).or_default().push(&asset.relative_path);
+    }
+    for (project_id, paths) in by_project {
+        let mut changes = ChangeEmitter::new(app_handle.clone(), project_id, Duration::ZERO);
+        changes.assets_removed(paths);
+        changes.emit();
+    }
 
-    Ok(())
+    Ok(assets.len())
 }
 
+/// Stop excluding these paths; the files come back with the next scan.
+/// Returns how many were excluded.
 #[tauri::command]
-pub async fn cancel_operation(state: State<'_, AppState>) -> Result<(), AppError> {
-    state.request_cancel();
-    Ok(())
+pub async fn include_excluded_assets(
+    project_id: String,
+    relative_paths: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    state.ensure_writable()?;
+    state.db.include_excluded_assets(&project_id, &relative_paths)
 }
 
 #[tauri::command]
-pub async fn get_assets(
+pub async fn get_excluded_assets(
     project_id: String,
-    search_query: Option<String>,
-    asset_types: Option<Vec<String>>,
-    page: i64,
-    page_size: i64,
     state: State<'_, AppState>,
-) -> Result<AssetsResponse, AppError> {
-    let (assets, total) = state.db.get_assets(
-        &project_id,
-        search_query.as_deref(),
-        asset_types.as_deref(),
-        page,
-        page_size,
-    )?;
+) -> Result<Vec<ExcludedAsset>, AppError> {
+    state.db.get_excluded_assets(&project_id)
+}
 
-    Ok(AssetsResponse { assets, total })
+/// Rename or move an asset and its `.meta` within the project, updating
+/// the index in place so its id and dependency edges are kept
+#[tauri::command]
+pub async fn move_asset(
+    asset_id: String,
+    new_relative_path: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Asset, AppError> {
+    state.ensure_writable()?;
+    // A running scan would index the file at both paths
+    if state.is_scan_running() {
+        return Err(AppError::ScanAlreadyRunning);
+    }
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let ignore_patterns = state.settings.read().ignore_patterns.clone();
+    let plugins = state.active_plugins();
+    let db = Arc::clone(&state.db);
+
+    let old = asset.clone();
+    let moved = tokio::task::spawn_blocking(move || {
+        asset_files::move_asset(&db, &asset, &new_relative_path, &ignore_patterns, plugins.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Move task failed: {}", e)))??;
+
+    if moved.relative_path != old.relative_path {
+        let mut changes = ChangeEmitter::new(app_handle, &moved.project_id, Duration::ZERO);
+        changes.assets_removed([old.relative_path.as_str()]);
+        changes.assets_indexed(std::slice::from_ref(&moved), None);
+        changes.emit();
+    }
+
+    Ok(moved)
+}
+
+/// Move assets and their `.meta` files to the OS trash. Without `confirm`
+/// nothing is deleted and the result lists the references that would break.
+#[tauri::command]
+pub async fn delete_assets(
+    asset_ids: Vec<String>,
+    confirm: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DeleteAssetsResult, AppError> {
+    if asset_ids.is_empty() {
+        return Err(AppError::InvalidArgument("No assets to delete".to_string()));
+    }
+    let confirm = confirm.unwrap_or(false);
+    if confirm {
+        state.ensure_writable()?;
+        // Don't delete rows out from under a running scan
+        if state.is_scan_running() {
+            return Err(AppError::ScanAlreadyRunning);
+        }
+    }
+    let db = Arc::clone(&state.db);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let assets = asset_ids
+            .into_iter()
+            .map(|id| db.get_asset(&id)?.ok_or(AppError::AssetNotFound(id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        asset_files::delete_assets(&db, &assets, confirm)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Delete task failed: {}", e)))??;
+
+    if let Some(operation) = &result.operation {
+        let mut changes = ChangeEmitter::new(app_handle, &operation.project_id, Duration::ZERO);
+        changes.assets_removed(operation.assets.iter().map(|a| a.relative_path.as_str()));
+        changes.emit();
+    }
+
+    Ok(result)
+}
+
+/// Undo a deletion by restoring its files from the OS trash; they are
+/// indexed again by the next scan
+#[tauri::command]
+pub async fn restore_deleted_assets(
+    operation_id: String,
+    state: State<'_, AppState>,
+) -> Result<TrashOperation, AppError> {
+    state.ensure_writable()?;
+    let db = Arc::clone(&state.db);
+
+    tokio::task::spawn_blocking(move || asset_files::restore_from_trash(&db, &operation_id))
+        .await
+        .map_err(|e| AppError::Custom(format!("Restore task failed: {}", e)))?
+}
+
+/// Deletions made from the catalog, most recent first
+#[tauri::command]
+pub async fn get_trash_operations(
+    project_id: String,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<TrashOperation>, AppError> {
+    state.db.get_trash_operations(Some(&project_id), None, limit.unwrap_or(50))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageLocations {
+    /// Folders currently in use
+    pub database_dir: String,
+    pub thumbnail_dir: String,
+    /// Folders that take effect after restart, if a move is pending
+    pub pending_database_dir: Option<String>,
+    pub pending_thumbnail_dir: Option<String>,
+}
+
+fn storage_locations(state: &AppState) -> Result<StorageLocations, AppError> {
+    let settings = state.settings.read();
+    let active_db = state
+        .db
+        .path()
+        .parent()
+        .unwrap_or(&state.app_data_dir)
+        .to_string_lossy()
+        .to_string();
+    let active_thumbs = state.thumbnail_dir()?.to_string_lossy().to_string();
+
+    let (pending_db, pending_thumbs) = match &settings.pending_storage_move {
+        Some(_) => (
+            Some(storage::database_dir(&settings, &state.app_data_dir).to_string_lossy().to_string()),
+            Some(storage::thumbnail_dir(&settings, &state.app_data_dir).to_string_lossy().to_string()),
+        ),
+        None => (None, None),
+    };
+
+    Ok(StorageLocations {
+        database_dir: active_db,
+        thumbnail_dir: active_thumbs,
+        pending_database_dir: pending_db,
+        pending_thumbnail_dir: pending_thumbs,
+    })
+}
+
+#[tauri::command]
+pub async fn get_storage_locations(state: State<'_, AppState>) -> Result<StorageLocations, AppError> {
+    storage_locations(&state)
+}
+
+/// Change where the database and thumbnail cache live. `None` restores the default
+/// location. Existing data is moved on the next startup.
+#[tauri::command]
+pub async fn set_storage_locations(
+    database_dir: Option<String>,
+    thumbnail_dir: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<StorageLocations, AppError> {
+    for dir in database_dir.iter().chain(thumbnail_dir.iter()) {
+        storage::validate_storage_dir(Path::new(dir))?;
+    }
+
+    let current = storage_locations(&state)?;
+    {
+        let mut settings = state.settings.write();
+        let mut updated = settings.clone();
+        updated.database_dir = database_dir;
+        updated.thumbnail_dir = thumbnail_dir;
+
+        let new_db = storage::database_dir(&updated, &state.app_data_dir);
+        let new_thumbs = storage::thumbnail_dir(&updated, &state.app_data_dir);
+
+        if new_db.join(storage::DATABASE_FILE).exists()
+            && new_db != Path::new(&current.database_dir)
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "{} already contains a database",
+                new_db.display()
+            )));
+        }
+
+        let changed = new_db != Path::new(&current.database_dir)
+            || new_thumbs != Path::new(&current.thumbnail_dir);
+        updated.pending_storage_move = changed.then_some(StorageMove {
+            from_database_dir: current.database_dir,
+            from_thumbnail_dir: current.thumbnail_dir,
+        });
+        updated.save()?;
+        *settings = updated;
+    }
+
+    storage_locations(&state)
+}
+
+/// Browse a published team catalog read-only instead of the local database,
+/// or go back to the local one with `None`. Takes effect on the next startup.
+#[tauri::command]
+pub async fn set_shared_catalog(
+    dir: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    if let Some(dir) = &dir {
+        let db_path = storage::shared_catalog_database(Path::new(dir));
+        let options = state.settings.read().db_options();
+        Database::open_read_only(&db_path, &options)?;
+    }
+
+    let mut settings = state.settings.write();
+    settings.shared_catalog_dir = dir;
+    settings.save()?;
+    Ok(())
+}
+
+/// Publish the local catalog and its thumbnails to a shared folder for the
+/// rest of the team to open with `set_shared_catalog`
+#[tauri::command]
+pub async fn publish_shared_catalog(
+    dest_dir: String,
+    state: State<'_, AppState>,
+) -> Result<PublishedCatalog, AppError> {
+    state.ensure_writable()?;
+    storage::validate_storage_dir(Path::new(&dest_dir))?;
+
+    let db = Arc::clone(&state.db);
+    let thumb_dir = state.thumbnail_dir()?;
+    let metrics = Arc::clone(&state.metrics);
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("catalog.publish");
+        storage::publish_catalog(&db, &thumb_dir, Path::new(&dest_dir))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Catalog publish task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn get_current_project(state: State<'_, AppState>) -> Result<Option<Project>, AppError> {
+    let settings = state.settings.read();
+
+    if let Some(root) = &settings.project_root {
+        return state.db.get_project_by_path(root);
+    }
+
+    Ok(None)
+}
+
+/// Problems reported by `check_database_integrity` before it stops looking
+const INTEGRITY_CHECK_MAX_ERRORS: usize = 100;
+
+/// Older checkpoints are ignored; the project has likely changed too much
+const MAX_CHECKPOINT_AGE_SECS: i64 = 24 * 60 * 60;
+
+#[tauri::command]
+pub async fn start_scan(
+    project_id: String,
+    mode: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let settings = state.settings.read().clone();
+
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+
+    // "quick" (default) skips files whose size and mtime are unchanged,
+    // "full" re-indexes everything while keeping existing asset ids
+    let mode = mode.unwrap_or_else(|| "quick".to_string());
+    let skip_unchanged = match mode.as_str() {
+        "quick" => true,
+        "full" => false,
+        other => return Err(AppError::InvalidArgument(format!("Unknown scan mode: {}", other))),
+    };
+
+    state.ensure_writable()?;
+
+    // Only one scan may write to the database at a time
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let telemetry = Arc::clone(&state.telemetry);
+    let metrics = Arc::clone(&state.metrics);
+    let safe_mode = state.is_safe_mode();
+    let plugins = state.active_plugins();
+    let rules_dir = storage::rules_dir(&state.app_data_dir);
+    let scan_started = std::time::Instant::now();
+
+    let root_path = project.root_path.clone();
+    let project_id_clone = project_id.clone();
+    // Libraries aren't Unity projects: no references, import settings or
+    // localization tables to read
+    let unity_project = !settings.is_library(&project.root_path);
+    let ignore_patterns = settings.ignore_patterns.clone();
+    let scan_threads = settings.effective_scan_threads();
+    let dependency_threads = settings.effective_dependency_threads();
+    let dependency_max_file_bytes = settings.dependency_max_file_bytes();
+    let batch_size = settings.scan_batch_size;
+    let directory_change_detection = settings.directory_change_detection;
+    let refresh_interval = std::time::Duration::from_millis(settings.ui_refresh_ms);
+
+    let progress = ProgressReporter::with_cancel(
+        app_handle.clone(),
+        Arc::clone(&state.jobs),
+        "scan",
+        "scan-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
+
+    // Spawn scanning task
+    tokio::task::spawn_blocking(move || {
+        // Released when the task returns (or panics)
+        let _scan_lock = scan_lock;
+        let _scan_timer = metrics.time("scan.total");
+        let db_clone = Arc::clone(&db);
+        let indexer = Indexer::new(Arc::clone(&db));
+        let mut changes = ChangeEmitter::new(app_handle.clone(), &project_id_clone, refresh_interval);
+
+        // Fetch existing assets for change detection and asset id reuse
+        let existing_assets = match db.get_existing_asset_info(&project_id_clone) {
+            Ok(map) => {
+                if map.is_empty() {
+                    None
+                } else {
+                    tracing::info!("Found {} existing assets for change detection", map.len());
+                    Some(map)
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch existing assets for change detection: {}", e);
+                None
+            }
+        };
+
+        let excluded = db.get_excluded_paths(&project_id_clone).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read excluded assets: {}", e);
+            Default::default()
+        });
+
+        let directories = DirectoryMtimes::new(
+            db.get_directory_mtimes(&project_id_clone).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read directory mtimes: {}", e);
+                Default::default()
+            }),
+            directory_change_detection,
+        );
+
+        // Pick up where a crashed, killed or cancelled scan left off
+        let checkpoint = match db.get_scan_checkpoint(&project_id_clone) {
+            Ok(Some(_)) if safe_mode => None,
+            Ok(Some(cp)) if chrono::Utc::now().timestamp() - cp.updated_at < MAX_CHECKPOINT_AGE_SECS => {
+                tracing::info!(
+                    "Resuming scan from checkpoint: {} of {} files indexed, phase {}",
+                    cp.indexed_count,
+                    cp.total_files,
+                    cp.phase
+                );
+                Some(cp)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Failed to read scan checkpoint: {}", e);
+                None
+            }
+        };
+        let resumed = checkpoint.is_some();
+
+        // Phase 0: Count files first for accurate progress (a resumed scan reuses its count)
+        let total_files = match &checkpoint {
+            Some(cp) => cp.total_files,
+            None => {
+                let _timer = metrics.time("scan.counting");
+                progress.emit("counting", 0, None);
+
+                let cancel_flag_count = Arc::clone(&cancel_flag);
+                match count_scannable_files(
+                    Path::new(&root_path),
+                    WalkOptions {
+                        ignore_patterns: &ignore_patterns,
+                        threads: scan_threads,
+                        resume_after: None,
+                        plugins: plugins.as_deref(),
+                        excluded: Some(&excluded),
+                    },
+                    cancel_flag_count,
+                    |count| {
+                        progress.emit("counting", count, None);
+                        progress.wait_while_paused();
+                    },
+                ) {
+                    Ok(count) => count,
+                    Err(e) => {
+                        tracing::error!("Failed to count files: {}", e);
+                        0
+                    }
+                }
+            }
+        };
+
+        // Check if cancelled during counting
+        if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+            progress.cancel(0, None, ProgressExtra::default());
+            return;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let mut scan_checkpoint = checkpoint.unwrap_or_else(|| ScanCheckpoint {
+            project_id: project_id_clone.clone(),
+            mode: mode.clone(),
+            phase: "indexing".to_string(),
+            total_files,
+            indexed_count: 0,
+            last_path: None,
+            started_at: now,
+            updated_at: now,
+        });
+        if let Err(e) = db.save_scan_checkpoint(&scan_checkpoint) {
+            tracing::warn!("Failed to save scan checkpoint: {}", e);
+        }
+
+        // Phase 1: Scan and index files, skipping what the checkpoint says is done
+        let resume_offset = scan_checkpoint.indexed_count;
+        let resume_after = scan_checkpoint.last_path.clone().map(PathBuf::from);
+        progress.emit("indexing", resume_offset, Some(total_files));
+
+        let cancel_flag_scan = Arc::clone(&cancel_flag);
+        let mut final_stats = ScanStats::default();
+        // A resume must not skip the files of a batch that failed to index
+        let mut batch_failed = false;
+        let total = if scan_checkpoint.phase != "indexing" {
+            Ok((0, ScanStats::default()))
+        } else {
+            let _timer = metrics.time("scan.indexing");
+            scan_files_batch(
+                Path::new(&root_path),
+                &project_id_clone,
+                WalkOptions {
+                    ignore_patterns: &ignore_patterns,
+                    threads: scan_threads,
+                    resume_after: resume_after.as_deref(),
+                    plugins: plugins.as_deref(),
+                    excluded: Some(&excluded),
+                },
+                batch_size,
+                cancel_flag_scan,
+                // Always passed so a first scan records directory mtimes too
+                Some(ChangeDetection {
+                    existing: existing_assets.as_ref().unwrap_or(&ExistingAssetMap::new()),
+                    skip_unchanged,
+                    directories: Some(&directories),
+                }),
+                |batch, walked, current_path| {
+                    let last_path = batch.last().map(|a| a.relative_path.clone());
+
+                    // Index the batch, then record it as done
+                    match indexer.upsert_batch(&batch) {
+                        Ok(_) => {
+                            changes.assets_indexed(&batch, existing_assets.as_ref());
+                            if !batch_failed {
+                                scan_checkpoint.indexed_count = resume_offset + walked;
+                                scan_checkpoint.last_path = last_path;
+                                scan_checkpoint.updated_at = chrono::Utc::now().timestamp();
+                                if let Err(e) = db.save_scan_checkpoint(&scan_checkpoint) {
+                                    tracing::warn!("Failed to save scan checkpoint: {}", e);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to index batch: {}", e);
+                            batch_failed = true;
+                        }
+                    }
+
+                    progress.emit_with(
+                        "indexing",
+                        resume_offset + walked,
+                        Some(total_files),
+                        ProgressExtra {
+                            current_path: Some(current_path.to_string()),
+                            ..Default::default()
+                        },
+                    );
+
+                    // Signal frontend to refresh at most once per refresh interval
+                    changes.emit_if_due();
+
+                    // Return true to continue, false to stop
+                    progress.wait_while_paused()
+                },
+            )
+        };
+
+        // Extract stats from scan result
+        if let Ok((_, stats)) = &total {
+            final_stats = *stats;
+            tracing::info!(
+                "{} scan complete: {} total files, {} unchanged (skipped), {} new, {} changed",
+                mode,
+                stats.total_files,
+                stats.unchanged_skipped,
+                stats.new_files,
+                stats.changed_files
+            );
+        }
+
+        // Check if cancelled during indexing (the checkpoint is kept for the next scan)
+        if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+            progress.cancel(0, None, ProgressExtra::default());
+            return;
+        }
+
+        // A resumed walk only saw the directories after its checkpoint
+        if total.is_ok() && !resumed {
+            if let Err(e) = db.replace_directory_mtimes(&project_id_clone, &directories.into_current()) {
+                tracing::warn!("Failed to save directory mtimes: {}", e);
+            }
+        }
+
+        let file_count = (resume_offset + final_stats.total_files) as i64;
+
+        scan_checkpoint.phase = "dependencies".to_string();
+        scan_checkpoint.indexed_count = file_count as usize;
+        scan_checkpoint.updated_at = chrono::Utc::now().timestamp();
+        if let Err(e) = db.save_scan_checkpoint(&scan_checkpoint) {
+            tracing::warn!("Failed to save scan checkpoint: {}", e);
+        }
+
+        // Signal final asset update
+        changes.emit();
+
+        let stats_extra = ProgressExtra {
+            current_path: None,
+            skipped: Some(final_stats.unchanged_skipped),
+            added: Some(final_stats.new_files),
+            changed: Some(final_stats.changed_files),
+        };
+
+        if unity_project {
+            // Phase 2: Resolve dependencies with progress
+            progress.emit_with("dependencies", 0, None, stats_extra.clone());
+
+            let mut dep_resolver =
+                DependencyResolver::new(Arc::clone(&db_clone)).with_max_file_bytes(dependency_max_file_bytes);
+            if let Some(plugins) = &plugins {
+                dep_resolver = dep_resolver.with_plugins(Arc::clone(plugins));
+            }
+            let cancel_flag_deps = Arc::clone(&cancel_flag);
+            let deps_timer = metrics.time("scan.dependencies");
+            if let Err(e) = dep_resolver.resolve_all_for_project_with_progress(
+                &project_id_clone,
+                dependency_threads,
+                cancel_flag_deps,
+                |processed, total| {
+                    progress.emit_with("dependencies", processed, Some(total), stats_extra.clone());
+                    progress.wait_while_paused();
+                },
+            ) {
+                tracing::error!("Failed to resolve dependencies: {}", e);
+            }
+            drop(deps_timer);
+
+            // Check if cancelled during dependencies
+            if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+                progress.cancel(0, None, stats_extra);
+                return;
+            }
+
+            // Phase 3: Texture size estimates for textures or .meta files that changed
+            progress.emit_with("estimating", 0, None, stats_extra.clone());
+            let estimate_timer = metrics.time("scan.texture_estimates");
+            if let Err(e) = texture_size::estimate_project(&db_clone, &project_id_clone, |done, total| {
+                progress.emit_with("estimating", done, Some(total), stats_extra.clone());
+                progress.wait_while_paused()
+            }) {
+                tracing::error!("Failed to estimate texture sizes: {}", e);
+            }
+            drop(estimate_timer);
+
+            if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+                progress.cancel(0, None, stats_extra);
+                return;
+            }
+
+            // Phase 4: Localization tables
+            progress.emit_with("localizing", 0, None, stats_extra.clone());
+            let localization_timer = metrics.time("scan.localization");
+            if let Err(e) = localization::index_project(&db_clone, &project_id_clone, |done, total| {
+                progress.emit_with("localizing", done, Some(total), stats_extra.clone());
+                progress.wait_while_paused()
+            }) {
+                tracing::error!("Failed to index localization tables: {}", e);
+            }
+            drop(localization_timer);
+
+            if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+                progress.cancel(0, None, stats_extra);
+                return;
+            }
+        }
+
+        // Phase 5: Validation rules, when the user has written any
+        let mut violations = None;
+        if rules::has_rules(&rules_dir) {
+            progress.emit_with("validating", 0, None, stats_extra.clone());
+            let _validation_timer = metrics.time("scan.validation");
+            match rules::run_rules(&db_clone, &project_id_clone, &rules_dir, |checked, total| {
+                progress.emit_with("validating", checked, Some(total), stats_extra.clone());
+                progress.wait_while_paused();
+                !cancel_flag.load(std::sync::atomic::Ordering::SeqCst)
+            }) {
+                Ok(summary) => {
+                    for error in &summary.errors {
+                        tracing::warn!("Rule {}: {}", error.rule, error.message);
+                    }
+                    violations = Some(summary.violations);
+                }
+                Err(e) => tracing::error!("Failed to run validation rules: {}", e),
+            }
+
+            if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
+                progress.cancel(0, None, stats_extra);
+                return;
+            }
+        }
+
+        // Update project scan time
+        if let Err(e) = db_clone.update_project_scan_time(&project_id_clone, file_count) {
+            tracing::error!("Failed to update project scan time: {}", e);
+        }
+        if let Err(e) = db_clone.record_scan_totals(&project_id_clone, &mode) {
+            tracing::warn!("Failed to record scan totals: {}", e);
+        }
+        if let Err(e) = db_clone.delete_scan_checkpoint(&project_id_clone) {
+            tracing::warn!("Failed to clear scan checkpoint: {}", e);
+        }
+
+        // Anonymous project shape: counts and sizes per type, no paths
+        if telemetry.is_enabled() {
+            let type_counts = db_clone.get_type_counts(&project_id_clone, None).unwrap_or_default();
+            telemetry.record(
+                "scan",
+                serde_json::json!({
+                    "mode": mode,
+                    "duration_ms": scan_started.elapsed().as_millis() as u64,
+                    "total_files": final_stats.total_files,
+                    "added": final_stats.new_files,
+                    "changed": final_stats.changed_files,
+                    "skipped": final_stats.unchanged_skipped,
+                    "scan_threads": scan_threads,
+                    "dependency_threads": dependency_threads,
+                    "types": type_counts
+                        .iter()
+                        .map(|t| (t.asset_type.clone(), serde_json::json!({ "count": t.count, "bytes": t.total_bytes })))
+                        .collect::<serde_json::Map<_, _>>(),
+                }),
+            );
+        }
+
+        // Complete scan phase - thumbnails will be generated separately via regenerate_thumbnails
+        progress.complete(
+            file_count as usize,
+            Some(final_stats.total_files),
+            stats_extra,
+            Some(serde_json::json!({
+                "mode": mode,
+                "resumed": resumed,
+                "file_count": file_count,
+                "total_files": final_stats.total_files,
+                "skipped": final_stats.unchanged_skipped,
+                "added": final_stats.new_files,
+                "changed": final_stats.changed_files,
+                "violations": violations,
+            })),
+        );
+
+        // The first completed scan starts live indexing
+        if let Some(state) = app_handle.try_state::<AppState>() {
+            state.apply_watch_settings(&app_handle);
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Cancel the job `job_id`, or without one the job holding the scan lock
+#[tauri::command]
+pub async fn cancel_operation(job_id: Option<String>, state: State<'_, AppState>) -> Result<(), AppError> {
+    match job_id {
+        Some(job_id) if !state.jobs.request_cancel(&job_id) => {
+            Err(AppError::NotFound(format!("Job not found: {}", job_id)))
+        }
+        Some(_) => Ok(()),
+        None => {
+            state.request_cancel();
+            Ok(())
+        }
+    }
+}
+
+/// Pause the job `job_id` at its next checkpoint. Other running jobs carry on.
+#[tauri::command]
+pub async fn pause_operation(job_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    set_job_paused(&state, &job_id, true)
+}
+
+#[tauri::command]
+pub async fn resume_operation(job_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    set_job_paused(&state, &job_id, false)
+}
+
+fn set_job_paused(state: &AppState, job_id: &str, paused: bool) -> Result<(), AppError> {
+    if state.jobs.set_paused(job_id, paused) {
+        Ok(())
+    } else {
+        Err(AppError::NotFound(format!("Job not found: {}", job_id)))
+    }
+}
+
+#[tauri::command]
+pub async fn is_scan_running(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.is_scan_running())
+}
+
+#[tauri::command]
+pub async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<JobStatus>, AppError> {
+    Ok(state.jobs.list())
+}
+
+#[tauri::command]
+pub async fn get_job_status(
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<JobStatus, AppError> {
+    state
+        .jobs
+        .get(&job_id)
+        .ok_or_else(|| AppError::NotFound(format!("Job not found: {}", job_id)))
+}
+
+#[tauri::command]
+pub async fn get_diagnostics(state: State<'_, AppState>) -> Result<Diagnostics, AppError> {
+    diagnostics::collect(&state)
+}
+
+/// Libraries found in the plugins folder, including ones that failed to load
+#[tauri::command]
+pub async fn get_plugins(state: State<'_, AppState>) -> Result<Vec<PluginStatus>, AppError> {
+    Ok(state.plugins.statuses().to_vec())
+}
+
+#[tauri::command]
+pub async fn get_startup_status(state: State<'_, AppState>) -> Result<StartupStatus, AppError> {
+    Ok(state.startup_status())
+}
+
+/// Safe mode refuses thumbnail generation (the usual crash source, since it
+/// decodes arbitrary images) and makes scans ignore saved checkpoints
+#[tauri::command]
+pub async fn set_safe_mode(enabled: bool, state: State<'_, AppState>) -> Result<(), AppError> {
+    state.safe_mode.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    tracing::info!("Safe mode {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn check_database_integrity(state: State<'_, AppState>) -> Result<IntegrityReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let messages = tokio::task::spawn_blocking(move || db.integrity_check(INTEGRITY_CHECK_MAX_ERRORS))
+        .await
+        .map_err(|e| AppError::Custom(format!("Integrity check task failed: {}", e)))??;
+
+    if !messages.is_empty() {
+        tracing::error!("Database integrity check found {} problem(s)", messages.len());
+    }
+
+    Ok(IntegrityReport {
+        ok: messages.is_empty(),
+        messages,
+    })
+}
+
+/// Recent log lines at or above `level` ("error", "warn", "info"), newest first
+#[tauri::command]
+pub async fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, AppError> {
+    let level = match level.as_deref() {
+        Some(level) => level
+            .parse::<tracing::Level>()
+            .map_err(|_| AppError::InvalidArgument(format!("Unknown log level: {}", level)))?,
+        None => tracing::Level::INFO,
+    };
+
+    tokio::task::spawn_blocking(move || logging::recent_logs(level, limit.unwrap_or(200)))
+        .await
+        .map_err(|e| AppError::Custom(format!("Log read task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn get_performance_metrics(state: State<'_, AppState>) -> Result<PerformanceMetrics, AppError> {
+    Ok(state.metrics.snapshot())
+}
+
+#[tauri::command]
+pub async fn reset_performance_metrics(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.metrics.reset();
+    Ok(())
+}
+
+/// Time the standard suite (scans, dependencies, thumbnails, queries) on a
+/// project, reporting each step on `benchmark-progress`
+#[tauri::command]
+pub async fn run_benchmark(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BenchmarkReport, AppError> {
+    state.ensure_writable()?;
+    let project = state
+        .db
+        .get_project(&project_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Project {}", project_id)))?;
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let settings = state.settings.read().clone();
+    let plugins = state.active_plugins();
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let progress = ProgressReporter::with_cancel(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "benchmark",
+        "benchmark-progress",
+        Arc::clone(&cancel_flag),
+    );
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let result = benchmark::run(&db, &project, &settings, plugins, Arc::clone(&cancel_flag), |step, done, total| {
+            progress.emit(step, done, total);
+            progress.wait_while_paused()
+        });
+
+        match &result {
+            Ok(report) if report.completed => {
+                progress.complete(report.steps.len(), None, ProgressExtra::default(), serde_json::to_value(report).ok())
+            }
+            Ok(report) => progress.cancel(report.steps.len(), None, ProgressExtra::default()),
+            Err(e) => progress.fail(&e.to_string()),
+        }
+        result
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Benchmark task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn get_telemetry_events(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<TelemetryEvent>, AppError> {
+    state.telemetry.recent_events(limit.unwrap_or(100))
+}
+
+/// Copy the telemetry log somewhere the user can attach or upload it
+#[tauri::command]
+pub async fn export_telemetry(path: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    if !state.telemetry.path().exists() {
+        return Err(AppError::NotFound("No telemetry has been recorded".to_string()));
+    }
+    std::fs::copy(state.telemetry.path(), &path)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_telemetry(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.telemetry.clear()
+}
+
+// Each argument is a named field of the frontend's invoke payload
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn get_assets(
+    project_id: String,
+    search_query: Option<String>,
+    asset_types: Option<Vec<String>>,
+    page: i64,
+    page_size: i64,
+    vcs_filter: Option<String>,
+    sort: Option<String>,
+    cursor: Option<AssetCursor>,
+    delivery_filter: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AssetsResponse, AppError> {
+    let query = AssetQuery {
+        project_id,
+        search_query,
+        asset_types,
+        page,
+        page_size,
+        vcs_filter,
+        sort,
+        cursor,
+        delivery_filter,
+    };
+    query_assets(&state, &app_handle, query).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetSummariesResponse {
+    pub assets: Vec<AssetSummary>,
+    pub total: i64,
+    pub total_exact: bool,
+    pub next_cursor: Option<AssetCursor>,
+}
+
+/// `get_assets` returning only what the grid draws, for a much smaller payload
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn get_asset_summaries(
+    project_id: String,
+    search_query: Option<String>,
+    asset_types: Option<Vec<String>>,
+    page: i64,
+    page_size: i64,
+    vcs_filter: Option<String>,
+    sort: Option<String>,
+    cursor: Option<AssetCursor>,
+    delivery_filter: Option<String>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AssetSummariesResponse, AppError> {
+    let query = AssetQuery {
+        project_id,
+        search_query,
+        asset_types,
+        page,
+        page_size,
+        vcs_filter,
+        sort,
+        cursor,
+        delivery_filter,
+    };
+    let response = query_assets(&state, &app_handle, query).await?;
+    Ok(AssetSummariesResponse {
+        assets: response.assets.iter().map(AssetSummary::from).collect(),
+        total: response.total,
+        total_exact: response.total_exact,
+        next_cursor: response.next_cursor,
+    })
+}
+
+/// Arguments shared by `get_assets` and `get_asset_summaries`
+struct AssetQuery {
+    project_id: String,
+    search_query: Option<String>,
+    asset_types: Option<Vec<String>>,
+    page: i64,
+    page_size: i64,
+    vcs_filter: Option<String>,
+    sort: Option<String>,
+    cursor: Option<AssetCursor>,
+    delivery_filter: Option<String>,
+}
+
+async fn query_assets(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    query: AssetQuery,
+) -> Result<AssetsResponse, AppError> {
+    let AssetQuery { project_id, search_query, asset_types, page, page_size, vcs_filter, sort, cursor, delivery_filter } =
+        query;
+    let vcs_filter = vcs_filter.as_deref().map(VcsFilter::parse).transpose()?;
+    let delivery_filter = delivery_filter.as_deref().map(DeliveryFilter::parse).transpose()?;
+    let sort = sort.as_deref().map(AssetSort::parse).transpose()?.unwrap_or_default();
+    let project = retry_busy(|| state.db.get_project(&project_id))?;
+    let db = Arc::clone(&state.db);
+
+    // A new search (not a further page of one) is kept for suggestions
+    if let Some(query) = search_query.clone().filter(|q| !q.trim().is_empty() && page == 0 && cursor.is_none()) {
+        if !db.is_read_only() {
+            let db = Arc::clone(&db);
+            let project_id = project_id.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = db.record_search_query(&project_id, &query) {
+                    tracing::debug!("Search not recorded: {}", e);
+                }
+            });
+        }
+    }
+
+    // Past the threshold an exact COUNT and a deep OFFSET each take seconds,
+    // so pages are fetched by cursor and the count is deferred. Filtering by
+    // version control status or delivery happens in memory and always
+    // counts exactly.
+    let threshold = state.settings.read().large_project_threshold;
+    let large = threshold > 0 && project.as_ref().is_some_and(|p| p.file_count.max(0) as u64 >= threshold);
+    if vcs_filter.is_none() && delivery_filter.is_none() && (large || cursor.is_some()) {
+        if page > 0 && cursor.is_none() {
+            return Err(AppError::InvalidArgument(
+                "Large projects are paged by cursor; pass next_cursor from the previous page".to_string(),
+            ));
+        }
+        if cursor.is_none() {
+            schedule_asset_count(app_handle.clone(), Arc::clone(&db), &project_id, &search_query, &asset_types);
+        }
+        let unfiltered = search_query.as_deref().is_none_or(|q| q.trim().is_empty())
+            && asset_types.as_ref().is_none_or(|t| t.is_empty());
+        let file_count = project.as_ref().map_or(0, |p| p.file_count);
+
+        return tokio::task::spawn_blocking(move || {
+            let (mut assets, next_cursor) = retry_busy(|| {
+                db.get_assets_after(
+                    &project_id,
+                    search_query.as_deref(),
+                    asset_types.as_deref(),
+                    sort,
+                    cursor.as_ref(),
+                    page_size,
+                )
+            })?;
+            if let Some(vcs) = project.and_then(|p| Vcs::discover(Path::new(&p.root_path))) {
+                if let Err(e) = vcs
+                    .annotate(&mut assets)
+                    .and_then(|_| vcs.annotate_details(&mut assets))
+                {
+                    tracing::warn!("Failed to read version control status: {}", e);
+                }
+            }
+            annotate_deliveries(&db, &project_id, &mut assets);
+            // The last scan's file count for the whole project; otherwise
+            // only that this page exists and whether another follows
+            let total = if unfiltered {
+                file_count
+            } else {
+                assets.len() as i64 + next_cursor.is_some() as i64
+            };
+            Ok(AssetsResponse { assets, total, total_exact: false, next_cursor })
+        })
+        .await
+        .map_err(|e| AppError::Custom(format!("Asset query failed: {}", e)))?;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let vcs = project.and_then(|p| Vcs::discover(Path::new(&p.root_path)));
+
+        if vcs_filter.is_none() && delivery_filter.is_none() {
+            let (mut assets, total) = retry_busy(|| {
+                db.get_assets(
+                    &project_id,
+                    search_query.as_deref(),
+                    asset_types.as_deref(),
+                    sort,
+                    page,
+                    page_size,
+                )
+            })?;
+            if let Some(vcs) = vcs {
+                // Annotations are extra; a broken repository or missing `cm` must not hide the list
+                if let Err(e) = vcs
+                    .annotate(&mut assets)
+                    .and_then(|_| vcs.annotate_details(&mut assets))
+                {
+                    tracing::warn!("Failed to read version control status: {}", e);
+                }
+            }
+            annotate_deliveries(&db, &project_id, &mut assets);
+            return Ok(AssetsResponse { assets, total, total_exact: true, next_cursor: None });
+        }
+
+        // Nothing is changed in a project outside version control
+        if vcs_filter.is_some() && vcs.is_none() {
+            return Ok(AssetsResponse { assets: Vec::new(), total: 0, total_exact: true, next_cursor: None });
+        }
+
+        // VCS and delivery state aren't in the asset query, so filter the
+        // whole result and page it here
+        let (mut assets, _) = retry_busy(|| {
+            db.get_assets(
+                &project_id,
+                search_query.as_deref(),
+                asset_types.as_deref(),
+                sort,
+                0,
+                i64::MAX,
+            )
+        })?;
+        if let (Some(filter), Some(vcs)) = (vcs_filter, &vcs) {
+            vcs.annotate(&mut assets)?;
+            assets.retain(|a| a.vcs.as_ref().is_some_and(|v| filter.matches(v)));
+        }
+        if let Some(filter) = delivery_filter {
+            deliveries::annotate(&db, &project_id, &mut assets)?;
+            assets.retain(|a| filter.matches(a.delivery.as_ref()));
+        }
+
+        let total = assets.len() as i64;
+        let start = (page.max(0) * page_size).clamp(0, total) as usize;
+        let end = (start as i64 + page_size.max(0)).min(total) as usize;
+        let mut assets: Vec<Asset> = assets.drain(start..end).collect();
+        if let Some(vcs) = vcs {
+            // Without a VCS filter the page wasn't annotated yet
+            let annotated = if vcs_filter.is_some() { Ok(()) } else { vcs.annotate(&mut assets) };
+            if let Err(e) = annotated.and_then(|_| vcs.annotate_details(&mut assets)) {
+                tracing::warn!("Failed to read version control details: {}", e);
+            }
+        }
+        if delivery_filter.is_none() {
+            annotate_deliveries(&db, &project_id, &mut assets);
+        }
+        Ok(AssetsResponse { assets, total, total_exact: true, next_cursor: None })
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Asset query failed: {}", e)))?
+}
+
+/// Delivered badges are extra; a failed lookup must not hide the list
+fn annotate_deliveries(db: &Database, project_id: &str, assets: &mut [Asset]) {
+    if let Err(e) = deliveries::annotate(db, project_id, assets) {
+        tracing::warn!("Failed to read deliveries: {}", e);
+    }
+}
+
+/// Count matches for a large-project query in the background and emit
+/// `assets-count`, unless a newer query is scheduled before it finishes
+fn schedule_asset_count(
+    app_handle: tauri::AppHandle,
+    db: Arc<Database>,
+    project_id: &str,
+    search_query: &Option<String>,
+    asset_types: &Option<Vec<String>>,
+) {
+    let generation = ASSET_COUNT_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let is_current = move || ASSET_COUNT_GENERATION.load(Ordering::SeqCst) == generation;
+    let mut event = AssetsCountEvent {
+        project_id: project_id.to_string(),
+        search_query: search_query.clone(),
+        asset_types: asset_types.clone(),
+        total: 0,
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(ASSET_COUNT_DEBOUNCE).await;
+        if !is_current() {
+            return;
+        }
+        let query = (event.project_id.clone(), event.search_query.clone(), event.asset_types.clone());
+        let counted = tokio::task::spawn_blocking(move || {
+            let (project_id, search_query, asset_types) = query;
+            db.get_assets(&project_id, search_query.as_deref(), asset_types.as_deref(), AssetSort::Name, 0, 0)
+        })
+        .await;
+        match counted {
+            Ok(Ok((_, total))) if is_current() => {
+                event.total = total;
+                let _ = app_handle.emit("assets-count", event);
+            }
+            Ok(Err(e)) => tracing::warn!("Failed to count assets: {}", e),
+            Err(e) => tracing::warn!("Asset count task failed: {}", e),
+            _ => {}
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn search_in_files(
+    project_id: String,
+    pattern: String,
+    types: Option<Vec<String>>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<FileSearchResult, AppError> {
+    let db = Arc::clone(&state.db);
+    let options = FileSearchOptions {
+        regex: regex.unwrap_or(false),
+        case_sensitive: case_sensitive.unwrap_or(false),
+        types,
+    };
+
+    tokio::task::spawn_blocking(move || {
+        search::search_in_files(&db, &project_id, &pattern, &options)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Search task failed: {}", e)))?
+}
+
+/// Completions for the asset search box from recent searches and the
+/// indexed file names, folders and terms
+#[tauri::command]
+pub async fn get_search_suggestions(
+    project_id: String,
+    prefix: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchSuggestion>, AppError> {
+    let db = Arc::clone(&state.db);
+
+    tokio::task::spawn_blocking(move || {
+        search::search_suggestions(&db, &project_id, &prefix, limit.unwrap_or(search::DEFAULT_SUGGESTIONS))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Suggestion task failed: {}", e)))?
+}
+
+/// Write the filtered asset table (paths, types, sizes, GUIDs, hashes and
+/// dependency counts) to a CSV or JSON file
+#[tauri::command]
+pub async fn export_asset_list(
+    project_id: String,
+    filters: Option<AssetListFilter>,
+    format: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<AssetListExport, AppError> {
+    let db = Arc::clone(&state.db);
+    let filters = filters.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        catalog::export_asset_list(&db, &project_id, &filters, &format, Path::new(&path))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Asset list export failed: {}", e)))?
+}
+
+/// Write the source/license/author fields of a selection, or of a built
+/// bundle, to an attribution CSV for a release
+#[tauri::command]
+pub async fn export_attribution_csv(
+    project_id: String,
+    asset_ids: Option<Vec<String>>,
+    bundle: Option<String>,
+    include_dependencies: Option<bool>,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<AttributionExport, AppError> {
+    let db = Arc::clone(&state.db);
+
+    tokio::task::spawn_blocking(move || {
+        catalog::export_attribution_csv(
+            &db,
+            &project_id,
+            asset_ids,
+            bundle.as_deref(),
+            include_dependencies.unwrap_or(false),
+            Path::new(&path),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Attribution export failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn get_asset(id: String, state: State<'_, AppState>) -> Result<Asset, AppError> {
+    let mut asset = retry_busy(|| state.db.get_asset(&id))?.ok_or_else(|| AppError::AssetNotFound(id))?;
+    let project = retry_busy(|| state.db.get_project(&asset.project_id))?;
+    let db = Arc::clone(&state.db);
+
+    tokio::task::spawn_blocking(move || {
+        let project_id = asset.project_id.clone();
+        annotate_deliveries(&db, &project_id, std::slice::from_mut(&mut asset));
+        if let Some(vcs) = project.and_then(|p| Vcs::discover(Path::new(&p.root_path))) {
+            let assets = std::slice::from_mut(&mut asset);
+            if let Err(e) = vcs.annotate(assets).and_then(|_| vcs.annotate_details(assets)) {
+                tracing::warn!("Failed to read version control status: {}", e);
+            }
+        }
+        asset
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Asset query failed: {}", e)))
+}
+
+/// Repository the project is in, or None when it isn't under version control
+#[tauri::command]
+pub async fn get_vcs_info(project_id: String, state: State<'_, AppState>) -> Result<Option<VcsRepoInfo>, AppError> {
+    let project = retry_busy(|| state.db.get_project(&project_id))?
+        .ok_or_else(|| AppError::NotFound(format!("Project not found: {}", project_id)))?;
+
+    tokio::task::spawn_blocking(move || Vcs::discover(Path::new(&project.root_path)).map(|vcs| vcs.info()))
+        .await
+        .map_err(|e| AppError::Custom(format!("VCS query failed: {}", e)))
+}
+
+#[tauri::command]
+pub async fn get_dependencies(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Dependency>, AppError> {
+    retry_busy(|| state.db.get_dependencies(&asset_id))
+}
+
+#[tauri::command]
+pub async fn get_dependents(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Dependency>, AppError> {
+    retry_busy(|| state.db.get_dependents(&asset_id))
+}
+
+#[tauri::command]
+pub async fn get_dependency_graph(
+    asset_id: String,
+    depth: Option<usize>,
+    direction: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<DependencyGraph, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let direction = graph_direction(direction)?;
+
+    let db = Arc::clone(&state.db);
+    tokio::task::spawn_blocking(move || {
+        let dep_resolver = DependencyResolver::new(db);
+        dep_resolver.get_dependency_graph(&asset, depth.unwrap_or(2).min(10), &direction)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Graph task failed: {}", e)))?
+}
+
+/// Write an asset's dependency graph as a Graphviz DOT file
+#[tauri::command]
+pub async fn export_dependency_dot(
+    asset_id: String,
+    path: String,
+    depth: Option<usize>,
+    direction: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<GraphExport, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let direction = graph_direction(direction)?;
+
+    let db = Arc::clone(&state.db);
+    tokio::task::spawn_blocking(move || {
+        let dep_resolver = DependencyResolver::new(db);
+        let graph = dep_resolver.get_dependency_graph(&asset, depth.unwrap_or(2).min(10), &direction)?;
+        catalog::write_dependency_dot(&graph, Path::new(&path))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Graph export failed: {}", e)))?
+}
+
+/// "dependencies" (default), "dependents" or "both"
+fn graph_direction(direction: Option<String>) -> Result<String, AppError> {
+    let direction = direction.unwrap_or_else(|| "dependencies".to_string());
+    if !matches!(direction.as_str(), "dependencies" | "dependents" | "both") {
+        return Err(AppError::InvalidArgument(format!("Unknown graph direction: {}", direction)));
+    }
+    Ok(direction)
+}
+
+/// Per-type totals after each scan that changed them, oldest first, for
+/// charting how the project grows
+#[tauri::command]
+pub async fn get_project_growth(
+    project_id: String,
+    since: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GrowthPoint>, AppError> {
+    retry_busy(|| state.db.get_project_growth(&project_id, since))
+}
+
+#[tauri::command]
+pub async fn get_type_counts(
+    project_id: String,
+    path_prefix: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<TypeCount>, AppError> {
+    if path_prefix.is_none() {
+        if let Some(counts) = state.take_warm_type_counts(&project_id) {
+            return Ok(counts);
+        }
+    }
+    retry_busy(|| state.db.get_type_counts(&project_id, path_prefix.as_deref()))
+}
+
+/// Asset counts and sizes per folder two levels deep, largest first
+#[tauri::command]
+pub async fn get_folder_rollups(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<FolderRollup>, AppError> {
+    if let Some(folders) = state.take_warm_folder_rollups(&project_id) {
+        return Ok(folders);
+    }
+    retry_busy(|| state.db.get_folder_rollups(&project_id))
+}
+
+#[tauri::command]
+pub async fn export_file(
+    asset_id: String,
+    dest_folder: String,
+    options: Option<ExportOverrides>,
+    state: State<'_, AppState>,
+) -> Result<ExportResult, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let options = state.settings.read().export_defaults.with_overrides(options)?;
+
+    let _timer = state.metrics.time("export.file");
+    let exporter = Exporter::new(Arc::clone(&state.db));
+    exporter.export_file(&asset, Path::new(&dest_folder), &options)
+}
+
+/// Where a project's assets, or one asset, were exported to, newest first
+#[tauri::command]
+pub async fn get_deliveries(
+    project_id: String,
+    asset_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Delivery>, AppError> {
+    retry_busy(|| state.db.get_deliveries(&project_id, asset_id.as_deref()))
+}
+
+/// Where a project's files copied in from a library or the Asset Store came
+/// from, or one asset's, newest first
+#[tauri::command]
+pub async fn get_provenance(
+    project_id: String,
+    asset_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Provenance>, AppError> {
+    retry_busy(|| state.db.get_provenance(&project_id, asset_id.as_deref()))
+}
+
+/// Compare files copied in from libraries and the Asset Store with their
+/// sources and list those with a newer version upstream
+#[tauri::command]
+pub async fn check_source_updates(project_id: String, state: State<'_, AppState>) -> Result<UpdateCheck, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("provenance.check_updates");
+        provenance::check_updates(&db, &project_id)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Update check failed: {}", e)))?
+}
+
+/// Replace a copied-in file with its source's current version, keeping its .meta
+#[tauri::command]
+pub async fn reimport_from_source(
+    project_id: String,
+    relative_path: String,
+    state: State<'_, AppState>,
+) -> Result<Provenance, AppError> {
+    state.ensure_writable()?;
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("provenance.reimport");
+        provenance::reimport(&db, &project_id, &relative_path)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Re-import failed: {}", e)))?
+}
+
+/// Check that export destinations still hold the versions delivered there
+/// by hashing them, for a project or one asset
+#[tauri::command]
+pub async fn verify_deliveries(
+    project_id: String,
+    asset_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<DeliveryCheck, AppError> {
+    state.ensure_writable()?;
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.verify_deliveries");
+        deliveries::verify_deliveries(&db, &project_id, asset_id.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Delivery check failed: {}", e)))?
+}
+
+/// Whether a file from outside the project is already in it, byte for
+/// byte or (for images) as a resized or re-encoded copy
+#[tauri::command]
+pub async fn find_external_file(
+    path: String,
+    project_id: String,
+    max_distance: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<ExternalFileReport, AppError> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(AppError::NotFound(format!("File not found: {}", path.display())));
+    }
+    let db = Arc::clone(&state.db);
+    let (thumbnail_options, threads) = {
+        let settings = state.settings.read();
+        (settings.thumbnail_options(), settings.effective_thumbnail_threads())
+    };
+    let max_distance = max_distance.unwrap_or(duplicates::DEFAULT_MAX_DISTANCE);
+
+    tokio::task::spawn_blocking(move || {
+        duplicates::find_external_file(&db, &project_id, &path, &thumbnail_options, threads, max_distance)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("File lookup task failed: {}", e)))?
+}
+
+/// Assets of the same type the detail pane suggests as related or substitutes
+#[tauri::command]
+pub async fn get_similar_assets(
+    asset_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SimilarAsset>, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let (thumbnail_options, threads) = {
+        let settings = state.settings.read();
+        (settings.thumbnail_options(), settings.effective_thumbnail_threads())
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("similar.assets");
+        similar::similar_assets(
+            &db,
+            &asset_id,
+            &thumbnail_options,
+            threads,
+            limit.unwrap_or(similar::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Similar assets task failed: {}", e)))?
+}
+
+/// Copy assets into a fresh temp folder so the frontend can drag
+/// them out of the app; each asset's `.meta` file comes along
+#[tauri::command]
+pub async fn stage_assets_for_drag(
+    asset_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<DragStage, AppError> {
+    if asset_ids.is_empty() {
+        return Err(AppError::InvalidArgument("No assets to stage".to_string()));
+    }
+    let db = Arc::clone(&state.db);
+
+    tokio::task::spawn_blocking(move || {
+        let assets = asset_ids
+            .into_iter()
+            .map(|id| db.get_asset(&id)?.ok_or(AppError::AssetNotFound(id)))
+            .collect::<Result<Vec<_>, _>>()?;
+        export::stage_for_drag(&assets, &storage::drag_staging_dir())
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Drag staging task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn export_bundle(
+    asset_id: String,
+    dest_folder: String,
+    options: Option<ExportOverrides>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let options = state.settings.read().export_defaults.with_overrides(options)?;
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::new(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "export",
+        "export-progress",
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.bundle");
+        progress.emit("exporting", 0, None);
+
+        let exporter = Exporter::new(db);
+        match exporter.export_bundle(&asset, Path::new(&dest_folder), &options) {
+            Ok(result) => {
+                let count = result.exported_files.len();
+                progress.complete(
+                    count,
+                    Some(count),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Bundle export failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Export an asset and its dependency tree as a .unitypackage in `dest_folder`
+#[tauri::command]
+pub async fn export_unitypackage(
+    asset_id: String,
+    dest_folder: String,
+    options: Option<ExportOverrides>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let overrides = ExportOverrides {
+        archive_format: Some("unitypackage".to_string()),
+        ..options.unwrap_or_default()
+    };
+    let options = state.settings.read().export_defaults.with_overrides(Some(overrides))?;
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::new(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "export",
+        "export-progress",
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.unitypackage");
+        progress.emit("exporting", 0, None);
+
+        let exporter = Exporter::new(db);
+        match exporter.export_bundle(&asset, Path::new(&dest_folder), &options) {
+            Ok(result) => {
+                let count = result.exported_files.len();
+                progress.complete(
+                    count,
+                    Some(count),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Unity package export failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Copy library assets into the active project, under `dest_folder`
+/// (relative to the project) or `Assets/<library name>`, generating a
+/// `.meta` for each file that has none
+#[tauri::command]
+pub async fn copy_from_library(
+    asset_ids: Vec<String>,
+    dest_folder: Option<String>,
+    options: Option<ExportOverrides>,
+    state: State<'_, AppState>,
+) -> Result<LibraryCopy, AppError> {
+    state.ensure_writable()?;
+    let (project_root, library_roots, options) = {
+        let settings = state.settings.read();
+        let project_root = settings
+            .project_root
+            .clone()
+            .ok_or_else(|| AppError::InvalidArgument("No active project to copy into".to_string()))?;
+        (
+            project_root,
+            settings.library_roots.clone(),
+            settings.export_defaults.with_overrides(options)?,
+        )
+    };
+
+    let mut assets = Vec::with_capacity(asset_ids.len());
+    for asset_id in asset_ids {
+        let asset = state
+            .db
+            .get_asset(&asset_id)?
+            .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+        assets.push(asset);
+    }
+    let Some(first) = assets.first() else {
+        return Err(AppError::InvalidArgument("No assets to copy".to_string()));
+    };
+    if assets.iter().any(|a| a.project_id != first.project_id) {
+        return Err(AppError::InvalidArgument("Assets must come from one library".to_string()));
+    }
+    let library = state
+        .db
+        .get_project(&first.project_id)?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    if !library_roots.contains(&library.root_path) {
+        return Err(AppError::InvalidArgument(format!("{} is not a library", library.name)));
+    }
+    let project = state
+        .db
+        .get_project_by_path(&project_root)?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let dest_dir = library::destination(Path::new(&project_root), &library, dest_folder.as_deref())?;
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.library_copy");
+        library::copy_into_project(db, &library, &project, &assets, &dest_dir, &options)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Library copy failed: {}", e)))?
+}
+
+/// Write one Markdown note per asset (front matter plus wiki-links to
+/// dependencies) into a folder that can be opened as an Obsidian vault.
+/// Hand-written text under each note's "## Notes" heading is kept.
+#[tauri::command]
+pub async fn export_markdown_notes(
+    project_id: String,
+    dest_folder: String,
+    filters: Option<AssetListFilter>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let filters = filters.unwrap_or_default();
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::new(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "export",
+        "export-progress",
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.notes");
+        progress.emit("exporting", 0, None);
+
+        let result = catalog::export_markdown_notes(
+            &db,
+            &project_id,
+            &filters,
+            Path::new(&dest_folder),
+            |done, total| {
+                progress.emit("exporting", done, Some(total));
+                progress.wait_while_paused()
+            },
+        );
+
+        match result {
+            Ok(result) if !result.completed => {
+                progress.cancel(result.written + result.unchanged, Some(result.total), ProgressExtra::default());
+            }
+            Ok(result) => {
+                progress.complete(
+                    result.total,
+                    Some(result.total),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Markdown export failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Add or remove a tag, or set a metadata field, on `asset_ids` or else on
+/// every asset matching `filters`, in one transaction. Runs as a
+/// "bulk_edit" job; the result is a `BulkEditResult`.
+#[tauri::command]
+pub async fn bulk_edit_assets(
+    project_id: String,
+    edit: BulkEdit,
+    filters: Option<AssetListFilter>,
+    asset_ids: Option<Vec<String>>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.ensure_writable()?;
+    // Fail before starting a job the edit can't run
+    edit.normalized()?;
+    let filters = filters.unwrap_or_default();
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::new(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "bulk_edit",
+        "bulk-edit-progress",
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("tags.bulk_edit");
+        progress.emit("editing", 0, None);
+
+        let result = tags::bulk_edit(&db, &project_id, &filters, asset_ids, &edit, |done, total| {
+            progress.emit("editing", done, Some(total))
+        });
+
+        match result {
+            Ok(result) => {
+                progress.complete(
+                    result.matched,
+                    Some(result.matched),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Bulk edit failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub async fn get_asset_tags(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<AssetTags, AppError> {
+    state.db.get_asset_tags(&asset_id)
+}
+
+/// Tags used in the project, most used first
+#[tauri::command]
+pub async fn get_project_tags(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TagCount>, AppError> {
+    state.db.get_project_tags(&project_id)
+}
+
+/// Write the project's whole index (assets, dependencies, thumbnails, stats)
+/// to one portable snapshot file. Runs as an "export" job.
+#[tauri::command]
+pub async fn export_project_snapshot(
+    project_id: String,
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::new(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "export",
+        "export-progress",
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("export.snapshot");
+        progress.emit("exporting", 0, None);
+
+        let result = snapshot::export_snapshot(&db, &project_id, Path::new(&path), |done, total| {
+            progress.emit("exporting", done, Some(total));
+            progress.wait_while_paused()
+        });
+
+        match result {
+            Ok(result) if !result.completed => {
+                progress.cancel(0, Some(result.stats.asset_count), ProgressExtra::default());
+            }
+            Ok(result) => {
+                progress.complete(
+                    result.stats.asset_count,
+                    Some(result.stats.asset_count),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Snapshot export failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Project, creation time and stats of a snapshot file, without importing it
+#[tauri::command]
+pub async fn get_snapshot_info(path: String) -> Result<SnapshotManifest, AppError> {
+    tokio::task::spawn_blocking(move || snapshot::read_snapshot_manifest(Path::new(&path)))
+        .await
+        .map_err(|e| AppError::Custom(format!("Snapshot task failed: {}", e)))?
+}
+
+/// Import a snapshot as a browsable project and make it the current one.
+/// `root_path` defaults to the exporting machine's project root; an existing
+/// project there is only overwritten with `replace`. Runs as an "import" job.
+#[tauri::command]
+pub async fn import_project_snapshot(
+    path: String,
+    root_path: Option<String>,
+    replace: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.ensure_writable()?;
+
+    // Importing writes a whole project; keep scans out meanwhile
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let settings = Arc::clone(&state.settings);
+    let thumb_dir = state.thumbnail_dir()?;
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::with_cancel(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "import",
+        "import-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let _timer = metrics.time("import.snapshot");
+        progress.emit("importing", 0, None);
+
+        let result = snapshot::import_snapshot(
+            &db,
+            Path::new(&path),
+            &thumb_dir,
+            root_path.as_deref(),
+            replace.unwrap_or(false),
+            |done, total| {
+                progress.emit("importing", done, Some(total));
+                progress.wait_while_paused()
+            },
+        );
+
+        match result {
+            Ok(result) if result.completed => {
+                // The files usually aren't on this machine, so this skips
+                // the folder check in set_project_root
+                if let Some(project) = &result.project {
+                    let mut settings = settings.write();
+                    settings.activate_project(&project.root_path);
+                    if let Err(e) = settings.save() {
+                        tracing::warn!("Failed to save settings after import: {}", e);
+                    }
+                }
+                progress.complete(
+                    result.stats.asset_count,
+                    Some(result.stats.asset_count),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&result).ok(),
+                );
+            }
+            Ok(result) => {
+                progress.cancel(0, Some(result.stats.asset_count), ProgressExtra::default());
+            }
+            Err(e) => {
+                tracing::error!("Snapshot import failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub async fn reveal_in_explorer(path: String) -> Result<(), AppError> {
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer")
+            .args(["/select,", &path])
+            .spawn()
+            .map_err(|e| AppError::Io(e))?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .args(["-R", &path])
+            .spawn()
+            .map_err(|e| AppError::Io(e))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(Path::new(&path).parent().unwrap_or(Path::new(&path)))
+            .spawn()
+            .map_err(|e| AppError::Io(e))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn open_asset(asset_id: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let path = Path::new(&asset.absolute_path);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "File no longer exists on disk: {}",
+            asset.absolute_path
+        )));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Explorer opens a file with its default app. Going through
+        // `cmd /C start` would let cmd interpret `&`, `^` or `%VAR%` in a
+        // file name, and flashes a console window.
+        std::process::Command::new("explorer")
+            .arg(path)
+            .spawn()
+            .map_err(AppError::Io)?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(path)
+            .spawn()
+            .map_err(AppError::Io)?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .map_err(AppError::Io)?;
+    }
+
+    Ok(())
+}
+
+/// Open a text asset in the configured code editor at `line` and `column`
+/// (1-based), e.g. to jump from an in-file search hit into the code
+#[tauri::command]
+pub async fn open_in_code_editor(
+    asset_id: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let editor = state.settings.read().code_editor.clone().ok_or_else(|| {
+        AppError::InvalidArgument("No code editor configured; choose one in Settings".to_string())
+    })?;
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let path = Path::new(&asset.absolute_path);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "File no longer exists on disk: {}",
+            asset.absolute_path
+        )));
+    }
+
+    code_editor::open(&editor, path, line, column)
+}
+
+/// Example setups for VS Code, Rider, Cursor, Sublime Text and Notepad++
+/// to start `code_editor` from
+#[tauri::command]
+pub async fn get_code_editor_presets() -> Result<Vec<CodeEditor>, AppError> {
+    Ok(code_editor::presets())
+}
+
+/// Copy a reference to an asset to the system clipboard and return the copied text.
+/// `kind` is one of "absolute_path", "relative_path" or "guid".
+#[tauri::command]
+pub async fn copy_asset_reference(
+    asset_id: String,
+    kind: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let text = match kind.as_str() {
+        "absolute_path" => asset.absolute_path,
+        // Unity always uses forward slashes for project paths
+        "relative_path" => asset.relative_path.replace('\\', "/"),
+        "guid" => asset
+            .unity_guid
+            .ok_or_else(|| AppError::InvalidArgument("Asset has no Unity GUID".to_string()))?,
+        other => {
+            return Err(AppError::InvalidArgument(format!("Unknown reference kind: {}", other)));
+        }
+    };
+
+    app_handle
+        .clipboard()
+        .write_text(text.clone())
+        .map_err(|e| AppError::Custom(format!("Failed to write to clipboard: {}", e)))?;
+
+    Ok(text)
+}
+
+#[tauri::command]
+pub async fn get_material_info(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<MaterialInfo>, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    if asset.asset_type != "material" {
+        return Ok(None);
+    }
+
+    Ok(parse_material_file(Path::new(&asset.absolute_path)))
+}
+
+/// Metadata that plugins extract from the asset's format, one entry per plugin
+#[tauri::command]
+pub async fn get_plugin_metadata(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PluginMetadata>, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    let Some(plugins) = state.active_plugins() else {
+        return Ok(Vec::new());
+    };
+    tokio::task::spawn_blocking(move || plugins.extract_metadata(Path::new(&asset.absolute_path)))
+        .await
+        .map_err(|e| AppError::Custom(format!("Plugin task failed: {}", e)))
+}
+
+#[tauri::command]
+pub async fn get_validation_rules(state: State<'_, AppState>) -> Result<Vec<RuleInfo>, AppError> {
+    Ok(rules::list_rules(&storage::rules_dir(&state.app_data_dir)))
+}
+
+/// Create or replace a rule. The script is compiled first so syntax errors
+/// are reported here instead of on the next scan.
+#[tauri::command]
+pub async fn save_validation_rule(
+    name: String,
+    script: String,
+    state: State<'_, AppState>,
+) -> Result<RuleInfo, AppError> {
+    rules::save_rule(&storage::rules_dir(&state.app_data_dir), &name, &script)
+}
+
+#[tauri::command]
+pub async fn delete_validation_rule(name: String, state: State<'_, AppState>) -> Result<(), AppError> {
+    rules::delete_rule(&storage::rules_dir(&state.app_data_dir), &name)
+}
+
+/// Re-run the rules without scanning, e.g. after editing one
+#[tauri::command]
+pub async fn run_validation_rules(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<RuleRunSummary, AppError> {
+    state.ensure_writable()?;
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+
+    let db = Arc::clone(&state.db);
+    let rules_dir = storage::rules_dir(&state.app_data_dir);
+    let metrics = Arc::clone(&state.metrics);
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let _timer = metrics.time("rules.run");
+        rules::run_rules(&db, &project_id, &rules_dir, |_, _| true)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Validation task failed: {}", e)))?
+}
+
+/// Materials whose shaders wouldn't render after moving the project to
+/// `target` ("builtin", "urp" or "hdrp", default "urp"), with folder rollups
+#[tauri::command]
+pub async fn get_render_pipeline_audit(
+    project_id: String,
+    target: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<RenderPipelineReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("render_pipeline.audit");
+        render_pipeline::audit_project(
+            &db,
+            &project_id,
+            target.as_deref().unwrap_or("urp"),
+            limit.unwrap_or(render_pipeline::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Render pipeline audit failed: {}", e)))?
+}
+
+/// Diff two indexed projects, such as a fork and its origin: assets only in
+/// one of them and those in both that differ. `hash_content` (default on)
+/// hashes equal-size files to compare their bytes.
+#[tauri::command]
+pub async fn compare_projects(
+    project_a: String,
+    project_b: String,
+    hash_content: Option<bool>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<ProjectComparison, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let threads = state.settings.read().effective_thumbnail_threads();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("compare.projects");
+        compare::compare_projects(
+            &db,
+            &project_a,
+            &project_b,
+            hash_content.unwrap_or(true),
+            threads,
+            limit.unwrap_or(compare::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Comparison task failed: {}", e)))?
+}
+
+/// GUIDs that point at different content in different indexed projects,
+/// usually from a folder copied between projects with its .meta files
+#[tauri::command]
+pub async fn get_guid_collisions(
+    hash_content: Option<bool>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<GuidCollisionReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let threads = state.settings.read().effective_thumbnail_threads();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("compare.guid_collisions");
+        compare::guid_collisions(&db, hash_content.unwrap_or(true), threads, limit.unwrap_or(compare::DEFAULT_LIMIT))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("GUID collision check failed: {}", e)))?
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleViolationReport {
+    pub counts: Vec<RuleViolationCount>,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Violations stored by the last rule run, with per-rule totals
+#[tauri::command]
+pub async fn get_rule_violations(
+    project_id: String,
+    rule: Option<String>,
+    asset_id: Option<String>,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<RuleViolationReport, AppError> {
+    Ok(RuleViolationReport {
+        counts: state.db.get_rule_violation_counts(&project_id)?,
+        violations: state.db.get_rule_violations(
+            &project_id,
+            rule.as_deref(),
+            asset_id.as_deref(),
+            limit.unwrap_or(1000),
+        )?,
+    })
+}
+
+/// Duration, codec, bit rate and resolution of an audio or video asset from
+/// ffprobe, cached per file version. None when ffprobe isn't configured.
+#[tauri::command]
+pub async fn get_media_info(asset_id: String, state: State<'_, AppState>) -> Result<Option<MediaInfo>, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+
+    if !media::MEDIA_TYPES.contains(&asset.asset_type.as_str()) {
+        return Ok(None);
+    }
+    if let Some(info) = state.db.get_media_info(&asset.id, asset.modified_time)? {
+        return Ok(Some(info));
+    }
+    let Some(ffprobe) = state.settings.read().ffprobe_path.clone() else {
+        return Ok(None);
+    };
+
+    let db = Arc::clone(&state.db);
+    tokio::task::spawn_blocking(move || {
+        let info = media::probe(Path::new(&ffprobe), Path::new(&asset.absolute_path))?;
+        // A shared catalog can still be probed, just not cached
+        if !db.is_read_only() {
+            db.save_media_info(&asset.id, asset.modified_time, &info)?;
+        }
+        Ok(Some(info))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("ffprobe task failed: {}", e)))?
+}
+
+/// Probe every audio and video asset not probed since it last changed, then
+/// measure the levels of audio not yet measured when ffmpeg sits next to
+/// ffprobe. Returns a job id; progress is reported on `media-progress`.
+#[tauri::command]
+pub async fn probe_project_media(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.ensure_writable()?;
+    let ffprobe = state
+        .settings
+        .read()
+        .ffprobe_path
+        .clone()
+        .ok_or_else(|| AppError::InvalidArgument("Set the ffprobe location in settings first".to_string()))?;
+
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::with_cancel(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "media",
+        "media-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let _timer = metrics.time("media.probe");
+
+        let assets = match db.get_assets_needing_media_info(&project_id) {
+            Ok(assets) => assets,
+            Err(e) => {
+                progress.fail(&e.to_string());
+                return;
+            }
+        };
+        let total = assets.len();
+        let mut probed = 0;
+        let mut failed = 0;
+
+        for (i, asset) in assets.iter().enumerate() {
+            progress.emit("probing", i, Some(total));
+            if !progress.wait_while_paused() {
+                progress.cancel(i, Some(total), ProgressExtra::default());
+                return;
+            }
+
+            let saved = media::probe(Path::new(&ffprobe), Path::new(&asset.absolute_path))
+                .and_then(|info| db.save_media_info(&asset.id, asset.modified_time, &info));
+            match saved {
+                Ok(()) => probed += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to probe {}: {}", asset.relative_path, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        let mut analyzed = 0;
+        if let Some(ffmpeg) = media::ffmpeg_beside(Path::new(&ffprobe)) {
+            let assets = match db.get_assets_needing_audio_levels(&project_id) {
+                Ok(assets) => assets,
+                Err(e) => {
+                    progress.fail(&e.to_string());
+                    return;
+                }
+            };
+            let total = assets.len();
+
+            for (i, asset) in assets.iter().enumerate() {
+                progress.emit("analyzing", i, Some(total));
+                if !progress.wait_while_paused() {
+                    progress.cancel(i, Some(total), ProgressExtra::default());
+                    return;
+                }
+
+                let duration = db
+                    .get_media_info(&asset.id, asset.modified_time)
+                    .ok()
+                    .flatten()
+                    .and_then(|info| info.duration_secs);
+                let saved = media::analyze_levels(&ffmpeg, Path::new(&asset.absolute_path), duration)
+                    .and_then(|levels| db.save_audio_levels(&asset.id, asset.modified_time, &levels));
+                match saved {
+                    Ok(()) => analyzed += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to analyze levels of {}: {}", asset.relative_path, e);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+
+        progress.complete(
+            total,
+            Some(total),
+            ProgressExtra::default(),
+            Some(serde_json::json!({ "probed": probed, "analyzed": analyzed, "failed": failed })),
+        );
+    });
+
+    Ok(job_id)
+}
+
+/// Audio clips that clip, peak above `thresholds.peak_db` or are mostly or
+/// edge-padded silence, from the levels measured by `probe_project_media`
+#[tauri::command]
+pub async fn get_audio_qa_report(
+    project_id: String,
+    thresholds: Option<AudioQaThresholds>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<AudioQaReport, AppError> {
+    media::qa_report(
+        &state.db,
+        &project_id,
+        thresholds.unwrap_or_default(),
+        limit.unwrap_or(500),
+    )
+}
+
+/// Estimated in-build size of a texture from its importer settings, cached
+/// per version of the file and its .meta. None for other asset types or
+/// when the texture has no importer settings.
+#[tauri::command]
+pub async fn get_texture_estimate(
+    asset_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<TextureEstimate>, AppError> {
+    let asset = state
+        .db
+        .get_asset(&asset_id)?
+        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    if asset.asset_type != "texture" {
+        return Ok(None);
+    }
+
+    let db = Arc::clone(&state.db);
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&asset.absolute_path);
+        let meta_time = texture_size::meta_modified_time(path);
+        if let Some(estimate) = db.get_texture_estimate(&asset.id, asset.modified_time, meta_time)? {
+            return Ok(Some(estimate));
+        }
+
+        let estimate = texture_size::estimate(path);
+        if let (Some(estimate), false) = (&estimate, db.is_read_only()) {
+            db.save_texture_estimate(&asset.id, asset.modified_time, meta_time, estimate)?;
+        }
+        Ok(estimate)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Texture estimate task failed: {}", e)))?
+}
+
+/// Re-estimate textures changed since the last scan, e.g. after editing
+/// import settings in Unity. Returns a job id; progress is reported on
+/// `texture-size-progress`.
+#[tauri::command]
+pub async fn estimate_texture_sizes(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    state.ensure_writable()?;
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::with_cancel(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "texture_sizes",
+        "texture-size-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let _timer = metrics.time("textures.estimate");
+
+        let result = texture_size::estimate_project(&db, &project_id, |done, total| {
+            progress.emit("estimating", done, Some(total));
+            progress.wait_while_paused()
+        });
+
+        match result {
+            Ok(stats) if stats.completed => progress.complete(
+                stats.textures,
+                Some(stats.textures),
+                ProgressExtra::default(),
+                serde_json::to_value(&stats).ok(),
+            ),
+            Ok(stats) => progress.cancel(
+                stats.estimated + stats.unchanged + stats.unsupported,
+                Some(stats.textures),
+                ProgressExtra::default(),
+            ),
+            Err(e) => {
+                tracing::error!("Texture size estimation failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// Localization keys whose name contains `query`, with every locale's
+/// string or asset. `missing_only` lists just the keys some locale lacks.
+#[tauri::command]
+pub async fn search_localization(
+    project_id: String,
+    query: Option<String>,
+    missing_only: Option<bool>,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LocalizedKey>, AppError> {
+    localization::search(
+        &state.db,
+        &project_id,
+        query.as_deref().unwrap_or(""),
+        missing_only.unwrap_or(false),
+        limit.unwrap_or(200),
+    )
 }
 
 #[tauri::command]
-pub async fn get_asset(id: String, state: State<'_, AppState>) -> Result<Asset, AppError> {
-    state
-        .db
-        .get_asset(&id)?
-        .ok_or_else(|| AppError::AssetNotFound(id))
+pub async fn get_localization_tables(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<LocalizationTable>, AppError> {
+    state.db.get_localization_tables(&project_id)
 }
 
+/// The localization keys an asset is a locale variant of
 #[tauri::command]
-pub async fn get_dependencies(
+pub async fn get_asset_localization(
     asset_id: String,
     state: State<'_, AppState>,
-) -> Result<Vec<Dependency>, AppError> {
-    state.db.get_dependencies(&asset_id)
+) -> Result<Vec<LocalizedKey>, AppError> {
+    localization::asset_keys(&state.db, &asset_id)
 }
 
+/// Textures ranked by estimated build size, as of the last scan or estimate run
 #[tauri::command]
-pub async fn get_dependents(
-    asset_id: String,
+pub async fn get_texture_size_report(
+    project_id: String,
+    limit: Option<i64>,
     state: State<'_, AppState>,
-) -> Result<Vec<Dependency>, AppError> {
-    state.db.get_dependents(&asset_id)
+) -> Result<Vec<TextureEstimateEntry>, AppError> {
+    state.db.get_texture_estimates(&project_id, limit.unwrap_or(1000))
 }
 
+/// Textures that are non-power-of-two, larger than their importer max size,
+/// uncompressed or carry a fully opaque alpha channel, with counts and the
+/// largest `limit` of each
 #[tauri::command]
-pub async fn get_type_counts(
+pub async fn get_texture_health(
     project_id: String,
+    limit: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<Vec<TypeCount>, AppError> {
-    state.db.get_type_counts(&project_id)
+) -> Result<TextureHealthReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let (thumbnail_options, threads) = {
+        let settings = state.settings.read();
+        (settings.thumbnail_options(), settings.effective_thumbnail_threads())
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("textures.health");
+        texture_health::check_project(
+            &db,
+            &project_id,
+            &thumbnail_options,
+            threads,
+            limit.unwrap_or(texture_health::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Texture health task failed: {}", e)))?
 }
 
+/// Run the project checks (missing .meta files, duplicate GUIDs, case-only
+/// collisions, broken references, empty folders) for a health dashboard
 #[tauri::command]
-pub async fn export_file(
-    asset_id: String,
-    dest_folder: String,
+pub async fn validate_project(
+    project_id: String,
+    limit: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<ExportResult, AppError> {
-    let asset = state
-        .db
-        .get_asset(&asset_id)?
-        .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+) -> Result<ValidationReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let ignore_patterns = state.settings.read().ignore_patterns.clone();
 
-    let exporter = Exporter::new(Arc::clone(&state.db));
-    exporter.export_file(&asset, Path::new(&dest_folder))
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("validation.project");
+        validation::validate_project(
+            &db,
+            &project_id,
+            &ignore_patterns,
+            limit.unwrap_or(validation::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Validation task failed: {}", e)))?
 }
 
 #[tauri::command]
-pub async fn export_bundle(
+pub async fn get_model_info(
     asset_id: String,
-    dest_folder: String,
     state: State<'_, AppState>,
-) -> Result<ExportResult, AppError> {
+) -> Result<Option<ModelInfo>, AppError> {
     let asset = state
         .db
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
 
-    let exporter = Exporter::new(Arc::clone(&state.db));
-    exporter.export_bundle(&asset, Path::new(&dest_folder), 5)
+    if asset.asset_type != "model" {
+        return Ok(None);
+    }
+
+    Ok(parse_model_info(Path::new(&asset.absolute_path)))
 }
 
+/// Models missing LODs or over their folder's triangle budget (from
+/// settings), and the LOD groups found by file suffix, embedded mesh names
+/// and prefab LODGroups
 #[tauri::command]
-pub async fn reveal_in_explorer(path: String) -> Result<(), AppError> {
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .args(["/select,", &path])
-            .spawn()
-            .map_err(|e| AppError::Io(e))?;
-    }
+pub async fn get_lod_report(
+    project_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<LodReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let (budgets, min_triangles, threads) = {
+        let settings = state.settings.read();
+        (
+            settings.triangle_budgets.clone(),
+            settings.lod_min_triangles,
+            settings.effective_dependency_threads(),
+        )
+    };
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .args(["-R", &path])
-            .spawn()
-            .map_err(|e| AppError::Io(e))?;
-    }
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("models.lod_report");
+        lods::lod_report(&db, &project_id, &budgets, min_triangles, threads, limit.unwrap_or(500))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("LOD report task failed: {}", e)))?
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(Path::new(&path).parent().unwrap_or(Path::new(&path)))
-            .spawn()
-            .map_err(|e| AppError::Io(e))?;
-    }
+/// References that cross from one feature folder into another, for the
+/// dependency boundaries set up in settings
+#[tauri::command]
+pub async fn get_boundary_report(
+    project_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<BoundaryReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let patterns = state.settings.read().dependency_boundaries.clone();
 
-    Ok(())
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("deps.boundary_report");
+        boundaries::boundary_report(&db, &project_id, &patterns, limit.unwrap_or(boundaries::DEFAULT_LIMIT))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Boundary report task failed: {}", e)))?
 }
 
+/// Cross-check the GUID-to-path mapping in the project's Unity Library with
+/// the index, listing what only one side knows and paths that disagree
 #[tauri::command]
-pub async fn get_material_info(
+pub async fn inspect_unity_library(
+    project_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<LibraryInspection, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    let ignore_patterns = state.settings.read().ignore_patterns.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("library.inspect");
+        unity_library::inspect_library(
+            &db,
+            &project_id,
+            &ignore_patterns,
+            limit.unwrap_or(unity_library::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Library inspection failed: {}", e)))?
+}
+
+/// Every scene's dependency closure sized on disk and in the build, split
+/// into assets only that scene uses and assets shared with other scenes
+#[tauri::command]
+pub async fn get_scene_costs(project_id: String, state: State<'_, AppState>) -> Result<SceneCostReport, AppError> {
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("scenes.costs");
+        scene_costs::scene_costs(&db, &project_id)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Scene cost task failed: {}", e)))?
+}
+
+/// Default and maximum sizes for text previews
+const TEXT_PREVIEW_DEFAULT_BYTES: usize = 64 * 1024;
+const TEXT_PREVIEW_MAX_BYTES: usize = 1024 * 1024;
+
+#[tauri::command]
+pub async fn get_asset_text_preview(
     asset_id: String,
+    max_bytes: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<Option<MaterialInfo>, AppError> {
+) -> Result<TextPreview, AppError> {
     let asset = state
         .db
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
 
-    if asset.asset_type != "material" {
-        return Ok(None);
-    }
+    let max_bytes = max_bytes
+        .unwrap_or(TEXT_PREVIEW_DEFAULT_BYTES)
+        .min(TEXT_PREVIEW_MAX_BYTES);
 
-    Ok(parse_material_file(Path::new(&asset.absolute_path)))
+    tokio::task::spawn_blocking(move || {
+        read_text_preview(Path::new(&asset.absolute_path), max_bytes)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Preview task failed: {}", e)))?
 }
 
+/// Hex/ASCII dump of part of any asset, with the format its header names
 #[tauri::command]
-pub async fn get_model_info(
+pub async fn get_asset_hex_preview(
     asset_id: String,
+    offset: Option<u64>,
+    length: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<Option<ModelInfo>, AppError> {
+) -> Result<HexPreview, AppError> {
     let asset = state
         .db
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
 
-    if asset.asset_type != "model" {
-        return Ok(None);
-    }
-
-    Ok(parse_model_info(Path::new(&asset.absolute_path)))
+    tokio::task::spawn_blocking(move || {
+        hex_preview::read_hex_preview(
+            Path::new(&asset.absolute_path),
+            offset.unwrap_or(0),
+            length.unwrap_or(hex_preview::DEFAULT_LENGTH),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Preview task failed: {}", e)))?
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -602,18 +3443,47 @@ pub async fn get_thumbnail_base64(
         .db
         .get_asset(&asset_id)?
         .ok_or_else(|| AppError::AssetNotFound(asset_id))?;
+    let thumbnail_options = state.settings.read().thumbnail_options();
+    Ok(thumbnail_data_url(&state.db, &asset, &thumbnail_options))
+}
+
+/// Thumbnails for a page of the grid in one round trip, keyed by asset id.
+/// Ids that no longer exist are left out.
+#[tauri::command]
+pub async fn get_thumbnails_base64(
+    asset_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, Option<String>>, AppError> {
+    let db = Arc::clone(&state.db);
+    let thumbnail_options = state.settings.read().thumbnail_options();
+
+    tokio::task::spawn_blocking(move || {
+        let mut thumbnails = std::collections::HashMap::new();
+        for asset_id in asset_ids {
+            if let Some(asset) = db.get_asset(&asset_id)? {
+                thumbnails.insert(asset_id, thumbnail_data_url(&db, &asset, &thumbnail_options));
+            }
+        }
+        Ok(thumbnails)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Thumbnail task failed: {}", e)))?
+}
 
+/// The stored thumbnail as a data URL, or one made from a texture on the fly.
+/// "TOO_LARGE" and "UNSUPPORTED" pass through as they are.
+fn thumbnail_data_url(db: &Database, asset: &Asset, thumbnail_options: &ThumbnailOptions) -> Option<String> {
     // First try thumbnail path
     if let Some(thumb_path) = &asset.thumbnail_path {
         // Check for special markers
         if thumb_path == "TOO_LARGE" {
-            return Ok(Some("TOO_LARGE".to_string()));
+            return Some("TOO_LARGE".to_string());
         }
         if thumb_path == "UNSUPPORTED" {
-            return Ok(Some("UNSUPPORTED".to_string()));
+            return Some("UNSUPPORTED".to_string());
         }
 
-        if let Ok(data) = std::fs::read(thumb_path) {
+        if let Ok(data) = std::fs::read(db.thumbnail_file(thumb_path)) {
             let base64 = base64_encode(&data);
             let ext = Path::new(thumb_path)
                 .extension()
@@ -623,15 +3493,20 @@ pub async fn get_thumbnail_base64(
                 "jpg" | "jpeg" => "image/jpeg",
                 "png" => "image/png",
                 "gif" => "image/gif",
+                "webp" => "image/webp",
                 _ => "image/png",
             };
-            return Ok(Some(format!("data:{};base64,{}", mime, base64)));
+            return Some(format!("data:{};base64,{}", mime, base64));
         }
     }
 
     // For textures, try to load and resize the original
     if asset.asset_type == "texture" {
         let source_path = Path::new(&asset.absolute_path);
+        if thumbnail_options.exceeds_decode_budget(source_path) {
+            return Some("TOO_LARGE".to_string());
+        }
+
         let ext = source_path
             .extension()
             .and_then(|e| e.to_str())
@@ -641,12 +3516,12 @@ pub async fn get_thumbnail_base64(
         // Check supported formats
         match ext.as_str() {
             "png" | "jpg" | "jpeg" | "tga" | "bmp" | "gif" => {
-                if let Ok(img) = image::open(source_path) {
+                if let Ok(img) = open_image_limited(source_path, thumbnail_options) {
                     let thumb = img.thumbnail(128, 128);
                     let mut buf = std::io::Cursor::new(Vec::new());
                     if thumb.write_to(&mut buf, image::ImageFormat::Png).is_ok() {
                         let base64 = base64_encode(buf.get_ref());
-                        return Ok(Some(format!("data:image/png;base64,{}", base64)));
+                        return Some(format!("data:image/png;base64,{}", base64));
                     }
                 }
             }
@@ -670,7 +3545,7 @@ pub async fn get_thumbnail_base64(
                         let mut buf = std::io::Cursor::new(Vec::new());
                         if thumb.write_to(&mut buf, image::ImageFormat::Png).is_ok() {
                             let base64 = base64_encode(buf.get_ref());
-                            return Ok(Some(format!("data:image/png;base64,{}", base64)));
+                            return Some(format!("data:image/png;base64,{}", base64));
                         }
                     }
                 }
@@ -679,7 +3554,7 @@ pub async fn get_thumbnail_base64(
         }
     }
 
-    Ok(None)
+    None
 }
 
 fn base64_encode(data: &[u8]) -> String {
@@ -687,127 +3562,211 @@ fn base64_encode(data: &[u8]) -> String {
     base64::engine::general_purpose::STANDARD.encode(data)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ThumbnailProgress {
-    pub generated: usize,
-    pub total: usize,
-    pub phase: String, // "counting", "generating", "complete"
-}
-
 #[tauri::command]
 pub async fn regenerate_thumbnails(
     project_id: String,
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), AppError> {
+) -> Result<String, AppError> {
+    state.ensure_writable()?;
+    if state.is_safe_mode() {
+        return Err(AppError::InvalidArgument(
+            "Thumbnail generation is disabled in safe mode".to_string(),
+        ));
+    }
+
     let db = Arc::clone(&state.db);
     let thumb_dir = state.thumbnail_dir()?;
+    let (thumbnail_options, thumbnail_threads, refresh_interval, blender_path, converters) = {
+        let settings = state.settings.read();
+        (
+            settings.thumbnail_options(),
+            settings.effective_thumbnail_threads(),
+            std::time::Duration::from_millis(settings.ui_refresh_ms),
+            settings.blender_path.clone(),
+            settings.texture_converters.clone(),
+        )
+    };
+    let plugins = state.active_plugins();
     let cancel_flag = Arc::clone(&state.cancel_flag);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::with_cancel(
+        app_handle.clone(),
+        Arc::clone(&state.jobs),
+        "thumbnails",
+        "thumbnail-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
 
     // Spawn thumbnail generation task
     tokio::task::spawn_blocking(move || {
         // Phase 1: Clear existing thumbnails and count assets
-        let _ = app_handle.emit(
-            "thumbnail-progress",
-            ThumbnailProgress {
-                generated: 0,
-                total: 0,
-                phase: "counting".to_string(),
-            },
-        );
+        progress.emit("counting", 0, None);
 
         // Check cancellation
         if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
-            let _ = app_handle.emit(
-                "thumbnail-progress",
-                ThumbnailProgress {
-                    generated: 0,
-                    total: 0,
-                    phase: "cancelled".to_string(),
-                },
-            );
+            progress.cancel(0, None, ProgressExtra::default());
             return;
         }
 
         // Clear existing thumbnail paths to force regeneration
-        if let Err(e) = db.clear_thumbnail_paths(&project_id) {
+        if let Err(e) = db.clear_thumbnail_paths(&project_id, thumbnail_options.max_attempts) {
             tracing::error!("Failed to clear thumbnail paths: {}", e);
         }
 
-        // Get count of texture and material assets
-        let total = match db.count_thumbnail_assets(&project_id) {
+        let mut preview_gen =
+            PreviewGenerator::new(Arc::clone(&db), thumb_dir, thumbnail_options).with_converters(converters);
+        if let Some(plugins) = plugins {
+            preview_gen = preview_gen.with_plugins(plugins);
+        }
+        if let Some(blender) = blender_path {
+            preview_gen = preview_gen.with_blender(PathBuf::from(blender));
+        }
+
+        // Get count of texture and material assets, and formats plugins or Blender render
+        let total = match db.count_thumbnail_assets(&project_id, &preview_gen.extra_extensions()) {
             Ok(count) => count,
             Err(e) => {
                 tracing::error!("Failed to count assets: {}", e);
+                progress.fail(&e.to_string());
                 return;
             }
         };
 
         if total == 0 {
-            let _ = app_handle.emit(
-                "thumbnail-progress",
-                ThumbnailProgress {
-                    generated: 0,
-                    total: 0,
-                    phase: "complete".to_string(),
-                },
-            );
+            progress.complete(0, Some(0), ProgressExtra::default(), None);
             return;
         }
 
         // Phase 2: Generate thumbnails in batches
-        let preview_gen = PreviewGenerator::new(Arc::clone(&db), thumb_dir, 128);
+        let _timer = metrics.time("thumbnails.generate");
         let mut generated = 0usize;
         let batch_size = 25;
+        let mut changes = ChangeEmitter::new(app_handle.clone(), &project_id, refresh_interval);
 
         loop {
-            // Check cancellation
-            if cancel_flag.load(std::sync::atomic::Ordering::SeqCst) {
-                let _ = app_handle.emit(
-                    "thumbnail-progress",
-                    ThumbnailProgress {
-                        generated,
-                        total,
-                        phase: "cancelled".to_string(),
-                    },
-                );
+            // Check cancellation (pausing holds here until resumed)
+            if !progress.wait_while_paused() {
+                progress.cancel(generated, Some(total), ProgressExtra::default());
                 return;
             }
 
-            let _ = app_handle.emit(
-                "thumbnail-progress",
-                ThumbnailProgress {
-                    generated,
-                    total,
-                    phase: "generating".to_string(),
-                },
-            );
+            progress.emit("generating", generated, Some(total));
 
-            match preview_gen.generate_thumbnails_for_project(&project_id, batch_size) {
+            match preview_gen.generate_thumbnails_for_project(&project_id, batch_size, thumbnail_threads) {
                 Ok(0) => break, // No more thumbnails to generate
                 Ok(count) => {
                     generated += count;
-                    let _ = app_handle.emit("assets-updated", 0);
+                    changes.thumbnails_generated(count);
+                    changes.emit_if_due();
                 }
                 Err(e) => {
                     tracing::error!("Thumbnail generation error: {}", e);
-                    break;
+                    progress.fail(&e.to_string());
+                    return;
                 }
             }
         }
 
         // Phase 3: Complete
-        let _ = app_handle.emit(
-            "thumbnail-progress",
-            ThumbnailProgress {
-                generated,
-                total,
-                phase: "complete".to_string(),
-            },
+        changes.emit();
+        progress.complete(
+            generated,
+            Some(total),
+            ProgressExtra::default(),
+            Some(serde_json::json!({ "generated": generated })),
         );
     });
 
-    Ok(())
+    Ok(job_id)
+}
+
+/// Outcome of `retry_thumbnail_failures`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailRetryResult {
+    pub retried: usize,
+    pub recovered: usize,
+    pub still_failing: usize,
+}
+
+/// Example setups for texconv, PVRTexTool, astcenc and basisu to start
+/// `texture_converters` from
+#[tauri::command]
+pub async fn get_texture_converter_presets() -> Result<Vec<TextureConverter>, AppError> {
+    Ok(converters::presets())
+}
+
+#[tauri::command]
+pub async fn get_thumbnail_failures(
+    project_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ThumbnailFailure>, AppError> {
+    state.db.get_thumbnail_failures(&project_id)
+}
+
+/// Give every failed thumbnail in the project a fresh set of attempts and
+/// try each of them once right away
+#[tauri::command]
+pub async fn retry_thumbnail_failures(
+    project_id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ThumbnailRetryResult, AppError> {
+    state.ensure_writable()?;
+    if state.is_safe_mode() {
+        return Err(AppError::InvalidArgument(
+            "Thumbnail generation is disabled in safe mode".to_string(),
+        ));
+    }
+
+    let db = Arc::clone(&state.db);
+    let thumb_dir = state.thumbnail_dir()?;
+    let (thumbnail_options, blender_path, converters) = {
+        let settings = state.settings.read();
+        (
+            settings.thumbnail_options(),
+            settings.blender_path.clone(),
+            settings.texture_converters.clone(),
+        )
+    };
+    let plugins = state.active_plugins();
+
+    let mut changes = ChangeEmitter::new(app_handle, &project_id, Duration::ZERO);
+    let result = tokio::task::spawn_blocking(move || -> Result<ThumbnailRetryResult, AppError> {
+        let ids = db.reset_thumbnail_failures(&project_id)?;
+        let mut preview_gen =
+            PreviewGenerator::new(Arc::clone(&db), thumb_dir, thumbnail_options).with_converters(converters);
+        if let Some(plugins) = plugins {
+            preview_gen = preview_gen.with_plugins(plugins);
+        }
+        if let Some(blender) = blender_path {
+            preview_gen = preview_gen.with_blender(PathBuf::from(blender));
+        }
+
+        let mut recovered = 0;
+        for id in &ids {
+            let Some(asset) = db.get_asset(id)? else {
+                continue;
+            };
+            match preview_gen.generate_thumbnail(&asset)? {
+                Some(path) if path != "UNSUPPORTED" => recovered += 1,
+                _ => {}
+            }
+        }
+
+        Ok(ThumbnailRetryResult {
+            retried: ids.len(),
+            recovered,
+            still_failing: ids.len() - recovered,
+        })
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Thumbnail retry task failed: {}", e)))??;
+
+    changes.thumbnails_generated(result.recovered);
+    changes.emit();
+    Ok(result)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -818,6 +3777,206 @@ pub struct ModelAssetInfo {
     pub modified_time: i64,
 }
 
+/// Asset Store cache folder in use, whether or not it exists
+#[tauri::command]
+pub async fn get_asset_store_cache_dir(state: State<'_, AppState>) -> Result<Option<String>, AppError> {
+    let settings = state.settings.read();
+    Ok(asset_store::cache_dir(settings.asset_store_cache_dir.as_deref()).map(|d| d.to_string_lossy().to_string()))
+}
+
+/// Index the Asset Store download cache as a searchable library.
+/// Returns a job id; progress is reported on `asset-store-progress`.
+#[tauri::command]
+pub async fn index_asset_store(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    state.ensure_writable()?;
+    let cache_dir = asset_store::cache_dir(state.settings.read().asset_store_cache_dir.as_deref())
+        .ok_or_else(|| AppError::NotFound("No Asset Store cache location on this platform".to_string()))?;
+
+    let scan_lock = state.try_begin_scan().ok_or(AppError::ScanAlreadyRunning)?;
+    state.reset_cancel();
+
+    let db = Arc::clone(&state.db);
+    let cancel_flag = Arc::clone(&state.cancel_flag);
+    let metrics = Arc::clone(&state.metrics);
+    let progress = ProgressReporter::with_cancel(
+        app_handle,
+        Arc::clone(&state.jobs),
+        "asset_store",
+        "asset-store-progress",
+        Arc::clone(&cancel_flag),
+    );
+    let job_id = progress.job_id().to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let _scan_lock = scan_lock;
+        let _timer = metrics.time("asset_store.index");
+        progress.emit("indexing", 0, None);
+
+        let result = asset_store::index_cache(&db, &cache_dir, |done, total| {
+            progress.emit("indexing", done, Some(total));
+            progress.wait_while_paused()
+        });
+
+        match result {
+            Ok(stats) if stats.completed => {
+                progress.complete(
+                    stats.packages,
+                    Some(stats.packages),
+                    ProgressExtra::default(),
+                    serde_json::to_value(&stats).ok(),
+                );
+            }
+            Ok(stats) => progress.cancel(stats.indexed, Some(stats.packages), ProgressExtra::default()),
+            Err(e) => {
+                tracing::error!("Asset Store indexing failed: {}", e);
+                progress.fail(&e.to_string());
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub async fn get_asset_store_packages(state: State<'_, AppState>) -> Result<Vec<StorePackage>, AppError> {
+    state.db.get_store_packages()
+}
+
+#[tauri::command]
+pub async fn get_asset_store_package_files(
+    package_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<StoreFile>, AppError> {
+    state.db.get_store_files(&package_id)
+}
+
+/// Search files across all cached packages, e.g. "rope" or "rope fbx"
+#[tauri::command]
+pub async fn search_asset_store(
+    query: String,
+    asset_type: Option<String>,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<StoreFileHit>, AppError> {
+    state
+        .db
+        .search_store_files(&query, asset_type.as_deref(), limit.unwrap_or(500))
+}
+
+/// Extract files from a cached package with their .meta files. Defaults to
+/// the current project root, where they land under `Assets/` as Unity would
+/// import them.
+#[tauri::command]
+pub async fn extract_asset_store_files(
+    package_id: String,
+    guids: Vec<String>,
+    dest_dir: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<StoreExtraction, AppError> {
+    if guids.is_empty() {
+        return Err(AppError::InvalidArgument("No files selected".to_string()));
+    }
+    let project_root = state.settings.read().project_root.clone();
+    let dest_dir = dest_dir
+        .or_else(|| project_root.clone())
+        .ok_or_else(|| AppError::InvalidArgument("No destination folder or project selected".to_string()))?;
+    // Extractions into the active project are recorded with their provenance
+    let project = match project_root {
+        Some(root) if Path::new(&dest_dir).starts_with(&root) => state.db.get_project_by_path(&root)?,
+        _ => None,
+    };
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("asset_store.extract");
+        asset_store::extract_files(&db, &package_id, &guids, Path::new(&dest_dir), project.as_ref())
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Extract task failed: {}", e)))?
+}
+
+/// Import an Addressables build layout, replacing the previous one. Without
+/// a path, the report the last build left in the project's Library is used.
+#[tauri::command]
+pub async fn import_build_layout(
+    project_id: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BuildLayoutImport, AppError> {
+    state.ensure_writable()?;
+    let project = retry_busy(|| state.db.get_project(&project_id))?
+        .ok_or_else(|| AppError::NotFound(format!("Project not found: {}", project_id)))?;
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => build_layout::find_layout(Path::new(&project.root_path)).ok_or_else(|| {
+            AppError::NotFound(
+                "No build layout in Library/com.unity.addressables; enable Debug Build Layout and build Addressables"
+                    .to_string(),
+            )
+        })?,
+    };
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("build_layout.import");
+        build_layout::import(&db, &project.id, &path)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Build layout task failed: {}", e)))?
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildLayoutReport {
+    pub bundles: Vec<BuiltBundle>,
+    pub assets: Vec<AssetBuildCost>,
+}
+
+/// Bundles of the imported build layout and the source assets that cost the
+/// most download size
+#[tauri::command]
+pub async fn get_build_layout_report(
+    project_id: String,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<BuildLayoutReport, AppError> {
+    Ok(BuildLayoutReport {
+        bundles: state.db.get_built_bundles(&project_id)?,
+        assets: state.db.get_asset_build_costs(&project_id, limit.unwrap_or(1000))?,
+    })
+}
+
+/// Import a player build report, recording each asset's size in the build.
+/// Without a path, the report of the last build in Editor.log is used.
+#[tauri::command]
+pub async fn import_build_report(
+    project_id: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BuildReportImport, AppError> {
+    state.ensure_writable()?;
+    let path = path
+        .map(PathBuf::from)
+        .or_else(build_report::editor_log_path)
+        .ok_or_else(|| AppError::NotFound("No Editor.log location on this platform".to_string()))?;
+
+    let db = Arc::clone(&state.db);
+    let metrics = Arc::clone(&state.metrics);
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics.time("build_report.import");
+        build_report::import(&db, &project_id, &path)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Build report task failed: {}", e)))?
+}
+
+/// Bundles an asset was built into; more than one means it's duplicated
+#[tauri::command]
+pub async fn get_asset_bundles(asset_id: String, state: State<'_, AppState>) -> Result<Vec<BundledAsset>, AppError> {
+    state.db.get_asset_bundle_entries(&asset_id)
+}
+
 #[tauri::command]
 pub async fn get_model_assets_for_thumbnails(
     project_id: String,
@@ -825,8 +3984,10 @@ pub async fn get_model_assets_for_thumbnails(
 ) -> Result<Vec<ModelAssetInfo>, AppError> {
     let assets = state.db.get_model_assets(&project_id)?;
 
+    // Models Blender already rendered don't need the WebGL fallback
     Ok(assets
         .into_iter()
+        .filter(|a| matches!(a.thumbnail_path.as_deref(), None | Some("UNSUPPORTED") | Some("TOO_LARGE")))
         .map(|a| ModelAssetInfo {
             id: a.id,
             absolute_path: a.absolute_path,
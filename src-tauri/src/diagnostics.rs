@@ -0,0 +1,76 @@
+use crate::db::{DbTuning, TableRowCount};
+use crate::error::AppResult;
+use crate::logging;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub tauri_version: String,
+    pub sqlite_version: String,
+    pub os: String,
+    pub arch: String,
+    pub app_data_dir: String,
+    pub db_path: String,
+    pub db_size_bytes: u64,
+    pub wal_size_bytes: u64,
+    pub thumbnail_dir: String,
+    pub log_dir: Option<String>,
+    pub thumbnail_count: usize,
+    pub thumbnail_cache_bytes: u64,
+    pub table_counts: Vec<TableRowCount>,
+    /// Effective page, cache and mmap settings with read timings, to compare
+    /// the `db_*` tuning settings on this machine
+    pub db_tuning: DbTuning,
+    pub scan_running: bool,
+}
+
+/// Gather environment and storage details for bug reports
+pub fn collect(state: &AppState) -> AppResult<Diagnostics> {
+    let db_path = state.db.path();
+    let wal_path = db_path.with_extension("db-wal");
+    let thumb_dir = state.thumbnail_dir()?;
+    let (thumbnail_count, thumbnail_cache_bytes) = dir_usage(&thumb_dir);
+
+    let app_data_dir = state.app_data_dir.to_string_lossy().to_string();
+
+    Ok(Diagnostics {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        sqlite_version: rusqlite::version().to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_data_dir,
+        db_path: db_path.to_string_lossy().to_string(),
+        db_size_bytes: file_size(db_path),
+        wal_size_bytes: file_size(&wal_path),
+        thumbnail_dir: thumb_dir.to_string_lossy().to_string(),
+        log_dir: logging::log_dir().map(|d| d.to_string_lossy().to_string()),
+        thumbnail_count,
+        thumbnail_cache_bytes,
+        table_counts: state.db.get_table_row_counts()?,
+        db_tuning: state.db.get_tuning_report()?,
+        scan_running: state.is_scan_running(),
+    })
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Number of files and total bytes directly inside a directory
+fn dir_usage(path: &Path) -> (usize, u64) {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return (0, 0),
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .fold((0, 0), |(count, bytes), m| (count + 1, bytes + m.len()))
+}
@@ -1,39 +0,0 @@
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum AppError {
-    #[error("Database error: {0}")]
-    Database(#[from] rusqlite::Error),
-
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
-
-    #[error("Image error: {0}")]
-    Image(#[from] image::ImageError),
-
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
-
-    #[error("Invalid project: {0}")]
-    InvalidProject(String),
-
-    #[error("Asset not found: {0}")]
-    AssetNotFound(String),
-
-    #[error("R2D2 pool error: {0}")]
-    Pool(#[from] r2d2::Error),
-
-    #[error("{0}")]
-    Custom(String),
-}
-
-impl serde::Serialize for AppError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
-}
-
-pub type AppResult<T> = Result<T, AppError>;
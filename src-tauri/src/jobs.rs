@@ -0,0 +1,416 @@
+use crate::notify;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Keep at most this many finished jobs around for status queries
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Snapshot of a background job, returned by `list_jobs` / `get_job_status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub kind: String,  // "scan", "thumbnails", "export"
+    pub state: String, // "running", "paused", "completed", "failed", "cancelled"
+    pub phase: String,
+    pub processed: usize,
+    pub total: Option<usize>,
+    /// Throughput of the current phase
+    pub items_per_sec: Option<f64>,
+    /// Estimated seconds remaining in the current phase
+    pub eta_secs: Option<f64>,
+    pub error: Option<String>,
+    /// Final payload for jobs that produce one (e.g. export results)
+    pub result: Option<serde_json::Value>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Flags a job polls at its checkpoints, so requests reach only that job
+#[derive(Clone, Default)]
+pub struct JobControl {
+    pub pause: Arc<AtomicBool>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+struct JobEntry {
+    status: JobStatus,
+    phase_started: Instant,
+    paused_at: Option<Instant>,
+    control: JobControl,
+}
+
+impl JobEntry {
+    fn snapshot(&self) -> JobStatus {
+        let mut status = self.status.clone();
+        if status.state == "running" {
+            let rate = items_per_sec(self.phase_started, status.processed);
+            status.items_per_sec = rate;
+            status.eta_secs = rate.and_then(|r| estimate_eta(r, status.processed, status.total));
+        }
+        status
+    }
+}
+
+/// Tracks every long-running operation so the frontend can poll its state
+pub struct JobManager {
+    jobs: RwLock<HashMap<String, JobEntry>>,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register a new running job polling `control` and return its id
+    pub fn create(&self, kind: &str, control: JobControl) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        let entry = JobEntry {
+            status: JobStatus {
+                id: id.clone(),
+                kind: kind.to_string(),
+                state: "running".to_string(),
+                phase: "starting".to_string(),
+                processed: 0,
+                total: None,
+                items_per_sec: None,
+                eta_secs: None,
+                error: None,
+                result: None,
+                started_at: chrono::Utc::now().timestamp(),
+                finished_at: None,
+            },
+            phase_started: Instant::now(),
+            paused_at: None,
+            control,
+        };
+
+        let mut jobs = self.jobs.write();
+        jobs.insert(id.clone(), entry);
+        prune_finished(&mut jobs);
+        id
+    }
+
+    /// Record progress for a job and return the updated snapshot
+    pub fn update(
+        &self,
+        id: &str,
+        phase: &str,
+        processed: usize,
+        total: Option<usize>,
+    ) -> Option<JobStatus> {
+        let mut jobs = self.jobs.write();
+        let entry = jobs.get_mut(id)?;
+        if entry.status.phase != phase {
+            entry.status.phase = phase.to_string();
+            entry.phase_started = Instant::now();
+        }
+        entry.status.processed = processed;
+        entry.status.total = total;
+        Some(entry.snapshot())
+    }
+
+    /// Ask an unfinished job to pause at its next checkpoint, or to carry on.
+    /// Returns false if there is no such job.
+    pub fn set_paused(&self, id: &str, paused: bool) -> bool {
+        match self.jobs.read().get(id) {
+            Some(entry) if entry.status.finished_at.is_none() => {
+                entry.control.pause.store(paused, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ask an unfinished job to stop at its next checkpoint. Returns false if
+    /// there is no such job.
+    pub fn request_cancel(&self, id: &str) -> bool {
+        match self.jobs.read().get(id) {
+            Some(entry) if entry.status.finished_at.is_none() => {
+                entry.control.cancel.store(true, Ordering::SeqCst);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn pause(&self, id: &str) {
+        let mut jobs = self.jobs.write();
+        if let Some(entry) = jobs.get_mut(id) {
+            entry.status.state = "paused".to_string();
+            entry.paused_at = Some(Instant::now());
+        }
+    }
+
+    pub fn resume(&self, id: &str) {
+        let mut jobs = self.jobs.write();
+        if let Some(entry) = jobs.get_mut(id) {
+            entry.status.state = "running".to_string();
+            // Don't count time spent paused towards the phase rate
+            if let Some(paused_at) = entry.paused_at.take() {
+                entry.phase_started += paused_at.elapsed();
+            }
+        }
+    }
+
+    pub fn complete(&self, id: &str, result: Option<serde_json::Value>) {
+        self.finish(id, "completed", None, result);
+    }
+
+    pub fn fail(&self, id: &str, error: &str) {
+        self.finish(id, "failed", Some(error.to_string()), None);
+    }
+
+    pub fn cancel(&self, id: &str) {
+        self.finish(id, "cancelled", None, None);
+    }
+
+    fn finish(
+        &self,
+        id: &str,
+        state: &str,
+        error: Option<String>,
+        result: Option<serde_json::Value>,
+    ) {
+        let mut jobs = self.jobs.write();
+        if let Some(entry) = jobs.get_mut(id) {
+            entry.status.state = state.to_string();
+            entry.status.error = error;
+            entry.status.result = result;
+            entry.status.finished_at = Some(chrono::Utc::now().timestamp());
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobStatus> {
+        self.jobs.read().get(id).map(|entry| entry.snapshot())
+    }
+
+    /// Number of jobs that are still running or paused
+    pub fn active_count(&self) -> usize {
+        self.jobs
+            .read()
+            .values()
+            .filter(|e| e.status.finished_at.is_none())
+            .count()
+    }
+
+    /// All known jobs, most recently started first
+    pub fn list(&self) -> Vec<JobStatus> {
+        let mut jobs: Vec<JobStatus> = self.jobs.read().values().map(|e| e.snapshot()).collect();
+        jobs.sort_by_key(|j| std::cmp::Reverse(j.started_at));
+        jobs
+    }
+}
+
+fn items_per_sec(phase_started: Instant, processed: usize) -> Option<f64> {
+    if processed == 0 {
+        return None;
+    }
+
+    let elapsed = phase_started.elapsed().as_secs_f64();
+    Some(processed as f64 / elapsed.max(0.001))
+}
+
+fn estimate_eta(rate: f64, processed: usize, total: Option<usize>) -> Option<f64> {
+    let total = total?;
+    if processed >= total {
+        return None;
+    }
+
+    Some((total - processed) as f64 / rate)
+}
+
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(String, i64)> = jobs
+        .values()
+        .filter(|e| e.status.finished_at.is_some())
+        .map(|e| (e.status.id.clone(), e.status.finished_at.unwrap_or(0)))
+        .collect();
+
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+
+    finished.sort_by_key(|(_, finished_at)| *finished_at);
+    let excess = finished.len() - MAX_FINISHED_JOBS;
+    for (id, _) in finished.into_iter().take(excess) {
+        jobs.remove(&id);
+    }
+}
+
+/// Progress event shared by every long-running operation.
+/// Emitted on `scan-progress`, `thumbnail-progress` and `export-progress`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub job_id: String,
+    pub kind: String,
+    pub phase: String,
+    pub processed: usize,
+    pub total: Option<usize>,
+    pub current_path: Option<String>,
+    pub items_per_sec: Option<f64>,
+    pub eta_secs: Option<f64>,
+    /// Number of unchanged files skipped during re-scan
+    pub skipped: Option<usize>,
+    /// Number of files indexed for the first time
+    pub added: Option<usize>,
+    /// Number of previously indexed files that changed on disk
+    pub changed: Option<usize>,
+}
+
+/// Optional fields attached to a progress event
+#[derive(Debug, Clone, Default)]
+pub struct ProgressExtra {
+    pub current_path: Option<String>,
+    pub skipped: Option<usize>,
+    pub added: Option<usize>,
+    pub changed: Option<usize>,
+}
+
+/// Creates a job and keeps its status and the frontend progress event in sync
+#[derive(Clone)]
+pub struct ProgressReporter {
+    app_handle: AppHandle,
+    jobs: Arc<JobManager>,
+    job_id: String,
+    kind: String,
+    event: &'static str,
+    control: JobControl,
+}
+
+impl ProgressReporter {
+    pub fn new(app_handle: AppHandle, jobs: Arc<JobManager>, kind: &str, event: &'static str) -> Self {
+        Self::with_cancel(app_handle, jobs, kind, event, Arc::default())
+    }
+
+    /// A job cancelled through `cancel`, for jobs that share
+    /// `AppState::cancel_flag` with the core code they call
+    pub fn with_cancel(
+        app_handle: AppHandle,
+        jobs: Arc<JobManager>,
+        kind: &str,
+        event: &'static str,
+        cancel: Arc<AtomicBool>,
+    ) -> Self {
+        let control = JobControl {
+            pause: Arc::default(),
+            cancel,
+        };
+        let job_id = jobs.create(kind, control.clone());
+        Self {
+            app_handle,
+            jobs,
+            job_id,
+            kind: kind.to_string(),
+            event,
+            control,
+        }
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn emit(&self, phase: &str, processed: usize, total: Option<usize>) {
+        self.emit_with(phase, processed, total, ProgressExtra::default());
+    }
+
+    pub fn emit_with(&self, phase: &str, processed: usize, total: Option<usize>, extra: ProgressExtra) {
+        let status = self.jobs.update(&self.job_id, phase, processed, total);
+        self.send(phase, processed, total, status.as_ref(), extra);
+    }
+
+    pub fn complete(
+        &self,
+        processed: usize,
+        total: Option<usize>,
+        extra: ProgressExtra,
+        result: Option<serde_json::Value>,
+    ) {
+        self.jobs.update(&self.job_id, "complete", processed, total);
+        self.jobs.complete(&self.job_id, result);
+        self.send("complete", processed, total, None, extra);
+        self.announce();
+    }
+
+    pub fn cancel(&self, processed: usize, total: Option<usize>, extra: ProgressExtra) {
+        self.jobs.cancel(&self.job_id);
+        self.send("cancelled", processed, total, None, extra);
+    }
+
+    pub fn fail(&self, error: &str) {
+        self.jobs.fail(&self.job_id, error);
+        self.send("failed", 0, None, None, ProgressExtra::default());
+        self.announce();
+    }
+
+    /// Notification and webhook for finished scans and exports
+    fn announce(&self) {
+        if let Some(status) = self.jobs.get(&self.job_id) {
+            notify::job_finished(&self.app_handle, &status);
+        }
+    }
+
+    /// Block while this job is paused, keeping its position intact.
+    /// Returns false if the job was cancelled while paused.
+    pub fn wait_while_paused(&self) -> bool {
+        let pause_flag = &self.control.pause;
+        let cancel_flag = &self.control.cancel;
+        if !pause_flag.load(Ordering::SeqCst) {
+            return !cancel_flag.load(Ordering::SeqCst);
+        }
+
+        self.jobs.pause(&self.job_id);
+        let (processed, total) = self
+            .jobs
+            .get(&self.job_id)
+            .map(|s| (s.processed, s.total))
+            .unwrap_or((0, None));
+        self.send("paused", processed, total, None, ProgressExtra::default());
+
+        while pause_flag.load(Ordering::SeqCst) && !cancel_flag.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        self.jobs.resume(&self.job_id);
+        !cancel_flag.load(Ordering::SeqCst)
+    }
+
+    fn send(
+        &self,
+        phase: &str,
+        processed: usize,
+        total: Option<usize>,
+        status: Option<&JobStatus>,
+        extra: ProgressExtra,
+    ) {
+        let _ = self.app_handle.emit(
+            self.event,
+            ProgressEvent {
+                job_id: self.job_id.clone(),
+                kind: self.kind.clone(),
+                phase: phase.to_string(),
+                processed,
+                total,
+                current_path: extra.current_path,
+                items_per_sec: status.and_then(|s| s.items_per_sec),
+                eta_secs: status.and_then(|s| s.eta_secs),
+                skipped: extra.skipped,
+                added: extra.added,
+                changed: extra.changed,
+            },
+        );
+    }
+}
@@ -1,53 +1,199 @@
+mod api;
+mod changes;
 mod commands;
-mod db;
-mod deps;
-mod error;
-mod export;
-mod indexer;
-mod previews;
-mod scanner;
-mod settings;
+mod diagnostics;
+mod jobs;
+mod logging;
+mod metrics;
+mod notify;
+mod session;
 mod state;
+mod telemetry;
+
+// The core lives in its own crate so the CLI can use it without Tauri;
+// imported here so app modules keep using `crate::db` etc.
+use scythe_core::{
+    asset_files, asset_store, benchmark, boundaries, build_layout, build_report, catalog,
+    code_editor, compare, converters, db, deliveries, deps, duplicates, error, export, hex_preview,
+    indexer, library, localization, lods, media, plugins, previews, provenance, render_pipeline,
+    rules, scanner, scene_costs, search, settings, similar, snapshot, storage, tags,
+    texture_health, texture_size, unity_library, validation, vcs, watcher,
+};
 
 use state::AppState;
-use tauri::Manager;
-use tracing_subscriber;
+use std::sync::Arc;
+use tauri::{Manager, RunEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tracing_subscriber::fmt::init();
+    logging::init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
+            match app.path().app_log_dir() {
+                Ok(dir) => {
+                    if let Err(e) = logging::open_log_dir(&dir) {
+                        tracing::warn!("File logging disabled: {}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("No log directory available: {}", e),
+            }
+
             let app_state = AppState::new(app.handle().clone())?;
+            logging::install_panic_hook();
+            telemetry::install_panic_hook(Arc::clone(&app_state.telemetry));
+            app_state.apply_api_settings();
+            app_state.start_warm_up();
+            app_state.apply_watch_settings(app.handle());
             app.manage(app_state);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::set_project_root,
+            commands::add_project,
+            commands::add_library,
+            commands::list_projects,
+            commands::switch_project,
+            commands::remove_project,
             commands::set_output_folder,
             commands::get_settings,
+            commands::get_all_settings,
+            commands::update_settings,
+            commands::get_api_status,
+            commands::regenerate_api_token,
+            commands::send_test_webhook,
+            commands::export_settings_profile,
+            commands::import_settings_profile,
+            commands::update_ignore_patterns,
+            commands::exclude_assets,
+            commands::include_excluded_assets,
+            commands::get_excluded_assets,
+            commands::move_asset,
+            commands::delete_assets,
+            commands::restore_deleted_assets,
+            commands::get_trash_operations,
+            commands::get_storage_locations,
+            commands::set_storage_locations,
+            commands::set_shared_catalog,
+            commands::publish_shared_catalog,
             commands::get_current_project,
             commands::start_scan,
+            commands::is_scan_running,
             commands::get_assets,
+            commands::get_asset_summaries,
+            commands::search_in_files,
+            commands::get_search_suggestions,
+            commands::bulk_edit_assets,
+            commands::get_asset_tags,
+            commands::get_project_tags,
+            commands::export_asset_list,
+            commands::export_attribution_csv,
             commands::get_asset,
+            commands::get_vcs_info,
             commands::get_dependencies,
             commands::get_dependents,
+            commands::get_dependency_graph,
+            commands::export_dependency_dot,
             commands::get_type_counts,
+            commands::get_folder_rollups,
+            commands::get_project_growth,
             commands::export_file,
+            commands::get_deliveries,
+            commands::get_provenance,
+            commands::check_source_updates,
+            commands::reimport_from_source,
+            commands::verify_deliveries,
             commands::export_bundle,
+            commands::export_unitypackage,
+            commands::copy_from_library,
+            commands::stage_assets_for_drag,
+            commands::find_external_file,
+            commands::get_similar_assets,
+            commands::export_markdown_notes,
+            commands::export_project_snapshot,
+            commands::get_snapshot_info,
+            commands::import_project_snapshot,
             commands::reveal_in_explorer,
+            commands::open_asset,
+            commands::open_in_code_editor,
+            commands::get_code_editor_presets,
+            commands::copy_asset_reference,
             commands::get_material_info,
+            commands::get_plugin_metadata,
+            commands::get_validation_rules,
+            commands::save_validation_rule,
+            commands::delete_validation_rule,
+            commands::run_validation_rules,
+            commands::get_rule_violations,
+            commands::get_asset_store_cache_dir,
+            commands::index_asset_store,
+            commands::get_asset_store_packages,
+            commands::get_asset_store_package_files,
+            commands::search_asset_store,
+            commands::extract_asset_store_files,
+            commands::import_build_layout,
+            commands::get_build_layout_report,
+            commands::import_build_report,
+            commands::get_asset_bundles,
             commands::get_model_info,
+            commands::get_lod_report,
+            commands::get_boundary_report,
+            commands::inspect_unity_library,
+            commands::get_scene_costs,
+            commands::get_media_info,
+            commands::probe_project_media,
+            commands::get_audio_qa_report,
+            commands::get_texture_estimate,
+            commands::estimate_texture_sizes,
+            commands::get_texture_size_report,
+            commands::get_texture_health,
+            commands::validate_project,
+            commands::get_render_pipeline_audit,
+            commands::compare_projects,
+            commands::get_guid_collisions,
+            commands::search_localization,
+            commands::get_localization_tables,
+            commands::get_asset_localization,
+            commands::get_asset_text_preview,
+            commands::get_asset_hex_preview,
             commands::get_bundle_preview,
             commands::get_thumbnail_base64,
+            commands::get_thumbnails_base64,
             commands::regenerate_thumbnails,
+            commands::get_thumbnail_failures,
+            commands::get_texture_converter_presets,
+            commands::retry_thumbnail_failures,
             commands::get_model_assets_for_thumbnails,
             commands::cancel_operation,
+            commands::pause_operation,
+            commands::resume_operation,
+            commands::list_jobs,
+            commands::get_job_status,
+            commands::get_diagnostics,
+            commands::get_plugins,
+            commands::get_startup_status,
+            commands::set_safe_mode,
+            commands::check_database_integrity,
+            commands::get_recent_logs,
+            commands::get_performance_metrics,
+            commands::reset_performance_metrics,
+            commands::run_benchmark,
+            commands::get_telemetry_events,
+            commands::export_telemetry,
+            commands::clear_telemetry,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                if let Some(state) = app.try_state::<AppState>() {
+                    state.shutdown();
+                }
+            }
+        });
 }
@@ -0,0 +1,241 @@
+use crate::error::AppResult;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+const LOG_FILE: &str = "scythe.log";
+/// Rotate once the active file passes this size
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept alongside the active one (scythe.log.1, scythe.log.2, ...)
+const MAX_ROTATED_FILES: usize = 3;
+
+/// One structured log line, as written to the log file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Default)]
+struct LogFile {
+    dir: Option<PathBuf>,
+    file: Option<File>,
+    size: u64,
+}
+
+/// The log directory is only known once Tauri has started, so the file layer
+/// drops events until `open_log_dir` is called (they still reach stdout)
+static LOG_FILE_STATE: Lazy<Mutex<LogFile>> = Lazy::new(|| Mutex::new(LogFile::default()));
+
+/// Install stdout and file logging. Call once at startup.
+pub fn init() {
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(tracing_subscriber::fmt::layer())
+        .with(FileLayer)
+        .init();
+}
+
+/// Log panics with a backtrace so they end up in the log file.
+/// Chains to the previously installed hook.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        tracing::error!(
+            target: "panic",
+            location = %location,
+            thread = std::thread::current().name().unwrap_or("unnamed"),
+            backtrace = %std::backtrace::Backtrace::force_capture(),
+            "Panic: {}",
+            message
+        );
+
+        previous(info);
+    }));
+}
+
+/// Start writing logs to `dir`
+pub fn open_log_dir(dir: &Path) -> AppResult<()> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(LOG_FILE);
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let mut state = LOG_FILE_STATE.lock();
+    state.dir = Some(dir.to_path_buf());
+    state.file = Some(file);
+    state.size = size;
+    Ok(())
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    LOG_FILE_STATE.lock().dir.clone()
+}
+
+/// Most recent entries at or above `min_level`, newest first.
+/// Reads the active file first, then rotated files until `limit` is reached.
+pub fn recent_logs(min_level: Level, limit: usize) -> AppResult<Vec<LogEntry>> {
+    let Some(dir) = log_dir() else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries = Vec::new();
+    for path in log_files(&dir) {
+        let file = match File::open(&path) {
+            Ok(f) => f,
+            Err(_) => continue,
+        };
+
+        let mut file_entries: Vec<LogEntry> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<LogEntry>(&line).ok())
+            .filter(|entry| {
+                entry
+                    .level
+                    .parse::<Level>()
+                    .map(|level| level <= min_level)
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        file_entries.reverse();
+        entries.extend(file_entries);
+        if entries.len() >= limit {
+            break;
+        }
+    }
+
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Active file followed by rotated files, newest first
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![dir.join(LOG_FILE)];
+    for index in 1..=MAX_ROTATED_FILES {
+        files.push(dir.join(format!("{}.{}", LOG_FILE, index)));
+    }
+    files
+}
+
+fn rotate(state: &mut LogFile) {
+    let Some(dir) = state.dir.clone() else {
+        return;
+    };
+
+    state.file = None;
+    let files = log_files(&dir);
+    for index in (1..files.len()).rev() {
+        let _ = fs::rename(&files[index - 1], &files[index]);
+    }
+
+    state.file = OpenOptions::new().create(true).append(true).open(&files[0]).ok();
+    state.size = 0;
+}
+
+struct FileLayer;
+
+impl<S: Subscriber> Layer<S> for FileLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut state = LOG_FILE_STATE.lock();
+        if state.file.is_none() {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+
+        let Ok(line) = serde_json::to_string(&entry) else {
+            return;
+        };
+
+        if state.size + line.len() as u64 > MAX_LOG_BYTES {
+            rotate(&mut state);
+        }
+
+        if let Some(file) = state.file.as_mut() {
+            if writeln!(file, "{}", line).is_ok() {
+                state.size += line.len() as u64 + 1;
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, value.into());
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.insert(field, format!("{:?}", value).into());
+        }
+    }
+}
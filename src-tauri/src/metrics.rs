@@ -0,0 +1,123 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Accumulated timings for one phase ("scan.indexing", "export.bundle", ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseMetrics {
+    pub phase: String,
+    pub runs: u64,
+    pub total_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    pub avg_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub app_version: String,
+    /// Unix time the counters were last reset (or the app started)
+    pub since: i64,
+    pub phases: Vec<PhaseMetrics>,
+}
+
+#[derive(Default)]
+struct PhaseTotals {
+    runs: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+    last: Duration,
+}
+
+/// Per-phase timings for the current session
+pub struct PerfMetrics {
+    phases: Mutex<HashMap<&'static str, PhaseTotals>>,
+    since: Mutex<i64>,
+}
+
+impl Default for PerfMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerfMetrics {
+    pub fn new() -> Self {
+        Self {
+            phases: Mutex::new(HashMap::new()),
+            since: Mutex::new(chrono::Utc::now().timestamp()),
+        }
+    }
+
+    /// Enter a tracing span for `phase` and time it until the guard is dropped
+    pub fn time(&self, phase: &'static str) -> PhaseTimer<'_> {
+        PhaseTimer {
+            metrics: self,
+            phase,
+            started: Instant::now(),
+            _span: tracing::info_span!("phase", name = phase).entered(),
+        }
+    }
+
+    pub fn record(&self, phase: &'static str, elapsed: Duration) {
+        let mut phases = self.phases.lock();
+        let totals = phases.entry(phase).or_default();
+        totals.min = if totals.runs == 0 { elapsed } else { totals.min.min(elapsed) };
+        totals.max = totals.max.max(elapsed);
+        totals.last = elapsed;
+        totals.total += elapsed;
+        totals.runs += 1;
+    }
+
+    pub fn snapshot(&self) -> PerformanceMetrics {
+        let mut phases: Vec<PhaseMetrics> = self
+            .phases
+            .lock()
+            .iter()
+            .map(|(phase, t)| PhaseMetrics {
+                phase: phase.to_string(),
+                runs: t.runs,
+                total_ms: millis(t.total),
+                min_ms: millis(t.min),
+                max_ms: millis(t.max),
+                last_ms: millis(t.last),
+                avg_ms: millis(t.total) / t.runs.max(1) as f64,
+            })
+            .collect();
+        phases.sort_by(|a, b| a.phase.cmp(&b.phase));
+
+        PerformanceMetrics {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            since: *self.since.lock(),
+            phases,
+        }
+    }
+
+    pub fn reset(&self) {
+        self.phases.lock().clear();
+        *self.since.lock() = chrono::Utc::now().timestamp();
+    }
+}
+
+/// Records the elapsed time of its phase when dropped, including early returns
+pub struct PhaseTimer<'a> {
+    metrics: &'a PerfMetrics,
+    phase: &'static str,
+    started: Instant,
+    _span: tracing::span::EnteredSpan,
+}
+
+impl Drop for PhaseTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        tracing::debug!("{} took {:?}", self.phase, elapsed);
+        self.metrics.record(self.phase, elapsed);
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
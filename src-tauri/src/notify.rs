@@ -0,0 +1,134 @@
+use crate::error::{AppError, AppResult};
+use crate::jobs::JobStatus;
+use crate::state::AppState;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON body POSTed to the configured webhook
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// "scan.completed", "export.failed", ... or "test"
+    pub event: String,
+    pub app_version: String,
+    pub project_name: Option<String>,
+    pub project_root: Option<String>,
+    pub duration_secs: Option<i64>,
+    /// Final job status, including the job's result stats
+    pub job: Option<JobStatus>,
+}
+
+impl WebhookPayload {
+    pub fn new(event: &str, project_root: Option<String>, job: Option<JobStatus>) -> Self {
+        Self {
+            event: event.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            project_name: project_root.as_deref().and_then(project_name),
+            project_root,
+            duration_secs: job.as_ref().and_then(duration_secs),
+            job,
+        }
+    }
+}
+
+/// Announce a finished scan or export with an OS notification and the
+/// webhook, as configured. Other job kinds and cancelled jobs are ignored.
+pub fn job_finished(app: &AppHandle, job: &JobStatus) {
+    if !matches!(job.kind.as_str(), "scan" | "export") || job.state == "cancelled" {
+        return;
+    }
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let (notify, webhook_url, project_root) = {
+        let settings = state.settings.read();
+        (
+            settings.notify_on_complete,
+            settings.webhook_url.clone(),
+            settings.project_root.clone(),
+        )
+    };
+
+    if notify {
+        let (title, body) = describe(job);
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            tracing::warn!("Failed to show notification: {}", e);
+        }
+    }
+
+    if let Some(url) = webhook_url {
+        let payload = WebhookPayload::new(&format!("{}.{}", job.kind, job.state), project_root, Some(job.clone()));
+        // Never hold up the job thread on a slow endpoint
+        std::thread::spawn(move || {
+            if let Err(e) = post_webhook(&url, &payload) {
+                tracing::warn!("{}", e);
+            }
+        });
+    }
+}
+
+pub fn post_webhook(url: &str, payload: &WebhookPayload) -> AppResult<()> {
+    ureq::post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .send_json(payload)
+        .map_err(|e| AppError::Custom(format!("Webhook request to {} failed: {}", url, e)))?;
+    Ok(())
+}
+
+/// Notification title and body with the job's headline stats
+fn describe(job: &JobStatus) -> (String, String) {
+    let what = if job.kind == "scan" { "Scan" } else { "Export" };
+    if job.state == "failed" {
+        return (
+            format!("{} failed", what),
+            job.error.clone().unwrap_or_else(|| "Unknown error".to_string()),
+        );
+    }
+
+    let result = job.result.as_ref();
+    let count = |key: &str| result.and_then(|r| r.get(key)).and_then(|v| v.as_u64());
+    let mut body = match job.kind.as_str() {
+        "scan" => format!(
+            "{} files: {} new, {} changed",
+            count("total_files").unwrap_or(0),
+            count("added").unwrap_or(0),
+            count("changed").unwrap_or(0)
+        ),
+        _ => match result.and_then(|r| r.get("exported_files")).and_then(|v| v.as_array()) {
+            Some(files) => format!("{} files exported", files.len()),
+            None => match count("written") {
+                Some(written) => format!("{} notes written", written),
+                None => "Done".to_string(),
+            },
+        },
+    };
+    if let Some(secs) = duration_secs(job) {
+        body.push_str(&format!(" in {}", format_duration(secs)));
+    }
+
+    (format!("{} complete", what), body)
+}
+
+fn duration_secs(job: &JobStatus) -> Option<i64> {
+    job.finished_at.map(|finished| finished - job.started_at)
+}
+
+fn format_duration(secs: i64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+fn project_name(root: &str) -> Option<String> {
+    Path::new(root)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
@@ -0,0 +1,66 @@
+use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Present while the app runs; removed on a clean exit
+const SESSION_MARKER: &str = "session.lock";
+
+/// What the frontend needs to decide whether to offer safe mode, and
+/// whether the catalog is read-only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupStatus {
+    /// The previous session crashed or was killed before shutting down
+    pub previous_session_crashed: bool,
+    /// Unix time the crashed session started, if known
+    pub previous_session_started: Option<i64>,
+    pub safe_mode: bool,
+    /// A shared catalog is open; scans and other writes are refused
+    pub read_only: bool,
+    /// Configured shared catalog folder, even if it failed to open
+    pub shared_catalog_dir: Option<String>,
+    /// Why the shared catalog couldn't be opened (the local one is in use)
+    pub shared_catalog_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// Problems reported by `PRAGMA integrity_check`, empty when `ok`
+    pub messages: Vec<String>,
+}
+
+/// A previous session whose marker was still present at startup
+#[derive(Debug, Clone, Copy)]
+pub struct CrashedSession {
+    pub started_at: Option<i64>,
+}
+
+/// Marks the running session so the next startup can tell it didn't end cleanly
+pub struct SessionMarker {
+    path: PathBuf,
+}
+
+impl SessionMarker {
+    /// Write the marker, reporting a previous session that left its marker behind
+    pub fn begin(app_data_dir: &Path) -> (Self, Option<CrashedSession>) {
+        let path = app_data_dir.join(SESSION_MARKER);
+        let previous = fs::read_to_string(&path).ok().map(|content| CrashedSession {
+            started_at: content.trim().parse().ok(),
+        });
+
+        if let Err(e) = fs::write(&path, chrono::Utc::now().timestamp().to_string()) {
+            tracing::warn!("Failed to write session marker: {}", e);
+        }
+
+        (Self { path }, previous)
+    }
+
+    /// Remove the marker once shutdown has finished
+    pub fn end(&self) -> AppResult<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
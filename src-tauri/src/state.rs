@@ -1,17 +1,56 @@
-use crate::db::Database;
+use crate::api::{ApiServer, ApiStatus};
+use crate::changes::ChangeEmitter;
+use crate::db::{Database, FolderRollup, TypeCount};
 use crate::error::{AppError, AppResult};
+use crate::jobs::JobManager;
+use crate::metrics::PerfMetrics;
+use crate::plugins::PluginRegistry;
+use crate::session::{CrashedSession, SessionMarker, StartupStatus};
 use crate::settings::Settings;
-use parking_lot::RwLock;
+use crate::storage;
+use crate::telemetry::Telemetry;
+use crate::watcher::{ProjectWatcher, WatchOptions};
+use parking_lot::{Mutex, RwLock};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 
+/// How long exit waits for background jobs to notice the cancellation
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
 pub struct AppState {
     pub db: Arc<Database>,
     pub settings: Arc<RwLock<Settings>>,
-    pub app_handle: AppHandle,
     pub cancel_flag: Arc<AtomicBool>,
     pub scan_running: Arc<AtomicBool>,
+    pub jobs: Arc<JobManager>,
+    pub telemetry: Arc<Telemetry>,
+    pub metrics: Arc<PerfMetrics>,
+    /// Format plugins loaded at startup
+    pub plugins: Arc<PluginRegistry>,
+    pub app_data_dir: PathBuf,
+    /// Set by the user after a crash; background jobs that touch many files are refused
+    pub safe_mode: Arc<AtomicBool>,
+    /// Thumbnail cache folder resolved at startup
+    thumb_dir: PathBuf,
+    /// Why the configured shared catalog couldn't be opened; the local one is used instead
+    shared_catalog_error: Option<String>,
+    session: SessionMarker,
+    crashed_session: Option<CrashedSession>,
+    api: Mutex<Option<ApiServer>>,
+    api_error: Mutex<Option<String>>,
+    watcher: Mutex<Option<ProjectWatcher>>,
+    warm_rollups: Arc<Mutex<Option<WarmRollups>>>,
+}
+
+/// Whole-project rollups computed by the startup warm-up, each handed to
+/// the first request for it; later requests query fresh
+struct WarmRollups {
+    project_id: String,
+    type_counts: Option<Vec<TypeCount>>,
+    folders: Option<Vec<FolderRollup>>,
 }
 
 impl AppState {
@@ -23,31 +62,333 @@ impl AppState {
 
         std::fs::create_dir_all(&app_data_dir)?;
 
-        let db_path = app_data_dir.join("scythe.db");
-        let db = Database::new(&db_path)?;
-
         let settings_path = app_data_dir.join("settings.json");
-        let settings = Settings::load(&settings_path)?;
+        let mut settings = Settings::load(&settings_path)?;
+
+        // Finish a storage location change before anything opens the files
+        let had_pending_move = settings.pending_storage_move.is_some();
+        let rebase = storage::apply_pending_move(&mut settings, &app_data_dir);
+
+        let shared = settings.shared_catalog_dir.as_ref().map(|dir| {
+            let dir = PathBuf::from(dir);
+            Database::open_read_only(
+                &storage::shared_catalog_database(&dir),
+                &settings.db_options(),
+            )
+            .map(|db| (db, storage::shared_catalog_thumbnails(&dir)))
+        });
+        let (db, thumb_dir, shared_catalog_error) = match shared {
+            Some(Ok((db, thumb_dir))) => {
+                tracing::info!("Opened shared catalog {} read-only", db.path().display());
+                (db, thumb_dir, None)
+            }
+            other => {
+                let error = match other {
+                    Some(Err(e)) => {
+                        tracing::error!(
+                            "Failed to open shared catalog, using the local one: {}",
+                            e
+                        );
+                        Some(e.to_string())
+                    }
+                    _ => None,
+                };
+                let db_dir = storage::database_dir(&settings, &app_data_dir);
+                std::fs::create_dir_all(&db_dir)?;
+                let db =
+                    Database::new(&db_dir.join(storage::DATABASE_FILE), &settings.db_options())?;
+                if let Some(rebase) = rebase {
+                    db.rebase_thumbnail_paths(&rebase.from, &rebase.to)?;
+                }
+                (db, storage::thumbnail_dir(&settings, &app_data_dir), error)
+            }
+        };
+        if had_pending_move {
+            settings.save()?;
+        }
+
+        let (session, crashed_session) = SessionMarker::begin(&app_data_dir);
+        if crashed_session.is_some() {
+            tracing::warn!("The previous session did not shut down cleanly");
+        }
+
+        let telemetry = Telemetry::new(&app_data_dir, settings.telemetry_enabled);
+
+        let plugins = if settings.plugins_enabled {
+            PluginRegistry::load_dir(&storage::plugins_dir(&app_data_dir))
+        } else {
+            PluginRegistry::empty()
+        };
 
         Ok(Self {
             db: Arc::new(db),
             settings: Arc::new(RwLock::new(settings)),
-            app_handle,
             cancel_flag: Arc::new(AtomicBool::new(false)),
             scan_running: Arc::new(AtomicBool::new(false)),
+            jobs: Arc::new(JobManager::new()),
+            telemetry: Arc::new(telemetry),
+            metrics: Arc::new(PerfMetrics::new()),
+            plugins: Arc::new(plugins),
+            app_data_dir,
+            safe_mode: Arc::new(AtomicBool::new(false)),
+            thumb_dir,
+            shared_catalog_error,
+            session,
+            crashed_session,
+            api: Mutex::new(None),
+            api_error: Mutex::new(None),
+            watcher: Mutex::new(None),
+            warm_rollups: Arc::new(Mutex::new(None)),
         })
     }
 
-    pub fn thumbnail_dir(&self) -> AppResult<std::path::PathBuf> {
-        let app_data_dir = self
-            .app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| AppError::Custom(format!("Failed to get app data dir: {}", e)))?;
+    /// Warm the database cache for the current project and compute its type
+    /// counts and folder rollups on a background thread, so the first page,
+    /// search and sidebar don't wait on a cold cache
+    pub fn start_warm_up(&self) {
+        let project_root = {
+            let settings = self.settings.read();
+            if !settings.startup_warm_up {
+                return;
+            }
+            settings.project_root.clone()
+        };
+        let Some(project_root) = project_root else {
+            return;
+        };
 
-        let thumb_dir = app_data_dir.join("thumbnails");
-        std::fs::create_dir_all(&thumb_dir)?;
-        Ok(thumb_dir)
+        let db = Arc::clone(&self.db);
+        let metrics = Arc::clone(&self.metrics);
+        let scan_running = Arc::clone(&self.scan_running);
+        let warm_rollups = Arc::clone(&self.warm_rollups);
+        let spawned = std::thread::Builder::new()
+            .name("warm-up".to_string())
+            .spawn(move || {
+                let started = Instant::now();
+                let project = match db.get_project_by_path(&project_root) {
+                    Ok(Some(project)) => project,
+                    Ok(None) => return,
+                    Err(e) => {
+                        tracing::warn!("Warm-up skipped: {}", e);
+                        return;
+                    }
+                };
+                let warmed = db.warm_up(&project.id);
+                let rollups = db
+                    .get_type_counts(&project.id, None)
+                    .and_then(|type_counts| Ok((type_counts, db.get_folder_rollups(&project.id)?)));
+                match (warmed, rollups) {
+                    (Ok(connections), Ok((type_counts, folders))) => {
+                        // A scan that started meanwhile makes these stale
+                        if !scan_running.load(Ordering::SeqCst) {
+                            *warm_rollups.lock() = Some(WarmRollups {
+                                project_id: project.id,
+                                type_counts: Some(type_counts),
+                                folders: Some(folders),
+                            });
+                        }
+                        metrics.record("startup.warm_up", started.elapsed());
+                        tracing::info!(
+                            "Warmed {} database connection(s) in {:?}",
+                            connections,
+                            started.elapsed()
+                        );
+                    }
+                    (Err(e), _) | (_, Err(e)) => tracing::warn!("Warm-up failed: {}", e),
+                }
+            });
+        if let Err(e) = spawned {
+            tracing::warn!("Failed to start warm-up: {}", e);
+        }
+    }
+
+    /// Type counts from the warm-up, once, if they are for `project_id`
+    pub fn take_warm_type_counts(&self, project_id: &str) -> Option<Vec<TypeCount>> {
+        let mut warm = self.warm_rollups.lock();
+        warm.as_mut()
+            .filter(|w| w.project_id == project_id)?
+            .type_counts
+            .take()
+    }
+
+    /// Folder rollups from the warm-up, once, if they are for `project_id`
+    pub fn take_warm_folder_rollups(&self, project_id: &str) -> Option<Vec<FolderRollup>> {
+        let mut warm = self.warm_rollups.lock();
+        warm.as_mut()
+            .filter(|w| w.project_id == project_id)?
+            .folders
+            .take()
+    }
+
+    pub fn thumbnail_dir(&self) -> AppResult<PathBuf> {
+        // A shared catalog's folder may not be writable, and needn't be
+        if !self.db.is_read_only() {
+            std::fs::create_dir_all(&self.thumb_dir)?;
+        }
+        Ok(self.thumb_dir.clone())
+    }
+
+    /// Refuse operations that write to the catalog while a shared one is open
+    pub fn ensure_writable(&self) -> AppResult<()> {
+        if self.db.is_read_only() {
+            return Err(AppError::ReadOnlyCatalog);
+        }
+        Ok(())
+    }
+
+    pub fn startup_status(&self) -> StartupStatus {
+        StartupStatus {
+            previous_session_crashed: self.crashed_session.is_some(),
+            previous_session_started: self.crashed_session.and_then(|s| s.started_at),
+            safe_mode: self.is_safe_mode(),
+            read_only: self.db.is_read_only(),
+            shared_catalog_dir: self.settings.read().shared_catalog_dir.clone(),
+            shared_catalog_error: self.shared_catalog_error.clone(),
+        }
+    }
+
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode.load(Ordering::SeqCst)
+    }
+
+    /// Plugins for scans and thumbnails; none in safe mode, since a crashing
+    /// plugin takes the whole app down with it
+    pub fn active_plugins(&self) -> Option<Arc<PluginRegistry>> {
+        (!self.is_safe_mode() && !self.plugins.is_empty()).then(|| Arc::clone(&self.plugins))
+    }
+
+    /// Start, restart or stop the HTTP API to match the current settings
+    pub fn apply_api_settings(&self) {
+        let (enabled, port, token) = {
+            let mut settings = self.settings.write();
+            if settings.api_enabled && settings.api_token.is_none() {
+                settings.regenerate_api_token();
+                if let Err(e) = settings.save() {
+                    tracing::warn!("Failed to save API token: {}", e);
+                }
+            }
+            (
+                settings.api_enabled,
+                settings.api_port,
+                settings.api_token.clone().unwrap_or_default(),
+            )
+        };
+
+        let mut api = self.api.lock();
+        if enabled
+            && api
+                .as_ref()
+                .is_some_and(|server| server.matches(port, &token))
+        {
+            return;
+        }
+        if let Some(server) = api.take() {
+            server.stop();
+        }
+
+        let mut api_error = self.api_error.lock();
+        *api_error = None;
+        if enabled {
+            match ApiServer::start(
+                port,
+                token,
+                Arc::clone(&self.db),
+                Arc::clone(&self.settings),
+            ) {
+                Ok(server) => *api = Some(server),
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    *api_error = Some(e.to_string());
+                }
+            }
+        }
+    }
+
+    /// Make `root` the active project and move the file watcher to it. Every
+    /// project keeps its scan data in the catalog, so switching back is instant.
+    pub fn activate_project(&self, root: &str, app_handle: &AppHandle) -> AppResult<()> {
+        {
+            let mut settings = self.settings.write();
+            settings.activate_project(root);
+            settings.save()?;
+        }
+        self.apply_watch_settings(app_handle);
+        Ok(())
+    }
+
+    /// Start, restart or stop live indexing of the current project to match
+    /// the settings. A project is only watched once it has been scanned.
+    pub fn apply_watch_settings(&self, app_handle: &AppHandle) {
+        let (options, project_root) = {
+            let settings = self.settings.read();
+            let options = WatchOptions {
+                ignore_patterns: settings.ignore_patterns.clone(),
+                plugins: self.active_plugins(),
+                dependency_max_file_bytes: settings.dependency_max_file_bytes(),
+            };
+            (
+                options,
+                settings
+                    .project_root
+                    .clone()
+                    .filter(|_| settings.watch_project),
+            )
+        };
+        let project = match project_root {
+            Some(root) if !self.db.is_read_only() => match self.db.get_project_by_path(&root) {
+                Ok(project) => project.filter(|p| p.last_scan_time.is_some()),
+                Err(e) => {
+                    tracing::warn!("File watcher not started: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let mut watcher = self.watcher.lock();
+        if let (Some(project), Some(current)) = (&project, watcher.as_ref()) {
+            if current.matches(&project.id, &options.ignore_patterns) {
+                return;
+            }
+        }
+        // Dropping the current watcher stops it
+        *watcher = None;
+        let Some(project) = project else {
+            return;
+        };
+
+        let app_handle = app_handle.clone();
+        let project_id = project.id.clone();
+        let started = ProjectWatcher::start(
+            Arc::clone(&self.db),
+            &project,
+            options,
+            Arc::clone(&self.scan_running),
+            move |batch| {
+                let mut changes =
+                    ChangeEmitter::new(app_handle.clone(), &project_id, Duration::ZERO);
+                changes.assets_indexed(&batch.added, None);
+                changes.assets_updated(&batch.updated);
+                changes.assets_removed(batch.removed.iter().map(String::as_str));
+                changes.emit();
+            },
+        );
+        match started {
+            Ok(started) => *watcher = Some(started),
+            Err(e) => tracing::error!("Failed to start file watcher: {}", e),
+        }
+    }
+
+    pub fn api_status(&self) -> ApiStatus {
+        let settings = self.settings.read();
+        let api = self.api.lock();
+        ApiStatus {
+            enabled: settings.api_enabled,
+            running: api.is_some(),
+            url: api.as_ref().map(ApiServer::url),
+            token: settings.api_token.clone(),
+            error: self.api_error.lock().clone(),
+        }
     }
 
     pub fn request_cancel(&self) {
@@ -62,11 +403,69 @@ impl AppState {
         self.cancel_flag.store(false, Ordering::SeqCst);
     }
 
-    pub fn set_scan_running(&self, running: bool) {
-        self.scan_running.store(running, Ordering::SeqCst);
+    /// Atomically claim the scan lock. Returns `None` if a scan is already running.
+    /// The lock is released when the returned guard is dropped.
+    pub fn try_begin_scan(&self) -> Option<ScanLock> {
+        let lock = self
+            .scan_running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| ScanLock {
+                flag: Arc::clone(&self.scan_running),
+            })?;
+        // The scan changes what the warm-up counted
+        *self.warm_rollups.lock() = None;
+        Some(lock)
     }
 
     pub fn is_scan_running(&self) -> bool {
         self.scan_running.load(Ordering::SeqCst)
     }
+
+    /// Stop background work and leave the database and settings consistent on
+    /// disk. Called from the exit event, after the windows have closed.
+    pub fn shutdown(&self) {
+        self.request_cancel();
+        if let Some(server) = self.api.lock().take() {
+            server.stop();
+        }
+        self.watcher.lock().take();
+
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while self.jobs.active_count() > 0 || self.is_scan_running() {
+            if Instant::now() >= deadline {
+                tracing::warn!(
+                    "Exiting with {} job(s) still running",
+                    self.jobs.active_count()
+                );
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        if let Err(e) = self.db.checkpoint() {
+            tracing::warn!("Failed to checkpoint database on exit: {}", e);
+        }
+        if let Err(e) = self.settings.read().save() {
+            tracing::warn!("Failed to save settings on exit: {}", e);
+        }
+
+        if let Err(e) = self.session.end() {
+            tracing::warn!("Failed to remove session marker: {}", e);
+        }
+
+        tracing::info!("Shutdown complete");
+    }
+}
+
+/// Guard that keeps `scan_running` set for as long as it is alive,
+/// so a panicking scan task can't leave the lock held forever
+pub struct ScanLock {
+    flag: Arc<AtomicBool>,
+}
+
+impl Drop for ScanLock {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::SeqCst);
+    }
 }
@@ -0,0 +1,134 @@
+use crate::error::AppResult;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const TELEMETRY_FILE: &str = "telemetry.jsonl";
+/// The log is rotated to `telemetry.jsonl.old` once it grows past this
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// One anonymous usage record. `data` never contains paths or project names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryEvent {
+    pub timestamp: i64,
+    pub kind: String, // "scan", "panic"
+    pub app_version: String,
+    pub os: String,
+    pub data: serde_json::Value,
+}
+
+/// Opt-in usage metrics, appended to a local JSON-lines file. Nothing is
+/// recorded unless `telemetry_enabled` is set, and nothing leaves the machine
+/// unless the user exports the file.
+pub struct Telemetry {
+    path: PathBuf,
+    enabled: AtomicBool,
+    write_lock: Mutex<()>,
+}
+
+impl Telemetry {
+    pub fn new(app_data_dir: &Path, enabled: bool) -> Self {
+        Self {
+            path: app_data_dir.join(TELEMETRY_FILE),
+            enabled: AtomicBool::new(enabled),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Append an event. Failures are logged and otherwise ignored.
+    pub fn record(&self, kind: &str, data: serde_json::Value) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let event = TelemetryEvent {
+            timestamp: chrono::Utc::now().timestamp(),
+            kind: kind.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            data,
+        };
+
+        if let Err(e) = self.append(&event) {
+            tracing::warn!("Failed to record telemetry: {}", e);
+        }
+    }
+
+    fn append(&self, event: &TelemetryEvent) -> AppResult<()> {
+        let _guard = self.write_lock.lock();
+
+        if fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            fs::rename(&self.path, self.path.with_extension("jsonl.old"))?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    /// Most recent events, newest first
+    pub fn recent_events(&self, limit: usize) -> AppResult<Vec<TelemetryEvent>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut events: Vec<TelemetryEvent> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+
+        events.reverse();
+        events.truncate(limit);
+        Ok(events)
+    }
+
+    pub fn clear(&self) -> AppResult<()> {
+        let _guard = self.write_lock.lock();
+        for path in [self.path.clone(), self.path.with_extension("jsonl.old")] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Record where panics happen before the default hook runs. The panic message
+/// is left out since it can contain file paths from the user's project.
+pub fn install_panic_hook(telemetry: Arc<Telemetry>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+
+        telemetry.record(
+            "panic",
+            serde_json::json!({
+                "location": location,
+                "thread": std::thread::current().name().unwrap_or("unnamed"),
+            }),
+        );
+
+        previous(info);
+    }));
+}
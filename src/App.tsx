@@ -7,11 +7,12 @@ import { AssetGrid } from './components/AssetGrid';
 import { DetailPanel } from './components/DetailPanel';
 import { ScanStatus } from './components/ScanStatus';
 import { EmptyState } from './components/EmptyState';
-import type { ScanProgress, ThumbnailProgress } from './types';
+import type { AssetsChanged, AssetsCountEvent, ScanProgress, ThumbnailProgress } from './types';
 
 function App() {
-  const { project, loadSettings, updateScanProgress, updateThumbnailProgress, scanProgress, thumbnailProgress, selectedAssetId, refreshAssets, loadTypeCounts } = useStore();
-  const refreshPending = useRef(false);
+  const { project, loadSettings, updateScanProgress, updateThumbnailProgress, scanProgress, thumbnailProgress, selectedAssetId, refreshAssets, applyAssetsCount, loadTypeCounts } = useStore();
+  // Views to refresh once the current batch of change events settles
+  const refreshPending = useRef<{ grid: boolean; counts: boolean } | null>(null);
 
   useEffect(() => {
     loadSettings();
@@ -20,17 +21,34 @@ function App() {
       updateScanProgress(event.payload);
     });
 
-    // Listen for asset updates and refresh the grid
-    const unlistenAssets = listen<number>('assets-updated', () => {
+    // Listen for asset changes and refresh only the views they touch
+    const unlistenAssets = listen<AssetsChanged>('assets-updated', (event) => {
+      const change = event.payload;
+      const { project, selectedTypes } = useStore.getState();
+      if (change.project_id !== project?.id) return;
+
+      const indexed = change.added + change.updated + change.removed > 0;
+      // Unknown types (an empty list) may include the filtered ones
+      const matchesFilter =
+        selectedTypes.length === 0 ||
+        change.asset_types.length === 0 ||
+        change.asset_types.some((type) => (selectedTypes as string[]).includes(type));
+      const grid = change.thumbnails > 0 || (indexed && matchesFilter);
+      if (!grid && !indexed) return;
+
       // Debounce refreshes
-      if (!refreshPending.current) {
-        refreshPending.current = true;
-        setTimeout(() => {
-          refreshAssets();
-          loadTypeCounts();
-          refreshPending.current = false;
-        }, 100);
+      if (refreshPending.current) {
+        refreshPending.current.grid ||= grid;
+        refreshPending.current.counts ||= indexed;
+        return;
       }
+      refreshPending.current = { grid, counts: indexed };
+      setTimeout(() => {
+        const pending = refreshPending.current;
+        refreshPending.current = null;
+        if (pending?.grid) refreshAssets();
+        if (pending?.counts) loadTypeCounts();
+      }, 100);
     });
 
     // Listen for thumbnail generation progress
@@ -38,12 +56,18 @@ function App() {
       updateThumbnailProgress(event.payload);
     });
 
+    // Exact totals for large projects, counted after the first page is shown
+    const unlistenCount = listen<AssetsCountEvent>('assets-count', (event) => {
+      applyAssetsCount(event.payload);
+    });
+
     return () => {
       unlistenProgress.then(fn => fn());
       unlistenAssets.then(fn => fn());
       unlistenThumbnails.then(fn => fn());
+      unlistenCount.then(fn => fn());
     };
-  }, [loadSettings, updateScanProgress, updateThumbnailProgress, refreshAssets, loadTypeCounts]);
+  }, [loadSettings, updateScanProgress, updateThumbnailProgress, refreshAssets, applyAssetsCount, loadTypeCounts]);
 
   return (
     <div className="app">
@@ -54,12 +78,13 @@ function App() {
           <div className="loading-spinner" />
           <div className="text">
             {thumbnailProgress.phase === 'counting' && 'Counting assets...'}
-            {thumbnailProgress.phase === 'generating' && `Generating texture thumbnails... (${thumbnailProgress.generated}/${thumbnailProgress.total})`}
-            {thumbnailProgress.phase === 'generating_models' && `Generating model thumbnails... (${thumbnailProgress.generated}/${thumbnailProgress.total})`}
+            {thumbnailProgress.phase === 'generating' && `Generating texture thumbnails... (${thumbnailProgress.processed}/${thumbnailProgress.total})`}
+            {thumbnailProgress.phase === 'paused' && `Thumbnail generation paused (${thumbnailProgress.processed}/${thumbnailProgress.total ?? 0})`}
+            {thumbnailProgress.phase === 'generating_models' && `Generating model thumbnails... (${thumbnailProgress.processed}/${thumbnailProgress.total})`}
           </div>
-          {thumbnailProgress.total > 0 && (
+          {!!thumbnailProgress.total && (
             <div className="progress-bar" style={{ flex: 1, maxWidth: '200px' }}>
-              <div className="fill" style={{ width: `${Math.round((thumbnailProgress.generated / thumbnailProgress.total) * 100)}%` }} />
+              <div className="fill" style={{ width: `${Math.round((thumbnailProgress.processed / thumbnailProgress.total) * 100)}%` }} />
             </div>
           )}
         </div>
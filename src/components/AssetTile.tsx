@@ -16,9 +16,11 @@ const TYPE_ICONS: Record<AssetType, string> = {
   material: '🎨',
   prefab: '🧩',
   audio: '🔊',
+  video: '🎞️',
   shader: '✨',
   scene: '🎬',
   scriptable_object: '📜',
+  script: '📝',
   unknown: '📄',
 };
 
@@ -33,7 +35,11 @@ export const AssetTile = memo(function AssetTile({ asset, selected, onClick }: A
     setTooLarge(false);
     setUnsupported(false);
 
-    if (asset.asset_type === 'texture' || asset.asset_type === 'material') {
+    // Models Blender rendered have a backend thumbnail; the rest fall back to WebGL
+    const hasRenderedModel = asset.asset_type === 'model' && asset.thumbnail_path !== null
+      && asset.thumbnail_path !== 'UNSUPPORTED' && asset.thumbnail_path !== 'TOO_LARGE';
+
+    if (asset.asset_type === 'texture' || asset.asset_type === 'material' || hasRenderedModel) {
       // Load texture/material thumbnails from backend (with persistent caching)
       setLoading(true);
       getThumbnail(asset.id, asset.modified_time)
@@ -68,7 +74,7 @@ export const AssetTile = memo(function AssetTile({ asset, selected, onClick }: A
     return () => {
       cancelled = true;
     };
-  }, [asset.id, asset.asset_type, asset.absolute_path, asset.extension, asset.modified_time]);
+  }, [asset.id, asset.asset_type, asset.absolute_path, asset.extension, asset.modified_time, asset.thumbnail_path]);
 
   return (
     <div
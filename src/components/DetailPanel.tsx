@@ -2,7 +2,7 @@ import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useStore } from '../state/store';
 import { ModelPreview } from './ModelPreview';
-import type { AssetType, MaterialInfo, ModelInfo, BundlePreview } from '../types';
+import type { AssetType, MaterialInfo, MediaInfo, ModelInfo, BundlePreview, TextureEstimate, LocalizedKey } from '../types';
 
 const TYPE_ICONS: Record<AssetType, string> = {
   texture: '🖼️',
@@ -10,9 +10,11 @@ const TYPE_ICONS: Record<AssetType, string> = {
   material: '🎨',
   prefab: '🧩',
   audio: '🔊',
+  video: '🎞️',
   shader: '✨',
   scene: '🎬',
   scriptable_object: '📜',
+  script: '📝',
   unknown: '📄',
 };
 
@@ -46,6 +48,9 @@ export function DetailPanel() {
 
   const [materialInfo, setMaterialInfo] = useState<MaterialInfo | null>(null);
   const [modelInfo, setModelInfo] = useState<ModelInfo | null>(null);
+  const [mediaInfo, setMediaInfo] = useState<MediaInfo | null>(null);
+  const [textureEstimate, setTextureEstimate] = useState<TextureEstimate | null>(null);
+  const [localizedKeys, setLocalizedKeys] = useState<LocalizedKey[]>([]);
   const [bundlePreview, setBundlePreview] = useState<BundlePreview | null>(null);
   const [imgSrc, setImgSrc] = useState<string | null>(null);
   const [showBundleDetails, setShowBundleDetails] = useState(false);
@@ -54,6 +59,9 @@ export function DetailPanel() {
     if (!selectedAsset) {
       setMaterialInfo(null);
       setModelInfo(null);
+      setMediaInfo(null);
+      setTextureEstimate(null);
+      setLocalizedKeys([]);
       setBundlePreview(null);
       setImgSrc(null);
       return;
@@ -86,6 +94,29 @@ export function DetailPanel() {
       setModelInfo(null);
     }
 
+    // Load audio/video info (needs ffprobe configured)
+    if (selectedAsset.asset_type === 'audio' || selectedAsset.asset_type === 'video') {
+      invoke<MediaInfo | null>('get_media_info', { assetId: selectedAsset.id })
+        .then(setMediaInfo)
+        .catch(() => setMediaInfo(null));
+    } else {
+      setMediaInfo(null);
+    }
+
+    // Estimate the in-build texture size from its import settings
+    if (selectedAsset.asset_type === 'texture') {
+      invoke<TextureEstimate | null>('get_texture_estimate', { assetId: selectedAsset.id })
+        .then(setTextureEstimate)
+        .catch(() => setTextureEstimate(null));
+    } else {
+      setTextureEstimate(null);
+    }
+
+    // Localization keys this asset is a locale variant of
+    invoke<LocalizedKey[]>('get_asset_localization', { assetId: selectedAsset.id })
+      .then(setLocalizedKeys)
+      .catch(() => setLocalizedKeys([]));
+
     // Load bundle preview
     invoke<BundlePreview>('get_bundle_preview', { assetId: selectedAsset.id })
       .then(setBundlePreview)
@@ -175,6 +206,23 @@ export function DetailPanel() {
           <span className="label">Size</span>
           <span className="value">{formatBytes(selectedAsset.size_bytes)}</span>
         </div>
+        {selectedAsset.build_size_bytes !== undefined && (
+          <div className="detail-row">
+            <span className="label">Build size</span>
+            <span className="value">{formatBytes(selectedAsset.build_size_bytes)}</span>
+          </div>
+        )}
+        {textureEstimate && (
+          <div className="detail-row">
+            <span className="label">Estimated</span>
+            <span
+              className="value"
+              title={`${textureEstimate.build_width}×${textureEstimate.build_height} ${textureEstimate.format}${textureEstimate.crunched ? ' (Crunch)' : ''}${textureEstimate.mipmaps ? ', mipmaps' : ''}`}
+            >
+              ~{formatBytes(textureEstimate.estimated_bytes)}
+            </span>
+          </div>
+        )}
         <div className="detail-row">
           <span className="label">Modified</span>
           <span className="value">{formatDate(selectedAsset.modified_time)}</span>
@@ -222,6 +270,55 @@ export function DetailPanel() {
         </div>
       )}
 
+      {/* Media Info */}
+      {mediaInfo && (
+        <div className="detail-section">
+          <h3>Media</h3>
+          {mediaInfo.duration_secs !== null && (
+            <div className="detail-row">
+              <span className="label">Duration</span>
+              <span className="value">{mediaInfo.duration_secs.toFixed(2)} s</span>
+            </div>
+          )}
+          {mediaInfo.codec !== null && (
+            <div className="detail-row">
+              <span className="label">Codec</span>
+              <span className="value">{mediaInfo.codec}</span>
+            </div>
+          )}
+          {mediaInfo.bit_rate !== null && (
+            <div className="detail-row">
+              <span className="label">Bit rate</span>
+              <span className="value">{Math.round(mediaInfo.bit_rate / 1000)} kb/s</span>
+            </div>
+          )}
+          {mediaInfo.sample_rate !== null && (
+            <div className="detail-row">
+              <span className="label">Sample rate</span>
+              <span className="value">{formatNumber(mediaInfo.sample_rate)} Hz</span>
+            </div>
+          )}
+          {mediaInfo.channels !== null && (
+            <div className="detail-row">
+              <span className="label">Channels</span>
+              <span className="value">{mediaInfo.channels}</span>
+            </div>
+          )}
+          {mediaInfo.width !== null && mediaInfo.height !== null && (
+            <div className="detail-row">
+              <span className="label">Resolution</span>
+              <span className="value">{mediaInfo.width} × {mediaInfo.height}</span>
+            </div>
+          )}
+          {mediaInfo.frame_rate !== null && (
+            <div className="detail-row">
+              <span className="label">Frame rate</span>
+              <span className="value">{mediaInfo.frame_rate.toFixed(2)} fps</span>
+            </div>
+          )}
+        </div>
+      )}
+
       {/* Material Info */}
       {materialInfo && (
         <div className="detail-section">
@@ -296,6 +393,33 @@ export function DetailPanel() {
         </div>
       )}
 
+      {localizedKeys.length > 0 && (
+        <div className="detail-section">
+          <h3>Localization</h3>
+          {localizedKeys.map((key) => (
+            <div key={`${key.collection}/${key.key_id}`} style={{ marginBottom: '8px' }}>
+              <div className="detail-row">
+                <span className="label">{key.collection}</span>
+                <span className="value" title={key.key}>{key.key}</span>
+              </div>
+              <div className="dependency-list">
+                {key.locales.map((entry) => (
+                  <div
+                    key={entry.locale}
+                    className="dependency-item"
+                    onClick={() => entry.asset_id && entry.asset_id !== selectedAsset.id && selectAsset(entry.asset_id)}
+                    style={{ opacity: entry.asset_id ? 1 : 0.5 }}
+                    title={entry.guid ?? undefined}
+                  >
+                    {entry.locale}: {entry.relative_path ?? entry.guid ?? 'missing'}
+                  </div>
+                ))}
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+
       {dependencies.length > 0 && (
         <div className="detail-section">
           <h3>Dependencies ({dependencies.length})</h3>
@@ -1,9 +1,10 @@
 import { useState, useCallback, useEffect, useRef } from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useStore } from '../state/store';
+import type { AssetSort } from '../types';
 
 export function Header() {
-  const { projectRoot, outputFolder, setProjectRoot, setOutputFolder, searchQuery, search, regenerateDatabase, cancelRegeneration, project, isRegenerating, scanProgress, thumbnailProgress } = useStore();
+  const { projectRoot, outputFolder, setProjectRoot, setOutputFolder, searchQuery, search, sort, setSort, regenerateDatabase, cancelRegeneration, project, isRegenerating, scanProgress, thumbnailProgress } = useStore();
   const [localSearch, setLocalSearch] = useState(searchQuery);
   const debounceRef = useRef<ReturnType<typeof setTimeout>>();
 
@@ -82,6 +83,19 @@ export function Header() {
             onChange={handleSearch}
           />
 
+          <select
+            className="input sort-select"
+            value={sort}
+            onChange={(e) => setSort(e.target.value as AssetSort)}
+            title="Sort assets"
+          >
+            <option value="name">Name</option>
+            <option value="size">Size on disk</option>
+            <option value="build_size">Build size</option>
+            <option value="estimated_size">Estimated size</option>
+            <option value="modified">Last modified</option>
+          </select>
+
           <button
             className={`btn ${isWorking ? 'btn-danger' : 'btn-secondary'}`}
             onClick={handleButtonClick}
@@ -6,25 +6,35 @@ interface ScanStatusProps {
 
 export function ScanStatus({ progress }: ScanStatusProps) {
   const percentage = progress.total
-    ? Math.round((progress.scanned / progress.total) * 100)
+    ? Math.round((progress.processed / progress.total) * 100)
     : 0;
 
-  const phaseLabels: Record<ScanProgress['phase'], string> = {
+  const phaseLabels: Partial<Record<ScanProgress['phase'], string>> = {
+    starting: 'Starting scan...',
     counting: 'Counting files...',
     walking: 'Discovering files...',
     indexing: 'Indexing assets...',
     dependencies: 'Resolving dependencies...',
+    estimating: 'Estimating texture sizes...',
+    localizing: 'Reading localization tables...',
     thumbnails: 'Generating thumbnails...',
     complete: 'Complete',
+    paused: 'Paused',
     cancelled: 'Cancelled',
+    failed: 'Failed',
   };
 
+  const eta = progress.eta_secs != null && progress.eta_secs >= 1
+    ? ` — ~${formatEta(progress.eta_secs)} left`
+    : '';
+
   return (
     <div className="scan-status">
       <div className="loading-spinner" />
       <div className="text">
         {phaseLabels[progress.phase]}
-        {progress.total && ` (${progress.scanned}/${progress.total})`}
+        {progress.total && ` (${progress.processed}/${progress.total})`}
+        {eta}
       </div>
       {progress.total && (
         <div className="progress-bar" style={{ flex: 1, maxWidth: '200px' }}>
@@ -39,3 +49,10 @@ export function ScanStatus({ progress }: ScanStatusProps) {
     </div>
   );
 }
+
+function formatEta(seconds: number): string {
+  if (seconds < 60) return `${Math.round(seconds)}s`;
+  const minutes = Math.floor(seconds / 60);
+  if (minutes < 60) return `${minutes}m ${Math.round(seconds % 60)}s`;
+  return `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
@@ -7,9 +7,11 @@ const ASSET_TYPES: { type: AssetType; label: string }[] = [
   { type: 'material', label: 'Materials' },
   { type: 'prefab', label: 'Prefabs' },
   { type: 'audio', label: 'Audio' },
+  { type: 'video', label: 'Video' },
   { type: 'shader', label: 'Shaders' },
   { type: 'scene', label: 'Scenes' },
   { type: 'scriptable_object', label: 'ScriptableObjects' },
+  { type: 'script', label: 'Scripts' },
 ];
 
 export function Sidebar() {
@@ -57,6 +57,46 @@ async function saveToIndexedDB(key: string, value: string): Promise<void> {
   }
 }
 
+// Backend fetches requested within one frame go out as a single
+// get_thumbnails_base64 call, so a page of tiles mounting together (each
+// after its own IndexedDB miss) costs one round trip instead of one each
+const FETCH_BATCH_WINDOW_MS = 16;
+const MAX_FETCH_BATCH = 60;
+
+interface QueuedFetch {
+  assetId: string;
+  resolve: (data: string | null) => void;
+  reject: (error: unknown) => void;
+}
+
+let fetchQueue: QueuedFetch[] = [];
+
+function fetchThumbnail(assetId: string): Promise<string | null> {
+  return new Promise((resolve, reject) => {
+    fetchQueue.push({ assetId, resolve, reject });
+    if (fetchQueue.length === 1) {
+      setTimeout(flushFetchQueue, FETCH_BATCH_WINDOW_MS);
+    }
+  });
+}
+
+async function flushFetchQueue(): Promise<void> {
+  const queue = fetchQueue;
+  fetchQueue = [];
+
+  for (let i = 0; i < queue.length; i += MAX_FETCH_BATCH) {
+    const batch = queue.slice(i, i + MAX_FETCH_BATCH);
+    try {
+      const results = await invoke<Record<string, string | null>>('get_thumbnails_base64', {
+        assetIds: batch.map(({ assetId }) => assetId),
+      });
+      batch.forEach(({ assetId, resolve }) => resolve(results[assetId] ?? null));
+    } catch (error) {
+      batch.forEach(({ reject }) => reject(error));
+    }
+  }
+}
+
 function getCacheKey(assetId: string, modifiedTime?: number): string {
   return modifiedTime ? `${assetId}_${modifiedTime}` : assetId;
 }
@@ -84,7 +124,7 @@ export async function getThumbnail(assetId: string, modifiedTime?: number): Prom
     }
 
     // L3: Fetch from backend
-    const result = await invoke<string | null>('get_thumbnail_base64', { assetId });
+    const result = await fetchThumbnail(assetId);
 
     // Store in both caches
     memoryCache.set(cacheKey, result);
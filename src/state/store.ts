@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Asset, AssetType, ScanProgress, ThumbnailProgress, ModelAssetInfo, Project, TypeCount, Dependency } from '../types';
+import type { AppError, Asset, AssetCursor, AssetsCountEvent, AssetsResponse, AssetSort, AssetType, ScanProgress, ThumbnailProgress, ProgressEvent, ProgressPhase, ModelAssetInfo, Project, TypeCount, Dependency, StartupStatus, IntegrityReport } from '../types';
 import { generateAllModelThumbnails } from '../services/modelThumbnailCache';
 
 interface AppState {
@@ -12,6 +12,10 @@ interface AppState {
   // Assets
   assets: Asset[];
   totalCount: number;
+  // False while a large project's count is an estimate
+  totalExact: boolean;
+  // Next page of a large project, which is paged by cursor
+  nextCursor: AssetCursor | null;
   isLoading: boolean;
   scanProgress: ScanProgress | null;
   thumbnailProgress: ThumbnailProgress | null;
@@ -20,9 +24,13 @@ interface AppState {
   // Regeneration state
   isRegenerating: boolean;
 
+  // Set after a crash; automatic scans and thumbnail jobs are skipped
+  safeMode: boolean;
+
   // Filters
   searchQuery: string;
   selectedTypes: AssetType[];
+  sort: AssetSort;
   page: number;
   pageSize: number;
 
@@ -39,6 +47,7 @@ interface AppState {
   loadMoreAssets: () => Promise<void>;
   search: (query: string) => void;
   toggleTypeFilter: (type: AssetType) => void;
+  setSort: (sort: AssetSort) => void;
   selectAsset: (id: string | null) => Promise<void>;
   startScan: () => Promise<void>;
   updateScanProgress: (progress: ScanProgress) => void;
@@ -50,22 +59,72 @@ interface AppState {
   exportBundle: (id: string) => Promise<void>;
   loadSettings: () => Promise<void>;
   refreshAssets: () => Promise<void>;
+  applyAssetsCount: (event: AssetsCountEvent) => void;
   loadTypeCounts: () => Promise<void>;
 }
 
+/** Placeholder progress shown before the backend's first event arrives */
+function localProgress(
+  kind: ProgressEvent['kind'],
+  phase: ProgressPhase,
+  processed = 0,
+  total: number | null = null,
+): ProgressEvent {
+  return {
+    job_id: '',
+    kind,
+    phase,
+    processed,
+    total,
+    current_path: null,
+    items_per_sec: null,
+    eta_secs: null,
+    skipped: null,
+    added: null,
+    changed: null,
+  };
+}
+
+/** Whether a command was rejected because a scan is already running */
+function isScanRunning(error: unknown): boolean {
+  return (error as AppError | null)?.code === 'SCAN_RUNNING';
+}
+
+/**
+ * totalCount after a page of get_assets. An estimate never drops below what
+ * is loaded (plus one while another page exists), and an exact count that
+ * already arrived for this query is kept for its later pages.
+ */
+function pageTotal(
+  result: AssetsResponse,
+  loaded: number,
+  previous: Pick<AppState, 'totalCount' | 'totalExact'> | null,
+): Pick<AppState, 'totalCount' | 'totalExact'> {
+  if (result.total_exact) return { totalCount: result.total, totalExact: true };
+  if (previous?.totalExact) return previous;
+  return {
+    totalCount: Math.max(result.total, loaded + (result.next_cursor ? 1 : 0)),
+    totalExact: false,
+  };
+}
+
 export const useStore = create<AppState>((set, get) => ({
   project: null,
   projectRoot: null,
   outputFolder: null,
   assets: [],
   totalCount: 0,
+  totalExact: true,
+  nextCursor: null,
   isLoading: false,
   scanProgress: null,
   thumbnailProgress: null,
   typeCounts: [],
   isRegenerating: false,
+  safeMode: false,
   searchQuery: '',
   selectedTypes: [],
+  sort: 'name',
   page: 0,
   pageSize: 50,
   selectedAssetId: null,
@@ -77,7 +136,9 @@ export const useStore = create<AppState>((set, get) => ({
     try {
       const project = await invoke<Project>('set_project_root', { path });
       set({ project, projectRoot: path, assets: [], totalCount: 0, page: 0 });
-      await get().startScan();
+      if (!get().safeMode) {
+        await get().startScan();
+      }
     } catch (error) {
       console.error('Failed to set project root:', error);
     }
@@ -93,22 +154,26 @@ export const useStore = create<AppState>((set, get) => ({
   },
 
   loadAssets: async () => {
-    const { project, searchQuery, selectedTypes, page, pageSize } = get();
+    const { project, searchQuery, selectedTypes, sort, page, pageSize, nextCursor } = get();
     if (!project) return;
 
     set({ isLoading: true });
     try {
-      const result = await invoke<{ assets: Asset[]; total: number }>('get_assets', {
+      const result = await invoke<AssetsResponse>('get_assets', {
         projectId: project.id,
         searchQuery: searchQuery || null,
         assetTypes: selectedTypes.length > 0 ? selectedTypes : null,
+        sort,
         page,
         pageSize,
+        cursor: page === 0 ? null : nextCursor,
       });
 
+      const assets = page === 0 ? result.assets : [...get().assets, ...result.assets];
       set({
-        assets: page === 0 ? result.assets : [...get().assets, ...result.assets],
-        totalCount: result.total,
+        assets,
+        ...pageTotal(result, assets.length, page === 0 ? null : get()),
+        nextCursor: result.next_cursor,
         isLoading: false
       });
     } catch (error) {
@@ -118,9 +183,10 @@ export const useStore = create<AppState>((set, get) => ({
   },
 
   loadMoreAssets: async () => {
-    const { page, totalCount, assets, isLoading } = get();
+    const { page, totalCount, totalExact, nextCursor, assets, isLoading } = get();
     // Don't load more if already loading or if we have all assets
     if (isLoading || assets.length >= totalCount) return;
+    if (!totalExact && !nextCursor) return;
 
     set({ page: page + 1 });
     await get().loadAssets();
@@ -142,6 +208,11 @@ export const useStore = create<AppState>((set, get) => ({
     get().loadAssets();
   },
 
+  setSort: (sort: AssetSort) => {
+    set({ sort, page: 0, isLoading: true });
+    get().loadAssets();
+  },
+
   selectAsset: async (id: string | null) => {
     set({ selectedAssetId: id });
 
@@ -163,15 +234,16 @@ export const useStore = create<AppState>((set, get) => ({
   },
 
   startScan: async () => {
-    const { project } = get();
+    const { project, scanProgress } = get();
     if (!project) return;
 
-    set({ scanProgress: { scanned: 0, total: null, current_path: '', phase: 'walking' } });
+    set({ scanProgress: localProgress('scan', 'walking') });
     try {
-      await invoke('start_scan', { projectId: project.id });
+      await invoke('start_scan', { projectId: project.id, mode: 'quick' });
     } catch (error) {
       console.error('Failed to start scan:', error);
-      set({ scanProgress: null });
+      // The scan that is already running keeps its progress
+      set({ scanProgress: isScanRunning(error) ? scanProgress : null });
     }
   },
 
@@ -184,11 +256,13 @@ export const useStore = create<AppState>((set, get) => ({
       get().loadTypeCounts();
 
       // If this was a full regeneration, continue with thumbnail generation
-      const { isRegenerating } = get();
-      if (isRegenerating) {
+      const { isRegenerating, safeMode } = get();
+      if (isRegenerating && !safeMode) {
         get().regenerateThumbnails();
+      } else {
+        set({ isRegenerating: false });
       }
-    } else if (progress.phase === 'cancelled') {
+    } else if (progress.phase === 'cancelled' || progress.phase === 'failed') {
       set({ scanProgress: null, isRegenerating: false });
       get().loadAssets();
       get().loadTypeCounts();
@@ -196,17 +270,21 @@ export const useStore = create<AppState>((set, get) => ({
   },
 
   regenerateDatabase: async () => {
-    const { project } = get();
+    const { project, scanProgress, isRegenerating } = get();
     if (!project) return;
 
     // Set flag to trigger thumbnail generation after scan completes
     set({ isRegenerating: true });
-    set({ scanProgress: { scanned: 0, total: null, current_path: '', phase: 'counting' } });
+    set({ scanProgress: localProgress('scan', 'counting') });
     try {
-      await invoke('start_scan', { projectId: project.id });
+      await invoke('start_scan', { projectId: project.id, mode: 'full' });
     } catch (error) {
       console.error('Failed to start scan:', error);
-      set({ scanProgress: null, isRegenerating: false });
+      if (isScanRunning(error)) {
+        set({ scanProgress, isRegenerating });
+      } else {
+        set({ scanProgress: null, isRegenerating: false });
+      }
     }
   },
 
@@ -222,7 +300,7 @@ export const useStore = create<AppState>((set, get) => ({
     const { project } = get();
     if (!project) return;
 
-    set({ thumbnailProgress: { generated: 0, total: 0, phase: 'counting' } });
+    set({ thumbnailProgress: localProgress('thumbnails', 'counting') });
     try {
       await invoke('regenerate_thumbnails', { projectId: project.id });
     } catch (error) {
@@ -234,8 +312,8 @@ export const useStore = create<AppState>((set, get) => ({
   updateThumbnailProgress: async (progress: ThumbnailProgress) => {
     set({ thumbnailProgress: progress });
 
-    // Handle cancellation
-    if (progress.phase === 'cancelled') {
+    // Handle cancellation and failure
+    if (progress.phase === 'cancelled' || progress.phase === 'failed') {
       set({ thumbnailProgress: null, isRegenerating: false });
       return;
     }
@@ -250,7 +328,7 @@ export const useStore = create<AppState>((set, get) => ({
 
       try {
         // Fetch model assets
-        set({ thumbnailProgress: { generated: 0, total: 0, phase: 'generating_models' } });
+        set({ thumbnailProgress: localProgress('thumbnails', 'generating_models') });
         const modelAssets = await invoke<ModelAssetInfo[]>('get_model_assets_for_thumbnails', { projectId: project.id });
 
         if (modelAssets.length === 0) {
@@ -260,7 +338,7 @@ export const useStore = create<AppState>((set, get) => ({
 
         // Generate model thumbnails client-side
         await generateAllModelThumbnails(modelAssets, (generated, total) => {
-          set({ thumbnailProgress: { generated, total, phase: 'generating_models' } });
+          set({ thumbnailProgress: localProgress('thumbnails', 'generating_models', generated, total) });
         });
 
         set({ thumbnailProgress: null, isRegenerating: false });
@@ -301,6 +379,19 @@ export const useStore = create<AppState>((set, get) => ({
 
   loadSettings: async () => {
     try {
+      const status = await invoke<StartupStatus>('get_startup_status');
+      if (
+        status.previous_session_crashed &&
+        window.confirm('Scythe did not shut down cleanly last time. Start in safe mode?')
+      ) {
+        await invoke('set_safe_mode', { enabled: true });
+        set({ safeMode: true });
+        const report = await invoke<IntegrityReport>('check_database_integrity');
+        if (!report.ok) {
+          console.error('Database integrity check failed:', report.messages);
+        }
+      }
+
       const settings = await invoke<{ project_root: string | null; output_folder: string | null }>('get_settings');
       set({
         projectRoot: settings.project_root,
@@ -321,22 +412,24 @@ export const useStore = create<AppState>((set, get) => ({
   },
 
   refreshAssets: async () => {
-    const { project, searchQuery, selectedTypes, pageSize } = get();
+    const { project, searchQuery, selectedTypes, sort, pageSize } = get();
     if (!project) return;
 
     // Don't clear assets - just fetch fresh data
     try {
-      const result = await invoke<{ assets: Asset[]; total: number }>('get_assets', {
+      const result = await invoke<AssetsResponse>('get_assets', {
         projectId: project.id,
         searchQuery: searchQuery || null,
         assetTypes: selectedTypes.length > 0 ? selectedTypes : null,
+        sort,
         page: 0,
         pageSize,
       });
 
       set({
         assets: result.assets,
-        totalCount: result.total,
+        ...pageTotal(result, result.assets.length, null),
+        nextCursor: result.next_cursor,
         page: 0,
       });
     } catch (error) {
@@ -344,6 +437,19 @@ export const useStore = create<AppState>((set, get) => ({
     }
   },
 
+  applyAssetsCount: (event: AssetsCountEvent) => {
+    const { project, searchQuery, selectedTypes } = get();
+    // Counts for a query the user has since changed are stale
+    if (
+      event.project_id !== project?.id ||
+      event.search_query !== (searchQuery || null) ||
+      JSON.stringify(event.asset_types) !== JSON.stringify(selectedTypes.length > 0 ? selectedTypes : null)
+    ) {
+      return;
+    }
+    set({ totalCount: event.total, totalExact: true });
+  },
+
   loadTypeCounts: async () => {
     const { project } = get();
     if (!project) return;
@@ -4,9 +4,11 @@ export type AssetType =
   | 'material'
   | 'prefab'
   | 'audio'
+  | 'video'
   | 'shader'
   | 'scene'
   | 'scriptable_object'
+  | 'script'
   | 'unknown';
 
 export interface Asset {
@@ -23,6 +25,136 @@ export interface Asset {
   unity_guid: string | null;
   import_type: string | null;
   thumbnail_path: string | null;
+  /** Present when the project is in a git repository or Plastic workspace */
+  vcs?: VcsInfo;
+  /** Size in the last imported player build report */
+  build_size_bytes?: number;
+  /** Present when an export delivered the asset */
+  delivery?: DeliveryBadge;
+}
+
+/** Grid-sized slice of an Asset, from get_asset_summaries */
+export interface AssetSummary {
+  id: string;
+  file_name: string;
+  asset_type: AssetType;
+  size_bytes: number;
+  has_thumbnail: boolean;
+  /** Relative path with middle folders elided */
+  short_path: string;
+  delivery?: DeliveryBadge;
+}
+
+/** Where the next large-project page of get_assets starts; pass back unchanged */
+export interface AssetCursor {
+  key: number;
+  file_name: string;
+  id: string;
+}
+
+/** Result of get_assets */
+export interface AssetsResponse {
+  assets: Asset[];
+  /** Estimated when total_exact is false; the exact count follows in assets-count */
+  total: number;
+  total_exact: boolean;
+  next_cursor: AssetCursor | null;
+}
+
+/** Payload of the assets-updated event: what changed since the previous one */
+export interface AssetsChanged {
+  project_id: string;
+  added: number;
+  updated: number;
+  removed: number;
+  /** Assets that got a new thumbnail */
+  thumbnails: number;
+  /** Folders (two levels deep) and types of the added, updated and removed assets; empty when unknown */
+  folders: string[];
+  asset_types: string[];
+}
+
+/** Payload of the assets-count event */
+export interface AssetsCountEvent {
+  project_id: string;
+  search_query: string | null;
+  asset_types: string[] | null;
+  total: number;
+}
+
+/** `sort` argument of get_assets */
+export type AssetSort = 'name' | 'size' | 'build_size' | 'estimated_size' | 'modified';
+
+export type VcsStatus = 'modified' | 'added' | 'deleted' | 'renamed' | 'untracked' | 'conflicted';
+
+/** `vcs_filter` argument of get_assets */
+export type VcsFilter = 'modified' | 'untracked' | 'changed' | 'branch' | 'lfs_pointer';
+
+/** `delivery_filter` argument of get_assets */
+export type DeliveryFilter = 'delivered' | 'not_delivered' | 'changed_since';
+
+export interface DeliveryBadge {
+  destinations: number;
+  last_delivered_at: number;
+  /** No destination got the asset's current version */
+  changed_since: boolean;
+}
+
+/** One file an export wrote, from get_deliveries */
+export interface Delivery {
+  asset_id: string;
+  relative_path: string;
+  /** Export folder, or the archive written into it */
+  destination: string;
+  /** File written, or the entry inside the archive */
+  path: string;
+  archive: boolean;
+  content_hash: string;
+  source_size: number;
+  source_modified_time: number;
+  exported_at: number;
+  verified_at: number | null;
+  status: 'current' | 'modified' | 'missing' | null;
+  /** The asset on disk is still the exported version */
+  current_version: boolean;
+}
+
+export interface DeliveryCheck {
+  checked: number;
+  current: number;
+  modified: number;
+  missing: number;
+  deliveries: Delivery[];
+}
+
+export interface CommitInfo {
+  id: string;
+  summary: string;
+  author: string;
+  email: string | null;
+  time: number;
+}
+
+export type VcsProvider = 'git' | 'plastic';
+
+export interface VcsInfo {
+  provider: VcsProvider;
+  status: VcsStatus | null;
+  changed_on_branch: boolean;
+  last_commit: CommitInfo | null;
+  /** Plastic user holding an exclusive checkout on the file */
+  locked_by: string | null;
+  lfs: boolean;
+  /** LFS content not pulled; the file on disk is only a pointer */
+  lfs_pointer: boolean;
+}
+
+export interface VcsRepoInfo {
+  provider: VcsProvider;
+  root: string;
+  branch: string | null;
+  base_branch: string | null;
+  head: string | null;
 }
 
 export interface Dependency {
@@ -42,21 +174,63 @@ export interface Project {
   file_count: number;
 }
 
-export interface ScanProgress {
-  scanned: number;
+/** A registered project from `list_projects` */
+export interface ProjectListEntry extends Project {
+  kind: 'project' | 'library';
+  active: boolean;
+}
+
+export type ProgressPhase =
+  | 'starting'
+  | 'counting'
+  | 'walking'
+  | 'indexing'
+  | 'dependencies'
+  | 'estimating'
+  | 'localizing'
+  | 'thumbnails'
+  | 'generating'
+  | 'generating_models'
+  | 'exporting'
+  | 'paused'
+  | 'complete'
+  | 'cancelled'
+  | 'failed';
+
+/** Progress event shared by scans, thumbnail generation and exports */
+export interface ProgressEvent {
+  job_id: string;
+  kind: 'scan' | 'thumbnails' | 'export';
+  phase: ProgressPhase;
+  processed: number;
   total: number | null;
-  current_path: string;
-  phase: 'counting' | 'walking' | 'indexing' | 'dependencies' | 'thumbnails' | 'complete' | 'cancelled';
+  current_path: string | null;
+  items_per_sec: number | null;
+  eta_secs: number | null;
   /** Number of unchanged files skipped during re-scan */
   skipped: number | null;
-  /** Number of new or changed files processed */
+  /** Number of files indexed for the first time */
+  added: number | null;
+  /** Number of previously indexed files that changed on disk */
   changed: number | null;
 }
 
-export interface ThumbnailProgress {
-  generated: number;
-  total: number;
-  phase: 'counting' | 'generating' | 'generating_models' | 'complete' | 'cancelled';
+export type ScanProgress = ProgressEvent;
+export type ThumbnailProgress = ProgressEvent;
+
+export interface JobStatus {
+  id: string;
+  kind: 'scan' | 'thumbnails' | 'export';
+  state: 'running' | 'completed' | 'failed' | 'cancelled';
+  phase: string;
+  processed: number;
+  total: number | null;
+  /** Estimated seconds remaining in the current phase */
+  eta_secs: number | null;
+  error: string | null;
+  result: unknown;
+  started_at: number;
+  finished_at: number | null;
 }
 
 export interface ModelAssetInfo {
@@ -74,23 +248,277 @@ export interface AssetFilter {
 }
 
 export interface AppSettings {
+  version: number;
+  /** The active project, one of `project_roots` */
   project_root: string | null;
+  /** Every project folder added, in the order added */
+  project_roots: string[];
+  /** Reference folders added as libraries; scanned and searchable, never active */
+  library_roots: string[];
   output_folder: string | null;
   ignore_patterns: string[];
   thumbnail_size: number;
+  thumbnail_format: 'png' | 'webp' | 'jpeg';
+  /** JPEG quality, 1-100 (WebP is lossless) */
+  thumbnail_quality: number;
+  /** Images over either budget are marked TOO_LARGE instead of decoded */
+  max_decode_pixels: number;
+  max_image_file_mb: number;
+  /** Failed thumbnail attempts before an asset is skipped until it changes */
+  thumbnail_max_attempts: number;
+  /** Asset types thumbnailed first, in order; unlisted types come after */
+  thumbnail_type_priority: string[];
   scan_on_focus: boolean;
+  /** Index file changes live once the project has been scanned */
+  watch_project: boolean;
+  /** Opt-in anonymous usage metrics, stored locally */
+  telemetry_enabled: boolean;
+  /** Files indexed per transaction during a scan */
+  scan_batch_size: number;
+  /** Minimum interval between assets-updated events */
+  ui_refresh_ms: number;
+  /** Quick scans skip files in directories whose mtime is unchanged; misses in-place edits */
+  directory_change_detection: boolean;
+  /** Asset count from which the browser pages by cursor with an estimated total; 0 always counts */
+  large_project_threshold: number;
+  /** Read connection pool size and database lock wait, applied on restart */
+  db_pool_size: number;
+  db_busy_timeout_ms: number;
+  /** Storage tuning for huge catalogs, applied on restart; a page size change rebuilds the database */
+  db_mmap_mb: number;
+  db_page_size: number;
+  db_cache_mb: number;
+  /** Warm the database cache for the current project in the background on startup */
+  startup_warm_up: boolean;
+  /** Worker thread counts; null means automatic (based on CPU count) */
+  scan_threads: number | null;
+  dependency_threads: number | null;
+  thumbnail_threads: number | null;
+  /** Dependency parsing reads at most this many MB of each file; 0 means no limit */
+  dependency_max_file_mb: number;
+  /** Read-only HTTP API on 127.0.0.1, authenticated with a bearer token */
+  api_enabled: boolean;
+  api_port: number;
+  api_token: string | null;
+  /** OS notification and optional webhook POST when scans or exports finish */
+  notify_on_complete: boolean;
+  webhook_url: string | null;
+  /** Load format plugins from the plugins folder; applied on restart */
+  plugins_enabled: boolean;
+  asset_store_cache_dir: string | null;
+  blender_path: string | null;
+  ffprobe_path: string | null;
+  texture_converters: TextureConverter[];
+  /** Per-folder triangle limits for the LOD report; the first match applies */
+  triangle_budgets: TriangleBudget[];
+  /** Models with at least this many triangles are expected to have LODs */
+  lod_min_triangles: number;
+  /** Feature folders that shouldn't reference each other, e.g. `Assets/Features/*` */
+  dependency_boundaries: string[];
+  /** Launched by open_in_code_editor; null until one is chosen */
+  code_editor: CodeEditor | null;
+  export_defaults: ExportOptions;
+  /** Custom storage folders; null uses the app data directory */
+  database_dir: string | null;
+  thumbnail_dir: string | null;
+  /** Published team catalog opened read-only; applied on restart */
+  shared_catalog_dir: string | null;
+}
+
+/** Shareable settings without machine-specific paths */
+export interface SettingsProfile {
+  version: number;
+  ignore_patterns: string[] | null;
+  thumbnail_size: number | null;
+  thumbnail_format: 'png' | 'webp' | 'jpeg' | null;
+  thumbnail_quality: number | null;
+  scan_on_focus: boolean | null;
+  watch_project: boolean | null;
+  export_defaults: ExportOptions | null;
+  triangle_budgets: TriangleBudget[] | null;
+  lod_min_triangles: number | null;
+  dependency_boundaries: string[] | null;
+}
+
+export interface LogEntry {
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+}
+
+export interface TelemetryEvent {
+  timestamp: number;
+  kind: 'scan' | 'panic';
+  app_version: string;
+  os: string;
+  data: Record<string, unknown>;
+}
+
+export interface StorageLocations {
+  database_dir: string;
+  thumbnail_dir: string;
+  /** Set when a move is pending until the next restart */
+  pending_database_dir: string | null;
+  pending_thumbnail_dir: string | null;
+}
+
+export interface PublishedCatalog {
+  database_path: string;
+  thumbnails_copied: number;
+  thumbnails_removed: number;
+}
+
+export type SettingsPatch = Partial<
+  Omit<
+    AppSettings,
+    'version' | 'project_root' | 'database_dir' | 'thumbnail_dir' | 'api_token' | 'shared_catalog_dir'
+  >
+>;
+
+export interface IgnorePatternsResult {
+  invalid: { pattern: string; reason: string }[];
+  newly_ignored_count: number;
+  newly_ignored_sample: string[];
+  pruned: number;
+  saved: boolean;
+}
+
+/** A file kept out of the index until it is included again */
+export interface ExcludedAsset {
+  relative_path: string;
+  excluded_at: number;
+}
+
+/** A reference from an asset that stays to one being deleted */
+export interface BrokenReference {
+  from_asset_id: string;
+  from_relative_path: string;
+  to_asset_id: string;
+  to_relative_path: string;
+  relation_type: string;
+}
+
+export interface TrashedAsset {
+  relative_path: string;
+  absolute_path: string;
+}
+
+/** Assets deleted to the OS trash together, undone together */
+export interface TrashOperation {
+  id: string;
+  project_id: string;
+  deleted_at: number;
+  restored_at: number | null;
+  assets: TrashedAsset[];
+}
+
+export interface DeleteAssetsResult {
+  /** References the deletion breaks, or would break before it's confirmed */
+  broken: BrokenReference[];
+  /** Set once files were moved to the trash */
+  operation: TrashOperation | null;
+  /** Assets whose files couldn't be moved; they stay in the index */
+  failed: { relative_path: string; error: string }[];
+}
+
+export interface ExportOptions {
+  max_depth: number;
+  conflict_policy: 'overwrite' | 'skip' | 'rename' | 'fail';
+  include_meta: boolean;
+  archive_format: 'folder' | 'zip' | 'unitypackage';
+}
+
+export interface AssetListFilter {
+  search_query: string | null;
+  asset_types: AssetType[] | null;
+}
+
+export interface AssetListExport {
+  path: string;
+  format: 'csv' | 'json';
+  rows: number;
+}
+
+export interface AttributionExport {
+  path: string;
+  rows: number;
+  /** Rows without a license field */
+  unlicensed: number;
 }
 
 export interface ExportResult {
   success: boolean;
   exported_files: string[];
   manifest_path: string | null;
+  /** Files left alone by the "skip" conflict policy */
+  skipped_files: string[];
+  archive_path: string | null;
   error: string | null;
 }
 
+export interface ExternalFileMatch {
+  asset: AssetSummary;
+  relative_path: string;
+  /** Same bytes, or an image with a close perceptual hash */
+  kind: 'identical' | 'similar';
+  /** Bits the image hashes differ by; 0 for identical files */
+  distance: number;
+}
+
+/** Result of find_external_file */
+export interface ExternalFileReport {
+  path: string;
+  size_bytes: number;
+  sha256: string;
+  /** False when the file isn't a decodable image, so only identical copies were looked for */
+  compared_images: boolean;
+  matches: ExternalFileMatch[];
+}
+
+/** One suggestion from get_similar_assets */
+export interface SimilarAsset {
+  asset: AssetSummary;
+  relative_path: string;
+  /** 0 to 1, higher is more alike */
+  score: number;
+  reasons: ('name' | 'folder' | 'dimensions' | 'shader' | 'image')[];
+  /** Bits the image hashes differ by, for textures with one */
+  image_distance: number | null;
+}
+
+/** Result of stage_assets_for_drag: files to hand to a drag-out */
+export interface DragStage {
+  /** Temp folder holding the staged files */
+  path: string;
+  /** Staged assets in the order given; .meta files sit beside them */
+  files: string[];
+}
+
 export interface TypeCount {
   asset_type: AssetType;
   count: number;
+  total_bytes: number;
+}
+
+/** Assets under a folder two levels deep (`Assets/Art`), from get_folder_rollups */
+export interface FolderRollup {
+  /** Empty for files at the project root */
+  folder: string;
+  asset_count: number;
+  total_bytes: number;
+}
+
+export interface GrowthPoint {
+  scanned_at: number;
+  mode: 'quick' | 'full';
+  total_count: number;
+  total_bytes: number;
+  /** Change since the previous point; the first point counts from zero */
+  delta_count: number;
+  delta_bytes: number;
+  types: TypeCount[];
 }
 
 export interface MaterialTexture {
@@ -104,6 +532,156 @@ export interface MaterialInfo {
   textures: MaterialTexture[];
 }
 
+export type PluginCapability = 'classify' | 'dependencies' | 'metadata' | 'preview';
+
+export interface PluginManifest {
+  name: string;
+  version: string;
+  extensions: string[];
+  capabilities: PluginCapability[];
+}
+
+/** A library in the plugins folder; `error` is set when it failed to load */
+export interface PluginStatus {
+  path: string;
+  manifest: PluginManifest | null;
+  error: string | null;
+}
+
+export interface PluginMetadata {
+  plugin: string;
+  data: Record<string, unknown>;
+}
+
+export type RuleSeverity = 'error' | 'warning' | 'info';
+
+export interface RuleInfo {
+  name: string;
+  severity: RuleSeverity;
+  path: string;
+  error: string | null;
+}
+
+export interface RuleError {
+  rule: string;
+  message: string;
+}
+
+export interface RuleRunSummary {
+  rules: number;
+  assets_checked: number;
+  violations: number;
+  errors: RuleError[];
+}
+
+export interface RuleViolation {
+  asset_id: string;
+  relative_path: string;
+  asset_type: string;
+  rule: string;
+  severity: RuleSeverity;
+  message: string;
+  created_at: number;
+}
+
+export interface RuleViolationCount {
+  rule: string;
+  severity: RuleSeverity;
+  count: number;
+}
+
+export interface RuleViolationReport {
+  counts: RuleViolationCount[];
+  violations: RuleViolation[];
+}
+
+/** Code editor launcher; args may use {file}, {line} and {column} */
+export interface CodeEditor {
+  name: string;
+  command: string;
+  args: string[];
+}
+
+/** External texture decoder; args may use {input}, {output} and {output_dir} */
+export interface TextureConverter {
+  name: string;
+  extensions: string[];
+  command: string;
+  args: string[];
+}
+
+export interface MediaInfo {
+  duration_secs: number | null;
+  format: string | null;
+  codec: string | null;
+  bit_rate: number | null;
+  sample_rate: number | null;
+  channels: number | null;
+  width: number | null;
+  height: number | null;
+  frame_rate: number | null;
+}
+
+/** Levels of an audio clip, measured by the ffmpeg beside ffprobe */
+export interface AudioLevels {
+  /** dBFS; null for digital silence */
+  peak_db: number | null;
+  rms_db: number | null;
+  loudness_lufs: number | null;
+  clipped_samples: number;
+  silence_secs: number;
+  leading_silence_secs: number;
+  trailing_silence_secs: number;
+}
+
+export interface AudioLevelsEntry extends AudioLevels {
+  asset_id: string;
+  relative_path: string;
+  duration_secs: number | null;
+}
+
+export interface AudioQaThresholds {
+  peak_db: number;
+  min_clipped_samples: number;
+  edge_silence_secs: number;
+  silence_ratio: number;
+}
+
+export interface AudioQaIssue {
+  issue: 'clipping' | 'peak_above' | 'excessive_silence';
+  count: number;
+  /** Worst first */
+  assets: AudioLevelsEntry[];
+}
+
+export interface AudioQaReport {
+  analyzed: number;
+  not_analyzed: number;
+  thresholds: AudioQaThresholds;
+  issues: AudioQaIssue[];
+}
+
+/** Format named by a file's leading bytes */
+export interface FileSignature {
+  format: string;
+  /** Extensions the format is normally saved with; empty when any goes */
+  extensions: string[];
+}
+
+/** Result of get_asset_hex_preview */
+export interface HexPreview {
+  offset: number;
+  /** 16 bytes per line: offset, hex and printable ASCII */
+  lines: string[];
+  bytes_read: number;
+  total_bytes: number;
+  signature: FileSignature | null;
+  /** The header belongs to another format than the extension says */
+  extension_mismatch: boolean;
+  /** The first bytes are all zero, typical of a truncated or zeroed file */
+  header_zeroed: boolean;
+}
+
 export interface ModelInfo {
   vertex_count: number | null;
   triangle_count: number | null;
@@ -113,6 +691,68 @@ export interface ModelInfo {
   bounds: [number, number, number, number, number, number] | null;
 }
 
+export interface TriangleBudget {
+  category: string;
+  /** Folder or file prefix relative to the project root */
+  path_prefix: string;
+  max_triangles: number;
+}
+
+export interface LodModel {
+  asset_id: string;
+  relative_path: string;
+  triangle_count: number | null;
+}
+
+export interface LodGroup {
+  name: string;
+  source: 'suffix' | 'embedded' | 'lod_group';
+  levels: number[];
+  models: LodModel[];
+}
+
+export interface BudgetOverrun extends LodModel {
+  category: string;
+  max_triangles: number;
+}
+
+export interface LodReport {
+  models: number;
+  /** Models the parsers couldn't count triangles for */
+  uncounted: number;
+  groups: LodGroup[];
+  missing_lods: LodModel[];
+  missing_lods_count: number;
+  over_budget: BudgetOverrun[];
+  over_budget_count: number;
+}
+
+export interface SceneCost {
+  asset_id: string;
+  relative_path: string;
+  /** Unique assets in the closure, the scene file included */
+  asset_count: number;
+  size_bytes: number;
+  build_bytes: number;
+  /** Assets no other scene reaches */
+  exclusive_count: number;
+  exclusive_size_bytes: number;
+  exclusive_build_bytes: number;
+  shared_count: number;
+  shared_size_bytes: number;
+  shared_build_bytes: number;
+}
+
+export interface SceneCostReport {
+  /** Largest in-build cost first */
+  scenes: SceneCost[];
+  asset_count: number;
+  size_bytes: number;
+  build_bytes: number;
+  /** Assets sized from a build report or texture estimate */
+  build_sized_count: number;
+}
+
 export interface BundleAssetInfo {
   id: string;
   file_name: string;
@@ -126,3 +766,612 @@ export interface BundlePreview {
   dependencies: BundleAssetInfo[];
   total_size_bytes: number;
 }
+
+export interface GraphNode {
+  id: string;
+  file_name: string;
+  relative_path: string;
+  asset_type: AssetType;
+  size_bytes: number;
+  has_thumbnail: boolean;
+  depth: number;
+  missing_references: number;
+}
+
+export interface GraphEdge {
+  from: string;
+  to: string;
+  relation_type: string;
+}
+
+export interface DependencyGraph {
+  root_id: string;
+  nodes: GraphNode[];
+  edges: GraphEdge[];
+  truncated: boolean;
+}
+
+/** Result of an `export_markdown_notes` job */
+export interface MarkdownExport {
+  folder: string;
+  total: number;
+  written: number;
+  unchanged: number;
+  completed: boolean;
+}
+
+export interface SnapshotStats {
+  asset_count: number;
+  dependency_count: number;
+  thumbnail_count: number;
+  total_bytes: number;
+  type_counts: TypeCount[];
+}
+
+/** Returned by get_snapshot_info */
+export interface SnapshotManifest {
+  format: string;
+  version: number;
+  app_version: string;
+  created_at: number;
+  project: Project;
+  stats: SnapshotStats;
+}
+
+/** Result of the export_project_snapshot job */
+export interface SnapshotExport {
+  path: string;
+  stats: SnapshotStats;
+  completed: boolean;
+}
+
+/** Result of the import_project_snapshot job */
+export interface SnapshotImport {
+  project: Project | null;
+  stats: SnapshotStats;
+  completed: boolean;
+}
+
+export interface GraphExport {
+  path: string;
+  nodes: number;
+  edges: number;
+  truncated: boolean;
+}
+
+export interface SearchHit {
+  asset_id: string;
+  relative_path: string;
+  line_number: number;
+  line: string;
+}
+
+export interface FileSearchResult {
+  hits: SearchHit[];
+  files_searched: number;
+  files_matched: number;
+  truncated: boolean;
+}
+
+export type AppErrorCode =
+  | 'DB_BUSY'
+  | 'DATABASE'
+  | 'IO'
+  | 'IMAGE'
+  | 'JSON'
+  | 'INVALID_PROJECT'
+  | 'ASSET_NOT_FOUND'
+  | 'SCAN_RUNNING'
+  | 'INVALID_ARGUMENT'
+  | 'NOT_FOUND'
+  | 'EXPORT_CONFLICT'
+  | 'INTERNAL';
+
+/** Shape of every error rejected by a backend command */
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details: Record<string, unknown> | null;
+}
+
+export interface ThumbnailFailure {
+  asset_id: string;
+  relative_path: string;
+  attempts: number;
+  last_error: string;
+  last_attempt_at: number;
+}
+
+export interface ThumbnailRetryResult {
+  retried: number;
+  recovered: number;
+  still_failing: number;
+}
+
+export interface PhaseMetrics {
+  phase: string;
+  runs: number;
+  total_ms: number;
+  min_ms: number;
+  max_ms: number;
+  last_ms: number;
+  avg_ms: number;
+}
+
+export interface PerformanceMetrics {
+  app_version: string;
+  since: number;
+  phases: PhaseMetrics[];
+}
+
+/** Step names: full_scan, incremental_scan, dependencies, thumbnails, query_* */
+export interface BenchmarkStep {
+  name: string;
+  duration_ms: number;
+  /** Files, assets, thumbnails or query runs */
+  items: number;
+  items_per_sec: number;
+}
+
+/** Result of run_benchmark */
+export interface BenchmarkReport {
+  app_version: string;
+  project_id: string;
+  asset_count: number;
+  started_at: number;
+  total_ms: number;
+  steps: BenchmarkStep[];
+  /** False when cancelled part way */
+  completed: boolean;
+}
+
+export interface StartupStatus {
+  previous_session_crashed: boolean;
+  previous_session_started: number | null;
+  safe_mode: boolean;
+  /** A shared catalog is open; scans and other writes are refused */
+  read_only: boolean;
+  shared_catalog_dir: string | null;
+  /** Why the shared catalog failed to open; the local one is in use */
+  shared_catalog_error: string | null;
+}
+
+export interface IntegrityReport {
+  ok: boolean;
+  messages: string[];
+}
+
+export interface ApiStatus {
+  enabled: boolean;
+  running: boolean;
+  /** Base URL while running, e.g. http://127.0.0.1:7878/api/v1 */
+  url: string | null;
+  token: string | null;
+  /** Why the server failed to start (usually the port is taken) */
+  error: string | null;
+}
+
+export interface StorePackage {
+  id: string;
+  path: string;
+  publisher: string;
+  category: string;
+  name: string;
+  size_bytes: number;
+  modified_time: number;
+  file_count: number;
+  indexed_at: number;
+}
+
+export interface StoreFile {
+  package_id: string;
+  guid: string;
+  path: string;
+  file_name: string;
+  extension: string;
+  asset_type: string;
+  size_bytes: number;
+}
+
+export interface StoreFileHit {
+  package_id: string;
+  package_name: string;
+  publisher: string;
+  guid: string;
+  path: string;
+  file_name: string;
+  asset_type: string;
+  size_bytes: number;
+}
+
+export interface StoreIndexStats {
+  cache_dir: string;
+  packages: number;
+  indexed: number;
+  unchanged: number;
+  removed: number;
+  files: number;
+  failed: { path: string; message: string }[];
+  completed: boolean;
+}
+
+export interface StoreExtraction {
+  dest_dir: string;
+  files: string[];
+  missing: string[];
+}
+
+export interface Provenance {
+  project_id: string;
+  /** Path of the copy in the project */
+  relative_path: string;
+  /** Set once a scan has indexed the copy */
+  asset_id: string | null;
+  source_kind: 'library' | 'asset_store';
+  /** Library project id or Asset Store package id */
+  source_id: string;
+  source_name: string;
+  /** Path in the library, or the package's import path */
+  source_path: string;
+  /** SHA-256 of the file as copied */
+  content_hash: string;
+  source_size: number;
+  source_modified_time: number;
+  imported_at: number;
+}
+
+export interface SourceUpdate {
+  provenance: Provenance;
+  status: 'updated' | 'source_missing';
+  /** SHA-256 of the source's current version */
+  source_hash: string | null;
+  source_modified_time: number | null;
+  /** The copy was edited after it was copied in; re-importing discards that */
+  local_changes: boolean;
+  copy_missing: boolean;
+}
+
+export interface UpdateCheck {
+  checked: number;
+  current: number;
+  updated: number;
+  source_missing: number;
+  /** Every copy that isn't current, updated ones first */
+  updates: SourceUpdate[];
+}
+
+export interface LibraryCopy {
+  /** Folder inside the active project the files went to */
+  dest_dir: string;
+  /** Paths written, relative to `dest_dir` */
+  copied_files: string[];
+  skipped_files: string[];
+  generated_metas: number;
+  errors: string[];
+}
+
+export interface BuiltBundle {
+  name: string;
+  group_name: string;
+  size_bytes: number;
+  compression: string | null;
+  asset_count: number;
+}
+
+export interface BundledAsset {
+  bundle: string;
+  asset_path: string;
+  guid: string | null;
+  asset_id: string | null;
+  size_bytes: number;
+  explicit: boolean;
+}
+
+export interface AssetBuildCost {
+  asset_id: string | null;
+  asset_path: string;
+  asset_type: string | null;
+  bundle_count: number;
+  size_bytes: number;
+  /** Estimated share of the compressed bundle files */
+  download_bytes: number;
+  explicit: boolean;
+}
+
+export interface BuildLayoutReport {
+  bundles: BuiltBundle[];
+  assets: AssetBuildCost[];
+}
+
+export interface BuildLayoutImport {
+  path: string;
+  bundles: number;
+  assets: number;
+  matched: number;
+  unmatched: string[];
+}
+
+export interface BuildReportImport {
+  path: string;
+  build_size_bytes: number | null;
+  assets: number;
+  matched: number;
+  unmatched: string[];
+}
+
+export interface TextureEstimate {
+  width: number;
+  height: number;
+  build_width: number;
+  build_height: number;
+  max_size: number;
+  format: string;
+  crunched: boolean;
+  mipmaps: boolean;
+  estimated_bytes: number;
+}
+
+export interface TextureEstimateEntry extends TextureEstimate {
+  asset_id: string;
+  relative_path: string;
+  size_bytes: number;
+}
+
+export interface TextureHealthIssue {
+  issue: 'non_power_of_two' | 'over_max_size' | 'uncompressed' | 'opaque_alpha';
+  count: number;
+  /** Source bytes of every texture with the issue */
+  total_bytes: number;
+  /** Largest first, up to the requested limit */
+  textures: TextureEstimateEntry[];
+}
+
+export interface TextureHealthReport {
+  /** Textures with importer settings */
+  checked: number;
+  alpha_checked: number;
+  issues: TextureHealthIssue[];
+}
+
+/** One change applied to every targeted asset by `bulk_edit_assets` */
+export type BulkEdit =
+  | { op: 'add_tag'; tag: string }
+  | { op: 'remove_tag'; tag: string }
+  /** No value clears the field */
+  | { op: 'set_field'; key: string; value: string | null };
+
+export interface BulkEditResult {
+  matched: number;
+  /** Of those, the ones the edit changed */
+  changed: number;
+}
+
+export interface AssetTags {
+  tags: string[];
+  fields: Record<string, string>;
+}
+
+export interface TagCount {
+  tag: string;
+  count: number;
+}
+
+export interface SearchSuggestion {
+  /** What to put in the search box */
+  text: string;
+  kind: 'recent' | 'folder' | 'file' | 'term';
+  /** Assets it matches; 0 for recent searches */
+  count: number;
+}
+
+export interface ValidationItem {
+  /** The paths involved, e.g. both assets sharing a GUID */
+  paths: string[];
+  detail: string | null;
+}
+
+export interface ValidationCheck {
+  check: 'missing_meta' | 'duplicate_guids' | 'case_collisions' | 'broken_references' | 'empty_folders';
+  severity: 'error' | 'warning';
+  count: number;
+  items: ValidationItem[];
+}
+
+export interface ValidationReport {
+  project_id: string;
+  checked_at: number;
+  unity_project: boolean;
+  assets_checked: number;
+  checks: ValidationCheck[];
+}
+
+export type RenderPipeline = 'builtin' | 'urp' | 'hdrp';
+
+export interface MaterialPipeline {
+  asset_id: string;
+  relative_path: string;
+  shader_name: string | null;
+  /** The shader's file, when it is in the project */
+  shader_path: string | null;
+  /** Empty when the shader couldn't be resolved */
+  pipelines: RenderPipeline[];
+  /** Assets referencing the material directly */
+  dependents: number;
+}
+
+export interface PipelineFolder {
+  folder: string;
+  materials: number;
+  breaking: number;
+  unresolved: number;
+}
+
+export interface RenderPipelineReport {
+  project_id: string;
+  target: RenderPipeline;
+  materials_checked: number;
+  /** Keyed by pipeline, or 'unknown' */
+  by_pipeline: Record<string, number>;
+  breaking_count: number;
+  breaking: MaterialPipeline[];
+  unresolved_count: number;
+  unresolved: MaterialPipeline[];
+  affected_assets: number;
+  folders: PipelineFolder[];
+}
+
+export interface BoundarySummary {
+  /** The boundary folder, e.g. `Assets/Features/Combat` */
+  folder: string;
+  assets: number;
+  outgoing: number;
+  incoming: number;
+}
+
+export interface BoundaryPair {
+  from_boundary: string;
+  to_boundary: string;
+  count: number;
+}
+
+export interface BoundaryViolation {
+  from_boundary: string;
+  from_path: string;
+  to_boundary: string;
+  to_path: string;
+  relation_type: string;
+}
+
+export interface BoundaryReport {
+  patterns: string[];
+  boundaries: BoundarySummary[];
+  violation_count: number;
+  pairs: BoundaryPair[];
+  violations: BoundaryViolation[];
+}
+
+export interface LibraryPathMismatch {
+  guid: string;
+  /** Where Unity last saw the GUID */
+  library_path: string;
+  /** Where the `.meta` holding it is now */
+  index_path: string;
+}
+
+export interface LibraryOnlyEntry {
+  guid: string;
+  /** Not known for the older `Library/metadata` layout */
+  path: string | null;
+  /** The file is on disk, so the scanner should have indexed it */
+  on_disk: boolean;
+}
+
+export interface IndexOnlyEntry {
+  guid: string;
+  relative_path: string;
+}
+
+export interface LibraryInspection {
+  library_dir: string;
+  /** null when the project has no readable Library */
+  format: 'source_asset_db' | 'metadata' | null;
+  library_modified_at: number | null;
+  library_entries: number;
+  matched: number;
+  path_mismatch_count: number;
+  path_mismatches: LibraryPathMismatch[];
+  library_only_count: number;
+  library_only: LibraryOnlyEntry[];
+  index_only_count: number;
+  index_only: IndexOnlyEntry[];
+}
+
+export interface ComparedAsset {
+  relative_path: string;
+  asset_type: string;
+  size_bytes: number;
+}
+
+export interface AssetDifference {
+  a: ComparedAsset;
+  b: ComparedAsset;
+  matched_by: 'path' | 'guid' | 'content';
+  /** A changed GUID breaks every reference to the asset */
+  differences: ('path' | 'content' | 'guid')[];
+}
+
+export interface ProjectComparison {
+  project_a: string;
+  project_b: string;
+  identical_count: number;
+  only_in_a_count: number;
+  only_in_b_count: number;
+  changed_count: number;
+  only_in_a: ComparedAsset[];
+  only_in_b: ComparedAsset[];
+  changed: AssetDifference[];
+  /** False when equal-size files weren't hashed */
+  content_compared: boolean;
+}
+
+export interface GuidCopy {
+  project_id: string;
+  project_name: string;
+  relative_path: string;
+  asset_type: string;
+  size_bytes: number;
+  content_hash: string | null;
+}
+
+export interface GuidCollision {
+  guid: string;
+  copies: GuidCopy[];
+  variants: number;
+  /** Copies at different paths: likely a folder copied with its .meta files */
+  paths_differ: boolean;
+}
+
+export interface GuidCollisionReport {
+  shared_guids: number;
+  collision_count: number;
+  collisions: GuidCollision[];
+  content_compared: boolean;
+}
+
+export interface LocalizationTable {
+  asset_id: string;
+  collection: string;
+  locale: string;
+  kind: 'string' | 'asset';
+  entry_count: number;
+}
+
+/** One key in one locale; a missing translation has neither value nor guid */
+export interface LocalizationEntry {
+  collection: string;
+  key_id: number;
+  key: string;
+  locale: string;
+  kind: 'string' | 'asset';
+  value: string | null;
+  guid: string | null;
+  asset_id: string | null;
+  relative_path?: string;
+}
+
+export interface LocalizedKey {
+  collection: string;
+  key_id: number;
+  key: string;
+  kind: 'string' | 'asset';
+  locales: LocalizationEntry[];
+  missing_locales: string[];
+}
+
+export interface TextureEstimateStats {
+  textures: number;
+  estimated: number;
+  unchanged: number;
+  unsupported: number;
+  completed: boolean;
+}